@@ -1,6 +1,6 @@
-// Note that aside from this comment, this shader is copied precisely from the Bevy Game of Life
-// example, in order to enable a clear comparison between Bevy's compute shader system directly
-// and using this crate.
+// Note that aside from this comment and drawing live cells with the cursor, this shader is copied
+// precisely from the Bevy Game of Life example, in order to enable a clear comparison between
+// Bevy's compute shader system directly and using this crate.
 
 // The shader reads the previous frame's state from the `input` texture, and writes the new state of
 // each pixel to the `output` texture. The textures are flipped each step to progress the
@@ -12,6 +12,14 @@
 
 @group(0) @binding(1) var output: texture_storage_2d<r32float, write>;
 
+struct ComputeCursor {
+	position: vec2<f32>,
+	buttons: u32,
+	on_texture: u32,
+}
+
+@group(0) @binding(2) var<uniform> cursor: ComputeCursor;
+
 fn hash(value: u32) -> u32 {
 	var state = value;
 	state = state ^ 2747636419u;
@@ -70,6 +78,12 @@ fn update(@builtin(global_invocation_id) invocation_id: vec3<u32>) {
 	} else {
 			alive = false;
 	}
+
+	// Cells near the cursor come alive while the left mouse button is held down.
+	let drawing = (cursor.buttons & 1u) != 0u && cursor.on_texture != 0u;
+	if (drawing && distance(vec2<f32>(location) + 0.5, cursor.position) < 3.0) {
+		alive = true;
+	}
 	let color = vec4<f32>(f32(alive));
 
 	textureStore(output, location, color);
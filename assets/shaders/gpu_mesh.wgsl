@@ -0,0 +1,61 @@
+// Generates a mesh entirely on the GPU. Every frame, `generate` writes one vertex per grid point
+// into `vertices`, and appends two triangles to `indices` for every grid cell above the waterline,
+// so the number of indices changes from frame to frame. The count is kept in `index_count`, which
+// is the only thing read back to the CPU.
+
+struct Vertex {
+	position: vec4<f32>,
+	color: vec4<f32>,
+}
+
+override GRID: u32 = 96u;
+
+const WATERLINE: f32 = 0.15;
+
+@group(0) @binding(0) var<storage, read_write> vertices: array<Vertex>;
+
+@group(0) @binding(1) var<storage, read_write> indices: array<u32>;
+
+@group(0) @binding(2) var<storage, read_write> index_count: atomic<u32>;
+
+@group(0) @binding(3) var<uniform> iteration: u32;
+
+fn height(position: vec2<f32>, time: f32) -> f32 {
+	let waves = sin(position.x * 5.0 + time) * cos(position.y * 4.0 - time * 0.7);
+	let ripples = sin(length(position) * 12.0 - time * 2.0);
+	return waves * 0.6 + ripples * 0.4;
+}
+
+fn grid_position(cell: vec2<f32>) -> vec2<f32> {
+	return cell / f32(GRID) * 2.0 - 1.0;
+}
+
+@compute @workgroup_size(1)
+fn reset_count() {
+	atomicStore(&index_count, 0u);
+}
+
+@compute @workgroup_size(8, 8, 1)
+fn generate(@builtin(global_invocation_id) id: vec3<u32>) {
+	if id.x > GRID || id.y > GRID {
+		return;
+	}
+	let time = f32(iteration) / 60.0;
+	let position = grid_position(vec2<f32>(id.xy));
+	let elevation = height(position, time);
+	let color = mix(vec3<f32>(0.1, 0.4, 0.2), vec3<f32>(0.9, 0.85, 0.7), clamp(elevation, 0.0, 1.0));
+	let corner = id.y * (GRID + 1u) + id.x;
+	vertices[corner] = Vertex(vec4<f32>(position * 0.9, 0.5, 1.0), vec4<f32>(color, 1.0));
+
+	if id.x == GRID || id.y == GRID || height(grid_position(vec2<f32>(id.xy) + 0.5), time) < WATERLINE {
+		return;
+	}
+	let first = atomicAdd(&index_count, 6u);
+	let above = corner + GRID + 1u;
+	indices[first] = corner;
+	indices[first + 1u] = corner + 1u;
+	indices[first + 2u] = above;
+	indices[first + 3u] = above;
+	indices[first + 4u] = corner + 1u;
+	indices[first + 5u] = above + 1u;
+}
@@ -0,0 +1,17 @@
+// Draws the vertices written by `gpu_mesh.wgsl`. They're already in clip space, so the vertex
+// shader just passes them through.
+
+struct VertexOutput {
+	@builtin(position) position: vec4<f32>,
+	@location(0) color: vec4<f32>,
+}
+
+@vertex
+fn vertex(@location(0) position: vec4<f32>, @location(1) color: vec4<f32>) -> VertexOutput {
+	return VertexOutput(position, color);
+}
+
+@fragment
+fn fragment(in: VertexOutput) -> @location(0) vec4<f32> {
+	return in.color;
+}
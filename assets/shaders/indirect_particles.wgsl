@@ -0,0 +1,85 @@
+// A fountain of particles, drawn with an indirect draw. Every frame, `update` moves the live
+// particles and emits new ones into dead slots, then appends every live particle to `instances`,
+// counting them in the instance count of `draw_args`. The render pipeline draws one quad per
+// instance, so the number drawn is decided entirely on the GPU.
+
+struct Particle {
+	position: vec2<f32>,
+	velocity: vec2<f32>,
+	life: f32,
+}
+
+struct Instance {
+	position: vec4<f32>,
+	color: vec4<f32>,
+}
+
+struct DrawIndirectArgs {
+	vertex_count: u32,
+	instance_count: atomic<u32>,
+	first_vertex: u32,
+	first_instance: u32,
+}
+
+const MAX_LIFE: f32 = 3.0;
+const DELTA: f32 = 1.0 / 60.0;
+const GRAVITY: f32 = -1.2;
+const EMIT_CHANCE: u32 = 40u;
+const SIZE: f32 = 0.008;
+
+@group(0) @binding(0) var<storage, read_write> particles: array<Particle>;
+
+@group(0) @binding(1) var<storage, read_write> instances: array<Instance>;
+
+@group(0) @binding(2) var<storage, read_write> draw_args: DrawIndirectArgs;
+
+@group(0) @binding(3) var<uniform> iteration: u32;
+
+fn hash(value: u32) -> u32 {
+	var state = value;
+	state = state ^ 2747636419u;
+	state = state * 2654435769u;
+	state = state ^ (state >> 16u);
+	state = state * 2654435769u;
+	state = state ^ (state >> 16u);
+	return state;
+}
+
+fn random(seed: u32) -> f32 {
+	return f32(hash(seed)) / 4294967295.0;
+}
+
+@compute @workgroup_size(1, 1, 1)
+fn reset_instance_count() {
+	atomicStore(&draw_args.instance_count, 0u);
+}
+
+@compute @workgroup_size(64, 1, 1)
+fn update(@builtin(global_invocation_id) invocation_id: vec3<u32>) {
+	let index = invocation_id.x;
+	if (index >= arrayLength(&particles)) {
+		return;
+	}
+
+	var particle = particles[index];
+	if (particle.life > 0.0) {
+		particle.velocity.y += GRAVITY * DELTA;
+		particle.position += particle.velocity * DELTA;
+		particle.life -= DELTA;
+	} else {
+		let seed = hash(index) ^ iteration;
+		if (hash(seed) % 1000u < EMIT_CHANCE) {
+			let angle = 1.5707963 + (random(seed + 1u) - 0.5) * 0.6;
+			let speed = 1.2 + random(seed + 2u) * 0.4;
+			particle = Particle(vec2<f32>(0.0, -0.9), vec2<f32>(cos(angle), sin(angle)) * speed, MAX_LIFE);
+		}
+	}
+	particles[index] = particle;
+
+	if (particle.life > 0.0) {
+		let age = 1.0 - particle.life / MAX_LIFE;
+		let color = mix(vec3<f32>(0.4, 0.8, 1.0), vec3<f32>(0.1, 0.2, 0.9), age);
+		let instance = atomicAdd(&draw_args.instance_count, 1u);
+		instances[instance] = Instance(vec4<f32>(particle.position, SIZE, 0.0), vec4<f32>(color, 1.0));
+	}
+}
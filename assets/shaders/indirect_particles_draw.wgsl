@@ -0,0 +1,30 @@
+// Draws a quad for every instance written by `indirect_particles.wgsl`. There's no vertex buffer
+// for the quad itself, the corners are picked by the vertex index.
+
+struct VertexOutput {
+	@builtin(position) position: vec4<f32>,
+	@location(0) color: vec4<f32>,
+}
+
+@vertex
+fn vertex(
+	@builtin(vertex_index) vertex_index: u32,
+	@location(0) position: vec4<f32>,
+	@location(1) color: vec4<f32>,
+) -> VertexOutput {
+	var corners = array<vec2<f32>, 6>(
+		vec2<f32>(-1.0, -1.0),
+		vec2<f32>(1.0, -1.0),
+		vec2<f32>(1.0, 1.0),
+		vec2<f32>(-1.0, -1.0),
+		vec2<f32>(1.0, 1.0),
+		vec2<f32>(-1.0, 1.0),
+	);
+	let corner = corners[vertex_index] * position.z;
+	return VertexOutput(vec4<f32>(position.xy + corner, 0.5, 1.0), color);
+}
+
+@fragment
+fn fragment(in: VertexOutput) -> @location(0) vec4<f32> {
+	return in.color;
+}
@@ -0,0 +1,23 @@
+// Fills a 256x1 color lookup texture with a palette that cycles over time, which a material samples in the same frame.
+
+struct ComputeGlobals {
+	time: f32,
+	delta_time: f32,
+	frame_count: u32,
+	seed: u32,
+}
+
+@group(0) @binding(0) var lut: texture_storage_2d<rgba8unorm, write>;
+
+@group(0) @binding(1) var<uniform> globals: ComputeGlobals;
+
+@compute @workgroup_size(64, 1, 1)
+fn fill(@builtin(global_invocation_id) id: vec3<u32>) {
+	let size = textureDimensions(lut);
+	if id.x >= size.x {
+		return;
+	}
+	let phase = f32(id.x) / f32(size.x) * 6.2831853 + globals.time;
+	let color = 0.5 + 0.5 * cos(vec3<f32>(phase) + vec3<f32>(0.0, 2.0943951, 4.1887902));
+	textureStore(lut, vec2<u32>(id.x, 0u), vec4<f32>(color, 1.0));
+}
@@ -0,0 +1,23 @@
+// A StandardMaterial extension that takes its base color from a lookup texture written by a compute shader, indexed
+// by the mesh's horizontal texture coordinate.
+
+#import bevy_pbr::{
+	forward_io::{VertexOutput, FragmentOutput},
+	pbr_fragment::pbr_input_from_standard_material,
+	pbr_functions::{alpha_discard, apply_pbr_lighting, main_pass_post_lighting_processing},
+}
+
+@group(2) @binding(100) var lut_texture: texture_2d<f32>;
+@group(2) @binding(101) var lut_sampler: sampler;
+
+@fragment
+fn fragment(in: VertexOutput, @builtin(front_facing) is_front: bool) -> FragmentOutput {
+	var pbr_input = pbr_input_from_standard_material(in, is_front);
+	pbr_input.material.base_color = textureSample(lut_texture, lut_sampler, vec2<f32>(in.uv.x, 0.5));
+	pbr_input.material.base_color = alpha_discard(pbr_input.material, pbr_input.material.base_color);
+
+	var out: FragmentOutput;
+	out.color = apply_pbr_lighting(pbr_input);
+	out.color = main_pass_post_lighting_processing(pbr_input, out.color);
+	return out;
+}
@@ -0,0 +1,96 @@
+// The particles are double buffered. Each step reads the previous state of every particle from
+// `particles_in`, and writes the new state to `particles_out`, and the buffers are swapped after.
+// The position of every live particle is also written to `positions`, and the number of live
+// particles counted in `alive_count`, as those are the only things read back to the CPU.
+
+struct Particle {
+	position: vec2<f32>,
+	velocity: vec2<f32>,
+	life: f32,
+}
+
+struct Params {
+	time: f32,
+	delta: f32,
+	gravity: f32,
+	spawning: u32,
+}
+
+const MAX_LIFE: f32 = 4.0;
+const DEAD_POSITION: vec2<f32> = vec2<f32>(1.0e9, 1.0e9);
+
+@group(0) @binding(0) var<storage, read> particles_in: array<Particle>;
+
+@group(0) @binding(1) var<storage, read_write> particles_out: array<Particle>;
+
+@group(0) @binding(2) var<storage, read_write> positions: array<vec2<f32>>;
+
+@group(0) @binding(3) var<storage, read_write> alive_count: atomic<u32>;
+
+@group(0) @binding(4) var<uniform> params: Params;
+
+fn hash(value: u32) -> u32 {
+	var state = value;
+	state = state ^ 2747636419u;
+	state = state * 2654435769u;
+	state = state ^ state >> 16u;
+	state = state * 2654435769u;
+	state = state ^ state >> 16u;
+	state = state * 2654435769u;
+	return state;
+}
+
+fn randomFloat(value: u32) -> f32 {
+	return f32(hash(value)) / 4294967295.0;
+}
+
+fn spawn(index: u32, seed: u32) -> Particle {
+	let angle = randomFloat(index * 3u + seed) * 6.2831853;
+	let speed = 100.0 + randomFloat(index * 3u + 1u + seed) * 200.0;
+	var particle: Particle;
+	particle.position = vec2<f32>(0.0, 0.0);
+	particle.velocity = vec2<f32>(cos(angle), sin(angle)) * speed + vec2<f32>(0.0, 250.0);
+	particle.life = randomFloat(index * 3u + 2u + seed) * MAX_LIFE;
+	return particle;
+}
+
+@compute @workgroup_size(64, 1, 1)
+fn init(@builtin(global_invocation_id) invocation_id: vec3<u32>) {
+	let index = invocation_id.x;
+	if (index >= arrayLength(&particles_out)) {
+		return;
+	}
+
+	particles_out[index] = spawn(index, 0u);
+}
+
+@compute @workgroup_size(1, 1, 1)
+fn reset_counter() {
+	atomicStore(&alive_count, 0u);
+}
+
+@compute @workgroup_size(64, 1, 1)
+fn update(@builtin(global_invocation_id) invocation_id: vec3<u32>) {
+	let index = invocation_id.x;
+	if (index >= arrayLength(&particles_in)) {
+		return;
+	}
+
+	var particle = particles_in[index];
+	if (particle.life > 0.0) {
+		particle.velocity.y += params.gravity * params.delta;
+		particle.position += particle.velocity * params.delta;
+		particle.life -= params.delta;
+	} else if (params.spawning != 0u) {
+		particle = spawn(index, bitcast<u32>(params.time));
+		particle.life = MAX_LIFE;
+	}
+	particles_out[index] = particle;
+
+	if (particle.life > 0.0) {
+		positions[index] = particle.position;
+		atomicAdd(&alive_count, 1u);
+	} else {
+		positions[index] = DEAD_POSITION;
+	}
+}
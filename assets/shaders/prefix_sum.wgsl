@@ -0,0 +1,22 @@
+// A Hillis-Steele inclusive prefix sum, one pass per iteration. Each pass adds to every value the one `offset` places
+// before it, doubling the offset each time, so after log2(n) passes every value is the sum of all those up to it.
+
+@group(0) @binding(0) var<storage, read> input: array<u32>;
+
+@group(0) @binding(1) var<storage, read_write> output: array<u32>;
+
+@group(0) @binding(2) var<uniform> iteration: u32;
+
+@compute @workgroup_size(64, 1, 1)
+fn scan(@builtin(global_invocation_id) id: vec3<u32>) {
+	let i = id.x;
+	if i >= arrayLength(&output) {
+		return;
+	}
+	let offset = 1u << iteration;
+	var sum = input[i];
+	if i >= offset {
+		sum += input[i - offset];
+	}
+	output[i] = sum;
+}
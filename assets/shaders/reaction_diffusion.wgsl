@@ -0,0 +1,74 @@
+// A Gray-Scott reaction-diffusion simulation. The red channel of each pixel holds the concentration
+// of chemical A, and the green channel chemical B. Like the Game of Life example, it reads the
+// previous state from the `input` texture and writes the new state to the `output` texture, and the
+// textures are flipped after each step.
+
+struct Params {
+	feed: f32,
+	kill: f32,
+	diffuse_a: f32,
+	diffuse_b: f32,
+}
+
+struct Paint {
+	position: vec2<f32>,
+	radius: f32,
+	enabled: u32,
+}
+
+@group(0) @binding(0) var input: texture_storage_2d<rg32float, read>;
+
+@group(0) @binding(1) var output: texture_storage_2d<rg32float, write>;
+
+@group(0) @binding(2) var<uniform> params: Params;
+
+@group(0) @binding(3) var<uniform> paint: Paint;
+
+@compute @workgroup_size(8, 8, 1)
+fn init(@builtin(global_invocation_id) invocation_id: vec3<u32>) {
+	let location = vec2<i32>(i32(invocation_id.x), i32(invocation_id.y));
+	let center = vec2<i32>(textureDimensions(output)) / 2;
+	let seeded = all(abs(location - center) < vec2<i32>(10, 10));
+	textureStore(output, location, vec4<f32>(1.0, select(0.0, 1.0, seeded), 0.0, 1.0));
+}
+
+fn load(location: vec2<i32>, offset_x: i32, offset_y: i32) -> vec2<f32> {
+	let size = vec2<i32>(textureDimensions(input));
+	let clamped = clamp(location + vec2<i32>(offset_x, offset_y), vec2<i32>(0, 0), size - vec2<i32>(1, 1));
+	return textureLoad(input, clamped).xy;
+}
+
+fn laplacian(location: vec2<i32>) -> vec2<f32> {
+	return
+		load(location, -1, -1) * 0.05 + load(location, 0, -1) * 0.2 + load(location, 1, -1) * 0.05 +
+		load(location, -1,  0) * 0.2  - load(location, 0,  0)       + load(location, 1,  0) * 0.2 +
+		load(location, -1,  1) * 0.05 + load(location, 0,  1) * 0.2 + load(location, 1,  1) * 0.05;
+}
+
+@compute @workgroup_size(8, 8, 1)
+fn update(@builtin(global_invocation_id) invocation_id: vec3<u32>) {
+	let location = vec2<i32>(i32(invocation_id.x), i32(invocation_id.y));
+
+	let current = load(location, 0, 0);
+	let a = current.x;
+	let b = current.y;
+	let reaction = a * b * b;
+	let diffusion = laplacian(location);
+	let new_a = a + params.diffuse_a * diffusion.x - reaction + params.feed * (1.0 - a);
+	let new_b = b + params.diffuse_b * diffusion.y + reaction - (params.kill + params.feed) * b;
+
+	textureStore(output, location, vec4<f32>(clamp(new_a, 0.0, 1.0), clamp(new_b, 0.0, 1.0), 0.0, 1.0));
+}
+
+// This runs after `update`, and before the textures are flipped, so it overwrites part of what
+// `update` just wrote. It runs every step, but only does anything while the mouse is held down.
+@compute @workgroup_size(8, 8, 1)
+fn paint_seed(@builtin(global_invocation_id) invocation_id: vec3<u32>) {
+	if (paint.enabled == 0u) {
+		return;
+	}
+	let location = vec2<f32>(f32(invocation_id.x), f32(invocation_id.y));
+	if (distance(location, paint.position) < paint.radius) {
+		textureStore(output, vec2<i32>(invocation_id.xy), vec4<f32>(0.0, 1.0, 0.0, 1.0));
+	}
+}
@@ -0,0 +1,8 @@
+// A shader that doesn't compile, for testing how compile errors are handled.
+
+@group(0) @binding(0) var<storage, read_write> data: array<f32>;
+
+@compute @workgroup_size(64, 1, 1)
+fn increment(@builtin(global_invocation_id) invocation_id: vec3<u32>) {
+	data[invocation_id.x] += not_declared;
+}
@@ -0,0 +1,20 @@
+// Counts how many times each cell of a 10x6 grid is covered by a chunked dispatch, for the crate's own tests.
+
+struct ComputeChunk {
+	offset: vec3<u32>,
+}
+
+@group(0) @binding(0) var<storage, read_write> data: array<f32>;
+
+@group(0) @binding(1) var<uniform> chunk: ComputeChunk;
+
+const WIDTH: u32 = 10u;
+const HEIGHT: u32 = 6u;
+
+@compute @workgroup_size(2, 2, 1)
+fn cover(@builtin(global_invocation_id) local_id: vec3<u32>) {
+	let id = local_id + chunk.offset;
+	if (id.x < WIDTH && id.y < HEIGHT) {
+		data[id.y * WIDTH + id.x] += 1.0;
+	}
+}
@@ -0,0 +1,9 @@
+// Counts past the range of a u32 with 64-bit atomics, for the crate's own tests.
+
+@group(0) @binding(0) var<storage, read_write> counters: array<atomic<u64>>;
+
+@compute @workgroup_size(4, 1, 1)
+fn count_wide() {
+	atomicAdd(&counters[0], 4294967296lu);
+	atomicAdd(&counters[1], 1lu);
+}
@@ -0,0 +1,26 @@
+// Counts the positive values in `data` into the workgroup count of an indirect dispatch, and counts the workgroups
+// that dispatch runs, for the crate's own tests.
+
+struct DispatchIndirectArgs {
+	x: atomic<u32>,
+	y: u32,
+	z: u32,
+}
+
+@group(0) @binding(0) var<storage, read_write> data: array<f32>;
+
+@group(0) @binding(1) var<storage, read_write> args: DispatchIndirectArgs;
+
+@group(0) @binding(2) var<storage, read_write> workgroups: atomic<u32>;
+
+@compute @workgroup_size(64, 1, 1)
+fn count_positive(@builtin(global_invocation_id) invocation_id: vec3<u32>) {
+	if (invocation_id.x < arrayLength(&data) && data[invocation_id.x] > 0.0) {
+		atomicAdd(&args.x, 1u);
+	}
+}
+
+@compute @workgroup_size(1, 1, 1)
+fn count_workgroups() {
+	atomicAdd(&workgroups, 1u);
+}
@@ -0,0 +1,11 @@
+// Fills a texture of any size, for the crate's own tests of dispatches worked out from a texture's size.
+
+@group(0) @binding(0) var texture: texture_storage_2d<r32float, write>;
+
+@compute @workgroup_size(8, 8, 1)
+fn fill(@builtin(global_invocation_id) id: vec3<u32>) {
+	if any(id.xy >= textureDimensions(texture)) {
+		return;
+	}
+	textureStore(texture, id.xy, vec4<f32>(1.0, 0.0, 0.0, 0.0));
+}
@@ -0,0 +1,12 @@
+// Reads the front half of a double buffered texture, for the crate's own tests.
+
+@group(0) @binding(0) var<storage, read_write> data: array<f32>;
+
+@group(0) @binding(1) var front: texture_storage_2d<r32float, read>;
+
+@group(0) @binding(2) var back: texture_storage_2d<r32float, write>;
+
+@compute @workgroup_size(1, 1, 1)
+fn read_front() {
+	data[0] = textureLoad(front, vec2<u32>(0u, 0u)).x;
+}
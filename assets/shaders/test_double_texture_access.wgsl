@@ -0,0 +1,11 @@
+// Adds the front half of a double buffered texture to the back half it reads and writes, for the crate's own tests.
+
+@group(0) @binding(1) var front: texture_storage_2d<r32float, read>;
+
+@group(0) @binding(2) var back: texture_storage_2d<r32float, read_write>;
+
+@compute @workgroup_size(2, 2, 1)
+fn accumulate(@builtin(global_invocation_id) id: vec3<u32>) {
+	let sum = textureLoad(front, id.xy).x + textureLoad(back, id.xy).x;
+	textureStore(back, id.xy, vec4<f32>(sum, 0.0, 0.0, 0.0));
+}
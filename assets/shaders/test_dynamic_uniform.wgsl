@@ -0,0 +1,9 @@
+// Writes the second value of the element of a dynamic uniform its step binds at the index given by the first, for the crate's own tests.
+
+@group(0) @binding(0) var<storage, read_write> values: array<u32>;
+@group(0) @binding(1) var<uniform> element: vec2<u32>;
+
+@compute @workgroup_size(1, 1, 1)
+fn write_element() {
+	values[element.x] = element.y;
+}
@@ -0,0 +1,85 @@
+// Records the globals buffer, from two steps of the same tick, the seed of every iteration, and the size, cursor
+// and camera uniforms, for the crate's own tests.
+
+struct ComputeGlobals {
+	time: f32,
+	delta_time: f32,
+	frame_count: u32,
+	seed: u32,
+}
+
+@group(0) @binding(0) var<storage, read_write> record: array<u32>;
+
+struct ComputeSize {
+	window_size: vec2<u32>,
+	texture_size: vec2<u32>,
+}
+
+@group(0) @binding(1) var<uniform> globals: ComputeGlobals;
+
+struct ComputeCursor {
+	position: vec2<f32>,
+	buttons: u32,
+	on_texture: u32,
+}
+
+struct ComputeCameraMatrices {
+	view: mat4x4<f32>,
+	inverse_view: mat4x4<f32>,
+	projection: mat4x4<f32>,
+	inverse_projection: mat4x4<f32>,
+	view_projection: mat4x4<f32>,
+	inverse_view_projection: mat4x4<f32>,
+	world_position: vec3<f32>,
+}
+
+@group(0) @binding(2) var<uniform> size: ComputeSize;
+
+@group(0) @binding(3) var<uniform> cursor: ComputeCursor;
+
+@group(0) @binding(4) var<uniform> camera: ComputeCameraMatrices;
+
+@group(0) @binding(5) var<uniform> iteration: u32;
+
+@compute @workgroup_size(1, 1, 1)
+fn record_globals() {
+	record[0] = bitcast<u32>(globals.time);
+	record[1] = bitcast<u32>(globals.delta_time);
+	record[2] = globals.frame_count;
+}
+
+@compute @workgroup_size(1, 1, 1)
+fn record_frame_count() {
+	record[3] = globals.frame_count;
+}
+
+@compute @workgroup_size(1, 1, 1)
+fn record_seed() {
+	record[iteration] = globals.seed;
+}
+
+@compute @workgroup_size(1, 1, 1)
+fn record_size() {
+	record[0] = size.window_size.x;
+	record[1] = size.window_size.y;
+	record[2] = size.texture_size.x;
+	record[3] = size.texture_size.y;
+}
+
+@compute @workgroup_size(1, 1, 1)
+fn record_cursor() {
+	record[0] = bitcast<u32>(cursor.position.x);
+	record[1] = bitcast<u32>(cursor.position.y);
+	record[2] = cursor.buttons;
+	record[3] = cursor.on_texture;
+}
+
+@compute @workgroup_size(1, 1, 1)
+fn record_camera() {
+	record[0] = bitcast<u32>(camera.world_position.x);
+	record[1] = bitcast<u32>(camera.world_position.y);
+	record[2] = bitcast<u32>(camera.world_position.z);
+	// The view matrix should take the camera's own position to the origin.
+	let origin = camera.view * vec4<f32>(camera.world_position, 1.0);
+	record[3] = bitcast<u32>(length(origin.xyz));
+}
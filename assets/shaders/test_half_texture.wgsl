@@ -0,0 +1,8 @@
+// Writes a pixel of a 16-bit float texture, for the crate's own tests.
+
+@group(0) @binding(0) var texture: texture_storage_2d<rgba16float, write>;
+
+@compute @workgroup_size(1, 1, 1)
+fn write_half() {
+	textureStore(texture, vec2<u32>(0u, 0u), vec4<f32>(0.5, -1.0, 3.0, 1024.0));
+}
@@ -0,0 +1,12 @@
+// A kernel importing from test_library.wgsl, to test that compute shaders resolve imports.
+
+#import bevy_compute_tests::library::step_size
+
+@group(0) @binding(0) var<storage, read_write> data: array<f32>;
+
+@compute @workgroup_size(64, 1, 1)
+fn increment_by_step(@builtin(global_invocation_id) invocation_id: vec3<u32>) {
+	if (invocation_id.x < arrayLength(&data)) {
+		data[invocation_id.x] += step_size();
+	}
+}
@@ -0,0 +1,19 @@
+// Counts the positive values in `data` into the instance count of an indirect draw.
+
+struct DrawIndirectArgs {
+	vertex_count: u32,
+	instance_count: atomic<u32>,
+	first_vertex: u32,
+	first_instance: u32,
+}
+
+@group(0) @binding(0) var<storage, read_write> data: array<f32>;
+
+@group(0) @binding(1) var<storage, read_write> args: DrawIndirectArgs;
+
+@compute @workgroup_size(64, 1, 1)
+fn count_positive(@builtin(global_invocation_id) invocation_id: vec3<u32>) {
+	if (invocation_id.x < arrayLength(&data) && data[invocation_id.x] > 0.0) {
+		atomicAdd(&args.instance_count, 1u);
+	}
+}
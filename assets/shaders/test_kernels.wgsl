@@ -0,0 +1,48 @@
+// Small kernels used by the crate's own tests.
+
+@group(0) @binding(0) var<storage, read_write> data: array<f32>;
+
+#ifdef WRITABLE_INPUT
+@group(0) @binding(1) var<storage, read_write> input: array<f32>;
+#else
+@group(0) @binding(1) var<storage, read> input: array<f32>;
+#endif
+
+@group(0) @binding(2) var<storage, read_write> output: array<f32>;
+
+@group(1) @binding(0) var<storage, read_write> agent: array<f32>;
+
+@compute @workgroup_size(64, 1, 1)
+fn increment(@builtin(global_invocation_id) invocation_id: vec3<u32>) {
+	if (invocation_id.x < arrayLength(&data)) {
+		data[invocation_id.x] += 1.0;
+	}
+}
+
+@compute @workgroup_size(64, 1, 1)
+fn decrement(@builtin(global_invocation_id) invocation_id: vec3<u32>) {
+	if (invocation_id.x < arrayLength(&data)) {
+		data[invocation_id.x] -= 1.0;
+	}
+}
+
+@compute @workgroup_size(64, 1, 1)
+fn advance(@builtin(global_invocation_id) invocation_id: vec3<u32>) {
+	if (invocation_id.x < arrayLength(&output)) {
+		output[invocation_id.x] = input[invocation_id.x] + 1.0;
+	}
+}
+
+@compute @workgroup_size(64, 1, 1)
+fn snapshot(@builtin(global_invocation_id) invocation_id: vec3<u32>) {
+	if (invocation_id.x < arrayLength(&data)) {
+		data[invocation_id.x] = input[invocation_id.x];
+	}
+}
+
+@compute @workgroup_size(64, 1, 1)
+fn advance_agent(@builtin(global_invocation_id) invocation_id: vec3<u32>) {
+	if (invocation_id.x < arrayLength(&agent)) {
+		agent[invocation_id.x] += data[invocation_id.x];
+	}
+}
@@ -0,0 +1,11 @@
+// A library imported by test_import.wgsl, to test that compute shaders resolve imports.
+
+#define_import_path bevy_compute_tests::library
+
+fn step_size() -> f32 {
+#ifdef DOUBLE_STEP
+	return 2.0;
+#else
+	return 1.0;
+#endif
+}
@@ -0,0 +1,24 @@
+// A ping-pong pipeline that declares its groups through a binding namespace, so two plugins can each run it against
+// their own buffers, for the crate's own tests.
+
+@group(#{GROUP_0}) @binding(0) var<storage, read> input: array<f32>;
+
+@group(#{GROUP_0}) @binding(1) var<storage, read_write> output: array<f32>;
+
+@group(#{GROUP_1}) @binding(0) var<storage, read_write> rule: array<f32>;
+
+@group(#{GROUP_1}) @binding(1) var<storage, read_write> snapshot: array<f32>;
+
+@compute @workgroup_size(64, 1, 1)
+fn update(@builtin(global_invocation_id) invocation_id: vec3<u32>) {
+	if (invocation_id.x < arrayLength(&output)) {
+		output[invocation_id.x] = input[invocation_id.x] + rule[invocation_id.x];
+	}
+}
+
+@compute @workgroup_size(64, 1, 1)
+fn take_snapshot(@builtin(global_invocation_id) invocation_id: vec3<u32>) {
+	if (invocation_id.x < arrayLength(&snapshot)) {
+		snapshot[invocation_id.x] = input[invocation_id.x];
+	}
+}
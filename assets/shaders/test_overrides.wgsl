@@ -0,0 +1,17 @@
+// A kernel with override constants, to test that they can be set per step.
+
+override STEP: f32 = 1.0;
+
+// This one is never given a value, so it keeps its default.
+override SCALE: f32 = 1.0;
+
+@id(0) override WORKGROUP_SIZE: u32 = 64u;
+
+@group(0) @binding(0) var<storage, read_write> data: array<f32>;
+
+@compute @workgroup_size(WORKGROUP_SIZE, 1, 1)
+fn add_step(@builtin(global_invocation_id) invocation_id: vec3<u32>) {
+	if (invocation_id.x < arrayLength(&data)) {
+		data[invocation_id.x] += STEP * SCALE;
+	}
+}
@@ -0,0 +1,10 @@
+// Writes the second push constant at the index given by the first, for the crate's own tests.
+
+var<push_constant> push: vec2<u32>;
+
+@group(0) @binding(0) var<storage, read_write> values: array<u32>;
+
+@compute @workgroup_size(1, 1, 1)
+fn write_push_constants() {
+	values[push.x] = push.y;
+}
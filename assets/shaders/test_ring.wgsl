@@ -0,0 +1,64 @@
+// Records the ring position each step sees, on either side of a RotateBuffers step, and stamps the ring's current
+// buffer, next to a double buffer swapped in the same task, for the crate's own tests.
+
+struct ComputeGlobals {
+	time: f32,
+	delta_time: f32,
+	frame_count: u32,
+	seed: u32,
+	ring_positions: vec4<u32>,
+}
+
+@group(0) @binding(0) var<storage, read_write> record: array<u32>;
+
+@group(0) @binding(1) var<uniform> globals: ComputeGlobals;
+
+@group(0) @binding(2) var<uniform> iteration: u32;
+
+@group(0) @binding(3) var<storage, read_write> ring0: array<u32, 4>;
+
+@group(0) @binding(4) var<storage, read_write> ring1: array<u32, 4>;
+
+@group(0) @binding(5) var<storage, read_write> ring2: array<u32, 4>;
+
+@group(0) @binding(6) var<storage, read> front: array<u32, 4>;
+
+@group(0) @binding(7) var<storage, read_write> back: array<u32, 4>;
+
+@compute @workgroup_size(1, 1, 1)
+fn record_before() {
+	record[iteration * 2u] = globals.ring_positions.x;
+}
+
+@compute @workgroup_size(1, 1, 1)
+fn record_after() {
+	record[iteration * 2u + 1u] = globals.ring_positions.x;
+}
+
+@compute @workgroup_size(1, 1, 1)
+fn stamp() {
+	switch globals.ring_positions.x {
+		case 0u: {
+			ring0[0] = iteration + 1u;
+		}
+		case 1u: {
+			ring1[0] = iteration + 1u;
+		}
+		default: {
+			ring2[0] = iteration + 1u;
+		}
+	}
+}
+
+@compute @workgroup_size(1, 1, 1)
+fn advance() {
+	back[0] = front[0] + 1u;
+}
+
+@compute @workgroup_size(1, 1, 1)
+fn snapshot() {
+	record[32] = ring0[0];
+	record[33] = ring1[0];
+	record[34] = ring2[0];
+	record[35] = front[0];
+}
@@ -0,0 +1,26 @@
+// Reads sampled textures of each sample type into `results`.
+
+@group(0) @binding(0) var<storage, read_write> results: array<f32>;
+
+@group(0) @binding(1) var color: texture_2d<f32>;
+
+@group(0) @binding(2) var linear_sampler: sampler;
+
+@group(0) @binding(3) var heights: texture_2d<f32>;
+
+@group(0) @binding(4) var counts: texture_2d<u32>;
+
+// Declared with the wrong sample type, for validation to catch.
+@group(1) @binding(0) var signed_counts: texture_2d<i32>;
+
+@compute @workgroup_size(1, 1, 1)
+fn read_textures() {
+	results[0] = textureSampleLevel(color, linear_sampler, vec2<f32>(0.5, 0.5), 0.0).r;
+	results[1] = textureLoad(heights, vec2<i32>(0, 0), 0).r;
+	results[2] = f32(textureLoad(counts, vec2<i32>(0, 0), 0).r);
+}
+
+@compute @workgroup_size(1, 1, 1)
+fn read_signed() {
+	results[0] = f32(textureLoad(signed_counts, vec2<i32>(0, 0), 0).r);
+}
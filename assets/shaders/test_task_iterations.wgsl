@@ -0,0 +1,26 @@
+// Records the iteration counts written to the iteration buffers of two tasks, for the crate's own tests.
+
+struct ComputeIterationCounts {
+	local: u32,
+	global: u32,
+}
+
+@group(0) @binding(0) var<storage, read_write> record: array<u32>;
+
+@group(0) @binding(1) var<uniform> local_count: u32;
+
+@group(0) @binding(2) var<uniform> counts: ComputeIterationCounts;
+
+// The first task records its local count at the start of the record, counting from 1, so an iteration that
+// isn't recorded stays 0.
+@compute @workgroup_size(1)
+fn record_local() {
+	record[local_count] = local_count + 1u;
+}
+
+// The second task records both its counts after that, two for each iteration.
+@compute @workgroup_size(1)
+fn record_counts() {
+	record[4u + counts.local * 2u] = counts.local + 1u;
+	record[4u + counts.local * 2u + 1u] = counts.global + 1u;
+}
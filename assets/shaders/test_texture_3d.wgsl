@@ -0,0 +1,17 @@
+// Writes every texel of a 3D texture and a texture array with its coordinates, for the crate's own tests.
+
+@group(0) @binding(0) var volume: texture_storage_3d<r32float, write>;
+@group(0) @binding(1) var layers: texture_storage_2d_array<r32float, write>;
+
+@compute @workgroup_size(1, 1, 1)
+fn write_coordinates() {
+	for (var z = 0u; z < 2u; z++) {
+		for (var y = 0u; y < 2u; y++) {
+			for (var x = 0u; x < 3u; x++) {
+				let value = vec4<f32>(f32(x + 10u * y + 100u * z), 0.0, 0.0, 0.0);
+				textureStore(volume, vec3<u32>(x, y, z), value);
+				textureStore(layers, vec2<u32>(x, y), z, value);
+			}
+		}
+	}
+}
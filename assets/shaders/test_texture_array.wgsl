@@ -0,0 +1,11 @@
+// Reads the red channel of the first two textures of a texture array into `data`.
+
+@group(0) @binding(0) var<storage, read_write> data: array<f32>;
+
+@group(0) @binding(1) var textures: binding_array<texture_2d<f32>>;
+
+@compute @workgroup_size(1, 1, 1)
+fn load_red() {
+	data[0] = textureLoad(textures[0], vec2<i32>(0, 0), 0).r;
+	data[1] = textureLoad(textures[1], vec2<i32>(0, 0), 0).r;
+}
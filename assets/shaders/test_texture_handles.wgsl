@@ -0,0 +1,14 @@
+// Reads a sampled texture and writes a storage texture, both added from image handles, for the crate's own tests.
+
+@group(0) @binding(0) var<storage, read_write> data: array<f32>;
+
+@group(0) @binding(1) var heights: texture_2d<f32>;
+
+@group(0) @binding(2) var eroded: texture_storage_2d<rgba8unorm, write>;
+
+@compute @workgroup_size(1, 1, 1)
+fn erode() {
+	let height = textureLoad(heights, vec2<i32>(0, 0), 0).r;
+	data[0] = height;
+	textureStore(eroded, vec2<u32>(1u, 0u), vec4<f32>(height * 0.5, 0.0, 0.0, 1.0));
+}
@@ -0,0 +1,13 @@
+// Records both halves of a double buffered uniform, for the crate's own tests.
+
+@group(0) @binding(0) var<storage, read_write> record: array<f32>;
+
+@group(0) @binding(1) var<uniform> front: vec4<f32>;
+
+@group(0) @binding(2) var<uniform> back: vec4<f32>;
+
+@compute @workgroup_size(1, 1, 1)
+fn record_halves() {
+	record[0] = front.x;
+	record[1] = back.x;
+}
@@ -0,0 +1,10 @@
+// Only references group 1, so the groups below it are left unused, for the crate's own tests.
+
+@group(1) @binding(0) var<storage, read_write> agent: array<f32>;
+
+@compute @workgroup_size(64, 1, 1)
+fn increment_agent(@builtin(global_invocation_id) invocation_id: vec3<u32>) {
+	if (invocation_id.x < arrayLength(&agent)) {
+		agent[invocation_id.x] += 1.0;
+	}
+}
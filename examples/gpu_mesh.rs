@@ -0,0 +1,287 @@
+extern crate bevy_compute;
+
+use bevy::{
+	core_pipeline::core_3d::{Opaque3d, Opaque3dBinKey, CORE_3D_DEPTH_FORMAT},
+	ecs::{
+		query::ROQueryItem,
+		system::{lifetimeless::SRes, SystemParamItem},
+	},
+	math::Vec3A,
+	prelude::*,
+	render::{
+		extract_component::{ExtractComponent, ExtractComponentPlugin},
+		extract_resource::{ExtractResource, ExtractResourcePlugin},
+		primitives::Aabb,
+		render_phase::{
+			AddRenderCommand, BinnedRenderPhaseType, DrawFunctions, PhaseItem, RenderCommand, RenderCommandResult,
+			SetItemPipeline, TrackedRenderPass, ViewBinnedRenderPhases,
+		},
+		render_resource::{
+			Buffer, BufferUsages, ColorTargetState, ColorWrites, CompareFunction, DepthStencilState, FragmentState,
+			IndexFormat, MultisampleState, PipelineCache, PrimitiveState, RenderPipelineDescriptor,
+			SpecializedRenderPipeline, SpecializedRenderPipelines, TextureFormat, VertexAttribute, VertexBufferLayout,
+			VertexFormat, VertexState, VertexStepMode,
+		},
+		renderer::{RenderDevice, RenderQueue},
+		view::{self, ExtractedView, RenderVisibleEntities, VisibilitySystems},
+		Render, RenderApp, RenderSet,
+	},
+};
+use bevy_compute::prelude::*;
+
+/// This example uses shader source files from the assets subdirectory
+const COMPUTE_SHADER_ASSET_PATH: &str = "shaders/gpu_mesh.wgsl";
+const DRAW_SHADER_ASSET_PATH: &str = "shaders/gpu_mesh_draw.wgsl";
+
+/// The mesh is a grid of this many cells on each side, with a vertex at every corner.
+const GRID: u32 = 96;
+const VERTEX_COUNT: u32 = (GRID + 1) * (GRID + 1);
+const MAX_INDEX_COUNT: u32 = GRID * GRID * 6;
+
+/// Each vertex is a clip space position followed by a color, both as `vec4<f32>`.
+const VERTEX_SIZE: u32 = 32;
+
+/// The handles of the buffers the mesh is drawn from, and the number of indices to draw, which is read back from the GPU. This is extracted to the render world every time the count changes.
+#[derive(Resource, Clone, ExtractResource)]
+struct GpuMesh {
+	vertices: ShaderBufferHandle,
+	indices: ShaderBufferHandle,
+	index_count_buffer: ShaderBufferHandle,
+	index_count: u32,
+}
+
+/// Marks the entity the mesh is drawn for, so it takes part in visibility checks like any other renderable entity.
+#[derive(Component, Clone, ExtractComponent)]
+struct GpuMeshEntity;
+
+fn main() {
+	let mut app = App::new();
+	app
+		.insert_resource(ClearColor(Color::srgb(0.05, 0.1, 0.3)))
+		.add_plugins((
+			DefaultPlugins,
+			BevyComputePlugin,
+			ExtractComponentPlugin::<GpuMeshEntity>::default(),
+			ExtractResourcePlugin::<GpuMesh>::default(),
+		))
+		.add_systems(Startup, setup)
+		.add_systems(Update, receive_index_count)
+		.add_systems(PostUpdate, view::check_visibility::<With<GpuMeshEntity>>.in_set(VisibilitySystems::CheckVisibility));
+
+	// The compute node always runs before the camera driver node in the render graph, so the vertices and indices
+	// generated in a frame are the ones drawn that frame.
+	app
+		.sub_app_mut(RenderApp)
+		.init_resource::<GpuMeshPipeline>()
+		.init_resource::<SpecializedRenderPipelines<GpuMeshPipeline>>()
+		.add_render_command::<Opaque3d, DrawGpuMeshCommands>()
+		.add_systems(Render, prepare_gpu_mesh_buffers.in_set(RenderSet::Prepare))
+		.add_systems(Render, queue_gpu_mesh.in_set(RenderSet::Queue));
+
+	app.run();
+}
+
+fn setup(
+	mut commands: Commands, mut buffer_set: ResMut<ShaderBufferSet>, render_device: Res<RenderDevice>,
+	render_queue: Res<RenderQueue>, mut start_compute_events: EventWriter<StartComputeEvent>,
+) {
+	let vertices =
+		buffer_set.add_vertex_buffer(&render_device, VERTEX_COUNT * VERTEX_SIZE, Binding::SingleBound(0, 0)).unwrap();
+	let indices = buffer_set.add_index_buffer(&render_device, MAX_INDEX_COUNT * 4, Binding::SingleBound(0, 1)).unwrap();
+	let index_count_buffer = buffer_set
+		.add_storage_zeroed(
+			&render_device,
+			4,
+			BufferUsages::STORAGE | BufferUsages::COPY_SRC,
+			Binding::SingleBound(0, 2),
+			false,
+		)
+		.unwrap();
+	let iteration = buffer_set
+		.add_uniform_init(&render_device, &render_queue, 0u32, BufferUsages::UNIFORM, Binding::SingleBound(0, 3))
+		.unwrap();
+	commands.insert_resource(GpuMesh { vertices, indices, index_count_buffer, index_count: 0 });
+
+	commands.spawn((
+		Visibility::default(),
+		Transform::default(),
+		Aabb { center: Vec3A::ZERO, half_extents: Vec3A::splat(1.0) },
+		GpuMeshEntity,
+	));
+	commands.spawn((Camera3d::default(), Transform::from_xyz(0.0, 0.0, 1.0).looking_at(Vec3::ZERO, Vec3::Y)));
+
+	let run_shader = |entry_point: &str, dispatch: DispatchSize| ComputeStep {
+		schedule: StepSchedule::EveryIteration,
+		run_if: None,
+		action: ComputeAction::RunShader {
+			shader: COMPUTE_SHADER_ASSET_PATH.to_owned(),
+			entry_point: entry_point.to_owned(),
+			shader_defs: Vec::new(),
+			overrides: vec![("GRID".to_owned(), GRID as f64)],
+			dispatch,
+			auto_swap: Vec::new(),
+			skip_if_empty: None,
+			buffer_orientation: Vec::new(),
+			push_constants: None,
+			dynamic_offsets: Vec::new(),
+		},
+	};
+	start_compute_events.send(StartComputeEvent {
+		sequence: ComputeSequenceId::MAIN,
+		tasks: vec![ComputeTask {
+			label: Some("Generate mesh".to_owned()),
+			iterations: None,
+			run_mode: TaskRunMode::Continuous,
+			budget: None,
+			submission: TaskSubmission::WithFrame,
+			start_after: None,
+			iteration_buffer: None,
+			steps: vec![
+				run_shader("reset_count", DispatchSize::Workgroups { x: 1, y: 1, z: 1 }),
+				run_shader("generate", DispatchSize::Exact { x: GRID + 1, y: GRID + 1, z: 1 }),
+				ComputeStep {
+					schedule: StepSchedule::EveryIteration,
+					run_if: None,
+					action: ComputeAction::CopyBuffer { src: index_count_buffer, offset: 0, size: None },
+				},
+			],
+		}],
+		iteration_buffer: Some(iteration),
+		seed: 0,
+	});
+}
+
+/// The number of indices generated is read back a couple of frames late, so the count drawn can be a little out of date. The indices past the count from the current frame are left over from earlier frames, and still form valid triangles, so this only shows up as a few cells lagging behind.
+fn receive_index_count(mut readback_events: EventReader<BufferReadbackEvent>, mut mesh: ResMut<GpuMesh>) {
+	for event in readback_events.read() {
+		if event.handle == mesh.index_count_buffer {
+			mesh.index_count = event.decode::<u32>().unwrap();
+		}
+	}
+}
+
+/// The GPU buffers the mesh is drawn from, fetched from the render world's copy of the [ShaderBufferSet] every frame. The render command needs these to be owned by a resource, as it borrows them for the whole render pass.
+#[derive(Resource)]
+struct GpuMeshBuffers {
+	vertices: Buffer,
+	indices: Buffer,
+	index_count: u32,
+}
+
+fn prepare_gpu_mesh_buffers(mut commands: Commands, buffer_set: Res<ShaderBufferSet>, mesh: Option<Res<GpuMesh>>) {
+	let Some(mesh) = mesh else {
+		return;
+	};
+	if let (Some(vertices), Some(indices)) = (buffer_set.gpu_buffer(mesh.vertices), buffer_set.gpu_buffer(mesh.indices)) {
+		commands.insert_resource(GpuMeshBuffers { vertices, indices, index_count: mesh.index_count });
+	}
+}
+
+#[derive(Resource)]
+struct GpuMeshPipeline {
+	shader: Handle<Shader>,
+}
+
+impl FromWorld for GpuMeshPipeline {
+	fn from_world(world: &mut World) -> Self {
+		GpuMeshPipeline { shader: world.resource::<AssetServer>().load(DRAW_SHADER_ASSET_PATH) }
+	}
+}
+
+impl SpecializedRenderPipeline for GpuMeshPipeline {
+	type Key = Msaa;
+
+	fn specialize(&self, msaa: Self::Key) -> RenderPipelineDescriptor {
+		RenderPipelineDescriptor {
+			label: Some("gpu mesh pipeline".into()),
+			layout: vec![],
+			push_constant_ranges: vec![],
+			vertex: VertexState {
+				shader: self.shader.clone(),
+				shader_defs: vec![],
+				entry_point: "vertex".into(),
+				buffers: vec![VertexBufferLayout {
+					array_stride: VERTEX_SIZE as u64,
+					step_mode: VertexStepMode::Vertex,
+					attributes: vec![
+						VertexAttribute { format: VertexFormat::Float32x4, offset: 0, shader_location: 0 },
+						VertexAttribute { format: VertexFormat::Float32x4, offset: 16, shader_location: 1 },
+					],
+				}],
+			},
+			fragment: Some(FragmentState {
+				shader: self.shader.clone(),
+				shader_defs: vec![],
+				entry_point: "fragment".into(),
+				targets: vec![Some(ColorTargetState {
+					format: TextureFormat::bevy_default(),
+					blend: None,
+					write_mask: ColorWrites::ALL,
+				})],
+			}),
+			primitive: PrimitiveState::default(),
+			depth_stencil: Some(DepthStencilState {
+				format: CORE_3D_DEPTH_FORMAT,
+				depth_write_enabled: false,
+				depth_compare: CompareFunction::Always,
+				stencil: default(),
+				bias: default(),
+			}),
+			multisample: MultisampleState { count: msaa.samples(), mask: !0, alpha_to_coverage_enabled: false },
+			zero_initialize_workgroup_memory: false,
+		}
+	}
+}
+
+type DrawGpuMeshCommands = (SetItemPipeline, DrawGpuMesh);
+
+struct DrawGpuMesh;
+
+impl<P: PhaseItem> RenderCommand<P> for DrawGpuMesh {
+	type Param = SRes<GpuMeshBuffers>;
+	type ViewQuery = ();
+	type ItemQuery = ();
+
+	fn render<'w>(
+		_: &P, _: ROQueryItem<'w, Self::ViewQuery>, _: Option<ROQueryItem<'w, Self::ItemQuery>>,
+		buffers: SystemParamItem<'w, '_, Self::Param>, pass: &mut TrackedRenderPass<'w>,
+	) -> RenderCommandResult {
+		let buffers = buffers.into_inner();
+		pass.set_vertex_buffer(0, buffers.vertices.slice(..));
+		pass.set_index_buffer(buffers.indices.slice(..), 0, IndexFormat::Uint32);
+		pass.draw_indexed(0..buffers.index_count, 0, 0..1);
+		RenderCommandResult::Success
+	}
+}
+
+fn queue_gpu_mesh(
+	pipeline_cache: Res<PipelineCache>, pipeline: Res<GpuMeshPipeline>, buffers: Option<Res<GpuMeshBuffers>>,
+	mut opaque_render_phases: ResMut<ViewBinnedRenderPhases<Opaque3d>>,
+	opaque_draw_functions: Res<DrawFunctions<Opaque3d>>,
+	mut specialized_pipelines: ResMut<SpecializedRenderPipelines<GpuMeshPipeline>>,
+	views: Query<(Entity, &RenderVisibleEntities, &Msaa), With<ExtractedView>>,
+) {
+	if buffers.is_none() {
+		return;
+	}
+	let draw_function = opaque_draw_functions.read().id::<DrawGpuMeshCommands>();
+	for (view_entity, visible_entities, msaa) in views.iter() {
+		let Some(opaque_phase) = opaque_render_phases.get_mut(&view_entity) else {
+			continue;
+		};
+		for &entity in visible_entities.get::<With<GpuMeshEntity>>().iter() {
+			let pipeline_id = specialized_pipelines.specialize(&pipeline_cache, &pipeline, *msaa);
+			opaque_phase.add(
+				Opaque3dBinKey {
+					draw_function,
+					pipeline: pipeline_id,
+					asset_id: AssetId::<Mesh>::invalid().untyped(),
+					material_bind_group_id: None,
+					lightmap_image: None,
+				},
+				entity,
+				BinnedRenderPhaseType::NonMesh,
+			);
+		}
+	}
+}
@@ -0,0 +1,136 @@
+extern crate bevy_compute;
+
+use std::{num::NonZeroU32, time::Duration};
+
+#[cfg(not(target_arch = "wasm32"))]
+use bevy::render::pipelined_rendering::PipelinedRenderingPlugin;
+use bevy::{
+	app::ScheduleRunnerPlugin,
+	prelude::*,
+	render::renderer::{RenderDevice, RenderQueue},
+	window::ExitCondition,
+	winit::WinitPlugin,
+};
+use bevy_compute::prelude::*;
+
+/// This example uses a shader source file from the assets subdirectory
+const SHADER_ASSET_PATH: &str = "shaders/prefix_sum.wgsl";
+
+const COUNT: u32 = 256;
+
+/// This runs as a batch job, with no window or camera: it dispatches a fixed number of iterations, reads the result back, prints it and exits.
+fn main() -> AppExit {
+	let default_plugins = DefaultPlugins
+		.set(WindowPlugin { primary_window: None, exit_condition: ExitCondition::DontExit, close_when_requested: false })
+		.disable::<WinitPlugin>();
+	// The render world swaps the double buffer after every pass, and the main world only catches up a frame later,
+	// which pipelined rendering would extract the next pass before. There's no pipelined rendering on the web.
+	#[cfg(not(target_arch = "wasm32"))]
+	let default_plugins = default_plugins.disable::<PipelinedRenderingPlugin>();
+	App::new()
+		.add_plugins((
+			default_plugins,
+			// With no window to drive the frames, the app is updated in a loop of its own.
+			ScheduleRunnerPlugin::run_loop(Duration::from_secs_f64(1.0 / 60.0)),
+			BevyComputePlugin,
+		))
+		.add_systems(Startup, setup)
+		.add_systems(Update, print_and_exit)
+		.run()
+}
+
+#[derive(Resource)]
+struct Sums(ShaderBufferHandle);
+
+fn setup(
+	mut commands: Commands, mut buffer_set: ResMut<ShaderBufferSet>, render_device: Res<RenderDevice>,
+	render_queue: Res<RenderQueue>, mut start_compute_events: EventWriter<StartComputeEvent>,
+) {
+	let values = buffer_set
+		.add_storage_init(
+			&render_device,
+			&render_queue,
+			(1..=COUNT).collect::<Vec<u32>>(),
+			BufferUsages::COPY_SRC,
+			Binding::Double(0, (0, 1)),
+			false,
+		)
+		.unwrap();
+	let iteration = buffer_set
+		.add_uniform_init(&render_device, &render_queue, 0u32, BufferUsages::empty(), Binding::SingleBound(0, 2))
+		.unwrap();
+	// Double buffers can't be read back directly, so the front half is copied into this one first.
+	let sums = buffer_set
+		.add_storage_zeroed(
+			&render_device,
+			COUNT * size_of::<u32>() as u32,
+			BufferUsages::COPY_SRC,
+			Binding::SingleUnbound,
+			false,
+		)
+		.unwrap();
+	commands.insert_resource(Sums(sums));
+
+	// Each pass doubles how far back the sums reach, so log2(COUNT) passes cover every value.
+	let passes = NonZeroU32::new(COUNT.ilog2());
+	start_compute_events.send(StartComputeEvent {
+		sequence: ComputeSequenceId::MAIN,
+		tasks: vec![
+			ComputeTask::ping_pong(
+				"Scan",
+				SHADER_ASSET_PATH,
+				"scan",
+				DispatchSize::Exact { x: COUNT, y: 1, z: 1 },
+				&[values],
+				passes,
+			),
+			ComputeTask {
+				label: Some("Read back".to_owned()),
+				iterations: NonZeroU32::new(1),
+				run_mode: TaskRunMode::Continuous,
+				budget: None,
+				submission: TaskSubmission::WithFrame,
+				start_after: None,
+				iteration_buffer: None,
+				steps: vec![
+					ComputeStep {
+						schedule: StepSchedule::EveryIteration,
+						run_if: None,
+						action: ComputeAction::CopyBufferToBuffer {
+							src: values,
+							dst: sums,
+							src_offset: 0,
+							dst_offset: 0,
+							size: None,
+						},
+					},
+					ComputeStep {
+						schedule: StepSchedule::EveryIteration,
+						run_if: None,
+						action: ComputeAction::CopyBuffer { src: sums, offset: 0, size: None },
+					},
+				],
+			},
+		],
+		iteration_buffer: Some(iteration),
+		seed: 0,
+	});
+}
+
+fn print_and_exit(sums: Res<Sums>, mut readbacks: EventReader<BufferReadbackEvent>, mut exit: EventWriter<AppExit>) {
+	for readback in readbacks.read().filter(|readback| readback.handle == sums.0) {
+		let sums = readback.as_slice_of::<u32>().unwrap();
+		let expected = (1..=COUNT).scan(0, |sum, value| {
+			*sum += value;
+			Some(*sum)
+		});
+		println!("{:?}", sums);
+		if sums.iter().copied().eq(expected) {
+			println!("The prefix sum of 1 to {} matches the CPU's", COUNT);
+			exit.send(AppExit::Success);
+		} else {
+			error!("The prefix sum doesn't match the CPU's");
+			exit.send(AppExit::error());
+		}
+	}
+}
@@ -0,0 +1,280 @@
+extern crate bevy_compute;
+
+use bevy::{
+	core_pipeline::core_3d::{Opaque3d, Opaque3dBinKey, CORE_3D_DEPTH_FORMAT},
+	ecs::{
+		query::ROQueryItem,
+		system::{lifetimeless::SRes, SystemParamItem},
+	},
+	math::Vec3A,
+	prelude::*,
+	render::{
+		extract_component::{ExtractComponent, ExtractComponentPlugin},
+		extract_resource::{ExtractResource, ExtractResourcePlugin},
+		primitives::Aabb,
+		render_phase::{
+			AddRenderCommand, BinnedRenderPhaseType, DrawFunctions, PhaseItem, RenderCommand, RenderCommandResult,
+			SetItemPipeline, TrackedRenderPass, ViewBinnedRenderPhases,
+		},
+		render_resource::{
+			Buffer, BufferUsages, ColorTargetState, ColorWrites, CompareFunction, DepthStencilState, DrawIndirectArgs,
+			FragmentState, MultisampleState, PipelineCache, PrimitiveState, RenderPipelineDescriptor,
+			SpecializedRenderPipeline, SpecializedRenderPipelines, TextureFormat, VertexAttribute, VertexBufferLayout,
+			VertexFormat, VertexState, VertexStepMode,
+		},
+		renderer::{RenderDevice, RenderQueue},
+		view::{self, ExtractedView, RenderVisibleEntities, VisibilitySystems},
+		Render, RenderApp, RenderSet,
+	},
+};
+use bevy_compute::prelude::*;
+
+/// This example uses shader source files from the assets subdirectory
+const COMPUTE_SHADER_ASSET_PATH: &str = "shaders/indirect_particles.wgsl";
+const DRAW_SHADER_ASSET_PATH: &str = "shaders/indirect_particles_draw.wgsl";
+
+const PARTICLE_COUNT: u32 = 16384;
+const WORKGROUP_SIZE: u32 = 64;
+
+/// Each particle is a position, a velocity and a remaining life, padded to the 8 byte alignment of its `vec2<f32>`s.
+const PARTICLE_SIZE: u32 = 24;
+
+/// Each instance is a clip space position and size, followed by a color, both as `vec4<f32>`.
+const INSTANCE_SIZE: u32 = 32;
+
+/// The handles of the buffers the particles are drawn from. These never change once the buffers are made, so this is only extracted to the render world once.
+#[derive(Resource, Clone, ExtractResource)]
+struct ParticleDrawBuffers {
+	instances: ShaderBufferHandle,
+	draw_args: ShaderBufferHandle,
+}
+
+/// Marks the entity the particles are drawn for, so it takes part in visibility checks like any other renderable entity.
+#[derive(Component, Clone, ExtractComponent)]
+struct ParticleFountain;
+
+fn main() {
+	let mut app = App::new();
+	app
+		.insert_resource(ClearColor(Color::BLACK))
+		.add_plugins((
+			DefaultPlugins,
+			BevyComputePlugin,
+			ExtractComponentPlugin::<ParticleFountain>::default(),
+			ExtractResourcePlugin::<ParticleDrawBuffers>::default(),
+		))
+		.add_systems(Startup, setup)
+		.add_systems(
+			PostUpdate,
+			view::check_visibility::<With<ParticleFountain>>.in_set(VisibilitySystems::CheckVisibility),
+		);
+
+	// The compute node always runs before the camera driver node in the render graph, so the instances and the
+	// instance count written in a frame are the ones drawn that frame.
+	app
+		.sub_app_mut(RenderApp)
+		.init_resource::<ParticlePipeline>()
+		.init_resource::<SpecializedRenderPipelines<ParticlePipeline>>()
+		.add_render_command::<Opaque3d, DrawParticlesCommands>()
+		.add_systems(Render, prepare_particle_buffers.in_set(RenderSet::Prepare))
+		.add_systems(Render, queue_particles.in_set(RenderSet::Queue));
+
+	app.run();
+}
+
+fn setup(
+	mut commands: Commands, mut buffer_set: ResMut<ShaderBufferSet>, render_device: Res<RenderDevice>,
+	render_queue: Res<RenderQueue>, mut start_compute_events: EventWriter<StartComputeEvent>,
+) {
+	buffer_set
+		.add_storage_zeroed(
+			&render_device,
+			PARTICLE_COUNT * PARTICLE_SIZE,
+			BufferUsages::STORAGE,
+			Binding::SingleBound(0, 0),
+			false,
+		)
+		.unwrap();
+	let instances =
+		buffer_set.add_vertex_buffer(&render_device, PARTICLE_COUNT * INSTANCE_SIZE, Binding::SingleBound(0, 1)).unwrap();
+	// Every particle is a quad of 6 vertices, and the compute shader counts up the instances.
+	let draw_args = buffer_set
+		.add_draw_indirect_args(
+			&render_device,
+			DrawIndirectArgs { vertex_count: 6, instance_count: 0, first_vertex: 0, first_instance: 0 },
+			Binding::SingleBound(0, 2),
+		)
+		.unwrap();
+	let iteration = buffer_set
+		.add_uniform_init(&render_device, &render_queue, 0u32, BufferUsages::UNIFORM, Binding::SingleBound(0, 3))
+		.unwrap();
+	commands.insert_resource(ParticleDrawBuffers { instances, draw_args });
+
+	commands.spawn((
+		Visibility::default(),
+		Transform::default(),
+		Aabb { center: Vec3A::ZERO, half_extents: Vec3A::splat(1.0) },
+		ParticleFountain,
+	));
+	commands.spawn((Camera3d::default(), Transform::from_xyz(0.0, 0.0, 1.0).looking_at(Vec3::ZERO, Vec3::Y)));
+
+	let run_shader = |entry_point: &str, workgroups: u32| ComputeStep {
+		schedule: StepSchedule::EveryIteration,
+		run_if: None,
+		action: ComputeAction::RunShader {
+			shader: COMPUTE_SHADER_ASSET_PATH.to_owned(),
+			entry_point: entry_point.to_owned(),
+			shader_defs: Vec::new(),
+			overrides: Vec::new(),
+			dispatch: DispatchSize::Workgroups { x: workgroups, y: 1, z: 1 },
+			auto_swap: Vec::new(),
+			skip_if_empty: None,
+			buffer_orientation: Vec::new(),
+			push_constants: None,
+			dynamic_offsets: Vec::new(),
+		},
+	};
+	start_compute_events.send(StartComputeEvent {
+		sequence: ComputeSequenceId::MAIN,
+		tasks: vec![ComputeTask {
+			label: Some("Emit particles".to_owned()),
+			iterations: None,
+			run_mode: TaskRunMode::Continuous,
+			budget: None,
+			submission: TaskSubmission::WithFrame,
+			start_after: None,
+			iteration_buffer: None,
+			steps: vec![run_shader("reset_instance_count", 1), run_shader("update", PARTICLE_COUNT / WORKGROUP_SIZE)],
+		}],
+		iteration_buffer: Some(iteration),
+		seed: 0,
+	});
+}
+
+/// The GPU buffers the particles are drawn from, fetched from the render world's copy of the [ShaderBufferSet]. The render command needs these to be owned by a resource, as it borrows them for the whole render pass.
+#[derive(Resource)]
+struct ParticleGpuBuffers {
+	instances: Buffer,
+	draw_args: Buffer,
+}
+
+fn prepare_particle_buffers(
+	mut commands: Commands, buffer_set: Res<ShaderBufferSet>, buffers: Option<Res<ParticleDrawBuffers>>,
+) {
+	let Some(buffers) = buffers else {
+		return;
+	};
+	if let (Some(instances), Some(draw_args)) =
+		(buffer_set.gpu_buffer(buffers.instances), buffer_set.gpu_buffer(buffers.draw_args))
+	{
+		commands.insert_resource(ParticleGpuBuffers { instances, draw_args });
+	}
+}
+
+#[derive(Resource)]
+struct ParticlePipeline {
+	shader: Handle<Shader>,
+}
+
+impl FromWorld for ParticlePipeline {
+	fn from_world(world: &mut World) -> Self {
+		ParticlePipeline { shader: world.resource::<AssetServer>().load(DRAW_SHADER_ASSET_PATH) }
+	}
+}
+
+impl SpecializedRenderPipeline for ParticlePipeline {
+	type Key = Msaa;
+
+	fn specialize(&self, msaa: Self::Key) -> RenderPipelineDescriptor {
+		RenderPipelineDescriptor {
+			label: Some("indirect particles pipeline".into()),
+			layout: vec![],
+			push_constant_ranges: vec![],
+			vertex: VertexState {
+				shader: self.shader.clone(),
+				shader_defs: vec![],
+				entry_point: "vertex".into(),
+				buffers: vec![VertexBufferLayout {
+					array_stride: INSTANCE_SIZE as u64,
+					step_mode: VertexStepMode::Instance,
+					attributes: vec![
+						VertexAttribute { format: VertexFormat::Float32x4, offset: 0, shader_location: 0 },
+						VertexAttribute { format: VertexFormat::Float32x4, offset: 16, shader_location: 1 },
+					],
+				}],
+			},
+			fragment: Some(FragmentState {
+				shader: self.shader.clone(),
+				shader_defs: vec![],
+				entry_point: "fragment".into(),
+				targets: vec![Some(ColorTargetState {
+					format: TextureFormat::bevy_default(),
+					blend: None,
+					write_mask: ColorWrites::ALL,
+				})],
+			}),
+			primitive: PrimitiveState::default(),
+			depth_stencil: Some(DepthStencilState {
+				format: CORE_3D_DEPTH_FORMAT,
+				depth_write_enabled: false,
+				depth_compare: CompareFunction::Always,
+				stencil: default(),
+				bias: default(),
+			}),
+			multisample: MultisampleState { count: msaa.samples(), mask: !0, alpha_to_coverage_enabled: false },
+			zero_initialize_workgroup_memory: false,
+		}
+	}
+}
+
+type DrawParticlesCommands = (SetItemPipeline, DrawParticles);
+
+struct DrawParticles;
+
+impl<P: PhaseItem> RenderCommand<P> for DrawParticles {
+	type Param = SRes<ParticleGpuBuffers>;
+	type ViewQuery = ();
+	type ItemQuery = ();
+
+	fn render<'w>(
+		_: &P, _: ROQueryItem<'w, Self::ViewQuery>, _: Option<ROQueryItem<'w, Self::ItemQuery>>,
+		buffers: SystemParamItem<'w, '_, Self::Param>, pass: &mut TrackedRenderPass<'w>,
+	) -> RenderCommandResult {
+		let buffers = buffers.into_inner();
+		pass.set_vertex_buffer(0, buffers.instances.slice(..));
+		pass.draw_indirect(&buffers.draw_args, 0);
+		RenderCommandResult::Success
+	}
+}
+
+fn queue_particles(
+	pipeline_cache: Res<PipelineCache>, pipeline: Res<ParticlePipeline>, buffers: Option<Res<ParticleGpuBuffers>>,
+	mut opaque_render_phases: ResMut<ViewBinnedRenderPhases<Opaque3d>>,
+	opaque_draw_functions: Res<DrawFunctions<Opaque3d>>,
+	mut specialized_pipelines: ResMut<SpecializedRenderPipelines<ParticlePipeline>>,
+	views: Query<(Entity, &RenderVisibleEntities, &Msaa), With<ExtractedView>>,
+) {
+	if buffers.is_none() {
+		return;
+	}
+	let draw_function = opaque_draw_functions.read().id::<DrawParticlesCommands>();
+	for (view_entity, visible_entities, msaa) in views.iter() {
+		let Some(opaque_phase) = opaque_render_phases.get_mut(&view_entity) else {
+			continue;
+		};
+		for &entity in visible_entities.get::<With<ParticleFountain>>().iter() {
+			let pipeline_id = specialized_pipelines.specialize(&pipeline_cache, &pipeline, *msaa);
+			opaque_phase.add(
+				Opaque3dBinKey {
+					draw_function,
+					pipeline: pipeline_id,
+					asset_id: AssetId::<Mesh>::invalid().untyped(),
+					material_bind_group_id: None,
+					lightmap_image: None,
+				},
+				entity,
+				BinnedRenderPhaseType::NonMesh,
+			);
+		}
+	}
+}
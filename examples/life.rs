@@ -4,19 +4,15 @@ use std::num::NonZeroU32;
 
 use bevy::{
 	prelude::*,
-	render::render_resource::{StorageTextureAccess, TextureFormat},
-};
-use bevy_compute::{
-	BevyComputePlugin, Binding, ComputeAction, ComputeStep, ComputeTask, DoubleBufferedSprite, ShaderBufferSet,
-	StartComputeEvent,
+	render::renderer::{RenderDevice, RenderQueue},
 };
+use bevy_compute::prelude::*;
 
 /// This example uses a shader source file from the assets subdirectory
 const SHADER_ASSET_PATH: &str = "shaders/game_of_life.wgsl";
 
 const DISPLAY_FACTOR: u32 = 4;
 const SIZE: (u32, u32) = (1280 / DISPLAY_FACTOR, 720 / DISPLAY_FACTOR);
-const WORKGROUP_SIZE: u32 = 8;
 
 fn main() {
 	App::new()
@@ -34,73 +30,68 @@ fn main() {
 				})
 				.set(ImagePlugin::default_nearest()),
 			BevyComputePlugin,
+			ComputeTextureMaterialPlugin,
 		))
-		.add_systems(Startup, setup)
+		.add_systems(Startup, (setup, setup_drawing).chain())
 		.run();
 }
 
 fn setup(
 	mut commands: Commands, mut buffer_set: ResMut<ShaderBufferSet>, mut images: ResMut<Assets<Image>>,
+	mut meshes: ResMut<Assets<Mesh>>, mut materials: ResMut<Assets<ComputeTextureMaterial2d>>,
 	mut start_compute_events: EventWriter<StartComputeEvent>,
 ) {
-	let image = buffer_set.add_texture_fill(
-		&mut images,
-		SIZE.0,
-		SIZE.1,
-		TextureFormat::R32Float,
-		&0.0f32.to_ne_bytes(),
-		StorageTextureAccess::ReadOnly,
-		Binding::Double(0, (0, 1)),
-	);
+	let image = buffer_set
+		.add_texture_fill(
+			&mut images,
+			SIZE.0,
+			SIZE.1,
+			TextureFormat::R32Float,
+			&0.0f32.to_ne_bytes(),
+			StorageTextureAccess::ReadOnly,
+			Binding::Double(0, (0, 1)),
+		)
+		.unwrap();
 
+	// The cells are stored in an R32Float texture, which a sprite would display in red,
+	// so display it in grayscale instead.
+	let material =
+		ComputeTextureMaterial2d::new(buffer_set.image_handle(image).unwrap()).with_colormap(Colormap::Grayscale, 0.0, 1.0);
 	commands.spawn((
-		Sprite {
-			image: buffer_set.image_handle(image).unwrap(),
-			custom_size: Some(Vec2::new(SIZE.0 as f32, SIZE.1 as f32)),
-			..default()
-		},
+		compute_texture_2d(&mut meshes, &mut materials, image, material, Vec2::new(SIZE.0 as f32, SIZE.1 as f32)),
 		Transform::from_scale(Vec3::splat(DISPLAY_FACTOR as f32)),
-		DoubleBufferedSprite(image),
 	));
 	commands.spawn(Camera2d);
+	buffer_set.name_buffer(image, "cells");
 
+	// Each task reads the cells from the front buffer and writes them to the back buffer, then swaps them.
+	let exact = DispatchSize::Exact { x: SIZE.0, y: SIZE.1, z: 1 };
 	start_compute_events.send(StartComputeEvent {
+		sequence: ComputeSequenceId::MAIN,
 		tasks: vec![
-			ComputeTask {
-				label: Some("Init".to_owned()),
-				iterations: NonZeroU32::new(1),
-				steps: vec![
-					ComputeStep {
-						max_frequency: None,
-						action: ComputeAction::RunShader {
-							shader: SHADER_ASSET_PATH.to_owned(),
-							entry_point: "init".to_owned(),
-							x_workgroup_count: SIZE.0 / WORKGROUP_SIZE,
-							y_workgroup_count: SIZE.1 / WORKGROUP_SIZE,
-							z_workgroup_count: 1,
-						},
-					},
-					ComputeStep { max_frequency: None, action: ComputeAction::SwapBuffers { buffer: image } },
-				],
-			},
-			ComputeTask {
-				label: Some("Update".to_owned()),
-				iterations: None,
-				steps: vec![
-					ComputeStep {
-						max_frequency: NonZeroU32::new(10),
-						action: ComputeAction::RunShader {
-							shader: SHADER_ASSET_PATH.to_owned(),
-							entry_point: "update".to_owned(),
-							x_workgroup_count: SIZE.0 / WORKGROUP_SIZE,
-							y_workgroup_count: SIZE.1 / WORKGROUP_SIZE,
-							z_workgroup_count: 1,
-						},
-					},
-					ComputeStep { max_frequency: NonZeroU32::new(10), action: ComputeAction::SwapBuffers { buffer: image } },
-				],
-			},
+			ComputeTask::ping_pong("Init", SHADER_ASSET_PATH, "init", exact, &[image], NonZeroU32::new(1)),
+			ComputeTask::ping_pong("Update", SHADER_ASSET_PATH, "update", exact, &[image], None)
+				.with_schedule(StepSchedule::MaxFrequencyHz(10.0)),
 		],
 		iteration_buffer: None,
+		seed: 0,
 	});
 }
+
+/// Holding the left mouse button down draws live cells under the cursor, which the shader reads from the cursor uniform.
+fn setup_drawing(
+	mut buffer_set: ResMut<ShaderBufferSet>, render_device: Res<RenderDevice>, render_queue: Res<RenderQueue>,
+) {
+	let cells = buffer_set.handle_by_name("cells").unwrap();
+	// The texture is displayed centered on the origin, scaled up to fill the window.
+	let display_size = Vec2::new((SIZE.0 * DISPLAY_FACTOR) as f32, (SIZE.1 * DISPLAY_FACTOR) as f32);
+	buffer_set
+		.add_cursor_uniform(
+			&render_device,
+			&render_queue,
+			cells,
+			Rect::from_center_size(Vec2::ZERO, display_size),
+			Binding::SingleBound(0, 2),
+		)
+		.unwrap();
+}
@@ -0,0 +1,115 @@
+extern crate bevy_compute;
+
+use bevy::{
+	pbr::{ExtendedMaterial, MaterialExtension},
+	prelude::*,
+	render::{
+		render_resource::{AsBindGroup, ShaderRef},
+		renderer::{RenderDevice, RenderQueue},
+	},
+};
+use bevy_compute::prelude::*;
+
+/// This example uses shader source files from the assets subdirectory
+const SHADER_ASSET_PATH: &str = "shaders/lut.wgsl";
+const MATERIAL_SHADER_ASSET_PATH: &str = "shaders/lut_material.wgsl";
+
+const LUT_SIZE: u32 = 256;
+
+type LutMaterial = ExtendedMaterial<StandardMaterial, LutExtension>;
+
+/// Takes the base color of a [StandardMaterial] from a lookup texture, which the compute sequence rewrites every frame without it ever leaving the GPU.
+#[derive(Asset, AsBindGroup, Reflect, Debug, Clone)]
+struct LutExtension {
+	#[texture(100)]
+	#[sampler(101)]
+	lut: Handle<Image>,
+}
+
+impl MaterialExtension for LutExtension {
+	fn fragment_shader() -> ShaderRef { MATERIAL_SHADER_ASSET_PATH.into() }
+}
+
+fn main() {
+	App::new()
+		.add_plugins((DefaultPlugins, BevyComputePlugin, MaterialPlugin::<LutMaterial>::default()))
+		.add_systems(Startup, (setup, setup_globals).chain())
+		.add_systems(Update, rotate)
+		.run();
+}
+
+fn setup(
+	mut commands: Commands, mut buffer_set: ResMut<ShaderBufferSet>, mut images: ResMut<Assets<Image>>,
+	mut meshes: ResMut<Assets<Mesh>>, mut materials: ResMut<Assets<LutMaterial>>,
+	mut start_compute_events: EventWriter<StartComputeEvent>,
+) {
+	let lut = buffer_set
+		.add_texture_fill(
+			&mut images,
+			LUT_SIZE,
+			1,
+			TextureFormat::Rgba8Unorm,
+			&[0, 0, 0, 255],
+			StorageTextureAccess::WriteOnly,
+			Binding::SingleBound(0, 0),
+		)
+		.unwrap();
+	buffer_set.name_buffer(lut, "lut");
+	// The material samples the texture in the same frame the compute shader writes it.
+	buffer_set.consume_in_render(lut);
+
+	commands.spawn((
+		Mesh3d(meshes.add(Torus::new(0.6, 1.4))),
+		MeshMaterial3d(materials.add(ExtendedMaterial {
+			base: StandardMaterial { perceptual_roughness: 0.4, ..default() },
+			extension: LutExtension { lut: buffer_set.image_handle(lut).unwrap() },
+		})),
+		Transform::from_rotation(Quat::from_rotation_x(0.8)),
+	));
+	commands.spawn((DirectionalLight::default(), Transform::from_xyz(2.0, 4.0, 3.0).looking_at(Vec3::ZERO, Vec3::Y)));
+	commands.spawn((Camera3d::default(), Transform::from_xyz(0.0, 2.0, 5.0).looking_at(Vec3::ZERO, Vec3::Y)));
+
+	start_compute_events.send(StartComputeEvent {
+		sequence: ComputeSequenceId::MAIN,
+		tasks: vec![ComputeTask {
+			label: Some("Fill LUT".to_owned()),
+			iterations: None,
+			run_mode: TaskRunMode::Continuous,
+			budget: None,
+			submission: TaskSubmission::WithFrame,
+			start_after: None,
+			iteration_buffer: None,
+			steps: vec![ComputeStep {
+				schedule: StepSchedule::EveryIteration,
+				run_if: None,
+				action: ComputeAction::RunShader {
+					shader: SHADER_ASSET_PATH.to_owned(),
+					entry_point: "fill".to_owned(),
+					shader_defs: Vec::new(),
+					overrides: Vec::new(),
+					dispatch: DispatchSize::Exact { x: LUT_SIZE, y: 1, z: 1 },
+					auto_swap: Vec::new(),
+					skip_if_empty: None,
+					buffer_orientation: Vec::new(),
+					push_constants: None,
+					dynamic_offsets: Vec::new(),
+				},
+			}],
+		}],
+		iteration_buffer: None,
+		seed: 0,
+	});
+}
+
+/// The shader cycles the palette by the time in the globals uniform.
+fn setup_globals(
+	mut buffer_set: ResMut<ShaderBufferSet>, render_device: Res<RenderDevice>, render_queue: Res<RenderQueue>,
+) {
+	buffer_set.add_globals(&render_device, &render_queue, Binding::SingleBound(0, 1)).unwrap();
+}
+
+fn rotate(mut query: Query<&mut Transform, With<Mesh3d>>, time: Res<Time>) {
+	for mut transform in &mut query {
+		transform.rotate_y(time.delta_secs() * 0.5);
+	}
+}
@@ -0,0 +1,215 @@
+// The ShaderType derive generates layout checks that trip the dead code lint.
+#![allow(dead_code)]
+
+extern crate bevy_compute;
+
+use std::num::NonZeroU32;
+
+use bevy::{
+	prelude::*,
+	render::{
+		render_resource::ShaderType,
+		renderer::{RenderDevice, RenderQueue},
+	},
+};
+use bevy_compute::prelude::*;
+
+/// This example uses a shader source file from the assets subdirectory
+const SHADER_ASSET_PATH: &str = "shaders/particles.wgsl";
+
+const PARTICLE_COUNT: u32 = 4096;
+const WORKGROUP_SIZE: u32 = 64;
+
+#[derive(ShaderType, Clone, Default)]
+struct Particle {
+	position: Vec2,
+	velocity: Vec2,
+	life: f32,
+}
+
+#[derive(ShaderType, Clone, Default)]
+struct Params {
+	time: f32,
+	delta: f32,
+	gravity: f32,
+	spawning: u32,
+}
+
+#[derive(Resource)]
+struct ParticleBuffers {
+	positions: ShaderBufferHandle,
+	alive_count: ShaderBufferHandle,
+	params: ShaderBufferHandle,
+}
+
+#[derive(Resource, Default)]
+struct ParticlePositions(Vec<Vec2>);
+
+#[derive(Component)]
+struct AliveCountText;
+
+fn main() {
+	App::new()
+		.insert_resource(ClearColor(Color::BLACK))
+		.init_resource::<ParticlePositions>()
+		.add_plugins((DefaultPlugins, BevyComputePlugin))
+		.add_systems(Startup, setup)
+		.add_systems(Update, (update_params, receive_readbacks, draw_particles))
+		.run();
+}
+
+fn setup(
+	mut commands: Commands, mut buffer_set: ResMut<ShaderBufferSet>, render_device: Res<RenderDevice>,
+	render_queue: Res<RenderQueue>, mut start_compute_events: EventWriter<StartComputeEvent>,
+) {
+	// Each step reads the particles from the front buffer, which is read-only, and writes them to the back buffer,
+	// which the SwapBuffers step after it makes the front buffer for the next.
+	let particles = buffer_set
+		.add_storage_init(
+			&render_device,
+			&render_queue,
+			vec![Particle::default(); PARTICLE_COUNT as usize],
+			BufferUsages::STORAGE,
+			Binding::Double(0, (0, 1)),
+			false,
+		)
+		.unwrap();
+	let positions = buffer_set
+		.add_storage_zeroed(
+			&render_device,
+			PARTICLE_COUNT * 8,
+			BufferUsages::STORAGE | BufferUsages::COPY_SRC,
+			Binding::SingleBound(0, 2),
+			false,
+		)
+		.unwrap();
+	let alive_count = buffer_set
+		.add_storage_zeroed(
+			&render_device,
+			4,
+			BufferUsages::STORAGE | BufferUsages::COPY_SRC,
+			Binding::SingleBound(0, 3),
+			false,
+		)
+		.unwrap();
+	let params = buffer_set
+		.add_uniform_init(
+			&render_device,
+			&render_queue,
+			Params::default(),
+			BufferUsages::UNIFORM,
+			Binding::SingleBound(0, 4),
+		)
+		.unwrap();
+	commands.insert_resource(ParticleBuffers { positions, alive_count, params });
+
+	commands.spawn(Camera2d);
+	commands.spawn((
+		Text::new("Press space to toggle spawning"),
+		Node { position_type: PositionType::Absolute, top: Val::Px(12.0), left: Val::Px(12.0), ..default() },
+		AliveCountText,
+	));
+
+	let run_shader = |entry_point: &str, workgroups: u32| ComputeStep {
+		schedule: StepSchedule::EveryIteration,
+		run_if: None,
+		action: ComputeAction::RunShader {
+			shader: SHADER_ASSET_PATH.to_owned(),
+			entry_point: entry_point.to_owned(),
+			shader_defs: Vec::new(),
+			overrides: Vec::new(),
+			dispatch: DispatchSize::Workgroups { x: workgroups, y: 1, z: 1 },
+			auto_swap: Vec::new(),
+			skip_if_empty: None,
+			buffer_orientation: Vec::new(),
+			push_constants: None,
+			dynamic_offsets: Vec::new(),
+		},
+	};
+	start_compute_events.send(StartComputeEvent {
+		sequence: ComputeSequenceId::MAIN,
+		tasks: vec![
+			ComputeTask {
+				label: Some("Init".to_owned()),
+				iterations: NonZeroU32::new(1),
+				run_mode: TaskRunMode::Continuous,
+				budget: None,
+				submission: TaskSubmission::WithFrame,
+				start_after: None,
+				iteration_buffer: None,
+				steps: vec![
+					run_shader("init", PARTICLE_COUNT / WORKGROUP_SIZE),
+					ComputeStep {
+						schedule: StepSchedule::EveryIteration,
+						run_if: None,
+						action: ComputeAction::SwapBuffers { buffer: particles },
+					},
+				],
+			},
+			ComputeTask {
+				label: Some("Update".to_owned()),
+				iterations: None,
+				run_mode: TaskRunMode::Continuous,
+				budget: None,
+				submission: TaskSubmission::WithFrame,
+				start_after: None,
+				iteration_buffer: None,
+				steps: vec![
+					run_shader("reset_counter", 1),
+					run_shader("update", PARTICLE_COUNT / WORKGROUP_SIZE),
+					ComputeStep {
+						schedule: StepSchedule::EveryIteration,
+						run_if: None,
+						action: ComputeAction::SwapBuffers { buffer: particles },
+					},
+					ComputeStep {
+						schedule: StepSchedule::EveryIteration,
+						run_if: None,
+						action: ComputeAction::CopyBuffer { src: positions, offset: 0, size: None },
+					},
+					ComputeStep {
+						schedule: StepSchedule::MaxFrequencyHz(10.0),
+						run_if: None,
+						action: ComputeAction::CopyBuffer { src: alive_count, offset: 0, size: None },
+					},
+				],
+			},
+		],
+		iteration_buffer: None,
+		seed: 0,
+	});
+}
+
+/// The time uniform is written every frame, after the compute sequence has started.
+fn update_params(
+	buffer_set: Res<ShaderBufferSet>, buffers: Res<ParticleBuffers>, time: Res<Time>, keys: Res<ButtonInput<KeyCode>>,
+	mut spawning: Local<Option<bool>>,
+) {
+	let spawning = spawning.get_or_insert(true);
+	if keys.just_pressed(KeyCode::Space) {
+		*spawning = !*spawning;
+	}
+	let params =
+		Params { time: time.elapsed_secs(), delta: time.delta_secs(), gravity: -200.0, spawning: *spawning as u32 };
+	buffer_set.write_buffer(buffers.params, params).unwrap();
+}
+
+fn receive_readbacks(
+	mut readback_events: EventReader<BufferReadbackEvent>, buffers: Res<ParticleBuffers>,
+	mut positions: ResMut<ParticlePositions>, mut text: Query<&mut Text, With<AliveCountText>>,
+) {
+	for event in readback_events.read() {
+		if event.handle == buffers.positions {
+			positions.0 = event.decode::<Vec<Vec2>>().unwrap();
+		} else if event.handle == buffers.alive_count {
+			let alive_count = event.decode::<u32>().unwrap();
+			text.single_mut().0 = format!("{} particles alive, press space to toggle spawning", alive_count);
+		}
+	}
+}
+
+fn draw_particles(mut gizmos: Gizmos, positions: Res<ParticlePositions>) {
+	for position in positions.0.iter().filter(|position| position.x < 1.0e8) {
+		gizmos.circle_2d(*position, 2.0, Color::srgb(1.0, 0.6, 0.2));
+	}
+}
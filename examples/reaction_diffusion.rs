@@ -0,0 +1,230 @@
+// The ShaderType derive generates layout checks that trip the dead code lint.
+#![allow(dead_code)]
+
+extern crate bevy_compute;
+
+use std::num::NonZeroU32;
+
+use bevy::{
+	prelude::*,
+	render::{
+		render_resource::ShaderType,
+		renderer::{RenderDevice, RenderQueue},
+	},
+	window::PrimaryWindow,
+};
+use bevy_compute::prelude::*;
+
+/// This example uses a shader source file from the assets subdirectory
+const SHADER_ASSET_PATH: &str = "shaders/reaction_diffusion.wgsl";
+
+const DISPLAY_FACTOR: u32 = 4;
+const SIZE: (u32, u32) = (1280 / DISPLAY_FACTOR, 720 / DISPLAY_FACTOR);
+const WORKGROUP_SIZE: u32 = 8;
+
+#[derive(ShaderType, Clone, Default)]
+struct Params {
+	feed: f32,
+	kill: f32,
+	diffuse_a: f32,
+	diffuse_b: f32,
+}
+
+#[derive(ShaderType, Clone, Default)]
+struct Paint {
+	position: Vec2,
+	radius: f32,
+	enabled: u32,
+}
+
+#[derive(Resource)]
+struct ReactionDiffusionBuffers {
+	params: ShaderBufferHandle,
+	paint: ShaderBufferHandle,
+}
+
+#[derive(Resource)]
+struct FeedKill {
+	feed: f32,
+	kill: f32,
+}
+
+#[derive(Component)]
+struct ParamsText;
+
+fn main() {
+	App::new()
+		.insert_resource(ClearColor(Color::BLACK))
+		.insert_resource(FeedKill { feed: 0.055, kill: 0.062 })
+		.add_plugins((
+			DefaultPlugins
+				.set(WindowPlugin {
+					primary_window: Some(Window {
+						resolution: ((SIZE.0 * DISPLAY_FACTOR) as f32, (SIZE.1 * DISPLAY_FACTOR) as f32).into(),
+						..default()
+					}),
+					..default()
+				})
+				.set(ImagePlugin::default_nearest()),
+			BevyComputePlugin,
+		))
+		.add_systems(Startup, setup)
+		.add_systems(Update, (update_params, update_paint))
+		.run();
+}
+
+fn setup(
+	mut commands: Commands, mut buffer_set: ResMut<ShaderBufferSet>, mut images: ResMut<Assets<Image>>,
+	render_device: Res<RenderDevice>, render_queue: Res<RenderQueue>,
+	mut start_compute_events: EventWriter<StartComputeEvent>,
+) {
+	let image = buffer_set
+		.add_texture_fill(
+			&mut images,
+			SIZE.0,
+			SIZE.1,
+			TextureFormat::Rg32Float,
+			&[1.0f32.to_ne_bytes(), 0.0f32.to_ne_bytes()].concat(),
+			StorageTextureAccess::ReadOnly,
+			Binding::Double(0, (0, 1)),
+		)
+		.unwrap();
+	let params = buffer_set
+		.add_uniform_init(
+			&render_device,
+			&render_queue,
+			Params::default(),
+			BufferUsages::UNIFORM,
+			Binding::SingleBound(0, 2),
+		)
+		.unwrap();
+	let paint = buffer_set
+		.add_uniform_init(
+			&render_device,
+			&render_queue,
+			Paint::default(),
+			BufferUsages::UNIFORM,
+			Binding::SingleBound(0, 3),
+		)
+		.unwrap();
+	commands.insert_resource(ReactionDiffusionBuffers { params, paint });
+
+	commands.spawn((
+		Sprite {
+			image: buffer_set.image_handle(image).unwrap(),
+			custom_size: Some(Vec2::new(SIZE.0 as f32, SIZE.1 as f32)),
+			..default()
+		},
+		Transform::from_scale(Vec3::splat(DISPLAY_FACTOR as f32)),
+		DisplayComputeBuffer(image),
+	));
+	commands.spawn(Camera2d);
+	commands.spawn((
+		Text::default(),
+		Node { position_type: PositionType::Absolute, top: Val::Px(12.0), left: Val::Px(12.0), ..default() },
+		ParamsText,
+	));
+
+	let run_shader = |entry_point: &str| ComputeStep {
+		schedule: StepSchedule::EveryIteration,
+		run_if: None,
+		action: ComputeAction::RunShader {
+			shader: SHADER_ASSET_PATH.to_owned(),
+			entry_point: entry_point.to_owned(),
+			shader_defs: Vec::new(),
+			overrides: Vec::new(),
+			dispatch: DispatchSize::Workgroups { x: SIZE.0 / WORKGROUP_SIZE, y: SIZE.1 / WORKGROUP_SIZE, z: 1 },
+			auto_swap: Vec::new(),
+			skip_if_empty: None,
+			buffer_orientation: Vec::new(),
+			push_constants: None,
+			dynamic_offsets: Vec::new(),
+		},
+	};
+	let swap = ComputeStep {
+		schedule: StepSchedule::EveryIteration,
+		run_if: None,
+		action: ComputeAction::SwapBuffers { buffer: image },
+	};
+
+	start_compute_events.send(StartComputeEvent {
+		sequence: ComputeSequenceId::MAIN,
+		tasks: vec![
+			ComputeTask {
+				label: Some("Init".to_owned()),
+				iterations: NonZeroU32::new(1),
+				run_mode: TaskRunMode::Continuous,
+				budget: None,
+				submission: TaskSubmission::WithFrame,
+				start_after: None,
+				iteration_buffer: None,
+				steps: vec![run_shader("init"), swap.clone()],
+			},
+			// There's no way to make a step conditional, so the paint step runs
+			// every iteration, and the shader checks whether it should do anything.
+			// It has to go after the update, as it overwrites part of what the
+			// update wrote, and before the swap, so that it's still writing to the
+			// back buffer.
+			ComputeTask {
+				label: Some("Update".to_owned()),
+				iterations: None,
+				run_mode: TaskRunMode::Continuous,
+				budget: None,
+				submission: TaskSubmission::WithFrame,
+				start_after: None,
+				iteration_buffer: None,
+				steps: vec![run_shader("update"), run_shader("paint_seed"), swap],
+			},
+		],
+		iteration_buffer: None,
+		seed: 0,
+	});
+}
+
+/// The arrow keys change the feed and kill rates. The uniform is rewritten every frame, long after the compute sequence has started, and the change is picked up by the next iteration.
+fn update_params(
+	buffer_set: Res<ShaderBufferSet>, buffers: Res<ReactionDiffusionBuffers>, keys: Res<ButtonInput<KeyCode>>,
+	mut feed_kill: ResMut<FeedKill>, mut text: Query<&mut Text, With<ParamsText>>,
+) {
+	if keys.just_pressed(KeyCode::ArrowUp) {
+		feed_kill.feed += 0.001;
+	}
+	if keys.just_pressed(KeyCode::ArrowDown) {
+		feed_kill.feed -= 0.001;
+	}
+	if keys.just_pressed(KeyCode::ArrowRight) {
+		feed_kill.kill += 0.001;
+	}
+	if keys.just_pressed(KeyCode::ArrowLeft) {
+		feed_kill.kill -= 0.001;
+	}
+	let params = Params { feed: feed_kill.feed, kill: feed_kill.kill, diffuse_a: 1.0, diffuse_b: 0.5 };
+	buffer_set.write_buffer(buffers.params, params).unwrap();
+	text.single_mut().0 =
+		format!("feed: {:.3} (up/down), kill: {:.3} (left/right), click to seed", feed_kill.feed, feed_kill.kill);
+}
+
+/// While the left mouse button is held, the paint uniform is enabled, and positioned under the cursor in texture space.
+fn update_paint(
+	buffer_set: Res<ShaderBufferSet>, buffers: Res<ReactionDiffusionBuffers>, mouse: Res<ButtonInput<MouseButton>>,
+	window: Query<&Window, With<PrimaryWindow>>, camera: Query<(&Camera, &GlobalTransform)>,
+) {
+	let (camera, camera_transform) = camera.single();
+	let cursor = window
+		.single()
+		.cursor_position()
+		.and_then(|cursor| camera.viewport_to_world_2d(camera_transform, cursor).ok())
+		.filter(|_| mouse.pressed(MouseButton::Left));
+	let paint = match cursor {
+		Some(cursor) => Paint {
+			position: Vec2::new(
+				cursor.x / DISPLAY_FACTOR as f32 + SIZE.0 as f32 / 2.0,
+				SIZE.1 as f32 / 2.0 - cursor.y / DISPLAY_FACTOR as f32,
+			),
+			radius: 5.0,
+			enabled: 1,
+		},
+		None => Paint::default(),
+	};
+	buffer_set.write_buffer(buffers.paint, paint).unwrap();
+}
@@ -0,0 +1,103 @@
+use std::sync::{Arc, Mutex};
+
+use bevy::{
+	prelude::*,
+	render::{render_resource::Buffer, renderer::RenderQueue, Extract},
+};
+
+use crate::shader_buffer_set::{ShaderBufferError, ShaderBufferHandle, ShaderBufferSet};
+
+/// The writes to storage and uniform buffers made from the main world, on their way to the GPU. This is a resource in the main world, and it's shared with the [ShaderBufferSet], whose [write_buffer](ShaderBufferSet::write_buffer) and its relatives queue their writes here too, so writes made either way land in the order they were made.
+///
+/// Queueing a write doesn't change the [ShaderBufferSet], so it isn't extracted to the render world again because of it, which only happens when buffers are added, deleted or configured. Parameters set every frame only cost the write itself. The writes queued during a frame are taken out of the queue when it's extracted, and made in the render world in [PrepareResources](bevy::render::RenderSet::PrepareResources), before anything the frame records. The double buffers swapped and the rings rotated by the compute steps go the same way, so mirroring them in the main world doesn't have the whole set extracted again either.
+#[derive(Resource, Clone, Default)]
+pub struct BufferWriteQueue {
+	writes: Arc<Mutex<Vec<QueuedWrite>>>,
+	swaps: Arc<Mutex<Vec<ShaderBufferHandle>>>,
+	rotations: Arc<Mutex<Vec<(ShaderBufferHandle, u32)>>>,
+}
+
+/// Where a queued write goes.
+enum WriteTarget {
+	/// Every half of a storage or uniform buffer, looked up when the write is made, so a write to a buffer that's been deleted since is dropped.
+	AllHalves(ShaderBufferHandle),
+
+	/// One half of a double buffer, picked when the write was queued. The write is dropped if the buffer has been deleted since, along with its halves.
+	Half(ShaderBufferHandle, Buffer),
+}
+
+struct QueuedWrite {
+	target: WriteTarget,
+	offset: u64,
+	data: Vec<u8>,
+}
+
+impl BufferWriteQueue {
+	/// Queue a write of raw bytes to the start of a storage or uniform buffer, or of both halves of a double buffer, to be made before the next frame's compute steps. This can be used from any system, with only `Res<BufferWriteQueue>`. Unlike [write_buffer_bytes](ShaderBufferSet::write_buffer_bytes), the write isn't checked until it's made in the render world: a write to a buffer that's been deleted by then is dropped, and one to a buffer that isn't a storage or uniform buffer, or that runs past its end, or whose size isn't a multiple of 4, is dropped with an error logged. Nor is it [recorded](ShaderBufferSet::start_recording).
+	pub fn queue_write(&self, handle: ShaderBufferHandle, bytes: &[u8]) { self.queue_write_halves(handle, 0, bytes.to_vec()); }
+
+	/// Queue a write of raw bytes at an offset into every half of a buffer.
+	pub(crate) fn queue_write_halves(&self, handle: ShaderBufferHandle, offset: u64, data: Vec<u8>) {
+		self.writes.lock().unwrap().push(QueuedWrite { target: WriteTarget::AllHalves(handle), offset, data });
+	}
+
+	/// Queue a write of raw bytes to the start of one half of a double buffer.
+	pub(crate) fn queue_write_half(&self, handle: ShaderBufferHandle, half: Buffer, data: Vec<u8>) {
+		self.writes.lock().unwrap().push(QueuedWrite { target: WriteTarget::Half(handle, half), offset: 0, data });
+	}
+
+	/// Queue the swap of a double buffer that's been made in the main world, to be made on the render world's copy of the set.
+	pub(crate) fn queue_swap(&self, handle: ShaderBufferHandle) { self.swaps.lock().unwrap().push(handle); }
+
+	/// Queue the rotation of a ring by a number of steps that's been made in the main world, to be made on the render world's copy of the set.
+	pub(crate) fn queue_rotation(&self, handle: ShaderBufferHandle, steps: u32) {
+		self.rotations.lock().unwrap().push((handle, steps));
+	}
+
+	fn take(&self) -> Vec<QueuedWrite> { std::mem::take(&mut *self.writes.lock().unwrap()) }
+
+	/// Take the swaps queued since the last extract, in the order they were made.
+	pub(crate) fn take_swaps(&self) -> Vec<ShaderBufferHandle> { std::mem::take(&mut *self.swaps.lock().unwrap()) }
+
+	/// Take the rotations queued since the last extract, with the number of steps of each.
+	pub(crate) fn take_rotations(&self) -> Vec<(ShaderBufferHandle, u32)> {
+		std::mem::take(&mut *self.rotations.lock().unwrap())
+	}
+}
+
+/// The writes taken out of the [BufferWriteQueue] when the frame was extracted, waiting to be made.
+#[derive(Resource, Default)]
+pub(crate) struct ExtractedBufferWrites(Vec<QueuedWrite>);
+
+pub(crate) fn extract_buffer_writes(
+	queue: Extract<Option<Res<BufferWriteQueue>>>, mut extracted: ResMut<ExtractedBufferWrites>,
+) {
+	if let Some(queue) = &*queue {
+		extracted.0.extend(queue.take());
+	}
+}
+
+/// Make the writes extracted this frame, in the order they were queued.
+pub(crate) fn make_buffer_writes(
+	mut extracted: ResMut<ExtractedBufferWrites>, buffers: Option<Res<ShaderBufferSet>>, render_queue: Res<RenderQueue>,
+) {
+	let writes = std::mem::take(&mut extracted.0);
+	let Some(buffers) = buffers else {
+		return;
+	};
+	for write in writes {
+		match write.target {
+			WriteTarget::Half(handle, half) => {
+				if buffers.all_gpu_buffers(handle).iter().any(|buffer| buffer.id() == half.id()) {
+					render_queue.write_buffer(&half, write.offset, &write.data);
+				}
+			}
+			WriteTarget::AllHalves(handle) => match buffers.check_write_range(handle, write.offset, write.data.len() as u64) {
+				Ok(()) => buffers.write_all_halves(handle, write.offset, &write.data, &render_queue),
+				// The buffer was deleted after the write was queued.
+				Err(ShaderBufferError::MissingBuffer { .. }) => {}
+				Err(error) => error!("Dropped a queued buffer write: {}", error),
+			},
+		}
+	}
+}
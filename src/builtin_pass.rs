@@ -0,0 +1,182 @@
+use std::{borrow::Cow, mem::size_of, num::NonZeroU64};
+
+use bevy::{
+	prelude::*,
+	render::{
+		render_asset::RenderAssets,
+		render_resource::{
+			BindGroupEntry, BindGroupLayout, BindGroupLayoutEntry, BindingResource, BindingType, Buffer, BufferBinding,
+			BufferBindingType, BufferInitDescriptor, BufferUsages, CachedComputePipelineId, ComputePassDescriptor,
+			ComputePipelineDescriptor, PipelineCache, ShaderStages, StorageTextureAccess, TextureFormat, TextureSampleType,
+			TextureViewDimension,
+		},
+		renderer::{RenderContext, RenderDevice},
+		texture::GpuImage,
+	},
+};
+use bytemuck::Pod;
+
+use crate::shader_buffer_set::{ShaderBufferHandle, ShaderBufferSet};
+
+/// One dispatch of a builtin pass, with the index of the entry point to run, and the parameters to run it with. The parameters are copied into the uniform buffer as-is, so they must be laid out the same as the WGSL struct, which is easiest with a `#[repr(C)]` struct of only 4-byte fields.
+pub(crate) struct BuiltinDispatch<P> {
+	pub entry_point: usize,
+	pub params: P,
+	pub workgroups: UVec3,
+}
+
+/// One of the resources a builtin pass binds, after the uniform buffer of parameters.
+pub(crate) enum BuiltinBinding {
+	/// A storage buffer from the [ShaderBufferSet], bound read-write as `array<u32>` or similar.
+	Buffer(ShaderBufferHandle),
+
+	/// A buffer owned by the pass itself, such as one for intermediate results, bound the same as a [BuiltinBinding::Buffer].
+	Scratch(Buffer),
+
+	/// A texture from the [ShaderBufferSet], bound as a `texture_2d<f32>` to read with `textureLoad`.
+	Texture(ShaderBufferHandle),
+
+	/// A texture from the [ShaderBufferSet], of the given format, bound as a write-only `texture_storage_2d`.
+	StorageTexture(ShaderBufferHandle, TextureFormat),
+}
+
+/// The pipelines and parameters needed to run one of the crate's own shaders, such as the sort, as a series of dispatches. These don't use the [ShaderBufferSet]'s bind groups. Instead, each has a bind group of its own, with a uniform buffer holding the parameters of every dispatch at binding 0, selected with a dynamic offset, followed by the resources it operates on, in order.
+pub(crate) struct BuiltinPass {
+	layout: BindGroupLayout,
+	pipelines: Vec<CachedComputePipelineId>,
+	params: Buffer,
+	params_size: u64,
+	bindings: Vec<BuiltinBinding>,
+	dispatches: Vec<(usize, u32, UVec3)>,
+}
+
+impl BuiltinPass {
+	pub fn new<P: Pod>(
+		device: &RenderDevice, pipeline_cache: &PipelineCache, shader: Handle<Shader>, entry_points: &[&'static str],
+		bindings: Vec<BuiltinBinding>, dispatches: Vec<BuiltinDispatch<P>>,
+	) -> Self {
+		let mut entries = vec![BindGroupLayoutEntry {
+			binding: 0,
+			visibility: ShaderStages::COMPUTE,
+			ty: BindingType::Buffer {
+				ty: BufferBindingType::Uniform,
+				has_dynamic_offset: true,
+				min_binding_size: NonZeroU64::new(size_of::<P>() as u64),
+			},
+			count: None,
+		}];
+		entries.extend(bindings.iter().enumerate().map(|(i, binding)| BindGroupLayoutEntry {
+			binding: i as u32 + 1,
+			visibility: ShaderStages::COMPUTE,
+			ty: match binding {
+				BuiltinBinding::Buffer(_) | BuiltinBinding::Scratch(_) => BindingType::Buffer {
+					ty: BufferBindingType::Storage { read_only: false },
+					has_dynamic_offset: false,
+					min_binding_size: None,
+				},
+				BuiltinBinding::Texture(_) => BindingType::Texture {
+					sample_type: TextureSampleType::Float { filterable: false },
+					view_dimension: TextureViewDimension::D2,
+					multisampled: false,
+				},
+				BuiltinBinding::StorageTexture(_, format) => BindingType::StorageTexture {
+					access: StorageTextureAccess::WriteOnly,
+					format: *format,
+					view_dimension: TextureViewDimension::D2,
+				},
+			},
+			count: None,
+		}));
+		let layout = device.create_bind_group_layout(None, &entries);
+
+		let pipelines = entry_points
+			.iter()
+			.map(|entry_point| {
+				pipeline_cache.queue_compute_pipeline(ComputePipelineDescriptor {
+					label: None,
+					layout: vec![layout.clone()],
+					push_constant_ranges: Vec::new(),
+					shader: shader.clone(),
+					shader_defs: vec![],
+					entry_point: Cow::Borrowed(entry_point),
+					zero_initialize_workgroup_memory: true,
+				})
+			})
+			.collect();
+
+		// Every dispatch's parameters are packed into one buffer, each at an offset
+		// aligned so it can be selected with a dynamic offset.
+		let alignment = device.limits().min_uniform_buffer_offset_alignment as u64;
+		let params_size = size_of::<P>() as u64;
+		let stride = params_size.div_ceil(alignment) * alignment;
+		let mut bytes = vec![0u8; (stride * dispatches.len().max(1) as u64) as usize];
+		let dispatches = dispatches
+			.into_iter()
+			.enumerate()
+			.map(|(i, dispatch)| {
+				let offset = i as u64 * stride;
+				bytes[offset as usize..(offset + params_size) as usize].copy_from_slice(bytemuck::bytes_of(&dispatch.params));
+				(dispatch.entry_point, offset as u32, dispatch.workgroups)
+			})
+			.collect();
+		let params = device.create_buffer_with_data(&BufferInitDescriptor {
+			label: None,
+			contents: &bytes,
+			usage: BufferUsages::UNIFORM,
+		});
+
+		Self { layout, pipelines, params, params_size, bindings, dispatches }
+	}
+
+	pub fn pipeline_ids(&self) -> impl Iterator<Item = CachedComputePipelineId> + '_ { self.pipelines.iter().copied() }
+
+	pub fn run(
+		&self, buffers: &ShaderBufferSet, gpu_images: &RenderAssets<GpuImage>, pipeline_cache: &PipelineCache,
+		device: &RenderDevice, context: &mut RenderContext,
+	) {
+		let gpu_buffers = self
+			.bindings
+			.iter()
+			.map(|binding| match binding {
+				BuiltinBinding::Buffer(handle) => Some(buffers.gpu_buffer(*handle).unwrap_or_else(|| {
+					panic!("Tried to run a builtin pass on {}, which isn't a storage buffer", handle.describe(buffers))
+				})),
+				BuiltinBinding::Scratch(buffer) => Some(buffer.clone()),
+				BuiltinBinding::Texture(_) | BuiltinBinding::StorageTexture(..) => None,
+			})
+			.collect::<Vec<_>>();
+		let mut entries = vec![BindGroupEntry {
+			binding: 0,
+			resource: BindingResource::Buffer(BufferBinding {
+				buffer: &self.params,
+				offset: 0,
+				size: NonZeroU64::new(self.params_size),
+			}),
+		}];
+		entries.extend(self.bindings.iter().zip(&gpu_buffers).enumerate().map(|(i, (binding, gpu_buffer))| {
+			let resource = match (binding, gpu_buffer) {
+				(_, Some(buffer)) => buffer.as_entire_binding(),
+				(BuiltinBinding::Texture(handle) | BuiltinBinding::StorageTexture(handle, _), None) => {
+					let Some(image) = buffers.image_handle(*handle).and_then(|image| gpu_images.get(&image)) else {
+						panic!("Tried to run a builtin pass on {}, which isn't a loaded texture", handle.describe(buffers));
+					};
+					BindingResource::TextureView(&image.texture_view)
+				}
+				(_, None) => panic!("Somehow tried to bind a buffer of a builtin pass without its GPU buffer"),
+			};
+			BindGroupEntry { binding: i as u32 + 1, resource }
+		}));
+		let bind_group = device.create_bind_group(None, &self.layout, &entries);
+
+		let encoder = context.command_encoder();
+		for (entry_point, offset, workgroups) in self.dispatches.iter() {
+			let Some(pipeline) = pipeline_cache.get_compute_pipeline(self.pipelines[*entry_point]) else {
+				panic!("Somehow running a builtin pass without its pipelines being loaded");
+			};
+			let mut pass = encoder.begin_compute_pass(&ComputePassDescriptor::default());
+			pass.set_pipeline(pipeline);
+			pass.set_bind_group(0, &bind_group, &[*offset]);
+			pass.dispatch_workgroups(workgroups.x, workgroups.y, workgroups.z);
+		}
+	}
+}
@@ -0,0 +1,240 @@
+use std::{
+	collections::HashSet,
+	sync::{Arc, Mutex, OnceLock},
+};
+
+use bevy::{
+	prelude::*,
+	render::{
+		extract_resource::ExtractResource,
+		render_resource::{
+			Buffer, BufferDescriptor, BufferUsages, CommandEncoder, MapMode, PipelineCache, TextureSampleType,
+			TextureViewDimension,
+		},
+		renderer::RenderDevice,
+	},
+};
+use bytemuck::{Pod, Zeroable};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use super::{
+	builtin_pass::{BuiltinBinding, BuiltinDispatch, BuiltinPass},
+	compute_data_transmission::{send_message, ComputeMessage, MessageSender},
+	ComputeErrorEvent, ComputeSequenceId,
+};
+use crate::shader_buffer_set::{ShaderBufferHandle, ShaderBufferSet};
+
+pub(crate) const CHECK_FINITE_SHADER_HANDLE: Handle<Shader> =
+	Handle::weak_from_u128(0x2c87_f1a4_5e09_4d3b_9a6f_d81e_07b4_c5a2);
+
+const WORKGROUP_SIZE: u32 = 256;
+
+/// The size of the result of a finite check, the index of the first value that isn't finite, followed by its bits.
+const RESULT_SIZE: u64 = 8;
+
+/// What a [CheckFinite](crate::ComputeAction::CheckFinite) action scans for NaNs and infinities.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum FiniteCheckType {
+	/// A storage buffer of `f32`s, every one of which is checked. Buffers of vectors or structs made only of `f32`s can be checked this way too, with the index counting the `f32`s from the start of the buffer.
+	Buffer,
+
+	/// A texture with a single float channel, such as [R32Float](bevy::render::render_resource::TextureFormat::R32Float), every texel of which is checked, with the index counting texels row by row. If it's a double buffer, the front buffer is read.
+	Texture,
+}
+
+/// Whether [CheckFinite](crate::ComputeAction::CheckFinite) steps run. They're on by default in debug builds, and off in release builds, where the steps are skipped without building their pipelines or dispatching anything, so they can be left in a sequence for good. This is read when a task starts, so changing it takes effect from the next task on.
+#[derive(Resource, Clone, Copy, Debug, ExtractResource)]
+pub struct ComputeFiniteChecks(pub bool);
+
+impl Default for ComputeFiniteChecks {
+	fn default() -> Self { Self(cfg!(debug_assertions)) }
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Pod, Zeroable)]
+struct FiniteCheckParams {
+	count: u32,
+	width: u32,
+}
+
+/// Check that a buffer or texture can be scanned for values that aren't finite, returning what's wrong if it can't, which [check_finite_pass] panics with.
+pub(crate) fn check_finite(
+	buffers: &ShaderBufferSet, buffer: ShaderBufferHandle, element: FiniteCheckType,
+) -> Result<(), String> {
+	match element {
+		FiniteCheckType::Buffer if buffers.gpu_buffer(buffer).is_none() => {
+			Err(format!("Tried to check {} for NaNs and infinities, but it isn't a storage buffer", buffer.describe(buffers)))
+		}
+		FiniteCheckType::Buffer => Ok(()),
+		FiniteCheckType::Texture => match buffers.texture_format_and_size(buffer) {
+			None => {
+				Err(format!("Tried to check {} for NaNs and infinities, but it isn't a texture", buffer.describe(buffers)))
+			}
+			Some((format, _))
+				if format.components() != 1
+					|| !matches!(format.sample_type(None, None), Some(TextureSampleType::Float { .. })) =>
+			{
+				Err(format!(
+					"Tried to check {} for NaNs and infinities, but only textures with a single float channel are supported",
+					buffer.describe(buffers)
+				))
+			}
+			Some(_) if buffers.texture_dimension(buffer) != Some(TextureViewDimension::D2) => Err(format!(
+				"Tried to check {} for NaNs and infinities, but only 2D textures are supported",
+				buffer.describe(buffers)
+			)),
+			Some(_) => Ok(()),
+		},
+	}
+}
+
+/// Make the buffer a finite check writes its result into, to be passed to [check_finite_pass], and copied out of with [ComputeFiniteChecker::record].
+pub(crate) fn finite_check_result(device: &RenderDevice) -> Buffer {
+	device.create_buffer(&BufferDescriptor {
+		label: None,
+		size: RESULT_SIZE,
+		usage: BufferUsages::STORAGE | BufferUsages::COPY_SRC,
+		mapped_at_creation: false,
+	})
+}
+
+/// Build the passes for a finite check, one to clear the result, one to find the first value that isn't finite with atomics, and one to record that value.
+pub(crate) fn check_finite_pass(
+	device: &RenderDevice, pipeline_cache: &PipelineCache, buffers: &ShaderBufferSet, buffer: ShaderBufferHandle,
+	element: FiniteCheckType, result: Buffer,
+) -> BuiltinPass {
+	if let Err(error) = check_finite(buffers, buffer, element) {
+		panic!("{}", error);
+	}
+
+	let (count, width, entry_points, src_binding) = match element {
+		FiniteCheckType::Buffer => {
+			let src_buffer = buffers.gpu_buffer(buffer).unwrap();
+			((src_buffer.size() / 4) as u32, 0, ["scan_buffer", "record_buffer"], BuiltinBinding::Buffer(buffer))
+		}
+		FiniteCheckType::Texture => {
+			let (_, size) = buffers.texture_format_and_size(buffer).unwrap();
+			(size.width * size.height, size.width, ["scan_texture", "record_texture"], BuiltinBinding::Texture(buffer))
+		}
+	};
+
+	let params = FiniteCheckParams { count, width };
+	let dispatches = vec![
+		BuiltinDispatch { entry_point: 0, params, workgroups: UVec3::ONE },
+		BuiltinDispatch { entry_point: 1, params, workgroups: UVec3::new(count.div_ceil(WORKGROUP_SIZE).max(1), 1, 1) },
+		BuiltinDispatch { entry_point: 2, params, workgroups: UVec3::ONE },
+	];
+
+	BuiltinPass::new(
+		device,
+		pipeline_cache,
+		CHECK_FINITE_SHADER_HANDLE,
+		// Only the entry points for the source are built, as the others expect a different binding.
+		&["clear", entry_points[0], entry_points[1]],
+		vec![BuiltinBinding::Scratch(result), src_binding],
+		dispatches,
+	)
+}
+
+/// Where a finite check ran, to say where in the compute sequence a value that isn't finite was found.
+pub(crate) struct FiniteCheckSite {
+	pub sender: MessageSender,
+	pub sequence: ComputeSequenceId,
+	pub generation: u32,
+	pub task: usize,
+	pub label: Option<String>,
+	pub step: usize,
+	pub iteration: u32,
+	pub buffer: ShaderBufferHandle,
+}
+
+/// A finite check recorded into a frame, and the buffer its result is copied into, which is mapped once the frame has been submitted.
+struct PendingCheck {
+	site: FiniteCheckSite,
+	buffer: Buffer,
+	mapped: Option<Arc<OnceLock<bool>>>,
+}
+
+#[derive(Default)]
+struct FiniteCheckerState {
+	pending: Vec<PendingCheck>,
+	free_buffers: Vec<Buffer>,
+	found: HashSet<(u32, usize, usize)>,
+}
+
+/// Reads back the results of finite checks in the render world, and reports the values that aren't finite. Each step only reports the first it finds, identified by the generation of its compute sequence, its task and its index, after which it stops checking.
+#[derive(Resource, Default)]
+pub(crate) struct ComputeFiniteChecker(Mutex<FiniteCheckerState>);
+
+impl ComputeFiniteChecker {
+	/// Whether a step has already found a value that isn't finite, so there's no need to run it again.
+	pub fn found(&self, generation: u32, task: usize, step: usize) -> bool {
+		self.0.lock().unwrap().found.contains(&(generation, task, step))
+	}
+
+	/// Drop the results still to be read, and the buffers they're copied into, once the app is exiting.
+	pub fn release(&self) { *self.0.lock().unwrap() = FiniteCheckerState::default(); }
+
+	/// Copy the result of a finite check that's just been recorded somewhere it can be read back from once the frame has been submitted.
+	pub fn record(&self, device: &RenderDevice, encoder: &mut CommandEncoder, result: &Buffer, site: FiniteCheckSite) {
+		let mut state = self.0.lock().unwrap();
+		let buffer = state.free_buffers.pop().unwrap_or_else(|| {
+			device.create_buffer(&BufferDescriptor {
+				label: None,
+				size: RESULT_SIZE,
+				usage: BufferUsages::MAP_READ | BufferUsages::COPY_DST,
+				mapped_at_creation: false,
+			})
+		});
+		encoder.copy_buffer_to_buffer(result, 0, &buffer, 0, RESULT_SIZE);
+		state.pending.push(PendingCheck { site, buffer, mapped: None });
+	}
+}
+
+/// Map the results of the finite checks recorded this frame, as the frame's commands have just been submitted, and report any value that isn't finite in the ones that have been mapped since.
+pub(crate) fn read_finite_checks(checker: Res<ComputeFiniteChecker>) {
+	let mut state = checker.0.lock().unwrap();
+	if state.pending.is_empty() {
+		return;
+	}
+	let state = &mut *state;
+	state.pending.retain_mut(|check| {
+		let Some(mapped) = &check.mapped else {
+			let mapped = Arc::new(OnceLock::new());
+			let result = mapped.clone();
+			check.buffer.slice(..).map_async(MapMode::Read, move |map_result| {
+				let _ = result.set(map_result.is_ok());
+			});
+			check.mapped = Some(mapped);
+			return true;
+		};
+		let Some(&success) = mapped.get() else {
+			return true;
+		};
+		if success {
+			let [index, bits]: [u32; 2] = bytemuck::pod_read_unaligned(&check.buffer.slice(..).get_mapped_range());
+			check.buffer.unmap();
+			state.free_buffers.push(check.buffer.clone());
+			let site = &check.site;
+			// Checks of the same step that were already in flight when it first found something aren't reported again.
+			if index != u32::MAX && state.found.insert((site.generation, site.task, site.step)) {
+				send_message(
+					&site.sender,
+					ComputeMessage::Error(ComputeErrorEvent::NonFiniteValue {
+						sequence: site.sequence,
+						task: site.task,
+						label: site.label.clone(),
+						step: site.step,
+						iteration: site.iteration,
+						buffer: site.buffer,
+						index,
+						value: f32::from_bits(bits),
+					}),
+				);
+			}
+		}
+		false
+	});
+}
@@ -0,0 +1,167 @@
+use bevy::{
+	prelude::*,
+	render::{
+		render_asset::RenderAssets,
+		render_resource::{
+			Buffer, BufferInitDescriptor, BufferUsages, CommandEncoder, Extent3d, ImageCopyBuffer, ImageCopyTexture,
+			ImageDataLayout, Origin3d, TextureAspect, TextureFormat,
+		},
+		renderer::RenderDevice,
+		texture::GpuImage,
+	},
+};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use wgpu::{COPY_BUFFER_ALIGNMENT, COPY_BYTES_PER_ROW_ALIGNMENT};
+
+use crate::{
+	half_float::f32_to_f16,
+	shader_buffer_set::{ShaderBufferHandle, ShaderBufferSet},
+};
+
+/// The most bytes of a texture a [ClearTexture](crate::ComputeAction::ClearTexture) step fills with each copy. The buffer the value is copied from holds this many bytes of rows, so filling a large texture takes several copies, rather than a buffer the size of the texture.
+const MAX_FILL_BYTES: u64 = 1 << 20;
+
+/// Which halves of a double buffer a [ClearBuffer](crate::ComputeAction::ClearBuffer) or [ClearTexture](crate::ComputeAction::ClearTexture) step clears. Any other buffer is always cleared, whichever this is.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum ClearHalves {
+	/// Clear the back buffer only, as it is when the step runs, which is the one the next shader writes, so the front buffer it reads from is left alone.
+	#[default]
+	Back,
+
+	/// Clear both halves.
+	Both,
+}
+
+/// Check that a range of a buffer can be cleared, returning what's wrong if it can't, which the compute node panics with when the task starts.
+pub(crate) fn check_clear_buffer(
+	buffers: &ShaderBufferSet, buffer: ShaderBufferHandle, offset: u64, size: Option<u64>,
+) -> Result<(), String> {
+	let Some(gpu_buffer) = buffers.gpu_buffer(buffer) else {
+		return Err(format!("Tried to clear {}, which isn't a storage or uniform buffer", buffer.describe(buffers)));
+	};
+	if offset % COPY_BUFFER_ALIGNMENT != 0 || size.is_some_and(|size| size % COPY_BUFFER_ALIGNMENT != 0) {
+		return Err(format!(
+			"Tried to clear {} bytes at offset {} of {}, but the offset and size must be multiples of 4",
+			size.map_or("all the".to_owned(), |size| size.to_string()),
+			offset,
+			buffer.describe(buffers)
+		));
+	}
+	if offset + size.unwrap_or(0) > gpu_buffer.size() {
+		return Err(format!(
+			"Tried to clear {} bytes at offset {} of {}, which is only {} bytes",
+			size.unwrap_or(0),
+			offset,
+			buffer.describe(buffers),
+			gpu_buffer.size()
+		));
+	}
+	Ok(())
+}
+
+/// Check that a texture can be filled with a value, returning the bytes of one texel of it, or what's wrong if it can't, which [texture_fill] panics with.
+pub(crate) fn check_clear_texture(
+	buffers: &ShaderBufferSet, texture: ShaderBufferHandle, value: [f64; 4],
+) -> Result<Vec<u8>, String> {
+	let Some((format, _)) = buffers.texture_format_and_size(texture) else {
+		return Err(format!("Tried to clear {}, which isn't a storage texture", texture.describe(buffers)));
+	};
+	texel_bytes(format, value).ok_or_else(|| {
+		format!(
+			"Tried to clear {}, but its format, {:?}, isn't one that can be filled with a value. Only uncompressed color formats of 8, 16 or 32 bits a channel can be",
+			texture.describe(buffers),
+			format
+		)
+	})
+}
+
+/// The bytes of one texel of a format with each of its channels set from a value, in order, or `None` if it isn't an uncompressed color format of 8, 16 or 32 bits a channel. Values are clamped to the range of normalized formats, and rounded, and truncated to integer formats, saturating at their limits.
+fn texel_bytes(format: TextureFormat, value: [f64; 4]) -> Option<Vec<u8>> {
+	use TextureFormat::*;
+	fn each<const N: usize>(value: &[f64], f: impl Fn(f64) -> [u8; N]) -> Vec<u8> {
+		value.iter().flat_map(|value| f(*value)).collect()
+	}
+	let unorm = |value: f64, max: f64| (value.clamp(0.0, 1.0) * max).round();
+	let snorm = |value: f64, max: f64| (value.clamp(-1.0, 1.0) * max).round();
+	let channels = &value[..(format.components() as usize).min(4)];
+	Some(match format {
+		R8Unorm | Rg8Unorm | Rgba8Unorm => each(channels, |value| [unorm(value, 255.0) as u8]),
+		Bgra8Unorm => each(&[value[2], value[1], value[0], value[3]], |value| [unorm(value, 255.0) as u8]),
+		R8Snorm | Rg8Snorm | Rgba8Snorm => each(channels, |value| (snorm(value, 127.0) as i8).to_le_bytes()),
+		R8Uint | Rg8Uint | Rgba8Uint => each(channels, |value| [value as u8]),
+		R8Sint | Rg8Sint | Rgba8Sint => each(channels, |value| (value as i8).to_le_bytes()),
+		R16Unorm | Rg16Unorm | Rgba16Unorm => each(channels, |value| (unorm(value, 65535.0) as u16).to_le_bytes()),
+		R16Snorm | Rg16Snorm | Rgba16Snorm => each(channels, |value| (snorm(value, 32767.0) as i16).to_le_bytes()),
+		R16Uint | Rg16Uint | Rgba16Uint => each(channels, |value| (value as u16).to_le_bytes()),
+		R16Sint | Rg16Sint | Rgba16Sint => each(channels, |value| (value as i16).to_le_bytes()),
+		R16Float | Rg16Float | Rgba16Float => each(channels, |value| f32_to_f16(value as f32).to_le_bytes()),
+		R32Uint | Rg32Uint | Rgba32Uint => each(channels, |value| (value as u32).to_le_bytes()),
+		R32Sint | Rg32Sint | Rgba32Sint => each(channels, |value| (value as i32).to_le_bytes()),
+		R32Float | Rg32Float | Rgba32Float => each(channels, |value| (value as f32).to_le_bytes()),
+		_ => return None,
+	})
+}
+
+/// Rows of a [ClearTexture](crate::ComputeAction::ClearTexture) step's value, each as wide as the texture, and padded out to the alignment copies need, made when its task starts. The texture is filled by copying them over each band of its rows in turn, in every layer.
+pub(crate) struct TextureFill {
+	buffer: Buffer,
+	bytes_per_row: u32,
+	rows: u32,
+	size: Extent3d,
+}
+
+/// Make the rows a texture is filled from, panicking if it can't be filled with the value.
+pub(crate) fn texture_fill(
+	device: &RenderDevice, buffers: &ShaderBufferSet, texture: ShaderBufferHandle, value: [f64; 4],
+) -> TextureFill {
+	let texel = check_clear_texture(buffers, texture, value).unwrap_or_else(|error| panic!("{}", error));
+	let (_, size) = buffers.texture_format_and_size(texture).unwrap();
+	let row = texel.repeat(size.width as usize);
+	let bytes_per_row = (row.len() as u32).next_multiple_of(COPY_BYTES_PER_ROW_ALIGNMENT);
+	let rows = (MAX_FILL_BYTES / bytes_per_row as u64).clamp(1, size.height as u64) as u32;
+	let mut contents = vec![0; bytes_per_row as usize * rows as usize];
+	for padded in contents.chunks_mut(bytes_per_row as usize) {
+		padded[..row.len()].copy_from_slice(&row);
+	}
+	let buffer = device.create_buffer_with_data(&BufferInitDescriptor {
+		label: None,
+		contents: &contents,
+		usage: BufferUsages::COPY_SRC,
+	});
+	TextureFill { buffer, bytes_per_row, rows, size }
+}
+
+impl TextureFill {
+	/// Fill every texel of each of the images with the value. Images that aren't on the GPU yet are skipped, as they'll be uploaded with their initial contents anyway.
+	pub fn clear(&self, images: Vec<Handle<Image>>, gpu_images: &RenderAssets<GpuImage>, encoder: &mut CommandEncoder) {
+		for image in images {
+			let Some(gpu_image) = gpu_images.get(&image) else {
+				continue;
+			};
+			for z in 0..self.size.depth_or_array_layers {
+				for y in (0..self.size.height).step_by(self.rows as usize) {
+					let rows = self.rows.min(self.size.height - y);
+					encoder.copy_buffer_to_texture(
+						ImageCopyBuffer {
+							buffer: &self.buffer,
+							layout: ImageDataLayout {
+								offset: 0,
+								bytes_per_row: Some(self.bytes_per_row),
+								rows_per_image: Some(rows),
+							},
+						},
+						ImageCopyTexture {
+							texture: &gpu_image.texture,
+							mip_level: 0,
+							origin: Origin3d { x: 0, y, z },
+							aspect: TextureAspect::All,
+						},
+						Extent3d { width: self.size.width, height: rows, depth_or_array_layers: 1 },
+					);
+				}
+			}
+		}
+	}
+}
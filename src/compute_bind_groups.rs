@@ -1,4 +1,13 @@
 use bevy::{prelude::*, render::render_resource::BindGroup};
 
+/// The bind groups bevy_compute binds for every group of the [ShaderBufferSet](crate::ShaderBufferSet) while a compute sequence is running, rebuilt every frame. This is a resource in the render world, and only exists once a compute sequence has started. To use one in a render pipeline, build the pipeline against the layout from [bind_group_layout](crate::ShaderBufferRenderSet::bind_group_layout), and make the buffers in the group visible to the right stages with [set_visibility](crate::ShaderBufferSet::set_visibility).
 #[derive(Resource)]
-pub struct ComputeBindGroups(pub Vec<BindGroup>);
+pub struct ComputeBindGroups(pub(crate) Vec<BindGroup>);
+
+impl ComputeBindGroups {
+	/// Get the bind group for a group, or `None` if the group doesn't exist. Only valid for the current frame.
+	pub fn get(&self, group: u32) -> Option<&BindGroup> { self.0.get(group as usize) }
+}
+
+#[derive(Resource)]
+pub struct ComputePerEntityBindGroups(pub Vec<BindGroup>);
@@ -0,0 +1,149 @@
+use std::{
+	sync::{Arc, Mutex, OnceLock},
+	time::Duration,
+};
+
+use bevy::{
+	prelude::*,
+	render::{
+		render_resource::{Buffer, BufferDescriptor, BufferUsages, CommandEncoder, MapMode, WgpuFeatures},
+		renderer::{RenderDevice, RenderQueue},
+	},
+	utils::HashMap,
+};
+use wgpu::{QuerySet, QuerySetDescriptor, QueryType, QUERY_SIZE};
+
+use super::{
+	compute_data_transmission::{send_message, ComputeMessage},
+	compute_diagnostics::{ComputeDiagnosticsEnabled, GPU_TIME},
+	compute_sequence::ComputeSequences,
+};
+
+/// The size of the two timestamps written around a frame's iterations.
+const TIMESTAMPS_SIZE: u64 = 2 * QUERY_SIZE as u64;
+
+/// The iterations of a task recorded in one frame, and the buffer their timestamps are copied into, which is mapped once the frame has been submitted.
+struct TimedFrame {
+	task: (u32, usize),
+	iterations: u32,
+	buffer: Buffer,
+	mapped: Option<Arc<OnceLock<bool>>>,
+}
+
+#[derive(Default)]
+struct BudgetTimerState {
+	query_set: Option<QuerySet>,
+	resolve_buffer: Option<Buffer>,
+	free_buffers: Vec<Buffer>,
+	frames: Vec<TimedFrame>,
+	per_iteration: HashMap<(u32, usize), Duration>,
+}
+
+/// Times the iterations of tasks with a [FrameBudget](crate::FrameBudget), or of every task when diagnostics are on, on the GPU, with timestamps written before and after each frame's iterations, in the render world. Tasks are identified by the generation of their compute sequence and their index.
+#[derive(Resource, Default)]
+pub(crate) struct ComputeBudgetTimer(Mutex<BudgetTimerState>);
+
+impl ComputeBudgetTimer {
+	/// Whether iterations can be timed, which needs timestamps to be written between commands, rather than only around passes.
+	pub fn supported(device: &RenderDevice) -> bool {
+		device.features().contains(WgpuFeatures::TIMESTAMP_QUERY | WgpuFeatures::TIMESTAMP_QUERY_INSIDE_ENCODERS)
+	}
+
+	/// Write the timestamp before a frame's iterations of a task.
+	pub fn begin(&self, device: &RenderDevice, encoder: &mut CommandEncoder) {
+		let mut state = self.0.lock().unwrap();
+		let query_set = state.query_set.get_or_insert_with(|| {
+			device.wgpu_device().create_query_set(&QuerySetDescriptor { label: None, ty: QueryType::Timestamp, count: 2 })
+		});
+		encoder.write_timestamp(query_set, 0);
+	}
+
+	/// Write the timestamp after a frame's iterations of a task, and copy both timestamps somewhere they can be read back from once the frame has been submitted.
+	pub fn end(&self, device: &RenderDevice, encoder: &mut CommandEncoder, task: (u32, usize), iterations: u32) {
+		let mut state = self.0.lock().unwrap();
+		let state = &mut *state;
+		let Some(query_set) = &state.query_set else {
+			panic!("Somehow ended the timing of compute iterations without beginning it");
+		};
+		encoder.write_timestamp(query_set, 1);
+		let resolve_buffer = state.resolve_buffer.get_or_insert_with(|| {
+			device.create_buffer(&BufferDescriptor {
+				label: None,
+				size: TIMESTAMPS_SIZE,
+				usage: BufferUsages::QUERY_RESOLVE | BufferUsages::COPY_SRC,
+				mapped_at_creation: false,
+			})
+		});
+		encoder.resolve_query_set(query_set, 0..2, resolve_buffer, 0);
+		let buffer = state.free_buffers.pop().unwrap_or_else(|| {
+			device.create_buffer(&BufferDescriptor {
+				label: None,
+				size: TIMESTAMPS_SIZE,
+				usage: BufferUsages::MAP_READ | BufferUsages::COPY_DST,
+				mapped_at_creation: false,
+			})
+		});
+		encoder.copy_buffer_to_buffer(resolve_buffer, 0, &buffer, 0, TIMESTAMPS_SIZE);
+		state.frames.push(TimedFrame { task, iterations, buffer, mapped: None });
+	}
+
+	/// Drop the query set and the buffers the timestamps are copied into, once the app is exiting.
+	pub fn release(&self) { *self.0.lock().unwrap() = BudgetTimerState::default(); }
+
+	/// How long one iteration of a task takes on the GPU, averaged over the latest frames, or `None` if it hasn't been measured yet.
+	pub fn per_iteration(&self, task: (u32, usize)) -> Option<Duration> {
+		self.0.lock().unwrap().per_iteration.get(&task).copied()
+	}
+
+	/// Forget the time per iteration of the compute sequences that are no longer running.
+	pub fn retain(&self, running: impl Fn(u32) -> bool) {
+		self.0.lock().unwrap().per_iteration.retain(|(generation, _), _| running(*generation));
+	}
+}
+
+/// Map the timestamps of the iterations recorded this frame, as the frame's commands have just been submitted, and read the ones that have been mapped since, to update the time per iteration, and report the GPU time of the frames they were recorded in, if diagnostics are on.
+pub(crate) fn read_budget_timings(
+	timer: Res<ComputeBudgetTimer>, render_queue: Res<RenderQueue>, sequences: Option<Res<ComputeSequences>>,
+	diagnostics: Option<Res<ComputeDiagnosticsEnabled>>,
+) {
+	let mut state = timer.0.lock().unwrap();
+	if state.frames.is_empty() {
+		return;
+	}
+	let period = render_queue.get_timestamp_period() as f64;
+	let state = &mut *state;
+	state.frames.retain_mut(|frame| {
+		let Some(mapped) = &frame.mapped else {
+			let mapped = Arc::new(OnceLock::new());
+			let result = mapped.clone();
+			frame.buffer.slice(..).map_async(MapMode::Read, move |map_result| {
+				let _ = result.set(map_result.is_ok());
+			});
+			frame.mapped = Some(mapped);
+			return true;
+		};
+		let Some(&success) = mapped.get() else {
+			return true;
+		};
+		if success {
+			let timestamps: [u64; 2] = bytemuck::pod_read_unaligned(&frame.buffer.slice(..).get_mapped_range());
+			let nanoseconds = timestamps[1].wrapping_sub(timestamps[0]) as f64 * period;
+			// The GPU time is of the whole frame, not of any one sequence.
+			if let (Some(sender), Some(_)) = (sequences.as_ref().and_then(|sequences| sequences.any_sender()), &diagnostics) {
+				let value = nanoseconds / 1e6;
+				send_message(sender, ComputeMessage::Diagnostic { path: GPU_TIME, value });
+			}
+			let measured = Duration::from_secs_f64(nanoseconds / frame.iterations as f64 / 1e9);
+			// Each measurement is averaged in with the ones before it, to smooth out the noise. A sequence only keeps the
+			// time of the task it's running.
+			let (generation, _) = frame.task;
+			let previous = state.per_iteration.get(&frame.task).copied();
+			state.per_iteration.retain(|(timed, _), _| *timed != generation);
+			let average = previous.map_or(measured, |previous| (previous * 3 + measured) / 4);
+			state.per_iteration.insert(frame.task, average);
+			frame.buffer.unmap();
+			state.free_buffers.push(frame.buffer.clone());
+		}
+		false
+	});
+}
@@ -0,0 +1,35 @@
+use bevy::{ecs::query::QueryItem, prelude::*, render::extract_component::ExtractComponent};
+
+use crate::shader_buffer_set::{ShaderBufferHandle, ShaderBufferSet};
+
+/// This component gives an entity a buffer of its own, for simulations made of many independent agents, each with a small state buffer. The buffer must have been added to the [ShaderBufferSet] with a [PerEntity](crate::Binding::PerEntity) binding, and every [RunShaderPerEntity](crate::ComputeAction::RunShaderPerEntity) action dispatches its shader once for every entity with one of these, with that entity's buffer bound.
+///
+/// The entity owns the buffer. When the entity is despawned, or this component is removed, the buffer is deleted from the [ShaderBufferSet].
+#[derive(Component, Clone, Copy)]
+pub struct ComputeBufferComponent(pub ShaderBufferHandle);
+
+/// The render world's copy of a [ComputeBufferComponent]. It's a separate type so that removing it from the render world doesn't delete the buffer.
+#[derive(Component, Clone, Copy)]
+pub struct ExtractedComputeBuffer(pub ShaderBufferHandle);
+
+impl ExtractComponent for ComputeBufferComponent {
+	type QueryData = &'static ComputeBufferComponent;
+	type QueryFilter = ();
+	type Out = ExtractedComputeBuffer;
+
+	fn extract_component(ComputeBufferComponent(handle): QueryItem<'_, Self::QueryData>) -> Option<Self::Out> {
+		Some(ExtractedComputeBuffer(*handle))
+	}
+}
+
+// This is an observer rather than a component hook, as extracting the component
+// to the render world already takes its hooks.
+pub(crate) fn release_compute_buffer(
+	trigger: Trigger<OnRemove, ComputeBufferComponent>, components: Query<&ComputeBufferComponent>,
+	mut buffers: ResMut<ShaderBufferSet>, mut images: ResMut<Assets<Image>>,
+) {
+	if let Ok(ComputeBufferComponent(handle)) = components.get(trigger.entity()) {
+		// The buffer may already be gone, such as when every buffer was deleted as the app exited.
+		let _ = buffers.delete_buffer(*handle, &mut images);
+	}
+}
@@ -0,0 +1,186 @@
+use bevy::{
+	prelude::*,
+	render::{
+		render_resource::{DownlevelFlags, TextureFormat, TextureUsages, WgpuFeatures},
+		renderer::{RenderAdapter, RenderDevice},
+		RenderApp,
+	},
+};
+use wgpu::TextureFormatFeatureFlags;
+
+use crate::shader_buffer_set::ShaderBufferSet;
+
+/// The features and limits of the GPU that matter to compute shaders, so apps can pick a fallback, such as copying between two textures instead of using one [ReadWrite](bevy::render::render_resource::StorageTextureAccess::ReadWrite) texture, without memorizing wgpu feature names. This is a resource in both the main and render worlds, filled in when the [BevyComputePlugin](crate::BevyComputePlugin) finishes, and the crate's own checks, such as whether compute shaders can run at all, read from it.
+#[derive(Resource, Clone, Debug, PartialEq, Eq)]
+pub struct ComputeCapabilities {
+	/// Whether compute shaders can run at all. This is false on WebGL2 and some other downlevel backends, in which case the plugin goes into the [Unsupported](crate::ComputeState::Unsupported) state.
+	pub compute_shaders: bool,
+
+	/// Whether storage textures of formats beyond `r32float`, `r32uint` and `r32sint`, which always can, can be bound with [ReadWrite](bevy::render::render_resource::StorageTextureAccess::ReadWrite) access, checked with [Rgba32Float](TextureFormat::Rgba32Float). Use [supports_read_write_storage](ComputeCapabilities::supports_read_write_storage) to check a specific format.
+	pub read_write_storage_textures: bool,
+
+	/// Every texture format that can be bound as a storage texture, with any access.
+	pub storage_formats: Vec<TextureFormat>,
+
+	/// Every storage texture format that can be bound with [ReadWrite](bevy::render::render_resource::StorageTextureAccess::ReadWrite) access.
+	pub read_write_storage_formats: Vec<TextureFormat>,
+
+	/// Whether a texture can be viewed in the sRGB version of its format, as needed by a storage texture added with an sRGB format. WebGL2 and some older native backends can't.
+	pub srgb_views: bool,
+
+	/// Whether 32-bit float textures can be sampled with filtering.
+	pub float32_filterable: bool,
+
+	/// Whether pipelines can use push constants.
+	pub push_constants: bool,
+
+	/// The most bytes of push constants a pipeline can use, which is 0 without [push_constants](ComputeCapabilities::push_constants).
+	pub max_push_constant_size: u32,
+
+	/// Whether timestamp queries can be written, to time GPU work.
+	pub timestamps: bool,
+
+	/// Whether textures can be bound as arrays, as needed by [add_texture_array_binding](crate::ShaderBufferSet::add_texture_array_binding).
+	pub texture_binding_arrays: bool,
+
+	/// Whether shaders can use 64-bit integers, `i64` and `u64`.
+	pub int64: bool,
+
+	/// Whether 64-bit integers can be used with every atomic operation, as the `atomicAdd` on the counters of [add_counters_u64](crate::ShaderBufferSet::add_counters_u64) needs.
+	pub int64_atomics: bool,
+
+	/// Whether shaders can use 16-bit floats, `f16`, such as in storage buffers of half precision data. Storage textures of 16-bit float formats, such as [Rgba16Float](TextureFormat::Rgba16Float), don't need this, as shaders read and write them as `f32`. Whether those can be bound is in [storage_formats](ComputeCapabilities::storage_formats).
+	pub shader_f16: bool,
+
+	/// The largest workgroup size allowed in each dimension.
+	pub max_workgroup_size: UVec3,
+
+	/// The most invocations allowed in one workgroup, across all three dimensions.
+	pub max_invocations_per_workgroup: u32,
+
+	/// The most workgroups one dispatch can have in each dimension.
+	pub max_workgroups_per_dimension: u32,
+
+	/// The largest storage buffer that can be bound, in bytes.
+	pub max_storage_buffer_binding_size: u32,
+}
+
+/// The formats checked for storage support, which are all the uncompressed color formats, other than the sRGB ones, which never support it.
+const COLOR_FORMATS: [TextureFormat; 40] = [
+	TextureFormat::R8Unorm,
+	TextureFormat::R8Snorm,
+	TextureFormat::R8Uint,
+	TextureFormat::R8Sint,
+	TextureFormat::R16Uint,
+	TextureFormat::R16Sint,
+	TextureFormat::R16Unorm,
+	TextureFormat::R16Snorm,
+	TextureFormat::R16Float,
+	TextureFormat::Rg8Unorm,
+	TextureFormat::Rg8Snorm,
+	TextureFormat::Rg8Uint,
+	TextureFormat::Rg8Sint,
+	TextureFormat::R32Uint,
+	TextureFormat::R32Sint,
+	TextureFormat::R32Float,
+	TextureFormat::Rg16Uint,
+	TextureFormat::Rg16Sint,
+	TextureFormat::Rg16Unorm,
+	TextureFormat::Rg16Snorm,
+	TextureFormat::Rg16Float,
+	TextureFormat::Rgba8Unorm,
+	TextureFormat::Rgba8Snorm,
+	TextureFormat::Rgba8Uint,
+	TextureFormat::Rgba8Sint,
+	TextureFormat::Bgra8Unorm,
+	TextureFormat::Rgb10a2Uint,
+	TextureFormat::Rgb10a2Unorm,
+	TextureFormat::Rg11b10Ufloat,
+	TextureFormat::Rg32Uint,
+	TextureFormat::Rg32Sint,
+	TextureFormat::Rg32Float,
+	TextureFormat::Rgba16Uint,
+	TextureFormat::Rgba16Sint,
+	TextureFormat::Rgba16Unorm,
+	TextureFormat::Rgba16Snorm,
+	TextureFormat::Rgba16Float,
+	TextureFormat::Rgba32Uint,
+	TextureFormat::Rgba32Sint,
+	TextureFormat::Rgba32Float,
+];
+
+impl ComputeCapabilities {
+	/// Read the capabilities of a device, and the adapter it was made from.
+	pub fn new(device: &RenderDevice, adapter: &RenderAdapter) -> Self {
+		let features = device.features();
+		let limits = device.limits();
+		let downlevel_flags = adapter.get_downlevel_capabilities().flags;
+		let push_constants = features.contains(WgpuFeatures::PUSH_CONSTANTS);
+		// Without this feature, only the format features every adapter guarantees can be used,
+		// whatever the adapter itself supports.
+		let format_features = |format: TextureFormat| {
+			if features.contains(WgpuFeatures::TEXTURE_ADAPTER_SPECIFIC_FORMAT_FEATURES) {
+				adapter.get_texture_format_features(format)
+			} else {
+				format.guaranteed_format_features(features)
+			}
+		};
+		let storage_formats: Vec<_> = COLOR_FORMATS
+			.into_iter()
+			.filter(|format| format_features(*format).allowed_usages.contains(TextureUsages::STORAGE_BINDING))
+			.collect();
+		let read_write_storage_formats: Vec<_> = storage_formats
+			.iter()
+			.copied()
+			.filter(|format| format_features(*format).flags.contains(TextureFormatFeatureFlags::STORAGE_READ_WRITE))
+			.collect();
+		Self {
+			compute_shaders: downlevel_flags.contains(DownlevelFlags::COMPUTE_SHADERS),
+			srgb_views: downlevel_flags.contains(DownlevelFlags::VIEW_FORMATS),
+			read_write_storage_textures: read_write_storage_formats.contains(&TextureFormat::Rgba32Float),
+			storage_formats,
+			read_write_storage_formats,
+			float32_filterable: features.contains(WgpuFeatures::FLOAT32_FILTERABLE),
+			push_constants,
+			max_push_constant_size: if push_constants { limits.max_push_constant_size } else { 0 },
+			timestamps: features.contains(WgpuFeatures::TIMESTAMP_QUERY),
+			texture_binding_arrays: features.contains(WgpuFeatures::TEXTURE_BINDING_ARRAY),
+			int64: features.contains(WgpuFeatures::SHADER_INT64),
+			int64_atomics: features.contains(WgpuFeatures::SHADER_INT64_ATOMIC_ALL_OPS),
+			shader_f16: features.contains(WgpuFeatures::SHADER_F16),
+			max_workgroup_size: UVec3::new(
+				limits.max_compute_workgroup_size_x,
+				limits.max_compute_workgroup_size_y,
+				limits.max_compute_workgroup_size_z,
+			),
+			max_invocations_per_workgroup: limits.max_compute_invocations_per_workgroup,
+			max_workgroups_per_dimension: limits.max_compute_workgroups_per_dimension,
+			max_storage_buffer_binding_size: limits.max_storage_buffer_binding_size,
+		}
+	}
+
+	/// Whether textures of a format can be bound as storage textures. sRGB formats never can.
+	pub fn supports_storage(&self, format: TextureFormat) -> bool { self.storage_formats.contains(&format) }
+
+	/// Whether storage textures of a format can be bound with [ReadWrite](bevy::render::render_resource::StorageTextureAccess::ReadWrite) access.
+	pub fn supports_read_write_storage(&self, format: TextureFormat) -> bool {
+		self.read_write_storage_formats.contains(&format)
+	}
+}
+
+/// Read the [ComputeCapabilities] of the render device, and insert them into both worlds, and into the [ShaderBufferSet] to check new buffers against.
+pub(crate) fn insert_compute_capabilities(app: &mut App) {
+	let world = app.world();
+	let (Some(device), Some(adapter)) = (world.get_resource::<RenderDevice>(), world.get_resource::<RenderAdapter>())
+	else {
+		return;
+	};
+	let capabilities = ComputeCapabilities::new(device, adapter);
+	if let Some(mut buffers) = app.world_mut().get_resource_mut::<ShaderBufferSet>() {
+		buffers.capabilities = Some(capabilities.clone());
+	}
+	if let Some(render_app) = app.get_sub_app_mut(RenderApp) {
+		render_app.insert_resource(capabilities.clone());
+	}
+	app.insert_resource(capabilities);
+}
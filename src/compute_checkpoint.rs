@@ -0,0 +1,667 @@
+use std::{
+	fs,
+	path::{Path, PathBuf},
+	sync::{Arc, Mutex, OnceLock},
+};
+
+use bevy::{
+	prelude::*,
+	render::{
+		render_asset::RenderAssets,
+		render_resource::{
+			Buffer, BufferDescriptor, BufferUsages, CommandEncoderDescriptor, ImageCopyBuffer, ImageDataLayout, MapMode,
+		},
+		renderer::{RenderDevice, RenderQueue},
+		texture::GpuImage,
+		Render, RenderApp, RenderSet,
+	},
+};
+use wgpu::COPY_BYTES_PER_ROW_ALIGNMENT;
+
+use crate::shader_buffer_set::{RawStorage, ShaderBufferHandle, ShaderBufferSet};
+
+/// The bytes every checkpoint file starts with.
+const MAGIC: &[u8; 8] = b"BEVYCKPT";
+
+/// The version of the checkpoint format written by [SaveBuffersEvent]. It's the first thing in the file after the magic bytes, so a file written by a later version of the format, which this version can't read, fails to load with [CheckpointError::UnsupportedVersion], rather than being misread.
+pub const CHECKPOINT_VERSION: u32 = 2;
+
+/// Send this to save the contents of some buffers to a checkpoint file, such as the state of a long-running simulation, to be loaded again later with a [LoadBuffersEvent]. Every buffer must have a [name](ShaderBufferSet::name_buffer), which is how it's matched up with a buffer when the file is loaded, and storage and uniform buffers must have been created with [BufferUsages::COPY_SRC]. Both halves of a double buffer are saved, along with which is the front buffer. Rings can't be saved. The buffers are read back from the GPU asynchronously, as they are at the start of a frame's compute steps, and the file is written in the main world once they've all arrived, after which a [BuffersSavedEvent] is sent, or a [CheckpointFailedEvent] if anything went wrong. Every buffer is checked before anything is read back, so if any doesn't exist, has no name, or can't be copied from, nothing is saved, and the [CheckpointFailedEvent] lists every one that couldn't be. Panics if a buffer is a texture array. Only available with the `checkpoint` feature.
+#[derive(Event, Clone, Debug)]
+pub struct SaveBuffersEvent {
+	/// The buffers to save.
+	pub handles: Vec<ShaderBufferHandle>,
+
+	/// The file to write. It's replaced if it already exists.
+	pub path: PathBuf,
+}
+
+/// Send this to load the contents of buffers from a checkpoint file written by a [SaveBuffersEvent]. Each buffer in the file is matched up with the buffer of the same [name](ShaderBufferSet::name_buffer) in the [ShaderBufferSet], which must be the same kind of buffer, with the same size, or extent and format, and be double buffered if and only if the saved one was. The file is read and checked in full first, so if any buffer doesn't match, nothing is loaded, and a [CheckpointFailedEvent] lists every buffer that didn't. Otherwise, the data is written to every half of every buffer, the front buffers of double buffers are set to the halves that were the front when they were saved, and a [BuffersLoadedEvent] is sent. The writes happen before the next compute steps run, but swaps made by a running compute task can race with setting the front buffer, so it's best to load while nothing is swapping, such as before starting a compute sequence, or while its tasks are paused. Storage and uniform buffers must have been created with [BufferUsages::COPY_DST], which is checked along with the rest. Only available with the `checkpoint` feature.
+#[derive(Event, Clone, Debug)]
+pub struct LoadBuffersEvent {
+	/// The file to read.
+	pub path: PathBuf,
+}
+
+/// What kind of buffer a checkpoint entry holds, and its size or extent and format, which have to match the buffer it's loaded into.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CheckpointLayout {
+	/// A storage buffer of the given size in bytes.
+	Storage {
+		/// The size of the buffer in bytes.
+		size: u64,
+	},
+
+	/// A uniform buffer of the given size in bytes.
+	Uniform {
+		/// The size of the buffer in bytes.
+		size: u64,
+	},
+
+	/// A storage texture of the given extent and format.
+	Texture {
+		/// The name of the texture's format, as it's written in the file, such as `"Rgba8Unorm"`. A texture added with an sRGB format is stored, and saved, in the linear one.
+		format: String,
+
+		/// The width of the texture in texels.
+		width: u32,
+
+		/// The height of the texture in texels.
+		height: u32,
+
+		/// The depth of a 3D texture in texels, or the number of layers of a 2D array texture, which is 1 for any other texture. Files from version 1 of the format, which didn't record it, only have textures with 1.
+		depth_or_array_layers: u32,
+	},
+}
+
+/// A buffer saved to, or loaded from, a checkpoint file.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CheckpointEntry {
+	/// The name of the buffer.
+	pub name: String,
+
+	/// The kind of buffer, and its size or extent and format.
+	pub layout: CheckpointLayout,
+
+	/// Whether both halves of a double buffer were saved.
+	pub double_buffered: bool,
+
+	/// Whether the second half of a double buffer was the front buffer. Always false for other buffers.
+	pub front_is_second: bool,
+
+	/// The number of bytes of data saved, across both halves of a double buffer.
+	pub bytes: u64,
+}
+
+/// This event is thrown when the buffers asked for by a [SaveBuffersEvent] have been written to a checkpoint file.
+#[derive(Event, Clone, Debug)]
+pub struct BuffersSavedEvent {
+	/// The file that was written.
+	pub path: PathBuf,
+
+	/// The buffers that were saved, in the order they were asked for.
+	pub entries: Vec<CheckpointEntry>,
+}
+
+/// This event is thrown when the buffers in a checkpoint file have been loaded, as asked for by a [LoadBuffersEvent].
+#[derive(Event, Clone, Debug)]
+pub struct BuffersLoadedEvent {
+	/// The file that was read.
+	pub path: PathBuf,
+
+	/// The buffers that were loaded, in the order they were in the file.
+	pub entries: Vec<CheckpointEntry>,
+}
+
+/// This event is thrown when a [SaveBuffersEvent] or a [LoadBuffersEvent] fails. A failed save leaves the file alone, and a failed load leaves every buffer alone.
+#[derive(Event, Clone, Debug)]
+pub struct CheckpointFailedEvent {
+	/// The file that was being written or read.
+	pub path: PathBuf,
+
+	/// What went wrong.
+	pub error: CheckpointError,
+}
+
+/// Why a checkpoint couldn't be saved or loaded.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CheckpointError {
+	/// The file couldn't be written or read, with the message of the underlying IO error.
+	Io(String),
+
+	/// The file isn't a checkpoint, or is truncated or corrupted, with a description of what's wrong.
+	Malformed(String),
+
+	/// The file was written by a later version of the format than [CHECKPOINT_VERSION], which this version can't read.
+	UnsupportedVersion(u32),
+
+	/// Some of the buffers couldn't be saved, or didn't match the buffers they were to be loaded into. Every one that failed is listed.
+	Entries(Vec<CheckpointEntryError>),
+}
+
+/// A buffer that couldn't be saved or loaded, by its name.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CheckpointEntryError {
+	/// The name of the buffer, or its handle, as it's displayed, if it's being saved and has no name, or doesn't exist.
+	pub name: String,
+
+	/// What was wrong with it.
+	pub problem: CheckpointEntryProblem,
+}
+
+/// What was wrong with a buffer that couldn't be saved or loaded.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CheckpointEntryProblem {
+	/// The buffer doesn't exist, or was deleted before it could be read back, while saving.
+	Deleted,
+
+	/// The buffer has no [name](ShaderBufferSet::name_buffer), which is how it's matched up with a buffer when it's loaded, while saving.
+	Unnamed,
+
+	/// The buffer wasn't created with [BufferUsages::COPY_SRC], so it can't be read back, while saving.
+	NotCopySrc,
+
+	/// The buffer couldn't be mapped to read it back, while saving.
+	ReadbackFailed,
+
+	/// No buffer has the saved buffer's name, while loading.
+	NoSuchBuffer,
+
+	/// The buffer with the saved buffer's name is a different kind of buffer, or has a different size, or extent or format, while loading.
+	LayoutMismatch {
+		/// The layout of the saved buffer.
+		saved: CheckpointLayout,
+
+		/// The layout of the buffer it was to be loaded into.
+		current: CheckpointLayout,
+	},
+
+	/// The saved buffer was double buffered and the buffer with its name isn't, or the other way around, while loading.
+	DoubleBufferingMismatch {
+		/// Whether the saved buffer was double buffered.
+		saved: bool,
+	},
+
+	/// The buffer with the saved buffer's name wasn't created with [BufferUsages::COPY_DST], so it can't be written, while loading.
+	NotCopyDst,
+
+	/// The buffer is a [ring](crate::Binding::Ring), which checkpoints can't hold, while saving or loading.
+	Ring,
+}
+
+/// A buffer's entry in a checkpoint file, with the data of each of its halves.
+struct SavedEntry {
+	entry: CheckpointEntry,
+	data: Vec<Vec<u8>>,
+}
+
+/// One half of a buffer being read back, copied into a buffer that's mapped once the copy has been submitted. Texture rows are padded out to the alignment copies need, as `(padded, unpadded, count)`, and the padding is stripped once they're read.
+struct HalfReadback {
+	buffer: Buffer,
+	rows: Option<(u32, u32, u32)>,
+	mapped: Arc<OnceLock<bool>>,
+}
+
+/// A save asked for by a [SaveBuffersEvent], with the buffers it saves by name, and once the copies have been made, their readbacks.
+struct PendingSave {
+	path: PathBuf,
+	buffers: Vec<(String, ShaderBufferHandle)>,
+	readbacks: Option<Vec<(CheckpointEntry, Vec<HalfReadback>)>>,
+}
+
+#[derive(Default)]
+struct CheckpointState {
+	saves: Vec<PendingSave>,
+	finished: Vec<(PathBuf, Result<Vec<SavedEntry>, CheckpointError>)>,
+}
+
+/// The saves in progress, shared between the main world, which asks for them and writes the files, and the render world, which reads the buffers back.
+#[derive(Resource, Clone, Default)]
+struct ComputeCheckpoints(Arc<Mutex<CheckpointState>>);
+
+/// Adds the checkpoint events and the systems behind them.
+pub(crate) struct ComputeCheckpointPlugin;
+
+impl Plugin for ComputeCheckpointPlugin {
+	fn build(&self, app: &mut App) {
+		let checkpoints = ComputeCheckpoints::default();
+		app
+			.insert_resource(checkpoints.clone())
+			.add_event::<SaveBuffersEvent>()
+			.add_event::<LoadBuffersEvent>()
+			.add_event::<BuffersSavedEvent>()
+			.add_event::<BuffersLoadedEvent>()
+			.add_event::<CheckpointFailedEvent>()
+			.add_systems(Update, (save_buffers, load_buffers));
+		app
+			.sub_app_mut(RenderApp)
+			.insert_resource(checkpoints)
+			.add_systems(Render, read_back_checkpoints.in_set(RenderSet::PrepareResources));
+	}
+}
+
+/// The layout of one half of a buffer.
+fn layout_of(storage: &RawStorage) -> CheckpointLayout {
+	match storage {
+		RawStorage::Buffer { buffer, uniform: false } => CheckpointLayout::Storage { size: buffer.size() },
+		RawStorage::Buffer { buffer, uniform: true } => CheckpointLayout::Uniform { size: buffer.size() },
+		RawStorage::Texture { format, size, .. } => CheckpointLayout::Texture {
+			format: format!("{:?}", format),
+			width: size.width,
+			height: size.height,
+			depth_or_array_layers: size.depth_or_array_layers,
+		},
+	}
+}
+
+/// The number of bytes of data in one half of a buffer, with texture rows tightly packed, across every depth slice or array layer.
+fn data_size(storage: &RawStorage) -> u64 {
+	match storage {
+		RawStorage::Buffer { buffer, .. } => buffer.size(),
+		RawStorage::Texture { format, size, .. } => {
+			let rows = size.height as u64 * size.depth_or_array_layers as u64;
+			format.block_copy_size(None).unwrap() as u64 * size.width as u64 * rows
+		}
+	}
+}
+
+/// Queue up every [SaveBuffersEvent], and write the files of the saves whose buffers have all been read back.
+fn save_buffers(
+	mut requests: EventReader<SaveBuffersEvent>, buffers: Res<ShaderBufferSet>, checkpoints: Res<ComputeCheckpoints>,
+	mut saved: EventWriter<BuffersSavedEvent>, mut failed: EventWriter<CheckpointFailedEvent>,
+) {
+	let mut state = checkpoints.0.lock().unwrap();
+	for request in requests.read() {
+		let mut named = Vec::new();
+		let mut errors = Vec::new();
+		for handle in request.handles.iter() {
+			let name = buffers.buffer_name(*handle);
+			let error = |problem| {
+				let name = name.map_or_else(|| handle.to_string(), str::to_owned);
+				CheckpointEntryError { name, problem }
+			};
+			let Some((halves, _)) = buffers.raw_storage(*handle) else {
+				errors.push(error(CheckpointEntryProblem::Deleted));
+				continue;
+			};
+			let Some(name) = name else {
+				errors.push(error(CheckpointEntryProblem::Unnamed));
+				continue;
+			};
+			if buffers.ring_slot(*handle).is_some() {
+				errors.push(error(CheckpointEntryProblem::Ring));
+				continue;
+			}
+			let copyable = |half: &RawStorage| match half {
+				RawStorage::Buffer { buffer, .. } => buffer.usage().contains(BufferUsages::COPY_SRC),
+				RawStorage::Texture { .. } => true,
+			};
+			if !halves.iter().all(copyable) {
+				errors.push(error(CheckpointEntryProblem::NotCopySrc));
+				continue;
+			}
+			named.push((name.to_owned(), *handle));
+		}
+		if !errors.is_empty() {
+			let error = CheckpointError::Entries(errors);
+			error!("Failed to save a checkpoint to {}: {:?}", request.path.display(), error);
+			failed.send(CheckpointFailedEvent { path: request.path.clone(), error });
+			continue;
+		}
+		state.saves.push(PendingSave { path: request.path.clone(), buffers: named, readbacks: None });
+	}
+	for (path, result) in state.finished.drain(..) {
+		let result = result.and_then(|entries| {
+			fs::write(&path, encode(&entries)).map_err(|error| CheckpointError::Io(error.to_string()))?;
+			Ok(entries.into_iter().map(|entry| entry.entry).collect())
+		});
+		match result {
+			Ok(entries) => {
+				saved.send(BuffersSavedEvent { path, entries });
+			}
+			Err(error) => {
+				error!("Failed to save a checkpoint to {}: {:?}", path.display(), error);
+				failed.send(CheckpointFailedEvent { path, error });
+			}
+		}
+	}
+}
+
+/// Copy the buffers of every new save into buffers that can be mapped, before any compute steps run this frame, and hand the data of every save whose buffers have all been mapped since back to the main world.
+fn read_back_checkpoints(
+	checkpoints: Res<ComputeCheckpoints>, buffers: Option<Res<ShaderBufferSet>>, gpu_images: Res<RenderAssets<GpuImage>>,
+	device: Res<RenderDevice>, render_queue: Res<RenderQueue>,
+) {
+	let mut state = checkpoints.0.lock().unwrap();
+	if state.saves.is_empty() {
+		return;
+	}
+	let state = &mut *state;
+	state.saves.retain_mut(|save| {
+		let Some(readbacks) = &save.readbacks else {
+			let Some(buffers) = &buffers else {
+				return true;
+			};
+			return match start_readbacks(&save.buffers, buffers, &gpu_images, &device, &render_queue) {
+				Some(Ok(readbacks)) => {
+					save.readbacks = Some(readbacks);
+					true
+				}
+				Some(Err(errors)) => {
+					state.finished.push((save.path.clone(), Err(CheckpointError::Entries(errors))));
+					false
+				}
+				None => true,
+			};
+		};
+		if readbacks.iter().flat_map(|(_, halves)| halves).any(|half| half.mapped.get().is_none()) {
+			return true;
+		}
+		let mut entries = Vec::new();
+		let mut errors = Vec::new();
+		for (entry, halves) in readbacks {
+			if halves.iter().any(|half| half.mapped.get() == Some(&false)) {
+				errors.push(CheckpointEntryError { name: entry.name.clone(), problem: CheckpointEntryProblem::ReadbackFailed });
+				continue;
+			}
+			let data = halves.iter().map(read_half).collect();
+			entries.push(SavedEntry { entry: entry.clone(), data });
+		}
+		let result = if errors.is_empty() { Ok(entries) } else { Err(CheckpointError::Entries(errors)) };
+		state.finished.push((save.path.clone(), result));
+		false
+	});
+}
+
+/// Copy every half of the buffers of a save into buffers that can be mapped, submit the copies, and start mapping them. Returns `None` if a texture hasn't been uploaded to the GPU yet, so the save waits for a later frame, or the names of the buffers that have been deleted since the save was asked for.
+#[allow(clippy::type_complexity)]
+fn start_readbacks(
+	named: &[(String, ShaderBufferHandle)], buffers: &ShaderBufferSet, gpu_images: &RenderAssets<GpuImage>,
+	device: &RenderDevice, render_queue: &RenderQueue,
+) -> Option<Result<Vec<(CheckpointEntry, Vec<HalfReadback>)>, Vec<CheckpointEntryError>>> {
+	let mut errors = Vec::new();
+	let mut sources = Vec::new();
+	for (name, handle) in named {
+		let Some((halves, front_is_second)) = buffers.raw_storage(*handle) else {
+			errors.push(CheckpointEntryError { name: name.clone(), problem: CheckpointEntryProblem::Deleted });
+			continue;
+		};
+		for half in halves.iter() {
+			if let RawStorage::Texture { image, .. } = half {
+				gpu_images.get(image)?;
+			}
+		}
+		let entry = CheckpointEntry {
+			name: name.clone(),
+			layout: layout_of(&halves[0]),
+			double_buffered: halves.len() == 2,
+			front_is_second,
+			bytes: halves.iter().map(data_size).sum(),
+		};
+		sources.push((entry, halves));
+	}
+	if !errors.is_empty() {
+		return Some(Err(errors));
+	}
+	let mut encoder = device.create_command_encoder(&CommandEncoderDescriptor { label: Some("checkpoint_readback") });
+	let readbacks = sources
+		.into_iter()
+		.map(|(entry, halves)| {
+			let halves = halves
+				.iter()
+				.map(|half| {
+					let (size, rows) = match half {
+						RawStorage::Buffer { buffer, .. } => (buffer.size(), None),
+						RawStorage::Texture { format, size, .. } => {
+							// The rows of every depth slice or array layer follow on from those of the one before.
+							let unpadded = format.block_copy_size(None).unwrap() * size.width;
+							let padded = unpadded.next_multiple_of(COPY_BYTES_PER_ROW_ALIGNMENT);
+							let count = size.height * size.depth_or_array_layers;
+							(padded as u64 * count as u64, Some((padded, unpadded, count)))
+						}
+					};
+					let buffer = device.create_buffer(&BufferDescriptor {
+						label: Some("checkpoint_readback"),
+						size,
+						usage: BufferUsages::COPY_DST | BufferUsages::MAP_READ,
+						mapped_at_creation: false,
+					});
+					match half {
+						RawStorage::Texture { image, size, .. } => {
+							let (padded, _, _) = rows.unwrap();
+							encoder.copy_texture_to_buffer(
+								gpu_images.get(image).unwrap().texture.as_image_copy(),
+								ImageCopyBuffer {
+									buffer: &buffer,
+									layout: ImageDataLayout {
+										offset: 0,
+										bytes_per_row: Some(padded),
+										rows_per_image: Some(size.height),
+									},
+								},
+								*size,
+							);
+						}
+						RawStorage::Buffer { buffer: src, .. } => {
+							encoder.copy_buffer_to_buffer(src, 0, &buffer, 0, src.size());
+						}
+					}
+					HalfReadback { buffer, rows, mapped: Arc::new(OnceLock::new()) }
+				})
+				.collect::<Vec<_>>();
+			(entry, halves)
+		})
+		.collect::<Vec<_>>();
+	render_queue.submit([encoder.finish()]);
+	for half in readbacks.iter().flat_map(|(_, halves)| halves) {
+		let mapped = half.mapped.clone();
+		half.buffer.slice(..).map_async(MapMode::Read, move |result| {
+			let _ = mapped.set(result.is_ok());
+		});
+	}
+	Some(Ok(readbacks))
+}
+
+/// The data of one half of a buffer that's been mapped, with any padding of texture rows stripped.
+fn read_half(half: &HalfReadback) -> Vec<u8> {
+	let mapped = half.buffer.slice(..).get_mapped_range();
+	let data = match half.rows {
+		Some((padded, unpadded, count)) => {
+			mapped.chunks(padded as usize).take(count as usize).flat_map(|row| &row[..unpadded as usize]).copied().collect()
+		}
+		None => mapped.to_vec(),
+	};
+	drop(mapped);
+	half.buffer.destroy();
+	data
+}
+
+/// Load every checkpoint file asked for with a [LoadBuffersEvent] into the buffers with the same names.
+fn load_buffers(
+	mut requests: EventReader<LoadBuffersEvent>, mut buffers: ResMut<ShaderBufferSet>, render_queue: Res<RenderQueue>,
+	mut loaded: EventWriter<BuffersLoadedEvent>, mut failed: EventWriter<CheckpointFailedEvent>,
+) {
+	for request in requests.read() {
+		match load_checkpoint(&request.path, &mut buffers, &render_queue) {
+			Ok(entries) => {
+				loaded.send(BuffersLoadedEvent { path: request.path.clone(), entries });
+			}
+			Err(error) => {
+				error!("Failed to load a checkpoint from {}: {:?}", request.path.display(), error);
+				failed.send(CheckpointFailedEvent { path: request.path.clone(), error });
+			}
+		}
+	}
+}
+
+fn load_checkpoint(
+	path: &Path, buffers: &mut ShaderBufferSet, render_queue: &RenderQueue,
+) -> Result<Vec<CheckpointEntry>, CheckpointError> {
+	let bytes = fs::read(path).map_err(|error| CheckpointError::Io(error.to_string()))?;
+	let saved = decode(&bytes)?;
+	let mut errors = Vec::new();
+	let mut targets = Vec::new();
+	for SavedEntry { entry, data } in saved.iter() {
+		let error = |problem| CheckpointEntryError { name: entry.name.clone(), problem };
+		let Some(handle) = buffers.handle_by_name(&entry.name) else {
+			errors.push(error(CheckpointEntryProblem::NoSuchBuffer));
+			continue;
+		};
+		let (halves, _) = buffers.raw_storage(handle).unwrap();
+		if buffers.ring_slot(handle).is_some() {
+			errors.push(error(CheckpointEntryProblem::Ring));
+			continue;
+		}
+		let current = layout_of(&halves[0]);
+		if current != entry.layout {
+			errors.push(error(CheckpointEntryProblem::LayoutMismatch { saved: entry.layout.clone(), current }));
+			continue;
+		}
+		if entry.double_buffered != (halves.len() == 2) {
+			errors.push(error(CheckpointEntryProblem::DoubleBufferingMismatch { saved: entry.double_buffered }));
+			continue;
+		}
+		let writable = |half: &RawStorage| match half {
+			RawStorage::Buffer { buffer, .. } => buffer.usage().contains(BufferUsages::COPY_DST),
+			RawStorage::Texture { .. } => true,
+		};
+		if !halves.iter().all(writable) {
+			errors.push(error(CheckpointEntryProblem::NotCopyDst));
+			continue;
+		}
+		if let Some(half) = data.iter().find(|half| half.len() as u64 != data_size(&halves[0])) {
+			return Err(CheckpointError::Malformed(format!(
+				"\"{}\" has {} bytes of data, but its layout needs {}",
+				entry.name,
+				half.len(),
+				data_size(&halves[0])
+			)));
+		}
+		targets.push((handle, halves));
+	}
+	if !errors.is_empty() {
+		return Err(CheckpointError::Entries(errors));
+	}
+	for (SavedEntry { entry, data }, (handle, halves)) in saved.iter().zip(targets) {
+		for (half, data) in halves.into_iter().zip(data) {
+			match half {
+				RawStorage::Buffer { buffer, .. } => render_queue.write_buffer(&buffer, 0, data),
+				RawStorage::Texture { image, format, size } => buffers.write_whole_texture(image, format, size, data.clone()),
+			}
+		}
+		if entry.double_buffered {
+			buffers.set_front_half(handle, entry.front_is_second);
+		}
+	}
+	Ok(saved.into_iter().map(|saved| saved.entry).collect())
+}
+
+/// Write a checkpoint file. After the magic bytes, the version and the number of entries, each entry has its name, its kind as a byte, being 0 for storage buffers, 1 for uniform buffers and 2 for textures, then the size of a buffer, or the format name, width, height and depth or array layers of a texture, the last of which version 1 of the format left out, then the number of halves and which is the front, and then the data of each half. Every number is little-endian, and every string and block of data is preceded by its length.
+fn encode(entries: &[SavedEntry]) -> Vec<u8> {
+	let mut bytes = MAGIC.to_vec();
+	bytes.extend(CHECKPOINT_VERSION.to_le_bytes());
+	bytes.extend((entries.len() as u32).to_le_bytes());
+	for SavedEntry { entry, data } in entries {
+		bytes.extend((entry.name.len() as u32).to_le_bytes());
+		bytes.extend(entry.name.as_bytes());
+		match &entry.layout {
+			CheckpointLayout::Storage { size } => {
+				bytes.push(0);
+				bytes.extend(size.to_le_bytes());
+			}
+			CheckpointLayout::Uniform { size } => {
+				bytes.push(1);
+				bytes.extend(size.to_le_bytes());
+			}
+			CheckpointLayout::Texture { format, width, height, depth_or_array_layers } => {
+				bytes.push(2);
+				bytes.extend((format.len() as u32).to_le_bytes());
+				bytes.extend(format.as_bytes());
+				bytes.extend(width.to_le_bytes());
+				bytes.extend(height.to_le_bytes());
+				bytes.extend(depth_or_array_layers.to_le_bytes());
+			}
+		}
+		bytes.push(data.len() as u8);
+		bytes.push(entry.front_is_second as u8);
+		for half in data {
+			bytes.extend((half.len() as u64).to_le_bytes());
+			bytes.extend(half);
+		}
+	}
+	bytes
+}
+
+/// Reads the parts of a checkpoint file in order, failing if the file ends too soon.
+struct Reader<'a>(&'a [u8]);
+
+impl Reader<'_> {
+	fn bytes(&mut self, len: u64) -> Result<&[u8], CheckpointError> {
+		if len > self.0.len() as u64 {
+			return Err(CheckpointError::Malformed("the file ends in the middle of an entry".to_owned()));
+		}
+		let (bytes, rest) = self.0.split_at(len as usize);
+		self.0 = rest;
+		Ok(bytes)
+	}
+
+	fn u8(&mut self) -> Result<u8, CheckpointError> { Ok(self.bytes(1)?[0]) }
+
+	fn u32(&mut self) -> Result<u32, CheckpointError> { Ok(u32::from_le_bytes(self.bytes(4)?.try_into().unwrap())) }
+
+	fn u64(&mut self) -> Result<u64, CheckpointError> { Ok(u64::from_le_bytes(self.bytes(8)?.try_into().unwrap())) }
+
+	fn string(&mut self) -> Result<String, CheckpointError> {
+		let len = self.u32()?;
+		String::from_utf8(self.bytes(len as u64)?.to_vec())
+			.map_err(|_| CheckpointError::Malformed("a name isn't valid UTF-8".to_owned()))
+	}
+}
+
+/// Read a checkpoint file written by [encode].
+fn decode(bytes: &[u8]) -> Result<Vec<SavedEntry>, CheckpointError> {
+	let mut reader = Reader(bytes);
+	if reader.bytes(MAGIC.len() as u64).ok() != Some(MAGIC) {
+		return Err(CheckpointError::Malformed("the file isn't a checkpoint".to_owned()));
+	}
+	let version = reader.u32()?;
+	if version > CHECKPOINT_VERSION {
+		return Err(CheckpointError::UnsupportedVersion(version));
+	}
+	let count = reader.u32()?;
+	let mut entries = Vec::new();
+	for _ in 0..count {
+		let name = reader.string()?;
+		let layout = match reader.u8()? {
+			0 => CheckpointLayout::Storage { size: reader.u64()? },
+			1 => CheckpointLayout::Uniform { size: reader.u64()? },
+			2 => CheckpointLayout::Texture {
+				format: reader.string()?,
+				width: reader.u32()?,
+				height: reader.u32()?,
+				depth_or_array_layers: if version < 2 { 1 } else { reader.u32()? },
+			},
+			kind => return Err(CheckpointError::Malformed(format!("\"{}\" has an unknown kind {}", name, kind))),
+		};
+		let halves = reader.u8()?;
+		if halves != 1 && halves != 2 {
+			return Err(CheckpointError::Malformed(format!("\"{}\" has {} halves", name, halves)));
+		}
+		let front_is_second = reader.u8()? != 0;
+		let data = (0..halves)
+			.map(|_| {
+				let len = reader.u64()?;
+				Ok(reader.bytes(len)?.to_vec())
+			})
+			.collect::<Result<Vec<_>, _>>()?;
+		let entry = CheckpointEntry {
+			name,
+			layout,
+			double_buffered: halves == 2,
+			front_is_second,
+			bytes: data.iter().map(|half| half.len() as u64).sum(),
+		};
+		entries.push(SavedEntry { entry, data });
+	}
+	Ok(entries)
+}
@@ -1,15 +1,76 @@
-use std::sync::mpsc::{Receiver, SyncSender};
+#[cfg(target_arch = "wasm32")]
+use std::sync::mpsc::{channel, Sender};
+#[cfg(not(target_arch = "wasm32"))]
+use std::sync::mpsc::{sync_channel, SyncSender, TrySendError};
+use std::{collections::BTreeMap, sync::mpsc::Receiver};
 
-use super::{ComputeTaskDoneEvent, CopyBufferEvent};
+#[cfg(not(target_arch = "wasm32"))]
+use bevy::log::warn;
+use bevy::{diagnostic::DiagnosticPath, utils::HashMap};
+
+use super::{BufferReadbackEvent, ComputeErrorEvent, ComputeSequenceId, ComputeTaskDoneEvent, StepWriteAppliedEvent};
 use crate::shader_buffer_set::ShaderBufferHandle;
 
+/// The sending half of the channel from the render world to the main world. Natively it's bounded, so a render world that gets too far ahead of the main world is held back. On the web, both worlds run on the browser's main thread, so the render world can't wait for the main world, and it's unbounded instead.
+#[cfg(not(target_arch = "wasm32"))]
+pub type MessageSender = SyncSender<ComputeMessage>;
+#[cfg(target_arch = "wasm32")]
+pub type MessageSender = Sender<ComputeMessage>;
+
+pub fn message_channel() -> (MessageSender, Receiver<ComputeMessage>) {
+	#[cfg(not(target_arch = "wasm32"))]
+	return sync_channel(16);
+	#[cfg(target_arch = "wasm32")]
+	return channel();
+}
+
+/// The receiving halves of the channels of the compute sequences, one for each sequence that's running, or has finished. Each sequence gets a new channel when it's started, so anything still on its way from one that's been stopped or replaced is dropped along with the old channel.
+#[derive(Default)]
 pub struct ComputeDataTransmission {
-	pub sender: SyncSender<ComputeMessage>,
-	pub receiver: Receiver<ComputeMessage>,
+	pub receivers: BTreeMap<ComputeSequenceId, Receiver<ComputeMessage>>,
 }
 
 pub enum ComputeMessage {
-	CopyBuffer(CopyBufferEvent),
+	Readback(BufferReadbackEvent),
 	GroupDone(ComputeTaskDoneEvent),
 	SwapBuffers(ShaderBufferHandle),
+	RotateBuffers(ShaderBufferHandle, u32),
+	Progress { task: usize, iterations: u32, total_iterations: Option<u32>, pending: bool, seed: u32 },
+	Diagnostic { path: DiagnosticPath, value: f64 },
+	WatchdogTimeout { generation: u32, error: ComputeErrorEvent },
+	Error(ComputeErrorEvent),
+	StepWriteApplied(StepWriteAppliedEvent),
+	StepTimings(HashMap<(String, String), f64>),
+	ClearStepFlag(String),
+}
+
+/// Send a message to the main world. Messages are never dropped while their sequence is running: if the channel is full, because the main world has fallen behind, this logs a warning and blocks until there's room. On the web, the channel is never full. Once the sequence has been stopped or replaced, its channel is disconnected, and whatever it still sends is dropped, as there's nothing left in the main world for it.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn send_message(sender: &MessageSender, message: ComputeMessage) {
+	match sender.try_send(message) {
+		Ok(()) => {}
+		Err(TrySendError::Full(message)) => {
+			warn!("The bevy_compute message channel is full, blocking the render world until the main world catches up");
+			let _ = sender.send(message);
+		}
+		Err(TrySendError::Disconnected(_)) => {}
+	}
+}
+
+#[cfg(target_arch = "wasm32")]
+pub fn send_message(sender: &MessageSender, message: ComputeMessage) { let _ = sender.send(message); }
+
+/// Send a message to the main world without blocking, handing it back if the channel is full, so it can be sent again in a later frame. On the web, the channel is never full. Like [send_message], this drops the message once its sequence has been stopped or replaced.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn try_send_message(sender: &MessageSender, message: ComputeMessage) -> Result<(), ComputeMessage> {
+	match sender.try_send(message) {
+		Ok(()) | Err(TrySendError::Disconnected(_)) => Ok(()),
+		Err(TrySendError::Full(message)) => Err(message),
+	}
+}
+
+#[cfg(target_arch = "wasm32")]
+pub fn try_send_message(sender: &MessageSender, message: ComputeMessage) -> Result<(), ComputeMessage> {
+	send_message(sender, message);
+	Ok(())
 }
@@ -0,0 +1,309 @@
+use std::sync::{
+	atomic::{AtomicBool, Ordering},
+	mpsc::{sync_channel, Receiver, SyncSender},
+	Arc,
+};
+
+use bevy::{
+	prelude::*,
+	render::{
+		render_asset::RenderAssetUsages,
+		render_resource::{Buffer, BufferDescriptor, BufferUsages, Extent3d, MapMode, TextureDimension, TextureFormat},
+		renderer::{RenderDevice, RenderQueue},
+		Extract, Render, RenderApp, RenderSet,
+	},
+	utils::HashMap,
+};
+
+use crate::{
+	compute_polling::poll_device,
+	shader_buffer_set::{ShaderBufferHandle, ShaderBufferSet},
+};
+
+/// How a buffer registered with [ComputeDebugVisualizations] is drawn. The buffer must be a storage buffer created with [BufferUsages::COPY_SRC], and for a double buffer, the front buffer is the one drawn.
+#[derive(Clone, Copy, Debug)]
+pub enum VisualizeBuffer {
+	/// Draw a circle with [Gizmos] at the position of each of the first `count` elements of the buffer. Each element is `stride` bytes long, with its position stored as a `vec2<f32>` `position_offset` bytes into it, in the same 2D world coordinates the gizmos are drawn in.
+	Points {
+		/// The size of each element in bytes, including any padding.
+		stride: u32,
+		/// Where in each element its position is, in bytes from the start of the element.
+		position_offset: u32,
+		/// How many elements to draw. Elements past the end of the buffer are ignored.
+		count: u32,
+	},
+
+	/// Draw a line strip with [Gizmos] through the positions of the first `count` elements of the buffer, laid out the same as with [Points](VisualizeBuffer::Points).
+	Lines {
+		/// The size of each element in bytes, including any padding.
+		stride: u32,
+		/// Where in each element its position is, in bytes from the start of the element.
+		position_offset: u32,
+		/// How many elements to draw. Elements past the end of the buffer are ignored.
+		count: u32,
+	},
+
+	/// Treat the start of the buffer as a `width` by `height` grid of `f32`s, row by row, and draw it into a debug image, mapping the lowest value in the grid to black and the highest to white, through red and yellow. The image is returned by [add](ComputeDebugVisualizations::add), to be displayed with a [Sprite] or anything else that shows an image.
+	Heatmap {
+		/// The width of the grid, in elements.
+		width: u32,
+		/// The height of the grid, in elements.
+		height: u32,
+	},
+}
+
+struct DebugVisualization {
+	visualization: VisualizeBuffer,
+	data: Option<Vec<u8>>,
+	image: Option<Handle<Image>>,
+}
+
+/// The buffers being visualized by the [ComputeDebugVisualizationPlugin], and how they're drawn. This is added as a resource by that plugin. Each registered buffer is read back from the GPU a few times a second, without waiting for the copy to finish, so it never stalls the frame, and the visualization shows the most recent contents that have arrived.
+#[derive(Resource)]
+pub struct ComputeDebugVisualizations {
+	/// How many times per second each buffer is read back. Defaults to 5.
+	pub rate: f32,
+
+	/// The color points and lines are drawn with.
+	pub color: Color,
+
+	/// The radius of the circle drawn for each point.
+	pub point_radius: f32,
+
+	visualizations: HashMap<ShaderBufferHandle, DebugVisualization>,
+	since_readback: f32,
+	due: bool,
+}
+
+impl Default for ComputeDebugVisualizations {
+	fn default() -> Self {
+		Self {
+			rate: 5.0,
+			color: Color::srgb(1.0, 0.0, 1.0),
+			point_radius: 2.0,
+			visualizations: HashMap::new(),
+			since_readback: 0.0,
+			due: false,
+		}
+	}
+}
+
+impl ComputeDebugVisualizations {
+	/// Start visualizing a buffer. For a [Heatmap](VisualizeBuffer::Heatmap), this returns the image the heatmap is drawn into, which starts out black. Registering a buffer a second time replaces its visualization.
+	pub fn add(
+		&mut self, handle: ShaderBufferHandle, visualization: VisualizeBuffer, images: &mut Assets<Image>,
+	) -> Option<Handle<Image>> {
+		let image = match visualization {
+			VisualizeBuffer::Heatmap { width, height } => Some(images.add(Image::new_fill(
+				Extent3d { width, height, depth_or_array_layers: 1 },
+				TextureDimension::D2,
+				&[0, 0, 0, 255],
+				TextureFormat::Rgba8UnormSrgb,
+				RenderAssetUsages::all(),
+			))),
+			_ => None,
+		};
+		self.visualizations.insert(handle, DebugVisualization { visualization, data: None, image: image.clone() });
+		image
+	}
+
+	/// Stop visualizing a buffer.
+	pub fn remove(&mut self, handle: ShaderBufferHandle) { self.visualizations.remove(&handle); }
+
+	/// The most recent contents of a visualized buffer to arrive from the GPU, as a raw sequence of bytes, or `None` if it isn't being visualized, or nothing has arrived yet.
+	pub fn latest(&self, handle: ShaderBufferHandle) -> Option<&[u8]> {
+		self.visualizations.get(&handle)?.data.as_deref()
+	}
+}
+
+/// Adding this plugin alongside the [BevyComputePlugin](crate::BevyComputePlugin) lets buffers registered with the [ComputeDebugVisualizations] resource be drawn, as gizmos or a heatmap image, for seeing what a simulation is doing while debugging it. Buffers are read back on their own schedule, whether or not a compute sequence is running, and nothing here ever waits on the GPU.
+///
+/// It's only enabled in debug builds by default. In release builds, the [ComputeDebugVisualizations] resource is still added, so code registering buffers doesn't need to change, but nothing is read back or drawn unless [enabled](ComputeDebugVisualizationPlugin::enabled) is set.
+pub struct ComputeDebugVisualizationPlugin {
+	/// Whether buffers are actually read back and drawn. Defaults to true in debug builds, and false in release builds.
+	pub enabled: bool,
+}
+
+impl Default for ComputeDebugVisualizationPlugin {
+	fn default() -> Self { Self { enabled: cfg!(debug_assertions) } }
+}
+
+impl Plugin for ComputeDebugVisualizationPlugin {
+	fn build(&self, app: &mut App) {
+		app.init_resource::<ComputeDebugVisualizations>();
+		if !self.enabled {
+			return;
+		}
+
+		// Readbacks only ever go from the render world to the main world, and it's fine to drop them if the main world
+		// falls behind, as another will be along shortly.
+		let (sender, receiver) = sync_channel(16);
+		app
+			.insert_non_send_resource(DebugReadbackReceiver(receiver))
+			.add_systems(Update, (schedule_debug_readbacks, receive_debug_readbacks, draw_debug_visualizations).chain());
+		app
+			.sub_app_mut(RenderApp)
+			.insert_resource(DebugReadbacks { sender, requested: Vec::new(), in_flight: Vec::new() })
+			.add_systems(ExtractSchedule, extract_debug_readbacks)
+			.add_systems(
+				Render,
+				(finish_debug_readbacks, start_debug_readbacks).chain().in_set(RenderSet::Cleanup).after(poll_device),
+			);
+	}
+}
+
+struct DebugReadback {
+	handle: ShaderBufferHandle,
+	data: Vec<u8>,
+}
+
+struct DebugReadbackReceiver(Receiver<DebugReadback>);
+
+struct InFlightReadback {
+	handle: ShaderBufferHandle,
+	buffer: Buffer,
+	mapped: Arc<AtomicBool>,
+}
+
+#[derive(Resource)]
+struct DebugReadbacks {
+	sender: SyncSender<DebugReadback>,
+	requested: Vec<ShaderBufferHandle>,
+	in_flight: Vec<InFlightReadback>,
+}
+
+fn schedule_debug_readbacks(time: Res<Time>, mut visualizations: ResMut<ComputeDebugVisualizations>) {
+	visualizations.since_readback += time.delta_secs();
+	visualizations.due = visualizations.since_readback >= 1.0 / visualizations.rate;
+	if visualizations.due {
+		visualizations.since_readback = 0.0;
+	}
+}
+
+fn extract_debug_readbacks(
+	visualizations: Extract<Res<ComputeDebugVisualizations>>, mut readbacks: ResMut<DebugReadbacks>,
+) {
+	if visualizations.due {
+		readbacks.requested = visualizations.visualizations.keys().copied().collect();
+	}
+}
+
+// Both of these run after the render graph, so the copy is recorded after everything
+// the compute node did this frame, and mapped buffers are picked up a frame or so later.
+fn start_debug_readbacks(
+	mut readbacks: ResMut<DebugReadbacks>, buffers: Res<ShaderBufferSet>, device: Res<RenderDevice>,
+	queue: Res<RenderQueue>,
+) {
+	let readbacks = &mut *readbacks;
+	for handle in readbacks.requested.drain(..) {
+		if readbacks.in_flight.iter().any(|readback| readback.handle == handle) {
+			continue;
+		}
+		let Some(src) = buffers.gpu_buffer(handle) else {
+			warn_once!("Tried to visualize {}, which is not a storage buffer", handle.describe(&buffers));
+			continue;
+		};
+		if !src.usage().contains(BufferUsages::COPY_SRC) {
+			warn_once!("Tried to visualize {}, which was not created with BufferUsages::COPY_SRC", handle.describe(&buffers));
+			continue;
+		}
+		let buffer = device.create_buffer(&BufferDescriptor {
+			label: Some("bevy_compute debug readback"),
+			size: src.size(),
+			usage: BufferUsages::COPY_DST | BufferUsages::MAP_READ,
+			mapped_at_creation: false,
+		});
+		let mut encoder = device.create_command_encoder(&default());
+		encoder.copy_buffer_to_buffer(&src, 0, &buffer, 0, src.size());
+		queue.submit([encoder.finish()]);
+
+		let mapped = Arc::new(AtomicBool::new(false));
+		let flag = mapped.clone();
+		buffer.slice(..).map_async(MapMode::Read, move |result| {
+			if result.is_ok() {
+				flag.store(true, Ordering::Release);
+			}
+		});
+		readbacks.in_flight.push(InFlightReadback { handle, buffer, mapped });
+	}
+}
+
+fn finish_debug_readbacks(mut readbacks: ResMut<DebugReadbacks>) {
+	if readbacks.in_flight.is_empty() {
+		return;
+	}
+	let readbacks = &mut *readbacks;
+	readbacks.in_flight.retain(|readback| {
+		if !readback.mapped.load(Ordering::Acquire) {
+			return true;
+		}
+		let data = readback.buffer.slice(..).get_mapped_range().to_vec();
+		readback.buffer.unmap();
+		readback.buffer.destroy();
+		let _ = readbacks.sender.try_send(DebugReadback { handle: readback.handle, data });
+		false
+	});
+}
+
+fn receive_debug_readbacks(
+	receiver: NonSend<DebugReadbackReceiver>, mut visualizations: ResMut<ComputeDebugVisualizations>,
+	mut images: ResMut<Assets<Image>>,
+) {
+	for DebugReadback { handle, data } in receiver.0.try_iter() {
+		let Some(visualization) = visualizations.visualizations.get_mut(&handle) else {
+			continue;
+		};
+		if let (VisualizeBuffer::Heatmap { width, height }, Some(image)) =
+			(visualization.visualization, &visualization.image)
+		{
+			if let Some(image) = images.get_mut(image) {
+				image.data = heatmap(&data, width, height);
+			}
+		}
+		visualization.data = Some(data);
+	}
+}
+
+fn draw_debug_visualizations(mut gizmos: Gizmos, visualizations: Res<ComputeDebugVisualizations>) {
+	for visualization in visualizations.visualizations.values() {
+		let Some(data) = &visualization.data else {
+			continue;
+		};
+		match visualization.visualization {
+			VisualizeBuffer::Points { stride, position_offset, count } => {
+				for position in positions(data, stride, position_offset, count) {
+					gizmos.circle_2d(position, visualizations.point_radius, visualizations.color);
+				}
+			}
+			VisualizeBuffer::Lines { stride, position_offset, count } => {
+				gizmos.linestrip_2d(positions(data, stride, position_offset, count), visualizations.color);
+			}
+			VisualizeBuffer::Heatmap { .. } => {}
+		}
+	}
+}
+
+fn positions(data: &[u8], stride: u32, position_offset: u32, count: u32) -> impl Iterator<Item = Vec2> + '_ {
+	(0..count as usize)
+		.map(move |index| index * stride as usize + position_offset as usize)
+		.map_while(|start| data.get(start..start + 8))
+		.map(|bytes| Vec2::from_array(bytemuck::pod_read_unaligned(bytes)))
+}
+
+fn heatmap(data: &[u8], width: u32, height: u32) -> Vec<u8> {
+	let values: Vec<f32> =
+		data.chunks_exact(4).take((width * height) as usize).map(bytemuck::pod_read_unaligned).collect();
+	let (min, max) = values
+		.iter()
+		.filter(|value| value.is_finite())
+		.fold((f32::INFINITY, f32::NEG_INFINITY), |(min, max), value| (min.min(*value), max.max(*value)));
+	let range = if max > min { max - min } else { 1.0 };
+	let mut pixels = vec![0; (width * height * 4) as usize];
+	for (pixel, value) in pixels.chunks_exact_mut(4).zip(values) {
+		let t = if value.is_finite() { ((value - min) / range).clamp(0.0, 1.0) } else { 0.0 };
+		// Black to red to yellow to white, each over a third of the range.
+		let channel = |start: f32| (((t - start) * 3.0).clamp(0.0, 1.0) * 255.0).round() as u8;
+		pixel.copy_from_slice(&[channel(0.0), channel(1.0 / 3.0), channel(2.0 / 3.0), 255]);
+	}
+	pixels
+}
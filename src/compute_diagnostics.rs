@@ -0,0 +1,99 @@
+use bevy::{
+	diagnostic::{Diagnostic, DiagnosticMeasurement, DiagnosticPath, Diagnostics, DiagnosticsStore, RegisterDiagnostic},
+	prelude::*,
+	render::RenderApp,
+	utils::Instant,
+};
+
+use super::compute_sequence::ComputeSequence;
+use crate::{shader_buffer_set::ShaderBufferSet, ComputeSequenceId};
+
+/// The diagnostic path for the CPU time spent building bind groups each frame, in milliseconds.
+pub const BIND_GROUP_BUILD_TIME: DiagnosticPath = DiagnosticPath::const_new("compute/bind_group_build_time");
+
+/// The diagnostic path for the GPU time spent on the iterations of compute tasks each frame, in milliseconds, as measured with timestamp queries. It's only reported where timestamps can be written between commands, which isn't the case on the web.
+pub const GPU_TIME: DiagnosticPath = DiagnosticPath::const_new("compute/gpu_time");
+
+/// The diagnostic path for the bytes of GPU memory allocated through the [ShaderBufferSet], as counted by [allocated_bytes](ShaderBufferSet::allocated_bytes).
+pub const ALLOCATED_BYTES: DiagnosticPath = DiagnosticPath::const_new("compute/allocated_bytes");
+
+/// The name a compute task goes by in diagnostics and trace spans, its label if it has one, or its index otherwise.
+pub(crate) fn task_name(task_index: usize, task_label: Option<&str>) -> String {
+	task_label.map_or_else(|| format!("task_{}", task_index), |label| label.replace('/', "_"))
+}
+
+/// The part of a diagnostic path naming a compute task, which is the task's name, after `sequence_N/` for a task of any sequence but the [main](ComputeSequenceId::MAIN) one, so tasks at the same index of different sequences don't share a path.
+fn task_path(sequence: ComputeSequenceId, task_index: usize, task_label: Option<&str>) -> String {
+	match sequence {
+		ComputeSequenceId::MAIN => task_name(task_index, task_label),
+		ComputeSequenceId(id) => format!("sequence_{}/{}", id, task_name(task_index, task_label)),
+	}
+}
+
+/// The diagnostic path for the CPU time spent recording the commands of a given compute task each frame, in milliseconds. The task is identified by its label if it has one, or its index otherwise, as in `compute/encode_time/simulate`, and by its sequence too if that isn't the [main](ComputeSequenceId::MAIN) one, as in `compute/encode_time/sequence_1/simulate`.
+pub fn encode_time_path(sequence: ComputeSequenceId, task_index: usize, task_label: Option<&str>) -> DiagnosticPath {
+	DiagnosticPath::new(format!("compute/encode_time/{}", task_path(sequence, task_index, task_label)))
+}
+
+/// The diagnostic path for the number of iterations a given compute task runs per second, identified the same way as in [encode_time_path]. For a task that's [submitted separately](crate::TaskSubmission::Separate), this counts the iterations the GPU has finished.
+pub fn iterations_per_second_path(
+	sequence: ComputeSequenceId, task_index: usize, task_label: Option<&str>,
+) -> DiagnosticPath {
+	DiagnosticPath::new(format!("compute/iterations_per_second/{}", task_path(sequence, task_index, task_label)))
+}
+
+/// Adding this plugin alongside the [BevyComputePlugin](crate::BevyComputePlugin) makes the render world measure how much CPU time it spends building bind groups, and recording each compute task's commands, as well as how many iterations each task runs per second, and, where timestamp queries are available, how much GPU time the tasks take, along with the bytes allocated through the [ShaderBufferSet], and report them as Bevy diagnostics. These will show up in the output of Bevy's `LogDiagnosticsPlugin`, or anything else that reads the [DiagnosticsStore]. When this plugin isn't added, nothing is measured.
+pub struct ComputeDiagnosticsPlugin;
+
+impl Plugin for ComputeDiagnosticsPlugin {
+	fn build(&self, app: &mut App) {
+		app
+			.register_diagnostic(Diagnostic::new(BIND_GROUP_BUILD_TIME).with_suffix("ms"))
+			.register_diagnostic(Diagnostic::new(GPU_TIME).with_suffix("ms"))
+			.register_diagnostic(Diagnostic::new(ALLOCATED_BYTES).with_suffix("B"))
+			.insert_resource(ComputeDiagnosticsEnabled)
+			.add_systems(Last, record_allocated_bytes);
+		app.sub_app_mut(RenderApp).insert_resource(ComputeDiagnosticsEnabled);
+	}
+}
+
+#[derive(Resource)]
+pub(crate) struct ComputeDiagnosticsEnabled;
+
+fn record_allocated_bytes(mut diagnostics: Diagnostics, buffers: Res<ShaderBufferSet>) {
+	diagnostics.add_measurement(&ALLOCATED_BYTES, || buffers.allocated_bytes() as f64);
+}
+
+/// The latest iteration count reported for a task, to work out how many iterations it's run per second since.
+pub(crate) struct IterationSample {
+	generation: u32,
+	task: usize,
+	iterations: u32,
+	time: Instant,
+}
+
+/// Record how many iterations per second a task has run since its last sample, if it's the same task, and take a new sample.
+pub(crate) fn record_iteration_rate(
+	store: &mut DiagnosticsStore, last: &mut Option<IterationSample>, sequence: &ComputeSequence, task: usize,
+	iterations: u32,
+) {
+	let now = Instant::now();
+	if let Some(last) = last.as_ref() {
+		let elapsed = (now - last.time).as_secs_f64();
+		if (last.generation, last.task) == (sequence.generation, task) && iterations >= last.iterations && elapsed > 0.0 {
+			let label = sequence.tasks.get(task).and_then(|task| task.label.as_deref());
+			let value = (iterations - last.iterations) as f64 / elapsed;
+			record_diagnostic(store, iterations_per_second_path(sequence.id, task, label), value, "/s");
+		}
+	}
+	*last = Some(IterationSample { generation: sequence.generation, task, iterations, time: now });
+}
+
+pub(crate) fn record_diagnostic(store: &mut DiagnosticsStore, path: DiagnosticPath, value: f64, suffix: &'static str) {
+	if store.get(&path).is_none() {
+		store.add(Diagnostic::new(path.clone()).with_suffix(suffix));
+	}
+	if let Some(diagnostic) = store.get_mut(&path) {
+		diagnostic.add_measurement(DiagnosticMeasurement { time: Instant::now(), value });
+	}
+}
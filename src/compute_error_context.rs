@@ -0,0 +1,79 @@
+use std::sync::{Arc, Mutex};
+
+use bevy::{
+	prelude::*,
+	render::{renderer::RenderDevice, RenderApp},
+};
+
+/// The compute task being recorded, and the steps of it recorded so far, for the errors wgpu reports while it is to be attributed to.
+struct RecordingContext {
+	task: usize,
+	label: Option<String>,
+	steps: Vec<usize>,
+	recording: bool,
+}
+
+impl RecordingContext {
+	fn describe(&self) -> String {
+		let task_name =
+			self.label.as_ref().map_or_else(|| self.task.to_string(), |label| format!("{} (\"{}\")", self.task, label));
+		match self.steps.last() {
+			Some(step) if self.recording => format!("while recording step {} of compute task {}", step, task_name),
+			_ => format!("in the submission of steps {:?} of compute task {}", self.steps, task_name),
+		}
+	}
+}
+
+/// What the compute node is recording, from when it starts recording a frame until that frame has been submitted, shared with the device's uncaptured error handler. Errors wgpu reports outside that window, such as from other Bevy systems, are handled as they would be without bevy_compute.
+#[derive(Resource, Clone, Default)]
+pub(crate) struct ComputeErrorContext(Arc<Mutex<Option<RecordingContext>>>);
+
+impl ComputeErrorContext {
+	/// Start recording a frame of a task.
+	pub fn begin(&self, task: usize, label: Option<String>) {
+		*self.0.lock().unwrap() = Some(RecordingContext { task, label, steps: Vec::new(), recording: true });
+	}
+
+	/// Start recording a step of the task, after anything recorded so far has been submitted, if it has.
+	pub fn step(&self, step: usize) {
+		if let Some(context) = self.0.lock().unwrap().as_mut() {
+			context.recording = true;
+			if !context.steps.contains(&step) {
+				context.steps.push(step);
+			}
+		}
+	}
+
+	/// Finish recording the frame, or the part of it that's submitted next.
+	pub fn end(&self) {
+		if let Some(context) = self.0.lock().unwrap().as_mut() {
+			context.recording = false;
+		}
+	}
+
+	fn describe(&self) -> Option<String> { self.0.lock().ok()?.as_ref().map(RecordingContext::describe) }
+}
+
+/// Install the uncaptured error handler on the device, once it exists. wgpu has no way to get the handler it replaces, which is its default unless the app has installed one of its own, so outside the compute node's window, errors are handled the way wgpu's default does, by logging and panicking. An app that installs its own handler after this replaces it, and loses the attribution.
+pub(crate) fn install_error_handler(app: &mut App) {
+	let Some(device) = app.world().get_resource::<RenderDevice>().cloned() else {
+		return;
+	};
+	let Some(context) =
+		app.get_sub_app(RenderApp).and_then(|render_app| render_app.world().get_resource::<ComputeErrorContext>())
+	else {
+		return;
+	};
+	let context = context.clone();
+	device.wgpu_device().on_uncaptured_error(Box::new(move |error| {
+		if let Some(description) = context.describe() {
+			error!("wgpu reported an error {}: {}", description, error);
+			panic!("wgpu error {}: {}\n", description, error);
+		}
+		error!("Handling wgpu errors as fatal by default");
+		panic!("wgpu error: {}\n", error);
+	}));
+}
+
+/// Clear the context once the frame has been submitted, so errors from after it aren't attributed to it.
+pub(crate) fn clear_error_context(context: Res<ComputeErrorContext>) { *context.0.lock().unwrap() = None; }
@@ -0,0 +1,73 @@
+use std::{collections::VecDeque, time::Duration};
+
+use bevy::utils::Instant;
+
+use super::compute_sequence::{ComputeSequence, ComputeTaskIterations, TaskRunMode};
+
+/// How many of the most recent frames the time per iteration is the median of.
+const SAMPLES: usize = 15;
+
+/// How many frames of iterations have to have been timed before there's an estimate.
+const MIN_SAMPLES: usize = 5;
+
+/// Times the iterations of the running task by wall time, once a frame, as they're reported back from the render world. Each frame that ran iterations gives a sample of the time per iteration, and the estimate is the median of the most recent samples, so a hitch, such as a frame spent compiling a pipeline, doesn't throw it off.
+#[derive(Default)]
+pub(crate) struct IterationTimer {
+	generation: u32,
+	task: usize,
+	iterations: u32,
+	time: Option<Instant>,
+	samples: VecDeque<Duration>,
+}
+
+impl IterationTimer {
+	/// Take a sample of the iterations of a task, and return the time per iteration, once there are enough samples. Time spent while the task is paused or waiting for its start delay doesn't count.
+	pub fn sample(
+		&mut self, sequence: &ComputeSequence, task: usize, iterations: u32, waiting: bool,
+	) -> Option<Duration> {
+		let now = Instant::now();
+		if (self.generation, self.task) != (sequence.generation, task) || iterations < self.iterations {
+			self.samples.clear();
+			self.time = None;
+		}
+		match self.time {
+			Some(time) if !waiting && iterations > self.iterations => {
+				if self.samples.len() == SAMPLES {
+					self.samples.pop_front();
+				}
+				self.samples.push_back((now - time) / (iterations - self.iterations));
+				self.time = Some(now);
+			}
+			// Frames without new iterations count towards the next sample, unless the task is waiting.
+			Some(_) if !waiting => {}
+			_ => self.time = Some(now),
+		}
+		(self.generation, self.task, self.iterations) = (sequence.generation, task, iterations);
+		self.time_per_iteration()
+	}
+
+	fn time_per_iteration(&self) -> Option<Duration> {
+		if self.samples.len() < MIN_SAMPLES {
+			return None;
+		}
+		let mut samples: Vec<_> = self.samples.iter().copied().collect();
+		samples.sort_unstable();
+		Some(samples[samples.len() / 2])
+	}
+}
+
+/// The number of iterations each task after the current one will run for, in total, or `None` if any of them runs forever, or [on demand](TaskRunMode::OnDemand), which never finishes either. This is what they'd run for if they started now, so a change to [ComputeTaskIterations] before they start changes it.
+pub(crate) fn later_iterations(
+	sequence: &ComputeSequence, task_iterations: &ComputeTaskIterations, current_task: usize,
+) -> Option<u32> {
+	sequence.tasks.iter().enumerate().skip(current_task + 1).try_fold(0, |total: u32, (index, task)| {
+		if task.run_mode == TaskRunMode::OnDemand {
+			return None;
+		}
+		let iterations = match task.label.as_ref().and_then(|label| task_iterations.0.get(label)) {
+			Some(&iterations) => iterations,
+			None => task.iterations?.get(),
+		};
+		Some(total.saturating_add(iterations).saturating_add(sequence.extra_iterations[index]))
+	})
+}
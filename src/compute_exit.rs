@@ -0,0 +1,68 @@
+use bevy::{
+	prelude::*,
+	render::{render_graph::RenderGraph, Extract},
+};
+
+use super::{
+	check_finite::ComputeFiniteChecker,
+	compute_bind_groups::{ComputeBindGroups, ComputePerEntityBindGroups},
+	compute_budget::ComputeBudgetTimer,
+	compute_profiling::ComputeStepTimer,
+	compute_render_setup::ComputeLabel,
+	compute_sequence::ComputeSequences,
+	compute_sequence_info::ComputeSequenceInfo,
+	compute_watchdog::ComputeWatchdog,
+	empty_check::ComputeEmptyChecker,
+	read_buffer::ComputeReadbacks,
+};
+use crate::shader_buffer_set::{ShaderBufferRenderSet, ShaderBufferSet};
+
+/// Inserted in the main world once the app is exiting and everything has been released, so the render world can release its side in the same frame.
+#[derive(Resource)]
+pub(crate) struct ComputeExited;
+
+/// When the app exits, stop every compute sequence and delete every buffer, destroying the GPU buffers rather than leaving them to be dropped along with the device. The render world's side is released by [release_render_resources], when this frame is extracted.
+pub(crate) fn release_compute_resources(
+	mut commands: Commands, mut exits: EventReader<AppExit>, buffers: Option<ResMut<ShaderBufferSet>>,
+	images: Option<ResMut<Assets<Image>>>, exited: Option<Res<ComputeExited>>,
+) {
+	if exits.is_empty() || exited.is_some() {
+		return;
+	}
+	exits.clear();
+	// The sequences go first, so nothing is run against the buffers once they're gone.
+	commands.remove_resource::<ComputeSequences>();
+	commands.remove_resource::<ComputeSequenceInfo>();
+	if let (Some(mut buffers), Some(mut images)) = (buffers, images) {
+		buffers.delete_all_buffers(&mut images);
+	}
+	commands.insert_resource(ComputeExited);
+}
+
+/// Once the main world has released everything, release the render world's side, in order: the compute node, so nothing more is dispatched, then the bind groups, then the copy buffers, layouts and texture views, and last the buffers used to read back timings and checks.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn release_render_resources(
+	mut commands: Commands, exited: Extract<Option<Res<ComputeExited>>>, mut released: Local<bool>,
+	mut render_graph: ResMut<RenderGraph>, mut render_buffers: ResMut<ShaderBufferRenderSet>,
+	budget_timer: Res<ComputeBudgetTimer>, finite_checker: Res<ComputeFiniteChecker>, watchdog: Res<ComputeWatchdog>,
+	empty_checker: Res<ComputeEmptyChecker>, readbacks: Res<ComputeReadbacks>, step_timer: Option<Res<ComputeStepTimer>>,
+) {
+	if exited.is_none() || *released {
+		return;
+	}
+	*released = true;
+	// The node is only there if a compute sequence was ever started.
+	let _ = render_graph.remove_node(ComputeLabel);
+	commands.remove_resource::<ComputeSequences>();
+	commands.remove_resource::<ComputeBindGroups>();
+	commands.remove_resource::<ComputePerEntityBindGroups>();
+	render_buffers.release();
+	budget_timer.release();
+	if let Some(step_timer) = step_timer {
+		step_timer.release();
+	}
+	finite_checker.release();
+	empty_checker.release();
+	readbacks.release();
+	watchdog.release();
+}
@@ -0,0 +1,322 @@
+use bevy::{
+	core::FrameCount,
+	prelude::*,
+	render::{
+		camera::NormalizedRenderTarget, extract_component::ExtractComponent, renderer::RenderQueue, view::ExtractedView,
+	},
+	window::PrimaryWindow,
+};
+
+use crate::shader_buffer_set::{ShaderBufferHandle, ShaderBufferSet};
+
+// The ShaderType derive generates layout checks that trip the dead code lint,
+// and they can only be allowed from outside the struct.
+#[allow(dead_code)]
+mod globals {
+	#[cfg(doc)]
+	use bevy::{core::FrameCount, prelude::Time};
+	use bevy::{
+		math::{Mat4, UVec2, UVec3, UVec4, Vec2, Vec3},
+		render::render_resource::ShaderType,
+	};
+
+	/// The values written to the globals buffer added with [add_globals](crate::ShaderBufferSet::add_globals), once per tick, before any step of that tick runs. Every step, and every shader dispatched by a step, sees the same values within a tick, except for the [seed](ComputeGlobals::seed) and the [ring_positions](ComputeGlobals::ring_positions). In the shader it's a uniform of this struct:
+	///
+	/// ```wgsl
+	/// struct ComputeGlobals {
+	///     time: f32,
+	///     delta_time: f32,
+	///     frame_count: u32,
+	///     seed: u32,
+	///     ring_positions: vec4<u32>,
+	/// }
+	///
+	/// @group(0) @binding(3) var<uniform> globals: ComputeGlobals;
+	/// ```
+	#[derive(ShaderType, Clone, Copy, Default, Debug, PartialEq)]
+	pub struct ComputeGlobals {
+		/// The seconds since the app started, as told by Bevy's [Time]. This wraps around to zero every hour, like the time in Bevy's own shader globals, so it doesn't lose precision as an `f32`.
+		pub time: f32,
+
+		/// The seconds since the previous frame.
+		pub delta_time: f32,
+
+		/// The number of frames since the app started, as told by Bevy's [FrameCount]. This wraps around once it reaches `u32::MAX`.
+		pub frame_count: u32,
+
+		/// A pseudo-random seed for the iteration, which differs from one iteration to the next, but is the same every time a compute sequence is run with the same [seed](crate::StartComputeEvent::seed), as worked out by [iteration_seed](crate::iteration_seed). Unlike the rest of the globals, this changes between the iterations of a tick, when a task runs more than one per frame.
+		pub seed: u32,
+
+		/// The [position](crate::ShaderBufferSet::ring_position) of each [ring](crate::Binding::Ring), which is the index of its current buffer, in the component of its [slot](crate::ShaderBufferSet::ring_slot), so a shader can pick the buffer to read or write, such as with `switch globals.ring_positions.x`. Components no ring has are zero. This changes at every [RotateBuffers](crate::ComputeAction::RotateBuffers) step, for the shader steps after it, even within an iteration.
+		pub ring_positions: UVec4,
+	}
+
+	/// The values written to the size uniform added with [add_size_uniform](crate::ShaderBufferSet::add_size_uniform), whenever either size changes. In the shader it's a uniform of this struct:
+	///
+	/// ```wgsl
+	/// struct ComputeSize {
+	///     window_size: vec2<u32>,
+	///     texture_size: vec2<u32>,
+	/// }
+	///
+	/// @group(0) @binding(4) var<uniform> size: ComputeSize;
+	/// ```
+	#[derive(ShaderType, Clone, Copy, Default, Debug, PartialEq, Eq)]
+	pub struct ComputeSize {
+		/// The physical size of the primary window, in pixels, or zero if there isn't one.
+		pub window_size: UVec2,
+
+		/// The size of the texture the uniform tracks, in pixels, or zero if it doesn't track one.
+		pub texture_size: UVec2,
+	}
+
+	/// The values written to the cursor uniform added with [add_cursor_uniform](crate::ShaderBufferSet::add_cursor_uniform), once per tick, before any step of that tick runs. In the shader it's a uniform of this struct:
+	///
+	/// ```wgsl
+	/// struct ComputeCursor {
+	///     position: vec2<f32>,
+	///     buttons: u32,
+	///     on_texture: u32,
+	/// }
+	///
+	/// @group(0) @binding(5) var<uniform> cursor: ComputeCursor;
+	/// ```
+	#[derive(ShaderType, Clone, Copy, Default, Debug, PartialEq)]
+	pub struct ComputeCursor {
+		/// The position of the cursor in the pixels of the tracked texture, from its top left corner. This can be outside the texture, and keeps its last value when the cursor leaves the window.
+		pub position: Vec2,
+
+		/// The mouse buttons held down, with a bit each for [LEFT](ComputeCursor::LEFT), [RIGHT](ComputeCursor::RIGHT) and [MIDDLE](ComputeCursor::MIDDLE).
+		pub buttons: u32,
+
+		/// 1 if the cursor is in the window and over the texture, or 0 if it isn't.
+		pub on_texture: u32,
+	}
+
+	/// The values written to the camera uniform added with [add_camera_uniform](crate::ShaderBufferSet::add_camera_uniform), once per tick, before any step of that tick runs. These come from the camera marked with [ComputeCamera](crate::ComputeCamera), as extracted to the render world, so they match what the renderer draws with that frame. In the shader it's a uniform of this struct:
+	///
+	/// ```wgsl
+	/// struct ComputeCameraMatrices {
+	///     view: mat4x4<f32>,
+	///     inverse_view: mat4x4<f32>,
+	///     projection: mat4x4<f32>,
+	///     inverse_projection: mat4x4<f32>,
+	///     view_projection: mat4x4<f32>,
+	///     inverse_view_projection: mat4x4<f32>,
+	///     world_position: vec3<f32>,
+	/// }
+	///
+	/// @group(0) @binding(6) var<uniform> camera: ComputeCameraMatrices;
+	/// ```
+	#[derive(ShaderType, Clone, Copy, Default, Debug, PartialEq)]
+	pub struct ComputeCameraMatrices {
+		/// Transforms from world space to view space.
+		pub view: Mat4,
+
+		/// Transforms from view space to world space, which is the camera's global transform.
+		pub inverse_view: Mat4,
+
+		/// Transforms from view space to clip space.
+		pub projection: Mat4,
+
+		/// Transforms from clip space to view space.
+		pub inverse_projection: Mat4,
+
+		/// Transforms from world space to clip space.
+		pub view_projection: Mat4,
+
+		/// Transforms from clip space to world space.
+		pub inverse_view_projection: Mat4,
+
+		/// The position of the camera in world space.
+		pub world_position: Vec3,
+	}
+
+	/// The values written to the chunk uniform added with [add_chunk_uniform](crate::ShaderBufferSet::add_chunk_uniform), before each chunk of a [Chunked](crate::DispatchSize::Chunked) dispatch. In the shader it's a uniform of this struct, whose offset is added to the `global_invocation_id` to find the invocation's place in the whole grid:
+	///
+	/// ```wgsl
+	/// struct ComputeChunk {
+	///     offset: vec3<u32>,
+	/// }
+	///
+	/// @group(0) @binding(7) var<uniform> chunk: ComputeChunk;
+	///
+	/// @compute @workgroup_size(8, 8, 1)
+	/// fn main(@builtin(global_invocation_id) local_id: vec3<u32>) {
+	///     let id = local_id + chunk.offset;
+	///     ...
+	/// }
+	/// ```
+	#[derive(ShaderType, Clone, Copy, Default, Debug, PartialEq, Eq)]
+	pub struct ComputeChunk {
+		/// The offset of the first invocation of the chunk being dispatched, in invocations, not workgroups.
+		pub offset: UVec3,
+	}
+
+	/// The values written to a task's [iteration buffer](crate::ComputeTask::iteration_buffer) when it's a [Counts](crate::TaskIterationBuffer::Counts) buffer, before each of the task's iterations. In the shader it's a uniform or storage buffer of this struct:
+	///
+	/// ```wgsl
+	/// struct ComputeIterationCounts {
+	///     local: u32,
+	///     global: u32,
+	/// }
+	///
+	/// @group(0) @binding(8) var<uniform> counts: ComputeIterationCounts;
+	/// ```
+	#[derive(ShaderType, Clone, Copy, Default, Debug, PartialEq, Eq)]
+	pub struct ComputeIterationCounts {
+		/// The iteration count within the task, from 0 in its first iteration.
+		pub local: u32,
+
+		/// The iteration count across the whole compute sequence, which is the number of iterations the tasks before this one ran, plus the local count. A sequence that's started over from a task it had already finished counts from that task.
+		pub global: u32,
+	}
+}
+pub use globals::{
+	ComputeCameraMatrices, ComputeChunk, ComputeCursor, ComputeGlobals, ComputeIterationCounts, ComputeSize,
+};
+
+/// Marks the camera whose matrices are written to the camera uniform added with [add_camera_uniform](ShaderBufferSet::add_camera_uniform). Only one camera should have this; if more than one does, the uniform isn't written, and a warning is logged.
+#[derive(Component, Clone, Copy, Default, Debug, ExtractComponent)]
+pub struct ComputeCamera;
+
+impl ComputeCameraMatrices {
+	pub(crate) fn new(view: &ExtractedView) -> Self {
+		let inverse_view = view.world_from_view.compute_matrix();
+		let view_from_world = inverse_view.inverse();
+		let view_projection = view.clip_from_world.unwrap_or(view.clip_from_view * view_from_world);
+		Self {
+			view: view_from_world,
+			inverse_view,
+			projection: view.clip_from_view,
+			inverse_projection: view.clip_from_view.inverse(),
+			view_projection,
+			inverse_view_projection: view_projection.inverse(),
+			world_position: view.world_from_view.translation(),
+		}
+	}
+}
+
+/// The cursor uniform added with [add_cursor_uniform](ShaderBufferSet::add_cursor_uniform), and the value to write to it on the next tick.
+#[derive(Clone, Copy)]
+pub(crate) struct CursorUniform {
+	pub handle: ShaderBufferHandle,
+	pub texture: ShaderBufferHandle,
+	pub display: Rect,
+	pub value: ComputeCursor,
+}
+
+impl ComputeCursor {
+	/// The bit of [buttons](ComputeCursor::buttons) set while the left mouse button is held down.
+	pub const LEFT: u32 = 1;
+
+	/// The bit of [buttons](ComputeCursor::buttons) set while the right mouse button is held down.
+	pub const RIGHT: u32 = 2;
+
+	/// The bit of [buttons](ComputeCursor::buttons) set while the middle mouse button is held down.
+	pub const MIDDLE: u32 = 4;
+}
+
+impl ComputeGlobals {
+	pub(crate) fn new(time: Option<&Time>, frame_count: Option<&FrameCount>, seed: u32, ring_positions: UVec4) -> Self {
+		Self {
+			time: time.map_or(0.0, Time::elapsed_secs_wrapped),
+			delta_time: time.map_or(0.0, Time::delta_secs),
+			frame_count: frame_count.map_or(0, |frame_count| frame_count.0),
+			seed,
+			ring_positions,
+		}
+	}
+
+	/// The byte offset of [seed](ComputeGlobals::seed) in the buffer, for copying in the seeds of later iterations of a tick.
+	pub(crate) const SEED_OFFSET: u64 = 12;
+
+	/// The byte offset of [ring_positions](ComputeGlobals::ring_positions) in the buffer, for copying in the positions of rings rotated during a tick.
+	pub(crate) const RING_POSITIONS_OFFSET: u64 = 16;
+}
+
+/// The increment of the SplitMix64 generator, which is 2^64 divided by the golden ratio.
+const SPLITMIX_GAMMA: u64 = 0x9E37_79B9_7F4A_7C15;
+
+/// The output function of the SplitMix64 generator, which scrambles a state into a well mixed value.
+fn splitmix(state: u64) -> u64 {
+	let mut z = state;
+	z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+	z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+	z ^ (z >> 31)
+}
+
+/// The [seed](ComputeGlobals::seed) written to the globals buffer for an iteration of a task, from the [seed](crate::StartComputeEvent::seed) the compute sequence was started with. Each task gets its own SplitMix64 generator, whose starting state is the output of one seeded with the sequence's seed, at the task's index, and the seed for each iteration is the upper half of that generator's output at the iteration's index within the task. So the seeds depend only on the sequence's seed, the task and the iteration, and not on how many iterations run per frame, or whether the task was started over by an [ExtendComputeTaskEvent](crate::ExtendComputeTaskEvent), which makes runs reproducible, and lets a shader's random values be worked out again on the CPU.
+pub fn iteration_seed(seed: u64, task: usize, iteration: u32) -> u32 {
+	let task_state = splitmix(seed.wrapping_add((task as u64 + 1).wrapping_mul(SPLITMIX_GAMMA)));
+	(splitmix(task_state.wrapping_add((iteration as u64 + 1).wrapping_mul(SPLITMIX_GAMMA))) >> 32) as u32
+}
+
+/// Write the size uniform whenever the primary window or the tracked texture has changed size since it was last written, such as after a [WindowResized](bevy::window::WindowResized) event, and when it's first added.
+pub(crate) fn update_size_uniform(
+	buffers: Res<ShaderBufferSet>, windows: Query<&Window, With<PrimaryWindow>>, render_queue: Res<RenderQueue>,
+	mut written: Local<Option<(ShaderBufferHandle, ComputeSize)>>,
+) {
+	let Some((handle, texture)) = buffers.size_uniform() else {
+		return;
+	};
+	let size = ComputeSize {
+		window_size: windows.get_single().map_or(UVec2::ZERO, Window::physical_size),
+		texture_size: texture
+			.and_then(|texture| buffers.texture_format_and_size(texture))
+			.map_or(UVec2::ZERO, |(_, size)| UVec2::new(size.width, size.height)),
+	};
+	if *written != Some((handle, size)) {
+		buffers.set_owned_buffer(handle, size, &render_queue);
+		*written = Some((handle, size));
+	}
+}
+
+/// Work out where the cursor is over the texture tracked by the cursor uniform, through the highest ordered active camera that renders to the primary window, and store it in the [ShaderBufferSet], which is extracted to the render world, where the compute node writes it to the uniform before running that frame's steps.
+pub(crate) fn update_cursor_uniform(
+	mut buffers: ResMut<ShaderBufferSet>, windows: Query<(Entity, &Window), With<PrimaryWindow>>,
+	cameras: Query<(&Camera, &GlobalTransform)>, mouse_buttons: Option<Res<ButtonInput<MouseButton>>>,
+) {
+	let Some(cursor) = buffers.cursor_uniform() else {
+		return;
+	};
+	let Some((_, texture_size)) = buffers.texture_format_and_size(cursor.texture) else {
+		return;
+	};
+	let mut value = ComputeCursor { position: cursor.value.position, buttons: 0, on_texture: 0 };
+	if let Some(mouse_buttons) = mouse_buttons {
+		for (button, bit) in [
+			(MouseButton::Left, ComputeCursor::LEFT),
+			(MouseButton::Right, ComputeCursor::RIGHT),
+			(MouseButton::Middle, ComputeCursor::MIDDLE),
+		] {
+			if mouse_buttons.pressed(button) {
+				value.buttons |= bit;
+			}
+		}
+	}
+	let world_position = windows.get_single().ok().and_then(|(window_entity, window)| {
+		let cursor_position = window.cursor_position()?;
+		let (camera, camera_transform) = cameras
+			.iter()
+			.filter(|(camera, _)| {
+				camera.is_active
+					&& matches!(
+						camera.target.normalize(Some(window_entity)),
+						Some(NormalizedRenderTarget::Window(window)) if window.entity() == window_entity
+					)
+			})
+			.max_by_key(|(camera, _)| camera.order)?;
+		camera.viewport_to_world_2d(camera_transform, cursor_position).ok()
+	});
+	if let Some(world_position) = world_position {
+		// The texture's rows go down from the top of the display rect, while world space goes up.
+		let display = cursor.display;
+		let uv = Vec2::new(world_position.x - display.min.x, display.max.y - world_position.y) / display.size();
+		value.position = uv * Vec2::new(texture_size.width as f32, texture_size.height as f32);
+		value.on_texture = display.contains(world_position) as u32;
+	}
+	if value != cursor.value {
+		buffers.cursor_uniform_mut().unwrap().value = value;
+	}
+}
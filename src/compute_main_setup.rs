@@ -1,19 +1,331 @@
-use bevy::prelude::*;
+use std::collections::BTreeMap;
 
-use super::{compute_data_transmission::ComputeDataTransmission, compute_sequence::ComputeSequence, StartComputeEvent};
+use bevy::{prelude::*, render::renderer::RenderAdapterInfo};
 
+use super::{
+	compute_capabilities::ComputeCapabilities,
+	compute_data_transmission::{message_channel, ComputeDataTransmission},
+	compute_sequence::{ComputeAction, ComputeSequence, ComputeSequences, ComputeTaskStopConditions, TaskRunMode},
+	compute_sequence_info::{ComputeIterationState, ComputeSequenceInfo},
+	ComputeErrorEvent, ComputeProgress, ComputeSequenceId, ComputeSequenceStates, ComputeState, ExtendComputeTaskEvent,
+	PauseComputeEvent, PauseComputeTaskEvent, ResumeComputeEvent, StartComputeEvent, StopComputeEvent,
+	StopComputeTaskEvent, SwapStepShaderEvent, TriggerComputeTaskEvent, UpdateDispatchSizeEvent,
+};
+use crate::shader_buffer_set::ShaderBufferSet;
+
+/// Check whether the GPU adapter can run compute shaders at all, which it can't on WebGL2 and some other downlevel backends. If it can't, this reports it once, and puts the plugin into the [Unsupported](ComputeState::Unsupported) state, in which nothing is ever dispatched, so the rest of the app can keep running.
+pub(crate) fn check_compute_support(world: &mut World) {
+	let (Some(capabilities), Some(info)) =
+		(world.get_resource::<ComputeCapabilities>(), world.get_resource::<RenderAdapterInfo>())
+	else {
+		return;
+	};
+	if capabilities.compute_shaders {
+		return;
+	}
+	let backend = format!("{:?}", info.backend);
+	let adapter = info.name.clone();
+	error!(
+		"The {} adapter \"{}\" doesn't support compute shaders, so no compute sequences will run. On the web, this usually means the browser fell back to WebGL2, rather than WebGPU.",
+		backend, adapter
+	);
+	world.send_event(ComputeErrorEvent::ComputeUnsupported { backend, adapter });
+	world.insert_resource(ComputeState::Unsupported);
+}
+
+/// The running sequence an event is for, or `None` if it hasn't been started, or has been stopped.
+fn sequence_mut<'a>(
+	sequences: &'a mut Option<ResMut<ComputeSequences>>, sequence: ComputeSequenceId,
+) -> Option<&'a mut ComputeSequence> {
+	sequences.as_deref_mut()?.0.get_mut(&sequence)
+}
+
+#[allow(clippy::too_many_arguments)]
 pub fn compute_main_setup(
 	mut commands: Commands, mut start_events: EventReader<StartComputeEvent>,
-	transmission: NonSend<ComputeDataTransmission>,
+	(mut extend_events, mut trigger_events): (EventReader<ExtendComputeTaskEvent>, EventReader<TriggerComputeTaskEvent>),
+	mut stop_events: EventReader<StopComputeTaskEvent>,
+	mut pause_events: EventReader<PauseComputeTaskEvent>, mut swap_events: EventReader<SwapStepShaderEvent>,
+	mut stop_sequence_events: EventReader<StopComputeEvent>, mut pause_sequence_events: EventReader<PauseComputeEvent>,
+	mut resume_sequence_events: EventReader<ResumeComputeEvent>,
+	mut dispatch_events: EventReader<UpdateDispatchSizeEvent>, mut sequences: Option<ResMut<ComputeSequences>>,
+	mut states: ResMut<ComputeSequenceStates>,
+	(mut progress, mut state): (ResMut<ComputeProgress>, ResMut<ComputeState>), buffers: Res<ShaderBufferSet>,
+	mut transmission: NonSendMut<ComputeDataTransmission>, mut generation: Local<u32>,
 ) {
-	if let Some(event) = start_events.read().next() {
-		commands.insert_resource(ComputeSequence {
-			sender: transmission.sender.clone(),
+	if *state == ComputeState::Unsupported {
+		start_events.clear();
+		extend_events.clear();
+		trigger_events.clear();
+		stop_events.clear();
+		pause_events.clear();
+		swap_events.clear();
+		stop_sequence_events.clear();
+		pause_sequence_events.clear();
+		resume_sequence_events.clear();
+		dispatch_events.clear();
+		return;
+	}
+	for event in extend_events.read() {
+		let Some(sequence) = sequence_mut(&mut sequences, event.sequence) else {
+			warn!(
+				"Tried to extend compute task {}, but compute sequence {} hasn't been started",
+				event.task, event.sequence.0
+			);
+			continue;
+		};
+		if event.task >= sequence.tasks.len() {
+			panic!(
+				"Tried to extend compute task {}, but the compute sequence only has {} tasks",
+				event.task,
+				sequence.tasks.len()
+			);
+		}
+		let current_task = states.progress(event.sequence).map_or(0, |progress| progress.current_task);
+		if event.task >= current_task {
+			sequence.extra_iterations[event.task] += event.additional;
+		} else {
+			// The task has already finished, so the sequence is started over from it, which
+			// the render world picks up from the change of generation.
+			*generation += 1;
+			sequence.generation = *generation;
+			sequence.first_task = event.task;
+			sequence.restart_iterations = Some(event.additional);
+			sequence.extra_iterations = vec![0; sequence.tasks.len()];
+			sequence.triggered = vec![0; sequence.tasks.len()];
+			sequence.stopped = vec![false; sequence.tasks.len()];
+			sequence.paused = vec![false; sequence.tasks.len()];
+			let progress = ComputeProgress { current_task: event.task, ..default() };
+			states.0.insert(event.sequence, (ComputeState::Preparing, progress));
+		}
+	}
+	// The render world runs the triggered iterations once it sees the count go up.
+	for event in trigger_events.read() {
+		let Some(sequence) = sequence_mut(&mut sequences, event.sequence) else {
+			warn!(
+				"Tried to trigger compute task {}, but compute sequence {} hasn't been started",
+				event.label, event.sequence.0
+			);
+			continue;
+		};
+		let task = sequence.tasks.iter().position(|task| {
+			task.run_mode == TaskRunMode::OnDemand && task.label.as_deref() == Some(event.label.as_str())
+		});
+		let Some(task) = task else {
+			error!(
+				"Tried to trigger compute task {}, but the compute sequence has no on-demand task with that label",
+				event.label
+			);
+			continue;
+		};
+		sequence.triggered[task] = sequence.triggered[task].saturating_add(event.iterations);
+	}
+	for event in stop_events.read() {
+		let Some(sequence) = sequence_mut(&mut sequences, event.sequence) else {
+			warn!("Tried to stop compute task {}, but compute sequence {} hasn't been started", event.task, event.sequence.0);
+			continue;
+		};
+		if event.task >= sequence.tasks.len() {
+			panic!(
+				"Tried to stop compute task {}, but the compute sequence only has {} tasks",
+				event.task,
+				sequence.tasks.len()
+			);
+		}
+		sequence.stopped[event.task] = true;
+	}
+	for event in pause_events.read() {
+		let Some(sequence) = sequence_mut(&mut sequences, event.sequence) else {
+			warn!(
+				"Tried to pause or resume compute task {}, but compute sequence {} hasn't been started",
+				event.task, event.sequence.0
+			);
+			continue;
+		};
+		if event.task >= sequence.tasks.len() {
+			panic!(
+				"Tried to pause or resume compute task {}, but the compute sequence only has {} tasks",
+				event.task,
+				sequence.tasks.len()
+			);
+		}
+		sequence.paused[event.task] = event.paused;
+		sync_paused(sequence, &mut states);
+	}
+	// The render world picks up the new shader from the change to the step, and switches over to it once it's ready.
+	for event in swap_events.read() {
+		let Some(sequence) = sequence_mut(&mut sequences, event.sequence) else {
+			warn!(
+				"Tried to swap the shader of step {} of compute task {}, but compute sequence {} hasn't been started",
+				event.step, event.task, event.sequence.0
+			);
+			continue;
+		};
+		let Some(step) = sequence.tasks.get_mut(event.task).and_then(|task| task.steps.get_mut(event.step)) else {
+			panic!(
+				"Tried to swap the shader of step {} of compute task {}, but the compute sequence has no such step",
+				event.step, event.task
+			);
+		};
+		let (ComputeAction::RunShader { shader, entry_point, .. }
+		| ComputeAction::RunShaderPerEntity { shader, entry_point, .. }
+		| ComputeAction::RunShaderIndirect { shader, entry_point, .. }) = &mut step.action
+		else {
+			panic!(
+				"Tried to swap the shader of step {} of compute task {}, but it doesn't run a shader",
+				event.step, event.task
+			);
+		};
+		shader.clone_from(&event.shader);
+		if let Some(new_entry_point) = &event.entry_point {
+			entry_point.clone_from(new_entry_point);
+		}
+	}
+	// The render world picks up the new size from the change to the step, and works out the workgroup counts again.
+	for event in dispatch_events.read() {
+		let Some(sequence) = sequence_mut(&mut sequences, event.sequence) else {
+			warn!(
+				"Tried to change the dispatch size of step {} of compute task {}, but compute sequence {} hasn't been started",
+				event.step, event.task, event.sequence.0
+			);
+			continue;
+		};
+		let Some(step) = sequence.tasks.get_mut(event.task).and_then(|task| task.steps.get_mut(event.step)) else {
+			error!(
+				"Tried to change the dispatch size of step {} of compute task {}, but the compute sequence has no such step",
+				event.step, event.task
+			);
+			continue;
+		};
+		let Some(dispatch) = step.action.dispatch_mut() else {
+			error!(
+				"Tried to change the dispatch size of step {} of compute task {}, but it doesn't run a shader with one",
+				event.step, event.task
+			);
+			continue;
+		};
+		*dispatch = event.dispatch;
+	}
+	// Pausing and resuming the whole sequence leaves the pauses of its tasks as they were.
+	for event in pause_sequence_events.read() {
+		let Some(sequence) = sequence_mut(&mut sequences, event.0) else {
+			warn!("Tried to pause compute sequence {}, but it hasn't been started", event.0 .0);
+			continue;
+		};
+		sequence.all_paused = true;
+		sync_paused(sequence, &mut states);
+	}
+	for event in resume_sequence_events.read() {
+		let Some(sequence) = sequence_mut(&mut sequences, event.0) else {
+			warn!("Tried to resume compute sequence {}, but it hasn't been started", event.0 .0);
+			continue;
+		};
+		sequence.all_paused = false;
+		sync_paused(sequence, &mut states);
+	}
+	for event in stop_sequence_events.read() {
+		// The render world removes its copy of the sequence when this is extracted, and the compute node stops running
+		// it. Dropping the receiver of its channel drops anything it still has on the way.
+		if sequence_mut(&mut sequences, event.0).is_none() {
+			warn!("Tried to stop compute sequence {}, but it hasn't been started", event.0 .0);
+			continue;
+		}
+		if let Some(sequences) = sequences.as_deref_mut() {
+			sequences.0.remove(&event.0);
+		}
+		transmission.receivers.remove(&event.0);
+		states.0.remove(&event.0);
+		if event.0 == ComputeSequenceId::MAIN {
+			commands.remove_resource::<ComputeSequenceInfo>();
+			commands.remove_resource::<ComputeIterationState>();
+		}
+	}
+	let mut started = BTreeMap::new();
+	for event in start_events.read() {
+		#[cfg(feature = "trace")]
+		let _span = info_span!("compute_sequence_start", tasks = event.tasks.len()).entered();
+		*generation += 1;
+		// Anything still on the way from a sequence this one replaces belongs to it, and is dropped with its channel.
+		let (sender, receiver) = message_channel();
+		transmission.receivers.insert(event.sequence, receiver);
+		let sequence = ComputeSequence {
+			id: event.sequence,
+			sender,
 			tasks: event.tasks.clone(),
 			iteration_buffer: event.iteration_buffer,
-		});
-		if let Some(_) = start_events.read().next() {
-			panic!("Attempted to start multiple compute sequences at once");
+			seed: event.seed,
+			generation: *generation,
+			first_task: 0,
+			restart_iterations: None,
+			extra_iterations: vec![0; event.tasks.len()],
+			stopped: vec![false; event.tasks.len()],
+			paused: vec![false; event.tasks.len()],
+			all_paused: false,
+			triggered: vec![0; event.tasks.len()],
+		};
+		if started.insert(event.sequence, sequence).is_some() {
+			panic!("Attempted to start compute sequence {} more than once at once", event.sequence.0);
+		}
+		states.0.insert(event.sequence, (ComputeState::Preparing, ComputeProgress::default()));
+	}
+	// A skipped group is only a problem if a shader binds something in it, which can't be told here, so it's only
+	// warned about.
+	if !started.is_empty() {
+		if let Err(error) = buffers.check_groups() {
+			warn!("{}", error);
+		}
+	}
+	match sequences.as_deref_mut() {
+		Some(sequences) => sequences.0.extend(started),
+		None if !started.is_empty() => commands.insert_resource(ComputeSequences(started)),
+		None => {}
+	}
+	// Once the last sequence has been stopped, there's nothing left for the render world to run.
+	if sequences.as_ref().is_some_and(|sequences| sequences.0.is_empty()) {
+		commands.remove_resource::<ComputeSequences>();
+	}
+	if states.is_changed() {
+		states.mirror_main(&mut state, &mut progress);
+	}
+}
+
+/// Bring the state of a sequence in line with whether it's now paused at the task it's on, straight away, rather than once the render world catches up.
+fn sync_paused(sequence: &ComputeSequence, states: &mut ComputeSequenceStates) {
+	if let Some((state, progress)) = states.0.get_mut(&sequence.id) {
+		state.set_paused(sequence.paused_at(progress.current_task));
+	}
+}
+
+/// Run the stop condition in [ComputeTaskStopConditions] for the running task of each compute sequence, if it has one, and stop the task with a [StopComputeTaskEvent] once it returns true.
+pub(crate) fn run_stop_conditions(world: &mut World) {
+	let (Some(sequences), Some(conditions)) =
+		(world.get_resource::<ComputeSequences>(), world.get_resource::<ComputeTaskStopConditions>())
+	else {
+		return;
+	};
+	let states = world.resource::<ComputeSequenceStates>();
+	let mut due = Vec::new();
+	for (&id, sequence) in sequences.0.iter() {
+		let Some((ComputeState::Preparing | ComputeState::Running { .. } | ComputeState::Paused { .. }, progress)) =
+			states.0.get(&id)
+		else {
+			continue;
+		};
+		let task = progress.current_task;
+		if sequence.stopped.get(task).copied().unwrap_or(true) {
+			continue;
+		}
+		if let Some(condition) = sequence.tasks[task].label.as_ref().and_then(|label| conditions.0.get(label)).copied() {
+			due.push((id, task, condition));
+		}
+	}
+	for (sequence, task, condition) in due {
+		match world.run_system(condition) {
+			Ok(true) => {
+				world.send_event(StopComputeTaskEvent { sequence, task });
+			}
+			Ok(false) => {}
+			Err(error) => warn!("Couldn't run the stop condition of compute task {}: {}", task, error),
 		}
 	}
 }
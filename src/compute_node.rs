@@ -1,171 +1,1002 @@
 use std::{
 	borrow::Cow,
-	time::{Duration, Instant},
+	collections::{BTreeMap, BTreeSet},
+	mem::size_of,
+	num::NonZeroU32,
+	sync::{
+		atomic::{AtomicBool, AtomicU32, Ordering},
+		Arc,
+	},
+	time::Duration,
 };
 
 use bevy::{
-	ecs::system::SystemState,
+	core::FrameCount,
+	ecs::{query::QuerySingleError, system::SystemState},
 	prelude::*,
 	render::{
+		render_asset::RenderAssets,
 		render_graph::{Node, NodeRunError, RenderGraphContext},
 		render_resource::{
-			CachedComputePipelineId, CachedPipelineState, ComputePassDescriptor, ComputePipelineDescriptor, PipelineCache,
+			BindGroup, BindGroupId, BindGroupLayout, BindGroupLayoutId, Buffer, BufferInitDescriptor, BufferUsages,
+			CachedComputePipelineId, CachedPipelineState, CommandEncoder, ComputePass, ComputePassDescriptor,
+			ComputePipeline, ComputePipelineDescriptor, Pipeline, PipelineCache, PipelineCacheError, PushConstantRange,
+			ShaderStages,
 		},
-		renderer::{RenderContext, RenderDevice, RenderQueue},
+		renderer::{RenderAdapterInfo, RenderContext, RenderDevice, RenderQueue},
+		texture::{FallbackImage, GpuImage},
+		view::ExtractedView,
 	},
+	utils::{HashMap, HashSet, Instant},
 };
+use wgpu::{ComputePassTimestampWrites, QuerySet};
 
 use super::{
-	compute_bind_groups::ComputeBindGroups,
-	compute_data_transmission::ComputeMessage,
-	compute_sequence::{ComputeAction, ComputeSequence, ComputeStep},
-	ComputeTaskDoneEvent, CopyBufferEvent,
+	builtin_pass::BuiltinPass,
+	check_finite::{check_finite_pass, finite_check_result, ComputeFiniteChecker, ComputeFiniteChecks, FiniteCheckSite},
+	clear_buffer::{check_clear_buffer, texture_fill, TextureFill},
+	compute_bind_groups::{ComputeBindGroups, ComputePerEntityBindGroups},
+	compute_budget::ComputeBudgetTimer,
+	compute_capabilities::ComputeCapabilities,
+	compute_data_transmission::{send_message, ComputeMessage},
+	compute_diagnostics::{encode_time_path, task_name, ComputeDiagnosticsEnabled},
+	compute_error_context::ComputeErrorContext,
+	compute_globals::{iteration_seed, ComputeCamera, ComputeCameraMatrices, ComputeGlobals, ComputeIterationCounts},
+	compute_pipeline_ids::ComputePipelineIds,
+	compute_polling::ComputePolling,
+	compute_profiling::ComputeStepTimer,
+	compute_sequence::{
+		check_shader_passes, BufferOrientation, ComputeAction, ComputeSequence, ComputeSequences, ComputeStep,
+		ComputeStepFlags, ComputeTaskIterations, DispatchSize, StepCondition, StepSchedule, TaskIterationBuffer,
+		TaskRunMode, TaskSubmission,
+	},
+	compute_watchdog::{ComputeTaskWatchdogs, ComputeWatchdog},
+	convolve::convolve_passes,
+	empty_check::{check_empty_source, ComputeEmptyChecker},
+	histogram::histogram_pass,
+	prefix_sum::prefix_sum_pass,
+	read_buffer::ComputeReadbacks,
+	reduce_buffer::reduce_buffer_pass,
+	sort_buffer::sort_buffer_pass,
+	BufferReadbackEvent, ComputeErrorEvent, ComputeSequenceId, ComputeTaskDoneEvent,
+};
+use crate::{
+	prepared_shaders::{entry_points, PreparedShaders},
+	shader_buffer_set::{ShaderBufferHandle, ShaderBufferRenderSet, ShaderBufferSet},
 };
-use crate::shader_buffer_set::{ShaderBufferRenderSet, ShaderBufferSet};
 
+/// The render graph node that runs every compute sequence, recording their iterations one sequence after another, in the order of their ids. Each sequence has a [SequenceRunner] of its own, so it keeps its own task, iteration counts, pipelines and throttling, while the buffers and bind groups are shared.
+#[derive(Default)]
 pub struct ComputeNode {
+	runners: BTreeMap<ComputeSequenceId, SequenceRunner>,
+}
+
+/// Runs one compute sequence, from its first task to its last.
+struct SequenceRunner {
 	sequence: ComputeSequence,
 	current_task: usize,
 	current_pipelines_loaded: bool,
 	step_states: Vec<ComputeStepState>,
 	iterations: u32,
+	/// The number of iterations the tasks before the current one ran, for the global count of a [TaskIterationBuffer].
+	previous_iterations: u32,
 	group_start_time: Instant,
+	started_at: Instant,
+	frames: u32,
+	pending: bool,
+	uploading: bool,
+	draining: bool,
+	paused: bool,
+	/// Whether the current task runs [on demand](TaskRunMode::OnDemand), and has run every iteration it's been triggered for.
+	idle: bool,
+	frame_iterations: u32,
+	layouts: Vec<BindGroupLayout>,
+	layout_ids: Vec<BindGroupLayoutId>,
+	unused_group: Option<(BindGroupLayout, BindGroup)>,
+	base_iterations: Option<u32>,
+	resolved_task: Option<usize>,
+	completed: Arc<AtomicU32>,
+	gpu_done: Option<Arc<AtomicBool>>,
+	throttled: bool,
+	failed_pipelines: HashSet<CachedComputePipelineId>,
+	/// The [one-shot](StepCondition::OneShotFlag) flags this sequence has run steps for, which aren't checked again until they've been seen cleared.
+	fired_flags: HashSet<String>,
 }
 
 struct ComputeStepState {
+	index: usize,
 	step: ComputeStep,
 	id: Option<CachedComputePipelineId>,
-	last_run_time: Instant,
+	passes: Vec<(CachedComputePipelineId, UVec3)>,
+	builtin: Vec<BuiltinPass>,
+	finite_result: Option<Buffer>,
+	fill: Option<TextureFill>,
+	workgroups: UVec3,
+	texture_sizes: Vec<Option<UVec3>>,
+	chunks: Vec<UVec3>,
+	chunk_offsets: Option<Buffer>,
+	next_run: Option<Duration>,
+	frames_until_run: u32,
 	run_this_time: bool,
 	copy_buffer_ready: bool,
+	/// Whether the last copy into the copy buffer failed, so there's nothing new in it to read back.
+	copy_failed: AtomicBool,
+	swap: Option<ShaderSwap>,
+	pipelines: HashMap<CachedComputePipelineId, (ComputePipeline, bool)>,
+}
+
+/// A shader a step is being switched over to by a [SwapStepShaderEvent](crate::SwapStepShaderEvent), with the pipeline for it. The step keeps running its old pipeline until this one is ready. If it fails, that's only reported once, but the pipeline cache tries it again if the shader is reloaded, and the step is switched over if it works.
+struct ShaderSwap {
+	step: ComputeStep,
+	id: CachedComputePipelineId,
+	workgroups: UVec3,
+	chunks: Vec<(UVec3, UVec3)>,
+	failed: bool,
+}
+
+/// The indices of the bind groups a sequence's shaders reference between them, across all its tasks, or `None` if they aren't known for any of them, in which case they're assumed to use every group.
+fn groups_used(sequence: &ComputeSequence, prepared_shaders: Option<&PreparedShaders>) -> Option<BTreeSet<u32>> {
+	let steps = sequence.tasks.iter().flat_map(|task| task.steps.iter());
+	steps
+		.flat_map(|step| entry_points(&step.action))
+		.map(|(shader, _, shader_defs, ..)| prepared_shaders?.groups_used(shader, shader_defs))
+		.try_fold(BTreeSet::new(), |mut groups, used| {
+			groups.extend(used?);
+			Some(groups)
+		})
+}
+
+/// The bind group layouts a sequence's pipelines are made against, which are those of the `groups` it uses, or of every group if they aren't known, so a sequence doesn't depend on groups that only other sequences use. A pipeline layout lists every group up to the highest one, so any below that the sequence doesn't use get the [unused_group](ShaderBufferRenderSet::unused_group) layout.
+fn sequence_layouts(
+	render_buffers: &mut ShaderBufferRenderSet, buffers: &ShaderBufferSet, device: &RenderDevice, per_entity: bool,
+	groups: Option<&BTreeSet<u32>>,
+) -> Vec<BindGroupLayout> {
+	let mut layouts = render_buffers.bind_group_layouts(buffers, device, per_entity);
+	if let Some(groups) = groups {
+		layouts.truncate(groups.last().map_or(0, |group| *group as usize + 1));
+		let (unused, _) = render_buffers.unused_group(device);
+		for (group, layout) in layouts.iter_mut().enumerate() {
+			if !groups.contains(&(group as u32)) {
+				*layout = unused.clone();
+			}
+		}
+	}
+	layouts
+}
+
+/// The shader, workgroup count and chunks of a shader step, and the workgroup count of each pass of a [RunShaderMulti](ComputeAction::RunShaderMulti) step, or `None` if they aren't known yet, because the shader is still being prepared in the main world. Steps that don't run a shader have none.
+type PreparedStep = Option<(Handle<Shader>, UVec3, Vec<(UVec3, UVec3)>, Vec<UVec3>)>;
+
+/// Work out the shader, workgroup count and chunks of a step. Shaders with override constants are copied with the values set, the workgroup sizes of exact dispatches are read from the shader source, and the entry points are looked for in it, in the main world once the shaders have loaded, so the step can't be set up until then, or at all while an entry point is missing. Dispatches over a texture are worked out from its size as it is now. Panics if such a texture can't be dispatched over.
+fn prepare_step(
+	step: &ComputeStep, asset_server: &AssetServer, prepared_shaders: Option<&PreparedShaders>, buffers: &ShaderBufferSet,
+) -> Option<PreparedStep> {
+	let over_texture =
+		|dispatch: &DispatchSize| dispatch.over_texture(buffers).unwrap_or_else(|error| panic!("{}", error));
+	match &step.action {
+		ComputeAction::RunShaderIndirect { shader, entry_point, overrides, .. } => {
+			if !prepared_shaders?.entry_point_found(shader, entry_point) {
+				return None;
+			}
+			// The workgroup counts are read from the GPU, so there's nothing to work out but the shader.
+			let shader_handle =
+				if overrides.is_empty() { asset_server.load(shader) } else { prepared_shaders?.shader(shader, overrides)? };
+			Some(Some((shader_handle, UVec3::ZERO, Vec::new(), Vec::new())))
+		}
+		ComputeAction::RunShader { shader, entry_point, shader_defs, overrides, dispatch, .. }
+		| ComputeAction::RunShaderPerEntity { shader, entry_point, shader_defs, overrides, dispatch } => {
+			if !prepared_shaders?.entry_point_found(shader, entry_point) {
+				return None;
+			}
+			let shader_handle =
+				if overrides.is_empty() { asset_server.load(shader) } else { prepared_shaders?.shader(shader, overrides)? };
+			let dispatch = over_texture(dispatch);
+			let workgroup_size = if dispatch.needs_workgroup_size() {
+				prepared_shaders?.workgroup_size(shader, entry_point, shader_defs, overrides)?
+			} else {
+				UVec3::ONE
+			};
+			let chunks =
+				if let DispatchSize::Chunked { .. } = dispatch { dispatch.chunks(workgroup_size) } else { Vec::new() };
+			Some(Some((shader_handle, dispatch.workgroup_count(workgroup_size), chunks, Vec::new())))
+		}
+		ComputeAction::RunShaderMulti { shader, shader_defs, passes } => {
+			let workgroups = passes
+				.iter()
+				.map(|(entry_point, dispatch)| {
+					if !prepared_shaders?.entry_point_found(shader, entry_point) {
+						return None;
+					}
+					let dispatch = over_texture(dispatch);
+					let workgroup_size = if dispatch.needs_workgroup_size() {
+						prepared_shaders?.workgroup_size(shader, entry_point, shader_defs, &[])?
+					} else {
+						UVec3::ONE
+					};
+					Some(dispatch.workgroup_count(workgroup_size))
+				})
+				.collect::<Option<Vec<_>>>()?;
+			Some(Some((asset_server.load(shader), UVec3::ZERO, Vec::new(), workgroups)))
+		}
+		_ => Some(None),
+	}
+}
+
+/// The sizes of the textures a step's [FromTexture](DispatchSize::FromTexture) dispatches cover, with `None` for any that are still loading, which its workgroup counts are worked out again for whenever they change.
+fn dispatch_texture_sizes(step: &ComputeStep, buffers: &ShaderBufferSet) -> Vec<Option<UVec3>> {
+	entry_points(&step.action)
+		.into_iter()
+		.filter_map(|(.., dispatch)| dispatch.texture())
+		.map(|texture| buffers.dispatch_texture_size(texture).ok().flatten())
+		.collect()
+}
+
+/// The offsets of the chunks of a chunked dispatch are copied into the chunk uniform from this buffer, laid out like the uniform, before each chunk.
+fn chunk_offsets_buffer(device: &RenderDevice, buffers: &ShaderBufferSet, chunks: &[(UVec3, UVec3)]) -> Option<Buffer> {
+	(!chunks.is_empty()).then(|| {
+		if buffers.chunk_uniform().is_none() {
+			panic!("Tried to run a chunked dispatch, but no chunk uniform has been added for the chunk offsets");
+		}
+		let offsets = chunks.iter().map(|(offset, _)| [offset.x, offset.y, offset.z, 0]).collect::<Vec<_>>();
+		device.create_buffer_with_data(&BufferInitDescriptor {
+			label: None,
+			contents: bytemuck::cast_slice(&offsets),
+			usage: BufferUsages::COPY_SRC,
+		})
+	})
 }
 
-impl ComputeNode {
-	pub fn new(sequence: &ComputeSequence) -> Self {
+/// The shader and entry point a step runs, if it runs one.
+fn step_shader(step: &ComputeStep) -> Option<(&str, &str)> {
+	match &step.action {
+		ComputeAction::RunShader { shader, entry_point, .. }
+		| ComputeAction::RunShaderPerEntity { shader, entry_point, .. }
+		| ComputeAction::RunShaderIndirect { shader, entry_point, .. } => Some((shader, entry_point)),
+		_ => None,
+	}
+}
+
+/// The asset path of the shader a step runs, for any step that runs one, unlike [step_shader], which is only for steps with a single entry point.
+fn step_shader_path(step: &ComputeStep) -> Option<&str> {
+	match &step.action {
+		ComputeAction::RunShaderMulti { shader, .. } => Some(shader),
+		_ => step_shader(step).map(|(shader, _)| shader),
+	}
+}
+
+impl SequenceRunner {
+	/// Release the buffers the copy steps of the current task read back through.
+	fn release_copy_buffers(&self, world: &mut World) {
+		world.resource_scope(|world, mut render_buffers: Mut<ShaderBufferRenderSet>| {
+			let buffers = world.resource::<ShaderBufferSet>();
+			for step in self.step_states.iter() {
+				if let ComputeAction::CopyBuffer { src, .. } = step.step.action {
+					render_buffers.remove_copy_buffer(self.sequence.id, src, buffers);
+				}
+			}
+		});
+	}
+
+	/// Whether this runs the [main](ComputeSequenceId::MAIN) sequence, which is the only one that writes queued with [queue_write_at](ShaderBufferSet::queue_write_at) are made in, and that's [recorded](ShaderBufferSet::start_recording) and replayed, as both are by task index.
+	fn main(&self) -> bool { self.sequence.id == ComputeSequenceId::MAIN }
+
+	/// The sequence as it is now, in the render world, with the changes made to it since it was started, such as tasks that have been extended or paused, or steps that have had their shaders swapped.
+	fn latest<'a>(&self, world: &'a World) -> &'a ComputeSequence {
+		&world.resource::<ComputeSequences>().0[&self.sequence.id]
+	}
+
+	fn new(sequence: &ComputeSequence) -> Self {
 		Self {
 			sequence: sequence.clone(),
-			current_task: 0,
+			current_task: sequence.first_task,
 			current_pipelines_loaded: false,
 			step_states: Vec::new(),
 			iterations: 0,
+			previous_iterations: 0,
 			group_start_time: Instant::now(),
+			started_at: Instant::now(),
+			frames: 0,
+			pending: false,
+			uploading: false,
+			draining: false,
+			paused: false,
+			idle: false,
+			frame_iterations: 1,
+			layouts: Vec::new(),
+			layout_ids: Vec::new(),
+			unused_group: None,
+			base_iterations: None,
+			resolved_task: None,
+			completed: Arc::default(),
+			gpu_done: None,
+			throttled: false,
+			failed_pipelines: HashSet::new(),
+			fired_flags: HashSet::new(),
+		}
+	}
+
+	/// The counts written to a [Counts](TaskIterationBuffer::Counts) iteration buffer of the current task before the given iteration of it.
+	fn iteration_counts(&self, iteration: u32) -> ComputeIterationCounts {
+		ComputeIterationCounts { local: iteration, global: self.previous_iterations + iteration }
+	}
+
+	/// The number of iterations of the current task that are done. Iterations of a task that's submitted with the frame are done once they're recorded, while those of a task that's submitted separately are only done once the GPU has finished them.
+	fn done_iterations(&self) -> u32 {
+		match self.sequence.tasks[self.current_task].submission {
+			TaskSubmission::WithFrame => self.iterations,
+			TaskSubmission::Separate => self.completed.load(Ordering::Acquire),
+		}
+	}
+
+	/// The number of iterations the current task runs for, or `None` if it runs forever. The base count is worked out once, when the task starts, from [ComputeTaskIterations] if the task's label is in it, and otherwise from the task itself, or is zero for a task that runs [on demand](TaskRunMode::OnDemand), and any iterations the task has been extended or triggered for since are added on top.
+	fn total_iterations(&mut self, world: &World) -> Option<u32> {
+		if self.resolved_task != Some(self.current_task) {
+			let task = &self.sequence.tasks[self.current_task];
+			let from_resource =
+				task.label.as_ref().and_then(|label| world.get_resource::<ComputeTaskIterations>()?.0.get(label).copied());
+			self.base_iterations = match self.sequence.restart_iterations {
+				Some(iterations) if self.current_task == self.sequence.first_task => Some(iterations),
+				_ if task.run_mode == TaskRunMode::OnDemand => Some(0),
+				_ => from_resource.or(task.iterations.map(NonZeroU32::get)),
+			};
+			self.resolved_task = Some(self.current_task);
+		}
+		let latest = self.latest(world);
+		let extra = latest.extra_iterations.get(self.current_task).copied().unwrap_or(0);
+		let triggered = latest.triggered.get(self.current_task).copied().unwrap_or(0);
+		self.base_iterations.map(|base| base.saturating_add(extra).saturating_add(triggered))
+	}
+
+	fn send_readback(&self, src: ShaderBufferHandle, data: Vec<u8>) {
+		let event = BufferReadbackEvent {
+			sequence: self.sequence.id,
+			handle: src,
+			group: self.sequence.tasks[self.current_task].label.clone(),
+			iteration: self.iterations,
+			data,
+		};
+		send_message(&self.sequence.sender, ComputeMessage::Readback(event));
+	}
+
+	/// Record the writes queued with [queue_write_at](ShaderBufferSet::queue_write_at) that are due before a step of the current task, or at the end of the iteration, and tell the main world they've been made.
+	fn apply_step_writes(
+		&self, before_step: Option<usize>, iteration: u32, buffers: &ShaderBufferSet, device: &RenderDevice,
+		context: &mut RenderContext,
+	) {
+		let applied = buffers.step_writes().apply(
+			self.current_task,
+			before_step,
+			iteration,
+			buffers,
+			device,
+			context.command_encoder(),
+		);
+		for event in applied {
+			send_message(&self.sequence.sender, ComputeMessage::StepWriteApplied(event));
+		}
+	}
+
+	/// The double buffers a shader step swaps once it's dispatched.
+	fn auto_swaps(step: &ComputeStepState) -> &[ShaderBufferHandle] {
+		match &step.step.action {
+			ComputeAction::RunShader { auto_swap, .. } => auto_swap,
+			_ => &[],
 		}
 	}
 
+	/// The double buffers a shader step binds the other way round, each only once.
+	fn reversed_buffers(step: &ComputeStepState) -> Vec<ShaderBufferHandle> {
+		let ComputeAction::RunShader { buffer_orientation, .. } = &step.step.action else {
+			return Vec::new();
+		};
+		let mut reversed = Vec::new();
+		for (buffer, orientation) in buffer_orientation {
+			if *orientation == BufferOrientation::Reversed && !reversed.contains(buffer) {
+				reversed.push(*buffer);
+			}
+		}
+		reversed
+	}
+
+	/// Dispatch a shader step, or one pass of it, after `iteration` iterations of the task.
+	#[allow(clippy::too_many_arguments)]
 	fn run_shader(
-		&self, pipeline_id: CachedComputePipelineId, x_workgroup_size: u32, y_workgroup_size: u32, z_workgroup_size: u32,
-		world: &World, render_context: &mut RenderContext,
+		&self, pass: &mut ShaderPass, step: &ComputeStepState, world: &World, bind_groups: &[BindGroup],
+		pipeline_id: Option<CachedComputePipelineId>, workgroups: UVec3, iteration: u32,
 	) {
+		let per_entity = match step.step.action {
+			ComputeAction::RunShader { .. }
+			| ComputeAction::RunShaderMulti { .. }
+			| ComputeAction::RunShaderIndirect { .. } => false,
+			ComputeAction::RunShaderPerEntity { .. } => true,
+			_ => panic!("Somehow got to trying to run a shader for a step that doesn't run one"),
+		};
+		let UVec3 { x, y, z } = workgroups;
+		let Some(pipeline_id) = pipeline_id else {
+			panic!("Somehow got to trying to run a shader step with no pipeline ID");
+		};
 		let pipeline_cache = world.resource::<PipelineCache>();
-		let bind_groups = world.resource::<ComputeBindGroups>();
-		let Some(pipeline) = pipeline_cache.get_compute_pipeline(pipeline_id) else {
+		let pipeline = pipeline_cache.get_compute_pipeline(pipeline_id);
+		let Some(pipeline) = pipeline.or_else(|| step.pipelines.get(&pipeline_id).map(|(pipeline, _)| pipeline)) else {
 			panic!("Somehow running the shader without all the shader pipelines being loaded");
 		};
-		let encoder = render_context.command_encoder();
-		{
-			let mut pass = encoder.begin_compute_pass(&ComputePassDescriptor::default());
-			pass.set_pipeline(pipeline);
-			for (i, bind_group) in bind_groups.0.iter().enumerate() {
-				pass.set_bind_group(i as u32, bind_group, &[]);
+		if pass.pipeline != Some(pipeline_id) {
+			pass.pass.set_pipeline(pipeline);
+			pass.pipeline = Some(pipeline_id);
+		}
+		// Only the groups the sequence's pipelines were made with are set, with the elements of any dynamic uniforms
+		// in them the step picks.
+		let buffers = world.resource::<ShaderBufferSet>();
+		let elements = match &step.step.action {
+			ComputeAction::RunShader { dynamic_offsets, .. } => dynamic_offsets.as_slice(),
+			_ => &[],
+		};
+		for (i, bind_group) in bind_groups.iter().take(self.layouts.len()).enumerate() {
+			// A group none of the sequence's shaders reference has the unused layout in its pipelines, so it's bound to
+			// the unused bind group rather than its own.
+			match &self.unused_group {
+				Some((layout, unused)) if self.layout_ids[i] == layout.id() => {
+					pass.set_bind_group(i as u32, unused, Vec::new())
+				}
+				_ => pass.set_bind_group(i as u32, bind_group, buffers.dynamic_offsets(i as u32, elements)),
 			}
-			pass.dispatch_workgroups(x_workgroup_size, y_workgroup_size, z_workgroup_size);
+		}
+		if let ComputeAction::RunShader { shader, push_constants: Some(push_constants), .. } = &step.step.action {
+			pass.pass.set_push_constants(0, &push_constants.data(shader, world, iteration));
+		}
+		if per_entity {
+			// The per-entity group holds an empty bind group from the shared set,
+			// which is replaced by each entity's own in turn.
+			let Some(group) = buffers.per_entity_group() else {
+				panic!("Somehow running a shader per entity without any per-entity buffers");
+			};
+			for bind_group in world.resource::<ComputePerEntityBindGroups>().0.iter() {
+				pass.set_bind_group(group, bind_group, Vec::new());
+				pass.pass.dispatch_workgroups(x, y, z);
+			}
+		} else if let ComputeAction::RunShaderIndirect { indirect_buffer, offset, .. } = step.step.action {
+			let Some(buffer) = buffers.gpu_buffer(indirect_buffer) else {
+				panic!("Tried to dispatch indirectly from {}, which has been deleted", indirect_buffer.describe(buffers));
+			};
+			pass.pass.dispatch_workgroups_indirect(&buffer, offset);
+		} else {
+			pass.pass.dispatch_workgroups(x, y, z);
+		}
+	}
+}
+
+impl ComputeStepState {
+	/// Whether the step can run in every iteration, when more than one runs in a frame. Steps with any other schedule, and buffer copies, which alternate between copying and reading back from one frame to the next, run at most once a frame. Buffer reads get a buffer of their own every time, so they run in every iteration.
+	fn every_iteration(&self) -> bool {
+		self.step.schedule == StepSchedule::EveryIteration
+			&& !matches!(self.step.action, ComputeAction::CopyBuffer { .. })
+			&& !matches!(self.step.run_if, Some(StepCondition::OneShotFlag(_)))
+	}
+
+	/// Whether the step's [run_if](ComputeStep::run_if) condition is met in this frame, given the flags set in the main world, and the one-shot flags the sequence has already run steps for.
+	fn condition_met(&self, flags: Option<&ComputeStepFlags>, fired: &HashSet<String>) -> bool {
+		let set = |flag: &str| flags.is_some_and(|flags| flags.get(flag));
+		match &self.step.run_if {
+			None => true,
+			Some(StepCondition::ResourceFlag(flag)) => set(flag),
+			Some(StepCondition::OneShotFlag(flag)) => set(flag) && !fired.contains(flag),
+		}
+	}
+
+	/// Whether the step's [schedule](ComputeStep::schedule) lets it run in this frame, given the time since the app started, moving its schedule on if so. A step paced by frequency keeps the time its next run is due, which moves on by exactly one period every run, so the time between runs averages out to the period even when it isn't a whole number of frames.
+	fn scheduled(&mut self, now: Duration) -> bool {
+		match self.step.schedule {
+			StepSchedule::EveryIteration => true,
+			StepSchedule::MaxFrequencyHz(hz) => {
+				if self.next_run.is_some_and(|due| now < due) {
+					return false;
+				}
+				let period = Duration::from_secs_f64(1.0 / hz as f64);
+				let due = self.next_run.unwrap_or(now) + period;
+				self.next_run = Some(if due <= now { now + period } else { due });
+				true
+			}
+			StepSchedule::EveryNFrames(frames) => {
+				let run = self.frames_until_run == 0;
+				self.frames_until_run = if run { frames.get() - 1 } else { self.frames_until_run - 1 };
+				run
+			}
+		}
+	}
+
+	/// The indices of the chunks of a chunked dispatch to run in an iteration of the task, counting from zero.
+	fn chunks_in_iteration(&self, iteration: u32) -> impl Iterator<Item = usize> {
+		let count = self.chunks.len();
+		let per_iteration = match &self.step.action {
+			ComputeAction::RunShader {
+				dispatch: DispatchSize::Chunked { chunks_per_iteration: Some(chunks), .. }, ..
+			}
+			| ComputeAction::RunShaderPerEntity {
+				dispatch: DispatchSize::Chunked { chunks_per_iteration: Some(chunks), .. },
+				..
+			} => (chunks.get() as usize).min(count),
+			_ => count,
+		};
+		let start = if count == 0 { 0 } else { (iteration as u64 * per_iteration as u64 % count as u64) as usize };
+		(0..per_iteration).map(move |i| (start + i) % count)
+	}
+}
+
+/// Submit what's been recorded into the render context of a task that's submitted separately, and start it over. If iterations are counted as done with `completed`, that's once the GPU has finished them, which is only noticed when the device is polled.
+fn submit_separately(
+	context: &mut RenderContext, device: &RenderDevice, adapter_info: &RenderAdapterInfo, render_queue: &RenderQueue,
+	completed: Option<&Arc<AtomicU32>>,
+) {
+	let recorded = std::mem::replace(context, RenderContext::new(device.clone(), (***adapter_info).clone(), None));
+	let (command_buffers, _, _) = recorded.finish();
+	render_queue.submit(command_buffers);
+	if let Some(completed) = completed.cloned() {
+		render_queue.on_submitted_work_done(move || {
+			completed.fetch_add(1, Ordering::Release);
+		});
+	}
+}
+
+/// Whether the GPU has finished all the work that had been submitted when this was first called, which registers a callback in `done` to find out. That's only noticed when the device is polled.
+fn submitted_work_done(done: &mut Option<Arc<AtomicBool>>, render_queue: &RenderQueue) -> bool {
+	let done = done.get_or_insert_with(|| {
+		let done = Arc::new(AtomicBool::new(false));
+		let callback_done = done.clone();
+		render_queue.on_submitted_work_done(move || callback_done.store(true, Ordering::Release));
+		done
+	});
+	done.load(Ordering::Acquire)
+}
+
+/// A compute pass shared by consecutive shader steps. It remembers what it has bound, so a step whose pipeline matches the previous step's doesn't set it again, and one whose layout matches doesn't set bind groups that are already set. It isn't tied to the lifetime of the command encoder, so it can be kept between steps, and it has to be dropped, which ends it, before anything else is recorded into the encoder.
+struct ShaderPass {
+	pass: ComputePass<'static>,
+	pipeline: Option<CachedComputePipelineId>,
+	bound: Vec<Option<(BindGroupId, Vec<u32>)>>,
+	timed: bool,
+}
+
+impl ShaderPass {
+	fn new(encoder: &mut CommandEncoder, label: Option<&str>) -> Self {
+		Self {
+			pass: encoder.begin_compute_pass(&ComputePassDescriptor { label, timestamp_writes: None }).forget_lifetime(),
+			pipeline: None,
+			bound: Vec::new(),
+			timed: false,
+		}
+	}
+
+	/// Start a pass for a single dispatch timed by the [ComputeStepTimer], which writes its timestamps at `index` and the one after.
+	fn timed(encoder: &mut CommandEncoder, label: Option<&str>, query_set: &QuerySet, index: u32) -> Self {
+		let timestamp_writes = ComputePassTimestampWrites {
+			query_set,
+			beginning_of_pass_write_index: Some(index),
+			end_of_pass_write_index: Some(index + 1),
+		};
+		let descriptor = ComputePassDescriptor { label, timestamp_writes: Some(timestamp_writes) };
+		Self {
+			pass: encoder.begin_compute_pass(&descriptor).forget_lifetime(),
+			pipeline: None,
+			bound: Vec::new(),
+			timed: true,
+		}
+	}
+
+	/// The pass to record a dispatch into, which is the current one, if there is one, or a new one otherwise, labeled with the label of the task it's for, so GPU captures show which task each pass belongs to. A dispatch that's timed gets a pass of its own, and so does the one after it, so no other dispatch ends up in the time.
+	fn get<'a>(
+		pass: &'a mut Option<ShaderPass>, encoder: &mut CommandEncoder, label: Option<&str>,
+		timing: Option<(Arc<QuerySet>, u32)>,
+	) -> &'a mut ShaderPass {
+		if timing.is_some() || pass.as_ref().is_some_and(|pass| pass.timed) {
+			*pass = None;
+		}
+		match timing {
+			Some((query_set, index)) => pass.insert(ShaderPass::timed(encoder, label, &query_set, index)),
+			None => pass.get_or_insert_with(|| ShaderPass::new(encoder, label)),
+		}
+	}
+
+	fn set_bind_group(&mut self, index: u32, bind_group: &BindGroup, offsets: Vec<u32>) {
+		let index = index as usize;
+		if self.bound.len() <= index {
+			self.bound.resize(index + 1, None);
+		}
+		let bound = Some((bind_group.id(), offsets));
+		if self.bound[index] != bound {
+			self.pass.set_bind_group(index as u32, bind_group, &bound.as_ref().unwrap().1);
+			self.bound[index] = bound;
 		}
 	}
 }
 
 impl Node for ComputeNode {
 	fn update(&mut self, world: &mut World) {
+		let live = world
+			.get_resource::<ComputeSequences>()
+			.map_or_else(Vec::new, |sequences| sequences.0.iter().map(|(&id, sequence)| (id, sequence.generation)).collect());
+		// A sequence that's been stopped is cleaned up after, and nothing more of it is run.
+		let stopped = self.runners.keys().filter(|id| !live.iter().any(|(live, _)| live == *id)).copied();
+		for id in stopped.collect::<Vec<_>>() {
+			if let Some(runner) = self.runners.remove(&id) {
+				runner.release_copy_buffers(world);
+			}
+		}
+		for &(id, generation) in live.iter() {
+			// If the sequence has been started again, or started over from a task it had finished, clean up after the
+			// old run and start over with the new one.
+			if let Some(runner) = self.runners.get(&id).filter(|runner| runner.sequence.generation != generation) {
+				runner.release_copy_buffers(world);
+				self.runners.remove(&id);
+			}
+			self.runners.entry(id).or_insert_with(|| SequenceRunner::new(&world.resource::<ComputeSequences>().0[&id]));
+		}
+		let running = |generation| live.iter().any(|(_, live)| *live == generation);
+		world.resource::<ComputeEmptyChecker>().retain(running);
+		world.resource::<ComputeBudgetTimer>().retain(running);
+		for runner in self.runners.values_mut() {
+			runner.update(world);
+		}
+	}
+
+	fn run(
+		&self, graph: &mut RenderGraphContext, context: &mut RenderContext, world: &World,
+	) -> Result<(), NodeRunError> {
+		for runner in self.runners.values() {
+			runner.run(graph, context, world)?;
+		}
+		Ok(())
+	}
+}
+
+impl SequenceRunner {
+	#[allow(clippy::type_complexity)]
+	fn update(&mut self, world: &mut World) {
+		// With an empty sequence there's nothing to do, so don't touch any other resources.
+		if self.sequence.tasks.is_empty() {
+			return;
+		}
+
 		// All the tasks have been completed, so there's nothing to do.
 		if self.current_task >= self.sequence.tasks.len() {
 			return;
 		}
+		self.frames += 1;
+		let total_iterations = self.total_iterations(world);
+		let stopped = self.latest(world).stopped.get(self.current_task).copied().unwrap_or(false);
+		let finite_checks = world.get_resource::<ComputeFiniteChecks>().is_some_and(|checks| checks.0);
+		let reads_pending = world.resource::<ComputeReadbacks>().pending(self.sequence.generation);
+		let bind_groups_ready = world.contains_resource::<ComputeBindGroups>();
+		let capabilities = world.get_resource::<ComputeCapabilities>().cloned();
+		let step_flags = world.get_resource::<ComputeStepFlags>().cloned();
 
 		let mut system_state: SystemState<(
-			ResMut<ShaderBufferSet>,
+			Res<ShaderBufferSet>,
 			ResMut<ShaderBufferRenderSet>,
 			Res<RenderDevice>,
 			Res<RenderQueue>,
-			Res<ComputeSequence>,
+			Res<ComputeSequences>,
 			ResMut<PipelineCache>,
 			Res<AssetServer>,
+			Option<Res<PreparedShaders>>,
+			Option<Res<Time>>,
+			Option<Res<FrameCount>>,
+			Query<&ExtractedView, With<ComputeCamera>>,
+			Res<ComputeBudgetTimer>,
+			ResMut<ComputePipelineIds>,
 		)> = SystemState::new(world);
-		let (mut buffers, mut render_buffers, device, render_queue, sequence, mut pipeline_cache, asset_server) =
-			system_state.get_mut(world);
+		let (
+			buffers,
+			mut render_buffers,
+			device,
+			render_queue,
+			sequences,
+			mut pipeline_cache,
+			asset_server,
+			prepared_shaders,
+			time,
+			frame_count,
+			cameras,
+			budget_timer,
+			mut pipeline_ids,
+		) = system_state.get_mut(world);
+		let sequence = &sequences.0[&self.sequence.id];
 
 		let group = &self.sequence.tasks[self.current_task];
 
-		// If there's a maximum number of iterations, check if it's been reached.
-		// If it has, clean up after this task and move on to the next.
-		// This is an assignment, as it has to update the extracted group if the
-		// group is complete.
-		let group = if let Some(max_iterations) = group.iterations {
-			if self.iterations >= max_iterations.get() {
-				for step in self.step_states.iter() {
-					if let ComputeAction::CopyBuffer { src } = step.step.action {
-						render_buffers.remove_copy_buffer(src);
-					}
-				}
-				let now = Instant::now();
-				self.current_task += 1;
-				self.current_pipelines_loaded = false;
-				self.step_states.clear();
-				self.iterations = 0;
-				self
-					.sequence
-					.sender
-					.send(ComputeMessage::GroupDone(ComputeTaskDoneEvent {
-						group_finished: self.current_task - 1,
-						group_finished_label: group.label.clone(),
-						time_in_group: now - self.group_start_time,
-						final_group: self.current_task == self.sequence.tasks.len(),
-					}))
-					.unwrap();
-				self.group_start_time = now;
-				// All the tasks have been completed, so there's nothing to do.
-				if self.current_task >= self.sequence.tasks.len() {
-					return;
+		// If there's a maximum number of iterations, check if it's been reached, or
+		// if the task has been stopped early from the main world. If either, clean up
+		// after this task and move on to the next. This is an assignment, as it has to
+		// update the extracted group if the group is complete. A task that runs on demand
+		// only finishes when it's stopped.
+		let finished = group.run_mode == TaskRunMode::Continuous
+			&& total_iterations.is_some_and(|total| self.iterations >= total);
+		let group = if stopped || finished {
+			// When readbacks don't wait for the GPU, as on the web, the last readback
+			// may not have arrived yet. The task isn't finished until it has.
+			// Likewise, the iterations of a task that's submitted separately may still be running on the GPU,
+			// and so may the frames the last iterations of any other task were submitted with, which have all
+			// been submitted by now.
+			self.draining = self.step_states.iter().any(
+				|step| {
+					matches!(
+						step.step.action,
+						ComputeAction::CopyBuffer { src, .. } if render_buffers.readback_pending(self.sequence.id, src)
+					)
+				},
+			) || reads_pending
+				|| self.done_iterations() < self.iterations
+				|| !submitted_work_done(&mut self.gpu_done, &render_queue);
+			if self.draining {
+				if group.submission == TaskSubmission::Separate {
+					send_message(
+						&self.sequence.sender,
+						ComputeMessage::Progress {
+							task: self.current_task,
+							iterations: self.done_iterations(),
+							total_iterations,
+							pending: false,
+							seed: iteration_seed(self.sequence.seed, self.current_task, self.done_iterations()),
+						},
+					);
 				}
-				&self.sequence.tasks[self.current_task]
-			} else {
-				group
+				return;
 			}
+			for step in self.step_states.iter() {
+				if let ComputeAction::CopyBuffer { src, .. } = step.step.action {
+					render_buffers.remove_copy_buffer(self.sequence.id, src, &buffers);
+				}
+			}
+			// Replayed writes recorded after the task's last iteration are made before the next task's first.
+			if self.main() {
+				buffers.tracer().finish_task(self.current_task, &buffers, &render_queue);
+			}
+			let now = Instant::now();
+			let iterations = self.iterations;
+			self.current_task += 1;
+			self.previous_iterations += iterations;
+			self.current_pipelines_loaded = false;
+			self.step_states.clear();
+			self.iterations = 0;
+			self.frame_iterations = 1;
+			// Callbacks from the old task's submissions that haven't run yet mustn't count towards the new one.
+			self.completed = Arc::default();
+			self.gpu_done = None;
+			send_message(
+				&self.sequence.sender,
+				ComputeMessage::GroupDone(ComputeTaskDoneEvent {
+					sequence: self.sequence.id,
+					group_finished: self.current_task - 1,
+					group_finished_label: group.label.clone(),
+					time_in_group: now - self.group_start_time,
+					iterations,
+					final_group: self.current_task == self.sequence.tasks.len(),
+				}),
+			);
+			self.group_start_time = now;
+			// All the tasks have been completed, so there's nothing to do.
+			if self.current_task >= self.sequence.tasks.len() {
+				return;
+			}
+			&self.sequence.tasks[self.current_task]
 		} else {
 			group
 		};
 
+		// A texture added from an image that's still loading doesn't have a format yet, so neither does the layout
+		// of its group, and nothing is made or run against it until it has.
+		if buffers.textures_loading() {
+			self.uploading = true;
+			return;
+		}
+
+		// If the layout of any group the sequence uses has changed since the pipelines were made, such as
+		// when a texture array changes size, or a buffer is added or deleted mid-run, the
+		// pipelines are remade against the new layouts, as the old ones can't be used with
+		// the new bind groups.
+		let groups = groups_used(sequence, prepared_shaders.as_deref());
+		let groups = groups.as_ref();
+		let layouts = sequence_layouts(&mut render_buffers, &buffers, &device, false, groups);
+		let layout_ids = layouts.iter().map(BindGroupLayout::id).collect::<Vec<_>>();
+		if !self.step_states.is_empty() && layout_ids != self.layout_ids {
+			for step in self.step_states.iter_mut() {
+				// A shader swap in progress is started over against the new layouts.
+				step.swap = None;
+				let per_entity = matches!(step.step.action, ComputeAction::RunShaderPerEntity { .. });
+				let ids = step.id.iter_mut().chain(step.passes.iter_mut().map(|(id, _)| id));
+				for id in ids {
+					let mut descriptor = pipeline_cache.get_compute_pipeline_descriptor(*id).clone();
+					descriptor.layout = sequence_layouts(&mut render_buffers, &buffers, &device, per_entity, groups);
+					*id = pipeline_ids.queue(&pipeline_cache, descriptor);
+				}
+			}
+			self.current_pipelines_loaded = false;
+			pipeline_cache.process_queue();
+		}
+		self.layout_ids = layout_ids;
+		self.layouts = layouts;
+		self.unused_group = Some(render_buffers.unused_group(&device));
+
 		// If step_states is empty, this must be the first iteration on a new group,
 		// so it's time to initialize the step_states, which includes setting up all
 		// the pipelines in the PipelineCache.
-		if self.step_states.len() == 0 {
-			for step in group.steps.iter() {
-				if let ComputeAction::CopyBuffer { src } = step.action {
-					render_buffers.create_copy_buffer(src, &buffers, &device);
-				}
-				let id = if let ComputeAction::RunShader { shader, entry_point, .. } = &step.action {
-					let bind_group_layouts = buffers.bind_group_layouts(&device);
-					let shader = asset_server.load(shader);
-					Some(pipeline_cache.queue_compute_pipeline(ComputePipelineDescriptor {
+		if self.step_states.is_empty() {
+			let prepared = group
+				.steps
+				.iter()
+				.map(|step| prepare_step(step, &asset_server, prepared_shaders.as_deref(), &buffers))
+				.collect::<Option<Vec<_>>>();
+			let Some(prepared) = prepared else {
+				return;
+			};
+			for (index, (step, prepared)) in group.steps.iter().zip(prepared).enumerate() {
+				if let Err(error) = step.schedule.check() {
+					panic!("{}", error);
+				}
+				if let ComputeAction::CopyBuffer { src, offset, size } = step.action {
+					if let Err(error) =
+						render_buffers.create_copy_buffer_range(self.sequence.id, src, offset, size, &buffers, &device)
+					{
+						panic!("{}", error);
+					}
+				}
+				if let ComputeAction::ReadBuffer { buffer } = step.action {
+					if let Err(error) = buffers.check_copy_source(buffer) {
+						panic!("{}", error);
+					}
+				}
+				if let ComputeAction::ClearBuffer { buffer, offset, size, .. } = step.action {
+					if let Err(error) = check_clear_buffer(&buffers, buffer, offset, size) {
+						panic!("{}", error);
+					}
+				}
+				if let ComputeAction::CopyBufferToBuffer { src, dst, src_offset, dst_offset, size } = step.action {
+					if let Err(error) = buffers.check_buffer_copy(src, dst, src_offset, dst_offset, size) {
+						panic!("{}", error);
+					}
+				}
+				let texture_copy = match step.action {
+					ComputeAction::CopyTextureToBuffer { src, dst } => Some(buffers.check_texture_copy(src, dst, false)),
+					ComputeAction::CopyBufferToTexture { src, dst } => Some(buffers.check_texture_copy(dst, src, true)),
+					_ => None,
+				};
+				if let Some(Err(error)) = texture_copy {
+					panic!("{}", error);
+				}
+				if let ComputeAction::RunShaderIndirect { indirect_buffer, offset, .. } = step.action {
+					if let Err(error) = buffers.check_indirect_source(indirect_buffer, offset) {
+						panic!("{}", error);
+					}
+				}
+				if let ComputeAction::RotateBuffers { buffer } = step.action {
+					if let Err(error) = buffers.check_rotatable(buffer) {
+						panic!("{}", error);
+					}
+				}
+				if let ComputeAction::RunShader {
+					shader, auto_swap, skip_if_empty, buffer_orientation, push_constants, ..
+				} = &step.action
+				{
+					let swappable = auto_swap.iter().map(|buffer| buffers.check_swappable(*buffer));
+					let counter = skip_if_empty.map(|counter| check_empty_source(&buffers, counter));
+					let orientable = buffer_orientation.iter().map(|(buffer, _)| buffers.check_orientable(*buffer));
+					let pushable = push_constants.iter().map(|push| push.check(shader, capabilities.as_ref()));
+					if let Some(Err(error)) = swappable.chain(counter).chain(orientable).chain(pushable).find(Result::is_err) {
+						panic!("{}", error);
+					}
+				}
+				if let ComputeAction::RunShaderMulti { shader, passes, .. } = &step.action {
+					if let Err(error) = check_shader_passes(shader, passes) {
+						panic!("{}", error);
+					}
+				}
+				let id = if let (
+					ComputeAction::RunShader { shader, entry_point, shader_defs, .. }
+					| ComputeAction::RunShaderPerEntity { shader, entry_point, shader_defs, .. }
+					| ComputeAction::RunShaderIndirect { shader, entry_point, shader_defs, .. },
+					Some((shader_handle, _, _, _)),
+				) = (&step.action, prepared.clone())
+				{
+					let per_entity = matches!(step.action, ComputeAction::RunShaderPerEntity { .. });
+					if per_entity && buffers.per_entity_group().is_none() {
+						panic!(
+							"Tried to run {} per entity, but no per-entity buffers have been added, so their layout isn't known",
+							shader
+						);
+					}
+					let bind_group_layouts = sequence_layouts(&mut render_buffers, &buffers, &device, per_entity, groups);
+					let push_constant_ranges = match &step.action {
+						ComputeAction::RunShader { push_constants: Some(push_constants), .. } => {
+							vec![PushConstantRange { stages: ShaderStages::COMPUTE, range: 0..push_constants.size() }]
+						}
+						_ => Vec::new(),
+					};
+					Some(pipeline_ids.queue(&pipeline_cache, ComputePipelineDescriptor {
 						label: None,
 						layout: bind_group_layouts.clone(),
-						push_constant_ranges: Vec::new(),
-						shader: shader,
-						shader_defs: vec![],
+						push_constant_ranges,
+						shader: shader_handle,
+						shader_defs: shader_defs.iter().cloned().map(Into::into).collect(),
 						entry_point: Cow::Owned(entry_point.clone()),
 						zero_initialize_workgroup_memory: true,
 					}))
 				} else {
 					None
 				};
+				// The passes all share one pipeline layout and one shader module, as they're made from the same
+				// layouts, shader and shader defs.
+				let passes = match (&step.action, &prepared) {
+					(ComputeAction::RunShaderMulti { shader_defs, passes, .. }, Some((shader_handle, _, _, workgroups))) => {
+						passes
+							.iter()
+							.zip(workgroups)
+							.map(|((entry_point, _), workgroups)| {
+								let id = pipeline_ids.queue(&pipeline_cache, ComputePipelineDescriptor {
+									label: None,
+									layout: sequence_layouts(&mut render_buffers, &buffers, &device, false, groups),
+									push_constant_ranges: Vec::new(),
+									shader: shader_handle.clone(),
+									shader_defs: shader_defs.iter().cloned().map(Into::into).collect(),
+									entry_point: Cow::Owned(entry_point.clone()),
+									zero_initialize_workgroup_memory: true,
+								});
+								(id, *workgroups)
+							})
+							.collect()
+					}
+					_ => Vec::new(),
+				};
+				// Finite checks that are disabled aren't built at all, so they cost nothing.
+				let finite_result = (finite_checks && matches!(step.action, ComputeAction::CheckFinite { .. }))
+					.then(|| finite_check_result(&device));
+				let builtin = match &step.action {
+					&ComputeAction::SortBuffer { buffer, element_count, key } => {
+						vec![sort_buffer_pass(&device, &pipeline_cache, &buffers, buffer, element_count, key)]
+					}
+					&ComputeAction::ReduceBuffer { src, dst, op, element } => {
+						vec![reduce_buffer_pass(&device, &pipeline_cache, &buffers, src, dst, op, element)]
+					}
+					&ComputeAction::PrefixSum { buffer, element_count } => {
+						vec![prefix_sum_pass(&device, &pipeline_cache, &buffers, buffer, element_count)]
+					}
+					&ComputeAction::Histogram { src, bins, bin_count, range, source, out_of_range } => {
+						vec![histogram_pass(&device, &pipeline_cache, &buffers, src, bins, bin_count, range, source, out_of_range)]
+					}
+					ComputeAction::Convolve { src, dst, kernel, edges } => {
+						convolve_passes(&device, &pipeline_cache, &buffers, *src, *dst, kernel, *edges)
+					}
+					&ComputeAction::CheckFinite { buffer, element } => finite_result
+						.clone()
+						.map(|result| check_finite_pass(&device, &pipeline_cache, &buffers, buffer, element, result))
+						.into_iter()
+						.collect(),
+					_ => Vec::new(),
+				};
+				let fill = match step.action {
+					ComputeAction::ClearTexture { texture, value, .. } => Some(texture_fill(&device, &buffers, texture, value)),
+					_ => None,
+				};
+				let (workgroups, chunks) =
+					prepared.map_or((UVec3::ZERO, Vec::new()), |(_, workgroups, chunks, _)| (workgroups, chunks));
+				let chunk_offsets = chunk_offsets_buffer(&device, &buffers, &chunks);
 				self.step_states.push(ComputeStepState {
+					index,
 					step: step.clone(),
 					id,
-					last_run_time: if let Some(max_frequency) = step.max_frequency {
-						Instant::now() - Duration::from_secs_f32(2.0 / max_frequency.get() as f32)
-					} else {
-						Instant::now()
-					},
+					passes,
+					builtin,
+					finite_result,
+					fill,
+					workgroups,
+					texture_sizes: dispatch_texture_sizes(step, &buffers),
+					chunks: chunks.into_iter().map(|(_, workgroups)| workgroups).collect(),
+					chunk_offsets,
+					next_run: None,
+					frames_until_run: 0,
 					run_this_time: true,
 					copy_buffer_ready: true,
+					copy_failed: AtomicBool::new(false),
+					swap: None,
+					pipelines: HashMap::new(),
 				});
 			}
 			pipeline_cache.process_queue();
@@ -174,54 +1005,366 @@ impl Node for ComputeNode {
 		// If the pipelines have not been marked as loaded, check them.
 		// If they're loaded, mark them as such. Otherwise we can't continue yet.
 		if !self.current_pipelines_loaded {
-			let step_states = self.step_states.iter().flat_map(|step| {
-				if let Some(id) = step.id {
-					Some(pipeline_cache.get_compute_pipeline_state(id))
-				} else {
-					None
+			let mut loaded = true;
+			for step in self.step_states.iter() {
+				let shaders = step.id.into_iter().chain(step.passes.iter().map(|(id, _)| *id)).map(|id| (id, false));
+				let builtin = step.builtin.iter().flat_map(BuiltinPass::pipeline_ids).map(|id| (id, true));
+				for (id, builtin) in shaders.chain(builtin) {
+					match pipeline_cache.get_compute_pipeline_state(id) {
+						CachedPipelineState::Ok(_) => {
+							self.failed_pipelines.remove(&id);
+						}
+						// These two are retried by the pipeline cache on its next pass, once the
+						// shader has finished loading.
+						CachedPipelineState::Err(
+							PipelineCacheError::ShaderNotLoaded(_) | PipelineCacheError::ShaderImportNotYetAvailable,
+						) => loaded = false,
+						CachedPipelineState::Err(error) if builtin => panic!("{}", error),
+						// A shader that doesn't compile is reported once, and the task waits for it to be fixed,
+						// which the pipeline cache tries it again for when it's hot-reloaded.
+						CachedPipelineState::Err(error) => {
+							loaded = false;
+							if !self.failed_pipelines.insert(id) {
+								continue;
+							}
+							let shader = step_shader_path(&step.step).unwrap();
+							let entry_point = pipeline_cache.get_compute_pipeline_descriptor(id).entry_point.clone();
+							let label = self.sequence.tasks[self.current_task].label.clone();
+							error!(
+								"Couldn't make the pipeline for {} in {}, for step {} of compute task {}, so the task is waiting for it to be fixed: {}",
+								entry_point, shader, step.index, self.current_task, error
+							);
+							send_message(
+								&self.sequence.sender,
+								ComputeMessage::Error(ComputeErrorEvent::PipelineFailed {
+									sequence: self.sequence.id,
+									task: self.current_task,
+									label,
+									step: step.index,
+									shader: shader.to_owned(),
+									entry_point: entry_point.into_owned(),
+									error: error.to_string(),
+								}),
+							);
+						}
+						_ => {
+							loaded = false;
+							self.failed_pipelines.remove(&id);
+						}
+					}
 				}
-			});
-			let state = step_states.fold(Some(Ok(())), |acc, x| match (acc, x) {
-				(None, _) => None,
-				(Some(Err(e)), _) => Some(Err(e)),
-				(Some(Ok(_)), CachedPipelineState::Ok(_)) => Some(Ok(())),
-				(Some(Ok(_)), CachedPipelineState::Err(e)) => Some(Err(e)),
-				(Some(Ok(_)), _) => None,
-			});
-			self.current_pipelines_loaded = match state {
-				Some(Ok(_)) => true,
-				Some(Err(e)) => panic!("{}", e),
-				None => false,
 			}
+			self.current_pipelines_loaded = loaded;
+		}
+
+		// Bevy's pipeline cache throws away the pipelines of a shader when it's hot-reloaded, and has none until the new
+		// ones are made, so each step keeps the last pipelines that worked, and runs them until then, or for as long
+		// as the new ones fail.
+		if self.current_pipelines_loaded {
+			for step in self.step_states.iter_mut() {
+				for id in step.id.into_iter().chain(step.passes.iter().map(|(id, _)| *id)) {
+					match pipeline_cache.get_compute_pipeline_state(id) {
+						CachedPipelineState::Ok(Pipeline::ComputePipeline(pipeline)) => {
+							step.pipelines.insert(id, (pipeline.clone(), false));
+						}
+						CachedPipelineState::Err(
+							PipelineCacheError::ShaderNotLoaded(_) | PipelineCacheError::ShaderImportNotYetAvailable,
+						) => {}
+						CachedPipelineState::Err(error) => {
+							let Some((_, reported)) = step.pipelines.get_mut(&id).filter(|(_, reported)| !reported) else {
+								continue;
+							};
+							*reported = true;
+							let shader = step_shader_path(&step.step).unwrap();
+							let entry_point = pipeline_cache.get_compute_pipeline_descriptor(id).entry_point.clone();
+							let label = self.sequence.tasks[self.current_task].label.clone();
+							error!(
+								"Couldn't rebuild {} in {} for step {} of compute task {} after it was reloaded, so it carries on with its old pipeline: {}",
+								entry_point, shader, step.index, self.current_task, error
+							);
+							send_message(
+								&self.sequence.sender,
+								ComputeMessage::Error(ComputeErrorEvent::ShaderReloadFailed {
+									sequence: self.sequence.id,
+									task: self.current_task,
+									label,
+									step: step.index,
+									shader: shader.to_owned(),
+									entry_point: entry_point.into_owned(),
+									error: error.to_string(),
+								}),
+							);
+						}
+						_ => {}
+					}
+				}
+			}
+		}
+
+		// A step whose shader has been swapped in the main world keeps running its old pipeline until the new one is
+		// ready, and if the new one fails, it carries on with the old one.
+		for step in self.step_states.iter_mut() {
+			let Some(target) = sequence.tasks[self.current_task].steps.get(step.index) else {
+				continue;
+			};
+			let Some(id) = step.id.filter(|_| step_shader(target) != step_shader(&step.step)) else {
+				step.swap = None;
+				continue;
+			};
+			if step.swap.as_ref().is_some_and(|swap| step_shader(&swap.step) != step_shader(target)) {
+				step.swap = None;
+			}
+			let Some(swap) = step.swap.as_mut() else {
+				let Some(Some((shader, workgroups, chunks, _))) =
+					prepare_step(target, &asset_server, prepared_shaders.as_deref(), &buffers)
+				else {
+					continue;
+				};
+				let mut descriptor = pipeline_cache.get_compute_pipeline_descriptor(id).clone();
+				descriptor.shader = shader;
+				descriptor.entry_point = Cow::Owned(step_shader(target).unwrap().1.to_owned());
+				let id = pipeline_ids.queue(&pipeline_cache, descriptor);
+				step.swap = Some(ShaderSwap { step: target.clone(), id, workgroups, chunks, failed: false });
+				pipeline_cache.process_queue();
+				continue;
+			};
+			match pipeline_cache.get_compute_pipeline_state(swap.id) {
+				CachedPipelineState::Ok(_) => {
+					let swap = step.swap.take().unwrap();
+					step.chunk_offsets = chunk_offsets_buffer(&device, &buffers, &swap.chunks);
+					step.chunks = swap.chunks.into_iter().map(|(_, workgroups)| workgroups).collect();
+					step.workgroups = swap.workgroups;
+					step.id = Some(swap.id);
+					step.step = swap.step;
+				}
+				CachedPipelineState::Err(
+					PipelineCacheError::ShaderNotLoaded(_) | PipelineCacheError::ShaderImportNotYetAvailable,
+				) => {}
+				CachedPipelineState::Err(_) if swap.failed => {}
+				CachedPipelineState::Err(error) => {
+					swap.failed = true;
+					let (shader, entry_point) = step_shader(&swap.step).unwrap();
+					let label = self.sequence.tasks[self.current_task].label.clone();
+					error!(
+						"Couldn't swap step {} of compute task {} over to {} in {}, so it carries on with its old shader: {}",
+						step.index, self.current_task, entry_point, shader, error
+					);
+					send_message(
+						&self.sequence.sender,
+						ComputeMessage::Error(ComputeErrorEvent::ShaderSwapFailed {
+							sequence: self.sequence.id,
+							task: self.current_task,
+							label,
+							step: step.index,
+							shader: shader.to_owned(),
+							entry_point: entry_point.to_owned(),
+							error: error.to_string(),
+						}),
+					);
+				}
+				_ => {}
+			}
+		}
+
+		// A step whose dispatch size has been changed in the main world works out its workgroup counts again, once it's
+		// running the same shader as the main world has, as a swap works them out for the new shader itself.
+		for step in self.step_states.iter_mut() {
+			let Some(target) = sequence.tasks[self.current_task].steps.get(step.index) else {
+				continue;
+			};
+			let Some(dispatch) = target.action.dispatch() else {
+				continue;
+			};
+			if step.step.action.dispatch() == Some(dispatch) || step_shader(target) != step_shader(&step.step) {
+				continue;
+			}
+			let mut updated = step.step.clone();
+			*updated.action.dispatch_mut().unwrap() = dispatch;
+			let Some(Some((_, workgroups, chunks, _))) =
+				prepare_step(&updated, &asset_server, prepared_shaders.as_deref(), &buffers)
+			else {
+				continue;
+			};
+			step.chunk_offsets = chunk_offsets_buffer(&device, &buffers, &chunks);
+			step.chunks = chunks.into_iter().map(|(_, workgroups)| workgroups).collect();
+			step.workgroups = workgroups;
+			step.step = updated;
+		}
+
+		// A step dispatched over a texture works out its workgroup counts again once the texture has been resized, or
+		// has finished loading.
+		for step in self.step_states.iter_mut() {
+			let texture_sizes = dispatch_texture_sizes(&step.step, &buffers);
+			if texture_sizes == step.texture_sizes {
+				continue;
+			}
+			let Some(Some((_, workgroups, _, pass_workgroups))) =
+				prepare_step(&step.step, &asset_server, prepared_shaders.as_deref(), &buffers)
+			else {
+				continue;
+			};
+			step.workgroups = workgroups;
+			for ((_, workgroups), updated) in step.passes.iter_mut().zip(pass_workgroups) {
+				*workgroups = updated;
+			}
+			step.texture_sizes = texture_sizes;
+		}
+
+		// A buffer that's been resized is read back through a copy buffer of its new size.
+		for step in self.step_states.iter() {
+			if let ComputeAction::CopyBuffer { src, .. } = step.step.action {
+				render_buffers.fit_copy_buffer(self.sequence.id, src, &buffers, &device);
+			}
+		}
+
+		// A paused task, or a task of a paused sequence, keeps everything as it is until it's resumed.
+		self.paused = sequence.all_paused || sequence.paused.get(self.current_task).copied().unwrap_or(false);
+		if self.paused {
+			return;
+		}
+
+		// A task with a start delay doesn't run at all until it's up, and its time in the task is counted from then.
+		self.pending = group.start_after.is_some_and(|delay| !delay.elapsed(self.frames, self.started_at.elapsed()));
+		if self.pending {
+			self.group_start_time = Instant::now();
+			send_message(
+				&self.sequence.sender,
+				ComputeMessage::Progress {
+					task: self.current_task,
+					iterations: 0,
+					total_iterations,
+					pending: true,
+					seed: iteration_seed(self.sequence.seed, self.current_task, 0),
+				},
+			);
+			return;
+		}
+
+		// Nothing is dispatched until every texture is on the GPU with its initial contents, which is when
+		// its bind groups are made. Bevy can spread image uploads over several frames.
+		self.uploading = !bind_groups_ready;
+		if self.uploading {
+			return;
+		}
+
+		// A task that runs on demand sits idle between triggers, keeping everything as it is.
+		self.idle = group.run_mode == TaskRunMode::OnDemand
+			&& total_iterations.is_some_and(|total| self.iterations >= total);
+		if self.idle {
+			return;
 		}
 
 		// If the pipelines are actually loaded now, then:
-		// - update the iteration buffer, if there is one
+		// - update the iteration buffer and the buffers of globals, if there are any
 		// - for every step:
 		//   - if it has a frequency limit, check if it should run this frame
 		//   - if it's a buffer copy, alternate whether it copies into or out of the
 		//     copy buffer
+		self.throttled = false;
 		if self.current_pipelines_loaded {
+			// A task submitted separately doesn't record any more iterations while two frames' worth are still
+			// running on the GPU.
+			if group.submission == TaskSubmission::Separate {
+				self.throttled = self.iterations - self.done_iterations() >= 2 * self.frame_iterations;
+				if self.throttled {
+					send_message(
+						&self.sequence.sender,
+						ComputeMessage::Progress {
+							task: self.current_task,
+							iterations: self.done_iterations(),
+							total_iterations,
+							pending: false,
+							seed: iteration_seed(self.sequence.seed, self.current_task, self.done_iterations()),
+						},
+					);
+					return;
+				}
+			}
 			if let Some(buffer) = sequence.iteration_buffer {
-				buffers.set_buffer(buffer, self.iterations, &render_queue);
+				buffers.set_owned_buffer(buffer, self.iterations, &render_queue);
+			}
+			match group.iteration_buffer {
+				Some(TaskIterationBuffer::Local(buffer)) => buffers.set_owned_buffer(buffer, self.iterations, &render_queue),
+				Some(TaskIterationBuffer::Counts(buffer)) => {
+					buffers.set_owned_buffer(buffer, self.iteration_counts(self.iterations), &render_queue)
+				}
+				None => {}
+			}
+			if let Some(buffer) = buffers.globals() {
+				let seed = iteration_seed(self.sequence.seed, self.current_task, self.iterations);
+				let globals = ComputeGlobals::new(time.as_deref(), frame_count.as_deref(), seed, buffers.ring_positions());
+				buffers.set_owned_buffer(buffer, globals, &render_queue);
 			}
-			self.iterations += 1;
+			if let Some(cursor) = buffers.cursor_uniform() {
+				buffers.set_owned_buffer(cursor.handle, cursor.value, &render_queue);
+			}
+			if let Some(buffer) = buffers.camera_uniform() {
+				match cameras.get_single() {
+					Ok(view) => buffers.set_owned_buffer(buffer, ComputeCameraMatrices::new(view), &render_queue),
+					Err(QuerySingleError::MultipleEntities(_)) => {
+						warn!("More than one camera is marked with ComputeCamera, so the camera uniform isn't being written")
+					}
+					Err(QuerySingleError::NoEntities(_)) => {}
+				}
+			}
+			// A task with a frame time budget runs as many iterations as are expected to fit in it,
+			// but no more than twice as many as last frame, and no more than it has left to run.
+			self.frame_iterations = match group.budget {
+				Some(budget) => {
+					let task = (self.sequence.generation, self.current_task);
+					let per_iteration = budget_timer.per_iteration(task).unwrap_or(budget.estimate);
+					let fit = if per_iteration.is_zero() {
+						u32::MAX
+					} else {
+						(budget.time.as_secs_f64() / per_iteration.as_secs_f64()) as u32
+					};
+					fit.clamp(1, self.frame_iterations.saturating_mul(2))
+				}
+				None => 1,
+			};
+			// No more iterations are run in a frame than the task has left, so a triggered task runs just the
+			// iterations it was triggered for.
+			let remaining = total_iterations.map(|total| total - self.iterations);
+			if let Some(remaining) = remaining {
+				self.frame_iterations = self.frame_iterations.min(remaining);
+			}
+			if self.main() {
+				self.frame_iterations = buffers.tracer().frame_iterations(self.current_task, self.frame_iterations, remaining);
+			}
+			self.iterations += self.frame_iterations;
+			if self.main() {
+				buffers.tracer().set_cursor(self.current_task, self.iterations);
+			}
+			send_message(
+				&self.sequence.sender,
+				ComputeMessage::Progress {
+					task: self.current_task,
+					iterations: self.done_iterations(),
+					total_iterations,
+					pending: false,
+					seed: iteration_seed(self.sequence.seed, self.current_task, self.done_iterations()),
+				},
+			);
 
+			let now = time.as_deref().map_or_else(|| self.started_at.elapsed(), Time::elapsed);
+			self.fired_flags.retain(|flag| step_flags.as_ref().is_some_and(|flags| flags.get(flag)));
+			let mut fired = Vec::new();
 			for step in self.step_states.iter_mut() {
-				step.run_this_time = if let Some(max_frequency) = step.step.max_frequency {
-					let now = Instant::now();
-					if now - step.last_run_time > Duration::from_secs_f32(1.0 / max_frequency.get() as f32) {
-						step.last_run_time = now;
-						true
-					} else {
-						false
-					}
-				} else {
-					true
-				};
+				// A step whose condition isn't met is skipped without moving its schedule on.
+				step.run_this_time = step.condition_met(step_flags.as_ref(), &self.fired_flags) && step.scheduled(now);
 
 				if step.run_this_time {
-					step.copy_buffer_ready = !step.copy_buffer_ready;
+					// A copy that failed left nothing to read back, so it's tried again instead.
+					let failed = std::mem::take(step.copy_failed.get_mut());
+					step.copy_buffer_ready = !step.copy_buffer_ready && !failed;
+				}
+				if let (true, Some(StepCondition::OneShotFlag(flag))) = (step.run_this_time, &step.step.run_if) {
+					fired.push(flag.clone());
+				}
+			}
+			for flag in fired {
+				if self.fired_flags.insert(flag.clone()) {
+					send_message(&self.sequence.sender, ComputeMessage::ClearStepFlag(flag));
 				}
 			}
 		}
@@ -244,34 +1387,519 @@ impl Node for ComputeNode {
 		let device = world.resource::<RenderDevice>();
 		let buffers = world.resource::<ShaderBufferSet>();
 		let render_buffers = world.resource::<ShaderBufferRenderSet>();
+		let start = Instant::now();
 
-		// Iterate over all the steps and run them.
-		for step in self.step_states.iter() {
-			if !step.run_this_time {
-				continue;
-			}
+		let wait = world.resource::<ComputePolling>().waits();
 
-			match step.step.action {
-				ComputeAction::CopyBuffer { src } => {
-					if step.copy_buffer_ready {
-						let data = render_buffers.copy_from_copy_buffer_to_vec(src, device);
-						self.sequence.sender.send(ComputeMessage::CopyBuffer(CopyBufferEvent { buffer: src, data })).unwrap();
-					} else {
-						render_buffers.copy_to_copy_buffer(src, buffers, context);
+		// The task has run all its iterations, and is only waiting for readbacks to arrive.
+		if self.draining {
+			for step in self.step_states.iter() {
+				if let ComputeAction::CopyBuffer { src, .. } = step.step.action {
+					match render_buffers.read_copy_buffer_polled(self.sequence.id, src, buffers, device, wait) {
+						Ok(Some(data)) => self.send_readback(src, data),
+						Ok(None) => {}
+						Err(error) => error_once!("{}", error),
 					}
 				}
-				ComputeAction::RunShader { x_workgroup_count, y_workgroup_count, z_workgroup_count, .. } => {
-					if let Some(id) = step.id {
-						self.run_shader(id, x_workgroup_count, y_workgroup_count, z_workgroup_count, world, context);
-					} else {
-						panic!("Somehow got to trying to run a RunShader action step with no pipeline ID");
+			}
+			return Ok(());
+		}
+
+		if self.paused || self.throttled || self.pending || self.uploading || self.idle {
+			return Ok(());
+		}
+
+		let task = &self.sequence.tasks[self.current_task];
+		#[cfg(feature = "trace")]
+		let _span = info_span!("compute_task", name = %task_name(self.current_task, task.label.as_deref())).entered();
+
+		// A task submitted separately is recorded into a render context of its own, which is submitted after
+		// every iteration, instead of the render graph's.
+		let separate = task.submission == TaskSubmission::Separate;
+		let adapter_info = world.resource::<RenderAdapterInfo>();
+		let render_queue = world.resource::<RenderQueue>();
+		let mut own_context = separate.then(|| RenderContext::new(device.clone(), (***adapter_info).clone(), None));
+		let context = match own_context.as_mut() {
+			Some(own_context) => own_context,
+			None => context,
+		};
+
+		let error_context = world.resource::<ComputeErrorContext>();
+		error_context.begin(self.current_task, task.label.clone());
+		// Tasks with a budget are timed to work out how many iterations fit in it, and all of them are when
+		// diagnostics are on, to report their GPU time.
+		let diagnostics = world.contains_resource::<ComputeDiagnosticsEnabled>();
+		let timed = (task.budget.is_some() || diagnostics) && ComputeBudgetTimer::supported(device);
+		if timed {
+			world.resource::<ComputeBudgetTimer>().begin(device, context.command_encoder());
+		}
+
+		// When more than one iteration runs this frame, the iteration buffers are written for the first
+		// with the rest of the buffer writes, before the frame's commands, and copied into from a buffer
+		// of the counts before each of the others, so each sees its own count.
+		let first_iteration = self.iterations - self.frame_iterations;
+		let mut iteration_counts = Vec::new();
+		if self.frame_iterations > 1 {
+			let later = first_iteration + 1..self.iterations;
+			if let Some(buffer) = self.sequence.iteration_buffer.and_then(|buffer| buffers.gpu_buffer(buffer)) {
+				iteration_counts.push((buffer, 4, later.clone().collect::<Vec<_>>()));
+			}
+			if let Some(iteration_buffer) = task.iteration_buffer {
+				if let Some(buffer) = buffers.gpu_buffer(*iteration_buffer.buffer()) {
+					let counts = later
+						.flat_map(|iteration| match iteration_buffer {
+							TaskIterationBuffer::Local(_) => vec![iteration],
+							TaskIterationBuffer::Counts(_) => {
+								let counts = self.iteration_counts(iteration);
+								vec![counts.local, counts.global]
+							}
+						})
+						.collect();
+					iteration_counts.push((buffer, iteration_buffer.size(), counts));
+				}
+			}
+		}
+		let iteration_counts = iteration_counts
+			.into_iter()
+			.map(|(buffer, size, counts)| {
+				let counts = device.create_buffer_with_data(&BufferInitDescriptor {
+					label: None,
+					contents: bytemuck::cast_slice(&counts),
+					usage: BufferUsages::COPY_SRC,
+				});
+				(buffer, size, counts)
+			})
+			.collect::<Vec<_>>();
+		// The seed in the globals buffer is copied in the same way.
+		let iteration_seeds = match buffers.globals() {
+			Some(buffer) if self.frame_iterations > 1 => buffers.gpu_buffer(buffer).map(|buffer| {
+				let seeds = (first_iteration + 1..self.iterations)
+					.map(|iteration| iteration_seed(self.sequence.seed, self.current_task, iteration))
+					.collect::<Vec<_>>();
+				let seeds = device.create_buffer_with_data(&BufferInitDescriptor {
+					label: None,
+					contents: bytemuck::cast_slice(&seeds),
+					usage: BufferUsages::COPY_SRC,
+				});
+				(buffer, seeds)
+			}),
+			_ => None,
+		};
+
+		// Double buffers swapped in one iteration are swapped for the next, with bind groups made from
+		// a copy of the buffer set with the swaps applied. The swaps themselves are made in the main
+		// world, once the frame is done, so the bindings are swapped from the next frame on.
+		let mut swapped: Option<(ShaderBufferSet, Vec<BindGroup>)> = None;
+		let mut frame_swaps = Vec::new();
+		// Steps that bind double buffers the other way round get bind groups made the same way, with those
+		// buffers swapped, for each set of them, which are made again once an iteration's swaps change them.
+		let mut swap_rounds = 0;
+		// Rings rotated in one iteration are rotated in that copy too, but the shaders see their new positions
+		// straight away, copied into the globals buffer at the rotation, as none of their bindings change.
+		let mut frame_rotations = Vec::new();
+		let mut ring_positions = buffers.ring_positions();
+		let globals_buffer = buffers.globals().and_then(|buffer| buffers.gpu_buffer(buffer));
+		let mut oriented: HashMap<(u32, Vec<ShaderBufferHandle>), Vec<BindGroup>> = HashMap::new();
+		let mut oriented_bind_groups =
+			|iteration_buffers: &ShaderBufferSet, swap_rounds: u32, reversed: Vec<ShaderBufferHandle>| {
+				oriented
+					.entry((swap_rounds, reversed))
+					.or_insert_with_key(|(_, reversed)| {
+						let mut oriented_buffers = iteration_buffers.clone();
+						for buffer in reversed {
+							oriented_buffers.swap_front_buffer(*buffer);
+						}
+						// The textures were all uploaded when this frame's bind groups were made.
+						render_buffers
+							.bind_groups(
+								&oriented_buffers,
+								device,
+								world.resource::<RenderAssets<GpuImage>>(),
+								world.resource::<FallbackImage>(),
+								&self.layouts,
+							)
+							.unwrap_or_else(|error| panic!("{}", error))
+					})
+					.clone()
+			};
+
+		// Shader steps are recorded into one compute pass, across steps and iterations, until something has
+		// to be recorded into the command encoder itself, such as a copy or a builtin pass, which ends it.
+		// The next shader step starts a new one. The frame's timestamps are written outside of any pass.
+		// With the ComputeProfilingPlugin, every dispatch is timed in a pass of its own instead.
+		let mut pass: Option<ShaderPass> = None;
+		let pass_label = task.label.clone();
+		let step_timer = world.get_resource::<ComputeStepTimer>();
+		let timed_task = step_timer.map(|_| task_name(self.current_task, task.label.as_deref()));
+		let time_dispatch = |entry_point: &str| {
+			let timer = step_timer?;
+			timer.time_dispatch(device, timed_task.as_deref().unwrap(), entry_point)
+		};
+		let empty_checker = world.resource::<ComputeEmptyChecker>();
+		let readbacks = world.resource::<ComputeReadbacks>();
+		for iteration in 0..self.frame_iterations {
+			let last = iteration + 1 == self.frame_iterations;
+			for (buffer, size, counts) in iteration_counts.iter().filter(|_| iteration > 0) {
+				pass = None;
+				context.command_encoder().copy_buffer_to_buffer(counts, (iteration as u64 - 1) * size, buffer, 0, *size);
+			}
+			if let (Some((buffer, seeds)), true) = (&iteration_seeds, iteration > 0) {
+				pass = None;
+				context.command_encoder().copy_buffer_to_buffer(
+					seeds,
+					(iteration as u64 - 1) * 4,
+					buffer,
+					ComputeGlobals::SEED_OFFSET,
+					4,
+				);
+			}
+			let (iteration_buffers, bind_groups) = match &swapped {
+				Some((buffers, bind_groups)) => (buffers, bind_groups.as_slice()),
+				None => (buffers, world.resource::<ComputeBindGroups>().0.as_slice()),
+			};
+			let mut swaps = Vec::new();
+			let mut rotations = Vec::new();
+			if self.main() && buffers.tracer().writes_due(self.current_task, first_iteration + iteration) {
+				pass = None;
+				buffers.tracer().replay_writes(
+					self.current_task,
+					first_iteration + iteration,
+					buffers,
+					device,
+					context.command_encoder(),
+				);
+			}
+
+			// Iterate over all the steps and run them. Steps that can't run more than once a frame
+			// only run in the last iteration.
+			let steps = self.step_states.iter().filter(|step| step.run_this_time && (last || step.every_iteration()));
+			for step in steps {
+				// Writes queued for after the steps before this one are made before it, even if those steps
+				// didn't run in this iteration.
+				if self.main() && buffers.step_writes().due(self.current_task, Some(step.index)) {
+					pass = None;
+					self.apply_step_writes(Some(step.index), first_iteration + iteration, buffers, device, context);
+				}
+				error_context.step(step.index);
+				match step.step.action {
+					ComputeAction::CopyBuffer { src, .. } => {
+						if step.copy_buffer_ready {
+							match render_buffers.read_copy_buffer_polled(self.sequence.id, src, buffers, device, wait) {
+								Ok(Some(data)) => self.send_readback(src, data),
+								Ok(None) => {}
+								Err(error) => error_once!("{}", error),
+							}
+						} else {
+							pass = None;
+							let gpu_images = world.resource::<RenderAssets<GpuImage>>();
+							if let Err(error) =
+								render_buffers.copy_to_copy_buffer(self.sequence.id, src, iteration_buffers, gpu_images, context)
+							{
+								error_once!("{}", error);
+								step.copy_failed.store(true, Ordering::Release);
+							}
+						}
+					}
+					ComputeAction::CopyBufferToBuffer { src, dst, src_offset, dst_offset, size } => {
+						pass = None;
+						let (Some(src_buffer), Some(dst_buffer)) =
+							(iteration_buffers.gpu_buffer(src), iteration_buffers.gpu_buffer(dst))
+						else {
+							panic!(
+								"Tried to copy {} into {}, but one of them has been deleted",
+								src.describe(iteration_buffers),
+								dst.describe(iteration_buffers)
+							);
+						};
+						let size = size.unwrap_or(src_buffer.size() - src_offset);
+						context.command_encoder().copy_buffer_to_buffer(&src_buffer, src_offset, &dst_buffer, dst_offset, size);
+					}
+					ComputeAction::CopyTextureToBuffer { src, dst } => {
+						pass = None;
+						let gpu_images = world.resource::<RenderAssets<GpuImage>>();
+						iteration_buffers.copy_texture_rows(src, dst, false, gpu_images, context.command_encoder());
+					}
+					ComputeAction::CopyBufferToTexture { src, dst } => {
+						pass = None;
+						let gpu_images = world.resource::<RenderAssets<GpuImage>>();
+						iteration_buffers.copy_texture_rows(dst, src, true, gpu_images, context.command_encoder());
+					}
+					ComputeAction::ClearBuffer { buffer, offset, size, halves } => {
+						pass = None;
+						let gpu_buffers = iteration_buffers.cleared_gpu_buffers(buffer, halves);
+						if gpu_buffers.is_empty() {
+							panic!("Tried to clear {}, but it's been deleted", buffer.describe(iteration_buffers));
+						}
+						for gpu_buffer in gpu_buffers {
+							context.command_encoder().clear_buffer(&gpu_buffer, offset, size);
+						}
+					}
+					ComputeAction::ClearTexture { texture, halves, .. } => {
+						let Some(fill) = &step.fill else {
+							panic!("Somehow got to clearing a texture without the rows to fill it from");
+						};
+						pass = None;
+						let gpu_images = world.resource::<RenderAssets<GpuImage>>();
+						fill.clear(iteration_buffers.cleared_images(texture, halves), gpu_images, context.command_encoder());
+					}
+					ComputeAction::ReadBuffer { buffer } => {
+						pass = None;
+						readbacks.record(
+							device,
+							context.command_encoder(),
+							self.sequence.id,
+							self.sequence.generation,
+							buffer,
+							iteration_buffers,
+							world.resource::<RenderAssets<GpuImage>>(),
+							task.label.clone(),
+							first_iteration + iteration + 1,
+							&self.sequence.sender,
+						);
+					}
+					ComputeAction::RunShader { skip_if_empty: Some(counter), .. }
+						if empty_checker.empty(self.sequence.generation, counter) => {}
+					ComputeAction::RunShader { .. } | ComputeAction::RunShaderPerEntity { .. }
+						if step.chunk_offsets.is_some() =>
+					{
+						// Each chunk gets a compute pass of its own, as its offset has to be copied into the
+						// chunk uniform between them. The last chunk's pass is kept for the steps after it.
+						let Some(chunk_uniform) =
+							iteration_buffers.chunk_uniform().and_then(|uniform| iteration_buffers.gpu_buffer(uniform))
+						else {
+							panic!("Tried to run a chunked dispatch, but the chunk uniform has been deleted");
+						};
+						let offsets = step.chunk_offsets.as_ref().unwrap();
+						let reversed = Self::reversed_buffers(step);
+						let oriented =
+							(!reversed.is_empty()).then(|| oriented_bind_groups(iteration_buffers, swap_rounds, reversed));
+						let bind_groups = oriented.as_deref().unwrap_or(bind_groups);
+						for chunk in step.chunks_in_iteration(first_iteration + iteration) {
+							pass = None;
+							let offset = (chunk * size_of::<[u32; 4]>()) as u64;
+							context.command_encoder().copy_buffer_to_buffer(
+								offsets,
+								offset,
+								&chunk_uniform,
+								0,
+								size_of::<[u32; 4]>() as u64,
+							);
+							let timing = step_shader(&step.step).and_then(|(_, entry_point)| time_dispatch(entry_point));
+							let pass = ShaderPass::get(&mut pass, context.command_encoder(), pass_label.as_deref(), timing);
+							let workgroups = step.chunks[chunk];
+							self.run_shader(pass, step, world, bind_groups, step.id, workgroups, first_iteration + iteration);
+						}
+						swaps.extend(Self::auto_swaps(step));
+					}
+					ComputeAction::RunShader { .. }
+					| ComputeAction::RunShaderPerEntity { .. }
+					| ComputeAction::RunShaderIndirect { .. } => {
+						let reversed = Self::reversed_buffers(step);
+						let oriented =
+							(!reversed.is_empty()).then(|| oriented_bind_groups(iteration_buffers, swap_rounds, reversed));
+						let bind_groups = oriented.as_deref().unwrap_or(bind_groups);
+						let timing = step_shader(&step.step).and_then(|(_, entry_point)| time_dispatch(entry_point));
+						let pass = ShaderPass::get(&mut pass, context.command_encoder(), pass_label.as_deref(), timing);
+						self.run_shader(pass, step, world, bind_groups, step.id, step.workgroups, first_iteration + iteration);
+						swaps.extend(Self::auto_swaps(step));
+					}
+					ComputeAction::RunShaderMulti { passes: ref entry_points, .. } => {
+						for (&(id, workgroups), (entry_point, _)) in step.passes.iter().zip(entry_points) {
+							let pass = ShaderPass::get(
+								&mut pass,
+								context.command_encoder(),
+								pass_label.as_deref(),
+								time_dispatch(entry_point),
+							);
+							self.run_shader(pass, step, world, bind_groups, Some(id), workgroups, first_iteration + iteration);
+						}
+					}
+					ComputeAction::SwapBuffers { buffer } => {
+						swaps.push(buffer);
+					}
+					ComputeAction::RotateBuffers { buffer } => {
+						let Some((slot, depth)) = buffers.ring_slot_and_depth(buffer) else {
+							panic!("Tried to rotate {}, but it's been deleted", buffer.describe(buffers));
+						};
+						ring_positions[slot as usize] = (ring_positions[slot as usize] + 1) % depth;
+						if let Some(globals) = &globals_buffer {
+							pass = None;
+							let positions = device.create_buffer_with_data(&BufferInitDescriptor {
+								label: None,
+								contents: bytemuck::cast_slice(&ring_positions.to_array()),
+								usage: BufferUsages::COPY_SRC,
+							});
+							context.command_encoder().copy_buffer_to_buffer(
+								&positions,
+								0,
+								globals,
+								ComputeGlobals::RING_POSITIONS_OFFSET,
+								size_of::<UVec4>() as u64,
+							);
+						}
+						rotations.push(buffer);
+					}
+					ComputeAction::SortBuffer { .. }
+					| ComputeAction::ReduceBuffer { .. }
+					| ComputeAction::PrefixSum { .. }
+					| ComputeAction::Histogram { .. }
+					| ComputeAction::Convolve { .. } => {
+						if step.builtin.is_empty() {
+							panic!("Somehow got to trying to run a builtin action step with no builtin pass");
+						}
+						pass = None;
+						let gpu_images = world.resource::<RenderAssets<GpuImage>>();
+						for builtin in &step.builtin {
+							builtin.run(iteration_buffers, gpu_images, world.resource::<PipelineCache>(), device, context);
+						}
+					}
+					ComputeAction::CheckFinite { buffer, .. } => {
+						let ([builtin], Some(result)) = (step.builtin.as_slice(), &step.finite_result) else {
+							continue;
+						};
+						let checker = world.resource::<ComputeFiniteChecker>();
+						if checker.found(self.sequence.generation, self.current_task, step.index) {
+							continue;
+						}
+						pass = None;
+						let gpu_images = world.resource::<RenderAssets<GpuImage>>();
+						builtin.run(iteration_buffers, gpu_images, world.resource::<PipelineCache>(), device, context);
+						let site = FiniteCheckSite {
+							sender: self.sequence.sender.clone(),
+							sequence: self.sequence.id,
+							generation: self.sequence.generation,
+							task: self.current_task,
+							label: task.label.clone(),
+							step: step.index,
+							iteration: first_iteration + iteration + 1,
+							buffer,
+						};
+						checker.record(device, context.command_encoder(), result, site);
 					}
 				}
-				ComputeAction::SwapBuffers { buffer } => {
-					self.sequence.sender.send(ComputeMessage::SwapBuffers(buffer)).unwrap();
+			}
+
+			if self.main() && buffers.step_writes().due(self.current_task, None) {
+				pass = None;
+				self.apply_step_writes(None, first_iteration + iteration, buffers, device, context);
+			}
+
+			if !last && !swaps.is_empty() {
+				#[cfg(feature = "trace")]
+				let _span = info_span!("compute_swapped_bind_groups").entered();
+				let mut swapped_buffers = swapped.take().map_or_else(|| buffers.clone(), |(buffers, _)| buffers);
+				for buffer in swaps.iter() {
+					swapped_buffers.swap_front_buffer(*buffer);
+				}
+				for buffer in rotations.iter() {
+					swapped_buffers.rotate_ring(*buffer, 1);
+				}
+				let bind_groups = render_buffers
+					.bind_groups(
+						&swapped_buffers,
+						device,
+						world.resource::<RenderAssets<GpuImage>>(),
+						world.resource::<FallbackImage>(),
+						&self.layouts,
+					)
+					.unwrap_or_else(|error| panic!("{}", error));
+				swapped = Some((swapped_buffers, bind_groups));
+				swap_rounds += 1;
+			} else if !last && !rotations.is_empty() {
+				// The bind groups don't change, so they're carried over.
+				let (mut rotated_buffers, bind_groups) = swapped.take().unwrap_or_else(|| {
+					(buffers.clone(), world.resource::<ComputeBindGroups>().0.clone())
+				});
+				for buffer in rotations.iter() {
+					rotated_buffers.rotate_ring(*buffer, 1);
 				}
+				swapped = Some((rotated_buffers, bind_groups));
+			}
+			for buffer in swaps.iter().filter(|_| self.main()) {
+				buffers.tracer().record_swap(self.current_task, first_iteration + iteration, *buffer);
+			}
+			frame_swaps.extend(swaps);
+			frame_rotations.extend(rotations);
+			if separate {
+				pass = None;
+				error_context.end();
+				submit_separately(context, device, adapter_info, render_queue, Some(&self.completed));
 			}
 		}
+		drop(pass);
+
+		// The counts steps are skipped by are read back once a frame, after the frame's iterations.
+		for step in self.step_states.iter() {
+			if let ComputeAction::RunShader { skip_if_empty: Some(counter), .. } = step.step.action {
+				if let Some(src) = buffers.gpu_buffer(counter) {
+					empty_checker.record(device, context.command_encoder(), self.sequence.generation, counter, &src);
+				}
+			}
+		}
+
+		// Swapping a buffer twice leaves it as it was, so only the buffers swapped an odd number of
+		// times are swapped in the main world.
+		let mut net_swaps = Vec::<ShaderBufferHandle>::new();
+		for buffer in frame_swaps {
+			if let Some(i) = net_swaps.iter().position(|swapped| *swapped == buffer) {
+				net_swaps.remove(i);
+			} else {
+				net_swaps.push(buffer);
+			}
+		}
+		for buffer in net_swaps {
+			send_message(&self.sequence.sender, ComputeMessage::SwapBuffers(buffer));
+		}
+		// Likewise, rotating a ring all the way round leaves it as it was.
+		let mut net_rotations = Vec::<(ShaderBufferHandle, u32)>::new();
+		for buffer in frame_rotations {
+			match net_rotations.iter_mut().find(|(rotated, _)| *rotated == buffer) {
+				Some((_, steps)) => *steps += 1,
+				None => net_rotations.push((buffer, 1)),
+			}
+		}
+		for (buffer, steps) in net_rotations {
+			let depth = buffers.ring_slot_and_depth(buffer).map_or(1, |(_, depth)| depth);
+			if steps % depth != 0 {
+				send_message(&self.sequence.sender, ComputeMessage::RotateBuffers(buffer, steps % depth));
+			}
+		}
+
+		if timed {
+			let task = (self.sequence.generation, self.current_task);
+			world.resource::<ComputeBudgetTimer>().end(device, context.command_encoder(), task, self.frame_iterations);
+		}
+		if let Some(step_timer) = step_timer {
+			step_timer.finish(device, context.command_encoder());
+		}
+		error_context.end();
+		// Whatever was recorded after the last iteration, such as the end of the budget timing, is submitted too.
+		if separate {
+			submit_separately(context, device, adapter_info, render_queue, None);
+		}
+
+		// Iterations of tasks with a time limit are timed from when the frame is submitted.
+		let limit =
+			world.get_resource::<ComputeTaskWatchdogs>().and_then(|watchdogs| watchdogs.0.get(task.label.as_ref()?).copied());
+		if let Some(limit) = limit {
+			let steps = self.step_states.iter().enumerate().filter(|(_, step)| step.run_this_time).map(|(i, _)| i).collect();
+			world.resource::<ComputeWatchdog>().watch(
+				&self.sequence.sender,
+				self.sequence.id,
+				self.sequence.generation,
+				self.current_task,
+				task.label.clone(),
+				self.iterations,
+				steps,
+				limit,
+			);
+		}
+
+		if diagnostics {
+			let path = encode_time_path(self.sequence.id, self.current_task, task.label.as_deref());
+			let value = start.elapsed().as_secs_f64() * 1000.0;
+			send_message(&self.sequence.sender, ComputeMessage::Diagnostic { path, value });
+		}
 
 		Ok(())
 	}
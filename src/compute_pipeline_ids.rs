@@ -0,0 +1,45 @@
+use bevy::{
+	prelude::*,
+	render::render_resource::{
+		BindGroupLayoutId, CachedComputePipelineId, ComputePipelineDescriptor, PipelineCache, PushConstantRange,
+		ShaderDefVal,
+	},
+	utils::HashMap,
+};
+
+/// Everything a step's pipeline is made from, other than its label.
+#[derive(Clone, PartialEq, Eq, Hash)]
+struct PipelineKey {
+	shader: AssetId<Shader>,
+	entry_point: String,
+	shader_defs: Vec<ShaderDefVal>,
+	layout: Vec<BindGroupLayoutId>,
+	push_constant_ranges: Vec<PushConstantRange>,
+	zero_initialize_workgroup_memory: bool,
+}
+
+impl PipelineKey {
+	fn new(descriptor: &ComputePipelineDescriptor) -> Self {
+		Self {
+			shader: descriptor.shader.id(),
+			entry_point: descriptor.entry_point.to_string(),
+			shader_defs: descriptor.shader_defs.clone(),
+			layout: descriptor.layout.iter().map(|layout| layout.id()).collect(),
+			push_constant_ranges: descriptor.push_constant_ranges.clone(),
+			zero_initialize_workgroup_memory: descriptor.zero_initialize_workgroup_memory,
+		}
+	}
+}
+
+/// The pipelines queued for the shader steps of every compute sequence, by the shader, entry point, shader defs and layouts they're made from. Steps specialized the same way share one pipeline, even across sequences, and a sequence started again, or a task run again, reuses the pipelines it had rather than queueing them again. Bevy's [PipelineCache] never lets go of a pipeline, so without this, every restart would leave another copy of each one behind. With it, there's one for each specialization that's ever been run.
+#[derive(Resource, Default)]
+pub(crate) struct ComputePipelineIds(HashMap<PipelineKey, CachedComputePipelineId>);
+
+impl ComputePipelineIds {
+	/// The id of the pipeline made from a descriptor, which is only queued if no pipeline has been made from the same one yet.
+	pub fn queue(
+		&mut self, pipeline_cache: &PipelineCache, descriptor: ComputePipelineDescriptor,
+	) -> CachedComputePipelineId {
+		*self.0.entry(PipelineKey::new(&descriptor)).or_insert_with(|| pipeline_cache.queue_compute_pipeline(descriptor))
+	}
+}
@@ -0,0 +1,97 @@
+#[cfg(not(target_arch = "wasm32"))]
+use std::{
+	sync::{
+		atomic::{AtomicBool, Ordering},
+		Arc,
+	},
+	time::Duration,
+};
+
+#[cfg(not(target_arch = "wasm32"))]
+use bevy::render::RenderApp;
+use bevy::{
+	prelude::*,
+	render::{render_resource::Maintain, renderer::RenderDevice},
+};
+
+/// How the GPU device is polled for finished work, which is what runs the callbacks bevy_compute waits on, such as a buffer readback being mapped, a checkpoint being read back, the timestamps of a [FrameBudget](crate::FrameBudget) arriving, or an iteration watched by [ComputeTaskWatchdogs](crate::ComputeTaskWatchdogs) finishing. Set with [polling](crate::ComputePluginSettings::polling).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum PollingMode {
+	/// Poll once every render frame, after the frame has been submitted, and wait for the GPU when reading back a [CopyBuffer](crate::ComputeAction::CopyBuffer), so natively its data arrives in the frame it's copied in. This is the default.
+	#[default]
+	PerFrame,
+
+	/// Poll from a thread of its own, at this interval, so callbacks run soon after the GPU finishes, even when frames are slow. Nothing waits for the GPU on the render thread, so [CopyBuffer](crate::ComputeAction::CopyBuffer) data arrives over the following frames, as on the web. Not available on the web, where the browser runs the callbacks itself.
+	#[cfg(not(target_arch = "wasm32"))]
+	Background {
+		/// How long the thread sleeps between polls.
+		interval: Duration,
+	},
+
+	/// Never poll. The app polls the [RenderDevice] itself, such as with `device.poll(Maintain::Poll)` in a render world system, and until it does, nothing that waits on the GPU finishes. Nothing waits for the GPU on the render thread either, so [CopyBuffer](crate::ComputeAction::CopyBuffer) data arrives over the following frames, as on the web.
+	Manual,
+}
+
+/// The [PollingMode] in use, in the render world, along with the flag that stops the background thread, if there is one, when this is dropped along with the app.
+#[derive(Resource)]
+pub(crate) struct ComputePolling {
+	mode: PollingMode,
+	#[cfg(not(target_arch = "wasm32"))]
+	stop: Option<Arc<AtomicBool>>,
+}
+
+impl ComputePolling {
+	pub fn new(mode: PollingMode) -> Self {
+		Self {
+			mode,
+			#[cfg(not(target_arch = "wasm32"))]
+			stop: None,
+		}
+	}
+
+	/// Whether buffer readbacks wait for the GPU, so they're read in the frame they're copied in. They never do on the web, where nothing can block, so they're read over the following frames there whatever this is.
+	pub fn waits(&self) -> bool { self.mode == PollingMode::PerFrame }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl Drop for ComputePolling {
+	fn drop(&mut self) {
+		if let Some(stop) = &self.stop {
+			stop.store(true, Ordering::Release);
+		}
+	}
+}
+
+/// Start the background thread, if polling is done in the background, once the [RenderDevice] exists.
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) fn start_polling(app: &mut App) {
+	let Some(device) = app.world().get_resource::<RenderDevice>().cloned() else {
+		return;
+	};
+	let Some(mut polling) = app.get_sub_app_mut(RenderApp).and_then(|render_app| {
+		render_app.world_mut().get_resource_mut::<ComputePolling>().filter(|polling| polling.stop.is_none())
+	}) else {
+		return;
+	};
+	let PollingMode::Background { interval } = polling.mode else {
+		return;
+	};
+	let stop = Arc::new(AtomicBool::new(false));
+	polling.stop = Some(stop.clone());
+	std::thread::spawn(move || {
+		while !stop.load(Ordering::Acquire) {
+			device.poll(Maintain::Poll);
+			std::thread::sleep(interval);
+		}
+	});
+}
+
+#[cfg(target_arch = "wasm32")]
+pub(crate) fn start_polling(_app: &mut App) {}
+
+/// Poll the device once a frame, after the frame's commands have been submitted, when polling per frame. Everything that reads back results from the GPU runs after this.
+pub(crate) fn poll_device(polling: Res<ComputePolling>, device: Res<RenderDevice>) {
+	if polling.mode == PollingMode::PerFrame {
+		device.poll(Maintain::Poll);
+	}
+}
@@ -0,0 +1,171 @@
+use std::sync::{Arc, Mutex, OnceLock};
+
+use bevy::{
+	prelude::*,
+	render::{
+		render_resource::{Buffer, BufferDescriptor, BufferUsages, CommandEncoder, MapMode, WgpuFeatures},
+		renderer::{RenderDevice, RenderQueue},
+		Render, RenderApp, RenderSet,
+	},
+	utils::HashMap,
+};
+use wgpu::{QuerySet, QuerySetDescriptor, QueryType, QUERY_SET_MAX_QUERIES, QUERY_SIZE};
+
+use super::{
+	compute_data_transmission::{send_message, ComputeMessage},
+	compute_polling::poll_device,
+	compute_sequence::ComputeSequences,
+};
+
+/// Adding this plugin alongside the [BevyComputePlugin](crate::BevyComputePlugin) times every dispatch of a shader step on the GPU, with timestamps written at the start and end of its compute pass, and publishes how long each step took in the [ComputeTimings] resource, such as for tuning workgroup sizes. Each dispatch is recorded into a compute pass of its own while this is on, which costs a little, so it's best left out of release builds. Where the GPU doesn't support the `TIMESTAMP_QUERY` feature, as told by [ComputeCapabilities::timestamps](crate::ComputeCapabilities::timestamps), nothing is timed, and a warning is logged. When this plugin isn't added, nothing is timed, and the dispatches are recorded as usual.
+pub struct ComputeProfilingPlugin;
+
+impl Plugin for ComputeProfilingPlugin {
+	fn build(&self, app: &mut App) {
+		app.init_resource::<ComputeTimings>();
+		app
+			.sub_app_mut(RenderApp)
+			.init_resource::<ComputeStepTimer>()
+			.add_systems(Render, read_step_timings.in_set(RenderSet::Cleanup).after(poll_device));
+	}
+}
+
+/// How long the shader steps timed by the [ComputeProfilingPlugin] took on the GPU, in milliseconds. It's updated in the main world every frame the timestamps of an earlier frame have been read back, which is usually a frame or two after it ran, with the times from that frame. A step that dispatched more than once in a frame, such as over several iterations, or chunks of a [Chunked](crate::DispatchSize::Chunked) dispatch, has the total of its dispatches. A step that didn't run in that frame keeps its last time.
+#[derive(Resource, Clone, Debug, Default)]
+pub struct ComputeTimings {
+	/// The time of every step timed so far, by the name of its task, which is its label, or `task_N` for the task at index `N` if it has no label, as in [encode_time_path](crate::encode_time_path), and the entry point it runs. The passes of a [RunShaderMulti](crate::ComputeAction::RunShaderMulti) step are timed separately, by their own entry points.
+	pub steps: HashMap<(String, String), f64>,
+}
+
+impl ComputeTimings {
+	/// How long the step running an entry point in a task took on the GPU, in milliseconds, or `None` if it hasn't been timed yet. The task is named as it is in [steps](ComputeTimings::steps).
+	pub fn get(&self, task: &str, entry_point: &str) -> Option<f64> {
+		self.steps.get(&(task.to_owned(), entry_point.to_owned())).copied()
+	}
+}
+
+/// The dispatches timed in one frame, by the task and entry point of each, in the order of their pairs of timestamps, and the buffer the timestamps are copied into, which is mapped once the frame has been submitted.
+struct TimedFrame {
+	steps: Vec<(String, String)>,
+	buffer: Buffer,
+	mapped: Option<Arc<OnceLock<bool>>>,
+}
+
+#[derive(Default)]
+struct StepTimerState {
+	query_set: Option<Arc<QuerySet>>,
+	resolve_buffer: Option<Buffer>,
+	free_buffers: Vec<Buffer>,
+	recording: Vec<(String, String)>,
+	frames: Vec<TimedFrame>,
+	warned: bool,
+}
+
+/// Times the dispatches of shader steps in the render world, for the [ComputeProfilingPlugin], which is the only thing that adds it. A frame can time up to half as many dispatches as a query set can hold queries, and any past that aren't timed.
+#[derive(Resource, Default)]
+pub(crate) struct ComputeStepTimer(Mutex<StepTimerState>);
+
+impl ComputeStepTimer {
+	/// The query set and the index of the first of the two timestamps to write around the compute pass of a dispatch, or `None` if it can't be timed, because the GPU doesn't support timestamp queries, or this frame's queries have all been used.
+	pub fn time_dispatch(&self, device: &RenderDevice, task: &str, entry_point: &str) -> Option<(Arc<QuerySet>, u32)> {
+		let mut state = self.0.lock().unwrap();
+		if !device.features().contains(WgpuFeatures::TIMESTAMP_QUERY) {
+			if !state.warned {
+				state.warned = true;
+				warn!("The ComputeProfilingPlugin was added, but the GPU doesn't support timestamp queries, so nothing will be timed");
+			}
+			return None;
+		}
+		let index = state.recording.len() as u32 * 2;
+		if index + 2 > QUERY_SET_MAX_QUERIES {
+			return None;
+		}
+		let query_set = state
+			.query_set
+			.get_or_insert_with(|| {
+				Arc::new(device.wgpu_device().create_query_set(&QuerySetDescriptor {
+					label: None,
+					ty: QueryType::Timestamp,
+					count: QUERY_SET_MAX_QUERIES,
+				}))
+			})
+			.clone();
+		state.recording.push((task.to_owned(), entry_point.to_owned()));
+		Some((query_set, index))
+	}
+
+	/// Copy the timestamps of every dispatch timed this frame somewhere they can be read back from once the frame has been submitted.
+	pub fn finish(&self, device: &RenderDevice, encoder: &mut CommandEncoder) {
+		let mut state = self.0.lock().unwrap();
+		let state = &mut *state;
+		let (Some(query_set), false) = (&state.query_set, state.recording.is_empty()) else {
+			return;
+		};
+		let size = QUERY_SET_MAX_QUERIES as u64 * QUERY_SIZE as u64;
+		let resolve_buffer = state.resolve_buffer.get_or_insert_with(|| {
+			device.create_buffer(&BufferDescriptor {
+				label: None,
+				size,
+				usage: BufferUsages::QUERY_RESOLVE | BufferUsages::COPY_SRC,
+				mapped_at_creation: false,
+			})
+		});
+		let count = state.recording.len() as u32 * 2;
+		encoder.resolve_query_set(query_set, 0..count, resolve_buffer, 0);
+		let buffer = state.free_buffers.pop().unwrap_or_else(|| {
+			device.create_buffer(&BufferDescriptor {
+				label: None,
+				size,
+				usage: BufferUsages::MAP_READ | BufferUsages::COPY_DST,
+				mapped_at_creation: false,
+			})
+		});
+		encoder.copy_buffer_to_buffer(resolve_buffer, 0, &buffer, 0, count as u64 * QUERY_SIZE as u64);
+		let steps = std::mem::take(&mut state.recording);
+		state.frames.push(TimedFrame { steps, buffer, mapped: None });
+	}
+
+	/// Drop the query set and the buffers the timestamps are copied into, once the app is exiting.
+	pub fn release(&self) { *self.0.lock().unwrap() = StepTimerState::default(); }
+}
+
+/// Map the timestamps of the dispatches timed this frame, as the frame's commands have just been submitted, and read the ones that have been mapped since, sending the time of every step to the main world for the [ComputeTimings].
+fn read_step_timings(
+	timer: Res<ComputeStepTimer>, render_queue: Res<RenderQueue>, sequences: Option<Res<ComputeSequences>>,
+) {
+	let mut state = timer.0.lock().unwrap();
+	if state.frames.is_empty() {
+		return;
+	}
+	let period = render_queue.get_timestamp_period() as f64;
+	let state = &mut *state;
+	state.frames.retain_mut(|frame| {
+		let Some(mapped) = &frame.mapped else {
+			let mapped = Arc::new(OnceLock::new());
+			let result = mapped.clone();
+			frame.buffer.slice(..).map_async(MapMode::Read, move |map_result| {
+				let _ = result.set(map_result.is_ok());
+			});
+			frame.mapped = Some(mapped);
+			return true;
+		};
+		let Some(&success) = mapped.get() else {
+			return true;
+		};
+		if success {
+			let size = frame.steps.len() as u64 * 2 * QUERY_SIZE as u64;
+			let timestamps: Vec<u64> = bytemuck::pod_collect_to_vec(&frame.buffer.slice(..size).get_mapped_range());
+			let mut steps = HashMap::<_, f64>::new();
+			for (step, pair) in frame.steps.drain(..).zip(timestamps.chunks_exact(2)) {
+				*steps.entry(step).or_default() += pair[1].wrapping_sub(pair[0]) as f64 * period / 1e6;
+			}
+			// The timings are kept by task and step label, whichever sequence they're from.
+			if let Some(sender) = sequences.as_ref().and_then(|sequences| sequences.any_sender()) {
+				send_message(sender, ComputeMessage::StepTimings(steps));
+			}
+			frame.buffer.unmap();
+			state.free_buffers.push(frame.buffer.clone());
+		}
+		false
+	});
+}
@@ -3,19 +3,38 @@ use bevy::{
 	prelude::*,
 	render::{
 		graph::CameraDriverLabel,
-		render_graph::{RenderGraph, RenderLabel},
+		render_graph::{InternedRenderLabel, RenderGraph, RenderLabel},
 	},
 };
 
-use super::{compute_node::ComputeNode, compute_sequence::ComputeSequence};
+use super::compute_node::ComputeNode;
 
+/// The label of the node in the render graph that runs the compute sequences, which is recorded before the [CameraDriverLabel] node, and so before every camera is rendered. In an app whose render graph has no camera driver, it's left as a root of the graph, which still runs every time the graph does, so a headless app with no window or camera runs its compute sequences all the same.
 #[derive(Debug, Hash, PartialEq, Eq, Clone, RenderLabel)]
 pub struct ComputeLabel;
 
+/// Render graph nodes, other than the camera driver, that read what the compute sequence writes, such as [buffers consumed by rendering](crate::ShaderBufferSet::consume_in_render), and so need to be recorded after it. Insert this into the render app, and each node is ordered after the [ComputeLabel] node when the compute sequence is started. Nodes that aren't in the top level render graph by then are skipped with a warning.
+#[derive(Resource, Default, Clone, Debug)]
+pub struct ComputeRenderConsumers(pub Vec<InternedRenderLabel>);
+
 pub fn compute_render_setup(world: &mut World) {
-	let mut system_state: SystemState<(ResMut<RenderGraph>, Res<ComputeSequence>)> = SystemState::new(world);
-	let (mut render_graph, sequence) = system_state.get_mut(world);
+	#[cfg(feature = "trace")]
+	let _span = info_span!("compute_render_setup").entered();
+	let mut system_state: SystemState<(ResMut<RenderGraph>, Res<ComputeRenderConsumers>)> = SystemState::new(world);
+	let (mut render_graph, consumers) = system_state.get_mut(world);
+
+	// Sequences started after the last one was stopped are picked up by the node that's already there.
+	if render_graph.get_node_state(ComputeLabel).is_ok() {
+		return;
+	}
 
-	render_graph.add_node(ComputeLabel, ComputeNode::new(&sequence));
-	render_graph.add_node_edge(ComputeLabel, CameraDriverLabel);
+	render_graph.add_node(ComputeLabel, ComputeNode::default());
+	if render_graph.get_node_state(CameraDriverLabel).is_ok() {
+		render_graph.add_node_edge(ComputeLabel, CameraDriverLabel);
+	}
+	for &consumer in &consumers.0 {
+		if let Err(error) = render_graph.try_add_node_edge(ComputeLabel, consumer) {
+			warn!("Couldn't order the render graph node {:?} after the compute sequence: {}", consumer, error);
+		}
+	}
 }
@@ -1,45 +1,431 @@
-use std::{num::NonZeroU32, sync::mpsc::SyncSender};
+use std::{
+	borrow::Cow,
+	collections::{BTreeMap, HashMap},
+	fmt::{Debug, Display, Formatter},
+	hash::Hash,
+	num::NonZeroU32,
+	time::Duration,
+};
 
-use bevy::{prelude::*, render::extract_resource::ExtractResource};
+use bevy::{
+	ecs::system::SystemId,
+	prelude::*,
+	render::{extract_resource::ExtractResource, render_resource::ShaderDefVal},
+};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
 
-use super::compute_data_transmission::ComputeMessage;
-use crate::shader_buffer_set::ShaderBufferHandle;
+use super::{
+	check_finite::FiniteCheckType,
+	clear_buffer::ClearHalves,
+	compute_capabilities::ComputeCapabilities,
+	compute_data_transmission::MessageSender,
+	convolve::{ConvolutionEdges, ConvolutionKernel},
+	histogram::{HistogramOutOfRange, HistogramSource},
+	reduce_buffer::{ReduceElement, ReduceOp},
+	sort_buffer::SortKey,
+	ComputeSequenceId,
+};
+use crate::shader_buffer_set::{BindingNamespace, ShaderBufferHandle, ShaderBufferSet};
 
-#[derive(Resource, Clone, ExtractResource)]
+/// Every compute sequence that's running, or has finished, by its id, which is only inserted while there's at least one, and is extracted to the render world as a whole whenever any of them changes.
+#[derive(Resource, Clone, Default, ExtractResource)]
+pub(crate) struct ComputeSequences(pub BTreeMap<ComputeSequenceId, ComputeSequence>);
+
+impl ComputeSequences {
+	/// The sender of any of the sequences, for messages that aren't about any one of them, such as diagnostics.
+	pub fn any_sender(&self) -> Option<&MessageSender> { self.0.values().next().map(|sequence| &sequence.sender) }
+}
+
+#[derive(Clone)]
 pub(crate) struct ComputeSequence {
-	pub sender: SyncSender<ComputeMessage>,
+	pub id: ComputeSequenceId,
+	/// The sending half of the sequence's own channel to the main world, whose receiving half is dropped when the sequence is stopped or replaced, so nothing it sends after that arrives.
+	pub sender: MessageSender,
 	pub tasks: Vec<ComputeTask>,
 	pub iteration_buffer: Option<ShaderBufferHandle>,
+	pub seed: u64,
+	pub generation: u32,
+	/// The task to start from, which is only ever not the first when a finished task is restarted by an [ExtendComputeTaskEvent](crate::ExtendComputeTaskEvent).
+	pub first_task: usize,
+	/// The number of iterations the first task runs for when it's been restarted, in place of its usual count.
+	pub restart_iterations: Option<u32>,
+	/// The iterations each task has been extended by with [ExtendComputeTaskEvent](crate::ExtendComputeTaskEvent)s, on top of its usual count.
+	pub extra_iterations: Vec<u32>,
+	/// Whether each task has been stopped with a [StopComputeTaskEvent](crate::StopComputeTaskEvent), so it finishes at the next iteration boundary.
+	pub stopped: Vec<bool>,
+	/// Whether each task has been paused, with a [PauseComputeTaskEvent](crate::PauseComputeTaskEvent) or by a watchdog, so it doesn't iterate until it's resumed.
+	pub paused: Vec<bool>,
+	/// Whether the whole sequence has been paused with a [PauseComputeEvent](crate::PauseComputeEvent), so no task iterates until it's resumed with a [ResumeComputeEvent](crate::ResumeComputeEvent).
+	pub all_paused: bool,
+	/// The iterations each [on-demand](TaskRunMode::OnDemand) task has been triggered for with [TriggerComputeTaskEvent](crate::TriggerComputeTaskEvent)s, in total.
+	pub triggered: Vec<u32>,
 }
 
+impl ComputeSequence {
+	/// Whether nothing iterates at the given task, because the whole sequence is paused, or that task is.
+	pub fn paused_at(&self, task: usize) -> bool { self.all_paused || self.paused.get(task) == Some(&true) }
+}
+
+/// Iteration counts for compute tasks, by their [labels](ComputeTask::label), for counts that aren't known when the compute sequence starts, such as one set with a slider right up until the task runs. When a task with one of these labels starts, it runs for the count here, in place of its own [iterations](ComputeTask::iterations), even if that's `None`. The count is looked up once, when the task starts, so changing it after that has no effect on the running task, though it can still be extended with an [ExtendComputeTaskEvent](crate::ExtendComputeTaskEvent). A count of zero finishes the task without running it.
+#[derive(Resource, Clone, Default, Debug, ExtractResource)]
+pub struct ComputeTaskIterations(pub HashMap<String, u32>);
+
+/// Flags for gating compute steps from the main world, by name, for steps with a [run_if](ComputeStep::run_if) condition, such as one that resets a simulation on the iteration after the user presses a key. A flag that isn't here counts as unset. The flags are extracted to the render world every frame, so a change takes effect within a frame or two. A [OneShotFlag](StepCondition::OneShotFlag) is cleared here once its steps have run.
+#[derive(Resource, Clone, Default, Debug, ExtractResource)]
+pub struct ComputeStepFlags(pub HashMap<String, bool>);
+
+impl ComputeStepFlags {
+	/// Whether the flag with this name is set.
+	pub fn get(&self, name: &str) -> bool { self.0.get(name).copied().unwrap_or(false) }
+
+	/// Set or clear the flag with this name.
+	pub fn set(&mut self, name: impl Into<String>, value: bool) { self.0.insert(name.into(), value); }
+}
+
+/// Conditions for stopping compute tasks early, by their [labels](ComputeTask::label), for termination conditions that are cheapest to check on the CPU, such as how long a task has been running, or a flag set by game logic. Each is a system returning a `bool`, registered with [World::register_system], which is run once a frame in the main world while its task is running. Once it returns true, the task is stopped with a [StopComputeTaskEvent](crate::StopComputeTaskEvent), finishing cleanly at the next iteration boundary, and its [ComputeTaskDoneEvent](crate::ComputeTaskDoneEvent) is sent as usual. This works for tasks that would otherwise run forever, as well as ones with an iteration count.
+///
+/// ```Rust
+/// fn setup(mut commands: Commands, mut conditions: ResMut<ComputeTaskStopConditions>) {
+///     let condition = commands.register_system(|progress: Res<ComputeProgress>| progress.iterations >= 600);
+///     conditions.0.insert("Erode".to_owned(), condition);
+/// }
+/// ```
+#[derive(Resource, Clone, Default, Debug)]
+pub struct ComputeTaskStopConditions(pub HashMap<String, SystemId<(), bool>>);
+
 /// This describes a compute shader task, which is a set of things it should do every tick, for some number of iterations.
-#[derive(Clone)]
-pub struct ComputeTask {
+///
+/// The type parameter is how buffers are referenced. When starting a compute sequence, this must be a [ShaderBufferHandle], which is the default. But handles aren't stable between runs, so when compute tasks are stored in data files, buffers can be referenced by any other key, such as a name or an index, and converted to handles with [resolve](ComputeTask::resolve) once the buffers exist. With the `serde` feature enabled, all of these types can be serialized and deserialized, as long as the buffer key can be.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ComputeTask<B = ShaderBufferHandle> {
 	/// The optional label is sent back in the [ComputeTaskDoneEvent](crate::ComputeTaskDoneEvent) when this task is completed. It makes it easier to identify which task was completed.
 	pub label: Option<String>,
 
-	/// The number of times to run this task before considering it done. If this isn't provided, it will run forever.
+	/// The number of times to run this task before considering it done. If this isn't provided, it will run forever. This is ignored for a task that runs [on demand](TaskRunMode::OnDemand).
 	pub iterations: Option<NonZeroU32>,
 
+	/// Whether the task runs its iterations straight away, or only when it's triggered. See [TaskRunMode].
+	#[cfg_attr(feature = "serde", serde(default))]
+	pub run_mode: TaskRunMode,
+
+	/// An optional frame time budget, to run as many iterations each frame as fit in it, rather than one. This is for tasks that need a lot of iterations, such as a bake, to finish sooner without tanking the frame rate. If this isn't provided, the task runs one iteration a frame, which is also the way to keep a task's iterations in step with frames when that matters.
+	#[cfg_attr(feature = "serde", serde(default))]
+	pub budget: Option<FrameBudget>,
+
+	/// How the task's commands are submitted to the GPU, either along with the rest of the frame, or on their own. See [TaskSubmission].
+	#[cfg_attr(feature = "serde", serde(default))]
+	pub submission: TaskSubmission,
+
+	/// An optional delay before the task starts, counted from when the compute sequence was started, such as to keep a simulation frozen during an intro. If the task is reached before then, nothing of it runs until the delay is up, not even its buffer copies or swaps, and the frames it waits don't count as iterations. Its shaders are still loaded in the meantime. While it waits, the [ComputeProgress](crate::ComputeProgress) shows it as [pending](crate::ComputeProgress::pending).
+	#[cfg_attr(feature = "serde", serde(default))]
+	pub start_after: Option<StartDelay>,
+
+	/// An optional buffer to write this task's own iteration count into before each of its iterations, such as for tasks that run on different cadences, where the sequence's [iteration buffer](crate::StartComputeEvent::iteration_buffer) only tells a shader how far along whichever task is running. See [TaskIterationBuffer] for what can be written. The counts are mirrored into the main world in the [ComputeIterationState](crate::ComputeIterationState).
+	#[cfg_attr(feature = "serde", serde(default = "Option::default"))]
+	pub iteration_buffer: Option<TaskIterationBuffer<B>>,
+
 	/// The set of steps to execute on each iteration.
-	pub steps: Vec<ComputeStep>,
+	pub steps: Vec<ComputeStep<B>>,
+}
+
+/// A frame time budget for a [ComputeTask]. Every frame, the task runs as many iterations as it estimates will fit in the budget, based on how long its iterations take on the GPU, as measured with timestamp queries. The number of iterations is adjusted as the measurements come in, and never more than doubles from one frame to the next, so a poor estimate can't stall a frame for long. Where timestamp queries aren't available, as on the web, nothing is measured, and the [estimate](FrameBudget::estimate) is used throughout. The iteration count of the task, and the iteration count reported in the [ComputeProgress](crate::ComputeProgress) and the [ComputeTaskDoneEvent](crate::ComputeTaskDoneEvent), still count every iteration, and the [iteration buffer](crate::StartComputeEvent::iteration_buffer) still holds the count of each iteration as it runs.
+///
+/// The iterations run in a frame are recorded one after another, and all submitted at once, unless the task is [submitted separately](TaskSubmission::Separate), so a few things only happen once a frame. The bindings of double buffers swapped in an iteration are swapped for the next one, as usual, but the [globals](crate::ShaderBufferSet::add_globals), [cursor](crate::ShaderBufferSet::add_cursor_uniform) and [camera](crate::ShaderBufferSet::add_camera_uniform) uniforms are the same for every iteration in a frame, and steps with a [schedule](ComputeStep::schedule) other than [EveryIteration](StepSchedule::EveryIteration), as well as [CopyBuffer](ComputeAction::CopyBuffer) steps, only run in the last iteration of a frame.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct FrameBudget {
+	/// The GPU time to spend on the task's iterations each frame.
+	pub time: Duration,
+
+	/// How long one iteration is expected to take on the GPU, which is used until it's been measured, or throughout, if it can't be.
+	pub estimate: Duration,
+}
+
+/// How the commands of a [ComputeTask] are submitted to the GPU.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum TaskSubmission {
+	/// Record the task into the render graph's command encoder, and submit it with the rest of the frame, so the frame is only presented once the task's iterations for it are done. Its iterations are counted as done as soon as they're recorded. This is the default.
+	#[default]
+	WithFrame,
+
+	/// Record the task into a command encoder of its own, and submit each of its iterations on its own, before the frame's submission. An iteration only counts as done once the GPU has finished it, which is when it's counted in the [ComputeProgress](crate::ComputeProgress), and the task only finishes once all of them have. No more iterations are recorded while two frames' worth are still running on the GPU, so a task that's slower than the frame rate falls behind instead of piling up work. This is meant for long jobs such as bakes, together with a [FrameBudget], which spreads the iterations of a frame across that many submissions. With [Manual](crate::PollingMode::Manual) polling, the GPU has to be polled for the iterations to finish.
+	Separate,
+}
+
+/// When a [ComputeTask] runs its iterations.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum TaskRunMode {
+	/// Run iterations from the moment the task starts, until it's run all of them, or forever. This is the default.
+	#[default]
+	Continuous,
+
+	/// Run no iterations, but for those it's triggered for with [TriggerComputeTaskEvent](crate::TriggerComputeTaskEvent)s, such as a picking dispatch that runs once each time the mouse is clicked. The task sits idle until it's triggered, runs the iterations, and then sits idle again, without ever finishing, so the tasks after it never start, unless it's stopped with a [StopComputeTaskEvent](crate::StopComputeTaskEvent). That makes it best suited to a sequence of its own, or to the last task of one. Triggers sent before the task starts, or while it's still running the iterations of earlier ones, add up, and are all run in turn. The triggered iterations run like any others, with the task's steps' [schedules](ComputeStep::schedule) and [conditions](ComputeStep::run_if), and the double buffers they swap staying swapped from one trigger to the next, and the task's iteration count carries on from one trigger to the next, rather than starting over. Frames the task sits idle in don't count towards an [EveryNFrames](StepSchedule::EveryNFrames) schedule.
+	OnDemand,
+}
+
+/// How long a [ComputeTask] waits to start, counted from when its compute sequence was started. Extending a finished task with an [ExtendComputeTaskEvent](crate::ExtendComputeTaskEvent) starts the sequence over, and the delays along with it.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum StartDelay {
+	/// Wait this many frames, counting the one the sequence was started in.
+	Frames(u32),
+
+	/// Wait this many seconds.
+	Seconds(f32),
+}
+
+impl StartDelay {
+	/// Whether the delay is up, given the frames and time since the sequence was started.
+	pub(crate) fn elapsed(&self, frames: u32, time: Duration) -> bool {
+		match *self {
+			StartDelay::Frames(delay) => frames > delay,
+			StartDelay::Seconds(delay) => time.as_secs_f32() >= delay,
+		}
+	}
+}
+
+/// The buffer a [ComputeTask] writes its iteration counts into before each of its iterations, given in its [iteration_buffer](ComputeTask::iteration_buffer), and what it writes. Like the sequence's [iteration buffer](crate::StartComputeEvent::iteration_buffer), the counts start from 0 in the first iteration, and when a task runs more than one iteration in a frame, each of them sees its own counts.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum TaskIterationBuffer<B = ShaderBufferHandle> {
+	/// Write the iteration count within the task, as a `u32`, to this buffer.
+	Local(B),
+
+	/// Write a [ComputeIterationCounts](crate::ComputeIterationCounts), with both the iteration count within the task and the count across the whole sequence, to this buffer.
+	Counts(B),
+}
+
+impl<B> TaskIterationBuffer<B> {
+	/// The buffer the counts are written to.
+	pub fn buffer(&self) -> &B {
+		match self {
+			TaskIterationBuffer::Local(buffer) | TaskIterationBuffer::Counts(buffer) => buffer,
+		}
+	}
+
+	/// The number of bytes written to the buffer.
+	pub fn size(&self) -> u64 {
+		match self {
+			TaskIterationBuffer::Local(_) => 4,
+			TaskIterationBuffer::Counts(_) => 8,
+		}
+	}
+
+	/// Convert the buffer to another type, with the provided function. See [ComputeTask::map_buffers].
+	pub fn map_buffers<C, E>(&self, mut f: impl FnMut(&B) -> Result<C, E>) -> Result<TaskIterationBuffer<C>, E> {
+		Ok(match self {
+			TaskIterationBuffer::Local(buffer) => TaskIterationBuffer::Local(f(buffer)?),
+			TaskIterationBuffer::Counts(buffer) => TaskIterationBuffer::Counts(f(buffer)?),
+		})
+	}
+}
+
+/// Which way round the halves of a double buffer are bound for one [RunShader](ComputeAction::RunShader) step, given in its [buffer_orientation](ComputeAction::RunShader::buffer_orientation).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum BufferOrientation {
+	/// The front buffer is bound at the front binding, and the back buffer at the back binding, as they are for every other step. This is the default.
+	#[default]
+	Normal,
+
+	/// The back buffer is bound at the front binding, and the front buffer at the back binding, as if the buffer had been swapped for just this step, such as for a diagnostic step that reads what the step before it wrote to the back buffer, before it's swapped. Nothing is actually swapped, so the steps after it see the buffer the way round they would have anyway.
+	Reversed,
+}
+
+/// The push constants a [RunShader](ComputeAction::RunShader) step sets before its dispatch, given in its [push_constants](ComputeAction::RunShader::push_constants), which the shader declares as a `var<push_constant>`. Setting them costs far less than writing a uniform buffer, so they suit small parameters that change from one dispatch to the next, such as an iteration counter. They need the `PUSH_CONSTANTS` feature, which Bevy enables whenever the GPU has it, unless the [WgpuSettings](bevy::render::settings::WgpuSettings) say otherwise, but which isn't available on the web, and a pipeline can only have so many bytes of them. Both are in the [ComputeCapabilities], and are checked when the task starts.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum PushConstants {
+	/// The same bytes before every dispatch. There must be a multiple of 4 of them.
+	Bytes(Vec<u8>),
+
+	/// Bytes worked out in the render world before every dispatch, such as the iteration as a `u32`. These can't be serialized.
+	#[cfg_attr(feature = "serde", serde(skip))]
+	Computed {
+		/// The number of bytes the function returns, which must be a multiple of 4, as the pipeline is built for that many.
+		size: u32,
+
+		/// The function, given the render world and the number of iterations of the task that ran before this one. If it returns anything other than `size` bytes, the step panics.
+		data: fn(&World, u32) -> Vec<u8>,
+	},
+}
+
+/// Computed push constants are equal if they have the same size and the same function, by its address.
+impl PartialEq for PushConstants {
+	fn eq(&self, other: &Self) -> bool {
+		match (self, other) {
+			(PushConstants::Bytes(bytes), PushConstants::Bytes(other_bytes)) => bytes == other_bytes,
+			(
+				PushConstants::Computed { size, data },
+				PushConstants::Computed { size: other_size, data: other_data },
+			) => size == other_size && *data as usize == *other_data as usize,
+			_ => false,
+		}
+	}
+}
+
+impl PushConstants {
+	/// The number of bytes of push constants, which the pipeline is built for.
+	pub fn size(&self) -> u32 {
+		match self {
+			PushConstants::Bytes(bytes) => bytes.len() as u32,
+			PushConstants::Computed { size, .. } => *size,
+		}
+	}
+
+	/// Check that the push constants of a step running a shader can be set on this GPU, returning what's wrong if they can't.
+	pub(crate) fn check(&self, shader: &str, capabilities: Option<&ComputeCapabilities>) -> Result<(), String> {
+		let size = self.size();
+		if size == 0 || size % 4 != 0 {
+			return Err(format!(
+				"Tried to give {} {} bytes of push constants, but they must come to a multiple of 4 bytes",
+				shader, size
+			));
+		}
+		let Some(capabilities) = capabilities else {
+			return Ok(());
+		};
+		if !capabilities.push_constants {
+			return Err(format!(
+				"Tried to give {} push constants, but the GPU doesn't support the PUSH_CONSTANTS feature",
+				shader
+			));
+		}
+		if size > capabilities.max_push_constant_size {
+			return Err(format!(
+				"Tried to give {} {} bytes of push constants, but the GPU allows at most {}",
+				shader, size, capabilities.max_push_constant_size
+			));
+		}
+		Ok(())
+	}
+
+	/// The bytes to set before a dispatch, after `iteration` iterations of the task. Panics if a function returns the wrong number of bytes.
+	pub(crate) fn data(&self, shader: &str, world: &World, iteration: u32) -> Cow<'_, [u8]> {
+		match self {
+			PushConstants::Bytes(bytes) => Cow::Borrowed(bytes),
+			PushConstants::Computed { size, data } => {
+				let bytes = data(world, iteration);
+				if bytes.len() != *size as usize {
+					panic!(
+						"The push constants of {} came to {} bytes, but the pipeline was built for {}",
+						shader,
+						bytes.len(),
+						size
+					);
+				}
+				Cow::Owned(bytes)
+			}
+		}
+	}
+}
+
+/// How often a [ComputeStep] runs, given in its [schedule](ComputeStep::schedule).
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum StepSchedule {
+	/// Run in every iteration of the task, however many of them run in a frame. This is the default.
+	#[default]
+	EveryIteration,
+
+	/// Run at most this many times a second, by the render world's [Time], in the last iteration of a frame. The time a run is due is kept to a fraction of a frame, and moved on by exactly one period each time the step runs, so 30 Hz on a 144 Hz display runs every 4 or 5 frames, for 30 runs a second on average, rather than every 5 frames for 28.8. If the step falls more than a period behind, such as after a long frame, it runs once and starts counting again from there, instead of running every frame to catch up. The frequency must be positive and finite, which is checked when the task starts.
+	MaxFrequencyHz(f32),
+
+	/// Run once every this many frames, in the last iteration of a frame, starting with the first frame the task runs in. Only frames in which the task runs an iteration are counted.
+	EveryNFrames(NonZeroU32),
+}
+
+impl StepSchedule {
+	/// Fails if this is a [MaxFrequencyHz](StepSchedule::MaxFrequencyHz) whose frequency isn't positive and finite.
+	pub(crate) fn check(&self) -> Result<(), String> {
+		match *self {
+			StepSchedule::MaxFrequencyHz(hz) if !(hz.is_finite() && hz > 0.0) => {
+				Err(format!("Tried to run a step at most {} times a second, which isn't a positive, finite frequency", hz))
+			}
+			_ => Ok(()),
+		}
+	}
+}
+
+/// A condition for a [ComputeStep] to run at all, given in its [run_if](ComputeStep::run_if), checked once a frame against the [ComputeStepFlags].
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum StepCondition {
+	/// Run only while the flag with this name is set. While it's set, the step runs as its [schedule](ComputeStep::schedule) says, in every iteration if that's [EveryIteration](StepSchedule::EveryIteration).
+	ResourceFlag(String),
+
+	/// Run once each time the flag with this name is set, in the last iteration of the frame, after which the flag is cleared in the main world, so a single key press runs the step exactly once, however high the frame rate. Every step gated on the same flag runs in the same iteration. The flag isn't rechecked until the render world has seen it cleared, so setting it again in the frame or two before then has no effect.
+	OneShotFlag(String),
+}
+
+impl StepCondition {
+	/// The name of the flag the condition reads.
+	pub fn flag(&self) -> &str {
+		match self {
+			StepCondition::ResourceFlag(flag) | StepCondition::OneShotFlag(flag) => flag,
+		}
+	}
+}
+
+impl From<Option<NonZeroU32>> for StepSchedule {
+	fn from(max_frequency: Option<NonZeroU32>) -> Self {
+		max_frequency.map_or(StepSchedule::EveryIteration, |hz| StepSchedule::MaxFrequencyHz(hz.get() as f32))
+	}
 }
 
 /// A compute step is one action to do during a compute task.
-#[derive(Clone)]
-pub struct ComputeStep {
-	/// The max frequency allows you to make it so a step won't run on every iteration. If provided, then this is the maximum number of times it will run per second. For instance, if a max frequency of 30 is given, then it will be at least 1000 / 30 = 16.67 ms between each run. When it's going through the steps, if it hasn't been at least 16.67 ms since the last time it ran, it won't run this time.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(from = "SerializedComputeStep<B>"))]
+pub struct ComputeStep<B = ShaderBufferHandle> {
+	/// The schedule allows you to make it so a step won't run on every iteration. It can be paced by the clock, with [MaxFrequencyHz](StepSchedule::MaxFrequencyHz), which is the maximum number of times it will run per second, or by the frame count, with [EveryNFrames](StepSchedule::EveryNFrames). For instance, if a max frequency of 30 is given, then it will run about every 1000 / 30 = 16.67 ms, whatever the refresh rate of the display. When it's going through the steps, if the next run isn't due yet, it won't run this time.
 	///
 	/// Compute shaders can sometimes be rather expensive, and use a lot of GPU resources. Not running them every frame can sometimes be a significant performance improvement. If you have a long-running compute task which is providing a real-time visualization, it can be a useful optimization to say that the steps that update the visuals run at a lower frequency. In the Game of Life example, if the game is running at full speed on a 120 Hz monitor, it can be very difficult to see what's going down, so the example slows it down to 10 Hz.
-	pub max_frequency: Option<NonZeroU32>,
+	///
+	/// With the `serde` feature, a step serialized before there was a schedule, with the old `max_frequency` field, is deserialized with a [MaxFrequencyHz](StepSchedule::MaxFrequencyHz) schedule of that frequency.
+	pub schedule: StepSchedule,
+
+	/// An optional condition for the step to run at all, read from a flag in the [ComputeStepFlags] resource, such as for a reset that only runs after the user presses a key, without restarting the task. While the condition isn't met, the step is skipped entirely, as if it wasn't in the task, so it costs nothing, and its schedule doesn't move on.
+	pub run_if: Option<StepCondition>,
 
 	/// This is the actual action to perform.
-	pub action: ComputeAction,
+	pub action: ComputeAction<B>,
+}
+
+/// A [ComputeStep] as it's deserialized, which also takes the old `max_frequency` field, in place of a schedule.
+#[cfg(feature = "serde")]
+#[derive(Deserialize)]
+struct SerializedComputeStep<B> {
+	#[serde(default)]
+	schedule: StepSchedule,
+
+	#[serde(default)]
+	max_frequency: Option<NonZeroU32>,
+
+	#[serde(default)]
+	run_if: Option<StepCondition>,
+
+	action: ComputeAction<B>,
+}
+
+#[cfg(feature = "serde")]
+impl<B> From<SerializedComputeStep<B>> for ComputeStep<B> {
+	fn from(step: SerializedComputeStep<B>) -> Self {
+		let schedule = match step.schedule {
+			StepSchedule::EveryIteration => step.max_frequency.into(),
+			schedule => schedule,
+		};
+		ComputeStep { schedule, run_if: step.run_if, action: step.action }
+	}
 }
 
 /// A compute action describes the specific action to take during a compute step.
-#[derive(Clone)]
-pub enum ComputeAction {
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum ComputeAction<B = ShaderBufferHandle> {
 	/// This action runs a specific shader.
 	RunShader {
 		/// The Bevy asset path to the shader file to run.
@@ -48,25 +434,727 @@ pub enum ComputeAction {
 		/// The name of the function to run in that shader file.
 		entry_point: String,
 
-		/// The workgroup count in the X dimension.
-		x_workgroup_count: u32,
+		/// Shader defs to preprocess the shader with, for its `#ifdef` and `#if` directives, and any `#{NAME}` substitutions. Every different set of defs makes a different pipeline, which is shared by every step that runs the same entry point of the same shader with the same defs.
+		#[cfg_attr(feature = "serde", serde(default))]
+		shader_defs: Vec<ComputeShaderDef>,
 
-		/// The workgroup count in the Y dimension.
-		y_workgroup_count: u32,
+		/// Values for `override` constants declared in the shader file, by name. Every different set of values makes a different pipeline. Any name not declared in the shader is reported with a [ComputeErrorEvent](crate::ComputeErrorEvent).
+		#[cfg_attr(feature = "serde", serde(default))]
+		overrides: Vec<(String, f64)>,
 
-		/// The workgroup count in the Z dimension.
-		z_workgroup_count: u32,
+		/// How many workgroups to dispatch.
+		dispatch: DispatchSize<B>,
+
+		/// Double buffers to swap once the dispatch is recorded, as if a [SwapBuffers](ComputeAction::SwapBuffers) step followed this one. These only swap when this step runs, so they can't fall out of step with the dispatch, the way a separate swap step with a different max frequency can.
+		#[cfg_attr(feature = "serde", serde(default = "Vec::new"))]
+		auto_swap: Vec<B>,
+
+		/// A buffer whose first `u32` counts what the step has to process, such as a counter from [add_counters](crate::ShaderBufferSet::add_counters) that an earlier step counts spawned particles into. While the count is zero, the dispatch isn't recorded, and neither are its auto swaps. The count is read back from the GPU, so it's a frame or two old, and the step can still run for a frame or two after the count drops to zero, or be skipped for a frame or two after it rises again, so the shader should still guard against an empty count if that matters. Until the count has been read back once, the step runs. The buffer must have [BufferUsages::COPY_SRC](bevy::render::render_resource::BufferUsages::COPY_SRC).
+		#[cfg_attr(feature = "serde", serde(default = "Option::default"))]
+		skip_if_empty: Option<B>,
+
+		/// Double buffers to bind the other way round for just this step, with their back buffer at the front binding and their front buffer at the back binding, when given [BufferOrientation::Reversed]. The bind groups for each set of reversed buffers are made once a frame, and again in each iteration after a swap. Every buffer here must be a double buffer.
+		#[cfg_attr(feature = "serde", serde(default = "Vec::new"))]
+		buffer_orientation: Vec<(B, BufferOrientation)>,
+
+		/// Push constants to set before the dispatch, for small parameters that change from one dispatch to the next without writing a buffer. See [PushConstants].
+		#[cfg_attr(feature = "serde", serde(default))]
+		push_constants: Option<PushConstants>,
+
+		/// Which element of each [dynamic uniform](crate::ShaderBufferSet::add_uniform_dynamic) buffer to bind for this dispatch, by index, which is turned into the dynamic offset its group is bound with, so steps can share one buffer of parameters, each reading its own element, without writing the buffer between them. Any dynamic uniform in a bound group that isn't given here binds its first element, as it does for every other kind of shader step. Each buffer must be a dynamic uniform, and each index less than the number of elements it holds, which is checked when the task starts.
+		#[cfg_attr(feature = "serde", serde(default = "Vec::new"))]
+		dynamic_offsets: Vec<(B, u32)>,
 	},
 
-	/// This action copies the contents of a buffer back to the CPU. When this runs, it will throw a [CopyBufferEvent](crate::CopyBufferEvent), which contains the data. This is fairly slow, and actually takes two iterations to run, because the data must first be copied into an intermediate buffer before being copied to the CPU. It's highly recommended that if this is on a compute task that runs for many iterations, it's run with a max frequency. But keep in mind that because it takes two iterations to run, the frequency with which you will recieve data will be half the specified frequency.
+	/// This action runs a specific shader once for every entity with a [ComputeBufferComponent](crate::ComputeBufferComponent), with that entity's buffer bound in the per-entity group, and all the other groups bound as usual. The dispatch size is for each entity's dispatch. At least one [PerEntity](crate::Binding::PerEntity) buffer must have been added to the [ShaderBufferSet] before the task starts, so the layout of the per-entity group is known, but entities can come and go while it runs.
+	RunShaderPerEntity {
+		/// The Bevy asset path to the shader file to run.
+		shader: String,
+
+		/// The name of the function to run in that shader file.
+		entry_point: String,
+
+		/// Shader defs to preprocess the shader with, for its `#ifdef` and `#if` directives, and any `#{NAME}` substitutions.
+		#[cfg_attr(feature = "serde", serde(default))]
+		shader_defs: Vec<ComputeShaderDef>,
+
+		/// Values for `override` constants declared in the shader file, by name. Every different set of values makes a different pipeline. Any name not declared in the shader is reported with a [ComputeErrorEvent](crate::ComputeErrorEvent).
+		#[cfg_attr(feature = "serde", serde(default))]
+		overrides: Vec<(String, f64)>,
+
+		/// How many workgroups to dispatch.
+		dispatch: DispatchSize<B>,
+	},
+
+	/// This action runs a specific shader with its workgroup counts read from a buffer on the GPU, such as one an earlier step filled in with the number of particles emitted this frame, so the CPU never needs to know how much work there is. The counts are three `u32`s, for the X, Y and Z dimensions, which is the layout of the buffer [add_dispatch_indirect_args](crate::ShaderBufferSet::add_dispatch_indirect_args) adds, though any storage buffer made with [BufferUsages::INDIRECT](bevy::render::render_resource::BufferUsages::INDIRECT) will do. Each count can be at most 65535, as for any dispatch, which can't be checked, as the CPU never sees the counts. The buffer is checked when the task starts, which panics if it doesn't exist, is a texture or a double buffer, wasn't made with the INDIRECT usage, or is too small to hold the counts at the offset.
+	RunShaderIndirect {
+		/// The Bevy asset path to the shader file to run.
+		shader: String,
+
+		/// The name of the function to run in that shader file.
+		entry_point: String,
+
+		/// Shader defs to preprocess the shader with, for its `#ifdef` and `#if` directives, and any `#{NAME}` substitutions.
+		#[cfg_attr(feature = "serde", serde(default))]
+		shader_defs: Vec<ComputeShaderDef>,
+
+		/// Values for `override` constants declared in the shader file, by name. Every different set of values makes a different pipeline. Any name not declared in the shader is reported with a [ComputeErrorEvent](crate::ComputeErrorEvent).
+		#[cfg_attr(feature = "serde", serde(default))]
+		overrides: Vec<(String, f64)>,
+
+		/// The buffer to read the workgroup counts from.
+		indirect_buffer: B,
+
+		/// Where the workgroup counts start in the buffer, in bytes, which must be a multiple of 4.
+		#[cfg_attr(feature = "serde", serde(default))]
+		offset: u64,
+	},
+
+	/// This action runs several entry points of one shader file back to back, in the order given, each with its own dispatch size, such as the passes of a multi-pass filter. They're all preprocessed with the same shader defs, so they share one shader module, and they're all bound to the same bind groups, so only the pipeline changes between the dispatches. Chunked dispatches aren't supported here, which is checked when the task starts.
+	RunShaderMulti {
+		/// The Bevy asset path to the shader file to run.
+		shader: String,
+
+		/// Shader defs to preprocess the shader with, for its `#ifdef` and `#if` directives, and any `#{NAME}` substitutions.
+		#[cfg_attr(feature = "serde", serde(default))]
+		shader_defs: Vec<ComputeShaderDef>,
+
+		/// The name of each entry point to run, and how many workgroups to dispatch for it.
+		passes: Vec<(String, DispatchSize<B>)>,
+	},
+
+	/// This action copies the contents of a buffer or texture back to the CPU. Textures are copied row by row, with the padding the GPU needs between rows stripped out, and can be written into an [Image](bevy::image::Image) with a [WriteBackToImageEvent](crate::WriteBackToImageEvent). When this runs, it will throw a [CopyBufferEvent](crate::CopyBufferEvent), which contains the data. This is fairly slow, and actually takes two iterations to run, because the data must first be copied into an intermediate buffer before being copied to the CPU. It's highly recommended that if this is on a compute task that runs for many iterations, it's run with a max frequency. But keep in mind that because it takes two iterations to run, the frequency with which you will recieve data will be half the specified frequency.
 	CopyBuffer {
 		/// The buffer to copy out of.
-		src: ShaderBufferHandle,
+		src: B,
+
+		/// Where the part of a storage buffer to read back starts, in bytes, for reading back only a small part of a big buffer, such as a counter at the front of a buffer of particles, without copying the rest of it. The copy buffer is only as big as the part, and only that part is copied and sent back in the [CopyBufferEvent](crate::CopyBufferEvent). This is checked when the task starts, which panics if it isn't a multiple of 4, or the part doesn't fit in the buffer, or the source is a texture, which can only be read back whole.
+		#[cfg_attr(feature = "serde", serde(default))]
+		offset: u64,
+
+		/// How many bytes of a storage buffer to read back, which must be a multiple of 4, or `None` for everything from `offset` on.
+		#[cfg_attr(feature = "serde", serde(default))]
+		size: Option<u64>,
+	},
+
+	/// This action copies part or all of one storage buffer into another on the GPU, such as to snapshot a buffer a shader accumulates into every few iterations, for a later task to read while the accumulation carries on. Nothing is read back to the CPU, so this is as cheap as a copy gets. The source must have been made with [BufferUsages::COPY_SRC](bevy::render::render_resource::BufferUsages::COPY_SRC), and every storage buffer has [BufferUsages::COPY_DST](bevy::render::render_resource::BufferUsages::COPY_DST). If either is a double buffer, the front buffer is used. The buffers are checked when the task starts, which panics if either doesn't exist or isn't a storage buffer, the source is missing the usage, they're the same buffer, the offsets or size aren't multiples of 4, or the copied range doesn't fit in either buffer.
+	CopyBufferToBuffer {
+		/// The buffer to copy out of.
+		src: B,
+
+		/// The buffer to copy into.
+		dst: B,
+
+		/// Where the copy starts in the source, in bytes.
+		#[cfg_attr(feature = "serde", serde(default))]
+		src_offset: u64,
+
+		/// Where the copy starts in the destination, in bytes.
+		#[cfg_attr(feature = "serde", serde(default))]
+		dst_offset: u64,
+
+		/// How many bytes to copy, or `None` for everything in the source from `src_offset` on.
+		#[cfg_attr(feature = "serde", serde(default))]
+		size: Option<u64>,
+	},
+
+	/// This action copies every texel of a storage texture into a storage buffer on the GPU, such as to let a shader index the last frame's image as a flat array, with atomics. The texels are packed row by row, then layer by layer, with no padding between rows, so texel `(x, y, z)` starts at byte `((z * height + y) * width + x)` times the bytes per texel of the texture's format. The GPU can only copy whole textures with each row padded out to 256 bytes, so a texture whose rows are a multiple of that is copied in one go, and any other a row at a time, without a buffer of padded rows in between. If either is a double buffer, the front buffer is used. The buffers are checked when the task starts, which panics if the texture doesn't exist or isn't a storage texture, the buffer doesn't exist or isn't a storage buffer, or the buffer is too small for the texture, giving the number of bytes it needs.
+	CopyTextureToBuffer {
+		/// The texture to copy out of.
+		src: B,
+
+		/// The buffer to copy into.
+		dst: B,
+	},
+
+	/// This action copies the contents of a storage buffer into every texel of a storage texture on the GPU, laid out the same way as a [CopyTextureToBuffer](ComputeAction::CopyTextureToBuffer) step lays them out, such as to show a buffer a shader accumulated into with atomics as an image. The buffer must have been made with [BufferUsages::COPY_SRC](bevy::render::render_resource::BufferUsages::COPY_SRC), and is checked along with the texture when the task starts, the same way.
+	CopyBufferToTexture {
+		/// The buffer to copy out of.
+		src: B,
+
+		/// The texture to copy into.
+		dst: B,
+	},
+
+	/// This action fills part or all of a storage or uniform buffer with zeros on the GPU, such as to reset the counters of a spatial hash before the pass that bins into them every iteration, without a shader entry point of its own. It's recorded with the command encoder's `clear_buffer`, so it costs about as much as a copy. The buffer is checked when the task starts, which panics if it doesn't exist or isn't a storage or uniform buffer, the offset or size isn't a multiple of 4, or the range doesn't fit in it.
+	ClearBuffer {
+		/// The buffer to clear.
+		buffer: B,
+
+		/// Where the cleared range starts, in bytes.
+		#[cfg_attr(feature = "serde", serde(default))]
+		offset: u64,
+
+		/// How many bytes to clear, or `None` for everything from `offset` on.
+		#[cfg_attr(feature = "serde", serde(default))]
+		size: Option<u64>,
+
+		/// Whether only the back buffer of a double buffer is cleared, which is the default, or both halves.
+		#[cfg_attr(feature = "serde", serde(default))]
+		halves: ClearHalves,
+	},
+
+	/// This action fills every texel of a storage texture with one value, such as to reset an accumulation texture, on the GPU. The value is copied over the texture from a buffer of it made when the task starts, a band of rows at a time, so any uncompressed color format of 8, 16 or 32 bits a channel can be filled, without the `CLEAR_TEXTURE` feature. Every layer of a texture array, and every slice of a 3D texture, is filled. The texture is checked when the task starts, which panics if it doesn't exist, isn't a storage texture, or has a format that can't be filled.
+	ClearTexture {
+		/// The texture to clear.
+		texture: B,
+
+		/// The value to set each channel to, in RGBA order, with the channels past the texture's ignored. Normalized formats take values from 0 to 1, or -1 to 1 for signed ones, and integer formats take the value truncated to an integer, so every `u32` and `i32` can be given exactly.
+		#[cfg_attr(feature = "serde", serde(default))]
+		value: [f64; 4],
+
+		/// Whether only the back buffer of a double buffer is cleared, which is the default, or both halves.
+		#[cfg_attr(feature = "serde", serde(default))]
+		halves: ClearHalves,
+	},
+
+	/// This action reads the contents of a buffer or texture back to the CPU every time it runs, without stalling the GPU or skipping any reads, so a task can watch something like a particle count every iteration. Each read is copied into a buffer of its own, taken from a pool, mapped once the frame has been submitted, and delivered a frame or more later as a [BufferReadbackEvent](crate::BufferReadbackEvent), along with a [CopyBufferEvent](crate::CopyBufferEvent), in the order they were made. Unlike a [CopyBuffer](ComputeAction::CopyBuffer), it runs in every iteration, even when several run in a frame, so reads queue up while the GPU is ahead of them, and a task that hits its iteration limit waits for all of them before sending its [ComputeTaskDoneEvent](crate::ComputeTaskDoneEvent). Textures come back row by row, with no padding between rows.
+	ReadBuffer {
+		/// The buffer to read back.
+		buffer: B,
 	},
 
 	/// This action swaps a double buffer. The front buffer becomes the back buffer, and vice-versa. This swaps which bindings they use, which buffer's data will be returned on a [CopyBuffer](ComputeAction::CopyBuffer), and if this is a texture, which texture buffer's image handle will be returned on a call to [image_handle](crate::ShaderBufferSet::image_handle).
 	SwapBuffers {
 		/// The double buffer to swap.
-		buffer: ShaderBufferHandle,
+		buffer: B,
+	},
+
+	/// This action rotates a [ring](crate::Binding::Ring), moving its position on to its next buffer, or back to its first after the last. The buffers stay at their bindings, so nothing is bound again, and shader steps after this one, even in the same iteration, see the new position in the [ring_positions](crate::ComputeGlobals::ring_positions) of the globals. Steps that act on the ring's current buffer themselves, such as a [CopyBufferToBuffer](ComputeAction::CopyBufferToBuffer), follow it from the next iteration on, as they do a swap. Rings can share a group with double buffers, and be rotated in the same tasks as they're swapped.
+	RotateBuffers {
+		/// The ring to rotate.
+		buffer: B,
+	},
+
+	/// This action sorts the elements of a storage buffer on the GPU, in ascending order of their keys, using a bitonic sort. This is run with shaders built into this crate, and expands into log2(n) * (log2(n) + 1) / 2 dispatches, where n is the element count rounded up to a power of two. The element count doesn't need to be a power of two, and only the first `element_count` elements of the buffer are touched. If this is a double buffer, the front buffer is sorted.
+	SortBuffer {
+		/// The buffer to sort.
+		buffer: B,
+
+		/// The number of elements to sort.
+		element_count: u32,
+
+		/// The type of the key, and where it is in each element.
+		key: SortKey,
+	},
+
+	/// This action reduces every element of a storage buffer to a single value, such as their sum or maximum, on the GPU, and writes it to the front of another storage buffer, for instance to read back the total mass or the fastest speed of a simulation with a [CopyBuffer](ComputeAction::CopyBuffer). This is run with shaders built into this crate, and expands into one dispatch for every factor of 512 in the element count, with a scratch buffer managed internally. The whole source buffer is reduced, so its size is what sets the element count, which doesn't need to be a power of two. If either is a double buffer, the front buffer is used.
+	ReduceBuffer {
+		/// The buffer to reduce.
+		src: B,
+
+		/// The buffer to write the result to. Only its first element is written.
+		dst: B,
+
+		/// How the elements are combined.
+		op: ReduceOp,
+
+		/// The type of the elements.
+		element: ReduceElement,
+	},
+
+	/// This action replaces every element of a storage buffer of `u32`s with the sum of the elements before it, an exclusive prefix sum, on the GPU, as used for stream compaction or to find where each bucket of a counting sort begins. This is run with shaders built into this crate, which scan the buffer in blocks of 512 elements, then scan the totals of those blocks, and add them back, with the scratch buffers for the totals managed internally. Any element count works, whether or not it's a multiple of the block size. Sums wrap on overflow. If the buffer is a double buffer, the front buffer is scanned.
+	PrefixSum {
+		/// The buffer to scan, in place.
+		buffer: B,
+
+		/// The number of elements to scan, from the start of the buffer.
+		element_count: u32,
+	},
+
+	/// This action counts the values of a buffer or texture into equal bins spanning a range, with atomics, so a histogram of something like the densities of a fluid simulation can be read back without reading back every value. The bins are cleared first, so they only hold the counts from this run. This is run with shaders built into this crate, in two dispatches. The bins are a buffer of `u32`s, which is easiest to make with [add_counters](crate::ShaderBufferSet::add_counters), and read back with a [CopyBuffer](ComputeAction::CopyBuffer). A value equal to the top of the range counts in the last bin, and NaNs are never counted. If either is a double buffer, the front buffer is used.
+	Histogram {
+		/// The buffer or texture to read the values from. See [HistogramSource].
+		src: B,
+
+		/// The storage buffer to count into, which must hold at least `bin_count` `u32`s.
+		bins: B,
+
+		/// The number of bins.
+		bin_count: u32,
+
+		/// The lowest and highest values covered by the bins.
+		range: (f32, f32),
+
+		/// Whether the values are read from a buffer or a texture.
+		source: HistogramSource,
+
+		/// What to do with values outside the range.
+		out_of_range: HistogramOutOfRange,
+	},
+
+	/// This action convolves a float texture with a kernel, such as a gaussian blur of a simulation field, along its rows and then its columns, which is how separable kernels like blurs are applied. This is run with shaders built into this crate, in two dispatches, with the rows written to an intermediate buffer owned by the step, so the destination can be the source itself. The destination must be the same size as the source, and an [R32Float](bevy::render::render_resource::TextureFormat::R32Float), [Rgba16Float](bevy::render::render_resource::TextureFormat::Rgba16Float) or [Rgba32Float](bevy::render::render_resource::TextureFormat::Rgba32Float) texture, which are the float formats every GPU can write. If either is a double buffer, the front buffer is used.
+	Convolve {
+		/// The texture to read.
+		src: B,
+
+		/// The texture to write the result to, which can be the same as `src`.
+		dst: B,
+
+		/// The weights to apply along each axis.
+		kernel: ConvolutionKernel,
+
+		/// How texels past the edges of the texture are read.
+		edges: ConvolutionEdges,
+	},
+
+	/// This action scans a buffer of `f32`s or a single-channel texture for NaNs and infinities, to find where a simulation blows up. Put one after each step that might be at fault. If it finds a value that isn't finite, a [ComputeErrorEvent::NonFiniteValue](crate::ComputeErrorEvent::NonFiniteValue) is sent naming the task, this step, the iteration, and the index and value of the first one, and an error is logged. Each step only reports once per compute sequence, and stops checking after that. This is run with shaders built into this crate, in three dispatches, and the result is read back automatically over the following frames, so the event can arrive an iteration or two after the corruption. It only runs while [ComputeFiniteChecks](crate::ComputeFiniteChecks) is enabled, which it is by default in debug builds, and otherwise costs nothing. If the buffer is a double buffer, the front buffer is checked.
+	CheckFinite {
+		/// The buffer or texture to check.
+		buffer: B,
+
+		/// Whether it's a buffer or a texture.
+		element: FiniteCheckType,
+	},
+}
+
+/// A shader def for preprocessing a compute shader, the same way Bevy preprocesses its own shaders. A def can be tested with `#ifdef NAME`, compared with `#if NAME == 3`, and its value substituted with `#{NAME}`.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum ComputeShaderDef {
+	/// A def which is either defined or not. Defs converted from a bare name are this, set to true.
+	Bool(String, bool),
+
+	/// A signed integer def, which is an `i32` when substituted.
+	Int(String, i32),
+
+	/// An unsigned integer def, which is a `u32` when substituted.
+	UInt(String, u32),
+}
+
+impl ComputeShaderDef {
+	/// The name of the def.
+	pub fn name(&self) -> &str {
+		match self {
+			ComputeShaderDef::Bool(name, _) | ComputeShaderDef::Int(name, _) | ComputeShaderDef::UInt(name, _) => name,
+		}
+	}
+}
+
+/// How many workgroups a shader step is dispatched with.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum DispatchSize<B = ShaderBufferHandle> {
+	/// Dispatch exactly this many workgroups in each dimension.
+	Workgroups {
+		/// The workgroup count in the X dimension.
+		x: u32,
+
+		/// The workgroup count in the Y dimension.
+		y: u32,
+
+		/// The workgroup count in the Z dimension.
+		z: u32,
+	},
+
+	/// Dispatch enough workgroups to cover this many invocations in each dimension, such as the size of the texture being processed. The workgroup size is read from the entry point's `@workgroup_size` attribute, including any override constants the step sets, and the workgroup counts are rounded up, so the shader should ignore invocations that are out of bounds. Each dimension of the workgroup size must be a literal, a `#{NAME}` shader def, or a `const` or `override` declared in the same file.
+	Exact {
+		/// The number of invocations in the X dimension.
+		x: u32,
+
+		/// The number of invocations in the Y dimension.
+		y: u32,
+
+		/// The number of invocations in the Z dimension.
+		z: u32,
 	},
+
+	/// Dispatch this many workgroups in each dimension, split into chunks of at most so many workgroups each, for grids too big for one dispatch, which can be at most 65535 workgroups in each dimension, or work to spread over several iterations. The chunks are dispatched one after another, X first, with the ones at the far edges of the grid only as big as what's left over. Before each chunk, the offset of its first invocation is written to the chunk uniform, added with [add_chunk_uniform](crate::ShaderBufferSet::add_chunk_uniform), which the shader adds to its `global_invocation_id` to find where it is in the whole grid. This needs the workgroup size, which is read from the entry point's `@workgroup_size` attribute, as with [Exact](DispatchSize::Exact). Panics if the chunk uniform hasn't been added, or if any dimension of `max_per_dispatch` is zero.
+	Chunked {
+		/// The total number of workgroups in the X, Y and Z dimensions.
+		total: (u32, u32, u32),
+
+		/// The most workgroups in each dimension of one chunk.
+		max_per_dispatch: (u32, u32, u32),
+
+		/// If provided, only this many chunks are dispatched each iteration, carrying on from where the last iteration left off, and wrapping back around to the first chunk, so the grid is covered over several iterations. Along with a [FrameBudget], this spreads the work over as many frames as it takes. If this isn't provided, every chunk is dispatched every iteration.
+		chunks_per_iteration: Option<NonZeroU32>,
+	},
+
+	/// Dispatch enough workgroups to cover every texel of a storage texture, as an [Exact](DispatchSize::Exact) dispatch of its width, height and depth or number of layers would, with the workgroup size read from the entry point the same way. The texture's size is looked up again every frame the step runs, and the workgroup counts worked out again whenever it's changed, so nothing is dispatched for a texture added with [add_texture_from_handle](crate::ShaderBufferSet::add_texture_from_handle) while its image is still loading, and the whole image is covered once it has. For a double buffered texture, the size of its front buffer is used, though both halves are always the same size. A texture that's remade at a new size has a new handle, which an [UpdateDispatchSizeEvent](crate::UpdateDispatchSizeEvent) can switch the dispatch over to. The texture is checked when the task starts, which panics if it doesn't exist or isn't a storage texture.
+	FromTexture {
+		/// The storage texture to cover.
+		buffer: B,
+	},
+}
+
+impl<B> DispatchSize<B> {
+	/// The number of workgroups to dispatch, for an entry point with the given workgroup size. A [FromTexture](DispatchSize::FromTexture) dispatch has none until it's been made [Exact](DispatchSize::Exact) with its texture's size, which the compute node does before every frame it runs.
+	pub fn workgroup_count(&self, workgroup_size: UVec3) -> UVec3 {
+		match *self {
+			DispatchSize::Workgroups { x, y, z } => UVec3::new(x, y, z),
+			DispatchSize::Exact { x, y, z } => {
+				UVec3::new(x.div_ceil(workgroup_size.x), y.div_ceil(workgroup_size.y), z.div_ceil(workgroup_size.z))
+			}
+			DispatchSize::Chunked { total, .. } => UVec3::from(total),
+			DispatchSize::FromTexture { .. } => UVec3::ZERO,
+		}
+	}
+
+	/// Whether the workgroup size of the entry point is needed to work out the workgroup count, which it is for every dispatch but [Workgroups](DispatchSize::Workgroups).
+	pub(crate) fn needs_workgroup_size(&self) -> bool { !matches!(self, DispatchSize::Workgroups { .. }) }
+
+	/// Convert the texture of a [FromTexture](DispatchSize::FromTexture) dispatch to another type, with the provided function. See [ComputeTask::map_buffers].
+	pub fn map_buffers<C, E>(&self, mut f: impl FnMut(&B) -> Result<C, E>) -> Result<DispatchSize<C>, E> {
+		Ok(match self {
+			DispatchSize::Workgroups { x, y, z } => DispatchSize::Workgroups { x: *x, y: *y, z: *z },
+			DispatchSize::Exact { x, y, z } => DispatchSize::Exact { x: *x, y: *y, z: *z },
+			DispatchSize::Chunked { total, max_per_dispatch, chunks_per_iteration } => DispatchSize::Chunked {
+				total: *total,
+				max_per_dispatch: *max_per_dispatch,
+				chunks_per_iteration: *chunks_per_iteration,
+			},
+			DispatchSize::FromTexture { buffer } => DispatchSize::FromTexture { buffer: f(buffer)? },
+		})
+	}
+
+	/// The chunks a [Chunked](DispatchSize::Chunked) dispatch is split into, in the order they're dispatched, as the offset of the first invocation of each, for an entry point with the given workgroup size, and the number of workgroups in it. Any other dispatch is a single chunk, with no offset.
+	pub fn chunks(&self, workgroup_size: UVec3) -> Vec<(UVec3, UVec3)> {
+		let DispatchSize::Chunked { total, max_per_dispatch, .. } = *self else {
+			return vec![(UVec3::ZERO, self.workgroup_count(workgroup_size))];
+		};
+		let (total, max) = (UVec3::from(total), UVec3::from(max_per_dispatch));
+		if max.min_element() == 0 {
+			panic!("Tried to split a dispatch into chunks of {} workgroups, which is zero in at least one dimension", max);
+		}
+		let mut chunks = Vec::new();
+		for z in (0..total.z).step_by(max.z as usize) {
+			for y in (0..total.y).step_by(max.y as usize) {
+				for x in (0..total.x).step_by(max.x as usize) {
+					let origin = UVec3::new(x, y, z);
+					chunks.push((origin * workgroup_size, max.min(total - origin)));
+				}
+			}
+		}
+		chunks
+	}
+}
+
+impl DispatchSize {
+	/// This dispatch, with a [FromTexture](DispatchSize::FromTexture) one made [Exact](DispatchSize::Exact) over its texture's current size, which is nothing while the texture is still loading, returning what's wrong if the texture can't be dispatched over.
+	pub(crate) fn over_texture(&self, buffers: &ShaderBufferSet) -> Result<DispatchSize, String> {
+		let DispatchSize::FromTexture { buffer } = *self else {
+			return Ok(*self);
+		};
+		let UVec3 { x, y, z } = buffers.dispatch_texture_size(buffer)?.unwrap_or(UVec3::ZERO);
+		Ok(DispatchSize::Exact { x, y, z })
+	}
+
+	/// The texture of a [FromTexture](DispatchSize::FromTexture) dispatch, whose size it's worked out from.
+	pub(crate) fn texture(&self) -> Option<ShaderBufferHandle> {
+		match *self {
+			DispatchSize::FromTexture { buffer } => Some(buffer),
+			_ => None,
+		}
+	}
+}
+
+/// Check that the passes of a [RunShaderMulti](ComputeAction::RunShaderMulti) step can be run, returning what's wrong if they can't, which the compute node panics with when the step's task starts.
+pub(crate) fn check_shader_passes<B>(shader: &str, passes: &[(String, DispatchSize<B>)]) -> Result<(), String> {
+	if passes.is_empty() {
+		return Err(format!("Tried to run several entry points of {}, but no passes were given", shader));
+	}
+	match passes.iter().find(|(_, dispatch)| matches!(dispatch, DispatchSize::Chunked { .. })) {
+		Some((entry_point, _)) => Err(format!(
+			"Tried to run {} in {} with a chunked dispatch, but the passes of a RunShaderMulti step can't be chunked",
+			entry_point, shader
+		)),
+		None => Ok(()),
+	}
+}
+
+impl From<&str> for ComputeShaderDef {
+	fn from(name: &str) -> Self { ComputeShaderDef::Bool(name.to_owned(), true) }
+}
+
+impl From<String> for ComputeShaderDef {
+	fn from(name: String) -> Self { ComputeShaderDef::Bool(name, true) }
+}
+
+impl From<ComputeShaderDef> for ShaderDefVal {
+	fn from(def: ComputeShaderDef) -> Self {
+		match def {
+			ComputeShaderDef::Bool(name, value) => ShaderDefVal::Bool(name, value),
+			ComputeShaderDef::Int(name, value) => ShaderDefVal::Int(name, value),
+			ComputeShaderDef::UInt(name, value) => ShaderDefVal::UInt(name, value),
+		}
+	}
 }
+
+impl Display for ComputeShaderDef {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		match self {
+			ComputeShaderDef::Bool(name, true) => write!(f, "{}", name),
+			ComputeShaderDef::Bool(name, false) => write!(f, "!{}", name),
+			ComputeShaderDef::Int(name, value) => write!(f, "{}={}", name, value),
+			ComputeShaderDef::UInt(name, value) => write!(f, "{}={}u", name, value),
+		}
+	}
+}
+
+impl<B> ComputeTask<B> {
+	/// Make a task for the ping-pong pattern, with a single step that runs a shader reading the front buffers of some double buffers and writing their back buffers, then swaps them with [auto_swap](ComputeAction::RunShader::auto_swap), so each iteration reads what the last one wrote. Every buffer must be a double buffer, which is checked when the task starts. The task can be adjusted from there like any other, such as with [with_schedule](ComputeTask::with_schedule).
+	///
+	/// ```Rust
+	/// let update = ComputeTask::ping_pong("Update", "shaders/life.wgsl", "update", DispatchSize::Exact { x: 256, y: 256, z: 1 }, &[cells], None);
+	/// ```
+	pub fn ping_pong(
+		label: &str, shader: &str, entry_point: &str, dispatch: DispatchSize<B>, buffers: &[B],
+		iterations: Option<NonZeroU32>,
+	) -> Self
+	where
+		B: Clone,
+	{
+		if buffers.is_empty() {
+			panic!("Tried to make the ping-pong task {}, but it has no double buffers to swap", label);
+		}
+		ComputeTask {
+			label: Some(label.to_owned()),
+			iterations,
+			run_mode: TaskRunMode::Continuous,
+			budget: None,
+			submission: TaskSubmission::WithFrame,
+			start_after: None,
+			iteration_buffer: None,
+			steps: vec![ComputeStep {
+				schedule: StepSchedule::EveryIteration,
+				run_if: None,
+				action: ComputeAction::RunShader {
+					shader: shader.to_owned(),
+					entry_point: entry_point.to_owned(),
+					shader_defs: Vec::new(),
+					overrides: Vec::new(),
+					dispatch,
+					auto_swap: buffers.to_vec(),
+					skip_if_empty: None,
+					buffer_orientation: Vec::new(),
+					push_constants: None,
+					dynamic_offsets: Vec::new(),
+				},
+			}],
+		}
+	}
+
+	/// Set the [schedule](ComputeStep::schedule) of every step in this task, so they all run at the same rate.
+	pub fn with_schedule(mut self, schedule: StepSchedule) -> Self {
+		for step in self.steps.iter_mut() {
+			step.schedule = schedule;
+		}
+		self
+	}
+
+	/// Set every step in this task to run at most `max_frequency` times a second, or in every iteration if it's `None`.
+	#[deprecated(note = "use `with_schedule` with a `StepSchedule::MaxFrequencyHz`")]
+	pub fn with_max_frequency(self, max_frequency: Option<NonZeroU32>) -> Self {
+		self.with_schedule(max_frequency.into())
+	}
+
+	/// Run the shaders of this task against the groups of a [BindingNamespace], by adding its [shader_defs](BindingNamespace::shader_defs) to every shader step, so a shader that declares its bindings with `@group(#{GROUP_0})` and so on binds the groups the namespace reserved.
+	pub fn with_namespace(mut self, namespace: &BindingNamespace) -> Self {
+		for step in self.steps.iter_mut() {
+			if let ComputeAction::RunShader { shader_defs, .. }
+			| ComputeAction::RunShaderPerEntity { shader_defs, .. }
+			| ComputeAction::RunShaderIndirect { shader_defs, .. }
+			| ComputeAction::RunShaderMulti { shader_defs, .. } = &mut step.action
+			{
+				shader_defs.extend(namespace.shader_defs());
+			}
+		}
+		self
+	}
+
+	/// Convert every buffer reference in this task to another type, with the provided function. If the function fails on any buffer, the first error is returned. This is useful for converting handles to names or indices before serializing a task.
+	pub fn map_buffers<C, E>(&self, mut f: impl FnMut(&B) -> Result<C, E>) -> Result<ComputeTask<C>, E> {
+		Ok(ComputeTask {
+			label: self.label.clone(),
+			iterations: self.iterations,
+			run_mode: self.run_mode,
+			budget: self.budget,
+			submission: self.submission,
+			start_after: self.start_after,
+			iteration_buffer: self.iteration_buffer.as_ref().map(|buffer| buffer.map_buffers(&mut f)).transpose()?,
+			steps: self.steps.iter().map(|step| step.map_buffers(&mut f)).collect::<Result<_, _>>()?,
+		})
+	}
+
+	/// Convert every buffer key in this task to a [ShaderBufferHandle] using the provided resolver. If any key can't be resolved, an error naming that key is returned.
+	pub fn resolve(&self, resolver: &impl BufferResolver<B>) -> Result<ComputeTask, UnresolvedBufferError<B>>
+	where
+		B: Clone,
+	{
+		self.map_buffers(|key| resolver.resolve(key).ok_or_else(|| UnresolvedBufferError { key: key.clone() }))
+	}
+}
+
+impl<B> ComputeStep<B> {
+	/// Convert every buffer reference in this step to another type, with the provided function. See [ComputeTask::map_buffers].
+	pub fn map_buffers<C, E>(&self, f: impl FnMut(&B) -> Result<C, E>) -> Result<ComputeStep<C>, E> {
+		Ok(ComputeStep { schedule: self.schedule, run_if: self.run_if.clone(), action: self.action.map_buffers(f)? })
+	}
+}
+
+impl<B> ComputeAction<B> {
+	/// The dispatch size of an action that runs one shader with one, which is what an [UpdateDispatchSizeEvent](crate::UpdateDispatchSizeEvent) can change.
+	pub(crate) fn dispatch(&self) -> Option<DispatchSize<B>>
+	where
+		B: Clone,
+	{
+		match self {
+			ComputeAction::RunShader { dispatch, .. } | ComputeAction::RunShaderPerEntity { dispatch, .. } => {
+				Some(dispatch.clone())
+			}
+			_ => None,
+		}
+	}
+
+	/// The dispatch size of an action that runs one shader with one, to change it.
+	pub(crate) fn dispatch_mut(&mut self) -> Option<&mut DispatchSize<B>> {
+		match self {
+			ComputeAction::RunShader { dispatch, .. } | ComputeAction::RunShaderPerEntity { dispatch, .. } => Some(dispatch),
+			_ => None,
+		}
+	}
+
+	/// Convert every buffer reference in this action to another type, with the provided function. See [ComputeTask::map_buffers].
+	pub fn map_buffers<C, E>(&self, mut f: impl FnMut(&B) -> Result<C, E>) -> Result<ComputeAction<C>, E> {
+		Ok(match self {
+			ComputeAction::RunShader {
+				shader,
+				entry_point,
+				shader_defs,
+				overrides,
+				dispatch,
+				auto_swap,
+				skip_if_empty,
+				buffer_orientation,
+				push_constants,
+				dynamic_offsets,
+			} => ComputeAction::RunShader {
+				shader: shader.clone(),
+				entry_point: entry_point.clone(),
+				shader_defs: shader_defs.clone(),
+				overrides: overrides.clone(),
+				dispatch: dispatch.map_buffers(&mut f)?,
+				auto_swap: auto_swap.iter().map(&mut f).collect::<Result<_, _>>()?,
+				skip_if_empty: skip_if_empty.as_ref().map(&mut f).transpose()?,
+				buffer_orientation: buffer_orientation
+					.iter()
+					.map(|(buffer, orientation)| Ok((f(buffer)?, *orientation)))
+					.collect::<Result<_, _>>()?,
+				push_constants: push_constants.clone(),
+				dynamic_offsets: dynamic_offsets
+					.iter()
+					.map(|(buffer, element)| Ok((f(buffer)?, *element)))
+					.collect::<Result<_, _>>()?,
+			},
+			ComputeAction::RunShaderPerEntity { shader, entry_point, shader_defs, overrides, dispatch } => {
+				ComputeAction::RunShaderPerEntity {
+					shader: shader.clone(),
+					entry_point: entry_point.clone(),
+					shader_defs: shader_defs.clone(),
+					overrides: overrides.clone(),
+					dispatch: dispatch.map_buffers(&mut f)?,
+				}
+			}
+			ComputeAction::RunShaderIndirect { shader, entry_point, shader_defs, overrides, indirect_buffer, offset } => {
+				ComputeAction::RunShaderIndirect {
+					shader: shader.clone(),
+					entry_point: entry_point.clone(),
+					shader_defs: shader_defs.clone(),
+					overrides: overrides.clone(),
+					indirect_buffer: f(indirect_buffer)?,
+					offset: *offset,
+				}
+			}
+			ComputeAction::RunShaderMulti { shader, shader_defs, passes } => ComputeAction::RunShaderMulti {
+				shader: shader.clone(),
+				shader_defs: shader_defs.clone(),
+				passes: passes
+					.iter()
+					.map(|(entry_point, dispatch)| Ok((entry_point.clone(), dispatch.map_buffers(&mut f)?)))
+					.collect::<Result<_, _>>()?,
+			},
+			ComputeAction::CopyBuffer { src, offset, size } => {
+				ComputeAction::CopyBuffer { src: f(src)?, offset: *offset, size: *size }
+			}
+			ComputeAction::CopyBufferToBuffer { src, dst, src_offset, dst_offset, size } => {
+				ComputeAction::CopyBufferToBuffer {
+					src: f(src)?,
+					dst: f(dst)?,
+					src_offset: *src_offset,
+					dst_offset: *dst_offset,
+					size: *size,
+				}
+			}
+			ComputeAction::CopyTextureToBuffer { src, dst } => {
+				ComputeAction::CopyTextureToBuffer { src: f(src)?, dst: f(dst)? }
+			}
+			ComputeAction::CopyBufferToTexture { src, dst } => {
+				ComputeAction::CopyBufferToTexture { src: f(src)?, dst: f(dst)? }
+			}
+			ComputeAction::ClearBuffer { buffer, offset, size, halves } => {
+				ComputeAction::ClearBuffer { buffer: f(buffer)?, offset: *offset, size: *size, halves: *halves }
+			}
+			ComputeAction::ClearTexture { texture, value, halves } => {
+				ComputeAction::ClearTexture { texture: f(texture)?, value: *value, halves: *halves }
+			}
+			ComputeAction::ReadBuffer { buffer } => ComputeAction::ReadBuffer { buffer: f(buffer)? },
+			ComputeAction::SwapBuffers { buffer } => ComputeAction::SwapBuffers { buffer: f(buffer)? },
+			ComputeAction::RotateBuffers { buffer } => ComputeAction::RotateBuffers { buffer: f(buffer)? },
+			ComputeAction::SortBuffer { buffer, element_count, key } => {
+				ComputeAction::SortBuffer { buffer: f(buffer)?, element_count: *element_count, key: *key }
+			}
+			ComputeAction::ReduceBuffer { src, dst, op, element } => {
+				ComputeAction::ReduceBuffer { src: f(src)?, dst: f(dst)?, op: *op, element: *element }
+			}
+			ComputeAction::PrefixSum { buffer, element_count } => {
+				ComputeAction::PrefixSum { buffer: f(buffer)?, element_count: *element_count }
+			}
+			ComputeAction::Histogram { src, bins, bin_count, range, source, out_of_range } => ComputeAction::Histogram {
+				src: f(src)?,
+				bins: f(bins)?,
+				bin_count: *bin_count,
+				range: *range,
+				source: *source,
+				out_of_range: *out_of_range,
+			},
+			ComputeAction::Convolve { src, dst, kernel, edges } => {
+				ComputeAction::Convolve { src: f(src)?, dst: f(dst)?, kernel: kernel.clone(), edges: *edges }
+			}
+			ComputeAction::CheckFinite { buffer, element } => {
+				ComputeAction::CheckFinite { buffer: f(buffer)?, element: *element }
+			}
+		})
+	}
+}
+
+/// Anything that can map a buffer key, such as a name or an index, to a [ShaderBufferHandle]. Used by [ComputeTask::resolve]. It's implemented for a [HashMap] from any key to handles, and for a [Vec] of handles, indexed by `usize`.
+pub trait BufferResolver<K> {
+	/// Look up the handle for the given key, returning `None` if there isn't one.
+	fn resolve(&self, key: &K) -> Option<ShaderBufferHandle>;
+}
+
+impl<K: Hash + Eq> BufferResolver<K> for HashMap<K, ShaderBufferHandle> {
+	fn resolve(&self, key: &K) -> Option<ShaderBufferHandle> { self.get(key).copied() }
+}
+
+impl BufferResolver<String> for ShaderBufferSet {
+	fn resolve(&self, key: &String) -> Option<ShaderBufferHandle> { self.handle_by_name(key) }
+}
+
+impl BufferResolver<usize> for Vec<ShaderBufferHandle> {
+	fn resolve(&self, key: &usize) -> Option<ShaderBufferHandle> { self.get(*key).copied() }
+}
+
+/// The error returned by [ComputeTask::resolve] when a buffer key has no matching handle.
+#[derive(Clone, Debug)]
+pub struct UnresolvedBufferError<K> {
+	/// The key that couldn't be resolved.
+	pub key: K,
+}
+
+impl<K: Debug> Display for UnresolvedBufferError<K> {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result { write!(f, "No buffer found for key {:?}", self.key) }
+}
+
+impl<K: Debug> std::error::Error for UnresolvedBufferError<K> {}
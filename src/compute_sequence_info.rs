@@ -0,0 +1,201 @@
+use bevy::prelude::*;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use super::{
+	compute_sequence::{ComputeSequence, ComputeSequences, ComputeTask},
+	ComputeProgress, ComputeSequenceId, ComputeState, ComputeTaskDoneEvent,
+};
+use crate::shader_buffer_set::ShaderBufferHandle;
+
+/// A read-only description of the running [main](crate::ComputeSequenceId::MAIN) compute sequence, mirrored into the main world for tools such as debug panels to show. It's inserted when the sequence is started, with a copy of every task as it was started, and kept up to date with how far along each task is from the [ComputeProgress] and the [ComputeTaskDoneEvent]s, so it may lag behind by a frame or two. Changing it has no effect on the sequence, which is only changed with events such as [ExtendComputeTaskEvent](crate::ExtendComputeTaskEvent) and [PauseComputeTaskEvent](crate::PauseComputeTaskEvent).
+#[derive(Resource, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ComputeSequenceInfo {
+	/// The tasks of the sequence, in order, with their progress.
+	pub tasks: Vec<ComputeTaskInfo>,
+
+	/// The [iteration buffer](crate::StartComputeEvent::iteration_buffer) the sequence was started with, if any.
+	pub iteration_buffer: Option<ShaderBufferHandle>,
+
+	/// The [seed](crate::StartComputeEvent::seed) the sequence was started with.
+	pub seed: u64,
+
+	/// Which run of a compute sequence this describes, which changes every time one is started, or a finished task is extended and the sequence starts over from it.
+	pub generation: u32,
+}
+
+/// The iteration counts of the running [main](crate::ComputeSequenceId::MAIN) compute sequence, mirrored into the main world every frame, so a system can show them, such as "iteration 4521 / 10000", without reading back the [iteration buffers](ComputeTask::iteration_buffer) they're written to. They're the same counts the buffers are written with, and are taken from the [ComputeSequenceInfo] once it's been updated, so they may lag behind by a frame or two. It's inserted along with the [ComputeSequenceInfo], and removed when the sequence is stopped.
+#[derive(Resource, Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ComputeIterationState {
+	/// The number of iterations run so far across the whole sequence, as written to the [global](crate::ComputeIterationCounts::global) count.
+	pub global: u32,
+
+	/// The iteration counts of the tasks of the sequence, in order.
+	pub tasks: Vec<TaskIterationState>,
+
+	/// Which run of a compute sequence this describes, as in [ComputeSequenceInfo::generation].
+	pub generation: u32,
+}
+
+/// The iteration counts of one task of the running compute sequence, as mirrored in the [ComputeIterationState].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct TaskIterationState {
+	/// The task's [label](ComputeTask::label), if it has one.
+	pub label: Option<String>,
+
+	/// The number of iterations the task has run so far, or ran in total, once it's done.
+	pub iterations: u32,
+
+	/// The number of iterations the task runs for in total, or `None` if it runs forever, as in [ComputeTaskInfo::total_iterations].
+	pub total_iterations: Option<u32>,
+}
+
+impl ComputeIterationState {
+	/// The iteration counts of the first task with the given label, if there is one.
+	pub fn task(&self, label: &str) -> Option<&TaskIterationState> {
+		self.tasks.iter().find(|task| task.label.as_deref() == Some(label))
+	}
+}
+
+/// A task of the running compute sequence, as described by [ComputeSequenceInfo].
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ComputeTaskInfo {
+	/// The task, as it was started, with its label, steps, and configured number of iterations.
+	pub task: ComputeTask,
+
+	/// What the task is doing.
+	pub status: ComputeTaskStatus,
+
+	/// The number of iterations the task has run so far, or ran in total, once it's done.
+	pub iterations: u32,
+
+	/// The number of iterations the task runs for in total, or `None` if it runs forever. Until the task starts, this is the count it was configured with, and from then on, it includes any count from [ComputeTaskIterations](crate::ComputeTaskIterations) and any iterations it's been extended by.
+	pub total_iterations: Option<u32>,
+}
+
+/// What a task of the running compute sequence is doing, as described by [ComputeTaskInfo].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum ComputeTaskStatus {
+	/// The task hasn't been reached yet.
+	Queued,
+
+	/// The task has been reached, but its shader pipelines are still being prepared.
+	Preparing,
+
+	/// The task is waiting for its [start_after](ComputeTask::start_after) delay to be up.
+	Delayed,
+
+	/// The task is iterating.
+	Running,
+
+	/// The task has been paused with a [PauseComputeTaskEvent](crate::PauseComputeTaskEvent), or by a watchdog, or the whole sequence has been paused with a [PauseComputeEvent](crate::PauseComputeEvent).
+	Paused,
+
+	/// The task has finished.
+	Done,
+}
+
+impl ComputeSequenceInfo {
+	fn new(sequence: &ComputeSequence) -> Self {
+		Self {
+			tasks: sequence
+				.tasks
+				.iter()
+				.map(|task| ComputeTaskInfo {
+					task: task.clone(),
+					status: ComputeTaskStatus::Queued,
+					iterations: 0,
+					total_iterations: task.iterations.map(|iterations| iterations.get()),
+				})
+				.collect(),
+			iteration_buffer: sequence.iteration_buffer,
+			seed: sequence.seed,
+			generation: sequence.generation,
+		}
+	}
+}
+
+/// Insert a new [ComputeSequenceInfo] when the main compute sequence is started, and update the progress of its tasks.
+pub(crate) fn update_sequence_info(
+	mut commands: Commands, sequences: Option<Res<ComputeSequences>>, progress: Res<ComputeProgress>,
+	state: Res<ComputeState>, mut done_events: EventReader<ComputeTaskDoneEvent>,
+	info: Option<ResMut<ComputeSequenceInfo>>,
+) {
+	let Some(sequence) = sequences.as_ref().and_then(|sequences| sequences.0.get(&ComputeSequenceId::MAIN)) else {
+		done_events.clear();
+		return;
+	};
+	let mut new_info = None;
+	let info = match info {
+		Some(info) if info.generation == sequence.generation => info.into_inner(),
+		// A sequence that's started over from a task it's already finished keeps the tasks before it as they were.
+		Some(info) if sequence.restart_iterations.is_some() => {
+			let info = info.into_inner();
+			let restarted = ComputeSequenceInfo::new(sequence);
+			info.tasks.splice(sequence.first_task.., restarted.tasks.into_iter().skip(sequence.first_task));
+			info.generation = sequence.generation;
+			info
+		}
+		_ => new_info.insert(ComputeSequenceInfo::new(sequence)),
+	};
+
+	for event in done_events.read().filter(|event| event.sequence == ComputeSequenceId::MAIN) {
+		if let Some(task) = info.tasks.get_mut(event.group_finished) {
+			task.status = ComputeTaskStatus::Done;
+			task.iterations = event.iterations;
+		}
+	}
+	if let Some(task) = info.tasks.get_mut(progress.current_task) {
+		if task.status != ComputeTaskStatus::Done {
+			task.status = match *state {
+				ComputeState::Paused { .. } => ComputeTaskStatus::Paused,
+				ComputeState::Running { .. } if progress.pending => ComputeTaskStatus::Delayed,
+				ComputeState::Running { .. } => ComputeTaskStatus::Running,
+				_ => ComputeTaskStatus::Preparing,
+			};
+			task.iterations = progress.iterations;
+			task.total_iterations = progress.total_iterations.or(task.total_iterations);
+		}
+	}
+
+	if let Some(info) = new_info {
+		commands.insert_resource(info);
+	}
+}
+
+/// Mirror the iteration counts of the main compute sequence from its [ComputeSequenceInfo] into the [ComputeIterationState].
+pub(crate) fn update_iteration_state(
+	mut commands: Commands, sequences: Option<Res<ComputeSequences>>, info: Option<Res<ComputeSequenceInfo>>,
+	state: Option<ResMut<ComputeIterationState>>,
+) {
+	let Some(sequence) = sequences.as_ref().and_then(|sequences| sequences.0.get(&ComputeSequenceId::MAIN)) else {
+		return;
+	};
+	let Some(info) = info.filter(|info| info.generation == sequence.generation) else {
+		return;
+	};
+	let updated = ComputeIterationState {
+		global: info.tasks.iter().skip(sequence.first_task).map(|task| task.iterations).sum(),
+		tasks: info
+			.tasks
+			.iter()
+			.map(|task| TaskIterationState {
+				label: task.task.label.clone(),
+				iterations: task.iterations,
+				total_iterations: task.total_iterations,
+			})
+			.collect(),
+		generation: info.generation,
+	};
+	match state {
+		Some(mut state) => {
+			state.set_if_neq(updated);
+		}
+		None => commands.insert_resource(updated),
+	}
+}
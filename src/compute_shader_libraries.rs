@@ -0,0 +1,29 @@
+use bevy::{asset::AssetPath, prelude::*};
+
+/// Shader files that compute shaders import from, but never run themselves. A shader with a `#define_import_path` can only be imported by that name once it has been loaded, so any library imported by name must be added here, which loads it and keeps it loaded. Libraries imported by their asset path, such as `#import "shaders/noise.wgsl"::fbm`, are loaded automatically and don't need to be added.
+///
+/// ```Rust
+/// fn setup(asset_server: Res<AssetServer>, mut libraries: ResMut<ComputeShaderLibraries>) {
+///     libraries.add(&asset_server, "shaders/noise.wgsl");
+/// }
+/// ```
+#[derive(Resource, Default)]
+pub struct ComputeShaderLibraries(Vec<Handle<Shader>>);
+
+impl ComputeShaderLibraries {
+	/// Load the shader at the given asset path, and keep it loaded so its import path stays available to compute shaders.
+	pub fn add<'a>(&mut self, asset_server: &AssetServer, path: impl Into<AssetPath<'a>>) -> Handle<Shader> {
+		let handle = asset_server.load(path);
+		self.add_handle(handle.clone());
+		handle
+	}
+
+	/// Keep an already loaded shader loaded, so its import path stays available to compute shaders. This is also how to share a library with material shaders which was added with [load_internal_asset](bevy::asset::load_internal_asset) or to `Assets<Shader>` directly.
+	pub fn add_handle(&mut self, handle: Handle<Shader>) {
+		if !self.0.contains(&handle) {
+			self.0.push(handle);
+		}
+	}
+
+	pub(crate) fn handles(&self) -> &[Handle<Shader>] { &self.0 }
+}
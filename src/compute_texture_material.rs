@@ -0,0 +1,252 @@
+use bevy::{
+	asset::load_internal_asset,
+	prelude::*,
+	render::{
+		render_asset::RenderAssets,
+		render_resource::{AsBindGroup, AsBindGroupShaderType, ShaderRef},
+		texture::GpuImage,
+	},
+	sprite::{Material2d, Material2dPlugin},
+};
+
+use crate::{
+	display_compute_buffers::{front_image, retarget_material},
+	shader_buffer_set::{ShaderBufferHandle, ShaderBufferSet},
+	DisplayComputeBuffer,
+};
+
+const COLORMAP_SHADER_HANDLE: Handle<Shader> = Handle::weak_from_u128(0x2b9e_61d4_07c3_4a8f_b5e2_9d10_c46f_38a7);
+const MATERIAL_SHADER_HANDLE: Handle<Shader> = Handle::weak_from_u128(0x8f03_c2a7_5e19_4d6b_a0f4_37b8_e2c1_5d90);
+const MATERIAL_2D_SHADER_HANDLE: Handle<Shader> = Handle::weak_from_u128(0xd47a_0e85_b3f2_49c1_86d9_2a5c_f1e0_7b34);
+
+/// How a [ComputeTextureMaterial] or [ComputeTextureMaterial2d] turns the texels of its texture into colors.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Reflect)]
+pub enum Colormap {
+	/// Display the texture's colors as they are. This is right for textures that hold colors, such as `Rgba8Unorm` or `Rgba32Float`, but single-channel formats will come out red.
+	#[default]
+	Direct,
+
+	/// Map the texture's first channel from the material's `min`..`max` range to a ramp from black to white.
+	Grayscale,
+
+	/// Map the texture's first channel from the material's `min`..`max` range to the viridis colormap, which is perceptually uniform, and readable by the colorblind.
+	Viridis,
+
+	/// Map the texture's first channel from the material's `min`..`max` range to the first row of the material's `lut` image, from left to right. The LUT's colors are used as they are sampled, so a LUT in an sRGB format, such as one loaded from a PNG, is handled correctly.
+	Lut,
+}
+
+// The ShaderType derive generates layout checks that trip the dead code lint,
+// and they can only be allowed from outside the struct.
+#[allow(dead_code)]
+mod uniform {
+	use bevy::render::render_resource::ShaderType;
+
+	#[derive(Clone, ShaderType)]
+	pub struct ComputeTextureUniform {
+		pub colormap: u32,
+		pub min: f32,
+		pub max: f32,
+		pub flags: u32,
+	}
+}
+use uniform::ComputeTextureUniform;
+
+const FLAG_SRGB_ENCODED: u32 = 1;
+
+fn compute_texture_uniform(colormap: Colormap, min: f32, max: f32, srgb_encoded: bool) -> ComputeTextureUniform {
+	ComputeTextureUniform {
+		colormap: match colormap {
+			Colormap::Direct => 0,
+			Colormap::Grayscale => 1,
+			Colormap::Viridis => 2,
+			Colormap::Lut => 3,
+		},
+		min,
+		max,
+		flags: if srgb_encoded { FLAG_SRGB_ENCODED } else { 0 },
+	}
+}
+
+/// A material for displaying a texture from the [ShaderBufferSet] on a 3D mesh, such as a [Plane3d] or [Rectangle]. Texels are looked up without filtering, so any float format works, including ones that can't be filtered, like `R32Float`, and single-channel textures can be displayed through a [Colormap]. The material is unlit.
+///
+/// To keep the material showing the front buffer of a double buffered texture, put a [DisplayComputeBuffer] on the same entity. [compute_texture_3d] builds all of that in one go.
+#[derive(Asset, TypePath, AsBindGroup, Clone, Debug)]
+#[uniform(0, ComputeTextureUniform)]
+pub struct ComputeTextureMaterial {
+	/// The texture to display, as returned by [image_handle](ShaderBufferSet::image_handle).
+	#[texture(1, sample_type = "float", filterable = false)]
+	pub texture: Handle<Image>,
+
+	/// The image used by [Colormap::Lut]. Ignored by every other colormap.
+	#[texture(2, sample_type = "float", filterable = false)]
+	pub lut: Option<Handle<Image>>,
+
+	/// How the texels are turned into colors.
+	pub colormap: Colormap,
+
+	/// The value of the texture's first channel that's mapped to the start of the colormap. Lower values are clamped to it.
+	pub min: f32,
+
+	/// The value of the texture's first channel that's mapped to the end of the colormap. Higher values are clamped to it.
+	pub max: f32,
+
+	/// With [Colormap::Direct], whether the texture holds sRGB encoded colors, which need to be converted to linear before they're displayed. Storage textures can't use an sRGB format, so a shader that writes colors meant for display into an `Rgba8Unorm` texture usually wants this set.
+	pub srgb_encoded: bool,
+}
+
+impl ComputeTextureMaterial {
+	/// Create a material that displays the texture directly, with a `min` and `max` of 0 and 1.
+	pub fn new(texture: Handle<Image>) -> Self {
+		Self { texture, lut: None, colormap: Colormap::Direct, min: 0.0, max: 1.0, srgb_encoded: false }
+	}
+
+	/// Set the colormap, and the range of values mapped onto it.
+	pub fn with_colormap(mut self, colormap: Colormap, min: f32, max: f32) -> Self {
+		self.colormap = colormap;
+		self.min = min;
+		self.max = max;
+		self
+	}
+}
+
+impl AsBindGroupShaderType<ComputeTextureUniform> for ComputeTextureMaterial {
+	fn as_bind_group_shader_type(&self, _images: &RenderAssets<GpuImage>) -> ComputeTextureUniform {
+		compute_texture_uniform(self.colormap, self.min, self.max, self.srgb_encoded)
+	}
+}
+
+impl Material for ComputeTextureMaterial {
+	fn fragment_shader() -> ShaderRef { MATERIAL_SHADER_HANDLE.into() }
+}
+
+/// The 2D equivalent of [ComputeTextureMaterial], for displaying a texture from the [ShaderBufferSet] on a 2D mesh. [compute_texture_2d] builds a quad with one of these, which can be used in place of a [Sprite] when the texture needs a [Colormap].
+#[derive(Asset, TypePath, AsBindGroup, Clone, Debug)]
+#[uniform(0, ComputeTextureUniform)]
+pub struct ComputeTextureMaterial2d {
+	/// The texture to display, as returned by [image_handle](ShaderBufferSet::image_handle).
+	#[texture(1, sample_type = "float", filterable = false)]
+	pub texture: Handle<Image>,
+
+	/// The image used by [Colormap::Lut]. Ignored by every other colormap.
+	#[texture(2, sample_type = "float", filterable = false)]
+	pub lut: Option<Handle<Image>>,
+
+	/// How the texels are turned into colors.
+	pub colormap: Colormap,
+
+	/// The value of the texture's first channel that's mapped to the start of the colormap. Lower values are clamped to it.
+	pub min: f32,
+
+	/// The value of the texture's first channel that's mapped to the end of the colormap. Higher values are clamped to it.
+	pub max: f32,
+
+	/// With [Colormap::Direct], whether the texture holds sRGB encoded colors, which need to be converted to linear before they're displayed.
+	pub srgb_encoded: bool,
+}
+
+impl ComputeTextureMaterial2d {
+	/// Create a material that displays the texture directly, with a `min` and `max` of 0 and 1.
+	pub fn new(texture: Handle<Image>) -> Self {
+		Self { texture, lut: None, colormap: Colormap::Direct, min: 0.0, max: 1.0, srgb_encoded: false }
+	}
+
+	/// Set the colormap, and the range of values mapped onto it.
+	pub fn with_colormap(mut self, colormap: Colormap, min: f32, max: f32) -> Self {
+		self.colormap = colormap;
+		self.min = min;
+		self.max = max;
+		self
+	}
+}
+
+impl AsBindGroupShaderType<ComputeTextureUniform> for ComputeTextureMaterial2d {
+	fn as_bind_group_shader_type(&self, _images: &RenderAssets<GpuImage>) -> ComputeTextureUniform {
+		compute_texture_uniform(self.colormap, self.min, self.max, self.srgb_encoded)
+	}
+}
+
+impl Material2d for ComputeTextureMaterial2d {
+	fn fragment_shader() -> ShaderRef { MATERIAL_2D_SHADER_HANDLE.into() }
+}
+
+/// Build a 2D quad of the given size, displaying a texture from the [ShaderBufferSet] with a [ComputeTextureMaterial2d], and kept up to date with a [DisplayComputeBuffer]. Spawn it along with whatever [Transform] is needed:
+///
+/// ```Rust
+/// let material = ComputeTextureMaterial2d::new(buffer_set.image_handle(image).unwrap())
+///     .with_colormap(Colormap::Viridis, 0.0, 1.0);
+/// commands.spawn((
+///     compute_texture_2d(&mut meshes, &mut materials, image, material, Vec2::new(320.0, 180.0)),
+///     Transform::from_scale(Vec3::splat(4.0)),
+/// ));
+/// ```
+pub fn compute_texture_2d(
+	meshes: &mut Assets<Mesh>, materials: &mut Assets<ComputeTextureMaterial2d>, buffer: ShaderBufferHandle,
+	material: ComputeTextureMaterial2d, size: Vec2,
+) -> impl Bundle {
+	(
+		Mesh2d(meshes.add(Rectangle::from_size(size))),
+		MeshMaterial2d(materials.add(material)),
+		DisplayComputeBuffer(buffer),
+	)
+}
+
+/// Build a 3D quad of the given size, facing +Z, displaying a texture from the [ShaderBufferSet] with a [ComputeTextureMaterial], and kept up to date with a [DisplayComputeBuffer]. This works the same as [compute_texture_2d].
+pub fn compute_texture_3d(
+	meshes: &mut Assets<Mesh>, materials: &mut Assets<ComputeTextureMaterial>, buffer: ShaderBufferHandle,
+	material: ComputeTextureMaterial, size: Vec2,
+) -> impl Bundle {
+	(
+		Mesh3d(meshes.add(Rectangle::from_size(size))),
+		MeshMaterial3d(materials.add(material)),
+		DisplayComputeBuffer(buffer),
+	)
+}
+
+fn update_compute_texture_materials(
+	buffer_set: Res<ShaderBufferSet>, mut materials: ResMut<Assets<ComputeTextureMaterial>>,
+	mut materials_2d: ResMut<Assets<ComputeTextureMaterial2d>>,
+	displays: Query<(Ref<DisplayComputeBuffer>, &MeshMaterial3d<ComputeTextureMaterial>)>,
+	displays_2d: Query<(Ref<DisplayComputeBuffer>, &MeshMaterial2d<ComputeTextureMaterial2d>)>,
+) {
+	for (display, MeshMaterial3d(material)) in displays.iter() {
+		if let Some(image) = front_image(&buffer_set, &display) {
+			retarget_material(
+				&mut materials,
+				material,
+				image,
+				|material| Some(&material.texture),
+				|material, image| material.texture = image,
+			);
+		}
+	}
+	for (display, MeshMaterial2d(material)) in displays_2d.iter() {
+		if let Some(image) = front_image(&buffer_set, &display) {
+			retarget_material(
+				&mut materials_2d,
+				material,
+				image,
+				|material| Some(&material.texture),
+				|material, image| material.texture = image,
+			);
+		}
+	}
+}
+
+/// This plugin adds the [ComputeTextureMaterial] and [ComputeTextureMaterial2d], and the system that keeps any with a [DisplayComputeBuffer] showing the front buffer. It must be added alongside the [BevyComputePlugin](crate::BevyComputePlugin).
+pub struct ComputeTextureMaterialPlugin;
+
+impl Plugin for ComputeTextureMaterialPlugin {
+	fn build(&self, app: &mut App) {
+		load_internal_asset!(app, COLORMAP_SHADER_HANDLE, "shaders/compute_texture_colormap.wgsl", Shader::from_wgsl);
+		load_internal_asset!(app, MATERIAL_SHADER_HANDLE, "shaders/compute_texture_material.wgsl", Shader::from_wgsl);
+		load_internal_asset!(app, MATERIAL_2D_SHADER_HANDLE, "shaders/compute_texture_material_2d.wgsl", Shader::from_wgsl);
+
+		app
+			.add_plugins((
+				MaterialPlugin::<ComputeTextureMaterial>::default(),
+				Material2dPlugin::<ComputeTextureMaterial2d>::default(),
+			))
+			.add_systems(Update, update_compute_texture_materials);
+	}
+}
@@ -0,0 +1,227 @@
+use std::{
+	collections::VecDeque,
+	sync::{Arc, Mutex},
+};
+
+use bevy::{
+	prelude::*,
+	render::{
+		render_resource::{BufferInitDescriptor, BufferUsages, CommandEncoder},
+		renderer::{RenderDevice, RenderQueue},
+	},
+};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::shader_buffer_set::{ShaderBufferHandle, ShaderBufferSet};
+
+/// Something that happened to a buffer in a [ComputeTrace].
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum TraceEvent {
+	/// The buffer was written with [write_buffer](ShaderBufferSet::write_buffer) or [write_buffer_elements](ShaderBufferSet::write_buffer_elements), with the bytes that were written to it.
+	Write {
+		/// The buffer that was written.
+		buffer: ShaderBufferHandle,
+
+		/// Where in the buffer the bytes were written, which is only past the start when some of its elements were written with [write_buffer_elements](ShaderBufferSet::write_buffer_elements).
+		#[cfg_attr(feature = "serde", serde(default))]
+		offset: u64,
+
+		/// The bytes that were written, laid out as they are on the GPU.
+		data: Vec<u8>,
+	},
+
+	/// The double buffer was swapped, by a [SwapBuffers](crate::ComputeAction::SwapBuffers) step, or a shader step's `auto_swap`.
+	Swap {
+		/// The buffer that was swapped.
+		buffer: ShaderBufferHandle,
+	},
+}
+
+/// A write or swap in a [ComputeTrace], with the iteration boundary it happened at.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct TraceEntry {
+	/// The index of the task that was running, as in, the index into the `Vec<ComputeTask>` that was provided in the [StartComputeEvent](crate::StartComputeEvent).
+	pub task: usize,
+
+	/// The number of iterations of the task that had run before it happened. A write with an iteration equal to the task's total number of iterations happened after the task's last iteration, before the next task's first.
+	pub iteration: u32,
+
+	/// What happened.
+	pub event: TraceEvent,
+}
+
+/// The number of iterations a task ran in one frame, in a [ComputeTrace].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct TraceFrame {
+	/// The index of the task, as in, the index into the `Vec<ComputeTask>` that was provided in the [StartComputeEvent](crate::StartComputeEvent).
+	pub task: usize,
+
+	/// The number of iterations it ran that frame.
+	pub iterations: u32,
+}
+
+/// A recording of every [write_buffer](ShaderBufferSet::write_buffer) and swap made while a compute sequence ran, and how many iterations ran in each frame, made with [start_recording](ShaderBufferSet::start_recording), and played back with [replay](ShaderBufferSet::replay) to rerun the sequence exactly, such as to track down a bug that depends on when the CPU wrote to a buffer. With the `serde` feature enabled, it can be serialized to keep it on disk. Buffers are referred to by their handles, so it can only be replayed into buffers created in the same order as the ones it was recorded from, as they would be by rerunning the same setup code. Two traces can be compared with `==`, for instance a recording of a replay against the original, to see whether the swaps happened at the same iterations.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ComputeTrace {
+	/// Every write and swap, in the order they happened.
+	pub entries: Vec<TraceEntry>,
+
+	/// The number of iterations run in every frame in which any ran, in order.
+	pub frames: Vec<TraceFrame>,
+}
+
+/// A write to a buffer being replayed, with the iteration boundary it's made at.
+struct ReplayWrite {
+	task: usize,
+	iteration: u32,
+	buffer: ShaderBufferHandle,
+	offset: u64,
+	data: Vec<u8>,
+}
+
+#[derive(Default)]
+struct TracerState {
+	recording: Option<ComputeTrace>,
+	pending: Vec<(ShaderBufferHandle, u64, Vec<u8>)>,
+	writes: VecDeque<ReplayWrite>,
+	frames: VecDeque<TraceFrame>,
+	replaying: bool,
+	cursor: (usize, u32),
+}
+
+impl TracerState {
+	fn record(&mut self, task: usize, iteration: u32, event: TraceEvent) {
+		if let Some(trace) = &mut self.recording {
+			trace.entries.push(TraceEntry { task, iteration, event });
+		}
+	}
+}
+
+/// The recording or replay of a [ComputeTrace], shared by every copy of the [ShaderBufferSet], so the main world can start and stop it, and the render world can fill it in and play it back.
+#[derive(Clone, Default)]
+pub(crate) struct ComputeTracer(Arc<Mutex<TracerState>>);
+
+impl ComputeTracer {
+	pub fn start_recording(&self) {
+		let mut state = self.0.lock().unwrap();
+		state.recording = Some(ComputeTrace::default());
+	}
+
+	/// Stop recording, and return the trace. Writes that haven't been made yet are tagged with the iteration the compute node is at now.
+	pub fn stop_recording(&self) -> Option<ComputeTrace> {
+		let mut state = self.0.lock().unwrap();
+		let (task, iteration) = state.cursor;
+		for (buffer, offset, data) in state.pending.clone() {
+			state.record(task, iteration, TraceEvent::Write { buffer, offset, data });
+		}
+		state.recording.take()
+	}
+
+	pub fn recording(&self) -> bool { self.0.lock().unwrap().recording.is_some() }
+
+	pub fn replay(&self, trace: ComputeTrace) {
+		let mut state = self.0.lock().unwrap();
+		state.writes = trace
+			.entries
+			.into_iter()
+			.filter_map(|entry| match entry.event {
+				TraceEvent::Write { buffer, offset, data } => {
+					Some(ReplayWrite { task: entry.task, iteration: entry.iteration, buffer, offset, data })
+				}
+				TraceEvent::Swap { .. } => None,
+			})
+			.collect();
+		state.frames = trace.frames.into();
+		state.replaying = true;
+	}
+
+	/// Whether a replay is still going, which it is until every write and frame in it has been played back.
+	pub fn replaying(&self) -> bool {
+		let mut state = self.0.lock().unwrap();
+		if state.writes.is_empty() && state.frames.is_empty() {
+			state.replaying = false;
+		}
+		state.replaying
+	}
+
+	/// Hold a write made while recording until the render world makes it, so it's recorded at the iteration it actually lands before.
+	pub fn defer_write(&self, buffer: ShaderBufferHandle, offset: u64, data: Vec<u8>) {
+		self.0.lock().unwrap().pending.push((buffer, offset, data));
+	}
+
+	/// Record where the compute node is, as the number of iterations of the current task that have run once the frame's commands have run, which is where writes made before the next frame land.
+	pub fn set_cursor(&self, task: usize, iteration: u32) { self.0.lock().unwrap().cursor = (task, iteration); }
+
+	/// Record the number of iterations the compute node is about to run in a frame, or while replaying, return the number that were recorded for that frame instead, either way no more than the task has left to run.
+	pub fn frame_iterations(&self, task: usize, iterations: u32, remaining: Option<u32>) -> u32 {
+		let mut state = self.0.lock().unwrap();
+		let iterations = match state.frames.front() {
+			Some(frame) if state.replaying && frame.task == task => state.frames.pop_front().unwrap().iterations,
+			_ => iterations,
+		};
+		let iterations = remaining.map_or(iterations, |remaining| iterations.min(remaining));
+		if let Some(trace) = &mut state.recording {
+			trace.frames.push(TraceFrame { task, iterations });
+		}
+		iterations
+	}
+
+	pub fn record_swap(&self, task: usize, iteration: u32, buffer: ShaderBufferHandle) {
+		self.0.lock().unwrap().record(task, iteration, TraceEvent::Swap { buffer });
+	}
+
+	/// Whether there are replayed writes due before an iteration, which [replay_writes](Self::replay_writes) would record into the command encoder.
+	pub fn writes_due(&self, task: usize, iteration: u32) -> bool {
+		self.0.lock().unwrap().writes.front().is_some_and(|write| (write.task, write.iteration) <= (task, iteration))
+	}
+
+	/// Copy the replayed writes due at an iteration boundary of a task into their buffers, in the order they were recorded, along with any that were due earlier and somehow missed. Replayed writes are recorded like any other, if a recording is being made at the same time.
+	pub fn replay_writes(
+		&self, task: usize, iteration: u32, buffers: &ShaderBufferSet, device: &RenderDevice, encoder: &mut CommandEncoder,
+	) {
+		let mut state = self.0.lock().unwrap();
+		while state.writes.front().is_some_and(|write| (write.task, write.iteration) <= (task, iteration)) {
+			let write = state.writes.pop_front().unwrap();
+			let staging = device.create_buffer_with_data(&BufferInitDescriptor {
+				label: None,
+				contents: &write.data,
+				usage: BufferUsages::COPY_SRC,
+			});
+			for buffer in buffers.all_gpu_buffers(write.buffer) {
+				encoder.copy_buffer_to_buffer(&staging, 0, &buffer, write.offset, write.data.len() as u64);
+			}
+			let event = TraceEvent::Write { buffer: write.buffer, offset: write.offset, data: write.data };
+			state.record(write.task, write.iteration, event);
+		}
+	}
+
+	/// Make the replayed writes due at the end of a task, which land before the next task's first iteration.
+	pub fn finish_task(&self, task: usize, buffers: &ShaderBufferSet, render_queue: &RenderQueue) {
+		let mut state = self.0.lock().unwrap();
+		while state.writes.front().is_some_and(|write| write.task <= task) {
+			let write = state.writes.pop_front().unwrap();
+			buffers.write_all_halves(write.buffer, write.offset, &write.data, render_queue);
+			let event = TraceEvent::Write { buffer: write.buffer, offset: write.offset, data: write.data };
+			state.record(write.task, write.iteration, event);
+		}
+	}
+}
+
+/// Make the writes held back while recording, and record them at the iteration boundary they land before, which is where the compute node left off last frame, as they're made before this frame's compute steps.
+pub(crate) fn make_recorded_writes(buffers: Option<Res<ShaderBufferSet>>, render_queue: Res<RenderQueue>) {
+	let Some(buffers) = buffers else {
+		return;
+	};
+	let tracer = buffers.tracer();
+	let mut state = tracer.0.lock().unwrap();
+	let (task, iteration) = state.cursor;
+	for (buffer, offset, data) in std::mem::take(&mut state.pending) {
+		buffers.write_all_halves(buffer, offset, &data, &render_queue);
+		state.record(task, iteration, TraceEvent::Write { buffer, offset, data });
+	}
+}
@@ -0,0 +1,760 @@
+use std::collections::VecDeque;
+
+use bevy::{
+	asset::{LoadState, RecursiveDependencyLoadState},
+	prelude::*,
+	render::render_resource::{
+		BindGroupLayoutEntry, BindingType, BufferBindingType, SamplerBindingType, ShaderImport, Source,
+		StorageTextureAccess, TextureSampleType,
+	},
+	utils::HashMap,
+};
+use naga::{
+	valid::{Capabilities, ValidationFlags, Validator},
+	AddressSpace, ImageClass, Module, ScalarKind, ShaderStage, StorageAccess, TypeInner,
+};
+use naga_oil::compose::{Composer, NagaModuleDescriptor, ShaderDefValue};
+
+use super::{
+	check_finite::check_finite,
+	clear_buffer::{check_clear_buffer, check_clear_texture},
+	compute_capabilities::ComputeCapabilities,
+	compute_diagnostics::task_name,
+	compute_sequence::{check_shader_passes, ComputeAction, ComputeShaderDef, ComputeTask, DispatchSize, PushConstants},
+	compute_sequence_info::{ComputeSequenceInfo, ComputeTaskStatus},
+	compute_shader_libraries::ComputeShaderLibraries,
+	convolve::check_convolve,
+	empty_check::check_empty_source,
+	histogram::check_histogram,
+	prefix_sum::check_prefix_sum,
+	prepared_shaders::{entry_points, set_overrides, workgroup_size},
+	reduce_buffer::check_reduce_buffer,
+	sort_buffer::check_sort_buffer,
+};
+use crate::shader_buffer_set::{ShaderBufferHandle, ShaderBufferSet};
+
+/// This event checks a compute sequence, without starting it, for everything that would stop it from running, or that would be reported while it ran, such as a shader that doesn't exist or doesn't compile, an entry point that isn't in its shader, a buffer that's the wrong kind for the step using it, a workgroup size or count over the GPU's limits, or a binding the shader declares differently from the buffer bound there. It takes the same tasks and iteration buffer as a [StartComputeEvent](crate::StartComputeEvent), and nothing is installed or dispatched, so it can be sent as often as needed, for instance on every edit in a node graph editor, even while another compute sequence is running. Once the shaders it uses have loaded, a [ComputeValidationReport] is sent with everything that was found. Reports are sent in the same order as the events, so a report for an edit is never overtaken by one for an older edit.
+///
+/// Shaders are checked the same way Bevy prepares them for a pipeline, with their imports and shader defs, and the values of their override constants, but the GPU driver only sees them once a pipeline is built, so a shader that passes this can still, rarely, fail to build on some devices. Only the bindings an entry point actually uses are checked, as only those have to match.
+#[derive(Event, Clone)]
+pub struct ValidateComputeEvent {
+	/// The list of compute tasks to check, as they would be provided in a [StartComputeEvent](crate::StartComputeEvent).
+	pub tasks: Vec<ComputeTask>,
+
+	/// The iteration buffer to check, as it would be provided in a [StartComputeEvent](crate::StartComputeEvent).
+	pub iteration_buffer: Option<ShaderBufferHandle>,
+}
+
+/// How serious a [ComputeValidationFinding] is.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ValidationSeverity {
+	/// The sequence would run, but something is probably not what was meant, such as an override constant that would be ignored, and would be reported with a [ComputeErrorEvent](crate::ComputeErrorEvent) or a warning.
+	Warning,
+
+	/// The sequence would panic, or fail to build a pipeline, when it reached this step.
+	Error,
+}
+
+/// One thing found by a [ValidateComputeEvent].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ComputeValidationFinding {
+	/// How serious it is.
+	pub severity: ValidationSeverity,
+
+	/// The index of the task it was found in, as in, the index into the `Vec<ComputeTask>` that was provided in the [ValidateComputeEvent], or `None` if it's about the sequence as a whole, such as its iteration buffer.
+	pub task: Option<usize>,
+
+	/// The index of the step it was found in, within its task, or `None` if it's about the task or the sequence as a whole.
+	pub step: Option<usize>,
+
+	/// A description of what was found, in the same words as the panic or error it would have caused.
+	pub message: String,
+}
+
+/// This event is sent in reply to every [ValidateComputeEvent], with everything found, in the order the tasks and steps were checked. A sequence with no findings would run cleanly, as far as can be told without running it.
+#[derive(Event, Clone, Debug, Default)]
+pub struct ComputeValidationReport {
+	/// Everything that was found.
+	pub findings: Vec<ComputeValidationFinding>,
+}
+
+impl ComputeValidationReport {
+	/// Whether anything was found that would stop the sequence from running.
+	pub fn has_errors(&self) -> bool { self.findings.iter().any(|finding| finding.severity == ValidationSeverity::Error) }
+}
+
+/// A validation waiting for its shaders to load, which are held here so they stay loaded until it's done.
+pub(crate) struct PendingValidation {
+	event: ValidateComputeEvent,
+	shaders: Vec<Handle<Shader>>,
+}
+
+/// Check the sequences sent in [ValidateComputeEvent]s, once their shaders have loaded, or failed to, in the order they were sent.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn validate_compute_sequences(
+	mut events: EventReader<ValidateComputeEvent>, mut reports: EventWriter<ComputeValidationReport>,
+	mut pending: Local<VecDeque<PendingValidation>>, asset_server: Res<AssetServer>, shaders: Res<Assets<Shader>>,
+	libraries: Res<ComputeShaderLibraries>, buffers: Res<ShaderBufferSet>,
+	capabilities: Option<Res<ComputeCapabilities>>,
+) {
+	for event in events.read() {
+		let shaders = event
+			.tasks
+			.iter()
+			.flat_map(|task| task.steps.iter())
+			.filter_map(|step| match &step.action {
+				ComputeAction::RunShader { shader, .. }
+				| ComputeAction::RunShaderPerEntity { shader, .. }
+				| ComputeAction::RunShaderIndirect { shader, .. }
+				| ComputeAction::RunShaderMulti { shader, .. } => Some(asset_server.load(shader)),
+				_ => None,
+			})
+			.collect();
+		pending.push_back(PendingValidation { event: event.clone(), shaders });
+	}
+	let loaded = |handle: &Handle<Shader>| {
+		matches!(asset_server.get_load_state(handle), Some(LoadState::Failed(_)))
+			|| matches!(
+				asset_server.get_recursive_dependency_load_state(handle),
+				Some(RecursiveDependencyLoadState::Loaded | RecursiveDependencyLoadState::Failed(_))
+			)
+	};
+	while pending.front().is_some_and(|validation| validation.shaders.iter().chain(libraries.handles()).all(&loaded)) {
+		let validation = pending.pop_front().unwrap();
+		let mut validator = SequenceValidator {
+			asset_server: &asset_server,
+			shaders: &shaders,
+			buffers: &buffers,
+			capabilities: capabilities.as_deref(),
+			composer: Composer::default().with_capabilities(Capabilities::all()),
+			findings: Vec::new(),
+		};
+		validator.validate(&validation.event);
+		reports.send(ComputeValidationReport { findings: validator.findings });
+	}
+}
+
+/// The layout entries of every group, as they were when the buffers were last added to or deleted from, with and without the per-entity buffers' layout in the per-entity group.
+pub(crate) struct LayoutSnapshot {
+	generation: u32,
+	entries: [Vec<Vec<BindGroupLayoutEntry>>; 2],
+}
+
+impl LayoutSnapshot {
+	fn entry_at(&self, group: u32, binding: u32, per_entity: bool) -> Option<BindGroupLayoutEntry> {
+		self.entries[per_entity as usize].get(group as usize)?.iter().find(|entry| entry.binding == binding).copied()
+	}
+}
+
+/// Panic if a buffer was deleted, or replaced by one of a different kind, while a step of the compute sequence's current task still uses the binding it was at, naming the binding and the step, rather than leaving wgpu to crash when the step's pipeline is remade against the new layouts. Bindings that were empty before are left to the pipeline cache, as they were when the task started. This runs last in the frame, so it sees every change made to the buffers before they're extracted.
+pub(crate) fn check_running_bindings(
+	buffers: Res<ShaderBufferSet>, info: Option<Res<ComputeSequenceInfo>>, asset_server: Res<AssetServer>,
+	shaders: Res<Assets<Shader>>, mut last: Local<Option<LayoutSnapshot>>,
+) {
+	if last.as_ref().is_some_and(|last| last.generation == buffers.generation()) {
+		return;
+	}
+	let current = LayoutSnapshot {
+		generation: buffers.generation(),
+		entries: [buffers.bind_group_layout_entries(false), buffers.bind_group_layout_entries(true)],
+	};
+	let (Some(previous), Some(info)) = (last.replace(current), info) else {
+		return;
+	};
+	let Some((task_index, task)) = info.tasks.iter().enumerate().find(|(_, task)| task.status != ComputeTaskStatus::Done)
+	else {
+		return;
+	};
+	let mut validator = SequenceValidator {
+		asset_server: &asset_server,
+		shaders: &shaders,
+		buffers: &buffers,
+		capabilities: None,
+		composer: Composer::default().with_capabilities(Capabilities::all()),
+		findings: Vec::new(),
+	};
+	for (step_index, step) in task.task.steps.iter().enumerate() {
+		let per_entity = matches!(step.action, ComputeAction::RunShaderPerEntity { .. });
+		for (shader, entry_point, shader_defs, overrides, _) in entry_points(&step.action) {
+			let Some(bindings) = validator.used_bindings(shader, entry_point, shader_defs, overrides) else {
+				continue;
+			};
+			for (group, binding) in bindings {
+				let Some(before) = previous.entry_at(group, binding, per_entity) else {
+					continue;
+				};
+				let change = match buffers.layout_entry_at(group, binding, per_entity) {
+					None => "deleted",
+					Some(after) if after != before => "replaced by a different kind of buffer",
+					Some(_) => continue,
+				};
+				panic!(
+					"The buffer at group {} binding {} was {} while the compute sequence was running, but {} in {} uses it, in step {} of {}, so its pipeline can't be remade. A buffer can only be deleted mid-run once the task that's running doesn't use it",
+					group,
+					binding,
+					change,
+					entry_point,
+					shader,
+					step_index,
+					task_name(task_index, task.task.label.as_deref())
+				);
+			}
+		}
+	}
+}
+
+struct SequenceValidator<'a> {
+	asset_server: &'a AssetServer,
+	shaders: &'a Assets<Shader>,
+	buffers: &'a ShaderBufferSet,
+	capabilities: Option<&'a ComputeCapabilities>,
+	composer: Composer,
+	findings: Vec<ComputeValidationFinding>,
+}
+
+impl SequenceValidator<'_> {
+	fn report(&mut self, severity: ValidationSeverity, task: Option<usize>, step: Option<usize>, message: String) {
+		self.findings.push(ComputeValidationFinding { severity, task, step, message });
+	}
+
+	fn validate(&mut self, event: &ValidateComputeEvent) {
+		if self.capabilities.is_some_and(|capabilities| !capabilities.compute_shaders) {
+			let message = "The GPU adapter doesn't support compute shaders, so no compute sequences will run".to_owned();
+			self.report(ValidationSeverity::Error, None, None, message);
+			return;
+		}
+		if let Err(message) = self.buffers.check_groups() {
+			self.report(ValidationSeverity::Warning, None, None, message);
+		}
+		for &buffer in self.buffers.render_inputs() {
+			if self.buffers.check_swappable(buffer).is_ok() {
+				let message = format!(
+					"{} is consumed by rendering, but it's double buffered, so whatever binds it has to follow its front buffer every time it's swapped",
+					buffer.describe(self.buffers)
+				);
+				self.report(ValidationSeverity::Warning, None, None, message);
+			}
+		}
+		if let Some(buffer) = event.iteration_buffer {
+			match self.buffers.gpu_buffer(buffer) {
+				None => self.report(
+					ValidationSeverity::Error,
+					None,
+					None,
+					format!(
+						"Tried to write the iteration count to {}, which isn't a storage or uniform buffer",
+						buffer.describe(self.buffers)
+					),
+				),
+				Some(gpu_buffer) if gpu_buffer.size() < 4 => self.report(
+					ValidationSeverity::Error,
+					None,
+					None,
+					format!(
+						"Tried to write the iteration count to {}, which is too small to hold it",
+						buffer.describe(self.buffers)
+					),
+				),
+				Some(_) => {}
+			}
+		}
+		for (task_index, task) in event.tasks.iter().enumerate() {
+			if let Some(iteration_buffer) = task.iteration_buffer {
+				let buffer = *iteration_buffer.buffer();
+				match self.buffers.gpu_buffer(buffer) {
+					None => self.report(
+						ValidationSeverity::Error,
+						Some(task_index),
+						None,
+						format!(
+							"Tried to write the task's iteration count to {}, which isn't a storage or uniform buffer",
+							buffer.describe(self.buffers)
+						),
+					),
+					Some(gpu_buffer) if gpu_buffer.size() < iteration_buffer.size() => self.report(
+						ValidationSeverity::Error,
+						Some(task_index),
+						None,
+						format!(
+							"Tried to write the task's iteration count to {}, which is too small to hold it",
+							buffer.describe(self.buffers)
+						),
+					),
+					Some(_) => {}
+				}
+			}
+			let mut copied = Vec::new();
+			for (step_index, step) in task.steps.iter().enumerate() {
+				let mut problems = Vec::new();
+				let mut swaps: &[ShaderBufferHandle] = &[];
+				match &step.action {
+					ComputeAction::RunShader {
+						shader,
+						entry_point,
+						shader_defs,
+						overrides,
+						dispatch,
+						auto_swap,
+						skip_if_empty,
+						buffer_orientation,
+						push_constants,
+						dynamic_offsets,
+					} => {
+						let push_constant_size = push_constants.as_ref().map_or(0, PushConstants::size);
+						problems =
+							self.validate_shader(shader, entry_point, shader_defs, overrides, *dispatch, false, push_constant_size);
+						if let Some(push_constants) = push_constants {
+							problems.extend(push_constants.check(shader, self.capabilities).err().map(error));
+						}
+						swaps = auto_swap;
+						if let Some(buffer) = skip_if_empty {
+							problems.extend(check_empty_source(self.buffers, *buffer).err().map(error));
+						}
+						for (buffer, _) in buffer_orientation {
+							problems.extend(self.buffers.check_orientable(*buffer).err().map(error));
+						}
+						for (buffer, element) in dynamic_offsets {
+							problems.extend(self.buffers.check_dynamic_element(*buffer, *element).err().map(error));
+						}
+					}
+					ComputeAction::RunShaderPerEntity { shader, entry_point, shader_defs, overrides, dispatch } => {
+						problems = self.validate_shader(shader, entry_point, shader_defs, overrides, *dispatch, true, 0);
+					}
+					ComputeAction::RunShaderIndirect { shader, entry_point, shader_defs, overrides, indirect_buffer, offset } => {
+						// The workgroup counts are only known on the GPU, so only the workgroup size can be checked.
+						let dispatch = DispatchSize::Workgroups { x: 1, y: 1, z: 1 };
+						problems = self.validate_shader(shader, entry_point, shader_defs, overrides, dispatch, false, 0);
+						problems.extend(self.buffers.check_indirect_source(*indirect_buffer, *offset).err().map(error));
+					}
+					ComputeAction::RunShaderMulti { shader, shader_defs, passes } => {
+						problems.extend(check_shader_passes(shader, passes).err().map(error));
+						// Problems with the shader itself, rather than an entry point, are only reported once.
+						for (entry_point, dispatch) in passes {
+							for problem in self.validate_shader(shader, entry_point, shader_defs, &[], *dispatch, false, 0) {
+								if !problems.contains(&problem) {
+									problems.push(problem);
+								}
+							}
+						}
+					}
+					ComputeAction::CopyBuffer { src, offset, size } => {
+						if copied.contains(src) {
+							problems.push((
+								ValidationSeverity::Error,
+								format!("Tried to create a copy buffer for {}, which already has one", src.describe(self.buffers)),
+							));
+						}
+						copied.push(*src);
+						problems.extend(self.buffers.check_copy_range(*src, *offset, *size).err().map(error));
+					}
+					ComputeAction::CopyBufferToBuffer { src, dst, src_offset, dst_offset, size } => {
+						let checked = self.buffers.check_buffer_copy(*src, *dst, *src_offset, *dst_offset, *size);
+						problems.extend(checked.err().map(error));
+					}
+					ComputeAction::CopyTextureToBuffer { src, dst } => {
+						problems.extend(self.buffers.check_texture_copy(*src, *dst, false).err().map(error));
+					}
+					ComputeAction::CopyBufferToTexture { src, dst } => {
+						problems.extend(self.buffers.check_texture_copy(*dst, *src, true).err().map(error));
+					}
+					ComputeAction::ClearBuffer { buffer, offset, size, .. } => {
+						problems.extend(check_clear_buffer(self.buffers, *buffer, *offset, *size).err().map(error));
+					}
+					ComputeAction::ClearTexture { texture, value, .. } => {
+						problems.extend(check_clear_texture(self.buffers, *texture, *value).err().map(error));
+					}
+					ComputeAction::ReadBuffer { buffer } => {
+						problems.extend(self.buffers.check_copy_source(*buffer).err().map(error));
+					}
+					ComputeAction::SwapBuffers { buffer } => swaps = std::slice::from_ref(buffer),
+					ComputeAction::RotateBuffers { buffer } => {
+						problems.extend(self.buffers.check_rotatable(*buffer).err().map(error));
+					}
+					ComputeAction::SortBuffer { buffer, element_count, key } => {
+						problems.extend(check_sort_buffer(self.buffers, *buffer, *element_count, *key).err().map(error));
+					}
+					ComputeAction::ReduceBuffer { src, dst, .. } => {
+						problems.extend(check_reduce_buffer(self.buffers, *src, *dst).err().map(error));
+					}
+					ComputeAction::PrefixSum { buffer, element_count } => {
+						problems.extend(check_prefix_sum(self.buffers, *buffer, *element_count).err().map(error));
+					}
+					ComputeAction::Histogram { src, bins, bin_count, range, source, .. } => {
+						let checked = check_histogram(self.buffers, *src, *bins, *bin_count, *range, *source);
+						problems.extend(checked.err().map(error));
+					}
+					ComputeAction::Convolve { src, dst, kernel, .. } => {
+						problems.extend(check_convolve(self.buffers, *src, *dst, kernel).err().map(error));
+					}
+					ComputeAction::CheckFinite { buffer, element } => {
+						problems.extend(check_finite(self.buffers, *buffer, *element).err().map(error));
+					}
+				}
+				problems.extend(swaps.iter().filter_map(|buffer| self.buffers.check_swappable(*buffer).err()).map(error));
+				problems.extend(step.schedule.check().err().map(error));
+				let written = match &step.action {
+					ComputeAction::SortBuffer { buffer, .. } | ComputeAction::PrefixSum { buffer, .. } => Some(*buffer),
+					ComputeAction::ReduceBuffer { dst, .. }
+					| ComputeAction::CopyBufferToBuffer { dst, .. }
+					| ComputeAction::CopyTextureToBuffer { dst, .. } => Some(*dst),
+					ComputeAction::ClearBuffer { buffer, .. } => Some(*buffer),
+					ComputeAction::Histogram { bins, .. } => Some(*bins),
+					_ => None,
+				};
+				if let Some(buffer) = written.filter(|buffer| self.buffers.readonly(*buffer)) {
+					problems.push((
+						ValidationSeverity::Warning,
+						format!("{} was added as read-only, but this step writes to it", buffer.describe(self.buffers)),
+					));
+				}
+				for (severity, message) in problems {
+					self.report(severity, Some(task_index), Some(step_index), message);
+				}
+			}
+		}
+	}
+
+	/// Check a shader step, which gives the shader `push_constant_size` bytes of push constants, returning everything found.
+	#[allow(clippy::too_many_arguments)]
+	fn validate_shader(
+		&mut self, shader: &str, entry_point: &str, shader_defs: &[ComputeShaderDef], overrides: &[(String, f64)],
+		dispatch: DispatchSize, per_entity: bool, push_constant_size: u32,
+	) -> Vec<(ValidationSeverity, String)> {
+		let mut problems = Vec::new();
+		// A dispatch over a texture is checked with the texture's size as it is now.
+		let dispatch = dispatch.over_texture(self.buffers).unwrap_or_else(|texture_error| {
+			problems.push(error(texture_error));
+			dispatch
+		});
+		if per_entity && self.buffers.per_entity_group().is_none() {
+			problems.push(error(format!(
+				"Tried to run {} per entity, but no per-entity buffers have been added, so their layout isn't known",
+				shader
+			)));
+		}
+		if let DispatchSize::Chunked { max_per_dispatch, .. } = dispatch {
+			let max = UVec3::from(max_per_dispatch);
+			if max.min_element() == 0 {
+				problems.push(error(format!(
+					"Tried to split a dispatch into chunks of {} workgroups, which is zero in at least one dimension",
+					max
+				)));
+			}
+			if self.buffers.chunk_uniform().is_none() {
+				problems.push(error(
+					"Tried to run a chunked dispatch, but no chunk uniform has been added for the chunk offsets".to_owned(),
+				));
+			}
+		}
+
+		let handle = self.asset_server.load::<Shader>(shader);
+		if let Some(LoadState::Failed(load_error)) = self.asset_server.get_load_state(&handle) {
+			problems.push(error(format!("Couldn't load {}: {}", shader, load_error)));
+			return problems;
+		}
+		let Some(original) = self.shaders.get(&handle) else {
+			problems.push(error(format!("Couldn't load {}, or one of the shaders it imports", shader)));
+			return problems;
+		};
+		let Source::Wgsl(wgsl) = &original.source else {
+			problems.push(error(format!("Tried to prepare {} for running, but it isn't a WGSL shader", shader)));
+			return problems;
+		};
+		let (wgsl, unknown) = set_overrides(wgsl, overrides);
+		for name in unknown {
+			problems.push((
+				ValidationSeverity::Warning,
+				format!("The override {} isn't declared in {}, so it would be ignored", name, shader),
+			));
+		}
+		if dispatch.needs_workgroup_size() {
+			if let Err(size_error) = workgroup_size(&wgsl, entry_point, shader_defs) {
+				problems.push(error(format!(
+					"Couldn't work out the workgroup size of {} in {}: {}",
+					entry_point, shader, size_error
+				)));
+			}
+		}
+
+		let mut copy = original.clone();
+		copy.source = Source::Wgsl(wgsl.into());
+		let module = match self.compose(&copy, shader_defs) {
+			Ok(module) => module,
+			Err(compose_error) => {
+				problems.push(error(format!("{} doesn't compile: {}", shader, compose_error)));
+				return problems;
+			}
+		};
+		let Some(index) =
+			module.entry_points.iter().position(|entry| entry.name == entry_point && entry.stage == ShaderStage::Compute)
+		else {
+			problems.push(error(format!("{} has no compute entry point named {}", shader, entry_point)));
+			return problems;
+		};
+		problems.extend(self.check_limits(
+			shader,
+			entry_point,
+			UVec3::from(module.entry_points[index].workgroup_size),
+			dispatch,
+		));
+		let info = match Validator::new(ValidationFlags::all(), Capabilities::all()).validate(&module) {
+			Ok(info) => info,
+			Err(validation_error) => {
+				problems.push(error(format!("{} doesn't compile: {}", shader, validation_error.as_inner())));
+				return problems;
+			}
+		};
+		let uses = info.get_entry_point(index);
+		for (global, variable) in module.global_variables.iter() {
+			if uses[global].is_empty() {
+				continue;
+			}
+			let name = variable.name.as_deref().unwrap_or("_");
+			if variable.space == AddressSpace::PushConstant {
+				let size = module.types[variable.ty].inner.size(module.to_ctx());
+				if size > push_constant_size {
+					problems.push(error(format!(
+						"{} in {} uses {} bytes of push constants in {}, but the step gives it {}",
+						entry_point, shader, size, name, push_constant_size
+					)));
+				}
+				continue;
+			}
+			let Some(binding) = &variable.binding else {
+				continue;
+			};
+			let problem = match self.buffers.layout_entry_at(binding.group, binding.binding, per_entity) {
+				None => Some(format!(
+					"{} in {} uses {} at group {} binding {}, but no buffer is bound there",
+					entry_point, shader, name, binding.group, binding.binding
+				)),
+				// What kind of texture it is isn't known until its image has loaded.
+				Some(_) if self.buffers.texture_loading_at(binding.group, binding.binding) => None,
+				Some(entry) => binding_mismatch(&module, variable.space, variable.ty, &entry).map(|mismatch| {
+					format!(
+						"{} in {} uses {} at group {} binding {}, but {}",
+						entry_point, shader, name, binding.group, binding.binding, mismatch
+					)
+				}),
+			};
+			problems.extend(problem.map(error));
+		}
+		problems
+	}
+
+	/// The group and binding of everything an entry point uses, or `None` if its shader isn't loaded, or it doesn't compile, or the entry point isn't in it, in which case there's no pipeline for it to lose a binding of.
+	fn used_bindings(
+		&mut self, shader: &str, entry_point: &str, shader_defs: &[ComputeShaderDef], overrides: &[(String, f64)],
+	) -> Option<Vec<(u32, u32)>> {
+		let mut copy = self.shaders.get(&self.asset_server.load::<Shader>(shader))?.clone();
+		let Source::Wgsl(wgsl) = &copy.source else {
+			return None;
+		};
+		copy.source = Source::Wgsl(set_overrides(wgsl, overrides).0.into());
+		let module = self.compose(&copy, shader_defs).ok()?;
+		let index =
+			module.entry_points.iter().position(|entry| entry.name == entry_point && entry.stage == ShaderStage::Compute)?;
+		let info = Validator::new(ValidationFlags::all(), Capabilities::all()).validate(&module).ok()?;
+		let uses = info.get_entry_point(index);
+		let used = module.global_variables.iter().filter(|(global, _)| !uses[*global].is_empty());
+		Some(
+			used
+				.filter_map(|(_, variable)| variable.binding.as_ref())
+				.map(|binding| (binding.group, binding.binding))
+				.collect(),
+		)
+	}
+
+	/// Preprocess and parse a shader, with its imports, the same way Bevy's pipeline cache does, returning the error as it would be printed if it doesn't compile, without the terminal colors.
+	fn compose(&mut self, shader: &Shader, shader_defs: &[ComputeShaderDef]) -> Result<Module, String> {
+		let by_import_path =
+			self.shaders.iter().map(|(_, shader)| (shader.import_path().clone(), shader)).collect::<HashMap<_, _>>();
+		for import in shader.imports() {
+			add_import(&mut self.composer, &by_import_path, import)?;
+		}
+		let shader_defs = shader_defs
+			.iter()
+			.cloned()
+			.map(Into::into)
+			.chain(shader.shader_defs.iter().cloned())
+			.map(|def| match def {
+				bevy::render::render_resource::ShaderDefVal::Bool(name, value) => (name, ShaderDefValue::Bool(value)),
+				bevy::render::render_resource::ShaderDefVal::Int(name, value) => (name, ShaderDefValue::Int(value)),
+				bevy::render::render_resource::ShaderDefVal::UInt(name, value) => (name, ShaderDefValue::UInt(value)),
+			})
+			.collect();
+		self
+			.composer
+			.make_naga_module(NagaModuleDescriptor { shader_defs, ..shader.into() })
+			.map_err(|compose_error| strip_colors(&compose_error.emit_to_string(&self.composer)))
+	}
+
+	/// Check a shader step's workgroup size and count against the GPU's limits.
+	fn check_limits(
+		&self, shader: &str, entry_point: &str, size: UVec3, dispatch: DispatchSize,
+	) -> Vec<(ValidationSeverity, String)> {
+		let Some(capabilities) = self.capabilities else {
+			return Vec::new();
+		};
+		let mut problems = Vec::new();
+		if size.cmpgt(capabilities.max_workgroup_size).any() {
+			problems.push(error(format!(
+				"{} in {} has a workgroup size of {}, but the GPU allows at most {}",
+				entry_point, shader, size, capabilities.max_workgroup_size
+			)));
+		}
+		if size.element_product() > capabilities.max_invocations_per_workgroup {
+			problems.push(error(format!(
+				"{} in {} has {} invocations per workgroup, but the GPU allows at most {}",
+				entry_point,
+				shader,
+				size.element_product(),
+				capabilities.max_invocations_per_workgroup
+			)));
+		}
+		let count = match dispatch {
+			DispatchSize::Chunked { total, max_per_dispatch, .. } => UVec3::from(total).min(UVec3::from(max_per_dispatch)),
+			_ => dispatch.workgroup_count(size.max(UVec3::ONE)),
+		};
+		if count.max_element() > capabilities.max_workgroups_per_dimension {
+			problems.push(error(format!(
+				"{} in {} is dispatched with {} workgroups, but the GPU allows at most {} in each dimension",
+				entry_point, shader, count, capabilities.max_workgroups_per_dimension
+			)));
+		}
+		problems
+	}
+}
+
+fn error(message: String) -> (ValidationSeverity, String) { (ValidationSeverity::Error, message) }
+
+/// Remove the escape codes a shader error is colored with for a terminal, so it can be shown anywhere.
+fn strip_colors(text: &str) -> String {
+	let mut result = String::with_capacity(text.len());
+	let mut rest = text;
+	while let Some(start) = rest.find("\x1b[") {
+		result.push_str(&rest[..start]);
+		let code = &rest[start + 2..];
+		rest = code.find(|c: char| c.is_ascii_alphabetic()).map_or("", |end| &code[end + 1..]);
+	}
+	result.push_str(rest);
+	result
+}
+
+/// Add an imported shader to the composer, after everything it imports, if it hasn't been added already, returning the error as it would be printed if it doesn't compile. If it isn't loaded, it's left out, and the composer says what's missing when it's used.
+fn add_import(
+	composer: &mut Composer, by_import_path: &HashMap<ShaderImport, &Shader>, import: &ShaderImport,
+) -> Result<(), String> {
+	if composer.contains_module(&import.module_name()) {
+		return Ok(());
+	}
+	if let Some(shader) = by_import_path.get(import) {
+		for import in shader.imports() {
+			add_import(composer, by_import_path, import)?;
+		}
+		if let Err(compose_error) = composer.add_composable_module((*shader).into()).map(|_| ()) {
+			return Err(strip_colors(&compose_error.emit_to_string(composer)));
+		}
+	}
+	Ok(())
+}
+
+/// How a variable a shader declares differs from the layout entry of the buffer bound at its binding, or `None` if they match.
+fn binding_mismatch(
+	module: &Module, space: AddressSpace, ty: naga::Handle<naga::Type>, entry: &BindGroupLayoutEntry,
+) -> Option<String> {
+	let (inner, array) = match &module.types[ty].inner {
+		TypeInner::BindingArray { base, .. } => (&module.types[*base].inner, true),
+		inner => (inner, false),
+	};
+	if array != entry.count.is_some() {
+		return Some(if array {
+			"it's declared as a binding array, and the buffer bound there isn't a texture array".to_owned()
+		} else {
+			"the buffer bound there is a texture array, and it isn't declared as a binding array".to_owned()
+		});
+	}
+	match (space, inner, entry.ty) {
+		(AddressSpace::Uniform, _, BindingType::Buffer { ty: BufferBindingType::Uniform, .. }) => None,
+		(AddressSpace::Storage { access }, _, BindingType::Buffer { ty: BufferBindingType::Storage { read_only }, .. }) => {
+			if read_only && access.contains(StorageAccess::STORE) {
+				Some("it's declared read_write, and the buffer bound there is read-only".to_owned())
+			} else if !read_only && !access.contains(StorageAccess::STORE) {
+				Some("it's declared read, and the buffer bound there is read-write".to_owned())
+			} else {
+				None
+			}
+		}
+		(
+			AddressSpace::Handle,
+			TypeInner::Image { class: ImageClass::Storage { format, access }, .. },
+			BindingType::StorageTexture { access: bound_access, format: bound_format, .. },
+		) => {
+			let declared_access = match (access.contains(StorageAccess::LOAD), access.contains(StorageAccess::STORE)) {
+				(true, true) => StorageTextureAccess::ReadWrite,
+				(false, true) => StorageTextureAccess::WriteOnly,
+				_ => StorageTextureAccess::ReadOnly,
+			};
+			if format!("{:?}", format) != format!("{:?}", bound_format) {
+				Some(format!("it's declared with the format {:?}, and the texture bound there is {:?}", format, bound_format))
+			} else if declared_access != bound_access {
+				Some(format!(
+					"it's declared with {:?} access, and the texture bound there has {:?} access",
+					declared_access, bound_access
+				))
+			} else {
+				None
+			}
+		}
+		(AddressSpace::Handle, TypeInner::Image { class, .. }, BindingType::Texture { sample_type, .. })
+			if !matches!(class, ImageClass::Storage { .. }) =>
+		{
+			let matches = matches!(
+				(class, sample_type),
+				(ImageClass::Sampled { kind: ScalarKind::Float, .. }, TextureSampleType::Float { .. })
+					| (ImageClass::Sampled { kind: ScalarKind::Sint, .. }, TextureSampleType::Sint)
+					| (ImageClass::Sampled { kind: ScalarKind::Uint, .. }, TextureSampleType::Uint)
+					| (ImageClass::Depth { .. }, TextureSampleType::Depth)
+			);
+			(!matches).then(|| {
+				format!("it's declared as {:?}, and the texture bound there has the sample type {:?}", class, sample_type)
+			})
+		}
+		(AddressSpace::Handle, TypeInner::Sampler { comparison }, BindingType::Sampler(bound)) => {
+			(*comparison != (bound == SamplerBindingType::Comparison)).then(|| {
+				if *comparison {
+					"it's declared as a sampler_comparison, and the sampler bound there doesn't compare".to_owned()
+				} else {
+					"it's declared as a sampler, and the sampler bound there is a comparison sampler".to_owned()
+				}
+			})
+		}
+		(_, _, bound) => Some(format!(
+			"it's declared as {}, and the buffer bound there is {}",
+			declared_kind(space, inner),
+			bound_kind(bound)
+		)),
+	}
+}
+
+fn declared_kind(space: AddressSpace, inner: &TypeInner) -> &'static str {
+	match (space, inner) {
+		(AddressSpace::Uniform, _) => "a uniform buffer",
+		(AddressSpace::Storage { .. }, _) => "a storage buffer",
+		(_, TypeInner::Image { class: ImageClass::Storage { .. }, .. }) => "a storage texture",
+		(_, TypeInner::Image { .. }) => "a sampled texture",
+		(_, TypeInner::Sampler { .. }) => "a sampler",
+		_ => "something else",
+	}
+}
+
+fn bound_kind(ty: BindingType) -> &'static str {
+	match ty {
+		BindingType::Buffer { ty: BufferBindingType::Uniform, .. } => "a uniform buffer",
+		BindingType::Buffer { .. } => "a storage buffer",
+		BindingType::StorageTexture { .. } => "a storage texture",
+		BindingType::Texture { .. } => "a sampled texture",
+		BindingType::Sampler(_) => "a sampler",
+		_ => "something else",
+	}
+}
@@ -0,0 +1,111 @@
+use std::{
+	collections::HashMap,
+	sync::{Arc, Mutex, OnceLock},
+	time::Duration,
+};
+
+use bevy::{
+	prelude::*,
+	render::{extract_resource::ExtractResource, renderer::RenderQueue},
+	utils::Instant,
+};
+
+use super::{
+	compute_data_transmission::{send_message, ComputeMessage, MessageSender},
+	ComputeErrorEvent, ComputeSequenceId,
+};
+
+/// Time limits for the iterations of compute tasks, by their [labels](crate::ComputeTask::label), to catch a shader that runs away or stalls, such as one stuck in an infinite loop. Every iteration of a task with one of these labels is timed on the GPU, from when the frame it's recorded into is submitted until the GPU has finished that frame's work, which includes any rendering done that frame, so the limit should leave room for that. If an iteration takes longer than its limit, or still hasn't finished once its limit has passed, an error is logged, a [ComputeErrorEvent::WatchdogTimeout] is sent naming the task and the steps that ran in that iteration, and the task is paused, as with a [PauseComputeTaskEvent](crate::PauseComputeTaskEvent). This can't stop the GPU driver from resetting the device if a shader really does hang, but it does make it clear which task was at fault. Stalls are only noticed while the render world keeps running, as it checks once a frame.
+///
+/// ```Rust
+/// fn setup(mut watchdogs: ResMut<ComputeTaskWatchdogs>) {
+///     watchdogs.0.insert("Erode".to_owned(), Duration::from_millis(100));
+/// }
+/// ```
+#[derive(Resource, Clone, Default, Debug, ExtractResource)]
+pub struct ComputeTaskWatchdogs(pub HashMap<String, Duration>);
+
+/// An iteration of a task with a time limit, from when the compute node records it until the GPU has finished it.
+struct WatchedIteration {
+	sender: MessageSender,
+	sequence: ComputeSequenceId,
+	generation: u32,
+	task: usize,
+	label: Option<String>,
+	iteration: u32,
+	steps: Vec<usize>,
+	limit: Duration,
+	submitted: Option<Instant>,
+	finished: Arc<OnceLock<Instant>>,
+	reported: bool,
+}
+
+/// The iterations of tasks with time limits that the GPU hasn't finished yet, in the render world.
+#[derive(Resource, Default)]
+pub(crate) struct ComputeWatchdog(Mutex<Vec<WatchedIteration>>);
+
+impl ComputeWatchdog {
+	/// Stop watching every iteration, once the app is exiting.
+	pub fn release(&self) { self.0.lock().unwrap().clear(); }
+
+	/// Start watching an iteration the compute node has just recorded, which is submitted along with the rest of the frame.
+	#[allow(clippy::too_many_arguments)]
+	pub fn watch(
+		&self, sender: &MessageSender, sequence: ComputeSequenceId, generation: u32, task: usize, label: Option<String>,
+		iteration: u32, steps: Vec<usize>, limit: Duration,
+	) {
+		self.0.lock().unwrap().push(WatchedIteration {
+			sender: sender.clone(),
+			sequence,
+			generation,
+			task,
+			label,
+			iteration,
+			steps,
+			limit,
+			submitted: None,
+			finished: Arc::new(OnceLock::new()),
+			reported: false,
+		});
+	}
+}
+
+/// Time the iterations recorded this frame from now, as the frame's commands have just been submitted, and report any iteration that's taken longer than its limit, whether it's finished or not.
+pub(crate) fn check_compute_watchdog(watchdog: Res<ComputeWatchdog>, render_queue: Res<RenderQueue>) {
+	let mut watched = watchdog.0.lock().unwrap();
+	if watched.is_empty() {
+		return;
+	}
+	let now = Instant::now();
+	for iteration in watched.iter_mut().filter(|iteration| iteration.submitted.is_none()) {
+		iteration.submitted = Some(now);
+		let finished = iteration.finished.clone();
+		render_queue.on_submitted_work_done(move || {
+			let _ = finished.set(Instant::now());
+		});
+	}
+	watched.retain_mut(|iteration| {
+		let finished = iteration.finished.get().copied();
+		let elapsed = finished.unwrap_or(now) - iteration.submitted.unwrap();
+		if elapsed > iteration.limit && !iteration.reported {
+			iteration.reported = true;
+			send_message(
+				&iteration.sender,
+				ComputeMessage::WatchdogTimeout {
+					generation: iteration.generation,
+					error: ComputeErrorEvent::WatchdogTimeout {
+						sequence: iteration.sequence,
+						task: iteration.task,
+						label: iteration.label.clone(),
+						iteration: iteration.iteration,
+						steps: iteration.steps.clone(),
+						elapsed,
+						limit: iteration.limit,
+						finished: finished.is_some(),
+					},
+				},
+			);
+		}
+		finished.is_none()
+	});
+}
@@ -0,0 +1,200 @@
+use bevy::{
+	prelude::*,
+	render::{
+		render_resource::{
+			BufferDescriptor, BufferUsages, PipelineCache, TextureFormat, TextureSampleType, TextureViewDimension,
+		},
+		renderer::RenderDevice,
+	},
+};
+use bytemuck::{Pod, Zeroable};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use super::builtin_pass::{BuiltinBinding, BuiltinDispatch, BuiltinPass};
+use crate::shader_buffer_set::{ShaderBufferHandle, ShaderBufferSet};
+
+pub(crate) const CONVOLVE_SHADER_HANDLE: Handle<Shader> =
+	Handle::weak_from_u128(0x7b19_c3e5_a04d_4f62_8d2e_5f91_b6a3_0c7d);
+
+const WORKGROUP_SIZE: u32 = 16;
+
+/// The most weights a [ConvolutionKernel] can have, for a radius of up to 31 texels on either side of the centre.
+pub const MAX_CONVOLUTION_TAPS: usize = 63;
+
+/// The weights a [Convolve](crate::ComputeAction::Convolve) action applies along each axis in turn, centred on each texel.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum ConvolutionKernel {
+	/// A gaussian blur, with weights from a gaussian out to `radius` texels on either side, normalized to add up to 1.
+	Gaussian {
+		/// How many texels on either side of each texel are weighted, up to half of [MAX_CONVOLUTION_TAPS]. About three times `sigma` takes in all but a sliver of the gaussian.
+		radius: u32,
+
+		/// The standard deviation of the gaussian, in texels.
+		sigma: f32,
+	},
+
+	/// A box blur, averaging the texels out to `radius` texels on either side.
+	Box {
+		/// How many texels on either side of each texel are averaged, up to half of [MAX_CONVOLUTION_TAPS].
+		radius: u32,
+	},
+
+	/// Any weights, such as a sharpening or edge detection kernel, which are used as they are, without being normalized. There must be an odd number of them, up to [MAX_CONVOLUTION_TAPS], with the middle one for the texel itself.
+	Custom(Vec<f32>),
+}
+
+impl ConvolutionKernel {
+	/// The weights of the kernel, from the texel furthest to the left or above, to the one furthest to the right or below, or what's wrong with it.
+	fn weights(&self) -> Result<Vec<f32>, String> {
+		let max_radius = (MAX_CONVOLUTION_TAPS / 2) as u32;
+		match self {
+			ConvolutionKernel::Gaussian { radius, .. } | ConvolutionKernel::Box { radius } if *radius > max_radius => {
+				Err(format!("Tried to convolve with a radius of {}, but it can be at most {}", radius, max_radius))
+			}
+			ConvolutionKernel::Gaussian { sigma, .. } if !(sigma.is_finite() && *sigma > 0.0) => {
+				Err(format!("Tried to convolve with a gaussian of sigma {}, which must be positive", sigma))
+			}
+			ConvolutionKernel::Gaussian { radius, sigma } => {
+				let radius = *radius as i32;
+				let weights = (-radius..=radius)
+					.map(|offset| (-(offset * offset) as f32 / (2.0 * sigma * sigma)).exp())
+					.collect::<Vec<_>>();
+				let total: f32 = weights.iter().sum();
+				Ok(weights.into_iter().map(|weight| weight / total).collect())
+			}
+			ConvolutionKernel::Box { radius } => {
+				let taps = radius * 2 + 1;
+				Ok(vec![1.0 / taps as f32; taps as usize])
+			}
+			ConvolutionKernel::Custom(weights) if weights.len() % 2 == 0 || weights.len() > MAX_CONVOLUTION_TAPS => {
+				Err(format!(
+					"Tried to convolve with {} weights, but there must be an odd number of them, up to {}",
+					weights.len(),
+					MAX_CONVOLUTION_TAPS
+				))
+			}
+			ConvolutionKernel::Custom(weights) => Ok(weights.clone()),
+		}
+	}
+}
+
+/// How a [Convolve](crate::ComputeAction::Convolve) action reads the texels past the edges of the texture.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum ConvolutionEdges {
+	/// Repeat the texels at the edge.
+	#[default]
+	Clamp,
+
+	/// Wrap around to the other side, for a simulation on a torus.
+	Wrap,
+
+	/// Reflect the texture at the edge, repeating the texel at the edge once.
+	Mirror,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Pod, Zeroable)]
+struct ConvolveParams {
+	width: u32,
+	height: u32,
+	radius: u32,
+	edges: u32,
+	weights: [[f32; 4]; MAX_CONVOLUTION_TAPS.div_ceil(4)],
+}
+
+/// The formats a convolution can write, which are the float formats every GPU can write to as a storage texture, along with the entry point of the vertical pass for each.
+const DST_FORMATS: [(TextureFormat, &str); 3] = [
+	(TextureFormat::R32Float, "vertical_r32float"),
+	(TextureFormat::Rgba16Float, "vertical_rgba16float"),
+	(TextureFormat::Rgba32Float, "vertical_rgba32float"),
+];
+
+/// Check that one texture can be convolved into another, returning what's wrong if it can't, which [convolve_passes] panics with.
+pub(crate) fn check_convolve(
+	buffers: &ShaderBufferSet, src: ShaderBufferHandle, dst: ShaderBufferHandle, kernel: &ConvolutionKernel,
+) -> Result<(), String> {
+	kernel.weights()?;
+	let Some((src_format, src_size)) = buffers.texture_format_and_size(src) else {
+		return Err(format!("Tried to convolve {}, which isn't a texture", src.describe(buffers)));
+	};
+	if !matches!(src_format.sample_type(None, None), Some(TextureSampleType::Float { .. })) {
+		return Err(format!("Tried to convolve {}, but only float textures are supported", src.describe(buffers)));
+	}
+	let Some((dst_format, dst_size)) = buffers.texture_format_and_size(dst) else {
+		return Err(format!("Tried to convolve into {}, which isn't a texture", dst.describe(buffers)));
+	};
+	if !DST_FORMATS.iter().any(|(format, _)| *format == dst_format) {
+		return Err(format!(
+			"Tried to convolve into {}, but only R32Float, Rgba16Float and Rgba32Float textures can be written",
+			dst.describe(buffers)
+		));
+	}
+	for texture in [src, dst] {
+		if buffers.texture_dimension(texture) != Some(TextureViewDimension::D2) {
+			return Err(format!("Tried to convolve {}, but only 2D textures are supported", texture.describe(buffers)));
+		}
+	}
+	if (src_size.width, src_size.height) != (dst_size.width, dst_size.height) {
+		return Err(format!(
+			"Tried to convolve {} into {}, which is a different size",
+			src.describe(buffers),
+			dst.describe(buffers)
+		));
+	}
+	Ok(())
+}
+
+/// Build the passes for a separable convolution, one applying the kernel along each row of the source into an intermediate buffer owned by the passes, and one applying it along each column of that into the destination. They're separate passes, each with a bind group of its own, so the source and the destination can be the same texture.
+pub(crate) fn convolve_passes(
+	device: &RenderDevice, pipeline_cache: &PipelineCache, buffers: &ShaderBufferSet, src: ShaderBufferHandle,
+	dst: ShaderBufferHandle, kernel: &ConvolutionKernel, edges: ConvolutionEdges,
+) -> Vec<BuiltinPass> {
+	let weights = match check_convolve(buffers, src, dst, kernel).and_then(|_| kernel.weights()) {
+		Ok(weights) => weights,
+		Err(error) => panic!("{}", error),
+	};
+
+	let (dst_format, size) = buffers.texture_format_and_size(dst).unwrap();
+	let (_, vertical) = DST_FORMATS.iter().find(|(format, _)| *format == dst_format).unwrap();
+	let mut params = ConvolveParams {
+		width: size.width,
+		height: size.height,
+		radius: weights.len() as u32 / 2,
+		edges: edges as u32,
+		weights: Zeroable::zeroed(),
+	};
+	for (i, weight) in weights.into_iter().enumerate() {
+		params.weights[i / 4][i % 4] = weight;
+	}
+	let workgroups = UVec3::new(size.width.div_ceil(WORKGROUP_SIZE), size.height.div_ceil(WORKGROUP_SIZE), 1);
+
+	// Each texel of the intermediate buffer is a vec4<f32>, whatever the format, so nothing is lost between the passes.
+	let intermediate = device.create_buffer(&BufferDescriptor {
+		label: None,
+		size: size.width as u64 * size.height as u64 * 16,
+		usage: BufferUsages::STORAGE,
+		mapped_at_creation: false,
+	});
+	vec![
+		BuiltinPass::new(
+			device,
+			pipeline_cache,
+			CONVOLVE_SHADER_HANDLE,
+			&["horizontal"],
+			vec![BuiltinBinding::Texture(src), BuiltinBinding::Scratch(intermediate.clone())],
+			vec![BuiltinDispatch { entry_point: 0, params, workgroups }],
+		),
+		BuiltinPass::new(
+			device,
+			pipeline_cache,
+			CONVOLVE_SHADER_HANDLE,
+			// Only the entry point for the destination's format is built, as the others expect a different binding.
+			&[vertical],
+			vec![BuiltinBinding::Scratch(intermediate), BuiltinBinding::StorageTexture(dst, dst_format)],
+			vec![BuiltinDispatch { entry_point: 0, params, workgroups }],
+		),
+	]
+}
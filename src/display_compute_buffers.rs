@@ -0,0 +1,78 @@
+use bevy::prelude::*;
+
+use crate::{
+	shader_buffer_set::{ShaderBufferHandle, ShaderBufferSet},
+	DisplayComputeBuffer,
+};
+
+/// Look up the current front image of a texture buffer being displayed, along with whether it needs to be looked up at all. It only does when the buffer set has changed, which happens on every swap, or when the [DisplayComputeBuffer] was just added or changed. So on frames without a swap, nothing is touched.
+pub(crate) fn front_image(
+	buffer_set: &Res<ShaderBufferSet>, display: &Ref<DisplayComputeBuffer>,
+) -> Option<Handle<Image>> {
+	if !buffer_set.is_changed() && !display.is_changed() {
+		return None;
+	}
+	let DisplayComputeBuffer(handle) = **display;
+	Some(image_handle(buffer_set, handle))
+}
+
+fn image_handle(buffer_set: &ShaderBufferSet, handle: ShaderBufferHandle) -> Handle<Image> {
+	buffer_set.image_handle(handle).unwrap_or_else(|| {
+		panic!(
+			"Attempt to update which buffer is displayed on an entity, but {} is not a texture, or no longer exists",
+			handle.describe(buffer_set)
+		)
+	})
+}
+
+/// Point the texture of a material at a new image, but only if it's actually different, as getting a material mutably marks it as modified, which rebuilds its bind group.
+pub(crate) fn retarget_material<M: Asset>(
+	materials: &mut Assets<M>, material: &Handle<M>, image: Handle<Image>,
+	texture: impl Fn(&M) -> Option<&Handle<Image>>, set_texture: impl FnOnce(&mut M, Handle<Image>),
+) {
+	if materials.get(material).is_some_and(|material| texture(material) != Some(&image)) {
+		set_texture(materials.get_mut(material).unwrap(), image);
+	}
+}
+
+pub fn display_compute_buffers(
+	buffer_set: Res<ShaderBufferSet>, mut sprites: Query<(Ref<DisplayComputeBuffer>, &mut Sprite)>,
+	color_displays: Query<(Ref<DisplayComputeBuffer>, &MeshMaterial2d<ColorMaterial>)>,
+	standard_displays: Query<(Ref<DisplayComputeBuffer>, &MeshMaterial3d<StandardMaterial>)>,
+	mut color_materials: Option<ResMut<Assets<ColorMaterial>>>,
+	mut standard_materials: Option<ResMut<Assets<StandardMaterial>>>,
+) {
+	for (display, mut sprite) in sprites.iter_mut() {
+		if let Some(image) = front_image(&buffer_set, &display) {
+			if sprite.image != image {
+				sprite.image = image;
+			}
+		}
+	}
+	if let Some(materials) = &mut color_materials {
+		for (display, MeshMaterial2d(material)) in color_displays.iter() {
+			if let Some(image) = front_image(&buffer_set, &display) {
+				retarget_material(
+					materials,
+					material,
+					image,
+					|material| material.texture.as_ref(),
+					|material, image| material.texture = Some(image),
+				);
+			}
+		}
+	}
+	if let Some(materials) = &mut standard_materials {
+		for (display, MeshMaterial3d(material)) in standard_displays.iter() {
+			if let Some(image) = front_image(&buffer_set, &display) {
+				retarget_material(
+					materials,
+					material,
+					image,
+					|material| material.base_color_texture.as_ref(),
+					|material, image| material.base_color_texture = Some(image),
+				);
+			}
+		}
+	}
+}
@@ -0,0 +1,263 @@
+use std::fmt::Write;
+
+use bevy::prelude::*;
+
+use super::{
+	compute_sequence::{
+		BufferOrientation, ComputeAction, ComputeSequences, ComputeShaderDef, DispatchSize, StepCondition, StepSchedule,
+		TaskIterationBuffer,
+	},
+	ComputeSequenceStates,
+};
+use crate::shader_buffer_set::ShaderBufferSet;
+
+/// A system that logs the full state of bevy_compute: every buffer in the [ShaderBufferSet], and for every compute sequence that's been started, every task and step in it along with how far along it is. It isn't added by the [BevyComputePlugin](crate::BevyComputePlugin), as it's intended to be run on demand while debugging, for instance behind a key press:
+///
+/// ```Rust
+/// app.add_systems(Update, dump_compute_state.run_if(input_just_pressed(KeyCode::F12)));
+/// ```
+pub fn dump_compute_state(world: &World) {
+	let buffers = world.resource::<ShaderBufferSet>();
+	let mut dump = buffers.debug_dump();
+	let Some(sequences) = world.get_resource::<ComputeSequences>() else {
+		dump.push_str("No compute sequence has been started");
+		info!("{}", dump);
+		return;
+	};
+
+	if let Some(buffer) = buffers.globals() {
+		writeln!(dump, "globals buffer: {}", buffer.describe(buffers)).unwrap();
+	}
+	if let Some((buffer, _)) = buffers.size_uniform() {
+		writeln!(dump, "size uniform: {}", buffer.describe(buffers)).unwrap();
+	}
+	if let Some(cursor) = buffers.cursor_uniform() {
+		writeln!(dump, "cursor uniform: {}", cursor.handle.describe(buffers)).unwrap();
+	}
+	if let Some(buffer) = buffers.camera_uniform() {
+		writeln!(dump, "camera uniform: {}", buffer.describe(buffers)).unwrap();
+	}
+	let states = world.resource::<ComputeSequenceStates>();
+	for (id, sequence) in sequences.0.iter() {
+		let (state, progress) = states.0.get(id).cloned().unwrap_or_default();
+		writeln!(dump, "ComputeSequence {}: {} tasks, {:?}", id.0, sequence.tasks.len(), state).unwrap();
+		if let Some(buffer) = sequence.iteration_buffer {
+			writeln!(dump, "  iteration buffer: {}", buffer.describe(buffers)).unwrap();
+		}
+		for (i, task) in sequence.tasks.iter().enumerate() {
+			let iterations = task.iterations.map_or("∞".to_owned(), |iterations| iterations.to_string());
+			let status = if i < progress.current_task {
+				"done".to_owned()
+			} else if i == progress.current_task && progress.pending {
+				"waiting for its start delay".to_owned()
+			} else if i == progress.current_task {
+				let total = progress.total_iterations.map_or(iterations.clone(), |total| total.to_string());
+				format!("running, iteration {}/{}", progress.iterations, total)
+			} else {
+				"pending".to_owned()
+			};
+			writeln!(dump, "  task {} {:?}: {} iterations, {}", i, task.label.as_deref().unwrap_or(""), iterations, status)
+				.unwrap();
+			match task.iteration_buffer {
+				Some(TaskIterationBuffer::Local(buffer)) => {
+					writeln!(dump, "    iteration buffer: {}", buffer.describe(buffers)).unwrap()
+				}
+				Some(TaskIterationBuffer::Counts(buffer)) => {
+					writeln!(dump, "    iteration buffer: {}, with local and global counts", buffer.describe(buffers)).unwrap()
+				}
+				None => {}
+			}
+			for (j, step) in task.steps.iter().enumerate() {
+				write!(dump, "    step {}: ", j).unwrap();
+				match &step.action {
+					ComputeAction::RunShader {
+						shader,
+						entry_point,
+						shader_defs,
+						overrides,
+						dispatch,
+						auto_swap,
+						skip_if_empty,
+						buffer_orientation,
+						push_constants,
+						dynamic_offsets,
+					} => {
+						write!(
+							dump,
+							"run shader {}::{}{} {}",
+							shader,
+							entry_point,
+							describe_shader_defs(shader_defs, overrides),
+							describe_dispatch(dispatch, buffers)
+						)
+						.unwrap();
+						for buffer in auto_swap {
+							write!(dump, ", then swap buffers {}", buffer.describe(buffers)).unwrap();
+						}
+						if let Some(buffer) = skip_if_empty {
+							write!(dump, ", skipped while {} is empty", buffer.describe(buffers)).unwrap();
+						}
+						for (buffer, orientation) in buffer_orientation {
+							if *orientation == BufferOrientation::Reversed {
+								write!(dump, ", with {} bound the other way round", buffer.describe(buffers)).unwrap();
+							}
+						}
+						if let Some(push_constants) = push_constants {
+							write!(dump, ", with {} bytes of push constants", push_constants.size()).unwrap();
+						}
+						for (buffer, element) in dynamic_offsets {
+							write!(dump, ", with element {} of {} bound", element, buffer.describe(buffers)).unwrap();
+						}
+					}
+					ComputeAction::RunShaderPerEntity { shader, entry_point, shader_defs, overrides, dispatch } => {
+						write!(
+							dump,
+							"run shader {}::{}{} {} per entity",
+							shader,
+							entry_point,
+							describe_shader_defs(shader_defs, overrides),
+							describe_dispatch(dispatch, buffers)
+						)
+						.unwrap();
+					}
+					ComputeAction::RunShaderIndirect { shader, entry_point, shader_defs, overrides, indirect_buffer, offset } => {
+						write!(
+							dump,
+							"run shader {}::{}{} with the workgroup counts in {} at offset {}",
+							shader,
+							entry_point,
+							describe_shader_defs(shader_defs, overrides),
+							indirect_buffer.describe(buffers),
+							offset
+						)
+						.unwrap();
+					}
+					ComputeAction::RunShaderMulti { shader, shader_defs, passes } => {
+						write!(dump, "run shader {}{}", shader, describe_shader_defs(shader_defs, &[])).unwrap();
+						for (k, (entry_point, dispatch)) in passes.iter().enumerate() {
+							let separator = if k == 0 { ": " } else { ", then " };
+							write!(dump, "{}{} {}", separator, entry_point, describe_dispatch(dispatch, buffers)).unwrap();
+						}
+					}
+					ComputeAction::CopyBuffer { src, offset: 0, size: None } => {
+						write!(dump, "copy buffer {} to the CPU", src.describe(buffers)).unwrap()
+					}
+					ComputeAction::CopyBuffer { src, offset, size } => {
+						let size = size.map_or_else(|| "the rest".to_owned(), |size| format!("{} bytes", size));
+						write!(dump, "copy {} of {} from offset {} to the CPU", size, src.describe(buffers), offset).unwrap()
+					}
+					ComputeAction::CopyBufferToBuffer { src, dst, src_offset, dst_offset, size } => {
+						let size = size.map_or_else(|| "the rest".to_owned(), |size| format!("{} bytes", size));
+						write!(
+							dump,
+							"copy {} of {} from offset {} into {} at offset {}",
+							size,
+							src.describe(buffers),
+							src_offset,
+							dst.describe(buffers),
+							dst_offset
+						)
+						.unwrap()
+					}
+					ComputeAction::CopyTextureToBuffer { src, dst } | ComputeAction::CopyBufferToTexture { src, dst } => {
+						write!(dump, "copy {} into {}", src.describe(buffers), dst.describe(buffers)).unwrap()
+					}
+					ComputeAction::ClearBuffer { buffer, offset, size, halves } => {
+						let size = size.map_or_else(|| "the rest".to_owned(), |size| format!("{} bytes", size));
+						write!(dump, "clear {} of {} from offset {}, {:?} halves", size, buffer.describe(buffers), offset, halves)
+							.unwrap()
+					}
+					ComputeAction::ClearTexture { texture, value, halves } => {
+						write!(dump, "clear {} to {:?}, {:?} halves", texture.describe(buffers), value, halves).unwrap()
+					}
+					ComputeAction::ReadBuffer { buffer } => {
+						write!(dump, "read buffer {} back to the CPU", buffer.describe(buffers)).unwrap()
+					}
+					ComputeAction::SwapBuffers { buffer } => write!(dump, "swap buffers {}", buffer.describe(buffers)).unwrap(),
+					ComputeAction::RotateBuffers { buffer } => {
+						write!(dump, "rotate buffers {}", buffer.describe(buffers)).unwrap()
+					}
+					ComputeAction::SortBuffer { buffer, element_count, key } => write!(
+						dump,
+						"sort {} elements of {} by {:?} key at offset {}, stride {}",
+						element_count,
+						buffer.describe(buffers),
+						key.key_type,
+						key.offset,
+						key.stride
+					)
+					.unwrap(),
+					ComputeAction::ReduceBuffer { src, dst, op, element } => write!(
+						dump,
+						"reduce {:?} elements of {} by {:?} into {}",
+						element,
+						src.describe(buffers),
+						op,
+						dst.describe(buffers)
+					)
+					.unwrap(),
+					ComputeAction::PrefixSum { buffer, element_count } => {
+						write!(dump, "prefix sum {} elements of {}", element_count, buffer.describe(buffers)).unwrap()
+					}
+					ComputeAction::Histogram { src, bins, bin_count, range, source, out_of_range } => write!(
+						dump,
+						"histogram of {:?} {} into {} bins of {} from {} to {}, {:?} out of range",
+						source,
+						src.describe(buffers),
+						bin_count,
+						bins.describe(buffers),
+						range.0,
+						range.1,
+						out_of_range
+					)
+					.unwrap(),
+					ComputeAction::Convolve { src, dst, kernel, edges } => write!(
+						dump,
+						"convolve {} into {} with {:?}, {:?} at the edges",
+						src.describe(buffers),
+						dst.describe(buffers),
+						kernel,
+						edges
+					)
+					.unwrap(),
+					ComputeAction::CheckFinite { buffer, element } => {
+						write!(dump, "check {:?} {} for NaNs and infinities", element, buffer.describe(buffers)).unwrap()
+					}
+				}
+				match step.schedule {
+					StepSchedule::EveryIteration => {}
+					StepSchedule::MaxFrequencyHz(hz) => write!(dump, ", at most {} Hz", hz).unwrap(),
+					StepSchedule::EveryNFrames(frames) => write!(dump, ", every {} frames", frames).unwrap(),
+				}
+				match &step.run_if {
+					None => {}
+					Some(StepCondition::ResourceFlag(flag)) => write!(dump, ", while {} is set", flag).unwrap(),
+					Some(StepCondition::OneShotFlag(flag)) => write!(dump, ", once each time {} is set", flag).unwrap(),
+				}
+				dump.push('\n');
+			}
+		}
+	}
+	info!("{}", dump);
+}
+
+fn describe_shader_defs(shader_defs: &[ComputeShaderDef], overrides: &[(String, f64)]) -> String {
+	if shader_defs.is_empty() && overrides.is_empty() {
+		return String::new();
+	}
+	let defs = shader_defs.iter().map(ToString::to_string);
+	let overrides = overrides.iter().map(|(name, value)| format!("{}={}", name, value));
+	format!(" [{}]", defs.chain(overrides).collect::<Vec<_>>().join(", "))
+}
+
+fn describe_dispatch(dispatch: &DispatchSize, buffers: &ShaderBufferSet) -> String {
+	match dispatch {
+		DispatchSize::Workgroups { x, y, z } => format!("with {}x{}x{} workgroups", x, y, z),
+		DispatchSize::Exact { x, y, z } => format!("over {}x{}x{} invocations", x, y, z),
+		DispatchSize::Chunked { total: (x, y, z), max_per_dispatch: (max_x, max_y, max_z), chunks_per_iteration } => {
+			let per_iteration = chunks_per_iteration.map_or(String::new(), |chunks| format!(", {} per iteration", chunks));
+			format!("with {}x{}x{} workgroups in chunks of up to {}x{}x{}{}", x, y, z, max_x, max_y, max_z, per_iteration)
+		}
+		DispatchSize::FromTexture { buffer } => format!("over the size of {}", buffer.describe(buffers)),
+	}
+}
@@ -0,0 +1,116 @@
+use std::sync::{Arc, Mutex, OnceLock};
+
+use bevy::{
+	prelude::*,
+	render::{
+		render_resource::{Buffer, BufferDescriptor, BufferUsages, CommandEncoder, MapMode},
+		renderer::RenderDevice,
+	},
+	utils::HashMap,
+};
+
+use crate::shader_buffer_set::{ShaderBufferHandle, ShaderBufferSet};
+
+/// The size of the count read back, a single `u32`.
+const COUNT_SIZE: u64 = 4;
+
+/// Check that a buffer can be read back to skip a step by, returning what's wrong if it can't, which the compute node panics with when the step's task starts.
+pub(crate) fn check_empty_source(buffers: &ShaderBufferSet, buffer: ShaderBufferHandle) -> Result<(), String> {
+	match buffers.gpu_buffer(buffer) {
+		None => Err(format!("Tried to skip a step when {} is empty, but it isn't a storage buffer", buffer.describe(buffers))),
+		Some(gpu_buffer) if gpu_buffer.size() < COUNT_SIZE => Err(format!(
+			"Tried to skip a step when {} is empty, but it's too small to hold a count",
+			buffer.describe(buffers)
+		)),
+		Some(gpu_buffer) if !gpu_buffer.usage().contains(BufferUsages::COPY_SRC) => Err(format!(
+			"Tried to skip a step when {} is empty, but it wasn't created with BufferUsages::COPY_SRC, so its count can't be read back",
+			buffer.describe(buffers)
+		)),
+		Some(_) => Ok(()),
+	}
+}
+
+/// The latest count read back from a buffer, and the read that's in flight, if there is one.
+#[derive(Default)]
+struct EmptyCheck {
+	count: Option<u32>,
+	buffer: Option<Buffer>,
+	mapped: Option<Arc<OnceLock<bool>>>,
+	reading: bool,
+}
+
+/// The checks by the generation of the compute sequence that made them and the buffer they're of, as a handle from a sequence that's been stopped may have been reused since.
+#[derive(Default)]
+struct EmptyCheckerState {
+	checks: HashMap<(u32, ShaderBufferHandle), EmptyCheck>,
+}
+
+/// Reads back the counts in the first `u32` of the buffers that [skip_if_empty](crate::ComputeAction::RunShader::skip_if_empty) steps are skipped by, in the render world. One read of each buffer is in flight at a time, so the count a step is skipped by is from a frame or two before, which is the latest that can be known without waiting for the GPU.
+#[derive(Resource, Default)]
+pub(crate) struct ComputeEmptyChecker(Mutex<EmptyCheckerState>);
+
+impl ComputeEmptyChecker {
+	/// Whether the buffer's count was zero when it was last read back. Until it's been read back once, it isn't known to be empty, so the step runs.
+	pub fn empty(&self, generation: u32, buffer: ShaderBufferHandle) -> bool {
+		let state = self.0.lock().unwrap();
+		state.checks.get(&(generation, buffer)).is_some_and(|check| check.count == Some(0))
+	}
+
+	/// Forget the counts of the compute sequences that are no longer running, once they aren't being read.
+	pub fn retain(&self, running: impl Fn(u32) -> bool) {
+		self.0.lock().unwrap().checks.retain(|(generation, _), check| check.reading || running(*generation));
+	}
+
+	/// Drop the counts and the buffers they're copied into, once the app is exiting.
+	pub fn release(&self) { *self.0.lock().unwrap() = EmptyCheckerState::default(); }
+
+	/// Copy the count out of the buffer, to be read back once the frame has been submitted, unless a read of it is already in flight. Each compute sequence keeps its own counts, as the handles of one that's been stopped may have been reused.
+	pub fn record(
+		&self, device: &RenderDevice, encoder: &mut CommandEncoder, generation: u32, handle: ShaderBufferHandle,
+		src: &Buffer,
+	) {
+		let mut state = self.0.lock().unwrap();
+		let check = state.checks.entry((generation, handle)).or_default();
+		if check.reading {
+			return;
+		}
+		let buffer = check.buffer.get_or_insert_with(|| {
+			device.create_buffer(&BufferDescriptor {
+				label: None,
+				size: COUNT_SIZE,
+				usage: BufferUsages::MAP_READ | BufferUsages::COPY_DST,
+				mapped_at_creation: false,
+			})
+		});
+		encoder.copy_buffer_to_buffer(src, 0, buffer, 0, COUNT_SIZE);
+		check.reading = true;
+	}
+}
+
+/// Map the counts copied out this frame, as the frame's commands have just been submitted, and take the counts of the ones that have been mapped since.
+pub(crate) fn read_empty_checks(checker: Res<ComputeEmptyChecker>) {
+	let mut state = checker.0.lock().unwrap();
+	for check in state.checks.values_mut().filter(|check| check.reading) {
+		let Some(buffer) = &check.buffer else {
+			continue;
+		};
+		let Some(mapped) = &check.mapped else {
+			let mapped = Arc::new(OnceLock::new());
+			let result = mapped.clone();
+			buffer.slice(..).map_async(MapMode::Read, move |map_result| {
+				let _ = result.set(map_result.is_ok());
+			});
+			check.mapped = Some(mapped);
+			continue;
+		};
+		let Some(&success) = mapped.get() else {
+			continue;
+		};
+		if success {
+			check.count = Some(bytemuck::pod_read_unaligned(&buffer.slice(..).get_mapped_range()));
+			buffer.unmap();
+		}
+		check.mapped = None;
+		check.reading = false;
+	}
+}
@@ -3,19 +3,22 @@ use bevy::{
 	render::{extract_resource::ExtractResource, Extract},
 };
 
-use super::compute_sequence::ComputeSequence;
+use super::compute_sequence::ComputeSequences;
 
 pub fn extract_resources(
-	mut commands: Commands, main_data: Extract<Option<Res<ComputeSequence>>>,
-	target_data: Option<ResMut<ComputeSequence>>,
+	mut commands: Commands, main_data: Extract<Option<Res<ComputeSequences>>>,
+	target_data: Option<ResMut<ComputeSequences>>,
 ) {
 	if let Some(main_data) = &*main_data {
 		if let Some(mut target_data) = target_data {
 			if main_data.is_changed() {
-				*target_data = ComputeSequence::extract_resource(&main_data);
+				*target_data = ComputeSequences::extract_resource(main_data);
 			}
 		} else {
-			commands.insert_resource(ComputeSequence::extract_resource(&main_data));
+			commands.insert_resource(ComputeSequences::extract_resource(main_data));
 		}
+	} else if target_data.is_some() {
+		// The last sequence has been stopped with a StopComputeEvent.
+		commands.remove_resource::<ComputeSequences>();
 	}
 }
@@ -0,0 +1,48 @@
+/// Convert a 16-bit float, given as its raw bits, such as one channel of an [Rgba16Float](bevy::render::render_resource::TextureFormat::Rgba16Float) texture that was read back, to an `f32`. Every 16-bit float can be represented exactly, including subnormals, infinities and NaN.
+pub fn f16_to_f32(bits: u16) -> f32 {
+	let sign = ((bits & 0x8000) as u32) << 16;
+	let exponent = ((bits >> 10) & 0x1f) as u32;
+	let mantissa = (bits & 0x3ff) as u32;
+	match exponent {
+		0 => {
+			// Subnormals are the mantissa times 2^-24, which an f32 has plenty of range for.
+			let magnitude = mantissa as f32 / 16_777_216.0;
+			if sign != 0 {
+				-magnitude
+			} else {
+				magnitude
+			}
+		}
+		0x1f => f32::from_bits(sign | 0x7f80_0000 | (mantissa << 13)),
+		_ => f32::from_bits(sign | ((exponent + 112) << 23) | (mantissa << 13)),
+	}
+}
+
+/// Convert an `f32` to the raw bits of the nearest 16-bit float, rounding ties to even, such as to make the fill of an [Rgba16Float](bevy::render::render_resource::TextureFormat::Rgba16Float) texture. Values too large for a 16-bit float, whose largest is 65504, become infinity, and values too small become zero.
+pub fn f32_to_f16(value: f32) -> u16 {
+	let bits = value.to_bits();
+	let sign = ((bits >> 16) & 0x8000) as u16;
+	let exponent = ((bits >> 23) & 0xff) as i32;
+	let mantissa = bits & 0x7f_ffff;
+	if exponent == 0xff {
+		// NaN keeps a mantissa bit set, so it doesn't turn into infinity.
+		let nan = if mantissa != 0 { 0x200 | (mantissa >> 13) as u16 } else { 0 };
+		return sign | 0x7c00 | nan;
+	}
+	let exponent = exponent - 127 + 15;
+	if exponent >= 0x1f {
+		return sign | 0x7c00;
+	}
+	if exponent <= 0 {
+		if exponent < -10 {
+			return sign;
+		}
+		let mantissa = mantissa | 0x80_0000;
+		let shift = (14 - exponent) as u32;
+		let rounded = mantissa + (1 << (shift - 1)) - 1 + ((mantissa >> shift) & 1);
+		return sign | (rounded >> shift) as u16;
+	}
+	// Rounding up can carry into the exponent, which is still the right result, even when it carries into infinity.
+	let rounded = mantissa + 0xfff + ((mantissa >> 13) & 1);
+	sign | (((exponent as u32) << 10) + (rounded >> 13)).min(0x7c00) as u16
+}
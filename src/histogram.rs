@@ -0,0 +1,147 @@
+use bevy::{
+	prelude::*,
+	render::{
+		render_resource::{PipelineCache, TextureSampleType, TextureViewDimension},
+		renderer::RenderDevice,
+	},
+};
+use bytemuck::{Pod, Zeroable};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use super::builtin_pass::{BuiltinBinding, BuiltinDispatch, BuiltinPass};
+use crate::shader_buffer_set::{ShaderBufferHandle, ShaderBufferSet};
+
+pub(crate) const HISTOGRAM_SHADER_HANDLE: Handle<Shader> =
+	Handle::weak_from_u128(0x4e6a_d2f8_1b73_4c09_a5e1_70cb_39d4_f62e);
+
+const WORKGROUP_SIZE: u32 = 256;
+
+/// Where a [Histogram](crate::ComputeAction::Histogram) action reads its values from.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum HistogramSource {
+	/// A storage buffer of `f32`s, every one of which is counted.
+	Buffer,
+
+	/// A texture with a single float channel, such as [R32Float](bevy::render::render_resource::TextureFormat::R32Float), every texel of which is counted. If it's a double buffer, the front buffer is read.
+	Texture,
+}
+
+/// What a [Histogram](crate::ComputeAction::Histogram) action does with values outside its range.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum HistogramOutOfRange {
+	/// Count values below the range in the first bin, and values above it in the last.
+	Clamp,
+
+	/// Don't count values outside the range at all.
+	Discard,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Pod, Zeroable)]
+struct HistogramParams {
+	count: u32,
+	width: u32,
+	bin_count: u32,
+	clamp: u32,
+	range_min: f32,
+	range_max: f32,
+}
+
+/// Check that the values of a buffer or texture can be counted into bins, returning what's wrong if they can't, which [histogram_pass] panics with.
+pub(crate) fn check_histogram(
+	buffers: &ShaderBufferSet, src: ShaderBufferHandle, bins: ShaderBufferHandle, bin_count: u32, range: (f32, f32),
+	source: HistogramSource,
+) -> Result<(), String> {
+	if bin_count == 0 {
+		return Err(format!("Tried to make a histogram of {} with no bins", src.describe(buffers)));
+	}
+	if range.0 >= range.1 || !range.0.is_finite() || !range.1.is_finite() {
+		return Err(format!(
+			"Tried to make a histogram of {} over the range {} to {}, which must be finite and increasing",
+			src.describe(buffers),
+			range.0,
+			range.1
+		));
+	}
+	let Some(bins_buffer) = buffers.gpu_buffer(bins) else {
+		return Err(format!("Tried to make a histogram in {}, which isn't a storage buffer", bins.describe(buffers)));
+	};
+	if bin_count as u64 * 4 > bins_buffer.size() {
+		return Err(format!(
+			"Tried to make a histogram of {} bins in {}, which is too small to hold them",
+			bin_count,
+			bins.describe(buffers)
+		));
+	}
+	match source {
+		HistogramSource::Buffer if buffers.gpu_buffer(src).is_none() => {
+			Err(format!("Tried to make a histogram of {}, which isn't a storage buffer", src.describe(buffers)))
+		}
+		HistogramSource::Buffer => Ok(()),
+		HistogramSource::Texture => match buffers.texture_format_and_size(src) {
+			None => Err(format!("Tried to make a histogram of {}, which isn't a texture", src.describe(buffers))),
+			Some((format, _))
+				if format.components() != 1
+					|| !matches!(format.sample_type(None, None), Some(TextureSampleType::Float { .. })) =>
+			{
+				Err(format!(
+					"Tried to make a histogram of {}, but only textures with a single float channel are supported",
+					src.describe(buffers)
+				))
+			}
+			Some(_) if buffers.texture_dimension(src) != Some(TextureViewDimension::D2) => {
+				Err(format!("Tried to make a histogram of {}, but only 2D textures are supported", src.describe(buffers)))
+			}
+			Some(_) => Ok(()),
+		},
+	}
+}
+
+/// Build the passes for a histogram, one to clear the bins and one to count every value into them with atomics.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn histogram_pass(
+	device: &RenderDevice, pipeline_cache: &PipelineCache, buffers: &ShaderBufferSet, src: ShaderBufferHandle,
+	bins: ShaderBufferHandle, bin_count: u32, range: (f32, f32), source: HistogramSource,
+	out_of_range: HistogramOutOfRange,
+) -> BuiltinPass {
+	if let Err(error) = check_histogram(buffers, src, bins, bin_count, range, source) {
+		panic!("{}", error);
+	}
+
+	let (count, width, entry_point, src_binding) = match source {
+		HistogramSource::Buffer => {
+			let src_buffer = buffers.gpu_buffer(src).unwrap();
+			((src_buffer.size() / 4) as u32, 0, "bin_buffer", BuiltinBinding::Buffer(src))
+		}
+		HistogramSource::Texture => {
+			let (_, size) = buffers.texture_format_and_size(src).unwrap();
+			(size.width * size.height, size.width, "bin_texture", BuiltinBinding::Texture(src))
+		}
+	};
+
+	let params = HistogramParams {
+		count,
+		width,
+		bin_count,
+		clamp: (out_of_range == HistogramOutOfRange::Clamp) as u32,
+		range_min: range.0,
+		range_max: range.1,
+	};
+	let dispatches = vec![
+		BuiltinDispatch { entry_point: 0, params, workgroups: UVec3::new(bin_count.div_ceil(WORKGROUP_SIZE), 1, 1) },
+		BuiltinDispatch { entry_point: 1, params, workgroups: UVec3::new(count.div_ceil(WORKGROUP_SIZE).max(1), 1, 1) },
+	];
+
+	BuiltinPass::new(
+		device,
+		pipeline_cache,
+		HISTOGRAM_SHADER_HANDLE,
+		// Only the entry point for the source is built, as the other expects a different binding.
+		&["clear", entry_point],
+		vec![BuiltinBinding::Buffer(bins), src_binding],
+		dispatches,
+	)
+}
@@ -0,0 +1,103 @@
+use std::sync::{Arc, Mutex};
+
+use bevy::{
+	prelude::*,
+	render::render_resource::{encase::private::WriteInto, ShaderType},
+	window::PrimaryWindow,
+};
+use bevy_inspector_egui::{
+	bevy_egui::{EguiContext, EguiPlugin},
+	egui,
+	reflect_inspector::{ui_for_value, ui_for_value_readonly},
+	DefaultInspectorConfigPlugin,
+};
+
+use crate::{
+	shader_buffer_set::{ShaderBufferHandle, ShaderBufferSet},
+	ComputeProgress, ComputeState,
+};
+
+#[derive(Clone)]
+pub(crate) struct InspectableBuffer {
+	pub handle: ShaderBufferHandle,
+	value: Arc<Mutex<Box<dyn Reflect>>>,
+	write: fn(&dyn Reflect, ShaderBufferHandle, &mut ShaderBufferSet),
+}
+
+fn write_inspectable<T: Reflect + ShaderType + WriteInto + Clone>(
+	value: &dyn Reflect, handle: ShaderBufferHandle, buffers: &mut ShaderBufferSet,
+) {
+	if let Some(value) = value.downcast_ref::<T>() {
+		if let Err(error) = buffers.write_buffer(handle, value.clone()) {
+			warn!("{}", error);
+		}
+	}
+}
+
+impl ShaderBufferSet {
+	/// Register a buffer to be editable in the inspector window added by [ComputeInspectorPlugin]. The value provided is what the inspector will start out displaying, which should match what the buffer was initialized with, as the inspector has no way of reading the contents back from the GPU. Every time the value is edited in the inspector, it will be written to the buffer with [write_buffer](ShaderBufferSet::write_buffer). Registering a buffer that's already registered replaces its value. Only available with the `inspector` feature.
+	pub fn register_inspectable<T: Reflect + ShaderType + WriteInto + Clone>(
+		&mut self, handle: ShaderBufferHandle, value: T,
+	) {
+		self.inspectables.retain(|inspectable| inspectable.handle != handle);
+		self.inspectables.push(InspectableBuffer {
+			handle,
+			value: Arc::new(Mutex::new(Box::new(value))),
+			write: write_inspectable::<T>,
+		});
+	}
+}
+
+/// This plugin adds an egui window to the primary window, which shows every buffer in the [ShaderBufferSet], the [ComputeState] and [ComputeProgress] of the running compute sequence, and an editor for every buffer registered with [register_inspectable](ShaderBufferSet::register_inspectable). It must be added alongside the [BevyComputePlugin](crate::BevyComputePlugin), and is only available with the `inspector` feature.
+pub struct ComputeInspectorPlugin;
+
+impl Plugin for ComputeInspectorPlugin {
+	fn build(&self, app: &mut App) {
+		if !app.is_plugin_added::<DefaultInspectorConfigPlugin>() {
+			app.add_plugins(DefaultInspectorConfigPlugin);
+		}
+		if !app.is_plugin_added::<EguiPlugin>() {
+			app.add_plugins(EguiPlugin);
+		}
+		app.register_type::<ComputeProgress>().register_type::<ComputeState>().add_systems(Update, compute_inspector_ui);
+	}
+}
+
+fn compute_inspector_ui(world: &mut World) {
+	let Ok(egui_context) = world.query_filtered::<&mut EguiContext, With<PrimaryWindow>>().get_single(world) else {
+		return;
+	};
+	let mut egui_context = egui_context.clone();
+	let type_registry = world.resource::<AppTypeRegistry>().0.clone();
+	let type_registry = type_registry.read();
+
+	egui::Window::new("bevy_compute").show(egui_context.get_mut(), |ui| {
+		egui::ScrollArea::both().show(ui, |ui| {
+			ui.collapsing("Progress", |ui| {
+				ui_for_value_readonly(world.resource::<ComputeState>(), ui, &type_registry);
+				ui_for_value_readonly(world.resource::<ComputeProgress>(), ui, &type_registry);
+			});
+
+			ui.collapsing("Buffers", |ui| {
+				for line in world.resource::<ShaderBufferSet>().debug_dump().lines() {
+					ui.monospace(line);
+				}
+			});
+
+			let inspectables = world.resource::<ShaderBufferSet>().inspectables.clone();
+			if !inspectables.is_empty() {
+				ui.collapsing("Uniforms", |ui| {
+					for inspectable in inspectables {
+						let mut value = inspectable.value.lock().unwrap();
+						ui.label(inspectable.handle.describe(world.resource::<ShaderBufferSet>()));
+						if ui_for_value(value.as_partial_reflect_mut(), ui, &type_registry) {
+							let mut buffers = world.resource_mut::<ShaderBufferSet>();
+							(inspectable.write)(value.as_ref(), inspectable.handle, &mut buffers);
+						}
+						ui.separator();
+					}
+				});
+			}
+		});
+	});
+}
@@ -11,9 +11,15 @@
 //! This is done in the standard way. Just add this call to your Bevy app initialization:
 //!
 //! ```Rust
-//! app.add_plugins((BevyComputePlugin));
+//! app.add_plugins(BevyComputePlugin);
 //! ```
 //!
+//! Everything a typical app needs can be imported with `use bevy_compute::prelude::*;`.
+//!
+//! The plugin is configured with a [ComputePluginSettings] resource, inserted before the plugin is added. Its main setting is [polling](ComputePluginSettings::polling), which is how the GPU is polled for finished work, such as buffer readbacks. By default it's polled once a frame, and readbacks wait for the GPU, but it can be polled from a background thread instead, so results arrive as soon as they're ready, or left to the app to poll itself. See [PollingMode].
+//!
+//! The plugin costs next to nothing until it's used, so it can be added unconditionally, even where compute is an optional feature that's turned off. The [ShaderBufferSet] is only extracted to the render world in frames it changes, and until a [StartComputeEvent] is sent, the compute node isn't added to the render graph.
+//!
 //! # Making Buffers
 //!
 //! The [ShaderBufferSet] provides a simple API for managing GPU buffers. This is added as a resource by the [BevyComputePlugin], so you can request `Res<ShaderBufferSet>` in any system to manage your buffers.
@@ -24,21 +30,73 @@
 //! - [add_storage_zeroed](ShaderBufferSet::add_storage_zeroed) - Add a storage buffer filled with 0 bytes.
 //! - [add_storage_init](ShaderBufferSet::add_storage_init) - Add a storage buffer with initial data provided.
 //! - [add_uniform_init](ShaderBufferSet::add_uniform_init) - Add a uniform buffer with initial data provided.
+//! - [add_uniform_dynamic](ShaderBufferSet::add_uniform_dynamic) - Add a uniform buffer holding several elements of one type, only one of which is bound at a time, picked by each [RunShader](ComputeAction::RunShader) step with its [dynamic_offsets](ComputeAction::RunShader::dynamic_offsets), so steps can share one buffer of parameters. Each element is padded out to the GPU's `min_uniform_buffer_offset_alignment`, usually 256 bytes.
 //! - [add_texture_fill](ShaderBufferSet::add_texture_fill) - Add a texture buffer filled with a solid color.
+//! - [add_texture_fill_double](ShaderBufferSet::add_texture_fill_double) - Add a double buffered texture with each half filled with a different solid color, the first for the half bound at the front binding and the second for the back.
+//! - [add_texture_fill_3d](ShaderBufferSet::add_texture_fill_3d) - Add a 3D texture buffer filled with a solid color, bound as a `texture_storage_3d`.
+//! - [add_texture_fill_layers](ShaderBufferSet::add_texture_fill_layers) - Add an array of texture layers filled with a solid color, bound as a `texture_storage_2d_array`.
+//! - [add_vertex_buffer](ShaderBufferSet::add_vertex_buffer) - Add a zeroed storage buffer that can also be drawn from as vertex data.
+//! - [add_index_buffer](ShaderBufferSet::add_index_buffer) - Add a zeroed storage buffer that can also be drawn from as index data.
+//! - [add_draw_indirect_args](ShaderBufferSet::add_draw_indirect_args) - Add a storage buffer holding the arguments of an indirect draw.
+//! - [add_draw_indexed_indirect_args](ShaderBufferSet::add_draw_indexed_indirect_args) - Add a storage buffer holding the arguments of an indexed indirect draw.
+//! - [add_dispatch_indirect_args](ShaderBufferSet::add_dispatch_indirect_args) - Add a storage buffer holding the workgroup counts of an indirect dispatch, for a [RunShaderIndirect](ComputeAction::RunShaderIndirect) action.
+//! - [add_counters](ShaderBufferSet::add_counters) - Add a zeroed storage buffer of `u32` counters, for shaders to count into with atomics and the CPU to read back.
+//! - [add_counters_u64](ShaderBufferSet::add_counters_u64) - The same, with `u64` counters, for counts that would overflow a `u32`. This needs 64-bit atomics, which many GPUs and every browser lack, as told by [ComputeCapabilities::int64_atomics].
+//! - [add_globals](ShaderBufferSet::add_globals) - Add a uniform buffer the plugin keeps up to date with the time and frame count. See the "Globals" section below.
+//! - [add_size_uniform](ShaderBufferSet::add_size_uniform) - Add a uniform buffer the plugin keeps up to date with the size of the window and a texture. See the "Globals" section below.
+//! - [add_cursor_uniform](ShaderBufferSet::add_cursor_uniform) - Add a uniform buffer the plugin keeps up to date with the cursor position over a texture and the mouse buttons. See the "Globals" section below.
+//! - [add_camera_uniform](ShaderBufferSet::add_camera_uniform) - Add a uniform buffer the plugin keeps up to date with the matrices of a camera. See the "Globals" section below.
+//! - [add_texture_array_binding](ShaderBufferSet::add_texture_array_binding) - Bind an array of existing textures, with the sample type they're read as, as a WGSL `binding_array`. This needs the `TEXTURE_BINDING_ARRAY` feature, which isn't available on the web.
+//! - [add_sampled_texture](ShaderBufferSet::add_sampled_texture) - Bind an existing texture to be read with `textureLoad` or `textureSampleLevel`, with the sample type it's read as, such as a non-filterable float for `R32Float` textures.
+//! - [add_texture_from_handle](ShaderBufferSet::add_texture_from_handle) - Bind an existing image, such as one loaded from a file, as a storage texture, or a sampled one, without copying it. The shaders wait for it to load, and its usages are patched to bind it.
+//! - [add_sampler](ShaderBufferSet::add_sampler) - Add a sampler, for reading sampled textures with filtering, such as for bilinear lookups.
 //!
-//! All of these return a [ShaderBufferHandle], which you can store and treat like an opaque reference to access the buffer in the future. Except for [add_texture_fill](ShaderBufferSet::add_texture_fill), which returns a tuple of two such handles.
+//! All of these return a [ShaderBufferHandle], which you can store and treat like an opaque reference to access the buffer in the future, or a [ShaderBufferError] if the buffer can't be added. Except for [add_texture_fill](ShaderBufferSet::add_texture_fill), which returns a tuple of two such handles.
 //!
-//! Every one of these functions takes a [Binding], which determines how it's bound to the shaders. WGSL shaders require that each buffer have a group and a binding, which are numeric identifiers used to match the buffers specified on the CPU to those that exist in the shaders. The [Binding] is an enum, which can come in three types:
+//! The storage and uniform constructors that take a [BufferUsages](bevy::render::render_resource::BufferUsages) always add the usages the buffer needs to be bound and set with [write_buffer](ShaderBufferSet::write_buffer), `STORAGE` or `UNIFORM` along with `COPY_DST`, logging a debug message if any were missing, so the `usage` only has to list anything extra, such as `COPY_SRC` to read it back.
+//!
+//! Every one of these functions takes a [Binding], which determines how it's bound to the shaders. WGSL shaders require that each buffer have a group and a binding, which are numeric identifiers used to match the buffers specified on the CPU to those that exist in the shaders. The [Binding] is an enum, which can come in five types:
 //!
 //! - [SingleBound(u32, u32)](Binding::SingleBound) - This is the standard binding. The first value is the group and the second the binding.
 //! - [Double(u32, (u32, u32))](Binding::Double) - This is a double buffer. There's actually two buffers. One is considered the front buffer, and one the back buffer, and they can be swapped. The first value the group both buffers will be in, and the tuple is the bindings of the front and back buffers, respectively. This is discussed in more detail in the "Double Buffering" section below.
 //! - [SingleUnbound](Binding::SingleUnbound) - This buffer is not bound, and is thus inaccessible in shaders. While there are unbound buffers used in the background for data transmission purposes, it's rarely if ever useful to specify this at this level.
+//! - [PerEntity(u32, u32)](Binding::PerEntity) - This buffer belongs to a single entity, and is only bound while running a shader per entity. See the "Per-Entity Buffers" section below.
+//! - [Ring(u32, u32, u32)](Binding::Ring) - This is a ring of buffers, one of which is the current buffer, for keeping more than two states around. The first value is the group, the second the binding of the first buffer, and the third the number of buffers, which are bound at that binding and the ones after it. This is discussed in more detail in the "Rings" section below.
+//!
+//! Each binding in a group can only hold one buffer, counting both halves of a double buffer, so adding a buffer where another is already bound fails with [ShaderBufferError::DuplicateBinding], naming the buffer that's there, before anything is allocated, rather than leaving wgpu to fail on the group's layout later. Groups should also be numbered from zero without gaps, as a group with no buffers below one that has some gets an empty layout, which won't match a shader that binds anything in it. That's warned about when a compute sequence starts, and reported by a [ValidateComputeEvent].
 //!
 //! The [ShaderBufferSet] also provides a few more functions for managing buffers:
 //!
+//! - [add_namespace](ShaderBufferSet::add_namespace) - Reserves a range of groups for a plugin, so it can number its groups from zero without clashing with other plugins. See the "Binding Namespaces" section below.
+//! - [allocated_bytes](ShaderBufferSet::allocated_bytes) - The total bytes of GPU memory allocated through the set, counting both halves of double buffers. With a [memory_budget](ComputePluginSettings::memory_budget) in the plugin's settings, or one set with [set_memory_budget](ShaderBufferSet::set_memory_budget), adding a buffer that would go over it fails with [ShaderBufferError::BudgetExceeded] before anything is allocated, and [fits_budget](ShaderBufferSet::fits_budget) checks first whether one would fit.
+//! - [consume_in_render](ShaderBufferSet::consume_in_render) - Marks a buffer or texture as read by rendering in the same frame it's written, such as a lookup texture a material samples. See the "Drawing Compute-Generated Meshes" section below.
+//! - [debug_dump](ShaderBufferSet::debug_dump) - Renders a human-readable table of every buffer in the set, for debugging. The [dump_compute_state] system logs this along with the state of the running compute sequence.
 //! - [delete_buffer](ShaderBufferSet::delete_buffer) - Predictably, this deletes a buffer.
+//! - [delete_all_buffers](ShaderBufferSet::delete_all_buffers) - Deletes every buffer at once. This is done for you when the app exits, along with stopping the compute sequence and releasing everything the plugin made on the GPU, so nothing is left to be dropped along with the device.
+//! - [gpu_buffer](ShaderBufferSet::gpu_buffer) - Gets the raw GPU buffer of a storage or uniform buffer, for use in the render world.
 //! - [image_handle](ShaderBufferSet::image_handle) - Extracts the Bevy `Handle<Image>` associated with a texture buffer, so it can be displayed.
-//! - [set_buffer](ShaderBufferSet::set_buffer) - Sets the contents of a buffer.
+//! - [image_handles](ShaderBufferSet::image_handles) - Extracts the `Handle<Image>`s of both the front and back buffers of a double buffered texture.
+//! - [image_at_binding](ShaderBufferSet::image_at_binding) - Gets the `Handle<Image>` of the texture a shader currently sees at a binding, which tells the halves of a double buffered texture apart the way shaders do.
+//! - [labeled](ShaderBufferSet::labeled) - Labels the next buffer added, as in `buffers.labeled("particles").add_storage_init(...)`, so its GPU buffers or textures are named in GPU captures, such as RenderDoc's, and in wgpu's validation errors, with "(front)" and "(back)" after it for the halves of a double buffer. Panics from this crate show the label too. Bind groups are labeled with their group, and compute passes with the label of the task they're for.
+//! - [resize_storage](ShaderBufferSet::resize_storage) - Resizes a storage buffer, keeping its handle, optionally copying over as much of its old contents as fits, such as when the number of particles grows. Both halves of a double buffer are resized, and the bind groups are made again for the next compute steps.
+//! - [set_texture_array](ShaderBufferSet::set_texture_array) - Replaces the textures of a texture array binding, rebuilding the running pipelines if the number changes.
+//! - [set_texture_view](ShaderBufferSet::set_texture_view) - Binds only part of a texture buffer, such as a single mip level or array layer, described by a [TextureViewSpec].
+//! - [write_buffer](ShaderBufferSet::write_buffer) - Sets the contents of a buffer.
+//! - [write_front_buffer](ShaderBufferSet::write_front_buffer) - Sets the contents of only the front buffer of a double buffered storage or uniform buffer.
+//! - [write_back_buffer](ShaderBufferSet::write_back_buffer) - Sets the contents of only the back buffer of a double buffered storage or uniform buffer. See the "Double Buffering" section below.
+//! - [queue_write_at](ShaderBufferSet::queue_write_at) - Queues a write to a storage or uniform buffer, to be made between two steps of a task. See the "Double Buffering" section below.
+//! - [write_buffer_element](ShaderBufferSet::write_buffer_element) / [write_buffer_elements](ShaderBufferSet::write_buffer_elements) - Sets one element, or a run of elements, of a storage buffer holding an array of structs, without uploading the rest of it. Both halves of a double buffer are written.
+//! - [write_uniform_element](ShaderBufferSet::write_uniform_element) - Sets one element of a dynamic uniform buffer, leaving the others as they are.
+//! - [write_buffer_at](ShaderBufferSet::write_buffer_at) / [write_buffer_bytes](ShaderBufferSet::write_buffer_bytes) - Writes a value, or raw bytes, into a storage or uniform buffer at a byte offset, without uploading the rest of it. Both halves of a double buffer are written.
+//! - [set_buffer](ShaderBufferSet::set_buffer) and the other `set_*` functions that take a `RenderQueue` - Deprecated. They forward to the `write_*` functions above, which queue the write, so they don't need it.
+//! - [write_texture_from_image](ShaderBufferSet::write_texture_from_image) - Writes the pixels of an [Image] into a texture buffer, or part of one, such as a saved state loaded from a PNG. For a double buffer, a [TextureWriteTarget] picks whether both halves are written or only the back buffer.
+//! - [write_texture_region](ShaderBufferSet::write_texture_region) - Writes raw texel data into a rectangle of a texture buffer, such as a tile streamed into a larger texture, without uploading the rest of it again.
+//!
+//! [delete_buffer](ShaderBufferSet::delete_buffer), [resize_storage](ShaderBufferSet::resize_storage), [write_buffer](ShaderBufferSet::write_buffer), [write_front_buffer](ShaderBufferSet::write_front_buffer), [write_back_buffer](ShaderBufferSet::write_back_buffer), [queue_write_at](ShaderBufferSet::queue_write_at), [write_buffer_at](ShaderBufferSet::write_buffer_at), [write_buffer_bytes](ShaderBufferSet::write_buffer_bytes), [write_uniform_element](ShaderBufferSet::write_uniform_element) and the `write_buffer_element` functions return a [ShaderBufferError] rather than panicking when the handle doesn't refer to a buffer, such as one that's already been deleted, when it refers to the wrong kind of buffer, or when the data doesn't fit, so an app where buffers come and go can recover from a stale handle. Nothing is changed when they fail.
+//!
+//! The writes made by the `write_` functions don't change the [ShaderBufferSet], so they can be made through `Res<ShaderBufferSet>`, and setting a uniform every frame doesn't have the whole set extracted to the render world again. They're queued in the [BufferWriteQueue] resource, which a system can also queue raw bytes on directly with [queue_write](BufferWriteQueue::queue_write), and made in the render world before the next frame's compute steps, in the order they were made.
+//!
+//! Buffers can be added and deleted while a compute sequence is running, such as to stream in the regions of a world as they come into view, without starting the sequence over. When the layout of a group changes, the pipelines of the running task are remade against the new layouts, and the task waits for them, then carries on with its iteration count and buffers as they were. Deleting a buffer, or replacing it with a different kind of buffer at the same binding, is only allowed once no step of the running task uses its binding, as the step's pipeline couldn't be remade without it, so that panics, naming the binding and the step, rather than leaving wgpu to crash. Buffers used only by later tasks can be deleted, as long as they're added back before those tasks start. [generation](ShaderBufferSet::generation) counts the buffers added and deleted, so anything else that depends on which buffers there are can tell when to look again.
 //!
 //! ## Setting Buffer Contents
 //!
@@ -46,100 +104,772 @@
 //!
 //! # Starting the Compute Shader
 //!
-//! To start running the compute shaders, you need to throw a [StartComputeEvent]. This contains a [Vec] of [ComputeTask]s, which details all the compute tasks to complete, and a optional [ShaderBufferHandle], for the optional iteration buffer.
+//! To start running the compute shaders, you need to throw a [StartComputeEvent]. This contains the [ComputeSequenceId] of the sequence, which is [ComputeSequenceId::MAIN] unless you're running more than one, a [Vec] of [ComputeTask]s, which details all the compute tasks to complete, and a optional [ShaderBufferHandle], for the optional iteration buffer.
 //!
 //! ## ComputeTask
 //!
-//! A compute task represents one stage of your compute shader program. The compute task is optionally provided a number of iterations, and it will run for that many ticks before moving on to the next task. If that's not provided, it'll run forever. A compute task is also given a list of [ComputeStep]s, each of which is a specific shader to run, or other compute-related action to take, in order, each iteration. It can also be given an optional label, which is used to identify the task in the [ComputeTaskDoneEvent] that's thrown when the task completes.
+//! A compute task represents one stage of your compute shader program. The compute task is optionally provided a number of iterations, and it will run for that many ticks before moving on to the next task. If that's not provided, it'll run forever. A compute task is also given a list of [ComputeStep]s, each of which is a specific shader to run, or other compute-related action to take, in order, each iteration. It can also be given an optional label, which is used to identify the task in the [ComputeTaskDoneEvent] that's thrown when the task completes, once the GPU has finished its last iteration.
+//!
+//! Where the sequence's iteration buffer only holds the count within whichever task is running, a task can have an [iteration_buffer](ComputeTask::iteration_buffer) of its own, which it writes its own count into before each of its iterations, such as for an init task and an update task on different cadences. A [TaskIterationBuffer::Local] buffer gets the count within the task, as a `u32`, and a [TaskIterationBuffer::Counts] buffer gets a [ComputeIterationCounts], with both that and the count across the whole sequence. The same counts are mirrored into the main world every frame, in the [ComputeIterationState] resource, so a system can show something like "iteration 4521 / 10000" without reading anything back from the GPU.
+//!
+//! Each [ComputeStep] contains just three fields.
 //!
-//! Each [ComputeStep] contains just two fields.
+//! The first is a [StepSchedule], which says how often the step runs. By default it's [EveryIteration](StepSchedule::EveryIteration), but a step can instead be paced by the clock with [MaxFrequencyHz](StepSchedule::MaxFrequencyHz), so it won't necessarily run every iteration, but only if its next run is due. The frequency is in Hz, or runs per second, going by the render world's [Time]. So if a max frequency of 30 is provided, the step runs about every 1000/30=16.67 ms, whatever the refresh rate of the display. The time of the next run is kept to a fraction of a frame, so on a 144 Hz display it runs every 4 or 5 frames, which averages out to 30 times a second. A step can also be paced by frames instead, with [EveryNFrames](StepSchedule::EveryNFrames). This is often useful if you have a long running computation, and want to display the results in real time. You can potentially speed things up by only updating the display at a set framerate, even if the computation is running at a much faster rate.
 //!
-//! The first is an optional maximum frequency. If provided, this means this step won't necessarily run every iteration, but only if it's been long enough since the last time it ran. The frequency is in Hz, or iterations per second. So if a max frequency of 30 is provided, that means if it's been less than 1000/30=16.67 ms since the last time it ran, then it won't run this iteration. This is often useful if you have a long running computation, and want to display the results in real time. You can potentially speed things up by only updating the display at a set framerate, even if the computation is running at a much faster rate.
+//! The second is an optional [StepCondition], [run_if](ComputeStep::run_if), which gates the step on a flag in the [ComputeStepFlags] resource, set from the main world. With [ResourceFlag](StepCondition::ResourceFlag), the step runs only while the flag is set, and with [OneShotFlag](StepCondition::OneShotFlag), it runs once each time the flag is set, which then clears it, such as for a reset that runs on the iteration after the user presses a key, without restarting the task. While the condition isn't met, the step is skipped entirely, so it costs nothing.
 //!
-//! The second field of the [ComputeStep] is a [ComputeAction], which is an enum which describes what to actually do. It has the following options:
+//! The third field of the [ComputeStep] is a [ComputeAction], which is an enum which describes what to actually do. It has the following options:
 //!
-//! - [RunShader](ComputeAction::RunShader) - The meat of the compute shaders. This runs an actual shader. You must provide the Bevy asset path to the shader file, the name of the entry point function in that shader file, and the workgroup count in the x, y and z dimensions.
-//! - [CopyBuffer](ComputeAction::CopyBuffer) - Copy the data from a buffer to the CPU. Will be returned as a `Vec<u8>` via a [CopyBufferEvent].
+//! - [RunShader](ComputeAction::RunShader) - The meat of the compute shaders. This runs an actual shader. You must provide the Bevy asset path to the shader file, the name of the entry point function in that shader file, any shader defs to preprocess it with, any values for its override constants, any double buffers to swap after it runs, and a [DispatchSize]. This is either the workgroup count in the x, y and z dimensions, or with [Exact](DispatchSize::Exact), the number of invocations needed in each dimension, such as the size of a texture, in which case the workgroup counts are worked out from the workgroup size declared in the shader, or with [Chunked](DispatchSize::Chunked), a workgroup count split into several dispatches, or with [FromTexture](DispatchSize::FromTexture), a storage texture whose size is the number of invocations, so the workgroup counts round up to cover every texel, rather than dropping the remainder. It can also be skipped while a counter is zero, with [skip_if_empty](ComputeAction::RunShader::skip_if_empty), such as when no particles have been spawned. The counter is read back from the GPU, so it's a frame or two old. Double buffers can be bound the other way round for just that step with [buffer_orientation](ComputeAction::RunShader::buffer_orientation), such as to read what the step before wrote to the back buffer before it's swapped. Small parameters that change from one dispatch to the next, such as the iteration, can be passed as [push_constants](ComputeAction::RunShader::push_constants), without writing a buffer, on GPUs with the `PUSH_CONSTANTS` feature. Which element of each dynamic uniform buffer it binds is picked with [dynamic_offsets](ComputeAction::RunShader::dynamic_offsets).
+//! - [RunShaderPerEntity](ComputeAction::RunShaderPerEntity) - Run a shader once for every entity with a [ComputeBufferComponent], with that entity's buffer bound. See the "Per-Entity Buffers" section below.
+//! - [RunShaderIndirect](ComputeAction::RunShaderIndirect) - Run a shader with its workgroup counts read from a buffer on the GPU, such as one an earlier step filled in with the number of particles emitted, so the CPU never needs to know how much work there is.
+//! - [RunShaderMulti](ComputeAction::RunShaderMulti) - Run several entry points of one shader file back to back, each with its own dispatch size, such as the passes of a multi-pass filter. They share one shader module and the same bind groups, so only the pipeline is set between the dispatches. Every step that runs the same shader file with the same shader defs shares one shader module anyway, as Bevy's pipeline cache only makes one for each, but this keeps the passes together in one step.
+//! - [CopyBuffer](ComputeAction::CopyBuffer) - Copy the data from a buffer or texture to the CPU. Textures come back row by row, with no padding between rows, and double buffered textures from whichever half is the front buffer. Will be returned as a `Vec<u8>` via a [CopyBufferEvent], and also a [BufferReadbackEvent], which additionally says which task and iteration it came from, and can decode the bytes for you. Its [offset](ComputeAction::CopyBuffer::offset) and [size](ComputeAction::CopyBuffer::size) read back just part of a storage buffer, such as a counter at the front of a buffer of particles.
+//! - [CopyBufferToBuffer](ComputeAction::CopyBufferToBuffer) - Copy part or all of one storage buffer into another on the GPU, such as to snapshot a buffer a shader accumulates into, without reading anything back. `size: None` copies everything from the source offset on. The source needs `COPY_SRC`, and the buffers are checked when the task starts.
+//! - [CopyTextureToBuffer](ComputeAction::CopyTextureToBuffer) - Copy every texel of a storage texture into a storage buffer on the GPU, such as for a shader to index the last frame's image as a flat array with atomics. The texels are packed row by row, with none of the padding the GPU copies rows with, so the buffer needs width × height × depth × the bytes per texel, which is checked when the task starts.
+//! - [CopyBufferToTexture](ComputeAction::CopyBufferToTexture) - Copy a storage buffer into every texel of a storage texture on the GPU, laid out the same way. The buffer needs `COPY_SRC`.
+//! - [ClearBuffer](ComputeAction::ClearBuffer) - Fill part or all of a buffer with zeros on the GPU, such as to reset the counters a later pass bins into, without a shader of its own. `size: None` clears everything from the offset on. Only the back buffer of a double buffer is cleared, unless `halves` is [ClearHalves::Both].
+//! - [ClearTexture](ComputeAction::ClearTexture) - Fill every texel of a storage texture with one value, such as to reset an accumulation texture, for any uncompressed color format, without the `CLEAR_TEXTURE` feature. Double buffers are cleared the same way as with a [ClearBuffer](ComputeAction::ClearBuffer).
+//! - [ReadBuffer](ComputeAction::ReadBuffer) - Read the data from a buffer or texture back to the CPU every time it runs, even every iteration, without waiting for the GPU or skipping any reads. Each read is delivered a frame or more later, in order, as a [BufferReadbackEvent] and a [CopyBufferEvent].
 //! - [SwapBuffers](ComputeAction::SwapBuffers) - Swap double buffers. See the "Double Buffering" section below.
+//! - [RotateBuffers](ComputeAction::RotateBuffers) - Move a ring on to its next buffer. See the "Rings" section below.
+//! - [SortBuffer](ComputeAction::SortBuffer) - Sort the elements of a storage buffer by a `u32` or `f32` key, with a bitonic sort built into this crate. See [SortKey] for how to describe the element layout.
+//! - [ReduceBuffer](ComputeAction::ReduceBuffer) - Reduce every element of a storage buffer to their sum, minimum or maximum, as `f32`s, `u32`s or `i32`s, with a parallel reduction built into this crate, writing the result to the front of another buffer. See [ReduceOp] and [ReduceElement].
+//! - [PrefixSum](ComputeAction::PrefixSum) - Replace every `u32` in a storage buffer with the sum of the ones before it, an exclusive prefix sum, with shaders built into this crate.
+//! - [Histogram](ComputeAction::Histogram) - Count the values of a buffer of `f32`s or a single-channel texture into equal bins over a range, with shaders built into this crate. The bins are easiest to make with [add_counters](ShaderBufferSet::add_counters). See [HistogramSource] and [HistogramOutOfRange].
+//! - [Convolve](ComputeAction::Convolve) - Blur a float texture, or apply any other separable kernel, along its rows and then its columns, with shaders built into this crate. The result can be written back to the same texture. See [ConvolutionKernel] and [ConvolutionEdges].
+//! - [CheckFinite](ComputeAction::CheckFinite) - Scan a buffer of `f32`s or a single-channel texture for NaNs and infinities, reporting the first one found with a [ComputeErrorEvent::NonFiniteValue]. Only runs while [ComputeFiniteChecks] is enabled, which it is by default in debug builds. See [FiniteCheckType].
+//!
+//! A task runs one iteration a frame, unless it has a [FrameBudget]. Then it runs as many iterations a frame as fit in that much GPU time, which lets a task that needs thousands of iterations, such as a bake, finish in seconds without tanking the frame rate. How long an iteration takes is measured with timestamp queries where they're available, and taken from an estimate given with the budget where they aren't.
+//!
+//! A task is normally recorded into the render graph's command encoder and submitted along with the rest of the frame, so a big bake holds up the frame it runs in. Setting its [submission](ComputeTask::submission) to [TaskSubmission::Separate] records it into a command encoder of its own instead, with each iteration submitted on its own before the frame's submission. Its iterations only count as done once the GPU has finished them, so its progress and its [ComputeTaskDoneEvent] follow the GPU rather than the frames it was recorded in.
+//!
+//! An iteration count doesn't have to be fixed when the sequence starts. If a task's label is in the [ComputeTaskIterations] resource, it runs for the count there instead of its own, looked up when the task starts, such as a number of erosion passes set with a slider right up until they run. A running task can also be given more iterations with an [ExtendComputeTaskEvent], and extending a task that's already finished starts the sequence over from that task, for just the extra iterations. The [ComputeTaskDoneEvent] reports how many iterations a task ran for in the end. A task can also be stopped early with a [StopComputeTaskEvent], finishing at the next iteration boundary, or by a condition checked on the CPU, such as a time limit or a flag set by game logic, with a system registered under its label in the [ComputeTaskStopConditions] resource.
+//!
+//! A task whose [run_mode](ComputeTask::run_mode) is [TaskRunMode::OnDemand] doesn't run any iterations until it's triggered with a [TriggerComputeTaskEvent], naming it by its label, such as a picking dispatch that runs once each time the mouse is clicked. It runs the iterations it was triggered for, and then sits idle until it's triggered again, without finishing, or starting the sequence over. Triggers add up, and a label that doesn't name an on-demand task is logged as an error.
+//!
+//! The shader a step runs can be changed while it's running with a [SwapStepShaderEvent], such as for live-coding, without starting the sequence over, so the iteration counts and the buffers carry on as they were. The step keeps running its old shader until the new one's pipeline is ready, and keeps it if the new one fails to compile, sending a [ComputeErrorEvent::ShaderSwapFailed].
+//!
+//! With Bevy's asset hot-reloading on, editing a shader a running step uses works the same way. Its pipeline is rebuilt in the background, and the step keeps running the old one until then, or for as long as the new one fails to compile, sending a [ComputeErrorEvent::ShaderReloadFailed] and logging the error. The buffers, iteration counts and double buffers all carry on as they were. Shaders with [override constants](ComputeAction::RunShader::overrides) are reloaded too, with the same values, but the workgroup sizes of [Exact](DispatchSize::Exact) and [Chunked](DispatchSize::Chunked) dispatches are only read when the step starts, so a changed `@workgroup_size` takes a new sequence. A shader that's broken from the start doesn't crash the app either. If a step's entry point isn't in its shader, a [ComputeErrorEvent::MissingEntryPoint] is sent, and if its pipeline can't be made, such as because the shader doesn't compile, a [ComputeErrorEvent::PipelineFailed] is sent, with the error, which is also logged. Either way, it's sent once, and the task waits until the shader is fixed and reloaded.
+//!
+//! The dispatch size of a [RunShader](ComputeAction::RunShader) or [RunShaderPerEntity](ComputeAction::RunShaderPerEntity) step can be changed the same way with an [UpdateDispatchSizeEvent], such as to follow a texture that's recreated at a new resolution when the window is resized, which is easiest with [DispatchSize::Exact]. The step dispatches with the new size from the next frame on, and the workgroup counts are worked out again without making a new pipeline. A [FromTexture](DispatchSize::FromTexture) dispatch can be switched over to the new texture the same way, and from then on covers it at whatever size it is.
+//!
+//! For a progress bar, the [ComputeProgress] resource has how far along the current task is, its [remaining_iterations](ComputeProgress::remaining_iterations), and, once it's run for a few frames, estimates of how long until it's done, and how long until the whole sequence is done. These come from the median wall time per iteration over the last few frames, so a hitch doesn't throw them off.
+//!
+//! A task can be paused and resumed with a [PauseComputeTaskEvent], or held back with a [start_after](ComputeTask::start_after) delay, of a number of frames or seconds from when the sequence was started, such as to keep a simulation frozen during an intro. Nothing of a task runs while it waits, and the [ComputeProgress] shows it as [pending](ComputeProgress::pending). To catch a shader that runs away or stalls, such as one stuck in an infinite loop, give its task a time limit per iteration in the [ComputeTaskWatchdogs] resource. If an iteration takes longer than that on the GPU, or still hasn't finished once the limit has passed, an error is logged, the task is paused, and a [ComputeErrorEvent::WatchdogTimeout] is sent naming the task and the steps that ran in that iteration. This can't stop the driver from resetting a GPU that's really hung, but it does tell you which task was at fault.
+//!
+//! The whole sequence can be paused with a [PauseComputeEvent] for its id, and resumed with a [ResumeComputeEvent]. Nothing is dispatched while it's paused, its [ComputeState] is [Paused](ComputeState::Paused), and everything else is kept as it was, including the iteration count of the current task and which half of each double buffer is the front, so it carries on exactly where it left off. To halt it for good, send a [StopComputeEvent]. The task it was in isn't finished, the [ComputeState] goes back to [Idle](ComputeState::Idle), and the buffers keep whatever was last written to them, so another sequence can be started on them with a new [StartComputeEvent].
+//!
+//! To show what's running, such as in a debug panel, the [ComputeSequenceInfo] resource describes the running sequence in the main world. It holds a copy of every task as it was started, along with its [status](ComputeTaskStatus), whether queued, preparing, delayed, running, paused or done, and how many iterations it's run out of how many. It's kept up to date from the progress the render world reports, so it can lag behind by a frame or two, and it's read-only, so changing it has no effect on the sequence.
+//!
+//! Errors wgpu reports outside of an error scope while a frame of a task is being recorded or submitted, such as a buffer being destroyed while it's still bound, are attributed to the task. It still panics, as it would by default, but the log and the panic message name the task, and either the step being recorded or the steps recorded into the submission. Errors from anything else are handled as wgpu would by default. Installing an uncaptured error handler of your own on the device replaces this one.
+//!
+//! ## Multiple Sequences
+//!
+//! More than one compute sequence can run at once, such as a long-running simulation alongside short on-demand dispatches, without either interfering with the other. Each is identified by the [ComputeSequenceId] given in its [StartComputeEvent], and the events that control a sequence or its tasks, from [StopComputeEvent] to [SwapStepShaderEvent], name the sequence they're for, as do the [ComputeTaskDoneEvent]s and [BufferReadbackEvent]s it sends. Most apps only ever need one, which is [ComputeSequenceId::MAIN]. Each sequence keeps its own current task, iteration counts and throttling, and stopping, pausing or restarting one leaves the others as they are. Starting a sequence with the id of one that's already running replaces that one alone.
+//!
+//! The sequences share the buffers in the [ShaderBufferSet], but each one's pipelines are made against only the bind groups its own shaders reference, so a group that only one sequence uses can change layout, such as when a buffer is added to it, without the other sequences' pipelines being remade. When that can't be told from a shader's text, such as when it has `#import`s, the sequence is assumed to use every group. Sequences run one after another each frame, in the order of their ids.
+//!
+//! The [ComputeSequenceStates] resource has the [ComputeState] and [ComputeProgress] of every sequence by its id. The [ComputeState], [ComputeProgress] and [ComputeSequenceInfo] resources follow the main sequence alone, as do writes queued with [ShaderBufferSet::queue_write_at] and recordings made with [ShaderBufferSet::start_recording].
+//!
+//! ## Validating a Sequence
+//!
+//! To check a compute sequence before committing to a long run, such as on every edit in a node graph editor, send a [ValidateComputeEvent] with the same tasks and iteration buffer as a [StartComputeEvent]. Nothing is started or dispatched. Once its shaders have loaded, a [ComputeValidationReport] comes back, listing everything that would stop the sequence from running, or be reported while it ran, each with a [ValidationSeverity], and the task and step it's in. This covers shaders that don't exist or don't compile, missing entry points, buffers of the wrong kind for their steps, workgroup sizes and counts over the GPU's limits, and bindings the shader declares differently from the buffers bound there.
+//!
+//! ## Globals
+//!
+//! Most shaders that animate something want the time. [add_globals](ShaderBufferSet::add_globals) adds a uniform buffer the plugin writes a [ComputeGlobals] to every tick while a compute sequence is running, holding the seconds since the app started, the seconds since the last frame, the frame count, a seed for random numbers, and the position of each ring. These are written once per tick, before any step runs, so every step in a tick sees the same values, apart from the seed and the ring positions, which are described below. Declare it in the shader with this struct:
+//!
+//! ```wgsl
+//! struct ComputeGlobals {
+//!     time: f32,
+//!     delta_time: f32,
+//!     frame_count: u32,
+//!     seed: u32,
+//!     ring_positions: vec4<u32>,
+//! }
+//!
+//! @group(0) @binding(3) var<uniform> globals: ComputeGlobals;
+//! ```
+//!
+//! Shaders that need randomness, such as for noise or jittered sampling, can start from the [seed](ComputeGlobals::seed) in the globals. It's different for every iteration, even between iterations of the same tick, but it only depends on the [seed](StartComputeEvent::seed) the compute sequence was started with, the task and the iteration, so running a sequence again with the same seed gives the same values, however the iterations fell across frames. The seeds come from a SplitMix64 generator, and [iteration_seed] works out any iteration's seed on the CPU. The seed for the next iteration is in the [ComputeProgress] resource, so it can be saved along with a checkpoint.
+//!
+//! Fullscreen effects need the size of what they're drawing to. [add_size_uniform](ShaderBufferSet::add_size_uniform) adds a uniform buffer the plugin writes a [ComputeSize] to whenever the physical size of the primary window changes, such as when it's resized, along with the size of a texture buffer it can optionally track. Declare it in the shader with this struct:
+//!
+//! ```wgsl
+//! struct ComputeSize {
+//!     window_size: vec2<u32>,
+//!     texture_size: vec2<u32>,
+//! }
+//!
+//! @group(0) @binding(4) var<uniform> size: ComputeSize;
+//! ```
+//!
+//! Interactive simulations need to know where the cursor is. [add_cursor_uniform](ShaderBufferSet::add_cursor_uniform) adds a uniform buffer the plugin writes a [ComputeCursor] to every tick, holding the position of the cursor in the pixels of a texture buffer, given the rectangle in world space the texture is displayed in, along with the mouse buttons held down. The position is worked out in the main world, and written in the render world before the steps run, so every step in a tick sees the same cursor. Declare it in the shader with this struct:
+//!
+//! ```wgsl
+//! struct ComputeCursor {
+//!     position: vec2<f32>,
+//!     buttons: u32,
+//!     on_texture: u32,
+//! }
+//!
+//! @group(0) @binding(5) var<uniform> cursor: ComputeCursor;
+//! ```
+//!
+//! Screen-space effects need the camera's matrices. [add_camera_uniform](ShaderBufferSet::add_camera_uniform) adds a uniform buffer the plugin writes a [ComputeCameraMatrices] to every tick, holding the view, projection and view-projection matrices of the camera marked with the [ComputeCamera] component, their inverses, and the camera's position. These come from the camera as extracted to the render world, so they match what the renderer draws with that frame. Declare it in the shader with this struct:
+//!
+//! ```wgsl
+//! struct ComputeCameraMatrices {
+//!     view: mat4x4<f32>,
+//!     inverse_view: mat4x4<f32>,
+//!     projection: mat4x4<f32>,
+//!     inverse_projection: mat4x4<f32>,
+//!     view_projection: mat4x4<f32>,
+//!     inverse_view_projection: mat4x4<f32>,
+//!     world_position: vec3<f32>,
+//! }
+//!
+//! @group(0) @binding(6) var<uniform> camera: ComputeCameraMatrices;
+//! ```
+//!
+//! Dispatches too big for one go, with more than 65535 workgroups in a dimension, can be split up with [Chunked](DispatchSize::Chunked), which dispatches the grid in chunks of at most a given size, with smaller chunks at the far edges to make up the remainder. [add_chunk_uniform](ShaderBufferSet::add_chunk_uniform) adds a uniform buffer the plugin writes a [ComputeChunk] to before each chunk, holding the offset of the chunk's first invocation, to add to the `global_invocation_id`. A chunked dispatch can also run only a few chunks each iteration, to spread the work over several iterations, or with a [FrameBudget], over several frames. Declare it in the shader with this struct:
+//!
+//! ```wgsl
+//! struct ComputeChunk {
+//!     offset: vec3<u32>,
+//! }
+//!
+//! @group(0) @binding(7) var<uniform> chunk: ComputeChunk;
+//! ```
+//!
+//! The plugin owns the contents of all five buffers, so setting them with [write_buffer](ShaderBufferSet::write_buffer) logs a warning.
 //!
 //! # Double Buffering
 //!
 //! It can sometimes be useful to have double buffers, where one buffer is the front buffer, and one the back buffer, and you read from the front buffer while writing to the back buffer, and then swap them for the next frame. This allows you to avoid reading from and writing to the same buffer, which can result in weird behavior when some of the data you're reading was written last frame, and some was written earlier this frame.
 //!
-//! So this plugin supports this directly. When you declare a buffer with the [Double](Binding::Double) binding type, it will actually create two buffers internally. One of them is considered the front buffer, which will be bound to the first binding provided, and the back buffer will be bound to the second binding. When the [SwapBuffers](ComputeAction::SwapBuffers) compute action happens, it will swap which buffer is considered the front buffer.
+//! So this plugin supports this directly. When you declare a buffer with the [Double](Binding::Double) binding type, it will actually create two buffers internally. One of them is considered the front buffer, which will be bound to the first binding provided, and the back buffer will be bound to the second binding. When the [SwapBuffers](ComputeAction::SwapBuffers) compute action happens, it will swap which buffer is considered the front buffer. Since a shader step that writes to a double buffer is almost always followed by a swap, a [RunShader](ComputeAction::RunShader) action can swap double buffers itself, with its `auto_swap` list, right after its dispatch. These only swap when the step actually runs, so there's no separate step whose schedule could disagree with it. Explicit [SwapBuffers](ComputeAction::SwapBuffers) steps still work for anything more unusual. A task that does nothing but this, reading the front buffers and writing the back buffers over and over, can be made in one line with [ComputeTask::ping_pong], as in the `life` example, which checks that every buffer is a double buffer when the task starts.
+//!
+//! The front half of a double buffered texture is bound read-only and the back half write-only, whatever access the texture was added with. [set_double_texture_access](ShaderBufferSet::set_double_texture_access) changes that, such as making the back half read-write for a blur that reads the neighbors it has already written, or both halves read-write for a step that works in place. The access goes with the half, not the texture, so a swap doesn't change what a shader sees at each binding, only which texture is there.
+//!
+//! Storage buffers are double buffered the same way as textures, such as the particles of the `particles` example, which a step reads from the front buffer and writes, moved along, to the back buffer. The front buffer is bound read-only, so shaders declare it as `var<storage, read>`, and the back buffer read-write, whatever `readonly` the buffer was added with. [set_double_storage_access](ShaderBufferSet::set_double_storage_access) changes that, such as to let a step update the front buffer in place as well. [write_buffer](ShaderBufferSet::write_buffer) writes both halves, while [write_front_buffer](ShaderBufferSet::write_front_buffer) and [write_back_buffer](ShaderBufferSet::write_back_buffer) write only one, such as to reset the particles being read without touching the half about to be written over.
+//!
+//! Uniform buffers can be double buffered too, such as for parameters streamed in from the CPU, with [write_back_buffer](ShaderBufferSet::write_back_buffer) writing the new ones to the back buffer while the shaders keep reading the old ones from the front buffer, until a swap switches them over. Most parameters don't need this. Writes made with [write_buffer](ShaderBufferSet::write_buffer) are queued, and land before the next frame's dispatches, so they never race with dispatches from an earlier frame that are still running on the GPU. Double buffering them only helps when the shaders need both the old and the new values at once, or the switch has to happen at a particular step, rather than at the start of a frame.
+//!
+//! A CPU edit to a double buffer that's updated and swapped every iteration, such as the user painting into a grid, can land on either half with [write_buffer](ShaderBufferSet::write_buffer), depending on where in the sequence the frame starts. [queue_write_at](ShaderBufferSet::queue_write_at) queues a write to be made at a precise point instead, after a given step of a task and before the next one, such as after the step that updates the grid and before the one that swaps it, in the next iteration of that task. Both halves are written, each queued write is made exactly once, and a [StepWriteAppliedEvent] with the id [queue_write_at](ShaderBufferSet::queue_write_at) returned is sent once it has been, so the main world knows when to let go of the edit.
+//!
+//! When you do a [CopyBuffer](ComputeAction::CopyBuffer) compute action on a double buffer, it will always copy out of the front buffer. Also, if you call the [image_handle](ShaderBufferSet::image_handle) function on a double buffer texture, it will return the handle for the front buffer. If you want both, [image_handles](ShaderBufferSet::image_handles) returns the handles of the front and back buffers, labelled as such.
+//!
+//! There's also a special accommodation for displaying a double buffered texture. Put a [DisplayComputeBuffer] component on an entity with a [Sprite], a `MeshMaterial2d<ColorMaterial>` or a `MeshMaterial3d<StandardMaterial>`, and whenever the buffers are swapped, the sprite's image, or the material's texture, will be updated to the new front buffer. Nothing is touched on frames without a swap, so this is cheap to leave on any number of entities.
+//!
+//! ## Rings
+//!
+//! Some simulations keep more than two states around, such as a multistep integrator reading the last three. A [Ring](Binding::Ring) binding makes a ring of buffers, all alike, bound at consecutive bindings, one of which is the current buffer, at the ring's [position](ShaderBufferSet::ring_position). Unlike the halves of a double buffer, the buffers never move between bindings. A [RotateBuffers](ComputeAction::RotateBuffers) step moves the position on to the next buffer instead, wrapping around to the first after the last. Shaders find the position in the `ring_positions` of the [globals](ShaderBufferSet::add_globals), at the ring's [slot](ShaderBufferSet::ring_slot), which is updated at every rotation, so shader steps after a rotation see the new position, even in the same iteration:
+//!
+//! ```wgsl
+//! @group(0) @binding(4) var<storage, read> state0: array<f32>;
+//! @group(0) @binding(5) var<storage, read> state1: array<f32>;
+//! @group(0) @binding(6) var<storage, read> state2: array<f32>;
+//!
+//! fn current(i: u32) -> f32 {
+//!     switch globals.ring_positions.x {
+//!         case 0u: { return state0[i]; }
+//!         case 1u: { return state1[i]; }
+//!         default: { return state2[i]; }
+//!     }
+//! }
+//! ```
+//!
+//! Rings can share groups with double buffers, and a task can swap some buffers and rotate others. Only storage and uniform buffers can be rings, there can only be [four](ShaderBufferSet::RING_SLOTS) of them at once, and they can't be read back or saved in checkpoints.
+//!
+//! # Displaying Textures
+//!
+//! A [Sprite] displays a texture's colors as they are, which is fine for colors, but single-channel formats like `R32Float` come out as shades of red. If you add the [ComputeTextureMaterialPlugin] alongside the [BevyComputePlugin], you can display textures with a [ComputeTextureMaterial2d] in 2D, or a [ComputeTextureMaterial] in 3D, instead. These can map a single channel through a [Colormap], which can be grayscale, viridis or a lookup table of your own, over a range of values you choose, and can decode textures holding sRGB colors. The [compute_texture_2d] and [compute_texture_3d] functions build a quad displaying a texture, with a [DisplayComputeBuffer] component that keeps it showing the front buffer as double buffers are swapped:
+//!
+//! ```Rust
+//! let material = ComputeTextureMaterial2d::new(buffer_set.image_handle(image).unwrap())
+//!     .with_colormap(Colormap::Viridis, 0.0, 1.0);
+//! commands.spawn((
+//!     compute_texture_2d(&mut meshes, &mut materials, image, material, Vec2::new(320.0, 180.0)),
+//!     Transform::from_scale(Vec3::splat(4.0)),
+//! ));
+//! ```
+//!
+//! Storage textures can't have sRGB formats, so a texture added with `Rgba8UnormSrgb` or `Bgra8UnormSrgb` is stored as `Rgba8Unorm` or `Bgra8Unorm`, which is the format shaders must declare, and its image gets an sRGB view for sprites and materials to sample through. Shaders read and write the raw values either way, with no gamma conversion. With a linear format, a sprite shows those values as linear colors, so sRGB-encoded colors written to it look washed out. With an sRGB format, the sprite decodes the values from sRGB, so linear colors need encoding in the shader first. This needs a GPU that can view textures in a different format, as told by [ComputeCapabilities], which rules out WebGL2 and some older backends.
+//!
+//! Textures of 16-bit float formats, such as `Rgba16Float`, take half the memory of their 32-bit counterparts, which adds up for large HDR fields. Shaders read and write them as `f32`s, but their fills and readbacks are raw 16-bit floats, which [f32_to_f16] and [f16_to_f32] convert to and from, and [BufferReadbackEvent::decode_f16] decodes a whole readback with. A fill that isn't exactly one pixel of the texture's format panics.
+//!
+//! Every texture, including both halves of a double buffer, holds its fill before any shader runs against it. Bevy uploads images when it prepares them, which a [RenderAssetBytesPerFrame](bevy::render::render_asset::RenderAssetBytesPerFrame) limit can spread over several frames, so a compute sequence doesn't dispatch anything until every texture in the [ShaderBufferSet] has been uploaded.
+//!
+//! To keep a texture's contents around as an ordinary [Image] asset, for instance to save the final state of a simulation or to keep showing it after the buffers are deleted, send a [WriteBackToImageEvent] with the texture and the image to write to, then run a [CopyBuffer](ComputeAction::CopyBuffer) of that texture. The next readback of the texture is written into the image, resizing it to match, or creating it if the handle isn't loaded yet. If the image's format doesn't match the texture's, a [ComputeErrorEvent::ImageFormatMismatch] is sent instead.
+//!
+//! # Drawing Compute-Generated Meshes
+//!
+//! Compute shaders can generate geometry that's drawn without ever going through the CPU. [add_vertex_buffer](ShaderBufferSet::add_vertex_buffer) and [add_index_buffer](ShaderBufferSet::add_index_buffer) add zeroed storage buffers that can also be bound as vertex and index buffers. The [ShaderBufferSet] is extracted to the render world, so a custom render command can fetch the raw GPU buffers with [gpu_buffer](ShaderBufferSet::gpu_buffer) and bind them for drawing. The compute node always runs before the camera driver node in the render graph, so whatever the compute steps write in a frame is what gets drawn that frame. The `gpu_mesh` example shows the whole setup, with a custom phase item that draws a mesh whose index count changes every frame, reading the count back from a small counter buffer.
+//!
+//! The number of things to draw can be decided on the GPU too. [add_draw_indirect_args](ShaderBufferSet::add_draw_indirect_args) and [add_draw_indexed_indirect_args](ShaderBufferSet::add_draw_indexed_indirect_args) add storage buffers holding the arguments of an indirect draw, which compute steps can write, such as counting up the instance count with an atomic as particles are emitted, and which a render command passes to `draw_indirect` or `draw_indexed_indirect`. As with vertex data, the arguments written in a frame are the ones drawn with that frame. The `indirect_particles` example draws a fountain of particles this way, with one instanced quad per live particle.
+//!
+//! Materials can read what compute shaders write without a readback too. Mark the buffer or texture with [consume_in_render](ShaderBufferSet::consume_in_render), then bind it in the material: a texture by its [image_handle](ShaderBufferSet::image_handle), under the usual `#[texture]` and `#[sampler]` attributes of `AsBindGroup`, or a storage buffer by its [gpu_buffer](ShaderBufferSet::gpu_buffer), as a `Buffer` field under `#[storage(n, read_only, buffer)]`. The render graph nodes of every camera already run after the compute node, and any other node that reads the results can be ordered after it too, by adding its label to the [ComputeRenderConsumers] resource in the render app. Only one half of a double buffer can be bound that way, and which half changes with every swap, so validating a sequence warns about consumed buffers that are double buffered. The `lut_material` example extends a `StandardMaterial` with a color lookup texture that a compute shader rewrites every frame.
+//!
+//! # Main and Render Worlds
 //!
-//! When you do a [CopyBuffer](ComputeAction::CopyBuffer) compute action on a double buffer, it will always copy out of the front buffer. Also, if you call the [image_handle](ShaderBufferSet::image_handle) function on a double buffer texture, it will return the handle for the front buffer.
+//! Most of the crate is used from the main world, where buffers are added and compute sequences are run. A few things are only meant for the render world, for plugins that draw with what the compute shaders write: [ShaderBufferRenderSet], [ComputeBindGroups], [ComputeLabel] and [ComputeRenderConsumers]. These are split into the [main] and [render] modules, and both are also re-exported from the crate root, as they always have been. The docs of the [render] module go through what happens to the buffers in each phase of a frame, from extract to the compute node, and what each phase guarantees.
 //!
-//! There's also a special accommodation for using a double buffered texture on a Bevy sprite. The [DoubleBufferedSprite] component requires a [Sprite] component, and it will automatically update that image handle on that sprite every frame to contain the new front buffer.
+//! The [ShaderBufferSet] is in both worlds, but only the main world's is the real one. The render world's is a copy of it, replaced whenever it's extracted, which happens in every frame the main world's changes, so its [world](ShaderBufferSet::world) is [ComputeWorld::Render], and in debug builds, the methods that would change it panic, as the change would be lost. Likewise, the methods of the [ShaderBufferRenderSet] panic in debug builds if they're passed the main world's [ShaderBufferSet], which may not match the frame being rendered.
+//!
+//! # Raw GPU Objects
+//!
+//! For sharing buffers and textures with other render crates, the render world has a [ShaderBufferRenderSet] resource with escape hatches to the raw wgpu objects: [wgpu_buffer](ShaderBufferRenderSet::wgpu_buffer) gets the buffer behind a storage or uniform buffer, and [wgpu_texture](ShaderBufferRenderSet::wgpu_texture) the texture behind a texture buffer. On the [ShaderBufferSet], [layout_entry_for](ShaderBufferSet::layout_entry_for) gets the bind group layout entries of a buffer, so external pipelines can build layouts compatible with the bind groups bevy_compute makes. These are advanced, and the objects they return are only guaranteed to be valid for the current frame, so fetch them again every frame rather than keeping them.
+//!
+//! In the render world, while a task with a [CopyBuffer](ComputeAction::CopyBuffer) step for a buffer is running, the [ShaderBufferRenderSet] can also read the copy buffer of that task's sequence directly, decoded into a [ShaderType] with [read_copy_buffer_as](ShaderBufferRenderSet::read_copy_buffer_as), or into a `Vec` of them for a runtime-sized array with [read_copy_buffer_as_vec](ShaderBufferRenderSet::read_copy_buffer_as_vec). These follow the same memory layout rules the buffers are written with, so the padding of WGSL structs is handled for you, and return a [ShaderBufferError] naming both sizes if the bytes don't fit the type, rather than panicking.
+//!
+//! To bind a group's buffers directly in a render pipeline, such as a fullscreen fragment pass visualizing the simulation, make them visible to the stages that need them with [set_visibility](ShaderBufferSet::set_visibility), which defaults to only [ShaderStages::COMPUTE](bevy::render::render_resource::ShaderStages::COMPUTE). Then build the pipeline against the layout from [bind_group_layout](ShaderBufferRenderSet::bind_group_layout), which is the exact layout the compute pipelines use, and bind the group from the [ComputeBindGroups] resource, which exists in the render world while a compute sequence is running. A pipeline that has to be made before then, such as a material's, can make a layout with the same entries from the [ShaderBufferSet] itself, in either world, with [bind_group_layout_for_group](ShaderBufferSet::bind_group_layout_for_group), which wgpu treats as the same layout.
+//!
+//! The bind groups and layouts of the last frame are also kept on the [ShaderBufferRenderSet], as [bind_group](ShaderBufferRenderSet::bind_group) and [current_layout](ShaderBufferRenderSet::current_layout), which are valid after [Queue](bevy::render::RenderSet::Queue), where they're queued, and until the next extract. Only the groups that bind something different than last frame get a new bind group there; the others keep the one they had, and layouts are made once for each set of bindings. Each group also counts its generations: [bind_group_generation](ShaderBufferRenderSet::bind_group_generation) goes up whenever the group binds something different, such as after a buffer in it was added, deleted or swapped, and [layout_generation](ShaderBufferRenderSet::layout_generation) whenever its layout changed, so an external pipeline only needs to be rebuilt when the latter does.
+//!
+//! # Per-Entity Buffers
+//!
+//! When a simulation is made of many independent agents, each with its own small state buffer, the buffers can be owned by entities instead. Add each buffer with a [PerEntity(u32, u32)](Binding::PerEntity) binding, and put it on its entity with a [ComputeBufferComponent]:
+//!
+//! ```Rust
+//! let state = buffer_set.add_storage_init(&device, &queue, AgentState::default(), usage, Binding::PerEntity(1, 0), false).unwrap();
+//! commands.spawn((Agent, ComputeBufferComponent(state)));
+//! ```
+//!
+//! A [RunShaderPerEntity](ComputeAction::RunShaderPerEntity) action then dispatches its shader once for every such entity, with that entity's buffer bound in the per-entity group, and every other group bound as usual. The whole group is reserved for per-entity buffers, and they must all have the same binding and kind, so they can share one layout. When an entity is despawned, its buffer is deleted.
+//!
+//! # Binding Namespaces
+//!
+//! Group and binding numbers are shared by everything that adds buffers to the [ShaderBufferSet], so two plugins that both use this crate, written without knowing about each other, would both want group 0. Each can reserve a range of groups of its own with [add_namespace](ShaderBufferSet::add_namespace) instead, and number its groups from zero within it. Its buffers are added with bindings moved into its range by [BindingNamespace::binding], and its tasks are run with [ComputeTask::with_namespace], which gives its shaders a shader def `GROUP_N` with the group that group `N` of the namespace ended up in:
+//!
+//! ```Rust
+//! let namespace = buffer_set.add_namespace("life", 1);
+//! let cells = buffer_set.add_texture_fill(&mut images, 256, 256, format, &fill, access, namespace.binding(Binding::Double(0, (0, 1)))).unwrap();
+//! let task = ComputeTask::ping_pong("life", "shaders/life.wgsl", "update", dispatch, &[cells], None).with_namespace(&namespace);
+//! ```
+//!
+//! ```wgsl
+//! @group(#{GROUP_0}) @binding(0) var input: texture_storage_2d<r32float, read>;
+//! @group(#{GROUP_0}) @binding(1) var output: texture_storage_2d<r32float, write>;
+//! ```
+//!
+//! A namespace's groups come after every group in use when it's added, so buffers added without one should be added first. Each plugin can run its tasks in a compute sequence of its own, with an id of its own, or the app can start one sequence with the tasks of every plugin, or with tasks that combine their steps.
+//!
+//! # Shader Imports
+//!
+//! Compute shaders are preprocessed the same way Bevy preprocesses its own shaders, so they can `#import` from other shader files, and use `#ifdef` and `#if` with the shader defs given to the [RunShader](ComputeAction::RunShader), [RunShaderPerEntity](ComputeAction::RunShaderPerEntity), [RunShaderIndirect](ComputeAction::RunShaderIndirect) or [RunShaderMulti](ComputeAction::RunShaderMulti) action, or to a [OneShotTask] with [shader_def](OneShotTask::shader_def). A library imported by its asset path, like `#import "shaders/noise.wgsl"::fbm`, is loaded automatically. But a library imported by the name in its `#define_import_path` can only be found once it's loaded, so add it to the [ComputeShaderLibraries] resource first:
+//!
+//! ```Rust
+//! fn setup(asset_server: Res<AssetServer>, mut libraries: ResMut<ComputeShaderLibraries>) {
+//!     libraries.add(&asset_server, "shaders/noise.wgsl");
+//! }
+//! ```
+//!
+//! The same library can be imported by material shaders too.
+//!
+//! One shader file can be specialized differently in each step, such as with a `WRAP_EDGES` def in one step and not in another, or with `KERNEL_RADIUS` set to a different value, as every different set of shader defs gets its own pipeline. Pipelines are kept by the shader, entry point, shader defs and bind group layouts they're made from, so steps specialized the same way share one, and a sequence that's started again reuses the pipelines it had.
+//!
+//! # Override Constants
+//!
+//! WGSL `override` constants declared in a shader file can be given values with the `overrides` of the [RunShader](ComputeAction::RunShader), [RunShaderPerEntity](ComputeAction::RunShaderPerEntity) or [RunShaderIndirect](ComputeAction::RunShaderIndirect) action, or with [override_constant](OneShotTask::override_constant) on a [OneShotTask]. This makes it easy to run one shader with different workgroup sizes or loop bounds in different steps, as every different set of values gets its own pipeline. Bevy can't pass override values to a pipeline itself, so this works by making a copy of the shader with each override that's given a value declared as a `const` instead. Overrides that aren't given a value keep their defaults, except that an override used in `@workgroup_size` must always be given one. Any name that isn't declared in the shader is reported with a [ComputeErrorEvent].
+//!
+//! # One-Shot Tasks
+//!
+//! If all you want is to run a shader once and get the result back, you can skip compute tasks altogether, and send a [RunComputeTaskEvent] with a [OneShotTask] instead:
+//!
+//! ```Rust
+//! run_events.send(RunComputeTaskEvent {
+//!     task_id: 0,
+//!     task: OneShotTask::new("shaders/sum.wgsl", "main", UVec3::new(64, 1, 1)).bind(input).readback(output),
+//! });
+//! ```
+//!
+//! Every buffer is bound as usual, but the ones the shader relies on can be listed with [bind](OneShotTask::bind), to have the task panic before it runs if any of them has been deleted, or isn't bound in a group. Once the shader has run, and the buffer has been read back, a [ComputeTaskCompleteEvent] will be thrown with the same task ID and the buffer's contents.
+//!
+//! # Diagnostics
+//!
+//! If you add the [ComputeDiagnosticsPlugin] alongside the [BevyComputePlugin], the CPU time the render world spends building bind groups and recording each compute task's commands will be reported as Bevy diagnostics, under [BIND_GROUP_BUILD_TIME] and [encode_time_path], along with the number of iterations each task runs per second, under [iterations_per_second_path]. The paths of tasks of sequences other than the [main](ComputeSequenceId::MAIN) one name the sequence too. Where timestamp queries can be written between commands, which isn't the case on the web, the GPU time spent on compute tasks each frame is reported under [GPU_TIME] too. The bytes allocated through the [ShaderBufferSet] are reported under [ALLOCATED_BYTES]. These show up in the output of Bevy's `LogDiagnosticsPlugin` without any extra work.
+//!
+//! For finer grained timings, such as when tuning workgroup sizes, add the [ComputeProfilingPlugin] too. Every dispatch of a shader step is then timed on the GPU with timestamp queries, in a compute pass of its own, and the [ComputeTimings] resource has how long each step took, in milliseconds, by its task and entry point, a frame or two after it ran. This needs the `TIMESTAMP_QUERY` feature, without which nothing is timed and a warning is logged. Without the plugin, nothing is timed, and the dispatches share passes as usual.
+//!
+//! With the `trace` feature enabled, which enables Bevy's, starting a compute sequence, building bind groups, and recording each compute task's commands are wrapped in tracing spans, with the recording spans named after their tasks, so they show up in Tracy or any other tracing tool Bevy is set up with. Without it, the spans aren't compiled in at all.
+//!
+//! # Debug Visualization
+//!
+//! When a simulation misbehaves, it helps to see the data. Add the [ComputeDebugVisualizationPlugin] alongside the [BevyComputePlugin], and register buffers with the [ComputeDebugVisualizations] resource:
+//!
+//! ```Rust
+//! visualizations.add(particles, VisualizeBuffer::Points { stride: 20, position_offset: 0, count: 4096 }, &mut images);
+//! let heatmap = visualizations.add(grid, VisualizeBuffer::Heatmap { width: 256, height: 256 }, &mut images).unwrap();
+//! commands.spawn(Sprite::from_image(heatmap));
+//! ```
+//!
+//! Each registered buffer is read back a few times a second, 5 by default, set by [rate](ComputeDebugVisualizations::rate), whether or not a compute sequence is running. The readback never waits on the GPU, so it doesn't stall the frame, and the visualization shows whatever contents arrived most recently. [Points](VisualizeBuffer::Points) and [Lines](VisualizeBuffer::Lines) are drawn with gizmos, and a [Heatmap](VisualizeBuffer::Heatmap) is drawn into the image [add](ComputeDebugVisualizations::add) returns. The buffers must be storage buffers created with [BufferUsages::COPY_SRC](bevy::render::render_resource::BufferUsages::COPY_SRC). The plugin is only enabled in debug builds, unless its [enabled](ComputeDebugVisualizationPlugin::enabled) field is set, so it can be left in.
+//!
+//! # Serialization
+//!
+//! [ComputeTask], [ComputeStep] and [ComputeAction] are generic over how they reference buffers, defaulting to [ShaderBufferHandle]. Handles aren't stable between runs, so to store compute tasks in your own files, convert the handles to names or indices with [map_buffers](ComputeTask::map_buffers), and convert them back once the buffers exist with [resolve](ComputeTask::resolve). The simplest way to do this is to give your buffers names with [name_buffer](ShaderBufferSet::name_buffer), in which case the [ShaderBufferSet] itself can resolve a `ComputeTask<String>`. With the `serde` feature enabled, all three types implement `Serialize` and `Deserialize`.
+//!
+//! # Checkpoints
+//!
+//! With the `checkpoint` feature enabled, the contents of buffers can be saved to disk and loaded again, for instance to pick a long-running simulation up where it left off. Send a `SaveBuffersEvent` with the buffers to save and a file path, and they're read back from the GPU asynchronously and written to the file, followed by a `BuffersSavedEvent`. Send a `LoadBuffersEvent` with the path to load them again, followed by a `BuffersLoadedEvent`. Buffers are matched up by their names, so every saved buffer needs one, given with [name_buffer](ShaderBufferSet::name_buffer), and the buffer it's loaded into must have the same kind, size, extent and format. Both halves of a double buffer are saved and loaded, along with which is the front buffer. If anything goes wrong, a `CheckpointFailedEvent` says what, listing every buffer that didn't match, and nothing is loaded. The file records the version of its format, so files from later versions are rejected rather than misread.
+//!
+//! # Record and Replay
+//!
+//! To track down a bug that depends on when the CPU wrote to a buffer, call [start_recording](ShaderBufferSet::start_recording) before starting a compute sequence, and [stop_recording](ShaderBufferSet::stop_recording) once it's done, to get a [ComputeTrace] of every [write_buffer](ShaderBufferSet::write_buffer) and swap, with the iteration boundaries they happened at, and the number of iterations run each frame. Set the buffers back as they were, pass the trace to [replay](ShaderBufferSet::replay), and start the same compute sequence again, and every write is made at the same iteration, with the same number of iterations each frame, so the buffers come out the same. With the `serde` feature enabled, a trace can be serialized to keep it on disk.
+//!
+//! # Headless Apps
+//!
+//! The plugin doesn't need a window or a camera, so it can run as a batch job, such as on a CI machine: dispatch a task with a fixed number of [iterations](ComputeTask::iterations), read the results back with a [CopyBuffer](ComputeAction::CopyBuffer) step, and send [AppExit] once they arrive. With the `WinitPlugin` disabled and no primary window, a `ScheduleRunnerPlugin` updates the app in a loop instead. The compute node is ordered before Bevy's camera driver when the render graph has one, and otherwise left as a root of the graph, which runs every frame all the same. Pipelined rendering should be disabled in such an app if it uses double buffers, as the main world only finds out about a swap a frame after the render world makes it. The `headless_prefix_sum` example does all of this, computing a prefix sum and printing it.
+//!
+//! # Testing
+//!
+//! With the `test-utils` feature enabled, the `test_utils` module provides a headless app for running compute sequences to completion in tests, and reading back and checking the results. It skips gracefully on machines without a GPU.
+//!
+//! # Inspector
+//!
+//! With the `inspector` feature enabled, you can add the `ComputeInspectorPlugin` to get an egui window, via bevy-inspector-egui, that shows every buffer, the progress of the running compute sequence, and lets you live-edit any buffer registered with `ShaderBufferSet::register_inspectable`.
+//!
+//! # GPU Capabilities
+//!
+//! Not every GPU supports everything. The [ComputeCapabilities] resource, available in both the main and render worlds once the plugin has finished, lists the features and limits that matter to compute shaders, such as whether storage textures can be [ReadWrite](bevy::render::render_resource::StorageTextureAccess::ReadWrite), whether push constants and timestamp queries are available, and the largest workgroups and storage buffers allowed, so an app can choose a fallback up front. The crate's own checks read from the same resource.
+//!
+//! [ReadWrite](bevy::render::render_resource::StorageTextureAccess::ReadWrite) storage textures are the most common trap. Every GPU supports them for `R32Float`, `R32Uint` and `R32Sint`, but many, including every browser, don't for any other format, so [add_texture_fill](ShaderBufferSet::add_texture_fill) panics when asked for one the GPU can't bind, rather than leaving it to fail when the pipeline is built. A [Binding::Double] texture works everywhere instead, with shaders reading the front buffer and writing the back, and a [SwapBuffers](ComputeAction::SwapBuffers) step after each pass.
+//!
+//! # Web
+//!
+//! Compute shaders need WebGPU, so on the web, enable the `webgpu` feature, which switches Bevy over from WebGL2. The `life` example builds for the web with `cargo build --example life --target wasm32-unknown-unknown --features webgpu`. The `.cargo/config.toml` in this repo sets the `getrandom` backend the web needs, and your own project will need the same setting.
+//!
+//! If the GPU adapter can't run compute shaders at all, as when a browser without WebGPU support falls back to WebGL2, nothing panics. A [ComputeUnsupported](ComputeErrorEvent::ComputeUnsupported) error is sent once, naming the backend in use, and the [ComputeState] resource is set to [Unsupported](ComputeState::Unsupported), after which compute sequences are silently ignored, so the app can show a "not supported" screen instead.
+//!
+//! Nothing can block the browser's main thread, so buffer readbacks work a little differently. Natively, with the default polling, a buffer copied back with [CopyBuffer](ComputeAction::CopyBuffer) is read in the same frame it's copied, but on the web, its [BufferReadbackEvent] arrives over the following frames, once the browser has mapped it. A copy buffer isn't overwritten until its previous contents have been delivered, and a task that hits its iteration limit waits for its outstanding readbacks before sending its [ComputeTaskDoneEvent]. With [Background](PollingMode::Background) or [Manual](PollingMode::Manual) polling, nothing waits natively either, so readbacks take the same path there as on the web, and just arrive sooner.
+//!
+//! WebGPU doesn't currently support timestamp queries or push constants. Without timestamp queries, a [FrameBudget] goes by its [estimate](FrameBudget::estimate) throughout, the GPU time isn't reported to the diagnostics, and the [ComputeProfilingPlugin] times nothing. A step with [PushConstants] panics when its task starts, as it would on any GPU without them. Shaders and pipelines of your own can't rely on either on the web, and should check [ComputeCapabilities] first.
 
+mod buffer_write_queue;
+mod builtin_pass;
+mod check_finite;
+mod clear_buffer;
 mod compute_bind_groups;
+mod compute_budget;
+mod compute_buffer_component;
+mod compute_capabilities;
+#[cfg(feature = "checkpoint")]
+mod compute_checkpoint;
 mod compute_data_transmission;
+mod compute_debug_visualization;
+mod compute_diagnostics;
+mod compute_error_context;
+mod compute_eta;
+mod compute_exit;
+mod compute_globals;
 mod compute_main_setup;
 mod compute_node;
+mod compute_pipeline_ids;
+mod compute_polling;
+mod compute_profiling;
 mod compute_render_setup;
 mod compute_sequence;
+mod compute_sequence_info;
+mod compute_shader_libraries;
+mod compute_texture_material;
+mod compute_trace;
+mod compute_validation;
+mod compute_watchdog;
+mod convolve;
+mod display_compute_buffers;
+mod dump_compute_state;
+mod empty_check;
 mod extract_resources;
+mod half_float;
+mod histogram;
+#[cfg(feature = "inspector")]
+mod inspector;
+#[path = "main_world.rs"]
+pub mod main;
 mod parse_render_messages;
+mod prefix_sum;
+pub mod prelude;
+mod prepared_shaders;
 mod queue_bind_group;
+mod read_buffer;
+mod reduce_buffer;
+#[path = "render_world.rs"]
+pub mod render;
+mod run_compute_tasks;
 mod shader_buffer_set;
-mod swap_sprite_buffers;
+mod sort_buffer;
+mod step_writes;
+#[cfg(feature = "test-utils")]
+pub mod test_utils;
+mod write_back_to_image;
 
-use std::{sync::mpsc::sync_channel, time::Duration};
+use std::{collections::BTreeMap, time::Duration};
 
 use bevy::{
+	asset::load_internal_asset,
 	prelude::*,
-	render::{Render, RenderApp, RenderSet},
+	render::{
+		extract_component::ExtractComponentPlugin,
+		extract_resource::ExtractResourcePlugin,
+		render_resource::{
+			encase::{self, internal::CreateFrom},
+			ShaderType, TextureFormat,
+		},
+		Render, RenderApp, RenderSet,
+	},
+};
+pub use buffer_write_queue::BufferWriteQueue;
+use bytemuck::{Pod, PodCastError};
+use check_finite::{read_finite_checks, ComputeFiniteChecker, CHECK_FINITE_SHADER_HANDLE};
+pub use check_finite::{ComputeFiniteChecks, FiniteCheckType};
+pub use clear_buffer::ClearHalves;
+pub use compute_bind_groups::ComputeBindGroups;
+use compute_budget::{read_budget_timings, ComputeBudgetTimer};
+use compute_buffer_component::release_compute_buffer;
+pub use compute_buffer_component::ComputeBufferComponent;
+use compute_capabilities::insert_compute_capabilities;
+pub use compute_capabilities::ComputeCapabilities;
+#[cfg(feature = "checkpoint")]
+use compute_checkpoint::ComputeCheckpointPlugin;
+#[cfg(feature = "checkpoint")]
+pub use compute_checkpoint::{
+	BuffersLoadedEvent, BuffersSavedEvent, CheckpointEntry, CheckpointEntryError, CheckpointEntryProblem,
+	CheckpointError, CheckpointFailedEvent, CheckpointLayout, LoadBuffersEvent, SaveBuffersEvent, CHECKPOINT_VERSION,
 };
 use compute_data_transmission::ComputeDataTransmission;
-use compute_main_setup::compute_main_setup;
+pub use compute_debug_visualization::{ComputeDebugVisualizationPlugin, ComputeDebugVisualizations, VisualizeBuffer};
+pub use compute_diagnostics::{
+	encode_time_path, iterations_per_second_path, ComputeDiagnosticsPlugin, ALLOCATED_BYTES, BIND_GROUP_BUILD_TIME,
+	GPU_TIME,
+};
+use compute_error_context::{clear_error_context, install_error_handler, ComputeErrorContext};
+use compute_exit::{release_compute_resources, release_render_resources};
+pub use compute_globals::{
+	iteration_seed, ComputeCamera, ComputeCameraMatrices, ComputeChunk, ComputeCursor, ComputeGlobals,
+	ComputeIterationCounts, ComputeSize,
+};
+use compute_globals::{update_cursor_uniform, update_size_uniform};
+use compute_main_setup::{check_compute_support, compute_main_setup, run_stop_conditions};
+use compute_pipeline_ids::ComputePipelineIds;
+pub use compute_polling::PollingMode;
+use compute_polling::{poll_device, start_polling, ComputePolling};
+pub use compute_profiling::{ComputeProfilingPlugin, ComputeTimings};
 use compute_render_setup::compute_render_setup;
-use compute_sequence::ComputeSequence;
+pub use compute_render_setup::{ComputeLabel, ComputeRenderConsumers};
+use compute_sequence::ComputeSequences;
 pub use compute_sequence::*;
+use compute_sequence_info::{update_iteration_state, update_sequence_info};
+pub use compute_sequence_info::{
+	ComputeIterationState, ComputeSequenceInfo, ComputeTaskInfo, ComputeTaskStatus, TaskIterationState,
+};
+pub use compute_shader_libraries::ComputeShaderLibraries;
+pub use compute_texture_material::{
+	compute_texture_2d, compute_texture_3d, Colormap, ComputeTextureMaterial, ComputeTextureMaterial2d,
+	ComputeTextureMaterialPlugin,
+};
+use compute_trace::make_recorded_writes;
+pub use compute_trace::{ComputeTrace, TraceEntry, TraceEvent, TraceFrame};
+use compute_validation::{check_running_bindings, validate_compute_sequences};
+pub use compute_validation::{
+	ComputeValidationFinding, ComputeValidationReport, ValidateComputeEvent, ValidationSeverity,
+};
+pub use compute_watchdog::ComputeTaskWatchdogs;
+use compute_watchdog::{check_compute_watchdog, ComputeWatchdog};
+use convolve::CONVOLVE_SHADER_HANDLE;
+pub use convolve::{ConvolutionEdges, ConvolutionKernel, MAX_CONVOLUTION_TAPS};
+use display_compute_buffers::display_compute_buffers;
+pub use dump_compute_state::dump_compute_state;
+use empty_check::{read_empty_checks, ComputeEmptyChecker};
 use extract_resources::extract_resources;
+pub use half_float::{f16_to_f32, f32_to_f16};
+use histogram::HISTOGRAM_SHADER_HANDLE;
+pub use histogram::{HistogramOutOfRange, HistogramSource};
+#[cfg(feature = "inspector")]
+pub use inspector::ComputeInspectorPlugin;
 use parse_render_messages::parse_render_messages;
+use prefix_sum::PREFIX_SUM_SHADER_HANDLE;
+use prepared_shaders::{prepare_shaders, PreparedShaders};
 use queue_bind_group::queue_bind_group;
+use read_buffer::{read_buffer_readbacks, ComputeReadbacks};
+use reduce_buffer::REDUCE_BUFFER_SHADER_HANDLE;
+pub use reduce_buffer::{ReduceElement, ReduceOp};
+pub use run_compute_tasks::OneShotTask;
+use run_compute_tasks::{run_compute_tasks, OneShotTasks};
 use shader_buffer_set::ShaderBufferSetPlugin;
 pub use shader_buffer_set::*;
-use swap_sprite_buffers::swap_sprite_buffers;
+use sort_buffer::SORT_BUFFER_SHADER_HANDLE;
+pub use sort_buffer::{SortKey, SortKeyType};
+pub use step_writes::{StepWriteAppliedEvent, StepWriteId};
+use write_back_to_image::{write_back_to_images, ImageWriteBacks};
 
 /// This plugin adds all the systems, resources and events necessary for bevy_compute to function. Please add it to your
 /// bevy app with:
 ///
 /// ```Rust
-/// app.add_plugins((BevyComputePlugin));
+/// app.add_plugins(BevyComputePlugin);
 /// ```
+///
+/// It's configured with a [ComputePluginSettings] resource, inserted before it's added.
+#[derive(Default)]
 pub struct BevyComputePlugin;
 
+/// The settings of the [BevyComputePlugin]. The plugin reads them once, when it's added, so this has to be inserted before that:
+///
+/// ```Rust
+/// app.insert_resource(ComputePluginSettings { polling: PollingMode::Manual, ..default() });
+/// app.add_plugins(BevyComputePlugin);
+/// ```
+///
+/// Without it, the defaults are used, and it's inserted with them.
+#[derive(Resource, Clone, Debug, Default)]
+pub struct ComputePluginSettings {
+	/// How the GPU is polled for finished work, such as buffer readbacks. Defaults to [PerFrame](PollingMode::PerFrame).
+	pub polling: PollingMode,
+
+	/// A soft limit on the bytes of GPU memory the [ShaderBufferSet] allocates, past which adding a buffer fails with [ShaderBufferError::BudgetExceeded] rather than allocating it. Defaults to `None`, for no limit. See [set_memory_budget](ShaderBufferSet::set_memory_budget), which can change it later.
+	pub memory_budget: Option<u64>,
+}
+
 impl Plugin for BevyComputePlugin {
 	fn build(&self, app: &mut App) {
-		let (sender, receiver) = sync_channel(16);
+		load_internal_asset!(app, SORT_BUFFER_SHADER_HANDLE, "shaders/sort_buffer.wgsl", Shader::from_wgsl);
+		load_internal_asset!(app, REDUCE_BUFFER_SHADER_HANDLE, "shaders/reduce_buffer.wgsl", Shader::from_wgsl);
+		load_internal_asset!(app, PREFIX_SUM_SHADER_HANDLE, "shaders/prefix_sum.wgsl", Shader::from_wgsl);
+		load_internal_asset!(app, HISTOGRAM_SHADER_HANDLE, "shaders/histogram.wgsl", Shader::from_wgsl);
+		load_internal_asset!(app, CHECK_FINITE_SHADER_HANDLE, "shaders/check_finite.wgsl", Shader::from_wgsl);
+		load_internal_asset!(app, CONVOLVE_SHADER_HANDLE, "shaders/convolve.wgsl", Shader::from_wgsl);
+		let settings = app.world_mut().get_resource_or_insert_with(ComputePluginSettings::default).clone();
 
 		app
-			.add_plugins(ShaderBufferSetPlugin)
-			.insert_non_send_resource(ComputeDataTransmission { sender, receiver })
-			.add_systems(Update, compute_main_setup)
-			.add_systems(First, parse_render_messages.run_if(resource_exists::<ComputeSequence>))
-			.add_systems(Update, swap_sprite_buffers.run_if(resource_exists::<ComputeSequence>))
+			.add_plugins((
+				ShaderBufferSetPlugin,
+				ExtractComponentPlugin::<ComputeBufferComponent>::default(),
+				ExtractComponentPlugin::<ComputeCamera>::default(),
+				ExtractResourcePlugin::<PreparedShaders>::default(),
+				ExtractResourcePlugin::<ComputeTaskIterations>::default(),
+				ExtractResourcePlugin::<ComputeStepFlags>::default(),
+				ExtractResourcePlugin::<ComputeTaskWatchdogs>::default(),
+				ExtractResourcePlugin::<ComputeFiniteChecks>::default(),
+			))
+			.init_non_send_resource::<ComputeDataTransmission>()
+			.init_resource::<ComputeProgress>()
+			.init_resource::<ComputeState>()
+			.init_resource::<ComputeSequenceStates>()
+			.init_resource::<OneShotTasks>()
+			.init_resource::<ComputeShaderLibraries>()
+			.init_resource::<PreparedShaders>()
+			.init_resource::<ComputeTaskIterations>()
+			.init_resource::<ComputeStepFlags>()
+			.init_resource::<ComputeTaskStopConditions>()
+			.init_resource::<ComputeTaskWatchdogs>()
+			.init_resource::<ComputeFiniteChecks>()
+			.add_systems(
+				Update,
+				(
+					run_compute_tasks,
+					run_stop_conditions,
+					compute_main_setup,
+					update_sequence_info,
+					update_iteration_state,
+					prepare_shaders,
+				)
+					.chain(),
+			)
+			.add_systems(First, parse_render_messages.run_if(resource_exists::<ComputeSequences>))
+			.add_systems(Update, display_compute_buffers)
+			.add_systems(Update, validate_compute_sequences)
+			.add_systems(PostUpdate, (update_size_uniform, update_cursor_uniform))
+			.add_systems(Last, (check_running_bindings, release_compute_resources).chain())
+			.add_observer(release_compute_buffer)
 			.add_event::<StartComputeEvent>()
+			.add_event::<ExtendComputeTaskEvent>()
+			.add_event::<StopComputeTaskEvent>()
+			.add_event::<PauseComputeTaskEvent>()
+			.add_event::<TriggerComputeTaskEvent>()
+			.add_event::<StopComputeEvent>()
+			.add_event::<PauseComputeEvent>()
+			.add_event::<ResumeComputeEvent>()
+			.add_event::<UpdateDispatchSizeEvent>()
+			.add_event::<SwapStepShaderEvent>()
 			.add_event::<CopyBufferEvent>()
-			.add_event::<ComputeTaskDoneEvent>();
+			.add_event::<BufferReadbackEvent>()
+			.add_event::<ComputeTaskDoneEvent>()
+			.add_event::<RunComputeTaskEvent>()
+			.add_event::<ComputeTaskCompleteEvent>()
+			.add_event::<ComputeErrorEvent>()
+			.add_event::<ValidateComputeEvent>()
+			.add_event::<ComputeValidationReport>()
+			.add_event::<WriteBackToImageEvent>()
+			.add_event::<StepWriteAppliedEvent>()
+			.init_resource::<ImageWriteBacks>()
+			.add_systems(Update, write_back_to_images);
+		app.world_mut().resource_mut::<ShaderBufferSet>().set_memory_budget(settings.memory_budget);
 
 		let render_app = app.sub_app_mut(RenderApp);
 		render_app
-			.add_systems(ExtractSchedule, extract_resources)
-			.add_systems(Render, queue_bind_group.in_set(RenderSet::Queue).run_if(resource_exists::<ComputeSequence>))
-			.add_systems(Render, compute_render_setup.run_if(resource_added::<ComputeSequence>));
+			.add_systems(ExtractSchedule, (extract_resources, release_render_resources))
+			.add_systems(Render, queue_bind_group.in_set(RenderSet::Queue).run_if(resource_exists::<ComputeSequences>))
+			.add_systems(Render, compute_render_setup.run_if(resource_added::<ComputeSequences>))
+			.add_systems(Render, poll_device.in_set(RenderSet::Cleanup))
+			.add_systems(Render, clear_error_context.in_set(RenderSet::Cleanup))
+			.add_systems(
+				Render,
+				check_compute_watchdog
+					.in_set(RenderSet::Cleanup)
+					.after(poll_device)
+					.run_if(resource_exists::<ComputeSequences>),
+			)
+			.add_systems(Render, read_budget_timings.in_set(RenderSet::Cleanup).after(poll_device))
+			.add_systems(
+				Render,
+				read_finite_checks.in_set(RenderSet::Cleanup).after(poll_device).run_if(resource_exists::<ComputeSequences>),
+			)
+			.add_systems(Render, read_empty_checks.in_set(RenderSet::Cleanup).after(poll_device))
+			.add_systems(Render, read_buffer_readbacks.in_set(RenderSet::Cleanup).after(poll_device))
+			.add_systems(Render, make_recorded_writes.in_set(RenderSet::PrepareResources))
+			.init_resource::<ComputeWatchdog>()
+			.init_resource::<ComputeBudgetTimer>()
+			.init_resource::<ComputeFiniteChecker>()
+			.init_resource::<ComputeEmptyChecker>()
+			.init_resource::<ComputeReadbacks>()
+			.init_resource::<ComputeErrorContext>()
+			.init_resource::<ComputeRenderConsumers>()
+			.init_resource::<ComputePipelineIds>()
+			.insert_resource(ComputePolling::new(settings.polling));
+
+		#[cfg(feature = "checkpoint")]
+		app.add_plugins(ComputeCheckpointPlugin);
+	}
+
+	fn finish(&self, app: &mut App) {
+		insert_compute_capabilities(app);
+		check_compute_support(app.world_mut());
+		start_polling(app);
+		install_error_handler(app);
 	}
 }
 
-/// This event is how you start the compute shaders. Specify the details of how they're going to run with the [tasks](StartComputeEvent::tasks), and optionally provide a buffer to store the current iteration count with [iteration_buffer](StartComputeEvent::iteration_buffer).
+/// Which of the compute sequences running side by side an event is for, such as a long-running simulation in one, and short on-demand dispatches in another. Each sequence runs its own tasks, with its own iteration counts, pauses and frame budgets, and is started, stopped and paused on its own, while the buffers in the [ShaderBufferSet] are shared between all of them. The sequences that are running all record their iterations in the same frame, in the order of their ids. The [ComputeProgress], [ComputeState] and [ComputeSequenceInfo] follow the [MAIN](ComputeSequenceId::MAIN) sequence, which is the one events are for by default, and the [ComputeSequenceStates] follow all of them. Ids of `1 << 31` and up are set aside for the sequences a [RunComputeTaskEvent] runs in.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ComputeSequenceId(pub u32);
+
+impl ComputeSequenceId {
+	/// The sequence an app with only one compute sequence runs, which is the default.
+	pub const MAIN: Self = Self(0);
+}
+
+/// This event is how you start the compute shaders. Specify the details of how they're going to run with the [tasks](StartComputeEvent::tasks), and optionally provide a buffer to store the current iteration count with [iteration_buffer](StartComputeEvent::iteration_buffer). Sending another one later for the same [sequence](StartComputeEvent::sequence), such as after the [final_group](ComputeTaskDoneEvent::final_group) of the last sequence is done, replaces the previous compute sequence with the new one, while one for a different sequence starts it alongside any that are already running.
 #[derive(Event)]
 pub struct StartComputeEvent {
+	/// The sequence to start, which is [MAIN](ComputeSequenceId::MAIN) unless more than one is run at a time.
+	pub sequence: ComputeSequenceId,
+
 	/// Ths list of compute tasks to complete. It will run each task in sequence, and throw a [ComputeTaskDoneEvent] when they're done.
 	pub tasks: Vec<ComputeTask>,
 
 	/// An optional iteration buffer. This buffer should be a 4-byte uniform buffer, that stores a single u32. If provided, then every tick, it will be set to the current iteration count within the current compute task. It will reset to zero every time a new compute task starts.
 	pub iteration_buffer: Option<ShaderBufferHandle>,
+
+	/// The seed the [seed](ComputeGlobals::seed) in the globals buffer is worked out from for every iteration, with [iteration_seed]. Starting a sequence with the same seed gives the same seeds for the same iterations, so a run can be reproduced. Use a different one, such as from the system time, for a different run each time.
+	pub seed: u64,
+}
+
+/// This event adds iterations to a task of the running compute sequence, for instance to run a simulation for a few hundred more iterations than it was started with. If the task hasn't finished yet, it runs for that many more iterations than it would have. If it has, the sequence is started over from that task, which runs for just the additional iterations, followed by the tasks after it, as before. Either way, the [ComputeTaskDoneEvent] for the task reports how many iterations it ran for in the end. This has no effect on a task that runs forever. The main world only finds out a task has finished a frame or two after it has, so an extension sent right as a task finishes may be too late to extend it, without restarting it either.
+#[derive(Event)]
+pub struct ExtendComputeTaskEvent {
+	/// The sequence the task is in.
+	pub sequence: ComputeSequenceId,
+
+	/// The index of the task to extend, as in, the index into the `Vec<ComputeTask>` that was provided in the [StartComputeEvent].
+	pub task: usize,
+
+	/// The number of iterations to add.
+	pub additional: u32,
+}
+
+/// This event stops a task of the running compute sequence early, at the next iteration boundary, and then moves on to the next task, as if the task had run all its iterations. Its [ComputeTaskDoneEvent] is sent as usual, with the number of iterations it ran for. This works for tasks that would otherwise run forever, as well as ones with an iteration count. Stopping a task that hasn't started yet makes it finish as soon as it starts, without running, and stopping one that's already finished has no effect. To stop a task when a condition is met, without sending this yourself, see [ComputeTaskStopConditions].
+#[derive(Event)]
+pub struct StopComputeTaskEvent {
+	/// The sequence the task is in.
+	pub sequence: ComputeSequenceId,
+
+	/// The index of the task to stop, as in, the index into the `Vec<ComputeTask>` that was provided in the [StartComputeEvent].
+	pub task: usize,
+}
+
+/// This event pauses a task of the running compute sequence, or resumes it. A paused task stays where it is, without running any more iterations, until it's resumed, after which it carries on as if it had never been paused. Pausing a task that hasn't started yet makes it wait as soon as it starts. Tasks are also paused by their watchdogs, when an iteration takes longer than allowed in [ComputeTaskWatchdogs], and are resumed the same way.
+#[derive(Event)]
+pub struct PauseComputeTaskEvent {
+	/// The sequence the task is in.
+	pub sequence: ComputeSequenceId,
+
+	/// The index of the task to pause or resume, as in, the index into the `Vec<ComputeTask>` that was provided in the [StartComputeEvent].
+	pub task: usize,
+
+	/// Whether to pause the task, or resume it.
+	pub paused: bool,
+}
+
+/// This event triggers a task of the running compute sequence that runs [on demand](TaskRunMode::OnDemand), to run this many more iterations. Triggers add up, so sending several in the same frame, or sending one while the task is still running the iterations of another, runs all their iterations in turn. The task is found by its [label](ComputeTask::label), and if the sequence has no on-demand task with that label, an error is logged, and nothing is run.
+#[derive(Event)]
+pub struct TriggerComputeTaskEvent {
+	/// The sequence the task is in.
+	pub sequence: ComputeSequenceId,
+
+	/// The label of the task to trigger.
+	pub label: String,
+
+	/// The number of iterations to run.
+	pub iterations: u32,
+}
+
+/// This event stops a running compute sequence straight away, without finishing the task it's in, or sending any more [ComputeTaskDoneEvent]s, and without waiting for readbacks that haven't arrived yet. Nothing more is dispatched for it, its [ComputeState] goes back to [Idle](ComputeState::Idle), and its [ComputeProgress] and [ComputeSequenceInfo] are cleared. Any other sequences carry on as they were. The buffers are left as they are, with whatever the sequence last wrote to them, so a new sequence can be started on them with another [StartComputeEvent].
+#[derive(Event, Default)]
+pub struct StopComputeEvent(pub ComputeSequenceId);
+
+/// This event pauses a whole running compute sequence, until it's resumed with a [ResumeComputeEvent]. Nothing is dispatched for it while it's paused, and everything else stays as it is, including the iteration count of the current task and which half of each double buffer is the front, so once it's resumed, it carries on exactly where it left off. This is separate from pausing a task with a [PauseComputeTaskEvent], so a task that's paused on its own stays paused when the sequence is resumed.
+#[derive(Event, Default)]
+pub struct PauseComputeEvent(pub ComputeSequenceId);
+
+/// This event resumes a compute sequence paused with a [PauseComputeEvent].
+#[derive(Event, Default)]
+pub struct ResumeComputeEvent(pub ComputeSequenceId);
+
+/// This event switches a shader step of the running compute sequence over to a different shader, or a different entry point, without starting the sequence over, so the iteration counts and the contents of the buffers carry on as they were, such as for live-coding a shader. The step keeps running its old shader until the pipeline for the new one is ready, so no iterations go without it, and if the new one fails to compile, it carries on with the old one, and a [ComputeErrorEvent::ShaderSwapFailed] is sent. The step keeps its shader defs and override values. The step is changed in the compute sequence itself, so a task that hasn't started yet, or is started over by an [ExtendComputeTaskEvent], runs the new shader from the start.
+#[derive(Event)]
+pub struct SwapStepShaderEvent {
+	/// The sequence the step is in.
+	pub sequence: ComputeSequenceId,
+
+	/// The index of the task the step is in, as in, the index into the `Vec<ComputeTask>` that was provided in the [StartComputeEvent].
+	pub task: usize,
+
+	/// The index of the step in the task, which must be a [RunShader](ComputeAction::RunShader), [RunShaderPerEntity](ComputeAction::RunShaderPerEntity) or [RunShaderIndirect](ComputeAction::RunShaderIndirect) step.
+	pub step: usize,
+
+	/// The Bevy asset path to the shader file to run.
+	pub shader: String,
+
+	/// The name of the function to run in that shader file, or `None` to keep the step's entry point.
+	pub entry_point: Option<String>,
+}
+
+/// This event changes the dispatch size of a shader step of the running compute sequence, without starting it over, such as to follow a texture that's been recreated at a new resolution when the window is resized. The running task dispatches the step with the new size from the next frame on, and as the step is changed in the compute sequence itself, a task that hasn't started yet, or is started over by an [ExtendComputeTaskEvent], runs with the new size from the start. Only [RunShader](ComputeAction::RunShader) and [RunShaderPerEntity](ComputeAction::RunShaderPerEntity) steps have a single dispatch size to change. Changing a step that doesn't exist, or that doesn't have one, logs an error, and leaves the sequence as it was.
+#[derive(Event)]
+pub struct UpdateDispatchSizeEvent {
+	/// The sequence the step is in.
+	pub sequence: ComputeSequenceId,
+
+	/// The index of the task the step is in, as in, the index into the `Vec<ComputeTask>` that was provided in the [StartComputeEvent].
+	pub task: usize,
+
+	/// The index of the step in the task.
+	pub step: usize,
+
+	/// The new dispatch size.
+	pub dispatch: DispatchSize,
 }
 
 /// This event is thrown every time a [CopyBuffer][ComputeAction::CopyBuffer] compute action is executed. It contains the handle of the buffer that was copied, and a `Vec<u8>` with all the data. This is how you get data back out of the compute shader to the CPU.
@@ -152,9 +882,51 @@ pub struct CopyBufferEvent {
 	pub data: Vec<u8>,
 }
 
-/// This event is thrown every time a compute task is completed.
+/// This event is thrown every time data is read back from a buffer on the GPU to the CPU, such as by a [CopyBuffer](ComputeAction::CopyBuffer) compute action, which also throws a [CopyBufferEvent] with the same data.
+///
+/// The data is sent from the render world to the main world over a channel, and read out of it in the `First` schedule, so these events arrive at most a couple of frames after the GPU work that produced them, and always in order for any given buffer. They're never dropped: if the main world falls far enough behind that the channel fills up, a warning is logged, and the render world blocks until the main world catches up. On the web, where the render world can't block, the channel is unbounded instead.
+#[derive(Event, Clone)]
+pub struct BufferReadbackEvent {
+	/// The sequence that read the buffer back.
+	pub sequence: ComputeSequenceId,
+
+	/// The handle of the buffer that was read back.
+	pub handle: ShaderBufferHandle,
+
+	/// The label of the compute task that read the buffer back, if it has one.
+	pub group: Option<String>,
+
+	/// The iteration of the compute task that read the buffer back.
+	pub iteration: u32,
+
+	/// The data the buffer contained, as a raw sequence of bytes.
+	pub data: Vec<u8>,
+}
+
+impl BufferReadbackEvent {
+	/// Reinterpret the data as a slice of some plain-old-data type, such as `u32` or `[f32; 4]`, without copying it. This fails if the data's length isn't a multiple of the type's size, or if it isn't suitably aligned for the type.
+	pub fn as_slice_of<T: Pod>(&self) -> Result<&[T], PodCastError> { bytemuck::try_cast_slice(&self.data) }
+
+	/// Decode the data as a [ShaderType](bevy::render::render_resource::ShaderType), following the same memory layout rules the buffer was written with. This is the inverse of what [write_buffer](ShaderBufferSet::write_buffer) and the `add_*_init` functions do, and fails if there isn't enough data.
+	pub fn decode<T: ShaderType + CreateFrom>(&self) -> Result<T, encase::internal::Error> {
+		encase::StorageBuffer::new(self.data.as_slice()).create()
+	}
+
+	/// Decode the data as 16-bit floats, such as the channels of an [Rgba16Float](bevy::render::render_resource::TextureFormat::Rgba16Float) texture, converting each to an `f32` with [f16_to_f32]. [as_slice_of](BufferReadbackEvent::as_slice_of) with `u16` would only give their raw bits. This fails if the data's length isn't a multiple of 2.
+	pub fn decode_f16(&self) -> Result<Vec<f32>, PodCastError> {
+		if self.data.len() % 2 != 0 {
+			return Err(PodCastError::OutputSliceWouldHaveSlop);
+		}
+		Ok(self.data.chunks_exact(2).map(|bits| f16_to_f32(u16::from_le_bytes([bits[0], bits[1]]))).collect())
+	}
+}
+
+/// This event is thrown every time a compute task is completed, such as a bake whose results can then be read and shown. It's sent once the GPU has finished every iteration of the task, not just once the last one has been recorded, which is noticed when the device is [polled](PollingMode), usually a frame or so later. A task with a number of iterations sends it exactly once, after its last, while a task that runs forever never sends it, unless it's stopped with a [StopComputeTaskEvent].
 #[derive(Event)]
 pub struct ComputeTaskDoneEvent {
+	/// The sequence the task is in.
+	pub sequence: ComputeSequenceId,
+
 	/// The number of the completed task, as in, the index into the `Vec<ComputeTask>` that was provided in the [StartComputeEvent].
 	pub group_finished: usize,
 
@@ -164,11 +936,320 @@ pub struct ComputeTaskDoneEvent {
 	/// The time spent on the task. A timestamp is taken on start and completion of the task, and the difference provided here.
 	pub time_in_group: Duration,
 
+	/// The number of iterations the task ran for, which includes any it was extended by with an [ExtendComputeTaskEvent], or came from [ComputeTaskIterations].
+	pub iterations: u32,
+
 	/// Whether this is the final task. If all you care about is whether the entire compute sequence is done, then check this.
 	pub final_group: bool,
 }
 
-/// This component should be placed on any sprite entity that is intended to display a double buffered texture. It requires a [Sprite]. There is an internal system that will update the image handle on that [Sprite] to be the current front buffer.
-#[derive(Component)]
-#[require(Sprite)]
-pub struct DoubleBufferedSprite(pub ShaderBufferHandle);
+/// This event runs a single [OneShotTask], without having to set up a whole compute sequence. Any number of these can be sent in the same frame, and they'll run in the order they were sent, once any running compute sequence has finished. When each one is done, a [ComputeTaskCompleteEvent] will be thrown with the same task ID.
+///
+/// Under the hood, each batch of them runs in a compute sequence of its own, with an id of `1 << 31` or more, so its events are never mistaken for those of another sequence. Those ids are set aside for them, so they shouldn't be used in a [StartComputeEvent].
+#[derive(Event)]
+pub struct RunComputeTaskEvent {
+	/// An ID of your choosing, which will be provided in the [ComputeTaskCompleteEvent], to tell which task it's for. It needn't be unique, as each task is told apart from the others by an id of its own under the hood, and tasks sent with the same ID complete in the order they were sent.
+	pub task_id: u64,
+
+	/// The task to run.
+	pub task: OneShotTask,
+}
+
+/// This event is thrown when a task started with a [RunComputeTaskEvent] has completed.
+#[derive(Event)]
+pub struct ComputeTaskCompleteEvent {
+	/// The ID provided in the [RunComputeTaskEvent].
+	pub task_id: u64,
+
+	/// The contents of the buffer requested with [readback](OneShotTask::readback), as a raw sequence of bytes, or `None` if no readback was requested.
+	pub data: Option<Vec<u8>>,
+}
+
+/// Send this to have the next readback of a texture buffer, by a [CopyBuffer](ComputeAction::CopyBuffer) step, written into an [Image] asset in the main world, for baking a compute result into a texture that other systems, such as UI or saving, can use like any other image. Nothing is read back by this event itself, so the running task needs a [CopyBuffer](ComputeAction::CopyBuffer) step for the buffer. The image's data and size are replaced, and Bevy uploads it again wherever it's used. If the image doesn't exist yet, it's created with the texture's format. The image must have the same format as the texture, or its sRGB version, otherwise a [ComputeErrorEvent::ImageFormatMismatch] is sent instead. Panics if the buffer isn't a texture.
+#[derive(Event, Clone)]
+pub struct WriteBackToImageEvent {
+	/// The texture buffer to write back. For a double buffered texture, this is the front buffer at the time of the copy.
+	pub buffer: ShaderBufferHandle,
+
+	/// The image to write it into.
+	pub target: Handle<Image>,
+}
+
+/// This event is thrown when something is wrong with the compute sequence, but not so wrong that it can't keep running.
+#[derive(Event, Clone, Debug)]
+pub enum ComputeErrorEvent {
+	/// A value was given for an override constant that isn't declared in the shader, so it was ignored.
+	UnknownOverride {
+		/// The asset path of the shader.
+		shader: String,
+
+		/// The name of the override.
+		name: String,
+	},
+
+	/// A step runs an entry point that isn't in its shader, such as because of a typo in its name. The step waits, along with the rest of its task, until the shader is hot-reloaded with the entry point in it, or the step is switched to another one with a [SwapStepShaderEvent]. This is sent once each time the shader is checked, which is when it's first loaded, and every time it's reloaded.
+	MissingEntryPoint {
+		/// The asset path of the shader.
+		shader: String,
+
+		/// The entry point that isn't in it.
+		entry_point: String,
+	},
+
+	/// The pipeline for a step's shader couldn't be made, such as because the shader doesn't compile, or its bindings don't match the buffers. The task waits until the shader is fixed and hot-reloaded, which the pipeline is tried again for. This is sent once for each failure, and logged along with it, rather than every frame.
+	PipelineFailed {
+		/// The sequence the task is in.
+		sequence: ComputeSequenceId,
+
+		/// The index of the task, as in, the index into the `Vec<ComputeTask>` that was provided in the [StartComputeEvent].
+		task: usize,
+
+		/// The label of the task, if it has one.
+		label: Option<String>,
+
+		/// The index of the step in the task.
+		step: usize,
+
+		/// The asset path of the shader.
+		shader: String,
+
+		/// The entry point in the shader.
+		entry_point: String,
+
+		/// What went wrong.
+		error: String,
+	},
+
+	/// The GPU adapter doesn't support compute shaders, as on WebGL2, so no compute sequence will ever run. This is sent once, when the app starts, and [ComputeState] is left as [Unsupported](ComputeState::Unsupported).
+	ComputeUnsupported {
+		/// The graphics backend in use, such as `Gl`.
+		backend: String,
+
+		/// The name of the adapter.
+		adapter: String,
+	},
+
+	/// A texture buffer couldn't be written back to an image, as asked for by a [WriteBackToImageEvent], because the image has a different format. The image is left as it was.
+	ImageFormatMismatch {
+		/// The texture buffer that was read back.
+		buffer: ShaderBufferHandle,
+
+		/// The image it was to be written to.
+		target: Handle<Image>,
+
+		/// The format of the texture buffer.
+		buffer_format: TextureFormat,
+
+		/// The format of the image.
+		image_format: TextureFormat,
+	},
+
+	/// An iteration of a task took longer on the GPU than its limit in [ComputeTaskWatchdogs], or still hadn't finished once its limit had passed, so the task has been paused. It can be resumed with a [PauseComputeTaskEvent], once the limit or the shader has been fixed.
+	WatchdogTimeout {
+		/// The sequence the task is in.
+		sequence: ComputeSequenceId,
+
+		/// The index of the task, as in, the index into the `Vec<ComputeTask>` that was provided in the [StartComputeEvent].
+		task: usize,
+
+		/// The label of the task, if it has one.
+		label: Option<String>,
+
+		/// The iteration that took too long, counting from 1.
+		iteration: u32,
+
+		/// The indices of the steps that ran in that iteration, any of which could be the one at fault, as the iteration is timed as a whole.
+		steps: Vec<usize>,
+
+		/// How long the iteration took, or how long it had been running when it was reported, if it hadn't finished.
+		elapsed: Duration,
+
+		/// The limit it went over.
+		limit: Duration,
+
+		/// Whether the iteration had finished when it was reported. If it hadn't, the GPU may have stalled.
+		finished: bool,
+	},
+
+	/// A [CheckFinite](ComputeAction::CheckFinite) step found a NaN or infinity. The value was written by one of the steps before it, in the same iteration or an earlier one. Each step only reports the first value it finds, once per compute sequence.
+	NonFiniteValue {
+		/// The sequence the task is in.
+		sequence: ComputeSequenceId,
+
+		/// The index of the task, as in, the index into the `Vec<ComputeTask>` that was provided in the [StartComputeEvent].
+		task: usize,
+
+		/// The label of the task, if it has one.
+		label: Option<String>,
+
+		/// The index of the [CheckFinite](ComputeAction::CheckFinite) step in the task.
+		step: usize,
+
+		/// The iteration the step found it in, counting from 1.
+		iteration: u32,
+
+		/// The buffer or texture that was checked.
+		buffer: ShaderBufferHandle,
+
+		/// The index of the first value that isn't finite, counting `f32`s from the start of a buffer, or texels row by row in a texture.
+		index: u32,
+
+		/// The value itself, which is a NaN or an infinity.
+		value: f32,
+	},
+
+	/// The shader a step was being switched over to with a [SwapStepShaderEvent] couldn't be made into a pipeline, such as because it doesn't compile, so the step carries on with its old shader. The step is still switched over if the shader is fixed and hot-reloaded, or another [SwapStepShaderEvent] can be sent for a different shader.
+	ShaderSwapFailed {
+		/// The sequence the task is in.
+		sequence: ComputeSequenceId,
+
+		/// The index of the task, as in, the index into the `Vec<ComputeTask>` that was provided in the [StartComputeEvent].
+		task: usize,
+
+		/// The label of the task, if it has one.
+		label: Option<String>,
+
+		/// The index of the step in the task.
+		step: usize,
+
+		/// The asset path of the shader.
+		shader: String,
+
+		/// The entry point in the shader.
+		entry_point: String,
+
+		/// What went wrong.
+		error: String,
+	},
+
+	/// A shader a running step uses was hot-reloaded, but the new version couldn't be made into a pipeline, such as because it doesn't compile, so the step carries on with the pipeline it had. This is only sent once for each failure, and the step switches over to the new pipeline as soon as the shader is fixed and reloaded again.
+	ShaderReloadFailed {
+		/// The sequence the task is in.
+		sequence: ComputeSequenceId,
+
+		/// The index of the task, as in, the index into the `Vec<ComputeTask>` that was provided in the [StartComputeEvent].
+		task: usize,
+
+		/// The label of the task, if it has one.
+		label: Option<String>,
+
+		/// The index of the step in the task.
+		step: usize,
+
+		/// The asset path of the shader.
+		shader: String,
+
+		/// The entry point in the shader.
+		entry_point: String,
+
+		/// What went wrong.
+		error: String,
+	},
+}
+
+/// This resource mirrors the progress of the running compute sequence back into the main world. It's updated from the render world every time an iteration runs, so it may lag behind by a frame or two.
+#[derive(Resource, Default, Clone, Debug, PartialEq, Reflect)]
+pub struct ComputeProgress {
+	/// The index of the compute task currently running, as in, the index into the `Vec<ComputeTask>` that was provided in the [StartComputeEvent]. Once every task is done, this is equal to the number of tasks.
+	pub current_task: usize,
+
+	/// The number of iterations that have run so far in the current task.
+	pub iterations: u32,
+
+	/// The number of iterations the current task runs for in total, including any it's been extended by, or `None` if it runs forever. This is only known once the task has started.
+	pub total_iterations: Option<u32>,
+
+	/// Whether the current task is waiting for its [start_after](ComputeTask::start_after) delay to be up, so it hasn't run any iterations yet.
+	pub pending: bool,
+
+	/// The [seed](ComputeGlobals::seed) the next iteration of the current task gets in the globals buffer, after the [iterations](ComputeProgress::iterations) that have run so far.
+	pub seed: u32,
+
+	/// How long an iteration of the current task takes, in wall time, as the median over its last few frames, so a hitch doesn't throw it off. This is `None` until the task has run for a few frames, and time spent paused or waiting for its start delay doesn't count.
+	pub time_per_iteration: Option<Duration>,
+
+	/// How long until the current task is done, at its [time_per_iteration](ComputeProgress::time_per_iteration), or `None` if that isn't known yet, or the task runs forever.
+	pub task_eta: Option<Duration>,
+
+	/// How long until the whole compute sequence is done, or `None` if the [task_eta](ComputeProgress::task_eta) isn't known, or a later task runs forever. The tasks after the current one are assumed to take as long per iteration as it does, and their [start_after](ComputeTask::start_after) delays aren't counted, so this is only a rough estimate until the last task is running.
+	pub sequence_eta: Option<Duration>,
+}
+
+impl ComputeProgress {
+	/// The number of iterations the current task has left to run, or `None` if it runs forever, or hasn't started yet.
+	pub fn remaining_iterations(&self) -> Option<u32> {
+		self.total_iterations.map(|total| total.saturating_sub(self.iterations))
+	}
+}
+
+/// This resource reflects, in the main world, what the compute sequence is currently doing. It's updated from the render world as things happen, so it may lag behind by a frame or two.
+#[derive(Resource, Default, Clone, Debug, PartialEq, Eq, Reflect)]
+pub enum ComputeState {
+	/// No compute sequence has been started.
+	#[default]
+	Idle,
+
+	/// A compute sequence has been started, but the shader pipelines for the current task are still being prepared, so nothing has been dispatched yet. This happens at the start of every task.
+	Preparing,
+
+	/// The compute sequence is actively iterating.
+	Running {
+		/// The indices of the tasks currently iterating, as in, indices into the `Vec<ComputeTask>` that was provided in the [StartComputeEvent].
+		active_groups: Vec<usize>,
+	},
+
+	/// The compute sequence has been paused with a [PauseComputeEvent], or the task it's on has been, with a [PauseComputeTaskEvent] or by its [watchdog](ComputeTaskWatchdogs), so nothing is dispatched until it's resumed.
+	Paused {
+		/// The indices of the tasks that were iterating when it was paused, as in [Running](ComputeState::Running).
+		active_groups: Vec<usize>,
+	},
+
+	/// Every task in the compute sequence has completed.
+	Finished,
+
+	/// The GPU adapter doesn't support compute shaders, so every [StartComputeEvent] and [RunComputeTaskEvent] is ignored. Check for this to show a "not supported" screen, rather than waiting for results that will never come.
+	Unsupported,
+}
+
+impl ComputeState {
+	/// Go from [Running](ComputeState::Running) to [Paused](ComputeState::Paused), or back, to match whether the sequence is now paused at the task it's on. Any other state is left as it is, as nothing is iterating, and the state is worked out again once something is.
+	pub(crate) fn set_paused(&mut self, paused: bool) {
+		if let ComputeState::Running { active_groups } | ComputeState::Paused { active_groups } = self {
+			let active_groups = std::mem::take(active_groups);
+			*self = if paused { ComputeState::Paused { active_groups } } else { ComputeState::Running { active_groups } };
+		}
+	}
+}
+
+/// This resource has the [ComputeState] and [ComputeProgress] of every compute sequence that's running, or has finished, by its [ComputeSequenceId], for apps that run more than one at a time. Those of the [MAIN](ComputeSequenceId::MAIN) sequence are the same as the [ComputeState] and [ComputeProgress] resources. A sequence is left out once it's been stopped with a [StopComputeEvent], and until it's started. Like the others, it's updated from the render world, so it may lag behind by a frame or two.
+#[derive(Resource, Default, Clone, Debug)]
+pub struct ComputeSequenceStates(pub(crate) BTreeMap<ComputeSequenceId, (ComputeState, ComputeProgress)>);
+
+impl ComputeSequenceStates {
+	/// What a sequence is doing, or `None` if it hasn't been started, or has been stopped.
+	pub fn state(&self, sequence: ComputeSequenceId) -> Option<&ComputeState> {
+		self.0.get(&sequence).map(|(state, _)| state)
+	}
+
+	/// How far along a sequence is, or `None` if it hasn't been started, or has been stopped.
+	pub fn progress(&self, sequence: ComputeSequenceId) -> Option<&ComputeProgress> {
+		self.0.get(&sequence).map(|(_, progress)| progress)
+	}
+
+	/// The ids of the sequences that are running, or have finished, in order.
+	pub fn sequences(&self) -> impl Iterator<Item = ComputeSequenceId> + '_ { self.0.keys().copied() }
+
+	/// Copy the state and progress of the main sequence into the [ComputeState] and [ComputeProgress] resources, where they've changed, or clear them if it isn't running. The [Unsupported](ComputeState::Unsupported) state is left as it is.
+	pub(crate) fn mirror_main(&self, state: &mut ResMut<ComputeState>, progress: &mut ResMut<ComputeProgress>) {
+		if **state == ComputeState::Unsupported {
+			return;
+		}
+		let (main_state, main_progress) = self.0.get(&ComputeSequenceId::MAIN).cloned().unwrap_or_default();
+		state.set_if_neq(main_state);
+		progress.set_if_neq(main_progress);
+	}
+}
+
+/// This component should be placed on any entity that is intended to display a double buffered texture. If the entity has a [Sprite], a `MeshMaterial2d<ColorMaterial>`, a `MeshMaterial3d<StandardMaterial>`, or one of the [ComputeTextureMaterial]s, there is an internal system that will update the sprite's image, or the material's texture, to be the current front buffer whenever the buffers are swapped. It uses change detection, so it does nothing on frames without a swap, and only modifies a material when its texture is actually out of date.
+#[derive(Component, Clone, Copy)]
+pub struct DisplayComputeBuffer(pub ShaderBufferHandle);
@@ -0,0 +1,33 @@
+//! Everything meant to be used from the main world, which is where apps add buffers and run compute sequences. The few things meant for the render world are in [render](crate::render), along with how data gets from one to the other each frame. Both are also re-exported from the crate root.
+//!
+//! The [ShaderBufferSet] is in both worlds, but it's only changed in the main world. The render world has a copy of it, replaced whenever the main world's changes, whose methods that would change it panic in debug builds.
+
+#[cfg(feature = "inspector")]
+pub use crate::ComputeInspectorPlugin;
+pub use crate::{
+	compute_texture_2d, compute_texture_3d, dump_compute_state, encode_time_path, f16_to_f32, f32_to_f16, iteration_seed,
+	iterations_per_second_path, BevyComputePlugin, Binding, BindingNamespace, BufferOrientation, BufferReadbackEvent,
+	BufferResolver, BufferWriteQueue, ClearHalves, Colormap, ComputeAction, ComputeBufferComponent, ComputeCamera,
+	ComputeCameraMatrices, ComputeCapabilities, ComputeChunk, ComputeCursor, ComputeDebugVisualizationPlugin,
+	ComputeDebugVisualizations, ComputeDiagnosticsPlugin, ComputeErrorEvent, ComputeFiniteChecks, ComputeGlobals,
+	ComputeIterationCounts, ComputeIterationState, ComputePluginSettings, ComputeProfilingPlugin, ComputeProgress,
+	ComputeSequenceId, ComputeSequenceInfo, ComputeSequenceStates, ComputeShaderDef, ComputeShaderLibraries, ComputeSize,
+	ComputeState, ComputeStep, ComputeStepFlags, ComputeTask, ComputeTaskCompleteEvent, ComputeTaskDoneEvent,
+	ComputeTaskInfo, ComputeTaskIterations, ComputeTaskStatus, ComputeTaskStopConditions, ComputeTaskWatchdogs,
+	ComputeTextureMaterial, ComputeTextureMaterial2d, ComputeTextureMaterialPlugin, ComputeTimings, ComputeTrace,
+	ComputeValidationFinding, ComputeValidationReport, ComputeWorld, ConvolutionEdges, ConvolutionKernel, CopyBufferEvent,
+	DispatchSize, DisplayComputeBuffer, DoubleBufferImages, ExtendComputeTaskEvent, FiniteCheckType, FrameBudget,
+	HistogramOutOfRange, HistogramSource, OneShotTask, PauseComputeEvent, PauseComputeTaskEvent, PollingMode,
+	PushConstants, ReduceElement, ReduceOp, ResumeComputeEvent, RunComputeTaskEvent, ShaderBufferError,
+	ShaderBufferHandle, ShaderBufferSet, SortKey, SortKeyType, StartComputeEvent, StartDelay, StepCondition, StepSchedule,
+	StepWriteAppliedEvent, StepWriteId, StopComputeEvent, StopComputeTaskEvent, SwapStepShaderEvent, TaskIterationBuffer,
+	TaskIterationState, TaskRunMode, TaskSubmission, TextureViewSpec, TextureWriteTarget, TraceEntry, TraceEvent,
+	TraceFrame, TriggerComputeTaskEvent, UnresolvedBufferError, UpdateDispatchSizeEvent, ValidateComputeEvent,
+	ValidationSeverity, VisualizeBuffer, WriteBackToImageEvent, ALLOCATED_BYTES, BIND_GROUP_BUILD_TIME, GPU_TIME,
+	MAX_CONVOLUTION_TAPS,
+};
+#[cfg(feature = "checkpoint")]
+pub use crate::{
+	BuffersLoadedEvent, BuffersSavedEvent, CheckpointEntry, CheckpointEntryError, CheckpointEntryProblem,
+	CheckpointError, CheckpointFailedEvent, CheckpointLayout, LoadBuffersEvent, SaveBuffersEvent, CHECKPOINT_VERSION,
+};
@@ -1,26 +1,164 @@
-use bevy::prelude::*;
+use bevy::{diagnostic::DiagnosticsStore, prelude::*, utils::HashMap};
 
 use super::{
 	compute_data_transmission::{ComputeDataTransmission, ComputeMessage},
-	ComputeTaskDoneEvent, CopyBufferEvent,
+	compute_diagnostics::{record_diagnostic, record_iteration_rate, ComputeDiagnosticsEnabled, IterationSample},
+	compute_eta::{later_iterations, IterationTimer},
+	compute_profiling::ComputeTimings,
+	compute_sequence::{ComputeSequences, ComputeStepFlags, ComputeTaskIterations},
+	BufferReadbackEvent, ComputeErrorEvent, ComputeProgress, ComputeSequenceId, ComputeSequenceStates, ComputeState,
+	ComputeTaskDoneEvent, CopyBufferEvent, StepWriteAppliedEvent,
 };
 use crate::shader_buffer_set::ShaderBufferSet;
 
+#[allow(clippy::too_many_arguments)]
 pub fn parse_render_messages(
-	mut copy_buffer_events: EventWriter<CopyBufferEvent>, mut group_done_events: EventWriter<ComputeTaskDoneEvent>,
-	mut buffer_set: ResMut<ShaderBufferSet>, transmission: NonSend<ComputeDataTransmission>,
+	mut copy_buffer_events: EventWriter<CopyBufferEvent>, mut readback_events: EventWriter<BufferReadbackEvent>,
+	mut group_done_events: EventWriter<ComputeTaskDoneEvent>, mut buffer_set: ResMut<ShaderBufferSet>,
+	mut states: ResMut<ComputeSequenceStates>, mut main_state: (ResMut<ComputeProgress>, ResMut<ComputeState>),
+	mut diagnostics: Option<ResMut<DiagnosticsStore>>, mut errors: EventWriter<ComputeErrorEvent>,
+	mut sequences: ResMut<ComputeSequences>, transmission: NonSend<ComputeDataTransmission>,
+	diagnostics_enabled: Option<Res<ComputeDiagnosticsEnabled>>,
+	mut last_samples: Local<HashMap<ComputeSequenceId, IterationSample>>,
+	(task_iterations, mut step_flags): (Res<ComputeTaskIterations>, ResMut<ComputeStepFlags>),
+	mut timers: Local<HashMap<ComputeSequenceId, IterationTimer>>,
+	mut step_write_events: EventWriter<StepWriteAppliedEvent>, mut timings: Option<ResMut<ComputeTimings>>,
 ) {
-	while let Ok(data) = transmission.receiver.try_recv() {
-		match data {
-			ComputeMessage::CopyBuffer(event) => {
-				copy_buffer_events.send(event);
+	// Each sequence has a channel of its own, so its messages are only ever about it.
+	for (&id, receiver) in transmission.receivers.iter() {
+		// Neither is marked as changed unless a message changes it, as the sequences are extracted to the render world
+		// whenever they are.
+		let (Some(sequence), Some((state, progress))) =
+			(sequences.bypass_change_detection().0.get_mut(&id), states.bypass_change_detection().0.get_mut(&id))
+		else {
+			continue;
+		};
+		let mut latest_progress = None;
+		let mut received = false;
+		let mut watchdog_paused = false;
+		while let Ok(data) = receiver.try_recv() {
+			received = true;
+			match data {
+				ComputeMessage::Readback(event) => {
+					copy_buffer_events.send(CopyBufferEvent { buffer: event.handle, data: event.data.clone() });
+					readback_events.send(event);
+				}
+				ComputeMessage::GroupDone(event) => {
+					progress.current_task = event.group_finished + 1;
+					progress.iterations = 0;
+					progress.total_iterations = None;
+					progress.pending = false;
+					progress.time_per_iteration = None;
+					progress.task_eta = None;
+					progress.sequence_eta = None;
+					*state = if event.final_group { ComputeState::Finished } else { ComputeState::Preparing };
+					group_done_events.send(event);
+				}
+				ComputeMessage::SwapBuffers(handle) => {
+					buffer_set.bypass_change_detection().mirror_swap(handle);
+				}
+				ComputeMessage::RotateBuffers(handle, steps) => {
+					buffer_set.bypass_change_detection().mirror_rotation(handle, steps);
+				}
+				ComputeMessage::Progress { task, iterations, total_iterations, pending, seed } => {
+					progress.current_task = task;
+					progress.iterations = iterations;
+					progress.total_iterations = total_iterations;
+					progress.pending = pending;
+					progress.seed = seed;
+					let active_groups = vec![task];
+					*state = if sequence.paused_at(task) {
+						ComputeState::Paused { active_groups }
+					} else {
+						ComputeState::Running { active_groups }
+					};
+					latest_progress = Some((task, iterations));
+				}
+				// A one-shot flag is cleared once its steps have run, even if it's been set again since.
+				ComputeMessage::ClearStepFlag(flag) => {
+					step_flags.set(flag, false);
+				}
+				ComputeMessage::StepTimings(steps) => {
+					if let Some(timings) = &mut timings {
+						timings.steps.extend(steps);
+					}
+				}
+				ComputeMessage::Diagnostic { path, value } => {
+					if let Some(diagnostics) = &mut diagnostics {
+						record_diagnostic(diagnostics, path, value, "ms");
+					}
+				}
+				ComputeMessage::WatchdogTimeout { generation, error } => {
+					if let ComputeErrorEvent::WatchdogTimeout { task, label, iteration, steps, elapsed, limit, finished, .. } = &error
+					{
+						let task_name =
+							label.as_ref().map_or_else(|| task.to_string(), |label| format!("{} (\"{}\")", task, label));
+						let timing = if *finished { "took" } else { "has been running for" };
+						error!(
+							"Iteration {} of compute task {} {} {:?}, over its watchdog limit of {:?}, running steps {:?}. The task has been paused.",
+							iteration, task_name, timing, elapsed, limit, steps
+						);
+						// A timeout from before the sequence was started over from a task it had finished can still
+						// arrive after that, in which case there's nothing to pause.
+						if generation == sequence.generation {
+							sequence.paused[*task] = true;
+							state.set_paused(sequence.paused_at(progress.current_task));
+							watchdog_paused = true;
+						}
+					}
+					errors.send(error);
+				}
+				ComputeMessage::Error(error) => {
+					if let ComputeErrorEvent::NonFiniteValue { task, label, step, iteration, buffer, index, value, .. } = &error {
+						let task_name =
+							label.as_ref().map_or_else(|| task.to_string(), |label| format!("{} (\"{}\")", task, label));
+						error!(
+							"Step {} of compute task {} found {} at index {} of {} in iteration {}",
+							step,
+							task_name,
+							value,
+							index,
+							buffer.describe(&buffer_set),
+							iteration
+						);
+					}
+					errors.send(error);
+				}
+				ComputeMessage::StepWriteApplied(event) => {
+					step_write_events.send(event);
+				}
 			}
-			ComputeMessage::GroupDone(event) => {
-				group_done_events.send(event);
-			}
-			ComputeMessage::SwapBuffers(handle) => {
-				buffer_set.swap_front_buffer(handle);
+		}
+		// The rate and the time per iteration are only worked out once a frame, from the latest progress, as the messages
+		// of a frame arrive all at once.
+		if let Some((task, iterations)) = latest_progress {
+			if let (Some(diagnostics), Some(_)) = (&mut diagnostics, &diagnostics_enabled) {
+				let mut last_sample = last_samples.remove(&id);
+				record_iteration_rate(diagnostics, &mut last_sample, sequence, task, iterations);
+				last_samples.extend(last_sample.map(|sample| (id, sample)));
 			}
+			let waiting = progress.pending || sequence.paused_at(task);
+			progress.time_per_iteration = timers.entry(id).or_default().sample(sequence, task, iterations, waiting);
+			progress.task_eta =
+				progress.time_per_iteration.zip(progress.remaining_iterations()).map(|(time, left)| time * left);
+			progress.sequence_eta = progress
+				.task_eta
+				.zip(progress.time_per_iteration)
+				.zip(later_iterations(sequence, &task_iterations, task))
+				.map(|((eta, time), later)| eta + time * later);
+		}
+		if watchdog_paused {
+			sequences.set_changed();
 		}
+		if received {
+			states.set_changed();
+		}
+	}
+	// The timers and samples of sequences that have been stopped aren't needed any more.
+	timers.retain(|id, _| sequences.0.contains_key(id));
+	last_samples.retain(|id, _| sequences.0.contains_key(id));
+	if states.is_changed() {
+		let (progress, state) = &mut main_state;
+		states.mirror_main(state, progress);
 	}
 }
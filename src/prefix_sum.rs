@@ -0,0 +1,98 @@
+use bevy::{
+	prelude::*,
+	render::{
+		render_resource::{BufferDescriptor, BufferUsages, PipelineCache},
+		renderer::RenderDevice,
+	},
+};
+use bytemuck::{Pod, Zeroable};
+
+use super::builtin_pass::{BuiltinBinding, BuiltinDispatch, BuiltinPass};
+use crate::shader_buffer_set::{ShaderBufferHandle, ShaderBufferSet};
+
+pub(crate) const PREFIX_SUM_SHADER_HANDLE: Handle<Shader> =
+	Handle::weak_from_u128(0x91f3_0c5d_6a2e_4b87_b4d0_3e19_f85c_27a6);
+
+/// The number of elements each workgroup scans, which is two per invocation.
+const ELEMENTS_PER_WORKGROUP: u32 = 512;
+
+#[repr(C)]
+#[derive(Clone, Copy, Pod, Zeroable)]
+struct PrefixSumParams {
+	count: u32,
+	in_scratch: u32,
+	offset: u32,
+	write_sums: u32,
+	sums_offset: u32,
+}
+
+/// Check that the prefix sum of a buffer can be taken, returning what's wrong if it can't, which [prefix_sum_pass] panics with.
+pub(crate) fn check_prefix_sum(
+	buffers: &ShaderBufferSet, buffer: ShaderBufferHandle, element_count: u32,
+) -> Result<(), String> {
+	let Some(gpu_buffer) = buffers.gpu_buffer(buffer) else {
+		return Err(format!("Tried to take the prefix sum of {}, which isn't a storage buffer", buffer.describe(buffers)));
+	};
+	if element_count as u64 * 4 > gpu_buffer.size() {
+		return Err(format!(
+			"Tried to take the prefix sum of {} elements of {}, which is too small to hold them",
+			element_count,
+			buffer.describe(buffers)
+		));
+	}
+	Ok(())
+}
+
+/// Build the passes for an exclusive prefix sum of the first `element_count` `u32`s of `buffer`. The buffer is scanned in blocks of 512, with the total of each block written to a scratch buffer owned by the pass, and those totals are scanned the same way, level by level, until they fit in a single block. Then the scanned totals of each level are added back to the blocks of the level below, ending with the buffer itself, for 2 * ceil(log512(n)) - 1 dispatches in total.
+pub(crate) fn prefix_sum_pass(
+	device: &RenderDevice, pipeline_cache: &PipelineCache, buffers: &ShaderBufferSet, buffer: ShaderBufferHandle,
+	element_count: u32,
+) -> BuiltinPass {
+	if let Err(error) = check_prefix_sum(buffers, buffer, element_count) {
+		panic!("{}", error);
+	}
+
+	// Each level is the data being scanned, either the buffer or a region of the scratch buffer, with its element
+	// count, and whether its block totals go to the next level.
+	let mut levels =
+		vec![PrefixSumParams { count: element_count, in_scratch: 0, offset: 0, write_sums: 0, sums_offset: 0 }];
+	let mut scratch_words = 0;
+	loop {
+		let level = levels.last_mut().unwrap();
+		if level.count <= ELEMENTS_PER_WORKGROUP {
+			break;
+		}
+		let sums = level.count.div_ceil(ELEMENTS_PER_WORKGROUP);
+		level.write_sums = 1;
+		level.sums_offset = scratch_words;
+		levels.push(PrefixSumParams { count: sums, in_scratch: 1, offset: scratch_words, write_sums: 0, sums_offset: 0 });
+		scratch_words += sums;
+	}
+
+	let workgroups = |level: &PrefixSumParams| UVec3::new(level.count.div_ceil(ELEMENTS_PER_WORKGROUP).max(1), 1, 1);
+	let mut dispatches: Vec<_> = levels
+		.iter()
+		.map(|level| BuiltinDispatch { entry_point: 0, params: *level, workgroups: workgroups(level) })
+		.collect();
+	dispatches.extend(levels.iter().rev().skip(1).map(|level| BuiltinDispatch {
+		entry_point: 1,
+		params: *level,
+		workgroups: workgroups(level),
+	}));
+
+	let scratch = device.create_buffer(&BufferDescriptor {
+		label: Some("prefix sum scratch buffer"),
+		size: scratch_words.max(1) as u64 * 4,
+		usage: BufferUsages::STORAGE,
+		mapped_at_creation: false,
+	});
+
+	BuiltinPass::new(
+		device,
+		pipeline_cache,
+		PREFIX_SUM_SHADER_HANDLE,
+		&["scan_blocks", "add_sums"],
+		vec![BuiltinBinding::Buffer(buffer), BuiltinBinding::Scratch(scratch)],
+		dispatches,
+	)
+}
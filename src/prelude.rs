@@ -0,0 +1,23 @@
+//! Re-exports everything needed by a typical app using this crate, so `use bevy_compute::prelude::*;` is all you need.
+
+pub use bevy::render::render_resource::{BufferUsages, StorageTextureAccess, TextureFormat};
+
+pub use crate::{
+	compute_texture_2d, compute_texture_3d, BevyComputePlugin, Binding, BindingNamespace, BufferOrientation,
+	BufferReadbackEvent, BufferWriteQueue, ClearHalves, Colormap, ComputeAction, ComputeBufferComponent, ComputeCamera,
+	ComputeCameraMatrices, ComputeCapabilities, ComputeChunk, ComputeCursor, ComputeDebugVisualizationPlugin,
+	ComputeDebugVisualizations, ComputeDiagnosticsPlugin, ComputeErrorEvent, ComputeFiniteChecks, ComputeGlobals,
+	ComputeIterationCounts, ComputeIterationState, ComputePluginSettings, ComputeProfilingPlugin, ComputeProgress,
+	ComputeSequenceId, ComputeSequenceInfo, ComputeSequenceStates, ComputeShaderDef, ComputeShaderLibraries, ComputeSize,
+	ComputeState, ComputeStep, ComputeStepFlags, ComputeTask, ComputeTaskCompleteEvent, ComputeTaskDoneEvent,
+	ComputeTaskInfo, ComputeTaskIterations, ComputeTaskStatus, ComputeTaskStopConditions, ComputeTaskWatchdogs,
+	ComputeTextureMaterial, ComputeTextureMaterial2d, ComputeTextureMaterialPlugin, ComputeTimings, ComputeTrace,
+	ComputeValidationReport, ConvolutionEdges, ConvolutionKernel, CopyBufferEvent, DispatchSize, DisplayComputeBuffer,
+	DoubleBufferImages, ExtendComputeTaskEvent, FiniteCheckType, FrameBudget, HistogramOutOfRange, HistogramSource,
+	OneShotTask, PauseComputeEvent, PauseComputeTaskEvent, PollingMode, PushConstants, ReduceElement, ReduceOp,
+	ResumeComputeEvent, RunComputeTaskEvent, ShaderBufferHandle, ShaderBufferSet, SortKey, SortKeyType, StartComputeEvent,
+	StartDelay, StepCondition, StepSchedule, StepWriteAppliedEvent, StopComputeEvent, StopComputeTaskEvent,
+	SwapStepShaderEvent, TaskIterationBuffer, TaskIterationState, TaskRunMode, TaskSubmission, TextureViewSpec,
+	TextureWriteTarget, TriggerComputeTaskEvent, UpdateDispatchSizeEvent, ValidateComputeEvent, ValidationSeverity,
+	VisualizeBuffer, WriteBackToImageEvent,
+};
@@ -0,0 +1,391 @@
+use std::collections::BTreeSet;
+
+use bevy::{
+	prelude::*,
+	render::{
+		extract_resource::ExtractResource,
+		render_resource::{ShaderImport, Source},
+	},
+	utils::HashMap,
+};
+
+use super::{
+	compute_sequence::{ComputeAction, ComputeSequences, ComputeShaderDef, DispatchSize},
+	ComputeErrorEvent,
+};
+
+type OverrideKey = (String, Vec<(String, u64)>);
+
+type WorkgroupSizeKey = (OverrideKey, String, Vec<ComputeShaderDef>);
+
+/// An entry point a step runs, with the shader it's in, its shader defs, its override values and its dispatch size.
+type EntryPoint<'a> = (&'a str, &'a str, &'a [ComputeShaderDef], &'a [(String, f64)], DispatchSize);
+
+/// Every entry point a step runs, which is one for a shader step, one for each pass of a [RunShaderMulti](ComputeAction::RunShaderMulti) step, and none for anything else.
+pub(crate) fn entry_points(action: &ComputeAction) -> Vec<EntryPoint<'_>> {
+	match action {
+		ComputeAction::RunShader { shader, entry_point, shader_defs, overrides, dispatch, .. }
+		| ComputeAction::RunShaderPerEntity { shader, entry_point, shader_defs, overrides, dispatch } => {
+			vec![(shader, entry_point, shader_defs, overrides, *dispatch)]
+		}
+		// The workgroup counts of an indirect dispatch are read from the GPU, so its workgroup size isn't needed.
+		ComputeAction::RunShaderIndirect { shader, entry_point, shader_defs, overrides, .. } => {
+			vec![(shader, entry_point, shader_defs, overrides, DispatchSize::Workgroups { x: 1, y: 1, z: 1 })]
+		}
+		ComputeAction::RunShaderMulti { shader, shader_defs, passes } => passes
+			.iter()
+			.map(|(entry_point, dispatch)| (shader.as_str(), entry_point.as_str(), &shader_defs[..], &[][..], *dispatch))
+			.collect(),
+		_ => Vec::new(),
+	}
+}
+
+fn override_key(shader: &str, overrides: &[(String, f64)]) -> OverrideKey {
+	(shader.to_owned(), overrides.iter().map(|(name, value)| (name.clone(), value.to_bits())).collect())
+}
+
+/// Everything about the compute sequences' shaders that has to be worked out from their source, which is only available in the main world, once they've loaded.
+///
+/// Bevy's pipeline cache has no way to pass override values to a pipeline, so they're set by making a copy of the shader with each `override` declaration given a value rewritten to a `const` with that value, one for every combination of shader and override values, so each gets a pipeline of its own.
+///
+/// The workgroup sizes are those of the entry points run with a [DispatchSize::Exact], a [DispatchSize::Chunked] or a [DispatchSize::FromTexture], read from their `@workgroup_size` attributes, after override values are set.
+///
+/// Every entry point is also checked for in its shader, as a pipeline for one that isn't there fails wgpu's validation, which can't be recovered from. Steps wait until theirs have been found.
+///
+/// The number of bind groups each shader references is read from its `@group` attributes too, with its shader defs substituted, so each sequence's pipelines are made against only the groups its own shaders use.
+#[derive(Resource, Clone, Default, ExtractResource)]
+pub(crate) struct PreparedShaders {
+	sources: HashMap<String, Handle<Shader>>,
+	shaders: HashMap<OverrideKey, Handle<Shader>>,
+	workgroup_sizes: HashMap<WorkgroupSizeKey, UVec3>,
+	entry_points: HashMap<(String, String), bool>,
+	groups_used: HashMap<(String, Vec<ComputeShaderDef>), Option<BTreeSet<u32>>>,
+}
+
+impl PreparedShaders {
+	/// The copy of the shader with the given overrides set, or `None` if it hasn't been made yet, because the original shader is still loading.
+	pub fn shader(&self, shader: &str, overrides: &[(String, f64)]) -> Option<Handle<Shader>> {
+		self.shaders.get(&override_key(shader, overrides)).cloned()
+	}
+
+	/// Whether an entry point has been found in its shader, which it hasn't if the shader is still loading, or if it isn't there.
+	pub fn entry_point_found(&self, shader: &str, entry_point: &str) -> bool {
+		self.entry_points.get(&(shader.to_owned(), entry_point.to_owned())).copied().unwrap_or(false)
+	}
+
+	/// The indices of the bind groups a shader references with the given shader defs, from its `@group` attributes, or `None` if they haven't been read yet, because the shader is still loading, or they can't be told from the shader's text, such as when it imports other shaders, in which case every group should be assumed to be used.
+	pub fn groups_used(&self, shader: &str, shader_defs: &[ComputeShaderDef]) -> Option<&BTreeSet<u32>> {
+		self.groups_used.get(&(shader.to_owned(), shader_defs.to_vec()))?.as_ref()
+	}
+
+	/// The workgroup size of an entry point, or `None` if it hasn't been read yet, because the shader is still loading.
+	pub fn workgroup_size(
+		&self, shader: &str, entry_point: &str, shader_defs: &[ComputeShaderDef], overrides: &[(String, f64)],
+	) -> Option<UVec3> {
+		let key = (override_key(shader, overrides), entry_point.to_owned(), shader_defs.to_vec());
+		self.workgroup_sizes.get(&key).copied()
+	}
+}
+
+pub(crate) fn prepare_shaders(
+	sequences: Option<Res<ComputeSequences>>, asset_server: Res<AssetServer>, mut shaders: ResMut<Assets<Shader>>,
+	mut prepared: ResMut<PreparedShaders>, mut errors: EventWriter<ComputeErrorEvent>,
+	mut shader_events: EventReader<AssetEvent<Shader>>,
+) {
+	let Some(sequences) = sequences else {
+		return;
+	};
+	// When an original is hot-reloaded, its copies are made again in place, so the pipeline cache rebuilds their
+	// pipelines, as it does for the original's, and its entry points are checked for again. The workgroup sizes are
+	// left as they were, as the steps using them have already been set up.
+	for event in shader_events.read() {
+		let AssetEvent::Modified { id } = event else {
+			continue;
+		};
+		let Some((path, _)) = prepared.sources.iter().find(|(_, source)| source.id() == *id) else {
+			continue;
+		};
+		let path = path.clone();
+		prepared.entry_points.retain(|(shader, _), _| *shader != path);
+		prepared.groups_used.retain(|(shader, _), _| *shader != path);
+		let Some(original) = shaders.get(*id).cloned() else {
+			continue;
+		};
+		let Source::Wgsl(wgsl) = &original.source else {
+			continue;
+		};
+		for ((_, overrides), handle) in prepared.shaders.iter().filter(|((shader, _), _)| *shader == path) {
+			let overrides = overrides.iter().map(|(name, bits)| (name.clone(), f64::from_bits(*bits))).collect::<Vec<_>>();
+			shaders.insert(handle, override_copy(&original, &path, set_overrides(wgsl, &overrides).0, &overrides));
+		}
+	}
+	let steps = sequences.0.values().flat_map(|sequence| sequence.tasks.iter()).flat_map(|task| task.steps.iter());
+	let runs = steps.flat_map(|step| entry_points(&step.action));
+	for (shader, entry_point, shader_defs, overrides, dispatch) in runs {
+		let key = override_key(shader, overrides);
+		let size_key = (key.clone(), entry_point.to_owned(), shader_defs.to_vec());
+		let needs_shader = !overrides.is_empty() && !prepared.shaders.contains_key(&key);
+		let needs_size = dispatch.needs_workgroup_size() && !prepared.workgroup_sizes.contains_key(&size_key);
+		let entry_key = (shader.to_owned(), entry_point.to_owned());
+		let needs_check = !prepared.entry_points.contains_key(&entry_key);
+		let groups_key = (shader.to_owned(), shader_defs.to_vec());
+		let needs_groups = !prepared.groups_used.contains_key(&groups_key);
+		if !needs_shader && !needs_size && !needs_check && !needs_groups {
+			continue;
+		}
+		// The original is only kept so it stays loaded, which doesn't need to be extracted.
+		let source = prepared
+			.bypass_change_detection()
+			.sources
+			.entry(shader.to_owned())
+			.or_insert_with(|| asset_server.load(shader))
+			.clone();
+		let Some(original) = shaders.get(&source) else {
+			continue;
+		};
+		if needs_check {
+			// Only WGSL can be checked, so other shaders are left to the pipeline cache.
+			let found = if let Source::Wgsl(wgsl) = &original.source { has_entry_point(wgsl, entry_point) } else { true };
+			if !found {
+				errors.send(ComputeErrorEvent::MissingEntryPoint {
+					shader: shader.to_owned(),
+					entry_point: entry_point.to_owned(),
+				});
+			}
+			prepared.entry_points.insert(entry_key.clone(), found);
+		}
+		if needs_groups {
+			let groups = if let Source::Wgsl(wgsl) = &original.source { groups_used(wgsl, shader_defs) } else { None };
+			prepared.groups_used.insert(groups_key, groups);
+		}
+		if !needs_shader && !needs_size {
+			continue;
+		}
+		let Source::Wgsl(wgsl) = &original.source else {
+			panic!("Tried to prepare {} for running, but it isn't a WGSL shader", shader);
+		};
+		let (wgsl, unknown) = set_overrides(wgsl, overrides);
+		if needs_size && prepared.entry_points[&entry_key] {
+			let size = workgroup_size(&wgsl, entry_point, shader_defs).unwrap_or_else(|error| {
+				panic!("Couldn't work out the workgroup size of {} in {}: {}", entry_point, shader, error)
+			});
+			prepared.workgroup_sizes.insert(size_key, size);
+		}
+		if needs_shader {
+			for name in unknown {
+				errors.send(ComputeErrorEvent::UnknownOverride { shader: shader.to_owned(), name });
+			}
+			let copy = override_copy(original, shader, wgsl, overrides);
+			let handle = shaders.add(copy);
+			prepared.shaders.insert(key, handle);
+		}
+	}
+}
+
+/// Whether a shader declares a compute entry point with the given name, as a function with a `@compute` attribute. Only the text is searched, so an entry point left out by a shader def still counts.
+pub(crate) fn has_entry_point(source: &str, entry_point: &str) -> bool {
+	let is_ident = |c: char| c.is_alphanumeric() || c == '_';
+	if entry_point.is_empty() {
+		return false;
+	}
+	let mut offset = 0;
+	while let Some(index) = source[offset..].find(entry_point) {
+		let start = offset + index;
+		offset = start + entry_point.len();
+		if source[..start].ends_with(is_ident) || source[offset..].starts_with(is_ident) {
+			continue;
+		}
+		let Some(before) = source[..start].trim_end().strip_suffix("fn") else {
+			continue;
+		};
+		if before.ends_with(is_ident) || !source[offset..].trim_start().starts_with('(') {
+			continue;
+		}
+		// The attributes of the function are everything since the end of whatever came before it.
+		let attributes = &before[before.rfind(['}', ';']).map_or(0, |i| i + 1)..];
+		if attributes.contains("@compute") {
+			return true;
+		}
+	}
+	false
+}
+
+/// The indices of the bind groups a shader references, from its `@group` attributes, which are either literals or shader defs substituted with `#{NAME}`, or `None` if they can't be told from its text, because it imports other shaders, which may reference groups of their own, or an index isn't a literal or a def that's given.
+pub(crate) fn groups_used(source: &str, shader_defs: &[ComputeShaderDef]) -> Option<BTreeSet<u32>> {
+	if source.contains("#import") {
+		return None;
+	}
+	let mut groups = BTreeSet::new();
+	for attribute in source.split("@group").skip(1) {
+		let index = attribute.trim_start().strip_prefix('(')?.split(')').next()?.trim();
+		let index = match index.strip_prefix("#{").and_then(|name| name.strip_suffix('}')) {
+			Some(name) => match shader_defs.iter().find(|def| def.name() == name)? {
+				ComputeShaderDef::UInt(_, value) => *value,
+				ComputeShaderDef::Int(_, value) => u32::try_from(*value).ok()?,
+				ComputeShaderDef::Bool(..) => return None,
+			},
+			None => index.parse().ok()?,
+		};
+		groups.insert(index);
+	}
+	Some(groups)
+}
+
+/// Make the copy of a shader with its overrides set, given its source with them rewritten, with an import path of its own, as two shaders with the same import path would replace each other in the pipeline cache.
+fn override_copy(original: &Shader, shader: &str, wgsl: String, overrides: &[(String, f64)]) -> Shader {
+	let mut copy = original.clone();
+	copy.source = Source::Wgsl(wgsl.into());
+	copy.import_path = ShaderImport::AssetPath(format!("{} with overrides {:?}", shader, overrides));
+	copy
+}
+
+/// Rewrite the declaration of every override constant given a value to a `const` with that value, returning the new source, and the names of any overrides that aren't declared in it.
+pub(crate) fn set_overrides(source: &str, overrides: &[(String, f64)]) -> (String, Vec<String>) {
+	let mut result = String::with_capacity(source.len());
+	let mut found = Vec::new();
+	let mut rest = source;
+	while let Some(declaration) = find_override(rest) {
+		let value = overrides.iter().find(|(name, _)| *name == declaration.name).map(|(_, value)| *value);
+		if let Some(value) = value {
+			result.push_str(&rest[..declaration.start]);
+			match declaration.type_name {
+				Some(type_name) => {
+					result.push_str(&format!("const {}: {} = {};", declaration.name, type_name, literal(type_name, value)))
+				}
+				None => result.push_str(&format!("const {} = {};", declaration.name, literal("", value))),
+			}
+			found.push(declaration.name.to_owned());
+		} else {
+			result.push_str(&rest[..declaration.end]);
+		}
+		rest = &rest[declaration.end..];
+	}
+	result.push_str(rest);
+	let unknown = overrides.iter().map(|(name, _)| name).filter(|name| !found.contains(name)).cloned().collect();
+	(result, unknown)
+}
+
+struct OverrideDeclaration<'a> {
+	start: usize,
+	end: usize,
+	name: &'a str,
+	type_name: Option<&'a str>,
+}
+
+/// Find the first `override` declaration, skipping comments and virtual function overrides. The declaration starts at its `@id` attribute, if it has one, and ends after its semicolon.
+fn find_override(source: &str) -> Option<OverrideDeclaration<'_>> {
+	let is_ident = |c: char| c.is_alphanumeric() || c == '_';
+	let mut offset = 0;
+	while let Some(index) = source[offset..].find("override") {
+		let start = offset + index;
+		offset = start + "override".len();
+		let line_start = source[..start].rfind('\n').map_or(0, |i| i + 1);
+		if source[line_start..start].contains("//")
+			|| source[..start].ends_with(is_ident)
+			|| source[offset..].starts_with(is_ident)
+		{
+			continue;
+		}
+		let after = source[offset..].trim_start();
+		let name_len = after.find(|c: char| !is_ident(c)).unwrap_or(after.len());
+		let name = &after[..name_len];
+		if name.is_empty() || name == "fn" {
+			continue;
+		}
+		let semicolon = offset + source[offset..].find(';')?;
+		let declaration = &source[offset..semicolon];
+		let type_name = declaration.split_once(':').map(|(_, rest)| rest.split('=').next().unwrap().trim());
+		// An @id attribute would be invalid on the const it's rewritten to.
+		let before = source[..start].trim_end();
+		let start = match before.rfind("@id") {
+			Some(id) if before.ends_with(')') && !before[id..].contains(';') => id,
+			_ => start,
+		};
+		return Some(OverrideDeclaration { start, end: semicolon + 1, name, type_name });
+	}
+	None
+}
+
+/// Format an override value as a literal of the given type. Integers are truncated, as they are when override values are converted by WebGPU. Without a type, the literal is left abstract, so it takes whatever type it's used as.
+fn literal(type_name: &str, value: f64) -> String {
+	match type_name {
+		"bool" => (value != 0.0).to_string(),
+		"i32" | "u32" => (value as i64).to_string(),
+		_ if value.fract() == 0.0 && type_name.is_empty() => (value as i64).to_string(),
+		_ => format!("{:?}", value),
+	}
+}
+
+/// Read the workgroup size of an entry point from its `@workgroup_size` attribute. Each dimension must be an integer literal, a `#{NAME}` substitution of an integer shader def, or the name of a `const` or `override` declared in the same file with one of those as its value.
+pub(crate) fn workgroup_size(
+	source: &str, entry_point: &str, shader_defs: &[ComputeShaderDef],
+) -> Result<UVec3, String> {
+	let function = find_item(source, "fn", entry_point).ok_or("the entry point isn't in the shader")?;
+	// The attribute is somewhere between the end of the previous item and the function.
+	let item_start = source[..function].rfind(['}', ';']).map_or(0, |i| i + 1);
+	let attribute = source[item_start..function].find("@workgroup_size").ok_or("it has no @workgroup_size")?;
+	let arguments = &source[item_start + attribute + "@workgroup_size".len()..function];
+	let arguments = arguments.trim_start().strip_prefix('(').and_then(|arguments| arguments.split_once(')'));
+	let Some((arguments, _)) = arguments else {
+		return Err("its @workgroup_size is malformed".to_owned());
+	};
+	let mut size = UVec3::ONE;
+	for (i, argument) in arguments.split(',').map(str::trim).filter(|argument| !argument.is_empty()).enumerate() {
+		if i > 2 {
+			return Err("its @workgroup_size has more than three dimensions".to_owned());
+		}
+		size[i] = evaluate(source, argument, shader_defs, 0)?;
+	}
+	Ok(size)
+}
+
+fn evaluate(source: &str, expression: &str, shader_defs: &[ComputeShaderDef], depth: u32) -> Result<u32, String> {
+	if depth > 8 {
+		return Err(format!("{} is defined in terms of itself", expression));
+	}
+	if let Some(name) = expression.strip_prefix("#{").and_then(|name| name.strip_suffix('}')) {
+		return match shader_defs.iter().find(|def| def.name() == name) {
+			Some(ComputeShaderDef::Int(_, value)) => Ok(*value as u32),
+			Some(ComputeShaderDef::UInt(_, value)) => Ok(*value),
+			_ => Err(format!("{} isn't an integer shader def given to the step", name)),
+		};
+	}
+	let digits = expression.trim_end_matches(['u', 'i']);
+	if let Some(hex) = digits.strip_prefix("0x").or_else(|| digits.strip_prefix("0X")) {
+		return u32::from_str_radix(hex, 16).map_err(|_| format!("{} isn't a valid integer", expression));
+	}
+	if digits.starts_with(|c: char| c.is_ascii_digit()) {
+		return digits.parse().map_err(|_| format!("{} isn't a valid integer", expression));
+	}
+	let declaration = find_item(source, "const", expression)
+		.or_else(|| find_item(source, "override", expression))
+		.ok_or_else(|| format!("{} isn't a literal, or a const or override declared in the shader", expression))?;
+	let declaration = &source[declaration..];
+	let value = declaration
+		.split_once(';')
+		.and_then(|(declaration, _)| declaration.split_once('='))
+		.map(|(_, value)| value.trim())
+		.ok_or_else(|| format!("{} has no value", expression))?;
+	evaluate(source, value, shader_defs, depth + 1)
+}
+
+/// Find where the name of an item declared with the given keyword starts, such as a function's name after `fn`.
+fn find_item(source: &str, keyword: &str, name: &str) -> Option<usize> {
+	let is_ident = |c: char| c.is_alphanumeric() || c == '_';
+	let mut offset = 0;
+	while let Some(index) = source[offset..].find(keyword) {
+		let start = offset + index;
+		offset = start + keyword.len();
+		let line_start = source[..start].rfind('\n').map_or(0, |i| i + 1);
+		if source[line_start..start].contains("//") || source[..start].ends_with(is_ident) {
+			continue;
+		}
+		let after = &source[offset..];
+		let trimmed = after.trim_start();
+		if trimmed.len() == after.len() || !trimmed.starts_with(name) || trimmed[name.len()..].starts_with(is_ident) {
+			continue;
+		}
+		return Some(offset + after.len() - trimmed.len());
+	}
+	None
+}
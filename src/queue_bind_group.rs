@@ -1,15 +1,60 @@
 use bevy::{
 	prelude::*,
-	render::{render_asset::RenderAssets, renderer::RenderDevice, texture::GpuImage},
+	render::{
+		render_asset::RenderAssets,
+		renderer::RenderDevice,
+		texture::{FallbackImage, GpuImage},
+	},
+	utils::Instant,
 };
 
-use super::compute_bind_groups::ComputeBindGroups;
-use crate::shader_buffer_set::ShaderBufferSet;
+use super::{
+	compute_bind_groups::{ComputeBindGroups, ComputePerEntityBindGroups},
+	compute_buffer_component::ExtractedComputeBuffer,
+	compute_data_transmission::{send_message, ComputeMessage},
+	compute_diagnostics::{ComputeDiagnosticsEnabled, BIND_GROUP_BUILD_TIME},
+	compute_sequence::ComputeSequences,
+};
+use crate::shader_buffer_set::{ShaderBufferRenderSet, ShaderBufferSet};
 
+#[allow(clippy::too_many_arguments)]
 pub fn queue_bind_group(
-	mut commands: Commands, buffers: Res<ShaderBufferSet>, gpu_images: Res<RenderAssets<GpuImage>>,
-	render_device: Res<RenderDevice>,
+	mut commands: Commands, buffers: Res<ShaderBufferSet>, mut render_buffers: ResMut<ShaderBufferRenderSet>,
+	gpu_images: Res<RenderAssets<GpuImage>>, fallback_image: Res<FallbackImage>, render_device: Res<RenderDevice>,
+	sequences: Res<ComputeSequences>, diagnostics: Option<Res<ComputeDiagnosticsEnabled>>,
+	per_entity_buffers: Query<&ExtractedComputeBuffer>,
 ) {
-	let bind_groups = buffers.bind_groups(&render_device, &gpu_images);
+	#[cfg(feature = "trace")]
+	let _span = info_span!("compute_bind_groups").entered();
+	let start = Instant::now();
+	// The bind groups aren't made until every texture has been uploaded with its initial contents, which can take
+	// more than one frame with a RenderAssetBytesPerFrame limit, and the compute node waits for them until then.
+	let bind_groups = buffers.textures_uploaded(&gpu_images).then(|| {
+		let layouts = render_buffers.bind_group_layouts(&buffers, &render_device, false);
+		render_buffers.prepare_texture_views(&buffers, &gpu_images);
+		let bind_groups = render_buffers.bind_groups(&buffers, &render_device, &gpu_images, &fallback_image, &layouts);
+		(layouts, bind_groups)
+	});
+	let Some((layouts, Ok(bind_groups))) = bind_groups else {
+		commands.remove_resource::<ComputeBindGroups>();
+		commands.remove_resource::<ComputePerEntityBindGroups>();
+		return;
+	};
+	render_buffers.queue_bind_groups(&buffers, &gpu_images, &layouts, &bind_groups);
 	commands.insert_resource(ComputeBindGroups(bind_groups));
+	let per_entity_bind_groups = match render_buffers.per_entity_layout(&buffers, &render_device) {
+		Some(layout) => per_entity_buffers
+			.iter()
+			.filter_map(|ExtractedComputeBuffer(handle)| {
+				buffers.per_entity_bind_group(*handle, &render_device, &gpu_images, &render_buffers, &layout)
+			})
+			.collect(),
+		None => Vec::new(),
+	};
+	commands.insert_resource(ComputePerEntityBindGroups(per_entity_bind_groups));
+	// The bind groups are shared by every sequence, so the time goes through any of them.
+	if let (Some(sender), Some(_)) = (sequences.any_sender(), diagnostics) {
+		let value = start.elapsed().as_secs_f64() * 1000.0;
+		send_message(sender, ComputeMessage::Diagnostic { path: BIND_GROUP_BUILD_TIME, value });
+	}
 }
@@ -0,0 +1,138 @@
+use std::{
+	collections::VecDeque,
+	sync::{Arc, Mutex, OnceLock},
+};
+
+use bevy::{
+	prelude::*,
+	render::{
+		render_asset::RenderAssets,
+		render_resource::{Buffer, BufferDescriptor, BufferUsages, CommandEncoder, MapMode},
+		renderer::RenderDevice,
+		texture::GpuImage,
+	},
+	utils::HashMap,
+};
+
+use crate::{
+	compute_data_transmission::{try_send_message, ComputeMessage, MessageSender},
+	shader_buffer_set::{unpadded_copy, ShaderBufferHandle, ShaderBufferSet, TextureRows},
+	BufferReadbackEvent, ComputeSequenceId,
+};
+
+/// A copy made by a [ReadBuffer](crate::ComputeAction::ReadBuffer) step, waiting to be mapped and sent to the main world.
+struct PendingRead {
+	sequence: ComputeSequenceId,
+	generation: u32,
+	handle: ShaderBufferHandle,
+	group: Option<String>,
+	iteration: u32,
+	copy: PendingCopy,
+	sender: MessageSender,
+}
+
+/// Where a read is up to. Once it's been read, its buffer goes back into the pool, and its data waits for room in the channel to the main world.
+enum PendingCopy {
+	Copied { buffer: Buffer, rows: Option<TextureRows> },
+	Mapping { buffer: Buffer, rows: Option<TextureRows>, mapped: Arc<OnceLock<bool>> },
+	Read(ComputeMessage),
+}
+
+#[derive(Default)]
+struct ReadbacksState {
+	pending: VecDeque<PendingRead>,
+	free: HashMap<u64, Vec<Buffer>>,
+}
+
+/// Reads back the buffers copied by [ReadBuffer](crate::ComputeAction::ReadBuffer) steps, in the render world. Every copy gets a buffer of its own, taken from a pool of the ones already read, so a step that runs every iteration never has to wait for its last copy to be read, or skip one. The copies are mapped once the frame has been submitted, and sent to the main world in the order they were made, as soon as they've been mapped, without waiting for the GPU. Reads are never sent by blocking on the channel to the main world, as a frame of many iterations can read more than it holds, so they wait for room in later frames instead.
+#[derive(Resource, Default)]
+pub(crate) struct ComputeReadbacks(Mutex<ReadbacksState>);
+
+impl ComputeReadbacks {
+	/// Whether any copies made in this run of a compute sequence are still waiting to be sent to the main world.
+	pub fn pending(&self, generation: u32) -> bool {
+		self.0.lock().unwrap().pending.iter().any(|read| read.generation == generation)
+	}
+
+	/// Drop the copies and the buffers they're made into, once the app is exiting.
+	pub fn release(&self) { *self.0.lock().unwrap() = ReadbacksState::default(); }
+
+	/// Copy a buffer or texture out, to be read back once the frame has been submitted. Copies from a compute sequence that's been stopped or replaced since are dropped when they're sent, along with its channel, as its handles may have been reused. A texture that hasn't been uploaded yet isn't copied.
+	#[allow(clippy::too_many_arguments)]
+	pub fn record(
+		&self, device: &RenderDevice, encoder: &mut CommandEncoder, sequence: ComputeSequenceId, generation: u32,
+		handle: ShaderBufferHandle, buffers: &ShaderBufferSet, gpu_images: &RenderAssets<GpuImage>, group: Option<String>,
+		iteration: u32, sender: &MessageSender,
+	) {
+		let mut state = self.0.lock().unwrap();
+		let copy = buffers.copy_for_readback(handle, gpu_images, encoder, |size| {
+			state.free.get_mut(&size).and_then(Vec::pop).unwrap_or_else(|| {
+				device.create_buffer(&BufferDescriptor {
+					label: None,
+					size,
+					usage: BufferUsages::MAP_READ | BufferUsages::COPY_DST,
+					mapped_at_creation: false,
+				})
+			})
+		});
+		if let Some((buffer, rows)) = copy {
+			state.pending.push_back(PendingRead {
+				sequence,
+				generation,
+				handle,
+				group,
+				iteration,
+				copy: PendingCopy::Copied { buffer, rows },
+				sender: sender.clone(),
+			});
+		}
+	}
+}
+
+/// Map the copies made this frame, as the frame's commands have just been submitted, read the ones that have been mapped since, putting their buffers back into the pool, and send as many as the channel has room for to the main world, in the order they were made.
+pub(crate) fn read_buffer_readbacks(readbacks: Res<ComputeReadbacks>) {
+	let mut state = readbacks.0.lock().unwrap();
+	let state = &mut *state;
+	for read in state.pending.iter_mut() {
+		match &read.copy {
+			PendingCopy::Copied { buffer, rows } => {
+				let mapped = Arc::new(OnceLock::new());
+				let result = mapped.clone();
+				buffer.slice(..).map_async(MapMode::Read, move |map_result| {
+					let _ = result.set(map_result.is_ok());
+				});
+				read.copy = PendingCopy::Mapping { buffer: buffer.clone(), rows: *rows, mapped };
+			}
+			PendingCopy::Mapping { buffer, rows, mapped } => {
+				let Some(&success) = mapped.get() else {
+					continue;
+				};
+				if !success {
+					panic!("Failed to map the readback buffer of {}", read.handle);
+				}
+				let data = unpadded_copy(&buffer.slice(..).get_mapped_range(), *rows);
+				buffer.unmap();
+				state.free.entry(buffer.size()).or_default().push(buffer.clone());
+				let event = BufferReadbackEvent {
+					sequence: read.sequence,
+					handle: read.handle,
+					group: read.group.take(),
+					iteration: read.iteration,
+					data,
+				};
+				read.copy = PendingCopy::Read(ComputeMessage::Readback(event));
+			}
+			PendingCopy::Read(_) => {}
+		}
+	}
+	while let Some(read) = state.pending.pop_front() {
+		let PendingCopy::Read(message) = read.copy else {
+			state.pending.push_front(read);
+			break;
+		};
+		if let Err(message) = try_send_message(&read.sender, message) {
+			state.pending.push_front(PendingRead { copy: PendingCopy::Read(message), ..read });
+			break;
+		}
+	}
+}
@@ -0,0 +1,143 @@
+use bevy::{
+	prelude::*,
+	render::{
+		render_resource::{BufferDescriptor, BufferUsages, PipelineCache},
+		renderer::RenderDevice,
+	},
+};
+use bytemuck::{Pod, Zeroable};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use super::builtin_pass::{BuiltinBinding, BuiltinDispatch, BuiltinPass};
+use crate::shader_buffer_set::{ShaderBufferHandle, ShaderBufferSet};
+
+pub(crate) const REDUCE_BUFFER_SHADER_HANDLE: Handle<Shader> =
+	Handle::weak_from_u128(0x2b9d_64e1_c07a_4a3f_8d15_e6f2_7c38_90a4);
+
+/// The number of elements each workgroup reduces to one, which is two per invocation.
+const ELEMENTS_PER_WORKGROUP: u32 = 512;
+
+/// The operation a [ReduceBuffer](crate::ComputeAction::ReduceBuffer) action combines the elements with.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum ReduceOp {
+	/// Add up every element. Integer sums wrap on overflow.
+	Sum,
+
+	/// Find the smallest element.
+	Min,
+
+	/// Find the largest element.
+	Max,
+}
+
+/// The type of the elements a [ReduceBuffer](crate::ComputeAction::ReduceBuffer) action reduces.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum ReduceElement {
+	/// The elements are `f32`s.
+	F32,
+
+	/// The elements are `u32`s.
+	U32,
+
+	/// The elements are `i32`s.
+	I32,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Pod, Zeroable)]
+struct ReduceParams {
+	count: u32,
+	op: u32,
+	element: u32,
+	from_scratch: u32,
+	in_offset: u32,
+	to_dst: u32,
+	out_offset: u32,
+}
+
+/// Check that one buffer can be reduced into another, returning what's wrong if it can't, which [reduce_buffer_pass] panics with.
+pub(crate) fn check_reduce_buffer(
+	buffers: &ShaderBufferSet, src: ShaderBufferHandle, dst: ShaderBufferHandle,
+) -> Result<(), String> {
+	if src == dst {
+		return Err(format!(
+			"Tried to reduce {} into itself, but the result must go in a different buffer",
+			src.describe(buffers)
+		));
+	}
+	if buffers.gpu_buffer(src).is_none() {
+		return Err(format!("Tried to reduce {}, which isn't a storage buffer", src.describe(buffers)));
+	}
+	let Some(dst_buffer) = buffers.gpu_buffer(dst) else {
+		return Err(format!("Tried to reduce into {}, which isn't a storage buffer", dst.describe(buffers)));
+	};
+	if dst_buffer.size() < 4 {
+		return Err(format!("Tried to reduce into {}, which is too small to hold the result", dst.describe(buffers)));
+	}
+	Ok(())
+}
+
+/// Build the passes for a parallel reduction of every element of `src` into the first element of `dst`. Each pass reduces up to 512 elements per workgroup, so there are ceil(log512(n)) passes. Every pass but the last writes its partial results to a scratch buffer owned by the pass, in one of two regions, alternating between them so no pass reads what it's writing.
+pub(crate) fn reduce_buffer_pass(
+	device: &RenderDevice, pipeline_cache: &PipelineCache, buffers: &ShaderBufferSet, src: ShaderBufferHandle,
+	dst: ShaderBufferHandle, op: ReduceOp, element: ReduceElement,
+) -> BuiltinPass {
+	if let Err(error) = check_reduce_buffer(buffers, src, dst) {
+		panic!("{}", error);
+	}
+
+	let count = (buffers.gpu_buffer(src).unwrap().size() / 4) as u32;
+	let first_outputs = count.div_ceil(ELEMENTS_PER_WORKGROUP);
+	let mut dispatches = Vec::new();
+	let mut params = ReduceParams {
+		count,
+		op: match op {
+			ReduceOp::Sum => 0,
+			ReduceOp::Min => 1,
+			ReduceOp::Max => 2,
+		},
+		element: match element {
+			ReduceElement::F32 => 0,
+			ReduceElement::U32 => 1,
+			ReduceElement::I32 => 2,
+		},
+		from_scratch: 0,
+		in_offset: 0,
+		to_dst: 0,
+		out_offset: 0,
+	};
+	loop {
+		let workgroups = params.count.div_ceil(ELEMENTS_PER_WORKGROUP).max(1);
+		params.to_dst = (workgroups == 1) as u32;
+		dispatches.push(BuiltinDispatch { entry_point: 0, params, workgroups: UVec3::new(workgroups, 1, 1) });
+		if workgroups == 1 {
+			break;
+		}
+		params.count = workgroups;
+		params.from_scratch = 1;
+		params.in_offset = params.out_offset;
+		params.out_offset = if params.out_offset == 0 { first_outputs } else { 0 };
+	}
+
+	// The first region holds the output of the first pass, and the second the output of the
+	// second, which is the largest any later pass writes.
+	let scratch_words = first_outputs + first_outputs.div_ceil(ELEMENTS_PER_WORKGROUP);
+	let scratch = device.create_buffer(&BufferDescriptor {
+		label: Some("reduce scratch buffer"),
+		size: scratch_words.max(1) as u64 * 4,
+		usage: BufferUsages::STORAGE,
+		mapped_at_creation: false,
+	});
+
+	BuiltinPass::new(
+		device,
+		pipeline_cache,
+		REDUCE_BUFFER_SHADER_HANDLE,
+		&["reduce"],
+		vec![BuiltinBinding::Buffer(src), BuiltinBinding::Buffer(dst), BuiltinBinding::Scratch(scratch)],
+		dispatches,
+	)
+}
@@ -0,0 +1,16 @@
+//! Everything meant to be used from the render world, such as from render graph nodes, render commands and systems in the [Render](bevy::render::Render) schedule. Everything else is used from the main world, and is in [main](crate::main). Both are also re-exported from the crate root.
+//!
+//! Each frame, what the main world sets up flows through the render world in these phases, each of which guarantees what the next can rely on:
+//! - Main world: buffers are added, deleted and configured on the [ShaderBufferSet], and events such as [StartComputeEvent](crate::StartComputeEvent) change the compute sequence. Nothing reaches the GPU here but the contents of new buffers. Writes made with [write_buffer](ShaderBufferSet::write_buffer) and its relatives, or queued on the [BufferWriteQueue](crate::BufferWriteQueue), wait in that queue, which doesn't change the [ShaderBufferSet].
+//! - Extract: if the main world's [ShaderBufferSet] changed, the render world's is replaced by a copy of it, with its [world](ShaderBufferSet::world) set to [ComputeWorld::Render], and the compute sequence is copied over if it changed. The writes waiting in the [BufferWriteQueue](crate::BufferWriteQueue) are taken out of it, and the double buffers the compute steps swapped, which the main world mirrors without changing its set, are swapped on the render world's copy, if it wasn't replaced. From here until the next extract, the render world sees the buffers as they were at the end of the main world's update, however the main world changes them in the meantime. Changes made to the copy are lost, so the methods that change it panic in debug builds.
+//! - Prepare: the writes taken out of the [BufferWriteQueue](crate::BufferWriteQueue) are made, in the order they were queued, so they land before anything the frame records. Texture writes queued by [write_texture_region](ShaderBufferSet::write_texture_region) are made, once their textures are on the GPU, along with the writes of a replayed [ComputeTrace](crate::ComputeTrace). Texture views are made for buffers with a [TextureViewSpec](crate::TextureViewSpec).
+//! - Queue: the [ComputeBindGroups] are built for every group, for the frame's front and back buffers, once every texture has been uploaded with its initial contents. Until then, they don't exist, and the compute node waits. Once they do, they exist for as long as a compute sequence is running, along with the layouts from [ShaderBufferRenderSet::bind_group_layout]. The same bind groups and layouts are kept on the [ShaderBufferRenderSet], with a generation per group that only changes when a group binds something different.
+//! - Graph: the node labelled [ComputeLabel] records the frame's compute steps into the frame's command encoder. It's ordered before the camera driver, and before every node in [ComputeRenderConsumers], so anything those read was written by the steps of the same frame. Any node that isn't ordered after it may see the results of the frame before.
+//! - Cleanup: the frame has been submitted, so buffers copied out for readback are mapped, and their contents are sent back to the main world, which gets them as a [BufferReadbackEvent](crate::BufferReadbackEvent) in a later frame.
+//!
+//! The GPU objects reached from here, such as through [ShaderBufferRenderSet::wgpu_buffer] or [ShaderBufferSet::gpu_buffer], are only guaranteed to be valid for the frame they were fetched in, as the main world can delete or swap buffers at any time.
+
+pub use crate::{
+	ComputeBindGroups, ComputeLabel, ComputeRenderConsumers, ComputeWorld, ShaderBufferHandle, ShaderBufferRenderSet,
+	ShaderBufferSet,
+};
@@ -0,0 +1,222 @@
+use std::{collections::VecDeque, num::NonZeroU32};
+
+use bevy::prelude::*;
+
+use super::{
+	compute_sequence::{
+		ComputeAction, ComputeShaderDef, ComputeStep, ComputeTask, DispatchSize, StepSchedule, TaskRunMode,
+		TaskSubmission,
+	},
+	BufferReadbackEvent, ComputeSequenceId, ComputeState, ComputeTaskCompleteEvent, ComputeTaskDoneEvent,
+	RunComputeTaskEvent, StartComputeEvent, StopComputeEvent,
+};
+use crate::shader_buffer_set::{ShaderBufferHandle, ShaderBufferSet};
+
+/// A single shader dispatch, to be run exactly once with a [RunComputeTaskEvent], optionally reading a buffer back to the CPU afterwards. This is a shortcut for the common case of using the GPU as a calculator, without having to think about compute tasks and iterations.
+///
+/// Every buffer in the [ShaderBufferSet] is bound according to the [Binding](crate::Binding) it was created with, so there's no need to specify which buffers the shader uses, but the ones it relies on can be listed with [bind](OneShotTask::bind), to have them checked before it runs.
+#[derive(Clone)]
+pub struct OneShotTask {
+	shader: String,
+	entry_point: String,
+	shader_defs: Vec<ComputeShaderDef>,
+	overrides: Vec<(String, f64)>,
+	workgroups: UVec3,
+	bindings: Vec<ShaderBufferHandle>,
+	readback: Option<ShaderBufferHandle>,
+}
+
+impl OneShotTask {
+	/// Create a one-shot task that runs the given entry point of the shader at the given asset path, with the given number of workgroups in each dimension.
+	pub fn new(shader: impl Into<String>, entry_point: impl Into<String>, workgroups: UVec3) -> Self {
+		Self {
+			shader: shader.into(),
+			entry_point: entry_point.into(),
+			shader_defs: Vec::new(),
+			overrides: Vec::new(),
+			workgroups,
+			bindings: Vec::new(),
+			readback: None,
+		}
+	}
+
+	/// Preprocess the shader with the given shader def. See [ComputeShaderDef].
+	pub fn shader_def(mut self, def: impl Into<ComputeShaderDef>) -> Self {
+		self.shader_defs.push(def.into());
+		self
+	}
+
+	/// Set the value of an `override` constant declared in the shader. See [overrides](crate::ComputeAction::RunShader::overrides).
+	pub fn override_constant(mut self, name: impl Into<String>, value: f64) -> Self {
+		self.overrides.push((name.into(), value));
+		self
+	}
+
+	/// Bind the given buffer for the shader, at the group and binding it was created with. As every buffer in the set is bound anyway, this only checks, when the task is about to run, that the buffer still exists and is bound in a group, and panics if it isn't, rather than running the shader without it.
+	pub fn bind(mut self, buffer: ShaderBufferHandle) -> Self {
+		self.bindings.push(buffer);
+		self
+	}
+
+	/// Read the given storage buffer back to the CPU once the shader has run. Its contents will be provided in the [ComputeTaskCompleteEvent].
+	pub fn readback(mut self, buffer: ShaderBufferHandle) -> Self {
+		self.readback = Some(buffer);
+		self
+	}
+}
+
+/// The id of the compute sequence the first batch of one-shot tasks runs in. Each batch runs in a sequence of its own, counting up from here, so their events can't be mistaken for those of any other sequence, or of an earlier batch.
+const FIRST_ONE_SHOT_SEQUENCE: u32 = 1 << 31;
+
+struct RunningTask {
+	task_id: u64,
+	sequence: ComputeSequenceId,
+	last_task_index: usize,
+	/// The label of the compute task reading the buffer back, which is made from an id of the task's own, as the task id it was sent with needn't be unique.
+	readback_label: Option<String>,
+	data: Option<Vec<u8>>,
+}
+
+#[derive(Resource, Default)]
+pub(crate) struct OneShotTasks {
+	queue: VecDeque<(u64, OneShotTask)>,
+	running: Option<Vec<RunningTask>>,
+	batches: u32,
+	/// The id the next task to run gets, which is used in its labels.
+	next_id: u64,
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn run_compute_tasks(
+	mut tasks: ResMut<OneShotTasks>, mut run_events: EventReader<RunComputeTaskEvent>,
+	mut readback_events: EventReader<BufferReadbackEvent>, mut done_events: EventReader<ComputeTaskDoneEvent>,
+	mut start_events: EventWriter<StartComputeEvent>, mut stop_events: EventWriter<StopComputeEvent>,
+	mut complete_events: EventWriter<ComputeTaskCompleteEvent>, state: Res<ComputeState>, buffers: Res<ShaderBufferSet>,
+) {
+	tasks.queue.extend(run_events.read().map(|event| (event.task_id, event.task.clone())));
+
+	let Some(running) = &mut tasks.running else {
+		// These events can only be from some other compute sequence.
+		readback_events.clear();
+		done_events.clear();
+		start_queued_tasks(&mut tasks, &mut start_events, &state, &buffers);
+		return;
+	};
+
+	for event in readback_events.read() {
+		if let Some(task) = running.iter_mut().find(|task| {
+			task.sequence == event.sequence && task.readback_label.is_some() && task.readback_label == event.group
+		}) {
+			task.data = Some(event.data.clone());
+		}
+	}
+	let mut finished = None;
+	for event in done_events.read() {
+		let Some(task) = running
+			.iter_mut()
+			.find(|task| task.sequence == event.sequence && task.last_task_index == event.group_finished)
+		else {
+			continue;
+		};
+		complete_events.send(ComputeTaskCompleteEvent { task_id: task.task_id, data: task.data.take() });
+		if event.final_group {
+			finished = Some(event.sequence);
+		}
+	}
+	if let Some(sequence) = finished {
+		// The batch's sequence is done with, so it's stopped, rather than being left in the ComputeSequenceStates.
+		stop_events.send(StopComputeEvent(sequence));
+		tasks.running = None;
+	}
+}
+
+fn start_queued_tasks(
+	tasks: &mut OneShotTasks, start_events: &mut EventWriter<StartComputeEvent>, state: &ComputeState,
+	buffers: &ShaderBufferSet,
+) {
+	// Only start the queued tasks when the main compute sequence isn't running, so
+	// as not to change its buffers under it.
+	if tasks.queue.is_empty() || !matches!(state, ComputeState::Idle | ComputeState::Finished) {
+		return;
+	}
+
+	// All the tasks queued so far are run in a compute sequence of their own, in
+	// the order they were submitted. Each gets one task to run the shader, and if
+	// it has a readback, another which copies the buffer back to the CPU. A buffer
+	// copy takes two iterations, so it can't be part of the shader's task, or the
+	// shader would run twice.
+	let sequence = ComputeSequenceId(FIRST_ONE_SHOT_SEQUENCE + tasks.batches % FIRST_ONE_SHOT_SEQUENCE);
+	tasks.batches = tasks.batches.wrapping_add(1);
+	let mut compute_tasks = Vec::new();
+	let mut running = Vec::new();
+	for (task_id, task) in tasks.queue.drain(..) {
+		for &buffer in &task.bindings {
+			if buffers.layout_entry_for(buffer).is_none_or(|entries| entries.is_empty()) {
+				panic!(
+					"Tried to bind {} for one-shot task {}, but it doesn't exist, or isn't bound in any group",
+					buffer.describe(buffers),
+					task_id
+				);
+			}
+		}
+		let id = tasks.next_id;
+		tasks.next_id += 1;
+		compute_tasks.push(ComputeTask {
+			label: Some(format!("one-shot task {}", id)),
+			iterations: NonZeroU32::new(1),
+			run_mode: TaskRunMode::Continuous,
+			budget: None,
+			submission: TaskSubmission::WithFrame,
+			start_after: None,
+			iteration_buffer: None,
+			steps: vec![ComputeStep {
+				schedule: StepSchedule::EveryIteration,
+				run_if: None,
+				action: ComputeAction::RunShader {
+					shader: task.shader,
+					entry_point: task.entry_point,
+					shader_defs: task.shader_defs,
+					overrides: task.overrides,
+					dispatch: DispatchSize::Workgroups { x: task.workgroups.x, y: task.workgroups.y, z: task.workgroups.z },
+					auto_swap: Vec::new(),
+					skip_if_empty: None,
+					buffer_orientation: Vec::new(),
+					push_constants: None,
+					dynamic_offsets: Vec::new(),
+				},
+			}],
+		});
+		let readback_label = task.readback.map(|buffer| {
+			let label = format!("one-shot task {} readback", id);
+			compute_tasks.push(ComputeTask {
+				label: Some(label.clone()),
+				iterations: NonZeroU32::new(2),
+				run_mode: TaskRunMode::Continuous,
+				budget: None,
+				submission: TaskSubmission::WithFrame,
+				start_after: None,
+				iteration_buffer: None,
+				steps: vec![ComputeStep {
+					schedule: StepSchedule::EveryIteration,
+					run_if: None,
+					action: ComputeAction::CopyBuffer { src: buffer, offset: 0, size: None },
+				}],
+			});
+			label
+		});
+		running.push(RunningTask {
+			task_id,
+			sequence,
+			last_task_index: compute_tasks.len() - 1,
+			readback_label,
+			data: None,
+		});
+	}
+	start_events.send(StartComputeEvent {
+		sequence,
+		tasks: compute_tasks,
+		iteration_buffer: None,
+		seed: 0,
+	});
+	tasks.running = Some(running);
+}
@@ -1,6 +1,11 @@
 use std::{
 	fmt::{Display, Formatter},
-	sync::mpsc::channel,
+	num::{NonZeroU32, NonZeroU64},
+	ops::Deref,
+	sync::{
+		atomic::{AtomicU8, Ordering},
+		Arc, Mutex,
+	},
 };
 
 use bevy::{
@@ -9,55 +14,277 @@ use bevy::{
 		extract_resource::ExtractResource,
 		render_asset::{RenderAssetUsages, RenderAssets},
 		render_resource::{
-			encase::private::{WriteInto, Writer},
+			encase::{
+				self,
+				private::{CreateFrom, WriteInto, Writer},
+			},
 			BindGroup, BindGroupEntry, BindGroupLayout, BindGroupLayoutEntry, BindingResource, BindingType, Buffer,
-			BufferBindingType, BufferDescriptor, BufferInitDescriptor, BufferUsages, Extent3d, Maintain, MapMode,
-			ShaderStages, ShaderType, StorageBuffer, StorageTextureAccess, TextureDimension, TextureFormat, TextureUsages,
-			TextureViewDimension,
+			BufferBinding, BufferBindingType, BufferDescriptor, BufferId, BufferInitDescriptor, BufferUsages, CommandEncoder,
+			CommandEncoderDescriptor, DispatchIndirectArgs, DrawIndexedIndirectArgs, DrawIndirectArgs, Extent3d, FilterMode,
+			ImageCopyBuffer, ImageCopyTexture, ImageDataLayout, MapMode, Origin3d, Sampler, SamplerBindingType,
+			SamplerDescriptor, SamplerId, ShaderSize, ShaderStages, ShaderType, StorageBuffer, StorageTextureAccess, Texture,
+			TextureAspect, TextureDimension, TextureFormat, TextureId, TextureSampleType, TextureUsages, TextureView,
+			TextureViewDescriptor, TextureViewDimension, WgpuFeatures,
 		},
 		renderer::{RenderContext, RenderDevice, RenderQueue},
-		texture::GpuImage,
-		Extract, RenderApp,
+		texture::{FallbackImage, GpuImage},
+		Extract, Render, RenderApp, RenderSet,
 	},
 	utils::HashMap,
 };
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use wgpu::{COPY_BUFFER_ALIGNMENT, COPY_BYTES_PER_ROW_ALIGNMENT};
+
+use crate::{
+	buffer_write_queue::{extract_buffer_writes, make_buffer_writes, BufferWriteQueue, ExtractedBufferWrites},
+	clear_buffer::ClearHalves,
+	compute_globals::CursorUniform,
+	compute_sequence::ComputeShaderDef,
+	compute_trace::{ComputeTrace, ComputeTracer},
+	step_writes::{StepWriteId, StepWrites},
+	ComputeCameraMatrices, ComputeCapabilities, ComputeChunk, ComputeCursor, ComputeGlobals, ComputeSequenceId, ComputeSize,
+};
+
+const INDIRECT_ARGS_USAGES: BufferUsages =
+	BufferUsages::STORAGE.union(BufferUsages::INDIRECT).union(BufferUsages::COPY_SRC).union(BufferUsages::COPY_DST);
+
+/// The usages every storage buffer is given, to be bound as one, and set with [write_buffer](ShaderBufferSet::write_buffer).
+const STORAGE_USAGES: BufferUsages = BufferUsages::STORAGE.union(BufferUsages::COPY_DST);
+
+/// The usages every uniform buffer is given, to be bound as one, and set with [write_buffer](ShaderBufferSet::write_buffer).
+const UNIFORM_USAGES: BufferUsages = BufferUsages::UNIFORM.union(BufferUsages::COPY_DST);
+
+/// The access the front and back buffers of a double buffer are bound with, unless changed with [set_double_storage_access](ShaderBufferSet::set_double_storage_access) or [set_double_texture_access](ShaderBufferSet::set_double_texture_access). A half of a storage buffer with any access but [ReadOnly](StorageTextureAccess::ReadOnly) is bound read-write.
+const DEFAULT_DOUBLE_ACCESS: (StorageTextureAccess, StorageTextureAccess) =
+	(StorageTextureAccess::ReadOnly, StorageTextureAccess::WriteOnly);
+
+/// The texture views made for [TextureViewSpec]s, by the texture they view, so a view is remade whenever its image is reuploaded.
+type TextureViews = HashMap<(TextureId, TextureViewSpec), TextureView>;
+
+/// The raw wgpu texture view, which is what a texture array is bound as a slice of.
+type WgpuTextureView = <TextureView as Deref>::Target;
+
+/// What a single binding of a bind group is bound to, so two bind groups made in different frames can be compared, and tell whether they bind the same resources. Textures are known by their texture rather than their view, since views other than the ones for a [TextureViewSpec] are remade along with their texture, and a texture that hasn't been uploaded to the GPU yet is `None`.
+#[derive(Clone, PartialEq, Eq)]
+enum BoundResource {
+	Buffer(BufferId),
+	Texture(Option<TextureId>, Option<TextureViewSpec>),
+	TextureArray(Vec<Option<TextureId>>),
+	Sampler(SamplerId),
+}
+
+/// The last bind group made for a group, along with its layout, what it bound, and how many times each has changed, for [ShaderBufferRenderSet::bind_group] and the accessors next to it.
+struct QueuedGroup {
+	layout: BindGroupLayout,
+	bind_group: BindGroup,
+	resources: Vec<BoundResource>,
+	generation: u32,
+	layout_generation: u32,
+}
+
+/// Which part of a texture buffer is bound, as set with [set_texture_view](ShaderBufferSet::set_texture_view), for instance to bind a single mip level of a texture as the target of a downsample pass. The default binds the whole texture, which is the same as not setting a view at all.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct TextureViewSpec {
+	/// The first mip level in the view.
+	pub base_mip_level: u32,
+
+	/// The number of mip levels in the view, or `None` for every level from the base onwards. Storage textures can only be bound with a single mip level.
+	pub mip_level_count: Option<u32>,
+
+	/// The first array layer in the view.
+	pub base_array_layer: u32,
+
+	/// The number of array layers in the view, or `None` for every layer from the base onwards. Texture buffers added with [add_texture_fill_layers](ShaderBufferSet::add_texture_fill_layers) are bound as texture arrays, so this can be any number of their layers, but other texture buffers are bound as single textures, so it must come to a single layer.
+	pub array_layer_count: Option<u32>,
+}
+
+/// Which halves of a double buffered texture [write_texture_region](ShaderBufferSet::write_texture_region) and [write_texture_from_image](ShaderBufferSet::write_texture_from_image) write to. A single texture is always written, whichever this is.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum TextureWriteTarget {
+	/// Write to both halves, so the image is there whichever half the shaders read from next.
+	#[default]
+	Both,
+
+	/// Write to the back buffer only, as it is when the write is queued, so the front buffer being displayed is left alone until the next swap.
+	Back,
+}
+
+/// One half of a buffer, as a whole, for saving its contents to a checkpoint and loading them back.
+#[cfg(feature = "checkpoint")]
+#[derive(Clone)]
+pub(crate) enum RawStorage {
+	Buffer { buffer: Buffer, uniform: bool },
+	Texture { image: Handle<Image>, format: TextureFormat, size: Extent3d },
+}
+
+/// A write of texels into a rectangle of a texture buffer, queued by [write_texture_region](ShaderBufferSet::write_texture_region), and made in the render world once the texture is on the GPU.
+struct TextureUpload {
+	image: Handle<Image>,
+	origin: UVec2,
+	size: Extent3d,
+	bytes_per_row: u32,
+	data: Vec<u8>,
+}
 
 #[derive(Clone)]
 enum ShaderBufferStorage {
-	Storage { buffer: Buffer, readonly: bool },
+	Storage {
+		buffer: Buffer,
+		readonly: bool,
+	},
 	Uniform(Buffer),
-	StorageTexture { format: TextureFormat, access: StorageTextureAccess, image: Handle<Image> },
+	// A uniform buffer of several elements, `stride` bytes apart, one of which is bound at a time, through a dynamic offset.
+	DynamicUniform {
+		buffer: Buffer,
+		element_size: u64,
+		stride: u64,
+	},
+	StorageTexture {
+		format: TextureFormat,
+		srgb: bool,
+		access: StorageTextureAccess,
+		size: Extent3d,
+		dimension: TextureViewDimension,
+		image: Handle<Image>,
+		// Whether the set made the image, rather than it belonging to whoever added it with add_texture_from_handle.
+		owned: bool,
+	},
+	// A texture added with add_texture_from_handle whose image hasn't loaded yet, which becomes a StorageTexture, or a
+	// SampledTexture if there's no access, once it has.
+	LoadingTexture {
+		image: Handle<Image>,
+		access: Option<StorageTextureAccess>,
+	},
+	TextureArray {
+		images: Vec<Handle<Image>>,
+		sample_type: TextureSampleType,
+	},
+	SampledTexture {
+		image: Handle<Image>,
+		sample_type: TextureSampleType,
+	},
+	Sampler {
+		sampler: Sampler,
+		binding_type: SamplerBindingType,
+	},
 }
 
 impl ShaderBufferStorage {
-	fn bind_group_entry<'a>(&'a self, binding: u32, gpu_images: &'a RenderAssets<GpuImage>) -> BindGroupEntry<'a> {
-		match self {
+	fn bind_group_entry<'a>(
+		&'a self, binding: u32, gpu_images: &'a RenderAssets<GpuImage>, views: &'a TextureViews,
+		view: Option<TextureViewSpec>, array: Option<&'a [&'a WgpuTextureView]>,
+	) -> Result<BindGroupEntry<'a>, ShaderBufferError> {
+		Ok(match self {
 			ShaderBufferStorage::Storage { buffer, readonly: _ } => {
 				BindGroupEntry { binding, resource: buffer.as_entire_binding() }
 			}
 			ShaderBufferStorage::Uniform(buffer) => BindGroupEntry { binding, resource: buffer.as_entire_binding() },
-			ShaderBufferStorage::StorageTexture { image, .. } => {
-				let image = gpu_images.get(image).unwrap();
-				BindGroupEntry { binding, resource: BindingResource::TextureView(&image.texture_view) }
+			ShaderBufferStorage::DynamicUniform { buffer, element_size, .. } => BindGroupEntry {
+				binding,
+				resource: BindingResource::Buffer(BufferBinding { buffer, offset: 0, size: NonZeroU64::new(*element_size) }),
+			},
+			ShaderBufferStorage::StorageTexture { image: handle, .. }
+			| ShaderBufferStorage::SampledTexture { image: handle, .. } => {
+				let Some(image) = gpu_images.get(handle) else {
+					return Err(ShaderBufferError::ImageNotUploaded { image: handle.clone() });
+				};
+				let view = match view {
+					Some(view) => views.get(&(image.texture.id(), view)).unwrap(),
+					None => &image.texture_view,
+				};
+				BindGroupEntry { binding, resource: BindingResource::TextureView(view) }
+			}
+			ShaderBufferStorage::LoadingTexture { image, .. } => {
+				return Err(ShaderBufferError::ImageNotUploaded { image: image.clone() });
+			}
+			ShaderBufferStorage::TextureArray { .. } => {
+				let Some(array) = array else {
+					panic!("Somehow tried to bind a texture array without its texture views");
+				};
+				BindGroupEntry { binding, resource: BindingResource::TextureViewArray(array) }
+			}
+			ShaderBufferStorage::Sampler { sampler, .. } => {
+				BindGroupEntry { binding, resource: BindingResource::Sampler(sampler) }
+			}
+		})
+	}
+
+	/// What [bind_group_entry](ShaderBufferStorage::bind_group_entry) binds, without making the entry.
+	fn bound_resource(&self, gpu_images: &RenderAssets<GpuImage>, view: Option<TextureViewSpec>) -> BoundResource {
+		let texture_id = |image: &Handle<Image>| gpu_images.get(image).map(|image| image.texture.id());
+		match self {
+			ShaderBufferStorage::Storage { buffer, .. }
+			| ShaderBufferStorage::Uniform(buffer)
+			| ShaderBufferStorage::DynamicUniform { buffer, .. } => BoundResource::Buffer(buffer.id()),
+			ShaderBufferStorage::StorageTexture { image, .. }
+			| ShaderBufferStorage::SampledTexture { image, .. }
+			| ShaderBufferStorage::LoadingTexture { image, .. } => BoundResource::Texture(texture_id(image), view),
+			ShaderBufferStorage::TextureArray { images, .. } => {
+				BoundResource::TextureArray(images.iter().map(texture_id).collect())
 			}
+			ShaderBufferStorage::Sampler { sampler, .. } => BoundResource::Sampler(sampler.id()),
+		}
+	}
+
+	/// The views of every texture in a texture array, in order, or `None` if this isn't a texture array. Textures that haven't been uploaded to the GPU yet, such as ones still loading, are replaced by the fallback image until they are.
+	fn texture_array_views<'a>(
+		&self, gpu_images: &'a RenderAssets<GpuImage>, fallback: &'a FallbackImage,
+	) -> Option<Vec<&'a WgpuTextureView>> {
+		let ShaderBufferStorage::TextureArray { images, .. } = self else {
+			return None;
+		};
+		Some(
+			images
+				.iter()
+				.map(|image| &**gpu_images.get(image).map_or(&fallback.d2.texture_view, |image| &image.texture_view))
+				.collect(),
+		)
+	}
+
+	/// The number of elements in the binding, which is only set for texture arrays.
+	fn count(&self) -> Option<NonZeroU32> {
+		match self {
+			ShaderBufferStorage::TextureArray { images, .. } => NonZeroU32::new(images.len() as u32),
+			_ => None,
 		}
 	}
 
 	fn bind_group_layout_entry_binding_type(&self, access_override: Option<StorageTextureAccess>) -> BindingType {
 		match &self {
 			ShaderBufferStorage::Storage { buffer: _, readonly } => BindingType::Buffer {
-				ty: BufferBindingType::Storage { read_only: *readonly },
+				ty: BufferBindingType::Storage {
+					read_only: access_override.map_or(*readonly, |access| access == StorageTextureAccess::ReadOnly),
+				},
 				has_dynamic_offset: false,
 				min_binding_size: None,
 			},
 			ShaderBufferStorage::Uniform(_) => {
 				BindingType::Buffer { ty: BufferBindingType::Uniform, has_dynamic_offset: false, min_binding_size: None }
 			}
-			ShaderBufferStorage::StorageTexture { format, access, .. } => BindingType::StorageTexture {
+			ShaderBufferStorage::DynamicUniform { element_size, .. } => BindingType::Buffer {
+				ty: BufferBindingType::Uniform,
+				has_dynamic_offset: true,
+				min_binding_size: NonZeroU64::new(*element_size),
+			},
+			ShaderBufferStorage::StorageTexture { format, access, dimension, .. } => BindingType::StorageTexture {
 				access: access_override.unwrap_or(*access),
 				format: *format,
+				view_dimension: *dimension,
+			},
+			ShaderBufferStorage::TextureArray { sample_type, .. }
+			| ShaderBufferStorage::SampledTexture { sample_type, .. } => BindingType::Texture {
+				sample_type: *sample_type,
+				view_dimension: TextureViewDimension::D2,
+				multisampled: false,
+			},
+			// Its format isn't known until it's loaded, and nothing is run against this layout until then.
+			ShaderBufferStorage::LoadingTexture { .. } => BindingType::Texture {
+				sample_type: TextureSampleType::Float { filterable: false },
 				view_dimension: TextureViewDimension::D2,
+				multisampled: false,
 			},
+			ShaderBufferStorage::Sampler { binding_type, .. } => BindingType::Sampler(*binding_type),
 		}
 	}
 
@@ -71,7 +298,7 @@ impl ShaderBufferStorage {
 
 		if let ShaderBufferStorage::Storage { buffer, readonly: _ } = &self {
 			set_buffer(data, buffer, render_queue);
-		} else if let ShaderBufferStorage::Uniform(buffer) = &self {
+		} else if let ShaderBufferStorage::Uniform(buffer) | ShaderBufferStorage::DynamicUniform { buffer, .. } = &self {
 			set_buffer(data, buffer, render_queue);
 		} else {
 			panic!("Tried to set data on a buffer that isn't a storage or uniform buffer");
@@ -81,27 +308,141 @@ impl ShaderBufferStorage {
 	pub fn delete(&mut self, images: &mut Assets<Image>) {
 		match &self {
 			ShaderBufferStorage::Storage { buffer, .. } => buffer.destroy(),
-			ShaderBufferStorage::Uniform(buffer) => buffer.destroy(),
-			ShaderBufferStorage::StorageTexture { image, .. } => {
+			ShaderBufferStorage::Uniform(buffer) | ShaderBufferStorage::DynamicUniform { buffer, .. } => buffer.destroy(),
+			ShaderBufferStorage::StorageTexture { image, owned: true, .. } => {
 				images.remove(image);
 			}
+			// The images in a texture array, a sampled texture, or a texture added from a handle belong to whoever
+			// added it, so they're left alone.
+			ShaderBufferStorage::StorageTexture { owned: false, .. }
+			| ShaderBufferStorage::LoadingTexture { .. }
+			| ShaderBufferStorage::TextureArray { .. }
+			| ShaderBufferStorage::SampledTexture { .. } => {}
+			ShaderBufferStorage::Sampler { .. } => {}
 		}
 	}
 
 	pub fn image_handle(&self) -> Option<Handle<Image>> {
 		match self {
-			ShaderBufferStorage::StorageTexture { image, .. } => Some(image.clone()),
+			ShaderBufferStorage::StorageTexture { image, .. }
+			| ShaderBufferStorage::SampledTexture { image, .. }
+			| ShaderBufferStorage::LoadingTexture { image, .. } => Some(image.clone()),
+			_ => None,
+		}
+	}
+
+	pub fn gpu_buffer(&self) -> Option<Buffer> { self.raw_buffer().cloned() }
+
+	fn is_srgb(&self) -> bool { matches!(self, ShaderBufferStorage::StorageTexture { srgb: true, .. }) }
+
+	fn texture_format_and_size(&self) -> Option<(TextureFormat, Extent3d)> {
+		match self {
+			ShaderBufferStorage::StorageTexture { format, size, .. } => Some((*format, *size)),
+			_ => None,
+		}
+	}
+
+	fn texture_dimension(&self) -> Option<TextureViewDimension> {
+		match self {
+			ShaderBufferStorage::StorageTexture { dimension, .. } => Some(*dimension),
 			_ => None,
 		}
 	}
 
-	pub fn gpu_buffer(&self) -> Option<Buffer> {
+	fn raw_buffer(&self) -> Option<&Buffer> {
 		match self {
-			ShaderBufferStorage::Storage { buffer, .. } => Some(buffer.clone()),
-			ShaderBufferStorage::Uniform(buffer) => Some(buffer.clone()),
+			ShaderBufferStorage::Storage { buffer, .. } => Some(buffer),
+			ShaderBufferStorage::Uniform(buffer) | ShaderBufferStorage::DynamicUniform { buffer, .. } => Some(buffer),
 			_ => None,
 		}
 	}
+
+	/// The bytes this storage allocated on the GPU. The images in a texture array, a sampled texture, or a texture added from a handle belong to whoever added it, so they aren't counted.
+	fn allocated_bytes(&self) -> u64 {
+		match self {
+			ShaderBufferStorage::Storage { buffer, .. }
+			| ShaderBufferStorage::Uniform(buffer)
+			| ShaderBufferStorage::DynamicUniform { buffer, .. } => buffer.size(),
+			ShaderBufferStorage::StorageTexture { format, size, owned: true, .. } => texture_bytes(*format, *size),
+			ShaderBufferStorage::StorageTexture { owned: false, .. }
+			| ShaderBufferStorage::LoadingTexture { .. }
+			| ShaderBufferStorage::TextureArray { .. }
+			| ShaderBufferStorage::SampledTexture { .. }
+			| ShaderBufferStorage::Sampler { .. } => 0,
+		}
+	}
+}
+
+impl ShaderBufferStorage {
+	fn summary(&self) -> String {
+		match self {
+			ShaderBufferStorage::Storage { buffer, .. } => format!("storage, {}", format_bytes(buffer.size())),
+			ShaderBufferStorage::Uniform(buffer) => format!("uniform, {}", format_bytes(buffer.size())),
+			ShaderBufferStorage::DynamicUniform { buffer, stride, .. } => {
+				format!("dynamic uniform, {} elements, {}", buffer.size() / stride, format_bytes(buffer.size()))
+			}
+			ShaderBufferStorage::StorageTexture { format, srgb, size, dimension, .. } => format!(
+				"storage texture, {}, {:?}{}",
+				match dimension {
+					TextureViewDimension::D3 => format!("{}x{}x{}", size.width, size.height, size.depth_or_array_layers),
+					TextureViewDimension::D2Array => {
+						format!("{}x{}, {} layers", size.width, size.height, size.depth_or_array_layers)
+					}
+					_ => format!("{}x{}", size.width, size.height),
+				},
+				format,
+				if *srgb { " with an sRGB view" } else { "" }
+			),
+			ShaderBufferStorage::LoadingTexture { .. } => "texture, still loading".to_owned(),
+			ShaderBufferStorage::TextureArray { images, .. } => format!("texture array, {} textures", images.len()),
+			ShaderBufferStorage::SampledTexture { sample_type, .. } => format!("sampled texture, {:?}", sample_type),
+			ShaderBufferStorage::Sampler { binding_type, .. } => format!("sampler, {:?}", binding_type),
+		}
+	}
+}
+
+impl Display for ShaderBufferStorage {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		match self {
+			ShaderBufferStorage::Storage { buffer, readonly } => {
+				write!(f, "storage, {} bytes, {:?}", buffer.size(), buffer.usage())?;
+				if *readonly {
+					write!(f, ", read-only")?;
+				}
+				Ok(())
+			}
+			ShaderBufferStorage::Uniform(buffer) => write!(f, "uniform, {} bytes, {:?}", buffer.size(), buffer.usage()),
+			ShaderBufferStorage::DynamicUniform { buffer, element_size, stride } => write!(
+				f,
+				"dynamic uniform, {} elements of {} bytes, {} bytes apart, {:?}",
+				buffer.size() / stride,
+				element_size,
+				stride,
+				buffer.usage()
+			),
+			ShaderBufferStorage::StorageTexture { format, srgb, access, size, .. } => {
+				write!(
+					f,
+					"storage texture, {}x{}x{}, {:?}, {:?}",
+					size.width, size.height, size.depth_or_array_layers, format, access
+				)?;
+				if *srgb {
+					write!(f, ", sRGB view")?;
+				}
+				Ok(())
+			}
+			ShaderBufferStorage::LoadingTexture { image, access } => {
+				write!(f, "texture, {:?}, {:?}, still loading", image.id(), access)
+			}
+			ShaderBufferStorage::TextureArray { images, sample_type } => {
+				write!(f, "texture array, {} textures, {:?}", images.len(), sample_type)
+			}
+			ShaderBufferStorage::SampledTexture { image, sample_type } => {
+				write!(f, "sampled texture, {:?}, {:?}", image.id(), sample_type)
+			}
+			ShaderBufferStorage::Sampler { binding_type, .. } => write!(f, "sampler, {:?}", binding_type),
+		}
+	}
 }
 
 #[derive(Clone, Copy, PartialEq, Eq)]
@@ -115,9 +456,11 @@ enum ShaderBufferInfo {
 	SingleBound { binding: (u32, u32), storage: ShaderBufferStorage },
 	SingleUnbound { storage: ShaderBufferStorage },
 	Double { binding: (u32, (u32, u32)), front: FrontBuffer, storage: (ShaderBufferStorage, ShaderBufferStorage) },
+	PerEntity { binding: (u32, u32), storage: ShaderBufferStorage },
+	Ring { binding: (u32, u32), slot: u32, position: u32, storage: Vec<ShaderBufferStorage> },
 }
 
-/// Specifies how a given buffer will be bound to the shaders.
+/// Specifies how a given buffer will be bound to the shaders. Adding a buffer at a binding that another buffer in the group already has, including either half of a double buffer, fails with [ShaderBufferError::DuplicateBinding].
 #[derive(Clone, Copy)]
 pub enum Binding {
 	/// This will be a single buffer accessible in shaders. The first number is the group, and the second the binding.
@@ -126,29 +469,90 @@ pub enum Binding {
 	/// This buffer will not be accessible in shaders. While there are absolutely uses for unbound buffers, it's rare that it'll be useful to specify an unbound buffer at this layer.
 	SingleUnbound,
 
-	/// This will actually be two buffers, of identical size, type and format. One will the front buffer, that is read from, and the other the back buffer, that is written to. Which buffers is which can be swapped with the [SwapBuffers](crate::ComputeAction::SwapBuffers) compute action. The first number is the group they will be both be bound in, and the second tuple is the bindings of the front and back buffers, respectively. If this binding is used for a texture buffer, then the front buffer will always be `ReadOnly` and the back buffer `WriteOnly`, overriding the provided access specifier.
+	/// This will actually be two buffers, of identical size, type and format. One will the front buffer, that is read from, and the other the back buffer, that is written to. Which buffers is which can be swapped with the [SwapBuffers](crate::ComputeAction::SwapBuffers) compute action. The first number is the group they will be both be bound in, and the second tuple is the bindings of the front and back buffers, respectively. If this binding is used for a texture buffer, then the front buffer will be `ReadOnly` and the back buffer `WriteOnly`, overriding the provided access specifier, unless changed with [set_double_texture_access](ShaderBufferSet::set_double_texture_access).
 	Double(u32, (u32, u32)),
+
+	/// This buffer belongs to a single entity, via a [ComputeBufferComponent](crate::ComputeBufferComponent), and is only bound while running a [RunShaderPerEntity](crate::ComputeAction::RunShaderPerEntity) action, which dispatches once for every such entity, with that entity's buffer bound. The first number is the group, and the second the binding. The whole group is reserved for per-entity buffers, and every per-entity buffer must use the same group and binding, and be the same kind of buffer, so they can all share one layout.
+	PerEntity(u32, u32),
+
+	/// This will actually be a ring of buffers, of identical size, type and format, such as the last few states of a multistep integrator, one of which is the current buffer. The first number is the group they will all be bound in, the second the binding of the first buffer, and the third the number of buffers, which are bound at that binding and the ones after it. Unlike the halves of a double buffer, the buffers stay at their bindings. A [RotateBuffers](crate::ComputeAction::RotateBuffers) step moves the ring's position on to the next buffer instead, wrapping around to the first after the last, and shaders find the position in the [ring_positions](crate::ComputeGlobals::ring_positions) of the [globals](ShaderBufferSet::add_globals), at the ring's [slot](ShaderBufferSet::ring_slot). Only storage and uniform buffers can be rings, and there can only be [RING_SLOTS](ShaderBufferSet::RING_SLOTS) of them at once.
+	Ring(u32, u32, u32),
+}
+
+/// A range of bind groups reserved with [add_namespace](ShaderBufferSet::add_namespace), so a plugin can number its groups from zero, without clashing with the groups of other plugins using the same [ShaderBufferSet]. Its buffers are added with [binding](BindingNamespace::binding), which moves a binding from the namespace's own groups to the ones it has reserved, and its tasks are run with [with_namespace](crate::ComputeTask::with_namespace), which tells its shaders where those groups ended up, with a shader def `GROUP_N` for each group `N` of the namespace. The shaders declare their bindings with `@group(#{GROUP_0})` in place of `@group(0)`, and so on.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BindingNamespace {
+	name: String,
+	first_group: u32,
+	groups: u32,
+}
+
+impl BindingNamespace {
+	/// The name the namespace was added with.
+	pub fn name(&self) -> &str { &self.name }
+
+	/// The bind group a group of the namespace is bound in. Panics if the namespace doesn't have that many groups.
+	pub fn group(&self, group: u32) -> u32 {
+		if group >= self.groups {
+			panic!(
+				"Tried to use group {} of binding namespace \"{}\", which only has {} groups",
+				group, self.name, self.groups
+			);
+		}
+		self.first_group + group
+	}
+
+	/// Move a binding in the namespace's own groups to the groups it has reserved, to add a buffer with. Unbound buffers are left as they are. Panics if the binding is in a group the namespace doesn't have.
+	pub fn binding(&self, binding: Binding) -> Binding {
+		match binding {
+			Binding::SingleBound(group, binding) => Binding::SingleBound(self.group(group), binding),
+			Binding::SingleUnbound => Binding::SingleUnbound,
+			Binding::Double(group, bindings) => Binding::Double(self.group(group), bindings),
+			Binding::PerEntity(group, binding) => Binding::PerEntity(self.group(group), binding),
+			Binding::Ring(group, binding, depth) => Binding::Ring(self.group(group), binding, depth),
+		}
+	}
+
+	/// The shader defs telling a shader which bind group each group of the namespace is bound in, `GROUP_0` and up, which [with_namespace](crate::ComputeTask::with_namespace) adds to every shader step of a task.
+	pub fn shader_defs(&self) -> Vec<ComputeShaderDef> {
+		(0..self.groups).map(|group| ComputeShaderDef::UInt(format!("GROUP_{}", group), self.group(group))).collect()
+	}
 }
 
 impl ShaderBufferInfo {
-	fn new<F: FnMut() -> ShaderBufferStorage>(binding: Binding, mut make_storage: F) -> Self {
+	/// A buffer whose storage is made by `make_storage`, which is given the label to make the GPU resources with, which for the halves of a double buffer is `label` with the half they start out as, and for the buffers of a ring, `label` with their place in it. A ring is given its slot when it's stored.
+	fn new<F: FnMut(Option<&str>) -> ShaderBufferStorage>(
+		binding: Binding, label: Option<&str>, mut make_storage: F,
+	) -> Self {
 		match binding {
-			Binding::SingleBound(group, binding) => Self::SingleBound { binding: (group, binding), storage: make_storage() },
-			Binding::SingleUnbound => Self::SingleUnbound { storage: make_storage() },
-			Binding::Double(group, bindings) => Self::Double {
-				binding: (group, bindings),
-				front: FrontBuffer::First,
-				storage: (make_storage(), make_storage()),
+			Binding::SingleBound(group, binding) => {
+				Self::SingleBound { binding: (group, binding), storage: make_storage(label) }
+			}
+			Binding::SingleUnbound => Self::SingleUnbound { storage: make_storage(label) },
+			Binding::PerEntity(group, binding) => Self::PerEntity { binding: (group, binding), storage: make_storage(label) },
+			Binding::Double(group, bindings) => {
+				let (back, front) = double_labels(label);
+				Self::Double {
+					binding: (group, bindings),
+					front: FrontBuffer::First,
+					storage: (make_storage(back.as_deref()), make_storage(front.as_deref())),
+				}
+			}
+			Binding::Ring(group, binding, depth) => Self::Ring {
+				binding: (group, binding),
+				slot: 0,
+				position: 0,
+				storage: ring_labels(label, depth).iter().map(|label| make_storage(label.as_deref())).collect(),
 			},
 		}
 	}
 
 	fn new_storage_uninit(
-		render_device: &RenderDevice, size: u32, usage: BufferUsages, binding: Binding, readonly: bool,
+		render_device: &RenderDevice, size: u32, usage: BufferUsages, binding: Binding, readonly: bool, label: Option<&str>,
 	) -> Self {
-		Self::new(binding, || ShaderBufferStorage::Storage {
+		Self::new(binding, label, |label| ShaderBufferStorage::Storage {
 			buffer: render_device.create_buffer(&BufferDescriptor {
-				label: None,
+				label,
 				size: size as u64,
 				usage,
 				mapped_at_creation: false,
@@ -158,28 +562,32 @@ impl ShaderBufferInfo {
 	}
 
 	fn new_storage_zeroed(
-		render_device: &RenderDevice, size: u32, usage: BufferUsages, binding: Binding, readonly: bool,
+		render_device: &RenderDevice, size: u32, usage: BufferUsages, binding: Binding, readonly: bool, label: Option<&str>,
 	) -> Self {
-		Self::new(binding, || ShaderBufferStorage::Storage {
-			buffer: render_device.create_buffer_with_data(&BufferInitDescriptor {
-				label: None,
-				contents: &vec![0u8; size as usize],
-				usage,
-			}),
+		Self::new_storage_bytes(render_device, &vec![0u8; size as usize], usage, binding, readonly, label)
+	}
+
+	fn new_storage_bytes(
+		render_device: &RenderDevice, contents: &[u8], usage: BufferUsages, binding: Binding, readonly: bool,
+		label: Option<&str>,
+	) -> Self {
+		Self::new(binding, label, |label| ShaderBufferStorage::Storage {
+			buffer: render_device.create_buffer_with_data(&BufferInitDescriptor { label, contents, usage }),
 			readonly,
 		})
 	}
 
 	fn new_storage_init<T: ShaderType + WriteInto + Default + Clone>(
 		render_device: &RenderDevice, render_queue: &RenderQueue, data: T, usage: BufferUsages, binding: Binding,
-		readonly: bool,
+		readonly: bool, label: Option<&str>,
 	) -> Self {
-		Self::new(binding, || ShaderBufferStorage::Storage {
+		Self::new(binding, label, |label| ShaderBufferStorage::Storage {
 			buffer: {
 				let mut buffer = StorageBuffer::default();
 				buffer.set(data.clone());
+				buffer.set_label(label);
 				buffer.add_usages(usage);
-				buffer.write_buffer(&render_device, &render_queue);
+				buffer.write_buffer(render_device, render_queue);
 				buffer.buffer().unwrap().clone()
 			},
 			readonly,
@@ -188,56 +596,151 @@ impl ShaderBufferInfo {
 
 	fn new_uniform_init<T: ShaderType + WriteInto + Default + Clone>(
 		render_device: &RenderDevice, render_queue: &RenderQueue, data: T, usage: BufferUsages, binding: Binding,
+		label: Option<&str>,
 	) -> Self {
-		Self::new(binding, || {
+		Self::new(binding, label, |label| {
 			ShaderBufferStorage::Uniform({
 				let mut buffer = StorageBuffer::default();
 				buffer.set(data.clone());
+				buffer.set_label(label);
 				buffer.add_usages(usage);
-				buffer.write_buffer(&render_device, &render_queue);
+				buffer.write_buffer(render_device, render_queue);
 				buffer.buffer().unwrap().clone()
 			})
 		})
 	}
 
+	/// A dynamic uniform buffer of `max_elements` elements of `element_size` bytes, each starting `stride` bytes after the last, zeroed as every new buffer is.
+	fn new_uniform_dynamic(
+		render_device: &RenderDevice, element_size: u64, stride: u64, max_elements: u32, usage: BufferUsages,
+		binding: Binding, label: Option<&str>,
+	) -> Self {
+		Self::new(binding, label, |label| ShaderBufferStorage::DynamicUniform {
+			buffer: render_device.create_buffer(&BufferDescriptor {
+				label,
+				size: stride * max_elements as u64,
+				usage,
+				mapped_at_creation: false,
+			}),
+			element_size,
+			stride,
+		})
+	}
+
+	/// A texture filled with `fills.0`, and for a double buffer, its other half with `fills.1`, which are bound at the first and second bindings respectively until the first swap. The dimension is how shaders see it, with a [D3](TextureViewDimension::D3) one made as a 3D texture, and any other as a 2D texture with `size.depth_or_array_layers` layers.
+	#[allow(clippy::too_many_arguments)]
 	fn new_write_texture(
-		images: &mut Assets<Image>, width: u32, height: u32, format: TextureFormat, fill: &[u8],
-		access: StorageTextureAccess, binding: Binding,
+		images: &mut Assets<Image>, size: Extent3d, dimension: TextureViewDimension, format: TextureFormat,
+		fills: (&[u8], &[u8]), access: StorageTextureAccess, binding: Binding, label: Option<&str>,
 	) -> Self {
-		Self::new(binding, || {
-			let mut image = Image::new_fill(
-				Extent3d { width: width, height: height, depth_or_array_layers: 1 },
-				TextureDimension::D2,
-				fill,
-				format,
-				RenderAssetUsages::RENDER_WORLD,
-			);
-			image.texture_descriptor.usage =
-				TextureUsages::COPY_DST | TextureUsages::STORAGE_BINDING | TextureUsages::TEXTURE_BINDING;
+		// sRGB formats can't be storage textures, so these are stored in the linear format, with the
+		// image's own view, which sprites and materials sample through, in the sRGB format.
+		let srgb = format.is_srgb();
+		let storage_format = format.remove_srgb_suffix();
+		// The first half of a double buffer made starts out bound at the second binding.
+		let mut fills = [fills.1, fills.0].into_iter();
+		Self::new(binding, label, |label| {
+			let fill = fills.next().unwrap();
+			let texture_dimension = match dimension {
+				TextureViewDimension::D3 => TextureDimension::D3,
+				_ => TextureDimension::D2,
+			};
+			let mut image = Image::new_fill(size, texture_dimension, fill, storage_format, RenderAssetUsages::RENDER_WORLD);
+			image.texture_descriptor.label = static_label(label);
+			image.texture_descriptor.usage = TextureUsages::COPY_SRC
+				| TextureUsages::COPY_DST
+				| TextureUsages::STORAGE_BINDING
+				| TextureUsages::TEXTURE_BINDING;
+			if srgb {
+				image.texture_descriptor.view_formats = srgb_view_formats(format);
+			}
+			// The view is only spelled out when it needs to be, as a texture array of one layer would otherwise be
+			// viewed as a plain 2D texture.
+			if srgb || dimension != TextureViewDimension::D2 {
+				image.texture_view_descriptor =
+					Some(TextureViewDescriptor { format: srgb.then_some(format), dimension: Some(dimension), ..default() });
+			}
 			let image = images.add(image);
-			ShaderBufferStorage::StorageTexture { format, access, image }
+			ShaderBufferStorage::StorageTexture { format: storage_format, srgb, access, size, dimension, image, owned: true }
 		})
 	}
 
-	fn bind_group_entries<'a>(&'a self, gpu_images: &'a RenderAssets<GpuImage>) -> Vec<BindGroupEntry<'a>> {
+	fn bind_group_entries<'a>(
+		&'a self, gpu_images: &'a RenderAssets<GpuImage>, views: &'a TextureViews, view: Option<TextureViewSpec>,
+		array: Option<&'a [&'a WgpuTextureView]>,
+	) -> Result<Vec<BindGroupEntry<'a>>, ShaderBufferError> {
 		match self {
-			Self::SingleBound { binding: (_, binding), storage } => vec![storage.bind_group_entry(*binding, gpu_images)],
-			Self::SingleUnbound { .. } => vec![],
+			Self::SingleBound { binding: (_, binding), storage } | Self::PerEntity { binding: (_, binding), storage } => {
+				Ok(vec![storage.bind_group_entry(*binding, gpu_images, views, view, array)?])
+			}
+			Self::SingleUnbound { .. } => Ok(vec![]),
 			Self::Double { binding: (_, (binding1, binding2)), storage: (storage1, storage2), front } => {
 				let (storage1, storage2) =
 					if *front == FrontBuffer::First { (storage2, storage1) } else { (storage1, storage2) };
-				vec![storage1.bind_group_entry(*binding1, gpu_images), storage2.bind_group_entry(*binding2, gpu_images)]
+				Ok(vec![
+					storage1.bind_group_entry(*binding1, gpu_images, views, view, None)?,
+					storage2.bind_group_entry(*binding2, gpu_images, views, view, None)?,
+				])
+			}
+			Self::Ring { binding: (_, first), storage, .. } => storage
+				.iter()
+				.zip(*first..)
+				.map(|(storage, binding)| storage.bind_group_entry(binding, gpu_images, views, view, None))
+				.collect(),
+		}
+	}
+
+	/// What [bind_group_entries](ShaderBufferInfo::bind_group_entries) binds, in the same order, without making the entries.
+	fn bound_resources(&self, gpu_images: &RenderAssets<GpuImage>, view: Option<TextureViewSpec>) -> Vec<BoundResource> {
+		match self {
+			Self::SingleBound { storage, .. } | Self::PerEntity { storage, .. } => {
+				vec![storage.bound_resource(gpu_images, view)]
+			}
+			Self::SingleUnbound { .. } => vec![],
+			Self::Double { storage: (storage1, storage2), front, .. } => {
+				let (storage1, storage2) =
+					if *front == FrontBuffer::First { (storage2, storage1) } else { (storage1, storage2) };
+				vec![storage1.bound_resource(gpu_images, view), storage2.bound_resource(gpu_images, view)]
+			}
+			Self::Ring { storage, .. } => storage.iter().map(|storage| storage.bound_resource(gpu_images, view)).collect(),
+		}
+	}
+
+	/// The image handles of every texture behind this buffer, which is both halves for a double buffered texture, and none if it isn't a texture buffer, as a ring never is.
+	fn all_image_handles(&self) -> Vec<Handle<Image>> {
+		match self {
+			Self::SingleBound { storage, .. } | Self::SingleUnbound { storage } | Self::PerEntity { storage, .. } => {
+				storage.image_handle().into_iter().collect()
+			}
+			Self::Double { storage: (storage1, storage2), .. } => {
+				storage1.image_handle().into_iter().chain(storage2.image_handle()).collect()
+			}
+			Self::Ring { .. } => Vec::new(),
+		}
+	}
+
+	/// The storage behind this buffer, which is both halves for a double buffer, and every buffer of a ring, to change in place.
+	fn storages_mut(&mut self) -> Vec<&mut ShaderBufferStorage> {
+		match self {
+			Self::SingleBound { storage, .. } | Self::SingleUnbound { storage } | Self::PerEntity { storage, .. } => {
+				vec![storage]
 			}
+			Self::Double { storage: (storage1, storage2), .. } => vec![storage1, storage2],
+			Self::Ring { storage, .. } => storage.iter_mut().collect(),
 		}
 	}
 
-	fn bind_group_layout_entry(&self) -> Vec<BindGroupLayoutEntry> {
+	/// The layout entries of this buffer. The halves of a double buffer are bound with the access in `double_access`, which is that of the front and back buffers, whichever of the two textures or storage buffers is the front at the time, with anything but read-only binding a storage buffer read-write.
+	fn bind_group_layout_entry(
+		&self, visibility: ShaderStages, (front_access, back_access): (StorageTextureAccess, StorageTextureAccess),
+	) -> Vec<BindGroupLayoutEntry> {
 		match &self {
-			&ShaderBufferInfo::SingleBound { binding: (_, binding), storage } => vec![BindGroupLayoutEntry {
+			&ShaderBufferInfo::SingleBound { binding: (_, binding), storage }
+			| ShaderBufferInfo::PerEntity { binding: (_, binding), storage } => vec![BindGroupLayoutEntry {
 				binding: *binding,
-				visibility: ShaderStages::COMPUTE,
+				visibility,
 				ty: storage.bind_group_layout_entry_binding_type(None),
-				count: None,
+				count: storage.count(),
 			}],
 			ShaderBufferInfo::SingleUnbound { .. } => vec![],
 			ShaderBufferInfo::Double { binding: (_, (binding1, binding2)), storage: (storage1, storage2), front } => {
@@ -246,288 +749,3033 @@ impl ShaderBufferInfo {
 				vec![
 					BindGroupLayoutEntry {
 						binding: *binding1,
-						visibility: ShaderStages::COMPUTE,
-						ty: storage1.bind_group_layout_entry_binding_type(Some(StorageTextureAccess::ReadOnly)),
+						visibility,
+						ty: storage1.bind_group_layout_entry_binding_type(Some(front_access)),
 						count: None,
 					},
 					BindGroupLayoutEntry {
 						binding: *binding2,
-						visibility: ShaderStages::COMPUTE,
-						ty: storage2.bind_group_layout_entry_binding_type(Some(StorageTextureAccess::WriteOnly)),
+						visibility,
+						ty: storage2.bind_group_layout_entry_binding_type(Some(back_access)),
 						count: None,
 					},
 				]
 			}
+			ShaderBufferInfo::Ring { binding: (_, first), storage, .. } => storage
+				.iter()
+				.zip(*first..)
+				.map(|(storage, binding)| BindGroupLayoutEntry {
+					binding,
+					visibility,
+					ty: storage.bind_group_layout_entry_binding_type(None),
+					count: None,
+				})
+				.collect(),
 		}
 	}
 
-	fn image_handle(&self) -> Option<Handle<Image>> {
-		match &self {
-			ShaderBufferInfo::SingleBound { storage, .. } | ShaderBufferInfo::SingleUnbound { storage } => {
-				storage.image_handle()
+	fn image_handle(&self) -> Option<Handle<Image>> { self.front_storage().image_handle() }
+
+	/// The bindings this buffer takes up in its group, which are both halves of a double buffer, every buffer of a ring, and none for an unbound buffer.
+	fn bindings(&self) -> Vec<u32> {
+		match self {
+			ShaderBufferInfo::SingleBound { binding: (_, binding), .. }
+			| ShaderBufferInfo::PerEntity { binding: (_, binding), .. } => vec![*binding],
+			ShaderBufferInfo::SingleUnbound { .. } => Vec::new(),
+			ShaderBufferInfo::Double { binding: (_, (binding1, binding2)), .. } => vec![*binding1, *binding2],
+			ShaderBufferInfo::Ring { binding: (_, first), storage, .. } => (*first..*first + storage.len() as u32).collect(),
+		}
+	}
+
+	/// The image of the texture bound at a binding of this buffer, or `None` if it doesn't have that binding or isn't a texture.
+	fn image_at_binding(&self, at: u32) -> Option<Handle<Image>> {
+		match self {
+			ShaderBufferInfo::SingleBound { binding: (_, binding), storage }
+			| ShaderBufferInfo::PerEntity { binding: (_, binding), storage } => {
+				(*binding == at).then(|| storage.image_handle()).flatten()
+			}
+			ShaderBufferInfo::SingleUnbound { .. } | ShaderBufferInfo::Ring { .. } => None,
+			ShaderBufferInfo::Double { binding: (_, (binding1, binding2)), storage: (storage1, storage2), front } => {
+				let (storage1, storage2) =
+					if *front == FrontBuffer::First { (storage2, storage1) } else { (storage1, storage2) };
+				if at == *binding1 {
+					storage1.image_handle()
+				} else if at == *binding2 {
+					storage2.image_handle()
+				} else {
+					None
+				}
 			}
+		}
+	}
+
+	fn image_handles(&self) -> Option<DoubleBufferImages> {
+		match &self {
+			ShaderBufferInfo::SingleBound { .. }
+			| ShaderBufferInfo::SingleUnbound { .. }
+			| ShaderBufferInfo::PerEntity { .. }
+			| ShaderBufferInfo::Ring { .. } => None,
 			ShaderBufferInfo::Double { storage: (storage1, storage2), front, .. } => {
-				let storage = match front {
-					FrontBuffer::First => storage1,
-					FrontBuffer::Second => storage2,
+				let (front, back) = match front {
+					FrontBuffer::First => (storage2, storage1),
+					FrontBuffer::Second => (storage1, storage2),
 				};
-				storage.image_handle()
+				Some(DoubleBufferImages { front: front.image_handle()?, back: back.image_handle()? })
 			}
 		}
 	}
 
-	fn gpu_buffer(&self) -> Option<Buffer> {
+	/// The storage behind a buffer, which for a double buffer is the half bound to its first binding, as the front buffer, the same one [bind_group_entries](ShaderBufferInfo::bind_group_entries) binds there, and for a ring, the buffer at its position.
+	fn front_storage(&self) -> &ShaderBufferStorage {
+		match &self {
+			ShaderBufferInfo::SingleBound { storage, .. }
+			| ShaderBufferInfo::SingleUnbound { storage }
+			| ShaderBufferInfo::PerEntity { storage, .. } => storage,
+			ShaderBufferInfo::Double { storage: (storage1, storage2), front, .. } => match front {
+				FrontBuffer::First => storage2,
+				FrontBuffer::Second => storage1,
+			},
+			ShaderBufferInfo::Ring { position, storage, .. } => &storage[*position as usize],
+		}
+	}
+
+	/// The half of a double buffer that's bound to its second binding, as the back buffer, or `None` for any other buffer.
+	fn back_storage(&self) -> Option<&ShaderBufferStorage> {
 		match &self {
-			ShaderBufferInfo::SingleBound { storage, .. } | ShaderBufferInfo::SingleUnbound { storage } => {
-				storage.gpu_buffer()
-			}
 			ShaderBufferInfo::Double { storage: (storage1, storage2), front, .. } => {
-				let storage = match front {
-					FrontBuffer::First => storage1,
-					FrontBuffer::Second => storage2,
-				};
-				storage.gpu_buffer()
+				Some(if *front == FrontBuffer::First { storage1 } else { storage2 })
 			}
+			_ => None,
 		}
 	}
 
+	fn gpu_buffer(&self) -> Option<Buffer> { self.front_storage().gpu_buffer() }
+
 	fn set<T: ShaderType + WriteInto + Clone>(&self, data: T, render_queue: &RenderQueue) {
 		match &self {
 			ShaderBufferInfo::SingleBound { storage, .. } => storage.set(data, render_queue),
 			ShaderBufferInfo::SingleUnbound { storage, .. } => storage.set(data, render_queue),
+			ShaderBufferInfo::PerEntity { storage, .. } => storage.set(data, render_queue),
 			ShaderBufferInfo::Double { storage: (storage1, storage2), .. } => {
 				storage1.set(data.clone(), render_queue);
 				storage2.set(data, render_queue);
 			}
+			ShaderBufferInfo::Ring { storage, .. } => {
+				for storage in storage {
+					storage.set(data.clone(), render_queue);
+				}
+			}
 		};
 	}
 
-	pub fn delete(&mut self, images: &mut Assets<Image>) {
+	fn handle(&self, id: u32) -> ShaderBufferHandle {
+		match self {
+			ShaderBufferInfo::SingleBound { binding: (group, _), .. }
+			| ShaderBufferInfo::Double { binding: (group, _), .. }
+			| ShaderBufferInfo::PerEntity { binding: (group, _), .. }
+			| ShaderBufferInfo::Ring { binding: (group, _), .. } => ShaderBufferHandle::Bound { group: *group, id },
+			ShaderBufferInfo::SingleUnbound { .. } => ShaderBufferHandle::Unbound { id },
+		}
+	}
+
+	/// The bytes this buffer allocated on the GPU, counting both halves of a double buffer, and every buffer of a ring.
+	fn allocated_bytes(&self) -> u64 {
 		match self {
-			ShaderBufferInfo::SingleBound { storage, .. } | ShaderBufferInfo::SingleUnbound { storage } => {
-				storage.delete(images)
+			ShaderBufferInfo::SingleBound { storage, .. }
+			| ShaderBufferInfo::SingleUnbound { storage }
+			| ShaderBufferInfo::PerEntity { storage, .. } => storage.allocated_bytes(),
+			ShaderBufferInfo::Double { storage: (storage1, storage2), .. } => {
+				storage1.allocated_bytes() + storage2.allocated_bytes()
 			}
+			ShaderBufferInfo::Ring { storage, .. } => storage.iter().map(ShaderBufferStorage::allocated_bytes).sum(),
+		}
+	}
+
+	pub fn delete(&mut self, images: &mut Assets<Image>) {
+		match self {
+			ShaderBufferInfo::SingleBound { storage, .. }
+			| ShaderBufferInfo::SingleUnbound { storage }
+			| ShaderBufferInfo::PerEntity { storage, .. } => storage.delete(images),
 			ShaderBufferInfo::Double { storage: (storage1, storage2), .. } => {
 				storage1.delete(images);
 				storage2.delete(images);
 			}
+			ShaderBufferInfo::Ring { storage, .. } => {
+				for storage in storage {
+					storage.delete(images);
+				}
+			}
 		}
 	}
 }
 
-/// Provides a system for managing all the buffers used by your shaders. This gives you the functions to add buffers, delete buffers, set the contents of buffers, and for texture buffers, to extract their image handle for display.
-#[derive(Resource, Clone, ExtractResource)]
-pub struct ShaderBufferSet {
-	buffers: HashMap<u32, ShaderBufferInfo>,
-	groups: Vec<Vec<u32>>,
-	next_id: u32,
-}
-
-/// This is an opaque identifier you can store to reference a buffer again in the future.
-#[derive(Clone, Copy, Eq, PartialEq, Hash)]
-pub enum ShaderBufferHandle {
-	#[doc(hidden)]
-	Bound { group: u32, id: u32 },
-	#[doc(hidden)]
-	Unbound { id: u32 },
+impl ShaderBufferInfo {
+	fn summary(&self) -> String {
+		match self {
+			ShaderBufferInfo::SingleBound { binding: (group, binding), storage } => {
+				format!("{}, group {} binding {}", storage.summary(), group, binding)
+			}
+			ShaderBufferInfo::SingleUnbound { storage } => format!("{}, unbound", storage.summary()),
+			ShaderBufferInfo::PerEntity { binding: (group, binding), storage } => {
+				format!("{}, per-entity, group {} binding {}", storage.summary(), group, binding)
+			}
+			ShaderBufferInfo::Double { binding: (group, (binding1, binding2)), front, storage: (storage, _) } => {
+				let front = match front {
+					FrontBuffer::First => "First",
+					FrontBuffer::Second => "Second",
+				};
+				format!(
+					"{}, group {} bindings {}/{}, double-buffered front={}",
+					storage.summary(),
+					group,
+					binding1,
+					binding2,
+					front
+				)
+			}
+			ShaderBufferInfo::Ring { binding: (group, first), slot, position, storage } => format!(
+				"{}, group {} bindings {}-{}, ring slot={} position={}",
+				storage[0].summary(),
+				group,
+				first,
+				*first as usize + storage.len() - 1,
+				slot,
+				position
+			),
+		}
+	}
 }
 
-impl Display for ShaderBufferHandle {
+impl Display for ShaderBufferInfo {
 	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
 		match self {
-			ShaderBufferHandle::Bound { group, id } => {
-				write!(f, "{{ group({}), id({}) }}", group, id)
+			ShaderBufferInfo::SingleBound { binding: (group, binding), storage } => {
+				write!(f, "group {}, binding {} | {}", group, binding, storage)
+			}
+			ShaderBufferInfo::SingleUnbound { storage } => write!(f, "unbound | {}", storage),
+			ShaderBufferInfo::PerEntity { binding: (group, binding), storage } => {
+				write!(f, "group {}, binding {}, per entity | {}", group, binding, storage)
+			}
+			ShaderBufferInfo::Double { binding: (group, (binding1, binding2)), front, storage: (storage1, storage2) } => {
+				let front = match front {
+					FrontBuffer::First => "first",
+					FrontBuffer::Second => "second",
+				};
+				write!(
+					f,
+					"group {}, bindings {}/{}, double buffered, front = {} | first: {} | second: {}",
+					group, binding1, binding2, front, storage1, storage2
+				)
+			}
+			ShaderBufferInfo::Ring { binding: (group, first), slot, position, storage } => {
+				write!(
+					f,
+					"group {}, bindings {}-{}, ring, slot = {}, position = {}",
+					group,
+					first,
+					*first as usize + storage.len() - 1,
+					slot,
+					position
+				)?;
+				for (i, storage) in storage.iter().enumerate() {
+					write!(f, " | {}: {}", i, storage)?;
+				}
+				Ok(())
 			}
-			ShaderBufferHandle::Unbound { id } => write!(f, "{{ id({}) }}", id),
 		}
 	}
 }
 
-fn bind_group_layout(buffers: &Vec<&ShaderBufferInfo>, device: &RenderDevice) -> BindGroupLayout {
-	device.create_bind_group_layout(
-		None,
-		buffers.iter().flat_map(|buffer| buffer.bind_group_layout_entry()).collect::<Vec<_>>().as_slice(),
-	)
+/// The image handles of both halves of a double buffered texture, as returned by [image_handles](ShaderBufferSet::image_handles).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DoubleBufferImages {
+	/// The image handle of the current front buffer. This is the same handle [image_handle](ShaderBufferSet::image_handle) would return.
+	pub front: Handle<Image>,
+
+	/// The image handle of the current back buffer.
+	pub back: Handle<Image>,
 }
 
-impl ShaderBufferSet {
-	pub(crate) fn new() -> Self { Self { buffers: HashMap::new(), groups: Vec::new(), next_id: 0 } }
+/// Which world a [ShaderBufferSet] belongs to. The main world's is the one to change; the render world's is a copy of it, extracted every frame.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ComputeWorld {
+	/// The main world, where buffers are added, deleted and configured.
+	Main,
 
-	/// Add a new uninitialized storage buffer.
-	/// - render_device: The [RenderDevice] resouce from Bevy.
-	/// - size: The size of the buffer in bytes.
-	/// - usage: See Bevy's [BufferUsages].
-	/// - binding: How the buffer will be bound for access from the shader. See [Binding] for details. Specifying [Binding::Double] makes this a double buffer.
-	/// - readonly: If true, then this buffer can only be read in the shader, and can't be written to.
-	pub fn add_storage_uninit(
-		&mut self, render_device: &RenderDevice, size: u32, usage: BufferUsages, binding: Binding, readonly: bool,
-	) -> ShaderBufferHandle {
-		self.store_buffer(binding, ShaderBufferInfo::new_storage_uninit(render_device, size, usage, binding, readonly))
-	}
+	/// The render world, where the compute node and render graph nodes read the buffers. Its [ShaderBufferSet] is replaced by a fresh copy of the main world's in every frame the main world's changes, so changes made to it are lost.
+	Render,
+}
 
-	/// Add a new storage buffer initialized to all zero bytes.
-	/// - render_device: The [RenderDevice] resouce from Bevy.
-	/// - size: The size of the buffer in bytes.
-	/// - usage: See Bevy's [BufferUsages].
-	/// - binding: How the buffer will be bound for access from the shader. See [Binding] for details. Specifying [Binding::Double] makes this a double buffer.
-	/// - readonly: If true, then this buffer can only be read in the shader, and can't be written to.
-	pub fn add_storage_zeroed(
-		&mut self, render_device: &RenderDevice, size: u32, usage: BufferUsages, binding: Binding, readonly: bool,
-	) -> ShaderBufferHandle {
-		self.store_buffer(binding, ShaderBufferInfo::new_storage_zeroed(render_device, size, usage, binding, readonly))
-	}
+/// Provides a system for managing all the buffers used by your shaders. This gives you the functions to add buffers, delete buffers, set the contents of buffers, and for texture buffers, to extract their image handle for display.
+#[derive(Resource, Clone)]
+pub struct ShaderBufferSet {
+	buffers: HashMap<u32, ShaderBufferInfo>,
+	groups: Vec<Vec<u32>>,
+	next_id: u32,
+	names: HashMap<String, ShaderBufferHandle>,
+	buffer_names: HashMap<u32, String>,
+	labels: HashMap<u32, String>,
+	// The label given with labeled, for the next buffer added.
+	next_label: Option<String>,
+	visibilities: HashMap<u32, ShaderStages>,
+	double_access: HashMap<u32, (StorageTextureAccess, StorageTextureAccess)>,
+	texture_views: HashMap<u32, TextureViewSpec>,
+	render_inputs: Vec<ShaderBufferHandle>,
+	per_entity_layout: Option<(u32, BindGroupLayoutEntry)>,
+	namespaces: Vec<BindingNamespace>,
+	globals: Option<ShaderBufferHandle>,
+	size_uniform: Option<(ShaderBufferHandle, Option<ShaderBufferHandle>)>,
+	cursor_uniform: Option<CursorUniform>,
+	camera_uniform: Option<ShaderBufferHandle>,
+	chunk_uniform: Option<ShaderBufferHandle>,
+	// Shared with every copy extracted to the render world, which takes the uploads out as it makes them.
+	texture_uploads: Arc<Mutex<Vec<TextureUpload>>>,
+	// Shared the same way, so the render world can record and replay what the main world starts.
+	tracer: ComputeTracer,
+	// Shared the same way, so the render world can make the writes the main world queues between steps.
+	step_writes: StepWrites,
+	// Shared with the BufferWriteQueue resource, which the writes made with write_buffer go through.
+	write_queue: BufferWriteQueue,
+	pub(crate) capabilities: Option<ComputeCapabilities>,
+	memory_budget: Option<u64>,
+	world: ComputeWorld,
+	generation: u32,
+	#[cfg(feature = "inspector")]
+	pub(crate) inspectables: Vec<crate::inspector::InspectableBuffer>,
+}
 
-	/// Add a new storage buffer initialized with the provided data.
-	/// - render_device: The [RenderDevice] resouce from Bevy.
+impl ExtractResource for ShaderBufferSet {
+	type Source = ShaderBufferSet;
+
+	fn extract_resource(source: &Self::Source) -> Self { Self { world: ComputeWorld::Render, ..source.clone() } }
+}
+
+/// Why something couldn't be done with a buffer in the [ShaderBufferSet], such as adding it, or setting its contents.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ShaderBufferError {
+	/// Adding the buffer would take the bytes allocated through the set over its [memory budget](ShaderBufferSet::set_memory_budget).
+	BudgetExceeded {
+		/// The bytes the buffer would allocate, counting both halves of a double buffer.
+		requested: u64,
+
+		/// The bytes already allocated through the set.
+		used: u64,
+
+		/// The budget.
+		budget: u64,
+	},
+
+	/// The handle doesn't refer to a buffer in the set, such as after it's been [deleted](ShaderBufferSet::delete_buffer).
+	MissingBuffer {
+		/// The handle of the missing buffer.
+		buffer: ShaderBufferHandle,
+	},
+
+	/// The buffer isn't the kind the operation needs, such as setting data on a texture.
+	WrongBufferKind {
+		/// The buffer, as described by [describe](ShaderBufferHandle::describe).
+		buffer: String,
+
+		/// The kind of buffer the operation needs, such as "a storage or uniform buffer".
+		expected: &'static str,
+	},
+
+	/// The data is larger than the buffer it's written to.
+	TooLarge {
+		/// The buffer, as described by [describe](ShaderBufferHandle::describe).
+		buffer: String,
+
+		/// The bytes of data.
+		size: u64,
+
+		/// The bytes the buffer holds.
+		capacity: u64,
+	},
+
+	/// The elements run past the end of the buffer holding the array they're written to.
+	OutOfRange {
+		/// The buffer, as described by [describe](ShaderBufferHandle::describe).
+		buffer: String,
+
+		/// The index of the first element.
+		start: u32,
+
+		/// The number of elements.
+		count: usize,
+
+		/// The number of elements the buffer holds.
+		capacity: u64,
+	},
+
+	/// The bytes written at an offset run past the end of the buffer, or aren't aligned to 4 bytes, as the GPU needs both the offset and the size of a write to be.
+	BadWriteRange {
+		/// The buffer, as described by [describe](ShaderBufferHandle::describe).
+		buffer: String,
+
+		/// The offset of the write in bytes.
+		offset: u64,
+
+		/// The bytes of data.
+		size: u64,
+
+		/// The bytes the buffer holds.
+		capacity: u64,
+	},
+
+	/// A texture's image hasn't been uploaded to the GPU yet, so it can't be bound. Bevy can spread image uploads over several frames, so this usually clears up by itself.
+	ImageNotUploaded {
+		/// The image that hasn't been uploaded.
+		image: Handle<Image>,
+	},
+
+	/// The part of a storage buffer a [CopyBuffer](crate::ComputeAction::CopyBuffer) step reads back no longer fits in it, as it's been [resized](ShaderBufferSet::resize_storage) smaller since the step started.
+	BadReadRange {
+		/// The buffer, as described by [describe](ShaderBufferHandle::describe).
+		buffer: String,
+
+		/// The offset of the part in bytes.
+		offset: u64,
+
+		/// The bytes of the part.
+		size: u64,
+
+		/// The bytes the buffer holds.
+		capacity: u64,
+	},
+
+	/// The buffer has no copy buffer to read back from, as that only exists while the running task has a [CopyBuffer](crate::ComputeAction::CopyBuffer) step for it.
+	NoCopyBuffer {
+		/// The buffer, as described by [describe](ShaderBufferHandle::describe).
+		buffer: String,
+	},
+
+	/// The bytes read back from the buffer aren't the size of the type they're read as, or for an array, aren't a whole number of its elements.
+	ReadbackSizeMismatch {
+		/// The buffer, as described by [describe](ShaderBufferHandle::describe).
+		buffer: String,
+
+		/// The bytes read back.
+		size: u64,
+
+		/// The size of the type, or for an array, the stride of its elements, including the padding the memory layout rules add.
+		expected: u64,
+
+		/// Whether the bytes were read as an array.
+		array: bool,
+	},
+
+	/// A buffer is already bound at the binding a new buffer was to be added at.
+	DuplicateBinding {
+		/// The group of the binding.
+		group: u32,
+
+		/// The binding in the group.
+		binding: u32,
+
+		/// The buffer already bound there, as described by [describe](ShaderBufferHandle::describe).
+		existing: String,
+	},
+
+	/// The buffer, or the part of it asked for, can't be read back through a copy buffer, such as a double buffered storage buffer, or part of a texture.
+	Unreadable {
+		/// What's wrong, naming the buffer.
+		reason: String,
+	},
+
+	/// The buffer already has a copy buffer in the compute sequence one was to be created for.
+	DuplicateCopyBuffer {
+		/// The buffer, as described by [describe](ShaderBufferHandle::describe).
+		buffer: String,
+
+		/// The sequence the copy buffer is for.
+		sequence: ComputeSequenceId,
+	},
+
+	/// The GPU couldn't map a copy buffer to read it back. The copy is made again, and read back on a later frame.
+	MapFailed {
+		/// The buffer, as described by [describe](ShaderBufferHandle::describe).
+		buffer: String,
+	},
+
+	/// An image written into a texture buffer has a different format from it, other than an sRGB format matching its linear counterpart.
+	FormatMismatch {
+		/// The texture buffer, as described by [describe](ShaderBufferHandle::describe).
+		buffer: String,
+
+		/// The format of the image.
+		format: TextureFormat,
+
+		/// The format of the texture buffer.
+		expected: TextureFormat,
+	},
+
+	/// An image can't be written into a texture buffer, as it isn't a single 2D image, or doesn't have its pixel data in the main world.
+	UnwritableImage {
+		/// The texture buffer, as described by [describe](ShaderBufferHandle::describe).
+		buffer: String,
+
+		/// What's wrong with the image.
+		reason: String,
+	},
+
+	/// The rectangle written into a texture buffer doesn't fit inside it.
+	RegionOutOfBounds {
+		/// The texture buffer, as described by [describe](ShaderBufferHandle::describe).
+		buffer: String,
+
+		/// The top left corner of the rectangle, in texels.
+		origin: UVec2,
+
+		/// The size of the rectangle, in texels.
+		size: UVec2,
+	},
+
+	/// The data written into a rectangle of a texture buffer isn't exactly the size of its texels.
+	RegionSizeMismatch {
+		/// The texture buffer, as described by [describe](ShaderBufferHandle::describe).
+		buffer: String,
+
+		/// The size of the rectangle, in texels.
+		region: UVec2,
+
+		/// The bytes of data.
+		size: u64,
+
+		/// The bytes the rectangle's texels come to.
+		expected: u64,
+	},
+}
+
+impl Display for ShaderBufferError {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		match self {
+			ShaderBufferError::BudgetExceeded { requested, used, budget } => write!(
+				f,
+				"Tried to allocate {} for a buffer, but {} of the memory budget of {} is already used",
+				format_bytes(*requested),
+				format_bytes(*used),
+				format_bytes(*budget)
+			),
+			ShaderBufferError::MissingBuffer { buffer } => write!(f, "Tried to use {}, which doesn't exist", buffer),
+			ShaderBufferError::WrongBufferKind { buffer, expected } => {
+				write!(f, "Tried to use {} as {}, which it isn't", buffer, expected)
+			}
+			ShaderBufferError::TooLarge { buffer, size, capacity } => {
+				write!(f, "Tried to write {} bytes to {}, which is only {} bytes", size, buffer, capacity)
+			}
+			ShaderBufferError::OutOfRange { buffer, start, count, capacity } => write!(
+				f,
+				"Tried to set {} elements from index {} of {}, which only holds {} of them",
+				count, start, buffer, capacity
+			),
+			ShaderBufferError::BadWriteRange { buffer, offset, size, capacity } => write!(
+				f,
+				"Tried to write {} bytes at offset {} of {}, which is {} bytes, but a write must fit in the buffer, and its offset and size must be multiples of 4",
+				size, offset, buffer, capacity
+			),
+			ShaderBufferError::ImageNotUploaded { image } => {
+				write!(f, "Tried to bind the image {:?}, which hasn't been uploaded to the GPU yet", image.id())
+			}
+			ShaderBufferError::BadReadRange { buffer, offset, size, capacity } => write!(
+				f,
+				"Tried to read back {} bytes at offset {} of {}, which has been resized to {} bytes",
+				size, offset, buffer, capacity
+			),
+			ShaderBufferError::NoCopyBuffer { buffer } => {
+				write!(f, "Tried to read back {}, which isn't being copied by a CopyBuffer step of the running task", buffer)
+			}
+			ShaderBufferError::ReadbackSizeMismatch { buffer, size, expected, array: false } => {
+				write!(f, "Tried to read the {} bytes of {} back as a type of {} bytes", size, buffer, expected)
+			}
+			ShaderBufferError::ReadbackSizeMismatch { buffer, size, expected, array: true } => write!(
+				f,
+				"Tried to read the {} bytes of {} back as an array of elements of {} bytes, which they aren't a whole number of",
+				size, buffer, expected
+			),
+			ShaderBufferError::DuplicateBinding { group, binding, existing } => write!(
+				f,
+				"Tried to add a buffer at group {} binding {}, which {} is already bound to",
+				group, binding, existing
+			),
+			ShaderBufferError::Unreadable { reason } => write!(f, "{}", reason),
+			ShaderBufferError::DuplicateCopyBuffer { buffer, sequence } => write!(
+				f,
+				"Tried to create a copy buffer for {} in compute sequence {}, which already has one",
+				buffer, sequence.0
+			),
+			ShaderBufferError::MapFailed { buffer } => {
+				write!(f, "Failed to map the copy buffer of {} to read it back", buffer)
+			}
+			ShaderBufferError::FormatMismatch { buffer, format, expected } => write!(
+				f,
+				"Tried to write an image of format {:?} into {}, which is {:?}",
+				format, buffer, expected
+			),
+			ShaderBufferError::UnwritableImage { buffer, reason } => {
+				write!(f, "Tried to write an image into {}, but {}", buffer, reason)
+			}
+			ShaderBufferError::RegionOutOfBounds { buffer, origin, size } => {
+				write!(f, "Tried to write a {}x{} region at {} of {}, but it doesn't fit", size.x, size.y, origin, buffer)
+			}
+			ShaderBufferError::RegionSizeMismatch { buffer, region, size, expected } => write!(
+				f,
+				"Tried to write {} bytes to a {}x{} region of {}, but its texels come to {} bytes",
+				size, region.x, region.y, buffer, expected
+			),
+		}
+	}
+}
+
+impl std::error::Error for ShaderBufferError {}
+
+/// This is an opaque identifier you can store to reference a buffer again in the future. With the `serde` feature enabled, it can be serialized, as it is in a [ComputeTrace], but it only refers to the same buffer in another run if the buffers are created in the same order.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum ShaderBufferHandle {
+	#[doc(hidden)]
+	Bound { group: u32, id: u32 },
+	#[doc(hidden)]
+	Unbound { id: u32 },
+}
+
+impl Display for ShaderBufferHandle {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		match self {
+			ShaderBufferHandle::Bound { group, id } => {
+				write!(f, "{{ group({}), id({}) }}", group, id)
+			}
+			ShaderBufferHandle::Unbound { id } => write!(f, "{{ id({}) }}", id),
+		}
+	}
+}
+
+impl ShaderBufferHandle {
+	fn id(&self) -> u32 {
+		match self {
+			ShaderBufferHandle::Bound { id, .. } | ShaderBufferHandle::Unbound { id } => *id,
+		}
+	}
+
+	/// Describe this buffer in a single human-readable line, such as `"particles" (storage, 4 MiB, group 0 binding 2)`, or `{ group(0), id(3) } (storage texture, 512x512, Rgba8Unorm, group 0 bindings 0/1, double-buffered front=First)` for a buffer without a name. Unlike the [Display] implementation, which only has the handle to go on, this looks the buffer up in the provided set. If the buffer doesn't exist in the set, only the handle is printed.
+	pub fn describe(&self, buffers: &ShaderBufferSet) -> String {
+		let mut name = match buffers.buffer_name(*self) {
+			Some(name) => format!("\"{}\"", name),
+			None => self.to_string(),
+		};
+		if let Some(label) = buffers.buffer_label(*self).filter(|label| Some(*label) != buffers.buffer_name(*self)) {
+			name = format!("{} labeled \"{}\"", name, label);
+		}
+		match buffers.buffers.get(&self.id()) {
+			Some(buffer) => format!("{} ({})", name, buffer.summary()),
+			None => name,
+		}
+	}
+}
+
+/// The labels of the halves of a double buffer labeled `label`, in the order they're made, which is the back buffer and then the front buffer, as they start out.
+fn double_labels(label: Option<&str>) -> (Option<String>, Option<String>) {
+	(label.map(|label| format!("{} (back)", label)), label.map(|label| format!("{} (front)", label)))
+}
+
+/// The labels of the buffers of a ring labeled `label`, in the order they're bound.
+fn ring_labels(label: Option<&str>, depth: u32) -> Vec<Option<String>> {
+	(0..depth).map(|i| label.map(|label| format!("{} ({})", label, i))).collect()
+}
+
+/// A label for an image's texture descriptor, which only takes static labels, so it's leaked.
+fn static_label(label: Option<&str>) -> Option<&'static str> {
+	label.map(|label| &*Box::leak(label.to_owned().into_boxed_str()))
+}
+
+/// The bytes a texture of the given format and size takes up, not counting any padding the GPU adds.
+fn texture_bytes(format: TextureFormat, size: Extent3d) -> u64 {
+	let texel = format.block_copy_size(None).unwrap_or(0) as u64;
+	texel * size.width as u64 * size.height as u64 * size.depth_or_array_layers as u64
+}
+
+/// The view formats an image stored in the linear version of an sRGB format needs, for its own view to be in the sRGB format.
+fn srgb_view_formats(format: TextureFormat) -> &'static [TextureFormat] {
+	match format {
+		TextureFormat::Bgra8UnormSrgb => &[TextureFormat::Bgra8UnormSrgb],
+		_ => &[TextureFormat::Rgba8UnormSrgb],
+	}
+}
+
+/// Panic if a fill isn't exactly one pixel of the format, such as 4 bytes for an [R32Float](TextureFormat::R32Float) texture, or 8 for an [Rgba16Float](TextureFormat::Rgba16Float) one, whose channels can be made with [f32_to_f16](crate::f32_to_f16).
+fn check_texture_fill(format: TextureFormat, fill: &[u8]) {
+	let texel = format.block_copy_size(None).unwrap_or(0) as usize;
+	if fill.len() != texel {
+		panic!(
+			"Tried to add a texture of format {:?} with a fill of {} bytes, but a pixel of that format is {} bytes",
+			format,
+			fill.len(),
+			texel
+		);
+	}
+}
+
+/// Add the usages a buffer needs to be used the way it's declared to the ones it was asked for, rather than leaving wgpu to reject it once it's bound, logging the ones that were missing so it's clear what they are.
+fn required_usages(usage: BufferUsages, required: BufferUsages, kind: &str) -> BufferUsages {
+	let missing = required.difference(usage);
+	if !missing.is_empty() {
+		debug!("Added {:?} to the usages of a new {} buffer, as it needs them to be bound and set", missing, kind);
+	}
+	usage | required
+}
+
+fn format_bytes(bytes: u64) -> String {
+	const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+	if bytes < 1024 {
+		return format!("{} B", bytes);
+	}
+	let mut size = bytes as f64 / 1024.0;
+	let mut unit = 0;
+	while size >= 1024.0 && unit < UNITS.len() - 1 {
+		size /= 1024.0;
+		unit += 1;
+	}
+	if size.fract() == 0.0 {
+		format!("{} {}", size, UNITS[unit])
+	} else {
+		format!("{:.1} {}", size, UNITS[unit])
+	}
+}
+
+impl ShaderBufferSet {
+	pub(crate) fn new() -> Self {
+		Self {
+			buffers: HashMap::new(),
+			groups: Vec::new(),
+			next_id: 0,
+			names: HashMap::new(),
+			buffer_names: HashMap::new(),
+			labels: HashMap::new(),
+			next_label: None,
+			visibilities: HashMap::new(),
+			double_access: HashMap::new(),
+			texture_views: HashMap::new(),
+			render_inputs: Vec::new(),
+			per_entity_layout: None,
+			namespaces: Vec::new(),
+			globals: None,
+			size_uniform: None,
+			cursor_uniform: None,
+			camera_uniform: None,
+			chunk_uniform: None,
+			texture_uploads: Arc::new(Mutex::new(Vec::new())),
+			tracer: ComputeTracer::default(),
+			step_writes: StepWrites::default(),
+			write_queue: BufferWriteQueue::default(),
+			capabilities: None,
+			memory_budget: None,
+			world: ComputeWorld::Main,
+			generation: 0,
+			#[cfg(feature = "inspector")]
+			inspectables: Vec::new(),
+		}
+	}
+
+	/// Add a new uninitialized storage buffer.
+	/// - render_device: The [RenderDevice] resouce from Bevy.
+	/// - size: The size of the buffer in bytes.
+	/// - usage: See Bevy's [BufferUsages]. [BufferUsages::STORAGE] and [BufferUsages::COPY_DST] are always added, as the buffer can't be bound or set without them.
+	/// - binding: How the buffer will be bound for access from the shader. See [Binding] for details. Specifying [Binding::Double] makes this a double buffer.
+	/// - readonly: If true, then this buffer can only be read in the shader, and can't be written to. This is ignored for a [Binding::Double], whose front buffer is bound read-only and back buffer read-write, unless changed with [set_double_storage_access](ShaderBufferSet::set_double_storage_access).
+	pub fn add_storage_uninit(
+		&mut self, render_device: &RenderDevice, size: u32, usage: BufferUsages, binding: Binding, readonly: bool,
+	) -> Result<ShaderBufferHandle, ShaderBufferError> {
+		let usage = required_usages(usage, STORAGE_USAGES, "storage");
+		self.check_binding_free(binding)?;
+		self.reserve(binding, size as u64)?;
+		Ok(self.store_buffer(
+			binding,
+			ShaderBufferInfo::new_storage_uninit(render_device, size, usage, binding, readonly, self.next_label.as_deref()),
+		))
+	}
+
+	/// Add a new storage buffer initialized to all zero bytes.
+	/// - render_device: The [RenderDevice] resouce from Bevy.
+	/// - size: The size of the buffer in bytes.
+	/// - usage: See Bevy's [BufferUsages]. [BufferUsages::STORAGE] and [BufferUsages::COPY_DST] are always added, as the buffer can't be bound or set without them.
+	/// - binding: How the buffer will be bound for access from the shader. See [Binding] for details. Specifying [Binding::Double] makes this a double buffer.
+	/// - readonly: If true, then this buffer can only be read in the shader, and can't be written to. This is ignored for a [Binding::Double], whose front buffer is bound read-only and back buffer read-write, unless changed with [set_double_storage_access](ShaderBufferSet::set_double_storage_access).
+	pub fn add_storage_zeroed(
+		&mut self, render_device: &RenderDevice, size: u32, usage: BufferUsages, binding: Binding, readonly: bool,
+	) -> Result<ShaderBufferHandle, ShaderBufferError> {
+		let usage = required_usages(usage, STORAGE_USAGES, "storage");
+		self.check_binding_free(binding)?;
+		self.reserve(binding, size as u64)?;
+		Ok(self.store_buffer(
+			binding,
+			ShaderBufferInfo::new_storage_zeroed(render_device, size, usage, binding, readonly, self.next_label.as_deref()),
+		))
+	}
+
+	/// Add a new zeroed storage buffer that can also be bound as a vertex buffer, so a compute shader can generate mesh vertices that are drawn without a round trip through the CPU. This is [add_storage_zeroed](ShaderBufferSet::add_storage_zeroed) with [BufferUsages::VERTEX]; use that directly if the buffer needs any other usages. A render command can get the buffer to draw with from [gpu_buffer](ShaderBufferSet::gpu_buffer), as the [ShaderBufferSet] is extracted to the render world.
+	/// - render_device: The [RenderDevice] resouce from Bevy.
+	/// - size: The size of the buffer in bytes.
+	/// - binding: How the buffer will be bound for access from the shader. See [Binding] for details. Specifying [Binding::Double] makes this a double buffer, in which case the front buffer is the one to draw with.
+	pub fn add_vertex_buffer(
+		&mut self, render_device: &RenderDevice, size: u32, binding: Binding,
+	) -> Result<ShaderBufferHandle, ShaderBufferError> {
+		self.add_storage_zeroed(render_device, size, STORAGE_USAGES | BufferUsages::VERTEX, binding, false)
+	}
+
+	/// Add a new zeroed storage buffer that can also be bound as an index buffer, so a compute shader can generate mesh indices that are drawn without a round trip through the CPU. This is [add_storage_zeroed](ShaderBufferSet::add_storage_zeroed) with [BufferUsages::INDEX]; use that directly if the buffer needs any other usages. Compute shaders can only write whole words, so the indices should be written as `array<u32>` and drawn with [IndexFormat::Uint32](bevy::render::render_resource::IndexFormat::Uint32).
+	/// - render_device: The [RenderDevice] resouce from Bevy.
+	/// - size: The size of the buffer in bytes.
+	/// - binding: How the buffer will be bound for access from the shader. See [Binding] for details. Specifying [Binding::Double] makes this a double buffer, in which case the front buffer is the one to draw with.
+	pub fn add_index_buffer(
+		&mut self, render_device: &RenderDevice, size: u32, binding: Binding,
+	) -> Result<ShaderBufferHandle, ShaderBufferError> {
+		self.add_storage_zeroed(render_device, size, STORAGE_USAGES | BufferUsages::INDEX, binding, false)
+	}
+
+	/// Add a new storage buffer holding the arguments of a non-indexed indirect draw, so a compute shader can decide how many vertices or instances get drawn, such as the number of particles emitted this frame, without a round trip through the CPU. The buffer has [BufferUsages::STORAGE] and [BufferUsages::INDIRECT], along with [BufferUsages::COPY_SRC] and [BufferUsages::COPY_DST] so it can be read back with a [CopyBuffer](crate::ComputeAction::CopyBuffer) action and reset with [write_buffer](ShaderBufferSet::write_buffer). In the shader it's a struct of four `u32`s, `vertex_count`, `instance_count`, `first_vertex` and `first_instance`, any of which can be declared `atomic<u32>` to be counted up by many invocations. A render command can get the buffer to pass to `draw_indirect` from [gpu_buffer](ShaderBufferSet::gpu_buffer).
+	/// - render_device: The [RenderDevice] resouce from Bevy.
+	/// - args: The initial arguments, such as the vertex count of the mesh being instanced, with an instance count of 0 for the compute shader to fill in.
+	/// - binding: How the buffer will be bound for access from the shader. See [Binding] for details.
+	pub fn add_draw_indirect_args(
+		&mut self, render_device: &RenderDevice, args: DrawIndirectArgs, binding: Binding,
+	) -> Result<ShaderBufferHandle, ShaderBufferError> {
+		self.check_binding_free(binding)?;
+		self.reserve(binding, args.as_bytes().len() as u64)?;
+		Ok(self.store_buffer(
+			binding,
+			ShaderBufferInfo::new_storage_bytes(
+				render_device,
+				args.as_bytes(),
+				INDIRECT_ARGS_USAGES,
+				binding,
+				false,
+				self.next_label.as_deref(),
+			),
+		))
+	}
+
+	/// Add a new storage buffer holding the arguments of an indexed indirect draw. This is the same as [add_draw_indirect_args](ShaderBufferSet::add_draw_indirect_args), for `draw_indexed_indirect`, and in the shader it's a struct of `index_count`, `instance_count`, `first_index`, `base_vertex` and `first_instance`, where `base_vertex` is an `i32` and the rest are `u32`s.
+	/// - render_device: The [RenderDevice] resouce from Bevy.
+	/// - args: The initial arguments, such as the index count of the mesh being instanced, with an instance count of 0 for the compute shader to fill in.
+	/// - binding: How the buffer will be bound for access from the shader. See [Binding] for details.
+	pub fn add_draw_indexed_indirect_args(
+		&mut self, render_device: &RenderDevice, args: DrawIndexedIndirectArgs, binding: Binding,
+	) -> Result<ShaderBufferHandle, ShaderBufferError> {
+		self.check_binding_free(binding)?;
+		self.reserve(binding, args.as_bytes().len() as u64)?;
+		Ok(self.store_buffer(
+			binding,
+			ShaderBufferInfo::new_storage_bytes(
+				render_device,
+				args.as_bytes(),
+				INDIRECT_ARGS_USAGES,
+				binding,
+				false,
+				self.next_label.as_deref(),
+			),
+		))
+	}
+
+	/// Add a new storage buffer holding the workgroup counts of an indirect dispatch, so a compute shader can decide how much work a later [RunShaderIndirect](crate::ComputeAction::RunShaderIndirect) step does, such as one workgroup for every 64 particles emitted this frame, without a round trip through the CPU. This is the same as [add_draw_indirect_args](ShaderBufferSet::add_draw_indirect_args), for dispatches, and in the shader it's a struct of three `u32`s, `x`, `y` and `z`, any of which can be declared `atomic<u32>`.
+	/// - render_device: The [RenderDevice] resouce from Bevy.
+	/// - args: The initial workgroup counts, which should be at least 1 in the dimensions the shader doesn't fill in, as a count of 0 in any of them dispatches nothing.
+	/// - binding: How the buffer will be bound for access from the shader. See [Binding] for details. This can't be a [Binding::Double], as the step reading the counts needs to know which half to read them from.
+	pub fn add_dispatch_indirect_args(
+		&mut self, render_device: &RenderDevice, args: DispatchIndirectArgs, binding: Binding,
+	) -> Result<ShaderBufferHandle, ShaderBufferError> {
+		self.check_binding_free(binding)?;
+		self.reserve(binding, args.as_bytes().len() as u64)?;
+		Ok(self.store_buffer(
+			binding,
+			ShaderBufferInfo::new_storage_bytes(
+				render_device,
+				args.as_bytes(),
+				INDIRECT_ARGS_USAGES,
+				binding,
+				false,
+				self.next_label.as_deref(),
+			),
+		))
+	}
+
+	/// Add a new storage buffer of `u32` counters, all starting at zero, for shaders to count things into with atomics, such as the bins of a [Histogram](crate::ComputeAction::Histogram). In the shader it's an `array<atomic<u32>>`. The buffer has [BufferUsages::COPY_SRC] and [BufferUsages::COPY_DST] along with [BufferUsages::STORAGE], so the counts can be read back with a [CopyBuffer](crate::ComputeAction::CopyBuffer) action, and decoded from the [BufferReadbackEvent](crate::BufferReadbackEvent) as a `Vec<u32>`.
+	/// - render_device: The [RenderDevice] resouce from Bevy.
+	/// - count: The number of counters.
+	/// - binding: How the buffer will be bound for access from the shader. See [Binding] for details.
+	pub fn add_counters(
+		&mut self, render_device: &RenderDevice, count: u32, binding: Binding,
+	) -> Result<ShaderBufferHandle, ShaderBufferError> {
+		self.add_storage_zeroed(render_device, count * 4, STORAGE_USAGES | BufferUsages::COPY_SRC, binding, false)
+	}
+
+	/// Add a new storage buffer of `u64` counters, all starting at zero, for counts that would overflow the `u32`s of [add_counters](ShaderBufferSet::add_counters), such as samples accumulated over many frames. In the shader it's an `array<atomic<u64>>`, and the counts can be read back the same way, and decoded as a `Vec<u64>`.
+	/// - render_device: The [RenderDevice] resouce from Bevy.
+	/// - count: The number of counters.
+	/// - binding: How the buffer will be bound for access from the shader. See [Binding] for details.
+	///
+	/// Panics if the GPU doesn't support the `SHADER_INT64_ATOMIC_ALL_OPS` feature, which `atomicAdd` on a `u64` needs. It's missing on the web and many GPUs, so check [ComputeCapabilities::int64_atomics](crate::ComputeCapabilities::int64_atomics) first, and fall back to [add_counters](ShaderBufferSet::add_counters) without it.
+	pub fn add_counters_u64(
+		&mut self, render_device: &RenderDevice, count: u32, binding: Binding,
+	) -> Result<ShaderBufferHandle, ShaderBufferError> {
+		if !render_device.features().contains(WgpuFeatures::SHADER_INT64_ATOMIC_ALL_OPS) {
+			panic!(
+				"Tried to add 64-bit counters, but the GPU doesn't support the SHADER_INT64_ATOMIC_ALL_OPS feature. Check ComputeCapabilities::int64_atomics first, and use add_counters instead without it"
+			);
+		}
+		self.add_storage_zeroed(render_device, count * 8, STORAGE_USAGES | BufferUsages::COPY_SRC, binding, false)
+	}
+
+	/// Add a new storage buffer initialized with the provided data.
+	/// - render_device: The [RenderDevice] resouce from Bevy.
 	/// - render_queue: The [RenderQueue] resource from Bevy.
 	/// - data: The data. Must implement the [ShaderType] trait. The buffer's size will be determined by the size of this data.
-	/// - usage: See Bevy's [BufferUsages].
+	/// - usage: See Bevy's [BufferUsages]. [BufferUsages::STORAGE] and [BufferUsages::COPY_DST] are always added, as the buffer can't be bound or set without them.
 	/// - binding: How the buffer will be bound for access from the shader. See [Binding] for details. Specifying [Binding::Double] makes this a double buffer, in which case both buffers will be initialized with the provided data.
-	/// - readonly: If true, then this buffer can only be read in the shader, and can't be written to.
+	/// - readonly: If true, then this buffer can only be read in the shader, and can't be written to. This is ignored for a [Binding::Double], whose front buffer is bound read-only and back buffer read-write, unless changed with [set_double_storage_access](ShaderBufferSet::set_double_storage_access).
 	pub fn add_storage_init<T: ShaderType + WriteInto + Clone + Default>(
 		&mut self, render_device: &RenderDevice, render_queue: &RenderQueue, data: T, usage: BufferUsages,
 		binding: Binding, readonly: bool,
-	) -> ShaderBufferHandle {
-		self.store_buffer(
+	) -> Result<ShaderBufferHandle, ShaderBufferError> {
+		let usage = required_usages(usage, STORAGE_USAGES, "storage");
+		self.check_binding_free(binding)?;
+		self.reserve(binding, data.size().get())?;
+		Ok(self.store_buffer(
 			binding,
-			ShaderBufferInfo::new_storage_init(render_device, render_queue, data, usage, binding, readonly),
-		)
+			ShaderBufferInfo::new_storage_init(
+				render_device,
+				render_queue,
+				data,
+				usage,
+				binding,
+				readonly,
+				self.next_label.as_deref(),
+			),
+		))
 	}
 
 	/// Add a new uniform buffer initialized with the provided data.
 	/// - render_device: The [RenderDevice] resouce from Bevy.
 	/// - render_queue: The [RenderQueue] resource from Bevy.
 	/// - data: The data. Must implement the [ShaderType] trait. The buffer's size will be determined by the size of this data.
-	/// - usage: See Bevy's [BufferUsages].
-	/// - binding: How the buffer will be bound for access from the shader. See [Binding] for details. Specifying [Binding::Double] makes this a double buffer, but given that uniform buffers are always read-only, there's little point to double buffering them.
+	/// - usage: See Bevy's [BufferUsages]. [BufferUsages::UNIFORM] and [BufferUsages::COPY_DST] are always added, as the buffer can't be bound or set without them.
+	/// - binding: How the buffer will be bound for access from the shader. See [Binding] for details. Specifying [Binding::Double] makes this a double buffer, whose back buffer can be set with [write_back_buffer](ShaderBufferSet::write_back_buffer) while the shaders read the front one, though [write_buffer](ShaderBufferSet::write_buffer) is usually enough for parameters that change from frame to frame.
 	pub fn add_uniform_init<T: ShaderType + WriteInto + Clone + Default>(
 		&mut self, render_device: &RenderDevice, render_queue: &RenderQueue, data: T, usage: BufferUsages, binding: Binding,
-	) -> ShaderBufferHandle {
-		self.store_buffer(binding, ShaderBufferInfo::new_uniform_init(render_device, render_queue, data, usage, binding))
+	) -> Result<ShaderBufferHandle, ShaderBufferError> {
+		let usage = required_usages(usage, UNIFORM_USAGES, "uniform");
+		self.check_binding_free(binding)?;
+		self.reserve(binding, data.size().get())?;
+		Ok(self.store_buffer(
+			binding,
+			ShaderBufferInfo::new_uniform_init(render_device, render_queue, data, usage, binding, self.next_label.as_deref()),
+		))
+	}
+
+	/// Add a new dynamic uniform buffer, holding `max_elements` elements of `T`, only one of which is bound at a time, picked for each [RunShader](crate::ComputeAction::RunShader) step with its [dynamic_offsets](crate::ComputeAction::RunShader::dynamic_offsets), so several steps can each read their own parameters out of one buffer, without it being written between them. The shader declares it as a plain `var<uniform>` of `T`. Each element starts at a multiple of the GPU's `min_uniform_buffer_offset_alignment`, which is usually 256 bytes, as every dynamic offset must be, so a small `T` takes up far more room than its size. The elements start out zeroed, and are set with [write_uniform_element](ShaderBufferSet::write_uniform_element). Every other kind of shader step binds its first element. Panics if `max_elements` is zero, or if the binding isn't a [Binding::SingleBound], as the halves of a double buffer and per-entity buffers are bound without dynamic offsets.
+	/// - render_device: The [RenderDevice] resouce from Bevy.
+	/// - max_elements: The number of elements the buffer holds.
+	/// - usage: See Bevy's [BufferUsages]. [BufferUsages::UNIFORM] and [BufferUsages::COPY_DST] are always added, as the buffer can't be bound or set without them.
+	/// - binding: How the buffer will be bound for access from the shader. See [Binding] for details.
+	pub fn add_uniform_dynamic<T: ShaderSize>(
+		&mut self, render_device: &RenderDevice, max_elements: u32, usage: BufferUsages, binding: Binding,
+	) -> Result<ShaderBufferHandle, ShaderBufferError> {
+		let Binding::SingleBound(group, at) = binding else {
+			panic!("Tried to add a dynamic uniform buffer that isn't bound with Binding::SingleBound");
+		};
+		if max_elements == 0 {
+			panic!("Tried to add a dynamic uniform buffer at group {} binding {} with no elements", group, at);
+		}
+		let usage = required_usages(usage, UNIFORM_USAGES, "uniform");
+		let element_size = T::SHADER_SIZE.get();
+		let stride = element_size.next_multiple_of(render_device.limits().min_uniform_buffer_offset_alignment as u64);
+		self.check_binding_free(binding)?;
+		self.reserve(binding, stride * max_elements as u64)?;
+		Ok(self.store_buffer(
+			binding,
+			ShaderBufferInfo::new_uniform_dynamic(
+				render_device,
+				element_size,
+				stride,
+				max_elements,
+				usage,
+				binding,
+				self.next_label.as_deref(),
+			),
+		))
+	}
+
+	/// Add the globals buffer, a uniform buffer of [ComputeGlobals] which the plugin keeps up to date with the time, the time since the last frame, the frame count and a seed for the iteration, once per tick while a compute sequence is running. Its docs have the struct to declare in the shader. There can only be one globals buffer, so this panics if one has already been added; deleting it with [delete_buffer](ShaderBufferSet::delete_buffer) allows adding another.
+	/// - render_device: The [RenderDevice] resouce from Bevy.
+	/// - render_queue: The [RenderQueue] resource from Bevy.
+	/// - binding: How the buffer will be bound for access from the shader. See [Binding] for details.
+	pub fn add_globals(
+		&mut self, render_device: &RenderDevice, render_queue: &RenderQueue, binding: Binding,
+	) -> Result<ShaderBufferHandle, ShaderBufferError> {
+		if let Some(globals) = self.globals {
+			panic!("Tried to add a globals buffer, but {} already is one", globals.describe(self));
+		}
+		let handle =
+			self.add_uniform_init(render_device, render_queue, ComputeGlobals::default(), UNIFORM_USAGES, binding)?;
+		self.globals = Some(handle);
+		Ok(handle)
+	}
+
+	/// The globals buffer added with [add_globals](ShaderBufferSet::add_globals), if there is one.
+	pub(crate) fn globals(&self) -> Option<ShaderBufferHandle> { self.globals }
+
+	/// Add the size uniform, a uniform buffer of [ComputeSize] which the plugin keeps up to date with the physical size of the primary window, and optionally the size of a texture, so fullscreen effects don't have to remember to update it when the window is resized. Its docs have the struct to declare in the shader. There can only be one size uniform, so this panics if one has already been added, or if the texture to track isn't a texture buffer; deleting it with [delete_buffer](ShaderBufferSet::delete_buffer) allows adding another.
+	/// - render_device: The [RenderDevice] resouce from Bevy.
+	/// - render_queue: The [RenderQueue] resource from Bevy.
+	/// - texture: A texture buffer whose size to track along with the window's, if any.
+	/// - binding: How the buffer will be bound for access from the shader. See [Binding] for details.
+	pub fn add_size_uniform(
+		&mut self, render_device: &RenderDevice, render_queue: &RenderQueue, texture: Option<ShaderBufferHandle>,
+		binding: Binding,
+	) -> Result<ShaderBufferHandle, ShaderBufferError> {
+		if let Some((size_uniform, _)) = self.size_uniform {
+			panic!("Tried to add a size uniform, but {} already is one", size_uniform.describe(self));
+		}
+		if let Some(texture) = texture {
+			if self.texture_format_and_size(texture).is_none() {
+				panic!("Tried to track the size of {} in a size uniform, but it isn't a texture", texture.describe(self));
+			}
+		}
+		let handle = self.add_uniform_init(render_device, render_queue, ComputeSize::default(), UNIFORM_USAGES, binding)?;
+		self.size_uniform = Some((handle, texture));
+		Ok(handle)
+	}
+
+	/// The size uniform added with [add_size_uniform](ShaderBufferSet::add_size_uniform), if there is one, and the texture it tracks.
+	pub(crate) fn size_uniform(&self) -> Option<(ShaderBufferHandle, Option<ShaderBufferHandle>)> { self.size_uniform }
+
+	/// Add the cursor uniform, a uniform buffer of [ComputeCursor] which the plugin keeps up to date with the position of the cursor over a texture, and the mouse buttons held down, so shaders can let the user paint into a simulation. The position is worked out in the main world every frame, through the highest ordered active camera rendering to the primary window, and written to the uniform in the render world before that frame's steps run, so every step in a tick sees the same cursor. Its docs have the struct to declare in the shader. There can only be one cursor uniform, so this panics if one has already been added, or if the texture to track isn't a texture buffer; deleting it with [delete_buffer](ShaderBufferSet::delete_buffer) allows adding another.
+	/// - render_device: The [RenderDevice] resouce from Bevy.
+	/// - render_queue: The [RenderQueue] resource from Bevy.
+	/// - texture: The texture buffer to give the cursor position in the pixels of.
+	/// - display: The rectangle in world space the texture is displayed in, such as by a sprite, or by a mesh from [compute_texture_2d](crate::compute_texture_2d), which is the mesh's size scaled by its transform, centered on its translation. If the texture moves, update this with [set_cursor_display](ShaderBufferSet::set_cursor_display).
+	/// - binding: How the buffer will be bound for access from the shader. See [Binding] for details.
+	pub fn add_cursor_uniform(
+		&mut self, render_device: &RenderDevice, render_queue: &RenderQueue, texture: ShaderBufferHandle, display: Rect,
+		binding: Binding,
+	) -> Result<ShaderBufferHandle, ShaderBufferError> {
+		if let Some(cursor) = self.cursor_uniform {
+			panic!("Tried to add a cursor uniform, but {} already is one", cursor.handle.describe(self));
+		}
+		if self.texture_format_and_size(texture).is_none() {
+			panic!("Tried to track the cursor over {} in a cursor uniform, but it isn't a texture", texture.describe(self));
+		}
+		let handle =
+			self.add_uniform_init(render_device, render_queue, ComputeCursor::default(), UNIFORM_USAGES, binding)?;
+		self.cursor_uniform = Some(CursorUniform { handle, texture, display, value: ComputeCursor::default() });
+		Ok(handle)
+	}
+
+	/// Set the rectangle in world space the texture tracked by the [cursor uniform](ShaderBufferSet::add_cursor_uniform) is displayed in. Panics if there's no cursor uniform.
+	pub fn set_cursor_display(&mut self, display: Rect) {
+		self.assert_main_world("set the cursor display rect");
+		let Some(cursor) = &mut self.cursor_uniform else {
+			panic!("Tried to set the cursor display rect, but no cursor uniform has been added");
+		};
+		cursor.display = display;
+	}
+
+	/// The cursor uniform added with [add_cursor_uniform](ShaderBufferSet::add_cursor_uniform), if there is one.
+	pub(crate) fn cursor_uniform(&self) -> Option<CursorUniform> { self.cursor_uniform }
+
+	pub(crate) fn cursor_uniform_mut(&mut self) -> Option<&mut CursorUniform> { self.cursor_uniform.as_mut() }
+
+	/// Add the camera uniform, a uniform buffer of [ComputeCameraMatrices] which the plugin keeps up to date with the view and projection matrices, and their inverses, of the camera marked with [ComputeCamera](crate::ComputeCamera), for screen-space effects. The matrices are taken from the camera as extracted to the render world, so they're the ones the renderer uses that frame. Until a camera is marked, the matrices are all zero. Its docs have the struct to declare in the shader. There can only be one camera uniform, so this panics if one has already been added; deleting it with [delete_buffer](ShaderBufferSet::delete_buffer) allows adding another.
+	/// - render_device: The [RenderDevice] resouce from Bevy.
+	/// - render_queue: The [RenderQueue] resource from Bevy.
+	/// - binding: How the buffer will be bound for access from the shader. See [Binding] for details.
+	pub fn add_camera_uniform(
+		&mut self, render_device: &RenderDevice, render_queue: &RenderQueue, binding: Binding,
+	) -> Result<ShaderBufferHandle, ShaderBufferError> {
+		if let Some(camera_uniform) = self.camera_uniform {
+			panic!("Tried to add a camera uniform, but {} already is one", camera_uniform.describe(self));
+		}
+		let handle =
+			self.add_uniform_init(render_device, render_queue, ComputeCameraMatrices::default(), UNIFORM_USAGES, binding)?;
+		self.camera_uniform = Some(handle);
+		Ok(handle)
+	}
+
+	/// The camera uniform added with [add_camera_uniform](ShaderBufferSet::add_camera_uniform), if there is one.
+	pub(crate) fn camera_uniform(&self) -> Option<ShaderBufferHandle> { self.camera_uniform }
+
+	/// Add the chunk uniform, a uniform buffer of [ComputeChunk] which the plugin writes the offset of each chunk of a [Chunked](crate::DispatchSize::Chunked) dispatch to, before dispatching it. Its docs have the struct to declare in the shader. Every chunked dispatch shares this uniform, so there can only be one, and this panics if one has already been added; deleting it with [delete_buffer](ShaderBufferSet::delete_buffer) allows adding another.
+	/// - render_device: The [RenderDevice] resouce from Bevy.
+	/// - render_queue: The [RenderQueue] resource from Bevy.
+	/// - binding: How the buffer will be bound for access from the shader. See [Binding] for details.
+	pub fn add_chunk_uniform(
+		&mut self, render_device: &RenderDevice, render_queue: &RenderQueue, binding: Binding,
+	) -> Result<ShaderBufferHandle, ShaderBufferError> {
+		if let Some(chunk_uniform) = self.chunk_uniform {
+			panic!("Tried to add a chunk uniform, but {} already is one", chunk_uniform.describe(self));
+		}
+		let handle =
+			self.add_uniform_init(render_device, render_queue, ComputeChunk::default(), UNIFORM_USAGES, binding)?;
+		self.chunk_uniform = Some(handle);
+		Ok(handle)
+	}
+
+	/// The chunk uniform added with [add_chunk_uniform](ShaderBufferSet::add_chunk_uniform), if there is one.
+	pub(crate) fn chunk_uniform(&self) -> Option<ShaderBufferHandle> { self.chunk_uniform }
+
+	/// Whether the plugin writes the contents of a buffer itself, so it shouldn't be set.
+	fn plugin_writes(&self, handle: ShaderBufferHandle) -> bool {
+		self.globals == Some(handle)
+			|| self.size_uniform.is_some_and(|(size_uniform, _)| size_uniform == handle)
+			|| self.cursor_uniform.is_some_and(|cursor| cursor.handle == handle)
+			|| self.camera_uniform == Some(handle)
+			|| self.chunk_uniform == Some(handle)
 	}
 
 	/// Add a new texture buffer initialized with the provided solid color.
 	/// - images: The `Assets<Image>` resource from Bevy.
 	/// - width: The width of the texture in pixels.
 	/// - height: The height of the texture in pixels.
-	/// - format: The pixel format of the texture.
-	/// - fill: One pixel's worth of data, provided as a byte array. The entire texture will be filled with this.
-	/// - access: Whether this texture is read-only, write-only or read-write. This is ignored if the texture is double buffered.
+	/// - format: The pixel format of the texture. sRGB formats can't be storage textures, so for [Rgba8UnormSrgb](TextureFormat::Rgba8UnormSrgb) or [Bgra8UnormSrgb](TextureFormat::Bgra8UnormSrgb), the texture is stored in the linear format, such as `rgba8unorm`, which is what the shader must declare, and the image gets an sRGB view for sprites and materials to sample. See below for what this means for gamma.
+	/// - fill: One pixel's worth of data, provided as a byte array. The entire texture will be filled with this. It must be exactly one pixel of the format, or this panics. For 16-bit float formats, such as [Rgba16Float](TextureFormat::Rgba16Float), [f32_to_f16](crate::f32_to_f16) makes the channels.
+	/// - access: Whether this texture is read-only, write-only or read-write. This is ignored if the texture is double buffered, whose halves are bound with the access set with [set_double_texture_access](ShaderBufferSet::set_double_texture_access).
 	/// - binding: How the buffer will be bound for access from the shader. See [Binding] for details. Specifying [Binding::Double] makes this a double buffer, in which case the access mode specified in the previous argument is ignored.
+	///
+	/// Shaders read and write the raw values of a storage texture, with no gamma conversion either way. With a linear format, a sprite shows those values as linear colors, so a shader writing sRGB-encoded colors, such as ones picked in an image editor, will look washed out. With an sRGB format, the sprite decodes the values from sRGB as it samples them, so the shader must write sRGB-encoded colors, and encode any linear colors it computes, for them to look right.
+	///
+	/// Panics if the binding is a [Binding::Ring], as textures can't be rings, if the format can't be a storage texture on this GPU, as told by [ComputeCapabilities::supports_storage], or if it's an sRGB format and the GPU can't make [srgb_views](ComputeCapabilities::srgb_views).
+	///
+	/// Panics if the access is [StorageTextureAccess::ReadWrite], and the GPU can't bind textures of this format that way, as told by [ComputeCapabilities::supports_read_write_storage]. Every GPU can for `R32Float`, `R32Uint` and `R32Sint`, but many can't for anything else, including all of them on the web. A double buffer works everywhere instead, with a shader reading the front buffer and writing the back buffer, followed by a [SwapBuffers](crate::ComputeAction::SwapBuffers).
+	#[allow(clippy::too_many_arguments)]
 	pub fn add_texture_fill(
 		&mut self, images: &mut Assets<Image>, width: u32, height: u32, format: TextureFormat, fill: &[u8],
 		access: StorageTextureAccess, binding: Binding,
-	) -> ShaderBufferHandle {
-		self
-			.store_buffer(binding, ShaderBufferInfo::new_write_texture(images, width, height, format, fill, access, binding))
+	) -> Result<ShaderBufferHandle, ShaderBufferError> {
+		let size = Extent3d { width, height, depth_or_array_layers: 1 };
+		self.add_texture_fill_sized(images, size, TextureViewDimension::D2, format, fill, access, binding)
 	}
 
-	pub(crate) fn bind_groups(&self, device: &RenderDevice, gpu_images: &RenderAssets<GpuImage>) -> Vec<BindGroup> {
-		self
-			.groups
-			.iter()
-			.map(|buffer_ids| {
-				let buffers = buffer_ids.iter().map(|id| self.buffers.get(id).unwrap()).collect::<Vec<_>>();
-				device.create_bind_group(
-					None,
-					&bind_group_layout(&buffers, &device),
-					buffers.iter().flat_map(|buffer| buffer.bind_group_entries(gpu_images)).collect::<Vec<_>>().as_slice(),
-				)
-			})
-			.collect()
+	/// Add a new 3D texture buffer initialized with the provided solid color, such as the grid of a fluid simulation, which shaders declare as a `texture_storage_3d` and index with a `vec3<u32>`. This is [add_texture_fill] with a depth, in pixels, and panics for the same reasons. Double buffering, copies, readbacks and [ReadBuffer](crate::ComputeAction::ReadBuffer) all work on the whole volume, with its slices one after another, front to back. The builtin passes, such as [ComputeAction::Histogram](crate::ComputeAction::Histogram), only take 2D textures. wgpu's GL backend, which WebGL2 and some Linux drivers go through, only binds the first slice of a 3D storage texture to shaders, so a volume that shaders write all of needs a GPU with a native backend.
+	///
+	/// [add_texture_fill]: ShaderBufferSet::add_texture_fill
+	#[allow(clippy::too_many_arguments)]
+	pub fn add_texture_fill_3d(
+		&mut self, images: &mut Assets<Image>, width: u32, height: u32, depth: u32, format: TextureFormat, fill: &[u8],
+		access: StorageTextureAccess, binding: Binding,
+	) -> Result<ShaderBufferHandle, ShaderBufferError> {
+		let size = Extent3d { width, height, depth_or_array_layers: depth };
+		self.add_texture_fill_sized(images, size, TextureViewDimension::D3, format, fill, access, binding)
 	}
 
-	pub(crate) fn bind_group_layouts(&self, device: &RenderDevice) -> Vec<BindGroupLayout> {
-		self
-			.groups
-			.iter()
-			.map(|buffer_ids| {
-				let buffers = buffer_ids.iter().map(|id| self.buffers.get(id).unwrap()).collect::<Vec<_>>();
-				bind_group_layout(&buffers, device)
-			})
-			.collect()
+	/// Add a new array of 2D textures, all the same size and initialized with the provided solid color, which shaders declare as a `texture_storage_2d_array` and index with a layer as well as a pixel, such as one layer per particle species. This is [add_texture_fill] with a number of layers, and panics for the same reasons. Double buffering, copies and readbacks work on every layer, one after another. A [TextureViewSpec] can bind a range of the layers, rather than all of them. The builtin passes, such as [ComputeAction::Histogram](crate::ComputeAction::Histogram), only take 2D textures.
+	///
+	/// [add_texture_fill]: ShaderBufferSet::add_texture_fill
+	#[allow(clippy::too_many_arguments)]
+	pub fn add_texture_fill_layers(
+		&mut self, images: &mut Assets<Image>, width: u32, height: u32, layers: u32, format: TextureFormat, fill: &[u8],
+		access: StorageTextureAccess, binding: Binding,
+	) -> Result<ShaderBufferHandle, ShaderBufferError> {
+		let size = Extent3d { width, height, depth_or_array_layers: layers };
+		self.add_texture_fill_sized(images, size, TextureViewDimension::D2Array, format, fill, access, binding)
+	}
+
+	/// Add a texture of any size and dimension, for [add_texture_fill](ShaderBufferSet::add_texture_fill) and its 3D and layered variants.
+	#[allow(clippy::too_many_arguments)]
+	fn add_texture_fill_sized(
+		&mut self, images: &mut Assets<Image>, size: Extent3d, dimension: TextureViewDimension, format: TextureFormat,
+		fill: &[u8], access: StorageTextureAccess, binding: Binding,
+	) -> Result<ShaderBufferHandle, ShaderBufferError> {
+		self.check_texture_format(format, access, binding);
+		check_texture_fill(format, fill);
+		self.check_binding_free(binding)?;
+		self.reserve(binding, texture_bytes(format, size))?;
+		let label = self.next_label.as_deref();
+		let info =
+			ShaderBufferInfo::new_write_texture(images, size, dimension, format, (fill, fill), access, binding, label);
+		Ok(self.store_buffer(binding, info))
+	}
+
+	/// Add a new double buffered texture, with its halves initialized to two different solid colors, such as the previous and current states of a leapfrog integrator. This is [add_texture_fill] with a [Binding::Double], except that `first_fill` fills the half bound at the first binding of the [Binding::Double], which shaders read as the front buffer, and `second_fill` the half bound at the second binding, as the back buffer. That holds until the first swap, after which they trade places with each swap, as with any double buffer. [image_at_binding](ShaderBufferSet::image_at_binding) tells which image is bound where at any time. Panics if the binding isn't a [Binding::Double], as well as for the same reasons as [add_texture_fill].
+	///
+	/// [add_texture_fill]: ShaderBufferSet::add_texture_fill
+	#[allow(clippy::too_many_arguments)]
+	pub fn add_texture_fill_double(
+		&mut self, images: &mut Assets<Image>, width: u32, height: u32, format: TextureFormat, first_fill: &[u8],
+		second_fill: &[u8], binding: Binding,
+	) -> Result<ShaderBufferHandle, ShaderBufferError> {
+		if !matches!(binding, Binding::Double(..)) {
+			panic!("Tried to add a double buffered texture with different fills, but its binding isn't a Binding::Double");
+		}
+		let access = StorageTextureAccess::ReadOnly;
+		self.check_texture_format(format, access, binding);
+		check_texture_fill(format, first_fill);
+		check_texture_fill(format, second_fill);
+		let size = Extent3d { width, height, depth_or_array_layers: 1 };
+		self.check_binding_free(binding)?;
+		self.reserve(binding, texture_bytes(format, size))?;
+		let fills = (first_fill, second_fill);
+		let label = self.next_label.as_deref();
+		let info = ShaderBufferInfo::new_write_texture(
+			images,
+			size,
+			TextureViewDimension::D2,
+			format,
+			fills,
+			access,
+			binding,
+			label,
+		);
+		Ok(self.store_buffer(binding, info))
+	}
+
+	/// Panic if the GPU can't bind a storage texture of this format with this access and binding, as described on [add_texture_fill](ShaderBufferSet::add_texture_fill).
+	fn check_texture_format(&self, format: TextureFormat, access: StorageTextureAccess, binding: Binding) {
+		if let Binding::Ring(..) = binding {
+			panic!(
+				"Tried to add a storage texture bound with Binding::Ring, but only storage and uniform buffers can be rings"
+			);
+		}
+		if let Some(capabilities) = &self.capabilities {
+			if !capabilities.supports_storage(format.remove_srgb_suffix()) {
+				panic!(
+					"Tried to add a storage texture of format {:?}, but this GPU can't bind that format as a storage texture. See ComputeCapabilities::storage_formats for the formats it can",
+					format
+				);
+			}
+			if format.is_srgb() && !capabilities.srgb_views {
+				panic!(
+					"Tried to add a storage texture of format {:?}, which is stored as {:?} with an sRGB view, but this GPU can't make views in a different format. Use {:?} instead, and encode colors to sRGB in the shader if they should look the same",
+					format,
+					format.remove_srgb_suffix(),
+					format.remove_srgb_suffix()
+				);
+			}
+			if access == StorageTextureAccess::ReadWrite
+				&& !matches!(binding, Binding::Double(..))
+				&& !capabilities.supports_read_write_storage(format.remove_srgb_suffix())
+			{
+				panic!(
+					"Tried to add a ReadWrite storage texture of format {:?}, but this GPU can't bind that format as ReadWrite. Only R32Float, R32Uint and R32Sint are supported everywhere. Use one of those, or a Binding::Double instead, reading from the front buffer and writing to the back, with a SwapBuffers step after each pass",
+					format
+				);
+			}
+		}
+	}
+
+	/// Add a binding array of existing textures, bound as `binding_array<texture_2d<f32>, N>` in WGSL, where `N` is the number of images, for instance to pick one of several tile textures per cell. The textures are only read from, with `textureLoad`, or with `textureSampleLevel` and a sampler added with [add_sampler](ShaderBufferSet::add_sampler), and any that haven't been uploaded to the GPU yet are bound as a blank fallback texture until they are. Indexing the array with a value that isn't the same across the whole workgroup also needs the `SAMPLED_TEXTURE_AND_STORAGE_BUFFER_ARRAY_NON_UNIFORM_INDEXING` feature.
+	/// - render_device: The `RenderDevice` resource from Bevy.
+	/// - images: The images to bind, in the order they're indexed in the shader. There must be at least one.
+	/// - sample_type: How the textures are read, which must suit their format, as for [add_sampled_texture](ShaderBufferSet::add_sampled_texture).
+	/// - binding: How the array will be bound for access from the shader. This must be a [Binding::SingleBound].
+	///
+	/// Panics if the GPU doesn't support the `TEXTURE_BINDING_ARRAY` feature, which is missing on the web and some older GPUs. Whether it does is in [ComputeCapabilities::texture_binding_arrays](crate::ComputeCapabilities::texture_binding_arrays).
+	pub fn add_texture_array_binding(
+		&mut self, render_device: &RenderDevice, images: Vec<Handle<Image>>, sample_type: TextureSampleType,
+		binding: Binding,
+	) -> Result<ShaderBufferHandle, ShaderBufferError> {
+		if !render_device.features().contains(WgpuFeatures::TEXTURE_BINDING_ARRAY) {
+			panic!("Tried to add a texture array binding, but the GPU doesn't support the TEXTURE_BINDING_ARRAY feature");
+		}
+		if images.is_empty() {
+			panic!("Tried to add a texture array binding with no textures in it");
+		}
+		let Binding::SingleBound(..) = binding else {
+			panic!("Tried to add a texture array binding that isn't SingleBound, which is the only binding it supports");
+		};
+		self.check_binding_free(binding)?;
+		Ok(self.store_buffer(
+			binding,
+			ShaderBufferInfo::new(binding, None, |_| ShaderBufferStorage::TextureArray {
+				images: images.clone(),
+				sample_type,
+			}),
+		))
+	}
+
+	/// Replace the textures in a texture array binding made with [add_texture_array_binding](ShaderBufferSet::add_texture_array_binding). If the number of textures changes, so does the layout of the array's group, and the pipelines of the running compute task are rebuilt to match, so there may be a frame or two where no shaders run. Panics if the buffer doesn't exist, isn't a texture array, or `images` is empty.
+	pub fn set_texture_array(&mut self, handle: ShaderBufferHandle, images: Vec<Handle<Image>>) {
+		self.assert_main_world("set the textures of a texture array");
+		if images.is_empty() {
+			panic!("Tried to set the textures of {} to an empty array", handle.describe(self));
+		}
+		let description = handle.describe(self);
+		let Some(ShaderBufferInfo::SingleBound {
+			storage: ShaderBufferStorage::TextureArray { images: current, .. }, ..
+		}) = self.get_mut_buffer(handle)
+		else {
+			panic!("Tried to set the textures of {}, which isn't a texture array", description);
+		};
+		*current = images;
+	}
+
+	/// Bind an existing texture to be read by shaders, with `textureLoad`, or with `textureSampleLevel` and a sampler added with [add_sampler](ShaderBufferSet::add_sampler), such as an input image loaded from a file for bilinear lookups. It's bound as a `texture_2d` in WGSL, and the shaders don't run until it's been uploaded to the GPU. The image can be that of a texture buffer, from [image_handle](ShaderBufferSet::image_handle), to read it with a sampler, but not in a step that also writes to it. The image belongs to whoever added it, so it's left alone when the binding is deleted.
+	/// - image: The image to bind, which must have been made with [TextureUsages::TEXTURE_BINDING], as images are by default.
+	/// - sample_type: How the texture is read, which must suit its format, such as [TextureSampleType::Float] with `filterable: false` for `R32Float` and other formats many GPUs can't filter, which is `texture_2d<f32>` in WGSL, [TextureSampleType::Uint] for `texture_2d<u32>`, [TextureSampleType::Sint] for `texture_2d<i32>`, or [TextureSampleType::Depth] for `texture_depth_2d`. The format's own is given by [TextureFormat::sample_type].
+	/// - binding: How the texture will be bound for access from the shader. This must be a [Binding::SingleBound].
+	pub fn add_sampled_texture(
+		&mut self, image: Handle<Image>, sample_type: TextureSampleType, binding: Binding,
+	) -> Result<ShaderBufferHandle, ShaderBufferError> {
+		let Binding::SingleBound(..) = binding else {
+			panic!("Tried to add a sampled texture that isn't SingleBound, which is the only binding it supports");
+		};
+		self.check_binding_free(binding)?;
+		Ok(self.store_buffer(
+			binding,
+			ShaderBufferInfo::new(binding, None, |_| ShaderBufferStorage::SampledTexture {
+				image: image.clone(),
+				sample_type,
+			}),
+		))
+	}
+
+	/// Bind an existing image, such as a heightmap loaded with the `AssetServer`, as a texture buffer, without copying it, so shaders read it, or write it in place. The image doesn't need to have loaded yet. Until it has, and been uploaded to the GPU, its group has no bind group and the shaders don't run, and the format and size of the buffer aren't known. Once it's loaded, its usages are extended with the ones it needs to be bound, and it's bound as it would be if it had been added with [add_texture_fill](ShaderBufferSet::add_texture_fill), or [add_sampled_texture](ShaderBufferSet::add_sampled_texture), with the sample type of its format. The image belongs to whoever added it, so it's left alone when the binding is deleted, and isn't counted towards the [memory budget](ShaderBufferSet::set_memory_budget).
+	/// - image: The image to bind, which can be 2D, 3D or an array of 2D layers.
+	/// - binding: How the texture will be bound for access from the shader. This must be a [Binding::SingleBound].
+	/// - access: How shaders access it as a storage texture, or `None` to bind it as a sampled texture, to be read with `textureLoad`, or with `textureSampleLevel` and a sampler added with [add_sampler](ShaderBufferSet::add_sampler). Images loaded from files are usually in an sRGB format, such as [Rgba8UnormSrgb](TextureFormat::Rgba8UnormSrgb), which is stored in the linear format for a storage texture, as described on [add_texture_fill](ShaderBufferSet::add_texture_fill).
+	///
+	/// Once the image has loaded, this panics for the same reasons as [add_texture_fill](ShaderBufferSet::add_texture_fill) if it's bound as a storage texture, or if it's a 1D image, or its format can't be sampled.
+	pub fn add_texture_from_handle(
+		&mut self, image: Handle<Image>, binding: Binding, access: Option<StorageTextureAccess>,
+	) -> Result<ShaderBufferHandle, ShaderBufferError> {
+		let Binding::SingleBound(..) = binding else {
+			panic!("Tried to add a texture from a handle that isn't SingleBound, which is the only binding it supports");
+		};
+		self.check_binding_free(binding)?;
+		Ok(self.store_buffer(
+			binding,
+			ShaderBufferInfo::new(binding, None, |_| ShaderBufferStorage::LoadingTexture { image: image.clone(), access }),
+		))
+	}
+
+	/// Whether any texture added with [add_texture_from_handle](ShaderBufferSet::add_texture_from_handle) is still waiting for its image to load, in which case the layout of its group isn't known yet.
+	pub(crate) fn textures_loading(&self) -> bool {
+		self.buffers.values().any(|buffer| matches!(buffer.front_storage(), ShaderBufferStorage::LoadingTexture { .. }))
+	}
+
+	/// Bind every texture added with [add_texture_from_handle](ShaderBufferSet::add_texture_from_handle) whose image has loaded since, adding the usages it needs to the image.
+	fn load_textures(&mut self, images: &mut Assets<Image>) {
+		let loading = self
+			.buffers
+			.iter()
+			.filter_map(|(id, buffer)| match buffer {
+				ShaderBufferInfo::SingleBound { binding, storage: ShaderBufferStorage::LoadingTexture { image, access } } => {
+					Some((*id, *binding, image.clone(), *access))
+				}
+				_ => None,
+			})
+			.collect::<Vec<_>>();
+		for (id, (group, binding), handle, access) in loading {
+			let Some(image) = images.get_mut(&handle) else {
+				continue;
+			};
+			let format = image.texture_descriptor.format;
+			let size = image.texture_descriptor.size;
+			let storage = match access {
+				None => {
+					let Some(sample_type) = format.sample_type(None, None) else {
+						panic!("Tried to bind an image of format {:?} as a sampled texture, but it can't be sampled", format);
+					};
+					image.texture_descriptor.usage |= TextureUsages::TEXTURE_BINDING;
+					ShaderBufferStorage::SampledTexture { image: handle, sample_type }
+				}
+				Some(access) => {
+					let dimension = match (image.texture_descriptor.dimension, size.depth_or_array_layers) {
+						(TextureDimension::D1, _) => {
+							panic!("Tried to bind a 1D image as a storage texture, but only 2D and 3D ones can be")
+						}
+						(TextureDimension::D2, 1) => TextureViewDimension::D2,
+						(TextureDimension::D2, _) => TextureViewDimension::D2Array,
+						(TextureDimension::D3, _) => TextureViewDimension::D3,
+					};
+					self.check_texture_format(format, access, Binding::SingleBound(group, binding));
+					image.texture_descriptor.usage |= TextureUsages::COPY_SRC
+						| TextureUsages::COPY_DST
+						| TextureUsages::STORAGE_BINDING
+						| TextureUsages::TEXTURE_BINDING;
+					// As with add_texture_fill, an sRGB image is stored in the linear format, keeping its own view in
+					// the sRGB format for sprites and materials, as the bytes are the same either way.
+					let srgb = format.is_srgb();
+					if srgb {
+						image.texture_descriptor.format = format.remove_srgb_suffix();
+						image.texture_descriptor.view_formats = srgb_view_formats(format);
+					}
+					if srgb || dimension != TextureViewDimension::D2 {
+						image.texture_view_descriptor =
+							Some(TextureViewDescriptor { format: srgb.then_some(format), dimension: Some(dimension), ..default() });
+					}
+					let format = format.remove_srgb_suffix();
+					ShaderBufferStorage::StorageTexture { format, srgb, access, size, dimension, image: handle, owned: false }
+				}
+			};
+			if let Some(ShaderBufferInfo::SingleBound { storage: current, .. }) = self.buffers.get_mut(&id) {
+				*current = storage;
+			}
+		}
+	}
+
+	/// Add a sampler, for shaders to read sampled textures with, such as with `textureSampleLevel` for bilinear lookups. It's bound as a `sampler` in WGSL, or a `sampler_comparison` if the descriptor has a `compare` function. A sampler with any linear filter can only be used with textures whose sample type is filterable.
+	/// - render_device: The `RenderDevice` resource from Bevy.
+	/// - descriptor: How the sampler filters and addresses the texture.
+	/// - binding: How the sampler will be bound for access from the shader. This must be a [Binding::SingleBound].
+	pub fn add_sampler(
+		&mut self, render_device: &RenderDevice, descriptor: &SamplerDescriptor, binding: Binding,
+	) -> Result<ShaderBufferHandle, ShaderBufferError> {
+		let Binding::SingleBound(..) = binding else {
+			panic!("Tried to add a sampler that isn't SingleBound, which is the only binding it supports");
+		};
+		self.check_binding_free(binding)?;
+		let filters = [descriptor.mag_filter, descriptor.min_filter, descriptor.mipmap_filter];
+		let binding_type = if descriptor.compare.is_some() {
+			SamplerBindingType::Comparison
+		} else if filters.contains(&FilterMode::Linear) {
+			SamplerBindingType::Filtering
+		} else {
+			SamplerBindingType::NonFiltering
+		};
+		// A label given in the descriptor wins over one from labeled.
+		let label = descriptor.label.or(self.next_label.as_deref());
+		let sampler = render_device.create_sampler(&SamplerDescriptor { label, ..descriptor.clone() });
+		Ok(self.store_buffer(
+			binding,
+			ShaderBufferInfo::new(binding, None, |_| ShaderBufferStorage::Sampler { sampler: sampler.clone(), binding_type }),
+		))
+	}
+
+	/// Make the bind group of a group, with a layout from [ShaderBufferRenderSet::bind_group_layouts] with `per_entity` false, and the texture views from [ShaderBufferRenderSet::prepare_texture_views]. Fails with [ShaderBufferError::ImageNotUploaded] if a texture hasn't been uploaded yet, in which case the bind group can be made in a later frame. [ShaderBufferRenderSet::bind_groups] only calls this for the groups that bind something different than the bind group it already has.
+	fn bind_group(
+		&self, group: usize, device: &RenderDevice, gpu_images: &RenderAssets<GpuImage>, fallback: &FallbackImage,
+		render_buffers: &ShaderBufferRenderSet, layout: &BindGroupLayout,
+	) -> Result<BindGroup, ShaderBufferError> {
+		let buffer_ids = &self.groups[group];
+		// Texture arrays are bound as a slice of views, which has to outlive the entries.
+		let arrays = buffer_ids
+			.iter()
+			.map(|id| self.buffers.get(id).unwrap().front_storage().texture_array_views(gpu_images, fallback))
+			.collect::<Vec<_>>();
+		let entries = buffer_ids
+			.iter()
+			.zip(&arrays)
+			.map(|(id, array)| {
+				let buffer = self.buffers.get(id).unwrap();
+				let view = self.texture_view(*id);
+				buffer.bind_group_entries(gpu_images, &render_buffers.texture_views, view, array.as_deref())
+			})
+			.collect::<Result<Vec<_>, _>>()?;
+		Ok(device.create_bind_group(format!("compute group {}", group).as_str(), layout, entries.concat().as_slice()))
+	}
+
+	/// What every group's bind group from [bind_group](ShaderBufferSet::bind_group) binds, so the render set can tell when a group's bind group binds something different than it did last frame.
+	fn bound_resources(&self, gpu_images: &RenderAssets<GpuImage>) -> Vec<Vec<BoundResource>> {
+		self
+			.groups
+			.iter()
+			.map(|buffer_ids| {
+				buffer_ids
+					.iter()
+					.flat_map(|id| self.buffers.get(id).unwrap().bound_resources(gpu_images, self.texture_view(*id)))
+					.collect()
+			})
+			.collect()
+	}
+
+	/// The layout entries of every group. If `per_entity` is true, the per-entity group has the layout of the per-entity buffers, for pipelines run by a [RunShaderPerEntity](crate::ComputeAction::RunShaderPerEntity) action. Otherwise it's empty, like the bind group [bind_group](ShaderBufferSet::bind_group) makes for it.
+	pub(crate) fn bind_group_layout_entries(&self, per_entity: bool) -> Vec<Vec<BindGroupLayoutEntry>> {
+		self
+			.groups
+			.iter()
+			.enumerate()
+			.map(|(group, buffer_ids)| match &self.per_entity_layout {
+				Some((per_entity_group, entry)) if per_entity && *per_entity_group == group as u32 => vec![*entry],
+				_ => buffer_ids
+					.iter()
+					.flat_map(|id| {
+						self.buffers.get(id).unwrap().bind_group_layout_entry(self.visibility(*id), self.double_access(*id))
+					})
+					.collect(),
+			})
+			.collect()
+	}
+
+	/// Whether every texture buffer, including both halves of double buffers, has been uploaded to the GPU, filled with its initial contents. Bevy can spread image uploads over several frames, with a [RenderAssetBytesPerFrame](bevy::render::render_asset::RenderAssetBytesPerFrame) limit, so this isn't always so in the frame a texture is added. It isn't so while a texture added from a handle is still loading either.
+	pub(crate) fn textures_uploaded(&self, gpu_images: &RenderAssets<GpuImage>) -> bool {
+		!self.textures_loading()
+			&& self
+				.buffers
+				.values()
+				.flat_map(ShaderBufferInfo::all_image_handles)
+				.all(|image| gpu_images.get(&image).is_some())
+	}
+
+	/// The group every per-entity buffer is bound in, or `None` if no per-entity buffer has ever been added.
+	pub(crate) fn per_entity_group(&self) -> Option<u32> { self.per_entity_layout.as_ref().map(|(group, _)| *group) }
+
+	/// Fails if a group has been skipped, with no buffers in it, though a higher group has some, such as buffers added in groups 0 and 2 but none in 1. The skipped group's layout is empty, so it won't match a shader that binds anything in it, and wgpu only says so once the pipeline is made. The per-entity group is left out, as its buffers are bound separately.
+	pub(crate) fn check_groups(&self) -> Result<(), String> {
+		let in_use = |group: usize| !self.groups[group].is_empty() || self.per_entity_group() == Some(group as u32);
+		let Some(highest) = (0..self.groups.len()).rev().find(|&group| in_use(group)) else {
+			return Ok(());
+		};
+		match (0..highest).find(|&group| !in_use(group)) {
+			Some(skipped) => Err(format!(
+				"Group {} has no buffers, though group {} does, so its layout is empty, and won't match any shader that binds something in it",
+				skipped, highest
+			)),
+			None => Ok(()),
+		}
+	}
+
+	/// Make the bind group for a single per-entity buffer, or `None` if the buffer no longer exists, such as when its entity was despawned this frame, or if it's a texture that hasn't been uploaded yet.
+	pub(crate) fn per_entity_bind_group(
+		&self, handle: ShaderBufferHandle, device: &RenderDevice, gpu_images: &RenderAssets<GpuImage>,
+		render_buffers: &ShaderBufferRenderSet, layout: &BindGroupLayout,
+	) -> Option<BindGroup> {
+		let buffer = self.buffers.get(&handle.id())?;
+		let ShaderBufferInfo::PerEntity { .. } = buffer else {
+			panic!("Tried to bind {} to an entity, but it isn't a per-entity buffer", handle.describe(self));
+		};
+		let view = self.texture_view(handle.id());
+		let entries = buffer.bind_group_entries(gpu_images, &render_buffers.texture_views, view, None).ok()?;
+		let label = self.buffer_label(handle).unwrap_or("compute per-entity buffer");
+		Some(device.create_bind_group(label, layout, &entries))
+	}
+
+	/// Resize a storage buffer, keeping its handle, so it stays valid wherever it's been kept, including in the steps of a running compute sequence, such as when the number of particles in a simulation grows. A new GPU buffer of `size` bytes is made, with the same usages, and the old one is destroyed, along with both halves of a double buffer. If `preserve` is set, as much of the old contents as fits is copied over, with anything past it zeroed, which needs the buffer to have been made with [BufferUsages::COPY_SRC]. Otherwise, it starts out zeroed. Writes queued before this are made before the copy. Bind groups binding the buffer are made again before the next compute steps run, and [CopyBuffer](crate::ComputeAction::CopyBuffer) steps read back the new size, but shaders that were dispatched with a workgroup count for the old size need it changed, such as with an [UpdateDispatchSizeEvent](crate::UpdateDispatchSizeEvent). Fails without changing anything if the buffer doesn't exist, isn't a storage buffer, or would no longer fit in the [memory budget](ShaderBufferSet::set_memory_budget).
+	/// - handle: The handle to the buffer to be resized.
+	/// - size: The new size of the buffer in bytes.
+	/// - render_device: The [RenderDevice] resouce from Bevy.
+	/// - render_queue: The [RenderQueue] resource from Bevy.
+	/// - preserve: Whether to copy the old contents into the new buffer.
+	pub fn resize_storage(
+		&mut self, handle: ShaderBufferHandle, size: u32, render_device: &RenderDevice, render_queue: &RenderQueue,
+		preserve: bool,
+	) -> Result<(), ShaderBufferError> {
+		self.assert_main_world("resize a buffer");
+		let Some(mut buffer) = self.get_buffer(handle) else {
+			return Err(ShaderBufferError::MissingBuffer { buffer: handle });
+		};
+		let old_bytes = buffer.allocated_bytes();
+		let halves = buffer.storages_mut();
+		let expected = if preserve { "a storage buffer made with BufferUsages::COPY_SRC" } else { "a storage buffer" };
+		let resizable = |storage: &&mut ShaderBufferStorage| match storage {
+			ShaderBufferStorage::Storage { buffer, .. } => !preserve || buffer.usage().contains(BufferUsages::COPY_SRC),
+			_ => false,
+		};
+		if !halves.iter().all(resizable) {
+			return Err(ShaderBufferError::WrongBufferKind { buffer: handle.describe(self), expected });
+		}
+		let new_bytes = size as u64 * halves.len() as u64;
+		if new_bytes > old_bytes {
+			self.fits_budget(new_bytes - old_bytes)?;
+		}
+
+		let mut encoder = render_device.create_command_encoder(&CommandEncoderDescriptor { label: Some("resize_storage") });
+		let mut old = Vec::new();
+		let label = self.buffer_label(handle).map(str::to_owned);
+		let labels = match self.get_buffer(handle) {
+			Some(ShaderBufferInfo::Double { .. }) => {
+				let (back, front) = double_labels(label.as_deref());
+				vec![back, front]
+			}
+			Some(ShaderBufferInfo::Ring { storage, .. }) => ring_labels(label.as_deref(), storage.len() as u32),
+			_ => vec![label],
+		};
+		for (storage, label) in self.get_mut_buffer(handle).unwrap().storages_mut().into_iter().zip(labels) {
+			let ShaderBufferStorage::Storage { buffer, .. } = storage else {
+				unreachable!();
+			};
+			let resized = render_device.create_buffer(&BufferDescriptor {
+				label: label.as_deref(),
+				size: size as u64,
+				usage: buffer.usage(),
+				mapped_at_creation: false,
+			});
+			// Copies have to be a whole number of words, so an odd byte or two at the end is left zeroed.
+			let copied = buffer.size().min(size as u64) / COPY_BUFFER_ALIGNMENT * COPY_BUFFER_ALIGNMENT;
+			if preserve && copied > 0 {
+				encoder.copy_buffer_to_buffer(buffer, 0, &resized, 0, copied);
+			}
+			old.push(std::mem::replace(buffer, resized));
+		}
+		render_queue.submit([encoder.finish()]);
+		// The old buffers are only freed once the copy has been made.
+		for buffer in old {
+			buffer.destroy();
+		}
+		Ok(())
+	}
+
+	/// Delete a buffer. Fails with [ShaderBufferError::MissingBuffer] if it doesn't exist, such as if it's already been deleted, in which case nothing is changed. While a compute sequence is running, deleting a buffer that a step of the running task binds panics at the end of the frame, as the step's pipeline can't be remade without it.
+	/// - handle: The handle to the buffer to be deleted.
+	/// - images: The `Assets<Image>` resource from Bevy.
+	pub fn delete_buffer(
+		&mut self, handle: ShaderBufferHandle, images: &mut Assets<Image>,
+	) -> Result<(), ShaderBufferError> {
+		self.assert_main_world("delete a buffer");
+		if !self.buffers.contains_key(&handle.id()) {
+			return Err(ShaderBufferError::MissingBuffer { buffer: handle });
+		}
+		let buffer = match handle {
+			ShaderBufferHandle::Bound { group, id, .. } => {
+				let buffer = self.buffers.remove(&id);
+				if let Some(buffers) = self.groups.get_mut(group as usize) {
+					if let Some(index) = buffers.iter().position(|buffer_id| *buffer_id == id) {
+						buffers.remove(index);
+					}
+				}
+				buffer
+			}
+			ShaderBufferHandle::Unbound { id } => self.buffers.remove(&id),
+		};
+		self.generation += 1;
+		if let Some(mut buffer) = buffer {
+			let deleted_images = buffer.all_image_handles();
+			self.texture_uploads.lock().unwrap().retain(|upload| !deleted_images.contains(&upload.image));
+			buffer.delete(images);
+		}
+		if let Some(name) = self.buffer_names.remove(&handle.id()) {
+			self.names.remove(&name);
+		}
+		self.labels.remove(&handle.id());
+		self.visibilities.remove(&handle.id());
+		self.double_access.remove(&handle.id());
+		self.texture_views.remove(&handle.id());
+		self.render_inputs.retain(|input| *input != handle);
+		if self.globals == Some(handle) {
+			self.globals = None;
+		}
+		if self.size_uniform.is_some_and(|(size_uniform, _)| size_uniform == handle) {
+			self.size_uniform = None;
+		}
+		if self.cursor_uniform.is_some_and(|cursor| cursor.handle == handle) {
+			self.cursor_uniform = None;
+		}
+		if self.camera_uniform == Some(handle) {
+			self.camera_uniform = None;
+		}
+		if self.chunk_uniform == Some(handle) {
+			self.chunk_uniform = None;
+		}
+		#[cfg(feature = "inspector")]
+		self.inspectables.retain(|inspectable| inspectable.handle != handle);
+		Ok(())
+	}
+
+	/// Delete every buffer, as with [delete_buffer](ShaderBufferSet::delete_buffer), destroying the GPU buffers and removing the images this set owns. This is done for you when the app exits.
+	pub fn delete_all_buffers(&mut self, images: &mut Assets<Image>) {
+		let handles = self.buffers.iter().map(|(id, buffer)| buffer.handle(*id)).collect::<Vec<_>>();
+		for handle in handles {
+			// Every handle was taken from the set just now.
+			let _ = self.delete_buffer(handle, images);
+		}
+	}
+
+	/// Reserve a range of bind groups for a plugin, or any other code that adds its own buffers and runs its own tasks, so it can number its groups from zero, without coordinating with anything else that adds buffers to this set. See [BindingNamespace] for how to use it. The groups reserved come after every group in use when this is called, whether by buffers or by other namespaces, so buffers added without a namespace should be added first, or in groups of their own below the namespaces'. Adding a namespace that's already been added with the same number of groups returns it again, so a plugin can ask for it wherever it needs it. Panics if the name has already been used with a different number of groups.
+	pub fn add_namespace(&mut self, name: impl Into<String>, groups: u32) -> BindingNamespace {
+		self.assert_main_world("add a binding namespace");
+		let name = name.into();
+		if let Some(namespace) = self.namespaces.iter().find(|namespace| namespace.name == name) {
+			if namespace.groups != groups {
+				panic!(
+					"Tried to add binding namespace \"{}\" with {} groups, but it was already added with {}",
+					name, groups, namespace.groups
+				);
+			}
+			return namespace.clone();
+		}
+		let first_group = self
+			.namespaces
+			.iter()
+			.map(|namespace| namespace.first_group + namespace.groups)
+			.chain(self.per_entity_group().map(|group| group + 1))
+			.chain([self.groups.len() as u32])
+			.max()
+			.unwrap();
+		let namespace = BindingNamespace { name, first_group, groups };
+		self.namespaces.push(namespace.clone());
+		namespace
+	}
+
+	/// Give a buffer a name, so it can later be looked up with [handle_by_name](ShaderBufferSet::handle_by_name), for instance when compute tasks are loaded from a file and can't refer to handles directly. If the buffer already has a name, it's renamed, and the old name is freed. Names must be unique, so this panics if the name is already in use by a different buffer, or if the buffer doesn't exist. Deleting the buffer frees its name.
+	pub fn name_buffer(&mut self, handle: ShaderBufferHandle, name: impl Into<String>) {
+		self.assert_main_world("name a buffer");
+		let name = name.into();
+		if !self.buffers.contains_key(&handle.id()) {
+			panic!("Tried to name buffer {} \"{}\", but it doesn't exist", handle.describe(self), name);
+		}
+		if let Some(existing) = self.names.get(&name) {
+			if *existing != handle {
+				panic!(
+					"Tried to name buffer {} \"{}\", but that name is already used by {}",
+					handle.describe(self),
+					name,
+					existing.describe(self)
+				);
+			}
+			return;
+		}
+		if let Some(old_name) = self.buffer_names.insert(handle.id(), name.clone()) {
+			self.names.remove(&old_name);
+		}
+		self.names.insert(name, handle);
+	}
+
+	/// Get the name a buffer was given with [name_buffer](ShaderBufferSet::name_buffer), or `None` if it doesn't have one.
+	pub fn buffer_name(&self, handle: ShaderBufferHandle) -> Option<&str> {
+		self.buffer_names.get(&handle.id()).map(String::as_str)
+	}
+
+	/// Label the next buffer added, as in `buffers.labeled("particles").add_storage_init(...)`, so its GPU buffers or textures carry the label in GPU captures, such as RenderDoc's, and in wgpu's validation errors, with " (front)" and " (back)" after it for the halves of a double buffer, as they start out. The label is also shown by [describe](ShaderBufferHandle::describe), and so in the crate's own panics. Unlike a [name](ShaderBufferSet::name_buffer), it doesn't have to be unique, and buffers can't be looked up by it. A sampler is labeled too, unless its descriptor has a label of its own. Texture arrays, sampled textures and textures added from a handle bind images that belong to whoever added them, so they keep their own labels. Labeling a texture leaks the label, as images only take static labels, so this is best kept to buffers that are added once, rather than over and over.
+	pub fn labeled(&mut self, label: impl Into<String>) -> &mut Self {
+		self.assert_main_world("label a buffer");
+		self.next_label = Some(label.into());
+		self
+	}
+
+	/// Get the label a buffer was added with, after [labeled](ShaderBufferSet::labeled), or `None` if it wasn't given one.
+	pub fn buffer_label(&self, handle: ShaderBufferHandle) -> Option<&str> {
+		self.labels.get(&handle.id()).map(String::as_str)
+	}
+
+	/// Look up a buffer by the name it was given with [name_buffer](ShaderBufferSet::name_buffer), or `None` if no buffer has that name.
+	pub fn handle_by_name(&self, name: &str) -> Option<ShaderBufferHandle> { self.names.get(name).copied() }
+
+	/// Get the image handle for a texture buffer. If the provided buffer isn't a texture buffer, it will just return `None`. If the provided buffer is a double buffer, it will return the image handle for the current front buffer.
+	pub fn image_handle(&self, handle: ShaderBufferHandle) -> Option<Handle<Image>> {
+		if let Some(buffer) = self.get_buffer(handle) {
+			buffer.image_handle()
+		} else {
+			None
+		}
+	}
+
+	/// Get the image handles for both halves of a double buffered texture, labelled with which is currently the front buffer and which the back. This is evaluated at the time of the call, so after a [SwapBuffers](crate::ComputeAction::SwapBuffers), the two will have traded places. If the provided buffer isn't a double buffered texture, it will just return `None`, though [image_handle](ShaderBufferSet::image_handle) still works for single texture buffers.
+	///
+	/// For instance, to display both halves side by side, and keep them labelled correctly through any number of swaps:
+	///
+	/// ```Rust
+	/// #[derive(Component)]
+	/// enum Half {
+	///     Front,
+	///     Back,
+	/// }
+	///
+	/// fn setup(mut commands: Commands, buffer_set: Res<ShaderBufferSet>, texture: Res<MyTexture>) {
+	///     let images = buffer_set.image_handles(texture.0).unwrap();
+	///     commands.spawn((Sprite::from_image(images.front), Transform::from_xyz(-200.0, 0.0, 0.0), Half::Front));
+	///     commands.spawn((Sprite::from_image(images.back), Transform::from_xyz(200.0, 0.0, 0.0), Half::Back));
+	/// }
+	///
+	/// fn track_halves(mut sprites: Query<(&mut Sprite, &Half)>, buffer_set: Res<ShaderBufferSet>, texture: Res<MyTexture>) {
+	///     let images = buffer_set.image_handles(texture.0).unwrap();
+	///     for (mut sprite, half) in sprites.iter_mut() {
+	///         sprite.image = match half {
+	///             Half::Front => images.front.clone(),
+	///             Half::Back => images.back.clone(),
+	///         };
+	///     }
+	/// }
+	/// ```
+	pub fn image_handles(&self, handle: ShaderBufferHandle) -> Option<DoubleBufferImages> {
+		self.get_buffer(handle)?.image_handles()
+	}
+
+	/// Get the image of the texture bound at a binding of a texture buffer, as shaders see it right now, so the CPU can tell which half of a double buffered texture a shader reads from its front binding, and which it writes at its back binding. Each swap trades them. Returns `None` if the buffer doesn't exist, isn't a texture, or isn't bound at that binding.
+	pub fn image_at_binding(&self, handle: ShaderBufferHandle, binding: u32) -> Option<Handle<Image>> {
+		self.get_buffer(handle)?.image_at_binding(binding)
+	}
+
+	/// The number of [rings](Binding::Ring) there can be at once, one for each component of the [ring_positions](crate::ComputeGlobals::ring_positions) in the globals.
+	pub const RING_SLOTS: u32 = 4;
+
+	/// Get the slot of a [ring](Binding::Ring), which is the component of the [ring_positions](crate::ComputeGlobals::ring_positions) in the globals that holds its position. A ring gets the lowest slot no other ring has when it's added, so the first ring gets slot 0, and WGSL reads its position as `globals.ring_positions.x`. Returns `None` if the buffer doesn't exist or isn't a ring.
+	pub fn ring_slot(&self, handle: ShaderBufferHandle) -> Option<u32> {
+		match self.buffers.get(&handle.id())? {
+			ShaderBufferInfo::Ring { slot, .. } => Some(*slot),
+			_ => None,
+		}
+	}
+
+	/// Get the position of a [ring](Binding::Ring), which is the index of its current buffer, from 0 for the one at its first binding. Each [RotateBuffers](crate::ComputeAction::RotateBuffers) step moves it on by one, wrapping around to 0 after the last buffer. Like swaps, the rotations made by a running compute task show up here once the frame they were made in is done. Returns `None` if the buffer doesn't exist or isn't a ring.
+	pub fn ring_position(&self, handle: ShaderBufferHandle) -> Option<u32> {
+		match self.buffers.get(&handle.id())? {
+			ShaderBufferInfo::Ring { position, .. } => Some(*position),
+			_ => None,
+		}
+	}
+
+	/// The slot and the number of buffers of a ring, or `None` if the buffer doesn't exist or isn't a ring.
+	pub(crate) fn ring_slot_and_depth(&self, handle: ShaderBufferHandle) -> Option<(u32, u32)> {
+		match self.buffers.get(&handle.id())? {
+			ShaderBufferInfo::Ring { slot, storage, .. } => Some((*slot, storage.len() as u32)),
+			_ => None,
+		}
+	}
+
+	/// The positions of every ring, each at its slot, as they're written to the globals.
+	pub(crate) fn ring_positions(&self) -> UVec4 {
+		let mut positions = UVec4::ZERO;
+		for buffer in self.buffers.values() {
+			if let ShaderBufferInfo::Ring { slot, position, .. } = buffer {
+				positions[*slot as usize] = *position;
+			}
+		}
+		positions
+	}
+
+	/// The lowest slot no ring has, or `None` if they're all taken.
+	fn free_ring_slot(&self) -> Option<u32> {
+		(0..Self::RING_SLOTS).find(|free| {
+			!self.buffers.values().any(|buffer| matches!(buffer, ShaderBufferInfo::Ring { slot, .. } if slot == free))
+		})
+	}
+
+	/// Get the GPU buffer, as a [bevy_render::render_resource::buffer], for a storage or uniform buffer. If the provided buffer isn't a storage or uniform buffer, it will just return `None`. If the provided buffer is a double buffer, it will return the GPU buffer for the current front buffer, and if it's a ring, the one at the ring's position.
+	///
+	/// The [ShaderBufferSet] is extracted to the render world, so this can also be called from render world systems, for instance to bind a buffer written by a compute shader as vertex or index data in a custom render command. The compute node runs before the camera driver node in the render graph, so anything a compute step writes in a frame is visible to draws in the same frame.
+	pub fn gpu_buffer(&self, handle: ShaderBufferHandle) -> Option<Buffer> {
+		if let Some(buffer) = self.get_buffer(handle) {
+			buffer.gpu_buffer()
+		} else {
+			None
+		}
+	}
+
+	/// The halves of a buffer a [ClearBuffer](crate::ComputeAction::ClearBuffer) or [ClearTexture](crate::ComputeAction::ClearTexture) step clears, as it is now, which is the back buffer of a double buffer, or both halves with [ClearHalves::Both], and any other buffer itself.
+	fn cleared_storages(&self, handle: ShaderBufferHandle, halves: ClearHalves) -> Vec<&ShaderBufferStorage> {
+		let Some(buffer) = self.buffers.get(&handle.id()) else {
+			return Vec::new();
+		};
+		match (buffer.back_storage(), halves) {
+			(Some(back), ClearHalves::Back) => vec![back],
+			(Some(back), ClearHalves::Both) => vec![buffer.front_storage(), back],
+			(None, _) => vec![buffer.front_storage()],
+		}
+	}
+
+	/// The GPU buffers a [ClearBuffer](crate::ComputeAction::ClearBuffer) step clears, which are none if the buffer doesn't exist or is a texture.
+	pub(crate) fn cleared_gpu_buffers(&self, handle: ShaderBufferHandle, halves: ClearHalves) -> Vec<Buffer> {
+		self.cleared_storages(handle, halves).into_iter().filter_map(ShaderBufferStorage::gpu_buffer).collect()
+	}
+
+	/// The images a [ClearTexture](crate::ComputeAction::ClearTexture) step clears, which are none if the texture doesn't exist or is a buffer.
+	pub(crate) fn cleared_images(&self, handle: ShaderBufferHandle, halves: ClearHalves) -> Vec<Handle<Image>> {
+		self.cleared_storages(handle, halves).into_iter().filter_map(ShaderBufferStorage::image_handle).collect()
+	}
+
+	/// Record the copy of a [CopyTextureToBuffer](crate::ComputeAction::CopyTextureToBuffer) step, or of a [CopyBufferToTexture](crate::ComputeAction::CopyBufferToTexture) step if `into_texture` is set, between the front buffers of a storage texture and a storage buffer, with no padding between the rows in the buffer. Rows whose size the GPU can copy without padding are copied a layer at a time, and any others a row at a time.
+	pub(crate) fn copy_texture_rows(
+		&self, texture: ShaderBufferHandle, buffer: ShaderBufferHandle, into_texture: bool,
+		gpu_images: &RenderAssets<GpuImage>, encoder: &mut CommandEncoder,
+	) {
+		let (Some(image), Some((format, size)), Some(gpu_buffer)) =
+			(self.image_handle(texture), self.texture_format_and_size(texture), self.gpu_buffer(buffer))
+		else {
+			panic!(
+				"Tried to copy between {} and {}, but one of them has been deleted",
+				texture.describe(self),
+				buffer.describe(self)
+			);
+		};
+		// The texture may not have been uploaded yet, in which case there's nothing to copy.
+		let Some(gpu_image) = gpu_images.get(&image) else {
+			return;
+		};
+		let row = size.width * format.block_copy_size(None).unwrap();
+		let rows = if row % COPY_BYTES_PER_ROW_ALIGNMENT == 0 { size.height } else { 1 };
+		for z in 0..size.depth_or_array_layers {
+			for y in (0..size.height).step_by(rows as usize) {
+				let copy_buffer = ImageCopyBuffer {
+					buffer: &gpu_buffer,
+					layout: ImageDataLayout {
+						offset: (z as u64 * size.height as u64 + y as u64) * row as u64,
+						bytes_per_row: (rows > 1).then_some(row),
+						rows_per_image: None,
+					},
+				};
+				let copy_texture = ImageCopyTexture {
+					texture: &gpu_image.texture,
+					mip_level: 0,
+					origin: Origin3d { x: 0, y, z },
+					aspect: TextureAspect::All,
+				};
+				let extent = Extent3d { width: size.width, height: rows, depth_or_array_layers: 1 };
+				if into_texture {
+					encoder.copy_buffer_to_texture(copy_buffer, copy_texture, extent);
+				} else {
+					encoder.copy_texture_to_buffer(copy_texture, copy_buffer, extent);
+				}
+			}
+		}
+	}
+
+	/// Whether a buffer is a storage buffer that was added as read-only, so shaders can't write to it.
+	pub(crate) fn readonly(&self, handle: ShaderBufferHandle) -> bool {
+		self
+			.get_buffer(handle)
+			.is_some_and(|buffer| matches!(buffer.front_storage(), ShaderBufferStorage::Storage { readonly: true, .. }))
+	}
+
+	/// The format and size of a texture buffer, or of the front buffer of a double buffered one, or `None` if the buffer doesn't exist or isn't a texture.
+	pub(crate) fn texture_format_and_size(&self, handle: ShaderBufferHandle) -> Option<(TextureFormat, Extent3d)> {
+		self.get_buffer(handle)?.front_storage().texture_format_and_size()
+	}
+
+	/// How shaders see a texture buffer, such as [D3](TextureViewDimension::D3) for one added with [add_texture_fill_3d](ShaderBufferSet::add_texture_fill_3d), or `None` if the buffer doesn't exist or isn't a texture.
+	pub(crate) fn texture_dimension(&self, handle: ShaderBufferHandle) -> Option<TextureViewDimension> {
+		self.get_buffer(handle)?.front_storage().texture_dimension()
+	}
+
+	/// The size of the storage texture a [FromTexture](crate::DispatchSize::FromTexture) dispatch covers, as its width, height and depth or number of layers, or `None` while it's still loading, returning what's wrong if it doesn't exist or isn't a storage texture.
+	pub(crate) fn dispatch_texture_size(&self, handle: ShaderBufferHandle) -> Result<Option<UVec3>, String> {
+		let Some(buffer) = self.get_buffer(handle) else {
+			return Err(format!("Tried to dispatch over the size of {}, which doesn't exist", handle.describe(self)));
+		};
+		match buffer.front_storage() {
+			ShaderBufferStorage::StorageTexture { size, .. } => {
+				Ok(Some(UVec3::new(size.width, size.height, size.depth_or_array_layers)))
+			}
+			ShaderBufferStorage::LoadingTexture { access: Some(_), .. } => Ok(None),
+			_ => Err(format!("Tried to dispatch over the size of {}, which isn't a storage texture", handle.describe(self))),
+		}
+	}
+
+	/// Both halves of a double buffer, in order, along with whether the second is the front buffer, or just the one half of any other buffer, or `None` if the buffer doesn't exist. Panics if it's a texture array or sampled texture, whose images belong to whoever added it, or a sampler, which has no contents.
+	#[cfg(feature = "checkpoint")]
+	pub(crate) fn raw_storage(&self, handle: ShaderBufferHandle) -> Option<(Vec<RawStorage>, bool)> {
+		let raw = |storage: &ShaderBufferStorage| match storage {
+			ShaderBufferStorage::Storage { buffer, .. } => RawStorage::Buffer { buffer: buffer.clone(), uniform: false },
+			ShaderBufferStorage::Uniform(buffer) | ShaderBufferStorage::DynamicUniform { buffer, .. } => {
+				RawStorage::Buffer { buffer: buffer.clone(), uniform: true }
+			}
+			ShaderBufferStorage::StorageTexture { image, format, size, .. } => {
+				RawStorage::Texture { image: image.clone(), format: *format, size: *size }
+			}
+			ShaderBufferStorage::TextureArray { .. } => {
+				panic!("Tried to get the contents of {}, which is a texture array", handle.describe(self))
+			}
+			ShaderBufferStorage::SampledTexture { .. } => {
+				panic!("Tried to get the contents of {}, which is a sampled texture", handle.describe(self))
+			}
+			ShaderBufferStorage::LoadingTexture { .. } => {
+				panic!("Tried to get the contents of {}, whose image is still loading", handle.describe(self))
+			}
+			ShaderBufferStorage::Sampler { .. } => {
+				panic!("Tried to get the contents of {}, which is a sampler", handle.describe(self))
+			}
+		};
+		Some(match self.buffers.get(&handle.id())? {
+			ShaderBufferInfo::SingleBound { storage, .. }
+			| ShaderBufferInfo::SingleUnbound { storage }
+			| ShaderBufferInfo::PerEntity { storage, .. } => (vec![raw(storage)], false),
+			ShaderBufferInfo::Double { storage: (storage1, storage2), front, .. } => {
+				(vec![raw(storage1), raw(storage2)], *front == FrontBuffer::Second)
+			}
+			ShaderBufferInfo::Ring { storage, .. } => (storage.iter().map(raw).collect(), false),
+		})
+	}
+
+	/// Make the first or second half of a double buffer the front buffer, whichever it is now.
+	#[cfg(feature = "checkpoint")]
+	pub(crate) fn set_front_half(&mut self, handle: ShaderBufferHandle, second: bool) {
+		if self.raw_storage(handle).is_some_and(|(_, front_is_second)| front_is_second != second) {
+			self.swap_front_buffer(handle);
+		}
+	}
+
+	/// Queue a write of a whole texture, given its texels tightly packed, like [write_texture_region](ShaderBufferSet::write_texture_region), but to a single image, whichever half of a double buffer it is.
+	#[cfg(feature = "checkpoint")]
+	pub(crate) fn write_whole_texture(&self, image: Handle<Image>, format: TextureFormat, size: Extent3d, data: Vec<u8>) {
+		let bytes_per_row = size.width * format.block_copy_size(None).unwrap();
+		self.texture_uploads.lock().unwrap().push(TextureUpload { image, origin: UVec2::ZERO, size, bytes_per_row, data });
+	}
+
+	/// Get the layout entries a buffer contributes to its group's bind group layout, so external pipelines can build layouts compatible with the bind groups bevy_compute makes. This is one entry for most buffers, two for a double buffer, and none for an unbound buffer. Returns `None` if the buffer doesn't exist. The entries are visible to the stages set with [set_visibility](ShaderBufferSet::set_visibility), which is only [ShaderStages::COMPUTE] by default.
+	pub fn layout_entry_for(&self, handle: ShaderBufferHandle) -> Option<Vec<BindGroupLayoutEntry>> {
+		let buffer = self.buffers.get(&handle.id())?;
+		Some(buffer.bind_group_layout_entry(self.visibility(handle.id()), self.double_access(handle.id())))
+	}
+
+	/// Get the layout entries of a whole group, in the order its buffers were added, which is every entry [layout_entry_for](ShaderBufferSet::layout_entry_for) gets for the buffers in it. Returns `None` if the group doesn't exist. For the per-entity group, this is empty, like the layout of the bind group bevy_compute binds for it outside of per-entity shaders.
+	pub fn layout_entries_for_group(&self, group: u32) -> Option<Vec<BindGroupLayoutEntry>> {
+		self.bind_group_layout_entries(false).into_iter().nth(group as usize)
+	}
+
+	/// Make a bind group layout with the entries of a group, from [layout_entries_for_group](ShaderBufferSet::layout_entries_for_group), for a pipeline of your own that binds the group's bind group from [ComputeBindGroups](crate::ComputeBindGroups), such as a material's fragment shader reading a buffer a compute shader fills, with the buffer made visible to it with [set_visibility](ShaderBufferSet::set_visibility). Unlike [ShaderBufferRenderSet::bind_group_layout], this works in either world, and before any compute sequence has run, but it makes a new layout every time, so call it once and keep it until [layout_generation](ShaderBufferRenderSet::layout_generation) changes. wgpu treats layouts with the same entries as the same, so it works with the bind groups bevy_compute makes all the same. Returns `None` if the group doesn't exist.
+	pub fn bind_group_layout_for_group(&self, group: u32, device: &RenderDevice) -> Option<BindGroupLayout> {
+		let entries = self.layout_entries_for_group(group)?;
+		Some(device.create_bind_group_layout(format!("compute group {} layout", group).as_str(), &entries))
+	}
+
+	/// Set the shader stages a buffer is visible to in its group's bind group layout, so the same bind group can be used in a render pipeline, for instance with [ShaderStages::FRAGMENT] to read a texture a compute shader wrote in a fullscreen pass. [ShaderStages::COMPUTE] is always included, as the compute shaders still need it. This changes the layout of the whole group. The pipelines of the running compute task are rebuilt to match, but any pipeline of your own built against the old layout must be rebuilt too. Panics if the buffer doesn't exist, or is a per-entity buffer, as every per-entity buffer shares one layout.
+	pub fn set_visibility(&mut self, handle: ShaderBufferHandle, stages: ShaderStages) {
+		self.assert_main_world("set the visibility of a buffer");
+		let Some(buffer) = self.buffers.get(&handle.id()) else {
+			panic!("Tried to set the visibility of {}, which doesn't exist", handle.describe(self));
+		};
+		if let ShaderBufferInfo::PerEntity { .. } = buffer {
+			panic!("Tried to set the visibility of {}, which is a per-entity buffer", handle.describe(self));
+		}
+		self.visibilities.insert(handle.id(), stages | ShaderStages::COMPUTE);
+	}
+
+	/// Set whether the front and back buffers of a double buffered storage buffer are bound read-only. By default, the front buffer is read-only, while the back buffer is read-write, for ping-pong steps that read the front and write the back. Shaders must declare each half with the access it's bound with, `var<storage, read>` for a read-only half and `var<storage, read_write>` otherwise. Making the front read-write lets a step update it in place too, such as to clear what it has consumed, though it then mustn't be read by another invocation in the same dispatch. This changes the layout of the whole group, so the pipelines of the running compute task are rebuilt to match. Panics if the buffer doesn't exist, or isn't a double buffered storage buffer.
+	pub fn set_double_storage_access(&mut self, handle: ShaderBufferHandle, front_readonly: bool, back_readonly: bool) {
+		self.assert_main_world("set the access of a double buffer");
+		let Some(buffer) = self.buffers.get(&handle.id()) else {
+			panic!("Tried to set the access of {}, which doesn't exist", handle.describe(self));
+		};
+		if !matches!(buffer, ShaderBufferInfo::Double { storage: (ShaderBufferStorage::Storage { .. }, _), .. }) {
+			panic!("Tried to set the access of {}, which isn't a double buffered storage buffer", handle.describe(self));
+		}
+		let access = |readonly| if readonly { StorageTextureAccess::ReadOnly } else { StorageTextureAccess::ReadWrite };
+		self.double_access.insert(handle.id(), (access(front_readonly), access(back_readonly)));
+	}
+
+	/// Set the access the front and back buffers of a double buffered storage texture are bound with. By default, the front buffer is read-only, and the back buffer write-only, for ping-pong steps that read the front and write the back. Shaders must declare each half with the access it's bound with, such as `texture_storage_2d<r32float, read_write>` for a [ReadWrite](StorageTextureAccess::ReadWrite) half. Making the back read-write lets a step read what it has already written to it, such as a blur reading the neighbors it has written, and making both read-write lets a step work in place on either half. The access goes with the half, not the texture, so after a [SwapBuffers](crate::ComputeAction::SwapBuffers), the texture that's become the front is bound with the front's access. This changes the layout of the whole group, so the pipelines of the running compute task are rebuilt to match. Panics if the buffer doesn't exist, or isn't a double buffered storage texture, or if a half is read-write, and the GPU can't bind textures of this format that way, as told by [ComputeCapabilities::supports_read_write_storage].
+	pub fn set_double_texture_access(
+		&mut self, handle: ShaderBufferHandle, front_access: StorageTextureAccess, back_access: StorageTextureAccess,
+	) {
+		self.assert_main_world("set the access of a double buffer");
+		let Some(buffer) = self.buffers.get(&handle.id()) else {
+			panic!("Tried to set the access of {}, which doesn't exist", handle.describe(self));
+		};
+		let (
+			ShaderBufferInfo::Double { .. },
+			ShaderBufferStorage::StorageTexture { .. } | ShaderBufferStorage::LoadingTexture { access: Some(_), .. },
+		) = (buffer, buffer.front_storage())
+		else {
+			panic!("Tried to set the access of {}, which isn't a double buffered storage texture", handle.describe(self));
+		};
+		let read_write = front_access == StorageTextureAccess::ReadWrite || back_access == StorageTextureAccess::ReadWrite;
+		if let (true, Some(capabilities), Some((format, _))) =
+			(read_write, &self.capabilities, buffer.front_storage().texture_format_and_size())
+		{
+			if !capabilities.supports_read_write_storage(format.remove_srgb_suffix()) {
+				panic!(
+					"Tried to bind half of {} as ReadWrite, but this GPU can't bind its format, {:?}, that way. Only R32Float, R32Uint and R32Sint are supported everywhere",
+					handle.describe(self),
+					format
+				);
+			}
+		}
+		self.double_access.insert(handle.id(), (front_access, back_access));
+	}
+
+	/// Bind only part of a texture buffer, such as a single mip level or array layer, rather than the whole texture. For a double buffered texture, this applies to both halves. The view is made in the render world the first time it's bound, and kept until the texture changes. Pass `None` to go back to binding the whole texture. Panics if the buffer doesn't exist or isn't a texture buffer.
+	pub fn set_texture_view(&mut self, handle: ShaderBufferHandle, view: Option<TextureViewSpec>) {
+		self.assert_main_world("set the texture view of a buffer");
+		let Some(buffer) = self.buffers.get(&handle.id()) else {
+			panic!("Tried to set the texture view of {}, which doesn't exist", handle.describe(self));
+		};
+		if buffer.image_handle().is_none() {
+			panic!("Tried to set the texture view of {}, which isn't a texture buffer", handle.describe(self));
+		}
+		match view {
+			Some(view) => self.texture_views.insert(handle.id(), view),
+			None => self.texture_views.remove(&handle.id()),
+		};
+	}
+
+	/// Mark a buffer or texture as consumed by rendering in the same frame it's written, such as a lookup texture a material samples, or a buffer a material binds. The compute node is always recorded before the camera driver node in the render graph, and so before every render phase of every camera, so whatever the running compute task writes in a frame is what gets rendered that frame, as long as the task is [submitted with the frame](crate::TaskSubmission::WithFrame) or before it. Render graph nodes outside of the camera driver can be ordered after the compute node too, with [ComputeRenderConsumers](crate::ComputeRenderConsumers). A material can bind a texture by its [image_handle](ShaderBufferSet::image_handle), and a storage buffer by its [gpu_buffer](ShaderBufferSet::gpu_buffer), as a `Buffer` field with the `#[storage(n, read_only, buffer)]` attribute of `AsBindGroup`. Only one half of a double buffer can be bound that way, which changes with every swap, so validating a sequence warns about any of these that are double buffered. Panics if the buffer doesn't exist, or is a texture array, whose images belong to whoever added it.
+	pub fn consume_in_render(&mut self, handle: ShaderBufferHandle) {
+		self.assert_main_world("mark a buffer as consumed by rendering");
+		let Some(buffer) = self.buffers.get(&handle.id()) else {
+			panic!("Tried to mark {} as consumed by rendering, but it doesn't exist", handle.describe(self));
+		};
+		if let ShaderBufferStorage::TextureArray { .. } = buffer.front_storage() {
+			panic!("Tried to mark {} as consumed by rendering, but it's a texture array", handle.describe(self));
+		}
+		if !self.render_inputs.contains(&handle) {
+			self.render_inputs.push(handle);
+		}
+	}
+
+	/// Whether a buffer has been marked as consumed by rendering with [consume_in_render](ShaderBufferSet::consume_in_render).
+	pub fn consumed_in_render(&self, handle: ShaderBufferHandle) -> bool { self.render_inputs.contains(&handle) }
+
+	/// Every buffer marked as consumed by rendering, in the order they were marked.
+	pub(crate) fn render_inputs(&self) -> &[ShaderBufferHandle] { &self.render_inputs }
+
+	/// Get the part of a texture buffer that's bound, as set with [set_texture_view](ShaderBufferSet::set_texture_view). A texture added with an sRGB format is always bound with a view of its own, even if none was set, as its image's view is in the sRGB format, which can't be bound as a storage texture.
+	fn texture_view(&self, id: u32) -> Option<TextureViewSpec> {
+		self.texture_views.get(&id).copied().or_else(|| {
+			let srgb = self.buffers.get(&id)?.front_storage().is_srgb();
+			srgb.then(TextureViewSpec::default)
+		})
+	}
+
+	/// Get the shader stages a buffer is visible to, as set with [set_visibility](ShaderBufferSet::set_visibility).
+	fn visibility(&self, id: u32) -> ShaderStages { self.visibilities.get(&id).copied().unwrap_or(ShaderStages::COMPUTE) }
+
+	/// Get the access the front and back buffers of a double buffer are bound with, as set with [set_double_storage_access](ShaderBufferSet::set_double_storage_access) or [set_double_texture_access](ShaderBufferSet::set_double_texture_access).
+	fn double_access(&self, id: u32) -> (StorageTextureAccess, StorageTextureAccess) {
+		self.double_access.get(&id).copied().unwrap_or(DEFAULT_DOUBLE_ACCESS)
+	}
+
+	/// Render a human-readable table of every buffer in the set, one per line, with its handle and name, binding, kind, size or extent and format, usage flags, and for double buffers, which half is currently the front buffer. This is the same output as the [Display] implementation, and is intended for debugging.
+	pub fn debug_dump(&self) -> String { self.to_string() }
+
+	/// Whether the set has no buffers at all, which is how it starts out, and how it stays if compute is never used.
+	pub fn is_empty(&self) -> bool { self.buffers.is_empty() }
+
+	/// The total bytes allocated on the GPU through this set, counting both halves of double buffers, and textures as well as buffers, but not the images of texture arrays, which belong to whoever added them. Deleting a buffer takes it off the total. This is also reported as the [ALLOCATED_BYTES](crate::ALLOCATED_BYTES) diagnostic by the [ComputeDiagnosticsPlugin](crate::ComputeDiagnosticsPlugin).
+	pub fn allocated_bytes(&self) -> u64 { self.buffers.values().map(ShaderBufferInfo::allocated_bytes).sum() }
+
+	/// The soft limit on the bytes allocated through this set, if there is one. See [set_memory_budget](ShaderBufferSet::set_memory_budget).
+	pub fn memory_budget(&self) -> Option<u64> { self.memory_budget }
+
+	/// Set a soft limit on the bytes allocated through this set, as counted by [allocated_bytes](ShaderBufferSet::allocated_bytes), or remove it with `None`. Any `add_*` call that would take the total over the budget fails with [ShaderBufferError::BudgetExceeded] before anything is allocated, rather than leaving the driver to crash or corrupt memory once the GPU runs out, as integrated GPUs tend to. Buffers already allocated are left alone, even if they're over a budget set later. This is usually set with [memory_budget](crate::ComputePluginSettings::memory_budget) in the plugin's settings.
+	pub fn set_memory_budget(&mut self, budget: Option<u64>) {
+		self.assert_main_world("set the memory budget");
+		self.memory_budget = budget;
+	}
+
+	/// A count of the buffers added to and deleted from this set so far, which changes every time one is, so something that depends on which buffers there are, such as the bind group layouts, can tell when to look again. The render world's copy has the count of the main world's as of the last extract.
+	pub fn generation(&self) -> u32 { self.generation }
+
+	/// Which world this is, as the [ShaderBufferSet] is in both: the main world's is the one to change, and the render world's is a copy of it, made every frame.
+	pub fn world(&self) -> ComputeWorld { self.world }
+
+	/// In debug builds, panic if this is the render world's copy, on which a change would be lost at the next extract, rather than letting it silently do nothing.
+	fn assert_main_world(&self, action: &str) {
+		debug_assert!(
+			self.world == ComputeWorld::Main,
+			"Tried to {} on the render world's ShaderBufferSet, which is replaced by a copy of the main world's every frame, so the change would be lost. Make it on the main world's instead.",
+			action
+		);
+	}
+
+	/// Check whether a buffer of the given number of bytes, counting both halves of a double buffer, would fit in the [memory budget](ShaderBufferSet::set_memory_budget), so it can be left out or made smaller before the `add_*` call fails.
+	pub fn fits_budget(&self, requested: u64) -> Result<(), ShaderBufferError> {
+		let Some(budget) = self.memory_budget else {
+			return Ok(());
+		};
+		let used = self.allocated_bytes();
+		if used.saturating_add(requested) > budget {
+			return Err(ShaderBufferError::BudgetExceeded { requested, used, budget });
+		}
+		Ok(())
+	}
+
+	/// Fail with [ShaderBufferError::BudgetExceeded] if a buffer of the given bytes for each half, or each buffer of a ring, wouldn't fit in the memory budget, before it's allocated.
+	fn reserve(&self, binding: Binding, bytes: u64) -> Result<(), ShaderBufferError> {
+		let halves = match binding {
+			Binding::Double(..) => 2,
+			Binding::Ring(_, _, depth) => depth as u64,
+			_ => 1,
+		};
+		self.fits_budget(bytes * halves)
+	}
+
+	/// Check that a buffer is a double buffer, which can be swapped, returning what's wrong if it isn't, which [swap_front_buffer](ShaderBufferSet::swap_front_buffer) panics with.
+	pub(crate) fn check_swappable(&self, handle: ShaderBufferHandle) -> Result<(), String> {
+		match self.buffers.get(&handle.id()) {
+			None => Err(format!("Attempted to set the front buffer of {}, but it doesn't exist", handle.describe(self))),
+			Some(ShaderBufferInfo::Double { .. }) => Ok(()),
+			Some(_) => {
+				Err(format!("Attempt to set the front buffer of {}, which isn't a double buffer", handle.describe(self)))
+			}
+		}
+	}
+
+	/// Check that a buffer is a ring, which can be rotated, returning what's wrong if it isn't, which [rotate_ring](ShaderBufferSet::rotate_ring) panics with.
+	pub(crate) fn check_rotatable(&self, handle: ShaderBufferHandle) -> Result<(), String> {
+		match self.buffers.get(&handle.id()) {
+			None => Err(format!("Attempted to rotate {}, but it doesn't exist", handle.describe(self))),
+			Some(ShaderBufferInfo::Ring { .. }) => Ok(()),
+			Some(_) => Err(format!("Attempted to rotate {}, which isn't a ring", handle.describe(self))),
+		}
+	}
+
+	/// Check that a buffer can be bound the other way round for one step, with a [BufferOrientation](crate::BufferOrientation), returning what's wrong if it can't.
+	pub(crate) fn check_orientable(&self, handle: ShaderBufferHandle) -> Result<(), String> {
+		match self.buffers.get(&handle.id()) {
+			None => {
+				Err(format!("Tried to set the orientation of {} for a step, but it doesn't exist", handle.describe(self)))
+			}
+			Some(ShaderBufferInfo::Double { .. }) => Ok(()),
+			Some(_) => Err(format!(
+				"Tried to set the orientation of {} for a step, but it isn't a double buffer",
+				handle.describe(self)
+			)),
+		}
+	}
+
+	/// Check that a step can bind element `element` of a buffer, with its [dynamic_offsets](crate::ComputeAction::RunShader::dynamic_offsets), returning what's wrong if it can't.
+	pub(crate) fn check_dynamic_element(&self, handle: ShaderBufferHandle, element: u32) -> Result<(), String> {
+		let Some(buffer) = self.buffers.get(&handle.id()) else {
+			return Err(format!(
+				"Tried to bind element {} of {} for a step, but it doesn't exist",
+				element,
+				handle.describe(self)
+			));
+		};
+		let ShaderBufferStorage::DynamicUniform { buffer, stride, .. } = buffer.front_storage() else {
+			return Err(format!(
+				"Tried to bind element {} of {} for a step, but it isn't a dynamic uniform buffer",
+				element,
+				handle.describe(self)
+			));
+		};
+		let capacity = buffer.size() / stride;
+		if element as u64 >= capacity {
+			return Err(format!(
+				"Tried to bind element {} of {} for a step, but it only holds {} elements",
+				element,
+				handle.describe(self),
+				capacity
+			));
+		}
+		Ok(())
+	}
+
+	/// The dynamic offsets to bind a group with, one for each dynamic uniform buffer in it, in order of binding, as the GPU takes them, which is the offset of the element picked for it in `elements`, or of its first element if it isn't there.
+	pub(crate) fn dynamic_offsets(&self, group: u32, elements: &[(ShaderBufferHandle, u32)]) -> Vec<u32> {
+		let Some(buffer_ids) = self.groups.get(group as usize) else {
+			return Vec::new();
+		};
+		let mut offsets = buffer_ids
+			.iter()
+			.filter_map(|id| match self.buffers.get(id)? {
+				ShaderBufferInfo::SingleBound {
+					binding: (_, binding),
+					storage: ShaderBufferStorage::DynamicUniform { stride, .. },
+				} => {
+					let element = elements.iter().find(|(handle, _)| handle.id() == *id).map_or(0, |(_, element)| *element);
+					Some((*binding, (element as u64 * stride) as u32))
+				}
+				_ => None,
+			})
+			.collect::<Vec<_>>();
+		offsets.sort_by_key(|(binding, _)| *binding);
+		offsets.into_iter().map(|(_, offset)| offset).collect()
+	}
+
+	/// Swap a double buffer the compute steps have already swapped in the render world, queueing the swap for the render world's copy of the set too. Called through [bypass_change_detection](bevy::ecs::change_detection::DetectChangesMut::bypass_change_detection), so the whole set isn't extracted again on every swap.
+	pub(crate) fn mirror_swap(&mut self, handle: ShaderBufferHandle) {
+		self.swap_front_buffer(handle);
+		self.write_queue.queue_swap(handle);
+	}
+
+	pub(crate) fn swap_front_buffer(&mut self, handle: ShaderBufferHandle) {
+		if let Err(error) = self.check_swappable(handle) {
+			panic!("{}", error);
+		}
+		let Some(ShaderBufferInfo::Double { front, .. }) = self.get_mut_buffer(handle) else {
+			panic!("Somehow swapped a double buffer that isn't one");
+		};
+		*front = match front {
+			FrontBuffer::First => FrontBuffer::Second,
+			FrontBuffer::Second => FrontBuffer::First,
+		}
+	}
+
+	/// Rotate a ring the compute steps have already rotated in the render world, by the net number of steps they moved it on in a frame, queueing the rotation for the render world's copy of the set too, the same way as [mirror_swap](ShaderBufferSet::mirror_swap).
+	pub(crate) fn mirror_rotation(&mut self, handle: ShaderBufferHandle, steps: u32) {
+		self.rotate_ring(handle, steps);
+		self.write_queue.queue_rotation(handle, steps);
+	}
+
+	/// Move the position of a ring on by `steps` buffers, wrapping around after the last.
+	pub(crate) fn rotate_ring(&mut self, handle: ShaderBufferHandle, steps: u32) {
+		if let Err(error) = self.check_rotatable(handle) {
+			panic!("{}", error);
+		}
+		let Some(ShaderBufferInfo::Ring { position, storage, .. }) = self.get_mut_buffer(handle) else {
+			panic!("Somehow rotated a ring that isn't one");
+		};
+		*position = (*position + steps) % storage.len() as u32;
+	}
+
+	/// Set the contents of a buffer. The data must be a type that implements [ShaderType], and it must match the size of the buffer. If this is a double buffer, the both buffers will be set. The contents of the [globals](ShaderBufferSet::add_globals) buffer, the [size uniform](ShaderBufferSet::add_size_uniform), the [cursor uniform](ShaderBufferSet::add_cursor_uniform), the [camera uniform](ShaderBufferSet::add_camera_uniform) and the [chunk uniform](ShaderBufferSet::add_chunk_uniform) are owned by the plugin, so setting any of them logs a warning, as it will be overwritten. Fails without writing anything if the buffer doesn't exist, isn't a storage or uniform buffer, or is smaller than the data.
+	///
+	/// The write is queued in the [BufferWriteQueue], and made in the render world before the next frame's compute steps. It doesn't change the set, so setting a buffer every frame doesn't have the whole set extracted to the render world again.
+	pub fn write_buffer<T: ShaderType + WriteInto + Clone>(
+		&self, handle: ShaderBufferHandle, data: T,
+	) -> Result<(), ShaderBufferError> {
+		let buffer = self.writable_buffer(handle)?;
+		let mut bytes = Vec::new();
+		data.write_into(&mut Writer::new(&data, &mut bytes, 0).unwrap());
+		self.check_fits(handle, &buffer, bytes.len())?;
+		if self.plugin_writes(handle) {
+			warn!("Set data on {}, which the plugin writes to, so it will be overwritten", handle.describe(self));
+		}
+		if self.tracer.recording() {
+			self.tracer.defer_write(handle, 0, bytes);
+			return Ok(());
+		}
+		self.write_queue.queue_write_halves(handle, 0, bytes);
+		Ok(())
+	}
+
+	/// The front GPU buffer of a storage or uniform buffer, for writing data to, or why it can't be written.
+	fn writable_buffer(&self, handle: ShaderBufferHandle) -> Result<Buffer, ShaderBufferError> {
+		let Some(buffer) = self.get_buffer(handle) else {
+			return Err(ShaderBufferError::MissingBuffer { buffer: handle });
+		};
+		buffer.gpu_buffer().ok_or_else(|| ShaderBufferError::WrongBufferKind {
+			buffer: handle.describe(self),
+			expected: "a storage or uniform buffer",
+		})
+	}
+
+	/// Check that `size` bytes of data fit in a buffer.
+	fn check_fits(&self, handle: ShaderBufferHandle, buffer: &Buffer, size: usize) -> Result<(), ShaderBufferError> {
+		if size as u64 > buffer.size() {
+			return Err(ShaderBufferError::TooLarge {
+				buffer: handle.describe(self),
+				size: size as u64,
+				capacity: buffer.size(),
+			});
+		}
+		Ok(())
+	}
+
+	/// Set the contents of the front buffer of a double buffered storage or uniform buffer, the one bound to its first binding, as it is when this is called, leaving the back buffer alone, such as to reset the particles a ping-pong step reads from without touching the half it's about to write over. This is [write_back_buffer](ShaderBufferSet::write_back_buffer) for the other half, and like it, the write is queued as [write_buffer](ShaderBufferSet::write_buffer)'s is, but isn't [recorded](ShaderBufferSet::start_recording). Fails without writing anything if the buffer doesn't exist, isn't a double buffered storage or uniform buffer, or is smaller than the data.
+	pub fn write_front_buffer<T: ShaderType + WriteInto>(
+		&self, handle: ShaderBufferHandle, data: T,
+	) -> Result<(), ShaderBufferError> {
+		let Some(buffer) = self.get_buffer(handle) else {
+			return Err(ShaderBufferError::MissingBuffer { buffer: handle });
+		};
+		let front = if let ShaderBufferInfo::Double { .. } = buffer { buffer.gpu_buffer() } else { None };
+		let Some(buffer) = front else {
+			return Err(ShaderBufferError::WrongBufferKind {
+				buffer: handle.describe(self),
+				expected: "a double buffered storage or uniform buffer",
+			});
+		};
+		let mut bytes = Vec::new();
+		data.write_into(&mut Writer::new(&data, &mut bytes, 0).unwrap());
+		self.check_fits(handle, &buffer, bytes.len())?;
+		if self.plugin_writes(handle) {
+			warn!("Set data on {}, which the plugin writes to, so it will be overwritten", handle.describe(self));
+		}
+		self.write_queue.queue_write_half(handle, buffer, bytes);
+		Ok(())
+	}
+
+	/// Set the contents of the back buffer of a double buffered storage or uniform buffer, the one bound to its second binding, as it is when this is called, leaving the front buffer alone until the next swap, such as to stream a new set of parameters into a double buffered uniform while the shaders keep reading the old ones from the front buffer. A plain [write_buffer](ShaderBufferSet::write_buffer) is enough for most parameters that change every frame, as its writes are made before the next frame's dispatches run, so they never race with dispatches still in flight. This is only needed when the shaders have to see both the old and the new parameters, or the switch has to happen at a [SwapBuffers](crate::ComputeAction::SwapBuffers) step, rather than at the start of a frame. The write is queued as [write_buffer](ShaderBufferSet::write_buffer)'s is, but unlike it, isn't [recorded](ShaderBufferSet::start_recording). Fails without writing anything if the buffer doesn't exist, isn't a double buffered storage or uniform buffer, or is smaller than the data.
+	pub fn write_back_buffer<T: ShaderType + WriteInto>(
+		&self, handle: ShaderBufferHandle, data: T,
+	) -> Result<(), ShaderBufferError> {
+		let Some(buffer) = self.get_buffer(handle) else {
+			return Err(ShaderBufferError::MissingBuffer { buffer: handle });
+		};
+		let Some(buffer) = buffer.back_storage().and_then(ShaderBufferStorage::gpu_buffer) else {
+			return Err(ShaderBufferError::WrongBufferKind {
+				buffer: handle.describe(self),
+				expected: "a double buffered storage or uniform buffer",
+			});
+		};
+		let mut bytes = Vec::new();
+		data.write_into(&mut Writer::new(&data, &mut bytes, 0).unwrap());
+		self.check_fits(handle, &buffer, bytes.len())?;
+		if self.plugin_writes(handle) {
+			warn!("Set data on {}, which the plugin writes to, so it will be overwritten", handle.describe(self));
+		}
+		self.write_queue.queue_write_half(handle, buffer, bytes);
+		Ok(())
+	}
+
+	/// Queue a write to a storage or uniform buffer, to be made between two steps of a task, after step `step` of task `task` is recorded and before the step after it, in the next iteration of that task to run, such as to paint into a double buffer after the step that updates it, and before the step that swaps it. Writes made with [write_buffer](ShaderBufferSet::write_buffer) land before a frame's first step, which is before or after the swap depending on where in the sequence the frame starts. As with [write_buffer](ShaderBufferSet::write_buffer), the data must match the size of the buffer, and both halves of a double buffer are written. The write is made after step `step` even if that step doesn't run in that iteration, because of its [schedule](crate::ComputeStep::schedule), and a write after a step past the task's last is made at the end of the iteration. Each queued write is made once, after which a [StepWriteAppliedEvent](crate::StepWriteAppliedEvent) with the returned id is sent. A write for a task that doesn't run again waits until it does, such as in the next compute sequence. Only the [main](crate::ComputeSequenceId::MAIN) compute sequence makes queued writes. Unlike [write_buffer](ShaderBufferSet::write_buffer), the write isn't [recorded](ShaderBufferSet::start_recording). Fails without queueing anything if the buffer doesn't exist, isn't a storage or uniform buffer, or is smaller than the data.
+	pub fn queue_write_at<T: ShaderType + WriteInto>(
+		&mut self, handle: ShaderBufferHandle, task: usize, step: usize, data: T,
+	) -> Result<StepWriteId, ShaderBufferError> {
+		let buffer = self.writable_buffer(handle)?;
+		let mut bytes = Vec::new();
+		data.write_into(&mut Writer::new(&data, &mut bytes, 0).unwrap());
+		self.check_fits(handle, &buffer, bytes.len())?;
+		if self.plugin_writes(handle) {
+			warn!("Queued a write to {}, which the plugin writes to, so it will be overwritten", handle.describe(self));
+		}
+		Ok(self.step_writes.queue(handle, task, step, bytes))
+	}
+
+	pub(crate) fn step_writes(&self) -> &StepWrites { &self.step_writes }
+
+	/// Set one element of a [dynamic uniform](ShaderBufferSet::add_uniform_dynamic) buffer, leaving the others as they are, such as the parameters of one of the steps that share it. The write is queued and recorded as with [write_buffer](ShaderBufferSet::write_buffer). Fails without writing anything if the buffer doesn't exist or isn't a dynamic uniform buffer, with [ShaderBufferError::OutOfRange] if the index is past its last element, or with [ShaderBufferError::TooLarge] if the data is larger than an element.
+	pub fn write_uniform_element<T: ShaderType + WriteInto>(
+		&self, handle: ShaderBufferHandle, index: u32, data: T,
+	) -> Result<(), ShaderBufferError> {
+		let Some(buffer) = self.get_buffer(handle) else {
+			return Err(ShaderBufferError::MissingBuffer { buffer: handle });
+		};
+		let ShaderBufferStorage::DynamicUniform { buffer, element_size, stride } = buffer.front_storage() else {
+			return Err(ShaderBufferError::WrongBufferKind {
+				buffer: handle.describe(self),
+				expected: "a dynamic uniform buffer",
+			});
+		};
+		let capacity = buffer.size() / stride;
+		if index as u64 >= capacity {
+			return Err(ShaderBufferError::OutOfRange { buffer: handle.describe(self), start: index, count: 1, capacity });
+		}
+		let mut bytes = Vec::new();
+		data.write_into(&mut Writer::new(&data, &mut bytes, 0).unwrap());
+		if bytes.len() as u64 > *element_size {
+			return Err(ShaderBufferError::TooLarge {
+				buffer: handle.describe(self),
+				size: bytes.len() as u64,
+				capacity: *element_size,
+			});
+		}
+		let offset = index as u64 * stride;
+		if self.tracer.recording() {
+			self.tracer.defer_write(handle, offset, bytes);
+			return Ok(());
+		}
+		self.write_queue.queue_write_halves(handle, offset, bytes);
+		Ok(())
+	}
+
+	/// Set one element of a storage buffer holding an array, such as one agent's parameters in a buffer of them, without writing the rest of it. This is [write_buffer_elements](ShaderBufferSet::write_buffer_elements) with a single value.
+	pub fn write_buffer_element<T: ShaderSize + WriteInto>(
+		&self, handle: ShaderBufferHandle, index: u32, value: T,
+	) -> Result<(), ShaderBufferError> {
+		self.write_buffer_elements(handle, index, std::slice::from_ref(&value))
+	}
+
+	/// Set a run of elements of a storage buffer holding an array of `T`, starting at `start_index`, without writing the rest of it, such as when a few agents' parameters change in a large buffer of them. Where they go is worked out from the stride of `T` in an array, including any padding, so `T` must be the element type of the array the buffer holds from its start, as it would be if it was made with [add_storage_init](ShaderBufferSet::add_storage_init) from a `Vec<T>`. If this is a double buffer, both buffers are written. The write is queued and recorded as with [write_buffer](ShaderBufferSet::write_buffer). Fails without writing anything if the buffer doesn't exist or isn't a storage buffer, or with [ShaderBufferError::OutOfRange] if the elements run past its end, with how many elements it holds.
+	pub fn write_buffer_elements<T: ShaderSize + WriteInto>(
+		&self, handle: ShaderBufferHandle, start_index: u32, values: &[T],
+	) -> Result<(), ShaderBufferError> {
+		let buffer = self.writable_buffer(handle)?;
+		if !buffer.usage().contains(BufferUsages::STORAGE) {
+			return Err(ShaderBufferError::WrongBufferKind { buffer: handle.describe(self), expected: "a storage buffer" });
+		}
+		if values.is_empty() {
+			return Ok(());
+		}
+		// Written as a runtime-sized array, the elements are laid out with the stride they have in the buffer.
+		let mut bytes = Vec::new();
+		values.write_into(&mut Writer::new(values, &mut bytes, 0).unwrap());
+		let stride = (bytes.len() / values.len()) as u64;
+		let capacity = buffer.size() / stride;
+		if start_index as u64 + values.len() as u64 > capacity {
+			return Err(ShaderBufferError::OutOfRange {
+				buffer: handle.describe(self),
+				start: start_index,
+				count: values.len(),
+				capacity,
+			});
+		}
+		if self.plugin_writes(handle) {
+			warn!("Set data on {}, which the plugin writes to, so it will be overwritten", handle.describe(self));
+		}
+		let offset = start_index as u64 * stride;
+		if self.tracer.recording() {
+			self.tracer.defer_write(handle, offset, bytes);
+			return Ok(());
+		}
+		self.write_queue.queue_write_halves(handle, offset, bytes);
+		Ok(())
+	}
+
+	/// Set part of the contents of a storage or uniform buffer, writing `data` at `offset_bytes` and leaving the rest as it is, such as one field of a large uniform struct. The data is laid out as it would be at the start of the buffer, so the offset must put it where the shader expects it, and unlike [write_buffer_elements](ShaderBufferSet::write_buffer_elements), nothing checks that it does. This is [write_buffer_bytes](ShaderBufferSet::write_buffer_bytes) with `data` serialized, and works the same way otherwise.
+	pub fn write_buffer_at<T: ShaderType + WriteInto>(
+		&self, handle: ShaderBufferHandle, offset_bytes: u64, data: T,
+	) -> Result<(), ShaderBufferError> {
+		let mut bytes = Vec::new();
+		data.write_into(&mut Writer::new(&data, &mut bytes, 0).unwrap());
+		self.write_buffer_bytes(handle, offset_bytes, &bytes)
+	}
+
+	/// Write raw bytes into a storage or uniform buffer at an offset, leaving the rest as it is, such as to update a few particles in a buffer of a hundred thousand without uploading all of them again. If this is a double buffer, both buffers are written, and the write is queued and recorded, as with [write_buffer](ShaderBufferSet::write_buffer). Fails without writing anything if the buffer doesn't exist or isn't a storage or uniform buffer, or with [ShaderBufferError::BadWriteRange] if the bytes run past its end, or the offset or the number of bytes isn't a multiple of 4.
+	pub fn write_buffer_bytes(
+		&self, handle: ShaderBufferHandle, offset: u64, bytes: &[u8],
+	) -> Result<(), ShaderBufferError> {
+		self.check_write_range(handle, offset, bytes.len() as u64)?;
+		if bytes.is_empty() {
+			return Ok(());
+		}
+		if self.plugin_writes(handle) {
+			warn!("Set data on {}, which the plugin writes to, so it will be overwritten", handle.describe(self));
+		}
+		if self.tracer.recording() {
+			self.tracer.defer_write(handle, offset, bytes.to_vec());
+			return Ok(());
+		}
+		self.write_queue.queue_write_halves(handle, offset, bytes.to_vec());
+		Ok(())
+	}
+
+	/// Set the contents of a buffer with [write_buffer](ShaderBufferSet::write_buffer), which this forwards to. The write is queued, so the `render_queue` isn't used.
+	#[deprecated(note = "use `write_buffer`, which doesn't take a render queue")]
+	pub fn set_buffer<T: ShaderType + WriteInto + Clone>(
+		&mut self, handle: ShaderBufferHandle, data: T, _render_queue: &RenderQueue,
+	) -> Result<(), ShaderBufferError> {
+		self.write_buffer(handle, data)
+	}
+
+	/// Set the front buffer of a double buffer with [write_front_buffer](ShaderBufferSet::write_front_buffer), which this forwards to. The write is queued, so the `render_queue` isn't used.
+	#[deprecated(note = "use `write_front_buffer`, which doesn't take a render queue")]
+	pub fn set_front_buffer<T: ShaderType + WriteInto>(
+		&mut self, handle: ShaderBufferHandle, data: T, _render_queue: &RenderQueue,
+	) -> Result<(), ShaderBufferError> {
+		self.write_front_buffer(handle, data)
+	}
+
+	/// Set the back buffer of a double buffer with [write_back_buffer](ShaderBufferSet::write_back_buffer), which this forwards to. The write is queued, so the `render_queue` isn't used.
+	#[deprecated(note = "use `write_back_buffer`, which doesn't take a render queue")]
+	pub fn set_back_buffer<T: ShaderType + WriteInto>(
+		&mut self, handle: ShaderBufferHandle, data: T, _render_queue: &RenderQueue,
+	) -> Result<(), ShaderBufferError> {
+		self.write_back_buffer(handle, data)
+	}
+
+	/// Set one element of a dynamic uniform buffer with [write_uniform_element](ShaderBufferSet::write_uniform_element), which this forwards to. The write is queued, so the `render_queue` isn't used.
+	#[deprecated(note = "use `write_uniform_element`, which doesn't take a render queue")]
+	pub fn set_uniform_element<T: ShaderType + WriteInto>(
+		&mut self, handle: ShaderBufferHandle, index: u32, data: T, _render_queue: &RenderQueue,
+	) -> Result<(), ShaderBufferError> {
+		self.write_uniform_element(handle, index, data)
+	}
+
+	/// Set one element of a storage buffer with [write_buffer_element](ShaderBufferSet::write_buffer_element), which this forwards to. The write is queued, so the `render_queue` isn't used.
+	#[deprecated(note = "use `write_buffer_element`, which doesn't take a render queue")]
+	pub fn set_buffer_element<T: ShaderSize + WriteInto>(
+		&mut self, handle: ShaderBufferHandle, index: u32, value: T, _render_queue: &RenderQueue,
+	) -> Result<(), ShaderBufferError> {
+		self.write_buffer_element(handle, index, value)
+	}
+
+	/// Set a run of elements of a storage buffer with [write_buffer_elements](ShaderBufferSet::write_buffer_elements), which this forwards to. The write is queued, so the `render_queue` isn't used.
+	#[deprecated(note = "use `write_buffer_elements`, which doesn't take a render queue")]
+	pub fn set_buffer_elements<T: ShaderSize + WriteInto>(
+		&mut self, handle: ShaderBufferHandle, start_index: u32, values: &[T], _render_queue: &RenderQueue,
+	) -> Result<(), ShaderBufferError> {
+		self.write_buffer_elements(handle, start_index, values)
+	}
+
+	/// Set part of a buffer with [write_buffer_at](ShaderBufferSet::write_buffer_at), which this forwards to. The write is queued, so the `render_queue` isn't used.
+	#[deprecated(note = "use `write_buffer_at`, which doesn't take a render queue")]
+	pub fn set_buffer_at<T: ShaderType + WriteInto>(
+		&mut self, handle: ShaderBufferHandle, offset_bytes: u64, data: T, _render_queue: &RenderQueue,
+	) -> Result<(), ShaderBufferError> {
+		self.write_buffer_at(handle, offset_bytes, data)
+	}
+
+	/// Write raw bytes into a buffer with [write_buffer_bytes](ShaderBufferSet::write_buffer_bytes), which this forwards to. The write is queued, so the `render_queue` isn't used.
+	#[deprecated(note = "use `write_buffer_bytes`, which doesn't take a render queue")]
+	pub fn set_buffer_bytes(
+		&mut self, handle: ShaderBufferHandle, offset: u64, bytes: &[u8], _render_queue: &RenderQueue,
+	) -> Result<(), ShaderBufferError> {
+		self.write_buffer_bytes(handle, offset, bytes)
+	}
+
+	/// Check that `size` bytes written at `offset` into a storage or uniform buffer fit in it, and that both are multiples of 4, as the GPU needs them to be.
+	pub(crate) fn check_write_range(
+		&self, handle: ShaderBufferHandle, offset: u64, size: u64,
+	) -> Result<(), ShaderBufferError> {
+		let buffer = self.writable_buffer(handle)?;
+		if offset % COPY_BUFFER_ALIGNMENT != 0
+			|| size % COPY_BUFFER_ALIGNMENT != 0
+			|| offset.checked_add(size).is_none_or(|end| end > buffer.size())
+		{
+			return Err(ShaderBufferError::BadWriteRange {
+				buffer: handle.describe(self),
+				offset,
+				size,
+				capacity: buffer.size(),
+			});
+		}
+		Ok(())
+	}
+
+	/// Set the contents of a buffer, without warning if it's one the plugin writes to itself.
+	pub(crate) fn set_owned_buffer<T: ShaderType + WriteInto + Clone>(
+		&self, handle: ShaderBufferHandle, data: T, render_queue: &RenderQueue,
+	) {
+		if let Some(buffer) = self.get_buffer(handle) {
+			buffer.set(data, render_queue);
+		} else {
+			panic!("Tried to set data on {}, which doesn't exist", handle.describe(self));
+		}
 	}
 
-	/// Delete a buffer.
-	/// - handle: The handle to the buffer to be deleted.
-	/// - images: The `Assets<Image>` resource from Bevy.
-	pub fn delete_buffer(&mut self, handle: ShaderBufferHandle, images: &mut Assets<Image>) {
-		let buffer = match handle {
-			ShaderBufferHandle::Bound { group, id, .. } => {
-				let buffer = self.buffers.remove(&id);
-				if let Some(buffers) = self.groups.get_mut(group as usize) {
-					if let Some(index) = buffers.iter().position(|buffer_id| *buffer_id == id) {
-						buffers.remove(index);
-					}
-				}
-				buffer
+	/// Start recording a [ComputeTrace] of every [write_buffer](ShaderBufferSet::write_buffer) and swap, with the iteration boundaries they happen at, and the number of iterations run each frame, for replaying later with [replay](ShaderBufferSet::replay). Any recording already being made is thrown away. Writes to the buffers the plugin writes itself, such as the [globals](ShaderBufferSet::add_globals), aren't recorded, as they're written again every frame, so a replay is only exact if the shaders don't depend on them, or on the time, as steps with a [MaxFrequencyHz](crate::StepSchedule::MaxFrequencyHz) schedule do. Only the [main](crate::ComputeSequenceId::MAIN) compute sequence is recorded and replayed.
+	pub fn start_recording(&mut self) { self.tracer.start_recording(); }
+
+	/// Stop recording, and return the [ComputeTrace] recorded since [start_recording](ShaderBufferSet::start_recording), or `None` if nothing was being recorded. Writes made since the last frame are included, at the iteration the compute sequence is at now.
+	pub fn stop_recording(&mut self) -> Option<ComputeTrace> { self.tracer.stop_recording() }
+
+	/// Replay a [ComputeTrace] into the next compute sequence, which should be started with the same tasks as the one it was recorded from, and with the buffers in the same state. Each recorded write is made at the same iteration boundary it was recorded at, even in the middle of a frame, and each task runs the same number of iterations each frame as it did while recording, in place of its [FrameBudget](crate::FrameBudget). Recorded swaps aren't replayed, as they're made by the steps themselves. This can be combined with [start_recording](ShaderBufferSet::start_recording), to check that a replay swaps at the same iterations as the original. Writes made with [write_buffer](ShaderBufferSet::write_buffer) during a replay are made as usual, so they'll make it differ.
+	pub fn replay(&mut self, trace: ComputeTrace) { self.tracer.replay(trace); }
+
+	/// Whether a [replay](ShaderBufferSet::replay) is still going, which it is until every recorded write has been made, and every recorded frame has run.
+	pub fn replaying(&self) -> bool { self.tracer.replaying() }
+
+	pub(crate) fn tracer(&self) -> &ComputeTracer { &self.tracer }
+
+	/// The GPU buffers behind a storage or uniform buffer, which is both halves of a double buffer, every buffer of a ring, and none if it doesn't exist or is a texture.
+	pub(crate) fn all_gpu_buffers(&self, handle: ShaderBufferHandle) -> Vec<Buffer> {
+		match self.buffers.get(&handle.id()) {
+			Some(ShaderBufferInfo::Double { storage: (storage1, storage2), .. }) => {
+				storage1.gpu_buffer().into_iter().chain(storage2.gpu_buffer()).collect()
 			}
-			ShaderBufferHandle::Unbound { id } => self.buffers.remove(&id),
-		};
-		if let Some(mut buffer) = buffer {
-			buffer.delete(images);
+			Some(ShaderBufferInfo::Ring { storage, .. }) => {
+				storage.iter().filter_map(ShaderBufferStorage::gpu_buffer).collect()
+			}
+			Some(buffer) => buffer.gpu_buffer().into_iter().collect(),
+			None => Vec::new(),
 		}
 	}
 
-	/// Get the image handle for a texture buffer. If the provided buffer isn't a texture buffer, it will just return `None`. If the provided buffer is a double buffer, it will return the image handle for the current front buffer.
-	pub fn image_handle(&self, handle: ShaderBufferHandle) -> Option<Handle<Image>> {
-		if let Some(buffer) = self.get_buffer(handle) {
-			buffer.image_handle()
-		} else {
-			None
+	/// Write raw bytes at an offset into every half of a storage or uniform buffer. Buffers that have been deleted since the write was asked for are left alone.
+	pub(crate) fn write_all_halves(
+		&self, handle: ShaderBufferHandle, offset: u64, data: &[u8], render_queue: &RenderQueue,
+	) {
+		for buffer in self.all_gpu_buffers(handle) {
+			render_queue.write_buffer(&buffer, offset, data);
 		}
 	}
 
-	/// Get the GPU buffer, as a [bevy_render::render_resource::buffer], for a storage or uniform buffer. If the provided buffer isn't a storage or uniform buffer, it will just return `None`. If the provided buffer is a double buffer, it will return the GPU buffer for the current front buffer.
-	pub fn gpu_buffer(&self, handle: ShaderBufferHandle) -> Option<Buffer> {
-		if let Some(buffer) = self.get_buffer(handle) {
-			buffer.gpu_buffer()
-		} else {
-			None
+	/// Write the pixels of an image into a texture buffer while it's running, such as a saved simulation state loaded from a PNG, or a decal stamped into part of the texture. The image's top left corner goes at `origin`, in pixels of the texture, and the image must fit inside the texture from there. Its format must match the texture's, though an sRGB format matches its linear counterpart. For a double buffered texture, `target` picks whether both halves are written, or only the back buffer. This is written like [write_texture_region](ShaderBufferSet::write_texture_region). Fails without writing anything if the buffer doesn't exist or isn't a texture buffer, or if the image doesn't fit, has a different format, isn't a single 2D image, or doesn't have its pixel data in the main world.
+	pub fn write_texture_from_image(
+		&mut self, handle: ShaderBufferHandle, image: &Image, origin: UVec2, target: TextureWriteTarget,
+	) -> Result<(), ShaderBufferError> {
+		if self.get_buffer(handle).is_none() {
+			return Err(ShaderBufferError::MissingBuffer { buffer: handle });
+		}
+		let Some((format, _)) = self.texture_format_and_size(handle) else {
+			return Err(ShaderBufferError::WrongBufferKind { buffer: handle.describe(self), expected: "a texture buffer" });
+		};
+		let descriptor = &image.texture_descriptor;
+		if descriptor.format.remove_srgb_suffix() != format.remove_srgb_suffix() {
+			return Err(ShaderBufferError::FormatMismatch {
+				buffer: handle.describe(self),
+				format: descriptor.format,
+				expected: format,
+			});
+		}
+		if descriptor.dimension != TextureDimension::D2 || descriptor.size.depth_or_array_layers != 1 {
+			return Err(ShaderBufferError::UnwritableImage {
+				buffer: handle.describe(self),
+				reason: format!(
+					"it's a {:?} image with {} layers, and only single 2D images can be written",
+					descriptor.dimension, descriptor.size.depth_or_array_layers
+				),
+			});
+		}
+		if image.data.is_empty() {
+			return Err(ShaderBufferError::UnwritableImage {
+				buffer: handle.describe(self),
+				reason: "it has no pixel data. Images only keep their pixel data in the main world if their asset usage includes RenderAssetUsages::MAIN_WORLD".to_owned(),
+			});
 		}
+		let size = UVec2::new(descriptor.size.width, descriptor.size.height);
+		self.write_texture_region(handle, origin, size, &image.data, target)
 	}
 
-	pub(crate) fn swap_front_buffer(&mut self, handle: ShaderBufferHandle) {
-		let buffer = self.get_mut_buffer(handle);
-		let Some(buffer) = buffer else {
-			panic!("Attempted to set the front buffer of {}, but it doesn't exist", handle);
+	/// Write raw texel data into a rectangle of a texture buffer while it's running, such as a tile of edited terrain streamed into a much larger simulation texture, without uploading the rest of it again. The rectangle's top left corner is at `origin`, and it's `size` texels across, in pixels of the texture, and it must fit inside the texture. The data is the rectangle's rows, top to bottom, tightly packed in the texture's format, so it must be exactly `size.x * size.y` texels long. Rows don't need padding, as writes through the queue aren't bound by the row alignment of copies between buffers and textures. For a double buffered texture, `target` picks whether both halves are written, or only the back buffer, as it is when this is called, which is usually the one to write, so the front buffer being displayed doesn't change until the next swap. The data is copied when this is called, and written to the texture in the render world before the next compute steps run, or once the texture is on the GPU, if it isn't yet. Fails without writing anything if the buffer doesn't exist or isn't a texture buffer, or if the rectangle doesn't fit or the data is the wrong length.
+	pub fn write_texture_region(
+		&mut self, handle: ShaderBufferHandle, origin: UVec2, size: UVec2, data: &[u8], target: TextureWriteTarget,
+	) -> Result<(), ShaderBufferError> {
+		let Some(buffer) = self.get_buffer(handle) else {
+			return Err(ShaderBufferError::MissingBuffer { buffer: handle });
 		};
-		let ShaderBufferInfo::Double { front, .. } = buffer else {
-			panic!("Attempt to set the front buffer of {}, which isn't a double buffer", handle);
+		let Some((format, texture_size)) = buffer.front_storage().texture_format_and_size() else {
+			return Err(ShaderBufferError::WrongBufferKind { buffer: handle.describe(self), expected: "a texture buffer" });
 		};
-		*front = match front {
-			FrontBuffer::First => FrontBuffer::Second,
-			FrontBuffer::Second => FrontBuffer::First,
+		if origin.x as u64 + size.x as u64 > texture_size.width as u64
+			|| origin.y as u64 + size.y as u64 > texture_size.height as u64
+		{
+			return Err(ShaderBufferError::RegionOutOfBounds { buffer: handle.describe(self), origin, size });
+		}
+		let bytes_per_row = size.x * format.block_copy_size(None).unwrap();
+		let expected_len = bytes_per_row as usize * size.y as usize;
+		if data.len() != expected_len {
+			return Err(ShaderBufferError::RegionSizeMismatch {
+				buffer: handle.describe(self),
+				region: size,
+				size: data.len() as u64,
+				expected: expected_len as u64,
+			});
+		}
+		let targets = match (buffer.image_handles(), target) {
+			(Some(images), TextureWriteTarget::Both) => vec![images.front, images.back],
+			(Some(images), TextureWriteTarget::Back) => vec![images.back],
+			(None, _) => buffer.image_handle().into_iter().collect(),
+		};
+		let mut uploads = self.texture_uploads.lock().unwrap();
+		for target in targets {
+			uploads.push(TextureUpload {
+				image: target,
+				origin,
+				size: Extent3d { width: size.x, height: size.y, depth_or_array_layers: 1 },
+				bytes_per_row,
+				data: data.to_vec(),
+			});
 		}
+		Ok(())
 	}
 
-	/// Set the contents of a buffer. The data must be a type that implements [ShaderType], and it must match the size of the buffer. If this is a double buffer, the both buffers will be set.
-	pub fn set_buffer<T: ShaderType + WriteInto + Clone>(
-		&mut self, handle: ShaderBufferHandle, data: T, render_queue: &RenderQueue,
-	) {
-		if let Some(buffer) = self.get_buffer(handle) {
-			buffer.set(data, render_queue);
-		} else {
-			panic!("Tried to set data on a non-existent buffer");
+	/// Make the writes queued by [write_texture_region](ShaderBufferSet::write_texture_region), except for those whose texture isn't on the GPU yet, which are kept for a later frame.
+	fn write_texture_uploads(&self, gpu_images: &RenderAssets<GpuImage>, render_queue: &RenderQueue) {
+		self.texture_uploads.lock().unwrap().retain(|upload| {
+			let Some(gpu_image) = gpu_images.get(&upload.image) else {
+				return true;
+			};
+			render_queue.write_texture(
+				ImageCopyTexture {
+					texture: &gpu_image.texture,
+					mip_level: 0,
+					origin: Origin3d { x: upload.origin.x, y: upload.origin.y, z: 0 },
+					aspect: TextureAspect::All,
+				},
+				&upload.data,
+				ImageDataLayout {
+					offset: 0,
+					bytes_per_row: Some(upload.bytes_per_row),
+					rows_per_image: Some(upload.size.height),
+				},
+				upload.size,
+			);
+			false
+		});
+	}
+
+	/// Fails with [ShaderBufferError::DuplicateBinding] if a buffer can't be bound where `binding` asks, because another buffer in the group, or the other half of a double buffer, is already bound there, which would otherwise only be caught by wgpu when the group's layout is made, far from the buffer that caused it. Panics if it's a ring of no buffers, or every [ring slot](ShaderBufferSet::RING_SLOTS) is taken. The `add_*` functions check this before allocating anything.
+	fn check_binding_free(&self, binding: Binding) -> Result<(), ShaderBufferError> {
+		let (group, bindings) = match binding {
+			Binding::SingleBound(group, binding) => (group, vec![binding]),
+			Binding::Double(group, (front, back)) if front == back => {
+				return Err(ShaderBufferError::DuplicateBinding {
+					group,
+					binding: front,
+					existing: "the front buffer of the same double buffer".to_string(),
+				});
+			}
+			Binding::Double(group, (front, back)) => (group, vec![front, back]),
+			Binding::Ring(_, _, 0) => panic!("Tried to add a ring of no buffers"),
+			Binding::Ring(..) if self.free_ring_slot().is_none() => panic!(
+				"Tried to add a ring, but there are already {} rings, which is all the globals have room for",
+				Self::RING_SLOTS
+			),
+			Binding::Ring(group, first, depth) => (group, (first..first + depth).collect()),
+			Binding::SingleUnbound | Binding::PerEntity(..) => return Ok(()),
+		};
+		let ids = self.groups.get(group as usize).into_iter().flatten();
+		for &id in ids {
+			let Some(taken) = self.buffers.get(&id).map(ShaderBufferInfo::bindings) else {
+				continue;
+			};
+			if let Some(&binding) = bindings.iter().find(|binding| taken.contains(binding)) {
+				return Err(ShaderBufferError::DuplicateBinding {
+					group,
+					binding,
+					existing: ShaderBufferHandle::Bound { group, id }.describe(self),
+				});
+			}
 		}
+		Ok(())
 	}
 
-	fn store_buffer(&mut self, binding: Binding, buffer: ShaderBufferInfo) -> ShaderBufferHandle {
+	fn store_buffer(&mut self, binding: Binding, mut buffer: ShaderBufferInfo) -> ShaderBufferHandle {
+		self.assert_main_world("add a buffer");
 		let id = self.next_id;
+		match binding {
+			Binding::SingleBound(group, _) | Binding::Double(group, _) | Binding::Ring(group, ..) => {
+				if self.per_entity_group() == Some(group) {
+					panic!("Tried to add a buffer to group {}, which is reserved for per-entity buffers", group);
+				}
+			}
+			Binding::PerEntity(group, binding) => {
+				let entry = buffer.bind_group_layout_entry(ShaderStages::COMPUTE, DEFAULT_DOUBLE_ACCESS)[0];
+				match &self.per_entity_layout {
+					None => {
+						if self.groups.get(group as usize).is_some_and(|buffers| !buffers.is_empty()) {
+							panic!(
+								"Tried to add a per-entity buffer to group {}, but that group already has buffers that aren't per-entity",
+								group
+							);
+						}
+						self.per_entity_layout = Some((group, entry));
+					}
+					Some((per_entity_group, per_entity_entry)) => {
+						if *per_entity_group != group || *per_entity_entry != entry {
+							panic!(
+								"Tried to add a per-entity buffer at group {} binding {}, but every per-entity buffer must have the same group, binding and kind as the first, which was at group {} binding {}",
+								group, binding, per_entity_group, per_entity_entry.binding
+							);
+						}
+					}
+				}
+			}
+			Binding::SingleUnbound => {}
+		}
+		if let ShaderBufferInfo::Ring { slot, .. } = &mut buffer {
+			*slot = self.free_ring_slot().unwrap();
+		}
 		self.next_id += 1;
+		self.generation += 1;
 		self.buffers.insert(id, buffer);
+		if let Some(label) = self.next_label.take() {
+			self.labels.insert(id, label);
+		}
 		match binding {
-			Binding::SingleBound(group, _) | Binding::Double(group, _) => {
+			Binding::SingleBound(group, _)
+			| Binding::Double(group, _)
+			| Binding::PerEntity(group, _)
+			| Binding::Ring(group, ..) => {
 				if group as usize >= self.groups.len() {
 					self.groups.resize(group as usize + 1, Vec::new())
 				}
-				self.groups[group as usize].push(id);
+				// Per-entity buffers aren't part of the shared bind groups, so the group is
+				// left empty, and each entity's bind group is made separately.
+				if !matches!(binding, Binding::PerEntity(..)) {
+					self.groups[group as usize].push(id);
+				}
 				ShaderBufferHandle::Bound { group, id }
 			}
 			Binding::SingleUnbound => ShaderBufferHandle::Unbound { id },
 		}
 	}
 
+	/// The size of the copy buffer a buffer or texture is read back through, and for a texture, how its rows are laid out in it, or what's wrong if it can't be read back, which [create_copy_buffer_range](ShaderBufferRenderSet::create_copy_buffer_range) fails with. Textures are copied row by row, with each row padded out to the alignment copies need, and only the front buffer of a double buffered texture is copied.
+	fn copy_layout(&self, handle: ShaderBufferHandle) -> Result<(u32, Option<TextureRows>), String> {
+		let Some(src) = self.buffers.get(&handle.id()) else {
+			return Err(format!("Tried to create a copy buffer for {}, which does not exist", handle.describe(self)));
+		};
+		match (src, src.front_storage()) {
+			(ShaderBufferInfo::Double { .. }, ShaderBufferStorage::Storage { .. }) => {
+				Err(format!("Tried to create a copy buffer for {}, which is a double buffer", handle.describe(self)))
+			}
+			(ShaderBufferInfo::Ring { .. }, _) => {
+				Err(format!("Tried to create a copy buffer for {}, which is a ring", handle.describe(self)))
+			}
+			(_, ShaderBufferStorage::Storage { buffer, .. }) => Ok((buffer.size() as u32, None)),
+			(_, ShaderBufferStorage::StorageTexture { format, size, .. }) => {
+				let Some(block_size) = format.block_copy_size(None) else {
+					return Err(format!(
+						"Tried to create a copy buffer for {}, whose format can't be copied",
+						handle.describe(self)
+					));
+				};
+				let unpadded = block_size * size.width;
+				let padded = unpadded.next_multiple_of(COPY_BYTES_PER_ROW_ALIGNMENT);
+				let count = size.height * size.depth_or_array_layers;
+				Ok((padded * count, Some(TextureRows { padded, unpadded, count })))
+			}
+			_ => Err(format!(
+				"Tried to create a copy buffer for {}, which is not a storage buffer or texture",
+				handle.describe(self)
+			)),
+		}
+	}
+
+	/// Check that a [CopyBufferToBuffer](crate::ComputeAction::CopyBufferToBuffer) step can copy between two buffers, returning the number of bytes it copies, or what's wrong if it can't.
+	pub(crate) fn check_buffer_copy(
+		&self, src: ShaderBufferHandle, dst: ShaderBufferHandle, src_offset: u64, dst_offset: u64, size: Option<u64>,
+	) -> Result<u64, String> {
+		let storage = |handle: ShaderBufferHandle, role: &str| {
+			let Some(buffer) = self.get_buffer(handle) else {
+				return Err(format!("Tried to copy {} {}, which doesn't exist", role, handle.describe(self)));
+			};
+			match buffer.front_storage() {
+				ShaderBufferStorage::Storage { buffer, .. } => Ok(buffer.clone()),
+				_ => Err(format!("Tried to copy {} {}, which isn't a storage buffer", role, handle.describe(self))),
+			}
+		};
+		let src_buffer = storage(src, "out of")?;
+		let dst_buffer = storage(dst, "into")?;
+		if src.id() == dst.id() {
+			return Err(format!("Tried to copy {} into itself", src.describe(self)));
+		}
+		if !src_buffer.usage().contains(BufferUsages::COPY_SRC) {
+			return Err(format!(
+				"Tried to copy out of {}, which wasn't made with BufferUsages::COPY_SRC",
+				src.describe(self)
+			));
+		}
+		let size = size.unwrap_or(src_buffer.size().saturating_sub(src_offset));
+		if [src_offset, dst_offset, size].iter().any(|value| value % COPY_BUFFER_ALIGNMENT != 0) {
+			return Err(format!(
+				"Tried to copy {} bytes from offset {} of {} to offset {} of {}, but the offsets and size must be multiples of {}",
+				size,
+				src_offset,
+				src.describe(self),
+				dst_offset,
+				dst.describe(self),
+				COPY_BUFFER_ALIGNMENT
+			));
+		}
+		for (handle, buffer, offset) in [(src, &src_buffer, src_offset), (dst, &dst_buffer, dst_offset)] {
+			if offset + size > buffer.size() {
+				return Err(format!(
+					"Tried to copy {} bytes at offset {} of {}, which only holds {} bytes",
+					size,
+					offset,
+					handle.describe(self),
+					buffer.size()
+				));
+			}
+		}
+		Ok(size)
+	}
+
+	/// Check that a [CopyTextureToBuffer](crate::ComputeAction::CopyTextureToBuffer) step, or a [CopyBufferToTexture](crate::ComputeAction::CopyBufferToTexture) step if `into_texture` is set, can copy between a storage texture and a storage buffer, returning the number of bytes it copies, or what's wrong if it can't.
+	pub(crate) fn check_texture_copy(
+		&self, texture: ShaderBufferHandle, buffer: ShaderBufferHandle, into_texture: bool,
+	) -> Result<u64, String> {
+		let (texture_role, buffer_role) = if into_texture { ("into", "out of") } else { ("out of", "into") };
+		let Some(texture_info) = self.get_buffer(texture) else {
+			return Err(format!("Tried to copy {} {}, which doesn't exist", texture_role, texture.describe(self)));
+		};
+		let ShaderBufferStorage::StorageTexture { format, size, .. } = *texture_info.front_storage() else {
+			return Err(format!("Tried to copy {} {}, which isn't a storage texture", texture_role, texture.describe(self)));
+		};
+		let Some(buffer_info) = self.get_buffer(buffer) else {
+			return Err(format!("Tried to copy {} {}, which doesn't exist", buffer_role, buffer.describe(self)));
+		};
+		let ShaderBufferStorage::Storage { buffer: gpu_buffer, .. } = buffer_info.front_storage() else {
+			return Err(format!("Tried to copy {} {}, which isn't a storage buffer", buffer_role, buffer.describe(self)));
+		};
+		if into_texture && !gpu_buffer.usage().contains(BufferUsages::COPY_SRC) {
+			return Err(format!(
+				"Tried to copy out of {}, which wasn't made with BufferUsages::COPY_SRC",
+				buffer.describe(self)
+			));
+		}
+		let Some(texel) = format.block_copy_size(None) else {
+			return Err(format!(
+				"Tried to copy {} {}, whose format, {:?}, can't be copied",
+				texture_role,
+				texture.describe(self),
+				format
+			));
+		};
+		let expected = texture_bytes(format, size);
+		if gpu_buffer.size() < expected {
+			return Err(format!(
+				"Tried to copy {} {} {} {}, which needs {} bytes, for {}x{}x{} texels of {:?} at {} bytes each, but the buffer only holds {} bytes",
+				if into_texture { "into" } else { "out of" },
+				texture.describe(self),
+				if into_texture { "from" } else { "into" },
+				buffer.describe(self),
+				expected,
+				size.width,
+				size.height,
+				size.depth_or_array_layers,
+				format,
+				texel,
+				gpu_buffer.size()
+			));
+		}
+		Ok(expected)
+	}
+
+	/// Check that the workgroup counts of a [RunShaderIndirect](crate::ComputeAction::RunShaderIndirect) step can be read from a buffer at an offset, returning what's wrong if they can't.
+	pub(crate) fn check_indirect_source(&self, handle: ShaderBufferHandle, offset: u64) -> Result<(), String> {
+		let Some(buffer) = self.get_buffer(handle) else {
+			return Err(format!("Tried to dispatch indirectly from {}, which doesn't exist", handle.describe(self)));
+		};
+		if let ShaderBufferInfo::Double { .. } = buffer {
+			return Err(format!("Tried to dispatch indirectly from {}, which is a double buffer", handle.describe(self)));
+		}
+		if let ShaderBufferInfo::Ring { .. } = buffer {
+			return Err(format!("Tried to dispatch indirectly from {}, which is a ring", handle.describe(self)));
+		}
+		let Some(gpu_buffer) = buffer.front_storage().raw_buffer() else {
+			return Err(format!("Tried to dispatch indirectly from {}, which isn't a storage buffer", handle.describe(self)));
+		};
+		if !gpu_buffer.usage().contains(BufferUsages::INDIRECT) {
+			return Err(format!(
+				"Tried to dispatch indirectly from {}, which wasn't made with BufferUsages::INDIRECT",
+				handle.describe(self)
+			));
+		}
+		let size = size_of::<DispatchIndirectArgs>() as u64;
+		if offset % 4 != 0 || offset + size > gpu_buffer.size() {
+			return Err(format!(
+				"Tried to dispatch indirectly from {} at offset {}, but the {} bytes of workgroup counts must start at a multiple of 4 and fit in its {} bytes",
+				handle.describe(self),
+				offset,
+				size,
+				gpu_buffer.size()
+			));
+		}
+		Ok(())
+	}
+
+	/// Check that a buffer or texture can be read back by a [CopyBuffer](crate::ComputeAction::CopyBuffer) or [ReadBuffer](crate::ComputeAction::ReadBuffer) step, returning what's wrong if it can't.
+	pub(crate) fn check_copy_source(&self, handle: ShaderBufferHandle) -> Result<(), String> {
+		self.copy_layout(handle).map(|_| ())
+	}
+
+	/// Check that the part of a buffer a [CopyBuffer](crate::ComputeAction::CopyBuffer) step reads back can be read back, returning its offset and size, or `None` if the step reads back the whole buffer or texture, or what's wrong if it can't. Only part of a storage buffer can be read back, not of a texture, and the offset and size must be multiples of [COPY_BUFFER_ALIGNMENT], and fit in the buffer.
+	pub(crate) fn check_copy_range(
+		&self, handle: ShaderBufferHandle, offset: u64, size: Option<u64>,
+	) -> Result<Option<(u64, u64)>, String> {
+		let (buffer_size, rows) = self.copy_layout(handle)?;
+		if offset == 0 && size.is_none() {
+			return Ok(None);
+		}
+		if rows.is_some() {
+			return Err(format!(
+				"Tried to read back part of {}, but only part of a storage buffer can be read back, not of a texture",
+				handle.describe(self)
+			));
+		}
+		let buffer_size = buffer_size as u64;
+		let size = size.unwrap_or(buffer_size.saturating_sub(offset));
+		if size == 0 || offset % COPY_BUFFER_ALIGNMENT != 0 || size % COPY_BUFFER_ALIGNMENT != 0 {
+			return Err(format!(
+				"Tried to read back {} bytes at offset {} of {}, but the offset and size must be multiples of {}, and the size more than 0",
+				size,
+				offset,
+				handle.describe(self),
+				COPY_BUFFER_ALIGNMENT
+			));
+		}
+		if offset + size > buffer_size {
+			return Err(format!(
+				"Tried to read back {} bytes at offset {} of {}, which only holds {} bytes",
+				size,
+				offset,
+				handle.describe(self),
+				buffer_size
+			));
+		}
+		Ok(Some((offset, size)))
+	}
+
+	/// Copy the front buffer of a buffer or texture into a buffer made for reading it back, laid out as [copy_layout](ShaderBufferSet::copy_layout) says, returning the size and layout it was copied with, or `None` if there was nothing to copy, as when a texture hasn't been uploaded yet. Panics if it can't be read back.
+	pub(crate) fn copy_for_readback(
+		&self, handle: ShaderBufferHandle, gpu_images: &RenderAssets<GpuImage>, encoder: &mut CommandEncoder,
+		dst: impl FnOnce(u64) -> Buffer,
+	) -> Option<(Buffer, Option<TextureRows>)> {
+		let (size, rows) = self.copy_layout(handle).unwrap_or_else(|error| panic!("{}", error));
+		let src = self.get_buffer(handle).unwrap();
+		if matches!(src.front_storage(), ShaderBufferStorage::StorageTexture { image, .. } if gpu_images.get(image).is_none())
+		{
+			return None;
+		}
+		let dst = dst(size as u64);
+		copy_out(&src, &dst, rows, gpu_images, encoder);
+		Some((dst, rows))
+	}
+
+	/// The layout entry at a binding of a group, as the bind group layouts made for the compute pipelines have it, or `None` if nothing is bound there. If `per_entity` is true, the per-entity group has the layout of the per-entity buffers, as it does for a [RunShaderPerEntity](crate::ComputeAction::RunShaderPerEntity) action.
+	pub(crate) fn layout_entry_at(&self, group: u32, binding: u32, per_entity: bool) -> Option<BindGroupLayoutEntry> {
+		self
+			.bind_group_layout_entries(per_entity)
+			.into_iter()
+			.nth(group as usize)?
+			.into_iter()
+			.find(|entry| entry.binding == binding)
+	}
+
+	/// Whether the buffer bound at a group and binding is a texture added from a handle whose image is still loading, so what kind of texture it is isn't known yet.
+	pub(crate) fn texture_loading_at(&self, group: u32, binding: u32) -> bool {
+		self.buffers.values().any(|buffer| {
+			matches!(
+				buffer,
+				ShaderBufferInfo::SingleBound { binding: at, storage: ShaderBufferStorage::LoadingTexture { .. } }
+					if *at == (group, binding)
+			)
+		})
+	}
+
 	fn get_buffer(&self, handle: ShaderBufferHandle) -> Option<ShaderBufferInfo> {
 		match handle {
 			ShaderBufferHandle::Bound { id, .. } | ShaderBufferHandle::Unbound { id } => self.buffers.get(&id).cloned(),
@@ -541,93 +3789,570 @@ impl ShaderBufferSet {
 	}
 }
 
-fn extract_resources(mut commands: Commands, buffers: Extract<Option<Res<ShaderBufferSet>>>) {
+impl Display for ShaderBufferSet {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		write!(
+			f,
+			"ShaderBufferSet: {} buffers in {} groups, {} allocated",
+			self.buffers.len(),
+			self.groups.len(),
+			format_bytes(self.allocated_bytes())
+		)?;
+		match self.memory_budget {
+			Some(budget) => writeln!(f, " of a budget of {}", format_bytes(budget))?,
+			None => writeln!(f)?,
+		}
+		let mut ids = self.buffers.keys().copied().collect::<Vec<_>>();
+		ids.sort();
+		for id in ids {
+			let buffer = &self.buffers[&id];
+			let handle = buffer.handle(id);
+			match self.buffer_name(handle) {
+				Some(name) => write!(f, "  {} \"{}\" | {}", handle, name, buffer)?,
+				None => write!(f, "  {} | {}", handle, buffer)?,
+			}
+			if let Some(label) = self.buffer_label(handle) {
+				write!(f, " | labeled \"{}\"", label)?;
+			}
+			if self.consumed_in_render(handle) {
+				write!(f, " | consumed by rendering")?;
+			}
+			writeln!(f)?;
+		}
+		Ok(())
+	}
+}
+
+/// In debug builds, panic if the [ShaderBufferSet] passed to a method of the [ShaderBufferRenderSet] is the main world's, which can have buffers added or deleted since the frame being rendered was extracted.
+fn assert_render_world(buffers: &ShaderBufferSet) {
+	debug_assert!(
+		buffers.world == ComputeWorld::Render,
+		"Passed the main world's ShaderBufferSet to the ShaderBufferRenderSet, which only matches the render world's copy of it"
+	);
+}
+
+/// Copy the set to the render world when it's changed. Writes to its buffers don't change it, as they go through the [BufferWriteQueue], so the copy is only made when buffers are added, deleted or configured. The swaps and rotations made by the compute steps are queued there too, and made on the render world's copy, unless it's copied again anyway, with them already made.
+fn extract_resources(
+	mut commands: Commands, buffers: Extract<Option<Res<ShaderBufferSet>>>, extracted: Option<ResMut<ShaderBufferSet>>,
+) {
 	if let Some(buffers) = &*buffers {
-		commands.insert_resource(ShaderBufferSet::extract_resource(&buffers));
+		let swaps = buffers.write_queue.take_swaps();
+		let rotations = buffers.write_queue.take_rotations();
+		match extracted {
+			Some(mut extracted) => {
+				if buffers.is_changed() {
+					*extracted = ShaderBufferSet::extract_resource(buffers);
+				} else {
+					for handle in swaps {
+						extracted.swap_front_buffer(handle);
+					}
+					for (handle, steps) in rotations {
+						extracted.rotate_ring(handle, steps);
+					}
+				}
+			}
+			None => commands.insert_resource(ShaderBufferSet::extract_resource(buffers)),
+		}
+	}
+}
+
+fn write_texture_uploads(
+	buffers: Option<Res<ShaderBufferSet>>, gpu_images: Res<RenderAssets<GpuImage>>, render_queue: Res<RenderQueue>,
+) {
+	if let Some(buffers) = buffers {
+		buffers.write_texture_uploads(&gpu_images, &render_queue);
+	}
+}
+
+/// A buffer a storage buffer or texture is copied into, to be mapped and read on the CPU. Mapping is asynchronous, and on the web it can't be waited for, so its progress is tracked here, and updated from the map callback.
+struct CopyBuffer {
+	buffer: Buffer,
+	mapping: Arc<AtomicU8>,
+	rows: Option<TextureRows>,
+	/// The offset and size the copy buffer was asked for, where a size of `None` runs to the end of the buffer, so it can be made again for the same part after a resize.
+	requested: (u64, Option<u64>),
+	/// The offset and size of the part of a storage buffer that's copied, or `None` if all of it is.
+	range: Option<(u64, u64)>,
+}
+
+/// How the rows of a texture are laid out in its copy buffer, where each is padded out to [COPY_BYTES_PER_ROW_ALIGNMENT], so the padding can be stripped when it's read.
+#[derive(Clone, Copy)]
+pub(crate) struct TextureRows {
+	padded: u32,
+	unpadded: u32,
+	count: u32,
+}
+
+/// Copy the front buffer of a buffer or texture into a buffer to be read back, laid out as [TextureRows] says for a texture, returning whether they matched. A texture that hasn't been uploaded yet is left alone.
+fn copy_out(
+	src: &ShaderBufferInfo, dst: &Buffer, rows: Option<TextureRows>, gpu_images: &RenderAssets<GpuImage>,
+	encoder: &mut CommandEncoder,
+) -> bool {
+	match (src.front_storage(), rows) {
+		(ShaderBufferStorage::Storage { buffer: src, .. }, None) => {
+			encoder.copy_buffer_to_buffer(src, 0, dst, 0, src.size());
+		}
+		(ShaderBufferStorage::StorageTexture { image, size, .. }, Some(rows)) => {
+			// The texture may not have been uploaded yet, in which case there's nothing to copy.
+			if let Some(gpu_image) = gpu_images.get(image) {
+				encoder.copy_texture_to_buffer(
+					gpu_image.texture.as_image_copy(),
+					ImageCopyBuffer {
+						buffer: dst,
+						layout: ImageDataLayout { offset: 0, bytes_per_row: Some(rows.padded), rows_per_image: Some(size.height) },
+					},
+					*size,
+				);
+			}
+		}
+		_ => return false,
+	}
+	true
+}
+
+/// The contents of a mapped copy buffer, with the padding between the rows of a texture stripped out.
+pub(crate) fn unpadded_copy(mapped: &[u8], rows: Option<TextureRows>) -> Vec<u8> {
+	match rows {
+		Some(rows) => mapped
+			.chunks(rows.padded as usize)
+			.take(rows.count as usize)
+			.flat_map(|row| &row[..rows.unpadded as usize])
+			.copied()
+			.collect(),
+		None => mapped.to_vec(),
 	}
 }
 
+const COPY_BUFFER_IDLE: u8 = 0;
+const COPY_BUFFER_MAPPING: u8 = 1;
+const COPY_BUFFER_MAPPED: u8 = 2;
+const COPY_BUFFER_FAILED: u8 = 3;
+
+/// The render world's side of the [ShaderBufferSet], which holds the GPU objects bevy_compute makes for its own use, such as bind group layouts and readback buffers. This is a resource in the render world.
+///
+/// It also provides escape hatches to the raw wgpu objects behind the buffers, for sharing them with other render crates, such as a custom post-process reading a texture a compute shader wrote. These are advanced: the objects returned are only guaranteed to stay valid for the current frame, as buffers can be deleted or swapped at any time from the main world, so they should be fetched again every frame rather than kept.
+///
+/// The bind groups and layouts of the groups are queued every frame a compute sequence is running, in [RenderSet::Queue], reusing the last bind group of every group that still binds the same buffers and textures, and the layouts of groups that still have the same bindings, so [bind_group](ShaderBufferRenderSet::bind_group), [current_layout](ShaderBufferRenderSet::current_layout) and their generations are valid from after that set until the next extract. A render node in the render graph, or a system after [RenderSet::Queue], sees the ones the compute node uses that frame.
 #[derive(Resource)]
-pub(crate) struct ShaderBufferRenderSet {
-	copy_buffers: HashMap<ShaderBufferHandle, Buffer>,
+pub struct ShaderBufferRenderSet {
+	/// The copy buffers of each compute sequence, as two sequences can read back the same buffer at once.
+	copy_buffers: HashMap<(ComputeSequenceId, ShaderBufferHandle), CopyBuffer>,
+	layouts: HashMap<Vec<BindGroupLayoutEntry>, BindGroupLayout>,
+	texture_views: TextureViews,
+	groups: Vec<QueuedGroup>,
+	unused_group: Option<(BindGroupLayout, BindGroup)>,
 }
 
 impl ShaderBufferRenderSet {
-	fn new() -> Self { Self { copy_buffers: HashMap::new() } }
+	fn new() -> Self {
+		Self {
+			copy_buffers: HashMap::new(),
+			layouts: HashMap::new(),
+			texture_views: HashMap::new(),
+			groups: Vec::new(),
+			unused_group: None,
+		}
+	}
+
+	/// Get a bind group for every group of `buffers`, with the layouts from [bind_group_layouts](ShaderBufferRenderSet::bind_group_layouts) with `per_entity` false. A group that binds the same buffers and textures, in the same order, with the same layout, as the last bind group queued for it by [queue_bind_groups](ShaderBufferRenderSet::queue_bind_groups) reuses that bind group, so only the groups where a buffer was added, deleted, replaced or resized, or a double buffer was swapped, get a new one. A group given the [unused_group](ShaderBufferRenderSet::unused_group) layout gets the unused bind group. Fails like [ShaderBufferSet::bind_group].
+	pub(crate) fn bind_groups(
+		&self, buffers: &ShaderBufferSet, device: &RenderDevice, gpu_images: &RenderAssets<GpuImage>,
+		fallback: &FallbackImage, layouts: &[BindGroupLayout],
+	) -> Result<Vec<BindGroup>, ShaderBufferError> {
+		layouts
+			.iter()
+			.zip(buffers.bound_resources(gpu_images))
+			.enumerate()
+			.map(|(group, (layout, resources))| match (self.groups.get(group), &self.unused_group) {
+				(Some(queued), _) if queued.layout.id() == layout.id() && queued.resources == resources => {
+					Ok(queued.bind_group.clone())
+				}
+				(_, Some((unused_layout, unused))) if unused_layout.id() == layout.id() => Ok(unused.clone()),
+				_ => buffers.bind_group(group, device, gpu_images, fallback, self, layout),
+			})
+			.collect()
+	}
+
+	/// Keep the bind groups just made for every group, and their layouts, for [bind_group](ShaderBufferRenderSet::bind_group) and [current_layout](ShaderBufferRenderSet::current_layout), bumping a group's generation whenever it binds something different from last frame, and its layout generation whenever its layout changed.
+	pub(crate) fn queue_bind_groups(
+		&mut self, buffers: &ShaderBufferSet, gpu_images: &RenderAssets<GpuImage>, layouts: &[BindGroupLayout],
+		bind_groups: &[BindGroup],
+	) {
+		let mut previous = std::mem::take(&mut self.groups).into_iter();
+		self.groups = layouts
+			.iter()
+			.zip(bind_groups)
+			.zip(buffers.bound_resources(gpu_images))
+			.map(|((layout, bind_group), resources)| {
+				let (generation, layout_generation) = match previous.next() {
+					Some(group) if group.layout.id() != layout.id() => {
+						(group.generation.wrapping_add(1), group.layout_generation.wrapping_add(1))
+					}
+					Some(group) if group.resources != resources => (group.generation.wrapping_add(1), group.layout_generation),
+					Some(group) => (group.generation, group.layout_generation),
+					None => (0, 0),
+				};
+				QueuedGroup { layout: layout.clone(), bind_group: bind_group.clone(), resources, generation, layout_generation }
+			})
+			.collect();
+	}
+
+	/// Get the bind group bevy_compute bound for a group this frame, or `None` if the group doesn't exist, or no compute sequence has run yet. It's only guaranteed to be valid until the next extract, but it stays the same bind group, binding the same buffers and textures, as long as [bind_group_generation](ShaderBufferRenderSet::bind_group_generation) stays the same. If no compute sequence is running this frame, this is the one from the last frame one was.
+	pub fn bind_group(&self, group: u32) -> Option<&BindGroup> { Some(&self.groups.get(group as usize)?.bind_group) }
+
+	/// Get the layout of the bind group from [bind_group](ShaderBufferRenderSet::bind_group), or `None` if the group doesn't exist, or no compute sequence has run yet. Unlike [bind_group_layout](ShaderBufferRenderSet::bind_group_layout), this doesn't need the [ShaderBufferSet], but it's only updated in [RenderSet::Queue] while a compute sequence is running.
+	pub fn current_layout(&self, group: u32) -> Option<&BindGroupLayout> {
+		Some(&self.groups.get(group as usize)?.layout)
+	}
+
+	/// How many times the bind group of a group has bound something different, such as after a buffer in it was added, deleted or replaced, or a double buffer in it was swapped, or `None` if the group doesn't exist, or no compute sequence has run yet. An external bind group made from the same buffers only needs to be remade when this changes.
+	pub fn bind_group_generation(&self, group: u32) -> Option<u32> { Some(self.groups.get(group as usize)?.generation) }
+
+	/// How many times the layout of a group has changed, such as after a buffer was added to or deleted from it, or its [visibility](ShaderBufferSet::set_visibility) changed, or `None` if the group doesn't exist, or no compute sequence has run yet. An external pipeline built against [current_layout](ShaderBufferRenderSet::current_layout) needs to be rebuilt when this changes.
+	pub fn layout_generation(&self, group: u32) -> Option<u32> {
+		Some(self.groups.get(group as usize)?.layout_generation)
+	}
 
-	pub fn create_copy_buffer(&mut self, handle: ShaderBufferHandle, buffers: &ShaderBufferSet, device: &RenderDevice) {
-		if self.copy_buffers.contains_key(&handle) {
-			panic!("Tried to create a copy buffer for {}, which already has one", handle);
+	/// Make the texture view for every texture buffer with a [TextureViewSpec] that doesn't already have one, and drop the views that are no longer used, so they're ready for [ShaderBufferRenderSet::bind_groups]. Textures that haven't been uploaded to the GPU yet are skipped, like they are when making the bind groups.
+	pub(crate) fn prepare_texture_views(&mut self, buffers: &ShaderBufferSet, gpu_images: &RenderAssets<GpuImage>) {
+		let mut views = HashMap::new();
+		for (id, buffer) in buffers.buffers.iter() {
+			let Some(spec) = buffers.texture_view(*id) else {
+				continue;
+			};
+			let dimension = buffer.front_storage().texture_dimension();
+			for image in buffer.all_image_handles() {
+				let Some(image) = gpu_images.get(&image) else {
+					continue;
+				};
+				let key = (image.texture.id(), spec);
+				let view = self.texture_views.remove(&key).unwrap_or_else(|| {
+					image.texture.create_view(&TextureViewDescriptor {
+						format: Some(image.texture_format),
+						base_mip_level: spec.base_mip_level,
+						mip_level_count: spec.mip_level_count,
+						base_array_layer: spec.base_array_layer,
+						array_layer_count: spec.array_layer_count,
+						dimension,
+						..default()
+					})
+				});
+				views.insert(key, view);
+			}
 		}
-		let Some(src) = buffers.get_buffer(handle) else {
-			panic!("Tried to create a copy buffer for {}, which does not exist", handle);
-		};
-		let storage = match &src {
-			ShaderBufferInfo::SingleBound { storage, .. } | ShaderBufferInfo::SingleUnbound { storage } => storage,
-			_ => panic!("Tried to create a copy buffer for {}, which is a double buffer", handle),
-		};
-		let ShaderBufferStorage::Storage { buffer: src, .. } = storage else {
-			panic!("Tried to create a copy buffer for {}, which is not a storage buffer", handle);
+		self.texture_views = views;
+	}
+
+	/// Get the layout with the given entries, creating it only the first time it's asked for. The pipeline cache shares pipeline layouts between pipelines whose bind group layouts are the same objects, so handing out the same layout for the same entries lets every step with the same groups share one pipeline layout, and lets bind groups stay bound from one step to the next. Groups with the same entries share a layout, which is labeled after the first of them it's made for.
+	fn layout(&mut self, device: &RenderDevice, label: &str, entries: Vec<BindGroupLayoutEntry>) -> BindGroupLayout {
+		self.layouts.entry(entries).or_insert_with_key(|entries| device.create_bind_group_layout(label, entries)).clone()
+	}
+
+	/// Get the raw wgpu buffer behind a storage or uniform buffer, or `None` if the buffer doesn't exist, or is a texture. For a double buffer, this is the current front buffer. Only valid for the current frame.
+	pub fn wgpu_buffer<'a>(&self, handle: ShaderBufferHandle, buffers: &'a ShaderBufferSet) -> Option<&'a Buffer> {
+		assert_render_world(buffers);
+		buffers.buffers.get(&handle.id())?.front_storage().raw_buffer()
+	}
+
+	/// Get the raw wgpu texture behind a texture buffer, or `None` if the buffer doesn't exist, isn't a texture, or its image hasn't been uploaded to the GPU yet. For a double buffer, this is the current front buffer. Only valid for the current frame.
+	pub fn wgpu_texture<'a>(
+		&self, handle: ShaderBufferHandle, buffers: &ShaderBufferSet, gpu_images: &'a RenderAssets<GpuImage>,
+	) -> Option<&'a Texture> {
+		assert_render_world(buffers);
+		let image = buffers.buffers.get(&handle.id())?.front_storage().image_handle()?;
+		Some(&gpu_images.get(&image)?.texture)
+	}
+
+	/// Get the bind group layout bevy_compute uses for a group of the [ShaderBufferSet], so an external render pipeline can be built against it, and share the bind group from [ComputeBindGroups](crate::ComputeBindGroups). This is the same object the compute pipelines use, so it stays the same from frame to frame as long as the buffers in the group and their [visibility](ShaderBufferSet::set_visibility) don't change. Returns `None` if the group doesn't exist. For the per-entity group, this is the layout of the empty bind group, not the one used by per-entity shaders.
+	pub fn bind_group_layout(
+		&mut self, group: u32, buffers: &ShaderBufferSet, device: &RenderDevice,
+	) -> Option<BindGroupLayout> {
+		assert_render_world(buffers);
+		let entries = buffers.bind_group_layout_entries(false).into_iter().nth(group as usize)?;
+		Some(self.layout(device, &format!("compute group {} layout", group), entries))
+	}
+
+	/// The layouts of every group of the [ShaderBufferSet]. See [ShaderBufferSet::bind_group_layout_entries] for what `per_entity` does.
+	pub(crate) fn bind_group_layouts(
+		&mut self, buffers: &ShaderBufferSet, device: &RenderDevice, per_entity: bool,
+	) -> Vec<BindGroupLayout> {
+		buffers
+			.bind_group_layout_entries(per_entity)
+			.into_iter()
+			.enumerate()
+			.map(|(group, entries)| self.layout(device, &format!("compute group {} layout", group), entries))
+			.collect()
+	}
+
+	/// The empty layout a compute sequence's pipelines have for a group below the highest one its shaders reference, which they don't reference themselves, and the empty bind group bound to it, so the sequence doesn't depend on what's in that group.
+	pub(crate) fn unused_group(&mut self, device: &RenderDevice) -> (BindGroupLayout, BindGroup) {
+		let layout = self.layout(device, "compute unused group layout", Vec::new());
+		self
+			.unused_group
+			.get_or_insert_with(|| (layout.clone(), device.create_bind_group("compute unused group", &layout, &[])))
+			.clone()
+	}
+
+	/// The layout of the per-entity group, or `None` if no per-entity buffer has ever been added.
+	pub(crate) fn per_entity_layout(
+		&mut self, buffers: &ShaderBufferSet, device: &RenderDevice,
+	) -> Option<BindGroupLayout> {
+		let (_, entry) = buffers.per_entity_layout.as_ref()?;
+		Some(self.layout(device, "compute per-entity layout", vec![*entry]))
+	}
+
+	/// Create the copy buffer a compute sequence reads a buffer back through, for reading back just `size` bytes of it from `offset` on, or everything from `offset` on if that's `None`, so the copy buffer is only that big, and only those bytes are copied and read, as a [CopyBuffer](crate::ComputeAction::CopyBuffer) step does with its offset and size when its task starts. An offset of 0 and a size of `None` read back the whole buffer, which is the only way to read back a texture. The copy buffer is made again at the new size if the buffer is [resized](ShaderBufferSet::resize_storage), as long as the part still fits in it. Each sequence has its own copy buffers, so two sequences can read back the same buffer at once. Fails without creating anything if the buffer doesn't exist, or already has a copy buffer for this sequence, or if the part can't be read back, because it's part of a texture, its offset or size aren't multiples of 4, or it doesn't fit in the buffer.
+	pub fn create_copy_buffer_range(
+		&mut self, sequence: ComputeSequenceId, handle: ShaderBufferHandle, offset: u64, size: Option<u64>,
+		buffers: &ShaderBufferSet, device: &RenderDevice,
+	) -> Result<(), ShaderBufferError> {
+		if buffers.get_buffer(handle).is_none() {
+			return Err(ShaderBufferError::MissingBuffer { buffer: handle });
+		}
+		if self.copy_buffers.contains_key(&(sequence, handle)) {
+			return Err(ShaderBufferError::DuplicateCopyBuffer { buffer: handle.describe(buffers), sequence });
+		}
+		let unreadable = |reason| ShaderBufferError::Unreadable { reason };
+		let range = buffers.check_copy_range(handle, offset, size).map_err(unreadable)?;
+		let (full_size, rows) = buffers.copy_layout(handle).map_err(unreadable)?;
+		let copy_size = match range {
+			Some((_, size)) => u32::try_from(size).map_err(|_| ShaderBufferError::Unreadable {
+				reason: format!(
+					"Tried to read back {} bytes of {}, which is too many for a copy buffer",
+					size,
+					handle.describe(buffers)
+				),
+			})?,
+			None => full_size,
 		};
+		let label = buffers.buffer_label(handle).map(|label| format!("{} (copy)", label));
 		let dst = ShaderBufferInfo::new_storage_uninit(
 			device,
-			src.size() as u32,
+			copy_size,
 			BufferUsages::COPY_DST | BufferUsages::MAP_READ,
 			Binding::SingleUnbound,
 			false,
+			label.as_deref(),
 		);
 		let ShaderBufferInfo::SingleUnbound { storage: dst_storage } = dst else {
-			panic!("Tried to create a copy buffer for {}, but somehow it ended up not unbound", handle);
+			panic!("Tried to create a copy buffer for {}, but somehow it ended up not unbound", handle.describe(buffers));
 		};
 		let ShaderBufferStorage::Storage { buffer: dst, .. } = dst_storage else {
-			panic!("Tried to create a copy buffer for {}, but somehow it ended up as a non-storage buffer", handle);
+			panic!(
+				"Tried to create a copy buffer for {}, but somehow it ended up as a non-storage buffer",
+				handle.describe(buffers)
+			);
 		};
-		self.copy_buffers.insert(handle, dst);
+		self
+			.copy_buffers
+			.insert((sequence, handle), CopyBuffer {
+				buffer: dst,
+				mapping: Arc::new(AtomicU8::new(COPY_BUFFER_IDLE)),
+				rows,
+				requested: (offset, size),
+				range,
+			});
+		Ok(())
 	}
 
-	pub fn remove_copy_buffer(&mut self, handle: ShaderBufferHandle) {
-		let Some(buffer) = self.copy_buffers.get(&handle) else {
-			panic!("Tried to remove copy buffer for {}, but it doesn't have one", handle);
+	/// Destroy every copy buffer, and drop the bind group layouts, bind groups and texture views, once the app is exiting.
+	pub(crate) fn release(&mut self) {
+		for (_, copy) in self.copy_buffers.drain() {
+			copy.buffer.destroy();
+		}
+		self.texture_views.clear();
+		self.layouts.clear();
+		self.groups.clear();
+		self.unused_group = None;
+	}
+
+	/// Make the copy buffer of a buffer again, if the buffer has been [resized](ShaderBufferSet::resize_storage) since it was made, once no readback from it is pending. The copy buffer of part of a buffer is made again for the same part, which only changes size if it runs to the end of the buffer. If the part no longer fits in the buffer, the copy buffer is left as it is, and [copy_to_copy_buffer](ShaderBufferRenderSet::copy_to_copy_buffer) reports it.
+	pub(crate) fn fit_copy_buffer(
+		&mut self, sequence: ComputeSequenceId, handle: ShaderBufferHandle, buffers: &ShaderBufferSet,
+		device: &RenderDevice,
+	) {
+		let Some(copy) = self.copy_buffers.get(&(sequence, handle)) else {
+			return;
 		};
-		buffer.destroy();
-		self.copy_buffers.remove(&handle);
+		let (offset, size) = copy.requested;
+		let (Ok((full_size, _)), Ok(range)) = (buffers.copy_layout(handle), buffers.check_copy_range(handle, offset, size))
+		else {
+			return;
+		};
+		let fitted = range.map_or(full_size as u64, |(_, size)| size);
+		if (copy.range == range && copy.buffer.size() == fitted) || self.readback_pending(sequence, handle) {
+			return;
+		}
+		self.remove_copy_buffer(sequence, handle, buffers);
+		if let Err(error) = self.create_copy_buffer_range(sequence, handle, offset, size, buffers, device) {
+			error!("Couldn't make the copy buffer of a resized buffer again: {}", error);
+		}
 	}
 
-	pub fn copy_to_copy_buffer(
-		&self, handle: ShaderBufferHandle, buffers: &ShaderBufferSet, context: &mut RenderContext,
+	/// Destroy the copy buffer a compute sequence reads a buffer back through, leaving those of other sequences alone.
+	pub(crate) fn remove_copy_buffer(
+		&mut self, sequence: ComputeSequenceId, handle: ShaderBufferHandle, buffers: &ShaderBufferSet,
 	) {
+		let Some(copy) = self.copy_buffers.remove(&(sequence, handle)) else {
+			panic!("Tried to remove copy buffer for {}, but it doesn't have one", handle.describe(buffers));
+		};
+		copy.buffer.destroy();
+	}
+
+	/// Copy a buffer into its copy buffer, to be read back on a later frame, unless a readback from the copy buffer is still pending. Returns a [ShaderBufferError::BadReadRange] if only part of the buffer is read back, and it's been resized so the part no longer fits in it, and fails too if the buffer doesn't exist, has no copy buffer, or no longer matches it, in all of which cases nothing is copied.
+	pub(crate) fn copy_to_copy_buffer(
+		&self, sequence: ComputeSequenceId, handle: ShaderBufferHandle, buffers: &ShaderBufferSet,
+		gpu_images: &RenderAssets<GpuImage>, context: &mut RenderContext,
+	) -> Result<(), ShaderBufferError> {
 		let Some(src) = buffers.get_buffer(handle) else {
-			panic!("Tried to copy from buffer {}, which doesn't exist", handle);
+			return Err(ShaderBufferError::MissingBuffer { buffer: handle });
 		};
-		let src_storage = match &src {
-			ShaderBufferInfo::SingleBound { storage, .. } | ShaderBufferInfo::SingleUnbound { storage } => storage,
-			_ => panic!("Tried to copy from buffer {}, which is a double buffer", handle),
+		let Some(dst) = self.copy_buffers.get(&(sequence, handle)) else {
+			return Err(ShaderBufferError::NoCopyBuffer { buffer: handle.describe(buffers) });
+		};
+		// A copy buffer can't be written while it's being read, so until the last
+		// readback has arrived, there's nothing to do.
+		if self.readback_pending(sequence, handle) {
+			return Ok(());
+		}
+		if let Some((offset, size)) = dst.range {
+			let ShaderBufferStorage::Storage { buffer: src, .. } = src.front_storage() else {
+				return Err(ShaderBufferError::WrongBufferKind {
+					buffer: handle.describe(buffers),
+					expected: "a storage buffer",
+				});
+			};
+			if offset.checked_add(size).is_none_or(|end| end > src.size()) {
+				return Err(ShaderBufferError::BadReadRange {
+					buffer: handle.describe(buffers),
+					offset,
+					size,
+					capacity: src.size(),
+				});
+			}
+			context.command_encoder().copy_buffer_to_buffer(src, offset, &dst.buffer, 0, size);
+		} else if !copy_out(&src, &dst.buffer, dst.rows, gpu_images, context.command_encoder()) {
+			return Err(ShaderBufferError::Unreadable {
+				reason: format!("Tried to copy from buffer {}, which doesn't match its copy buffer", handle.describe(buffers)),
+			});
+		}
+		Ok(())
+	}
+
+	/// Read the contents of a copy buffer, as copied into it on an earlier frame, without blocking. The first call starts mapping the buffer and returns `None`, and a later call, once the device has been [polled](crate::PollingMode) and the mapping has finished, returns the contents, with the copy buffer left alone until then. This is the only way to read one on the web, where nothing can block. Fails if the buffer has no copy buffer, or if the mapping failed, after which the copy buffer can be copied into and read again.
+	pub(crate) fn read_copy_buffer(
+		&self, sequence: ComputeSequenceId, handle: ShaderBufferHandle, buffers: &ShaderBufferSet,
+	) -> Result<Option<Vec<u8>>, ShaderBufferError> {
+		let Some(copy) = self.copy_buffers.get(&(sequence, handle)) else {
+			return Err(ShaderBufferError::NoCopyBuffer { buffer: handle.describe(buffers) });
 		};
-		let ShaderBufferStorage::Storage { buffer: src, .. } = src_storage else {
-			panic!("Tried to copy from buffer {}, which is not a storage buffer", handle);
+		if copy.mapping.load(Ordering::Acquire) == COPY_BUFFER_IDLE {
+			copy.mapping.store(COPY_BUFFER_MAPPING, Ordering::Release);
+			let mapping = copy.mapping.clone();
+			copy.buffer.slice(..).map_async(MapMode::Read, move |result| {
+				let state = if result.is_ok() { COPY_BUFFER_MAPPED } else { COPY_BUFFER_FAILED };
+				mapping.store(state, Ordering::Release);
+			});
+		}
+		match copy.mapping.load(Ordering::Acquire) {
+			COPY_BUFFER_MAPPED => {
+				let data = unpadded_copy(&copy.buffer.slice(..).get_mapped_range(), copy.rows);
+				copy.buffer.unmap();
+				copy.mapping.store(COPY_BUFFER_IDLE, Ordering::Release);
+				Ok(Some(data))
+			}
+			COPY_BUFFER_FAILED => {
+				copy.mapping.store(COPY_BUFFER_IDLE, Ordering::Release);
+				Err(ShaderBufferError::MapFailed { buffer: handle.describe(buffers) })
+			}
+			_ => Ok(None),
+		}
+	}
+
+	/// Read the contents of a copy buffer like [read_copy_buffer](ShaderBufferRenderSet::read_copy_buffer), but wait for the GPU to finish mapping it, so they're always returned. This blocks the render thread, so it's only available natively.
+	#[cfg(not(target_arch = "wasm32"))]
+	pub(crate) fn read_copy_buffer_blocking(
+		&self, sequence: ComputeSequenceId, handle: ShaderBufferHandle, buffers: &ShaderBufferSet, device: &RenderDevice,
+	) -> Result<Vec<u8>, ShaderBufferError> {
+		if let Some(data) = self.read_copy_buffer(sequence, handle, buffers)? {
+			return Ok(data);
+		}
+		device.poll(bevy::render::render_resource::Maintain::Wait);
+		self
+			.read_copy_buffer(sequence, handle, buffers)?
+			.ok_or_else(|| ShaderBufferError::MapFailed { buffer: handle.describe(buffers) })
+	}
+
+	/// Read the contents of a copy buffer, waiting for them with [read_copy_buffer_blocking](ShaderBufferRenderSet::read_copy_buffer_blocking) if `wait` is set, and otherwise, and always on the web, without blocking, with [read_copy_buffer](ShaderBufferRenderSet::read_copy_buffer).
+	pub(crate) fn read_copy_buffer_polled(
+		&self, sequence: ComputeSequenceId, handle: ShaderBufferHandle, buffers: &ShaderBufferSet, device: &RenderDevice,
+		wait: bool,
+	) -> Result<Option<Vec<u8>>, ShaderBufferError> {
+		#[cfg(not(target_arch = "wasm32"))]
+		if wait {
+			return self.read_copy_buffer_blocking(sequence, handle, buffers, device).map(Some);
+		}
+		#[cfg(target_arch = "wasm32")]
+		let _ = (device, wait);
+		self.read_copy_buffer(sequence, handle, buffers)
+	}
+
+	/// Read the contents of the copy buffer a compute sequence reads a buffer back through, as last copied into it by a [CopyBuffer](crate::ComputeAction::CopyBuffer) step of the sequence's running task, which are only the part of it the step reads back if it's given a range, decoded as a [ShaderType] with the same memory layout rules the buffer was written with, padding and all. This is the inverse of [write_buffer](ShaderBufferSet::write_buffer) and the `add_*_init` functions. Natively, this waits for the GPU, and always returns the value. On the web, where nothing can block, it returns `None` until the copy buffer has been mapped, which takes until a later frame. Fails if the buffer has no copy buffer in that sequence, or if the bytes aren't exactly the size of `T`, such as after the layout of a WGSL struct changed without the Rust one.
+	pub fn read_copy_buffer_as<T: ShaderType + CreateFrom>(
+		&self, sequence: ComputeSequenceId, handle: ShaderBufferHandle, buffers: &ShaderBufferSet, device: &RenderDevice,
+	) -> Result<Option<T>, ShaderBufferError> {
+		let Some(data) = self.read_typed(sequence, handle, buffers, device)? else {
+			return Ok(None);
 		};
-		let Some(dst) = self.copy_buffers.get(&handle) else {
-			panic!("Tried to copy {} to it's copy buffer, but it doesn't yet have one", handle);
+		let mismatch = |expected| ShaderBufferError::ReadbackSizeMismatch {
+			buffer: handle.describe(buffers),
+			size: data.len() as u64,
+			expected,
+			array: false,
 		};
-		let encoder = context.command_encoder();
-		encoder.copy_buffer_to_buffer(&src, 0, &dst, 0, src.size());
+		// A type holding a runtime-sized array takes as many elements as fit, so its size is only known once it's read.
+		if (data.len() as u64) < T::min_size().get() {
+			return Err(mismatch(T::min_size().get()));
+		}
+		let value: T = encase::StorageBuffer::new(data.as_slice()).create().map_err(|_| mismatch(T::min_size().get()))?;
+		if value.size().get() != data.len() as u64 {
+			return Err(mismatch(value.size().get()));
+		}
+		Ok(Some(value))
 	}
 
-	pub fn copy_from_copy_buffer_to_vec(&self, handle: ShaderBufferHandle, device: &RenderDevice) -> Vec<u8> {
-		if let Some(buffer) = self.copy_buffers.get(&handle) {
-			let buffer_slice = buffer.slice(..);
-			let (sender, receiver) = channel();
-			buffer_slice.map_async(MapMode::Read, move |result| {
-				sender.send(result).unwrap();
-			});
-			device.poll(Maintain::Wait);
-			receiver.recv().unwrap().unwrap();
-			let result = buffer_slice.get_mapped_range().to_vec();
-			buffer.unmap();
-			result
-		} else {
-			panic!("Tried to copy from buffer {} to vec when it has not yet been copied to a copy buffer", handle);
+	/// Read the contents of the copy buffer of a buffer as a runtime-sized array of a [ShaderType], such as an `array<Particle>` in WGSL, the same way as [read_copy_buffer_as](ShaderBufferRenderSet::read_copy_buffer_as). Fails if the buffer has no copy buffer, or with a [ShaderBufferError::ReadbackSizeMismatch] if the bytes aren't a whole number of elements, each taking the stride of the array, which includes any padding after them, or can't be decoded as them.
+	pub fn read_copy_buffer_as_vec<T: ShaderType + ShaderSize + CreateFrom>(
+		&self, sequence: ComputeSequenceId, handle: ShaderBufferHandle, buffers: &ShaderBufferSet, device: &RenderDevice,
+	) -> Result<Option<Vec<T>>, ShaderBufferError> {
+		let Some(data) = self.read_typed(sequence, handle, buffers, device)? else {
+			return Ok(None);
+		};
+		// An array of one element is the size of the stride, padding and all.
+		let stride = <[T; 1]>::min_size().get();
+		let mismatch = || ShaderBufferError::ReadbackSizeMismatch {
+			buffer: handle.describe(buffers),
+			size: data.len() as u64,
+			expected: stride,
+			array: true,
+		};
+		if (data.len() as u64) % stride != 0 {
+			return Err(mismatch());
 		}
+		Ok(Some(encase::StorageBuffer::new(data.as_slice()).create().map_err(|_| mismatch())?))
+	}
+
+	/// The bytes of a copy buffer for the typed readers, or the error if there's no copy buffer, or it couldn't be mapped.
+	fn read_typed(
+		&self, sequence: ComputeSequenceId, handle: ShaderBufferHandle, buffers: &ShaderBufferSet, device: &RenderDevice,
+	) -> Result<Option<Vec<u8>>, ShaderBufferError> {
+		assert_render_world(buffers);
+		self.read_copy_buffer_polled(sequence, handle, buffers, device, true)
+	}
+
+	/// Whether a copy buffer has started being read by [read_copy_buffer](ShaderBufferRenderSet::read_copy_buffer), but the contents haven't been returned yet. This only happens when it doesn't wait for the GPU.
+	pub(crate) fn readback_pending(&self, sequence: ComputeSequenceId, handle: ShaderBufferHandle) -> bool {
+		self.copy_buffers.get(&(sequence, handle)).is_some_and(|copy| copy.mapping.load(Ordering::Acquire) != COPY_BUFFER_IDLE)
 	}
 }
 
@@ -635,10 +4360,22 @@ pub(crate) struct ShaderBufferSetPlugin;
 
 impl Plugin for ShaderBufferSetPlugin {
 	fn build(&self, app: &mut App) {
-		app.insert_resource(ShaderBufferSet::new());
+		let buffers = ShaderBufferSet::new();
+		app.insert_resource(buffers.write_queue.clone());
+		app.insert_resource(buffers);
 		app
 			.sub_app_mut(RenderApp)
-			.add_systems(ExtractSchedule, extract_resources)
+			.add_systems(ExtractSchedule, (extract_resources, extract_buffer_writes))
+			.add_systems(Render, (write_texture_uploads, make_buffer_writes).in_set(RenderSet::PrepareResources))
+			.init_resource::<ExtractedBufferWrites>()
 			.insert_resource(ShaderBufferRenderSet::new());
+		app.add_systems(Update, load_textures);
+	}
+}
+
+/// Bind the textures added from handles whose images have loaded, with [ShaderBufferSet::load_textures].
+fn load_textures(mut buffers: ResMut<ShaderBufferSet>, mut images: ResMut<Assets<Image>>) {
+	if buffers.textures_loading() {
+		buffers.load_textures(&mut images);
 	}
 }
@@ -0,0 +1,68 @@
+// Finds the first NaN or infinity in `count` f32 values. `clear` resets the result, then either
+// `scan_buffer` or `scan_texture` finds the lowest index of a value that isn't finite with
+// atomicMin, from a buffer of f32s or the first channel of a texture, read in rows of `width`
+// texels, and `record_buffer` or `record_texture` copies out the bits of the value at that index.
+// An index of 0xffffffff means every value was finite. Values are checked by their bits, so the
+// check can't be optimized away by a compiler that assumes floats are always finite.
+
+struct FiniteCheckParams {
+	count: u32,
+	width: u32,
+}
+
+struct FiniteCheckResult {
+	index: atomic<u32>,
+	value: u32,
+}
+
+@group(0) @binding(0) var<uniform> params: FiniteCheckParams;
+
+@group(0) @binding(1) var<storage, read_write> result: FiniteCheckResult;
+
+// Only one of these is bound, depending on the source, and each entry point uses only its own.
+@group(0) @binding(2) var<storage, read_write> src_buffer: array<u32>;
+@group(0) @binding(2) var src_texture: texture_2d<f32>;
+
+fn is_finite(bits: u32) -> bool {
+	return (bits & 0x7f800000u) != 0x7f800000u;
+}
+
+fn texel_bits(index: u32) -> u32 {
+	return bitcast<u32>(textureLoad(src_texture, vec2<u32>(index % params.width, index / params.width), 0).x);
+}
+
+@compute @workgroup_size(1, 1, 1)
+fn clear() {
+	atomicStore(&result.index, 0xffffffffu);
+	result.value = 0u;
+}
+
+@compute @workgroup_size(256, 1, 1)
+fn scan_buffer(@builtin(global_invocation_id) id: vec3<u32>) {
+	if (id.x < params.count && !is_finite(src_buffer[id.x])) {
+		atomicMin(&result.index, id.x);
+	}
+}
+
+@compute @workgroup_size(256, 1, 1)
+fn scan_texture(@builtin(global_invocation_id) id: vec3<u32>) {
+	if (id.x < params.count && !is_finite(texel_bits(id.x))) {
+		atomicMin(&result.index, id.x);
+	}
+}
+
+@compute @workgroup_size(1, 1, 1)
+fn record_buffer() {
+	let index = atomicLoad(&result.index);
+	if (index < params.count) {
+		result.value = src_buffer[index];
+	}
+}
+
+@compute @workgroup_size(1, 1, 1)
+fn record_texture() {
+	let index = atomicLoad(&result.index);
+	if (index < params.count) {
+		result.value = texel_bits(index);
+	}
+}
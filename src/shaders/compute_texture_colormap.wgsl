@@ -0,0 +1,67 @@
+#define_import_path bevy_compute::compute_texture_colormap
+
+struct ComputeTextureMaterial {
+	colormap: u32,
+	min: f32,
+	max: f32,
+	flags: u32,
+}
+
+const COLORMAP_DIRECT: u32 = 0u;
+const COLORMAP_GRAYSCALE: u32 = 1u;
+const COLORMAP_VIRIDIS: u32 = 2u;
+const COLORMAP_LUT: u32 = 3u;
+
+const FLAG_SRGB_ENCODED: u32 = 1u;
+
+fn srgb_to_linear(color: vec3<f32>) -> vec3<f32> {
+	let low = color / 12.92;
+	let high = pow((color + 0.055) / 1.055, vec3<f32>(2.4));
+	return select(high, low, color <= vec3<f32>(0.04045));
+}
+
+// A polynomial fit of matplotlib's viridis, which outputs sRGB encoded colors.
+fn viridis(t: f32) -> vec3<f32> {
+	let c0 = vec3<f32>(0.2777273272234177, 0.005407344544966578, 0.3340998053353061);
+	let c1 = vec3<f32>(0.1050930431085774, 1.404613529898575, 1.384590162594685);
+	let c2 = vec3<f32>(-0.3308618287255563, 0.214847559468213, 0.09509516302823659);
+	let c3 = vec3<f32>(-4.634230498983486, -5.799100973351585, -19.33244095627987);
+	let c4 = vec3<f32>(6.228269936347081, 14.17993336680509, 56.69055260068105);
+	let c5 = vec3<f32>(4.776384997670288, -13.74514537774601, -65.35303263337234);
+	let c6 = vec3<f32>(-5.435455855934631, 4.645852612178535, 26.3124352495832);
+	return c0 + t * (c1 + t * (c2 + t * (c3 + t * (c4 + t * (c5 + t * c6)))));
+}
+
+// Look up the texel under the given UV coordinates, and map it to a linear color
+// according to the material's colormap.
+fn compute_texture_color(
+	material: ComputeTextureMaterial,
+	texture: texture_2d<f32>,
+	lut: texture_2d<f32>,
+	uv: vec2<f32>,
+) -> vec4<f32> {
+	let size = textureDimensions(texture);
+	let texel = min(vec2<u32>(uv * vec2<f32>(size)), size - 1u);
+	let value = textureLoad(texture, texel, 0);
+
+	if material.colormap == COLORMAP_DIRECT {
+		if (material.flags & FLAG_SRGB_ENCODED) != 0u {
+			return vec4<f32>(srgb_to_linear(value.rgb), value.a);
+		}
+		return value;
+	}
+
+	let t = clamp((value.r - material.min) / (material.max - material.min), 0.0, 1.0);
+	switch material.colormap {
+		case COLORMAP_GRAYSCALE: {
+			return vec4<f32>(srgb_to_linear(vec3<f32>(t)), 1.0);
+		}
+		case COLORMAP_VIRIDIS: {
+			return vec4<f32>(srgb_to_linear(clamp(viridis(t), vec3<f32>(0.0), vec3<f32>(1.0))), 1.0);
+		}
+		default: {
+			let lut_width = textureDimensions(lut).x;
+			return textureLoad(lut, vec2<u32>(u32(round(t * f32(lut_width - 1u))), 0u), 0);
+		}
+	}
+}
@@ -0,0 +1,11 @@
+#import bevy_sprite::mesh2d_vertex_output::VertexOutput
+#import bevy_compute::compute_texture_colormap::{ComputeTextureMaterial, compute_texture_color}
+
+@group(2) @binding(0) var<uniform> material: ComputeTextureMaterial;
+@group(2) @binding(1) var texture: texture_2d<f32>;
+@group(2) @binding(2) var lut: texture_2d<f32>;
+
+@fragment
+fn fragment(mesh: VertexOutput) -> @location(0) vec4<f32> {
+	return compute_texture_color(material, texture, lut, mesh.uv);
+}
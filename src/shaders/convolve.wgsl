@@ -0,0 +1,97 @@
+// A separable convolution of a float texture. `horizontal` applies the weights along each row of
+// the source, into a buffer of one vec4<f32> per texel, and one of the `vertical_*` entry points
+// applies them along each column of that, into the destination, in whichever format it has. They're
+// run with different bind groups, so the source and destination can be the same texture. Texels
+// past the edges are read according to `edges`: 0 clamps, 1 wraps and 2 mirrors.
+
+struct ConvolveParams {
+	width: u32,
+	height: u32,
+	radius: u32,
+	edges: u32,
+	weights: array<vec4<f32>, 16>,
+}
+
+@group(0) @binding(0) var<uniform> params: ConvolveParams;
+
+// The horizontal pass reads the source and writes the intermediate buffer.
+@group(0) @binding(1) var src: texture_2d<f32>;
+@group(0) @binding(2) var<storage, read_write> rows: array<vec4<f32>>;
+
+// The vertical pass reads the intermediate buffer and writes the destination, with only the
+// texture of its format bound.
+@group(0) @binding(1) var<storage, read_write> columns: array<vec4<f32>>;
+@group(0) @binding(2) var dst_r32float: texture_storage_2d<r32float, write>;
+@group(0) @binding(2) var dst_rgba16float: texture_storage_2d<rgba16float, write>;
+@group(0) @binding(2) var dst_rgba32float: texture_storage_2d<rgba32float, write>;
+
+fn weight(tap: i32) -> f32 {
+	let index = u32(tap + i32(params.radius));
+	return params.weights[index / 4u][index % 4u];
+}
+
+// Negative coordinates are moved up by a whole number of sizes first, to take the remainder of a
+// positive number.
+fn wrap(coord: i32, size: i32) -> i32 {
+	return (coord + size * (1 - min(coord, 0) / size)) % size;
+}
+
+fn edge(coord: i32, size: i32) -> i32 {
+	switch params.edges {
+		case 1u: {
+			return wrap(coord, size);
+		}
+		case 2u: {
+			let wrapped = wrap(coord, size * 2);
+			return select(wrapped, size * 2 - 1 - wrapped, wrapped >= size);
+		}
+		default: {
+			return clamp(coord, 0, size - 1);
+		}
+	}
+}
+
+@compute @workgroup_size(16, 16, 1)
+fn horizontal(@builtin(global_invocation_id) id: vec3<u32>) {
+	if (id.x >= params.width || id.y >= params.height) {
+		return;
+	}
+	let radius = i32(params.radius);
+	var sum = vec4<f32>(0.0);
+	for (var tap = -radius; tap <= radius; tap++) {
+		let x = edge(i32(id.x) + tap, i32(params.width));
+		sum += weight(tap) * textureLoad(src, vec2<i32>(x, i32(id.y)), 0);
+	}
+	rows[id.y * params.width + id.x] = sum;
+}
+
+fn column_sum(id: vec2<u32>) -> vec4<f32> {
+	let radius = i32(params.radius);
+	var sum = vec4<f32>(0.0);
+	for (var tap = -radius; tap <= radius; tap++) {
+		let y = u32(edge(i32(id.y) + tap, i32(params.height)));
+		sum += weight(tap) * columns[y * params.width + id.x];
+	}
+	return sum;
+}
+
+@compute @workgroup_size(16, 16, 1)
+fn vertical_r32float(@builtin(global_invocation_id) id: vec3<u32>) {
+	if (id.x < params.width && id.y < params.height) {
+		textureStore(dst_r32float, id.xy, column_sum(id.xy));
+	}
+}
+
+@compute @workgroup_size(16, 16, 1)
+fn vertical_rgba16float(@builtin(global_invocation_id) id: vec3<u32>) {
+	if (id.x < params.width && id.y < params.height) {
+		textureStore(dst_rgba16float, id.xy, column_sum(id.xy));
+	}
+}
+
+@compute @workgroup_size(16, 16, 1)
+fn vertical_rgba32float(@builtin(global_invocation_id) id: vec3<u32>) {
+	if (id.x < params.width && id.y < params.height) {
+		textureStore(dst_rgba32float, id.xy, column_sum(id.xy));
+	}
+}
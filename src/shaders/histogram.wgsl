@@ -0,0 +1,54 @@
+// Bins `count` f32 values into `bin_count` equal bins spanning `range_min` to `range_max`, counted
+// with atomics. `clear` zeroes the bins, and then either `bin_buffer` or `bin_texture` counts the
+// values, from a buffer of f32s or the first channel of a texture, read in rows of `width` texels.
+// Values outside the range, including NaNs, are dropped when `clamp` is zero, and otherwise
+// counted in the first or last bin, with NaNs always dropped. A value of exactly `range_max`
+// counts in the last bin.
+
+struct HistogramParams {
+	count: u32,
+	width: u32,
+	bin_count: u32,
+	clamp: u32,
+	range_min: f32,
+	range_max: f32,
+}
+
+@group(0) @binding(0) var<uniform> params: HistogramParams;
+
+@group(0) @binding(1) var<storage, read_write> bins: array<atomic<u32>>;
+
+// Only one of these is bound, depending on the source, and each entry point uses only its own.
+@group(0) @binding(2) var<storage, read_write> src_buffer: array<f32>;
+@group(0) @binding(2) var src_texture: texture_2d<f32>;
+
+fn add_value(value: f32) {
+	let in_range = value >= params.range_min && value <= params.range_max;
+	if (!in_range && (params.clamp == 0u || value != value)) {
+		return;
+	}
+	let scaled = (value - params.range_min) / (params.range_max - params.range_min) * f32(params.bin_count);
+	let bin = u32(clamp(scaled, 0.0, f32(params.bin_count - 1u)));
+	atomicAdd(&bins[bin], 1u);
+}
+
+@compute @workgroup_size(256, 1, 1)
+fn clear(@builtin(global_invocation_id) id: vec3<u32>) {
+	if (id.x < params.bin_count) {
+		atomicStore(&bins[id.x], 0u);
+	}
+}
+
+@compute @workgroup_size(256, 1, 1)
+fn bin_buffer(@builtin(global_invocation_id) id: vec3<u32>) {
+	if (id.x < params.count) {
+		add_value(src_buffer[id.x]);
+	}
+}
+
+@compute @workgroup_size(256, 1, 1)
+fn bin_texture(@builtin(global_invocation_id) id: vec3<u32>) {
+	if (id.x < params.count) {
+		add_value(textureLoad(src_texture, vec2<u32>(id.x % params.width, id.x / params.width), 0).x);
+	}
+}
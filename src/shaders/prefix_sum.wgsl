@@ -0,0 +1,76 @@
+// The passes of an exclusive prefix sum over `count` u32s, in blocks of 512. `scan_blocks` scans
+// each block in place, and if `write_sums` is set, writes the total of each block to `scratch`,
+// starting at `sums_offset`. Those totals are then scanned the same way, recursively, until they
+// fit in one block, and `add_sums` adds the scanned totals back to every element of the blocks
+// they came from, working back down to the buffer itself. The data is the buffer being scanned
+// when `in_scratch` is zero, or otherwise a region of `scratch` starting at `offset`.
+
+struct PrefixSumParams {
+	count: u32,
+	in_scratch: u32,
+	offset: u32,
+	write_sums: u32,
+	sums_offset: u32,
+}
+
+@group(0) @binding(0) var<uniform> params: PrefixSumParams;
+
+@group(0) @binding(1) var<storage, read_write> buffer: array<u32>;
+
+@group(0) @binding(2) var<storage, read_write> scratch: array<u32>;
+
+var<workgroup> sums: array<u32, 256>;
+
+fn load(index: u32) -> u32 {
+	if (index >= params.count) {
+		return 0u;
+	}
+	if (params.in_scratch != 0u) {
+		return scratch[params.offset + index];
+	}
+	return buffer[index];
+}
+
+fn store(index: u32, value: u32) {
+	if (index >= params.count) {
+		return;
+	}
+	if (params.in_scratch != 0u) {
+		scratch[params.offset + index] = value;
+	} else {
+		buffer[index] = value;
+	}
+}
+
+@compute @workgroup_size(256, 1, 1)
+fn scan_blocks(@builtin(local_invocation_id) local_id: vec3<u32>, @builtin(workgroup_id) workgroup_id: vec3<u32>) {
+	let first = workgroup_id.x * 512u + local_id.x * 2u;
+	let a = load(first);
+	let b = load(first + 1u);
+	let pair = a + b;
+	sums[local_id.x] = pair;
+	workgroupBarrier();
+	for (var stride = 1u; stride < 256u; stride <<= 1u) {
+		var earlier = 0u;
+		if (local_id.x >= stride) {
+			earlier = sums[local_id.x - stride];
+		}
+		workgroupBarrier();
+		sums[local_id.x] += earlier;
+		workgroupBarrier();
+	}
+	let before = sums[local_id.x] - pair;
+	store(first, before);
+	store(first + 1u, before + a);
+	if (params.write_sums != 0u && local_id.x == 255u) {
+		scratch[params.sums_offset + workgroup_id.x] = sums[255];
+	}
+}
+
+@compute @workgroup_size(256, 1, 1)
+fn add_sums(@builtin(local_invocation_id) local_id: vec3<u32>, @builtin(workgroup_id) workgroup_id: vec3<u32>) {
+	let first = workgroup_id.x * 512u + local_id.x * 2u;
+	let total = scratch[params.sums_offset + workgroup_id.x];
+	store(first, load(first) + total);
+	store(first + 1u, load(first + 1u) + total);
+}
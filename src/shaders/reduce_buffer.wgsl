@@ -0,0 +1,111 @@
+// One pass of a parallel reduction, over `count` 4-byte elements. Each workgroup reduces up to 512
+// elements down to one, so a reduction is made of a sequence of these passes, each with 512 times
+// fewer elements than the last, until only one workgroup is left, which writes the result to the
+// front of `dst`. The first pass reads from `src`, and every later pass from the output of the one
+// before it, in `scratch`, which holds two regions used in turn, starting at `in_offset` and
+// `out_offset`.
+//
+// Elements past `count` are replaced by the identity of the operation, such as zero for a sum, so
+// counts that aren't a multiple of the workgroup size don't change the result.
+
+struct ReduceParams {
+	count: u32,
+	op: u32,
+	element: u32,
+	from_scratch: u32,
+	in_offset: u32,
+	to_dst: u32,
+	out_offset: u32,
+}
+
+const OP_SUM: u32 = 0u;
+const OP_MIN: u32 = 1u;
+const OP_MAX: u32 = 2u;
+
+const ELEMENT_F32: u32 = 0u;
+const ELEMENT_U32: u32 = 1u;
+const ELEMENT_I32: u32 = 2u;
+
+@group(0) @binding(0) var<uniform> params: ReduceParams;
+
+@group(0) @binding(1) var<storage, read_write> src: array<u32>;
+
+@group(0) @binding(2) var<storage, read_write> dst: array<u32>;
+
+@group(0) @binding(3) var<storage, read_write> scratch: array<u32>;
+
+var<workgroup> partial: array<u32, 256>;
+
+fn identity() -> u32 {
+	if (params.op == OP_MIN) {
+		// The bit patterns of positive infinity, and the largest u32 and i32.
+		return select(select(0x7fffffffu, 0xffffffffu, params.element == ELEMENT_U32), 0x7f800000u, params.element == ELEMENT_F32);
+	}
+	if (params.op == OP_MAX) {
+		// The bit patterns of negative infinity, and the smallest u32 and i32.
+		return select(select(0x80000000u, 0u, params.element == ELEMENT_U32), 0xff800000u, params.element == ELEMENT_F32);
+	}
+	return 0u;
+}
+
+fn combine(a: u32, b: u32) -> u32 {
+	if (params.element == ELEMENT_F32) {
+		let x = bitcast<f32>(a);
+		let y = bitcast<f32>(b);
+		if (params.op == OP_MIN) {
+			return bitcast<u32>(min(x, y));
+		}
+		if (params.op == OP_MAX) {
+			return bitcast<u32>(max(x, y));
+		}
+		return bitcast<u32>(x + y);
+	}
+	if (params.element == ELEMENT_I32) {
+		let x = bitcast<i32>(a);
+		let y = bitcast<i32>(b);
+		if (params.op == OP_MIN) {
+			return bitcast<u32>(min(x, y));
+		}
+		if (params.op == OP_MAX) {
+			return bitcast<u32>(max(x, y));
+		}
+		return bitcast<u32>(x + y);
+	}
+	if (params.op == OP_MIN) {
+		return min(a, b);
+	}
+	if (params.op == OP_MAX) {
+		return max(a, b);
+	}
+	return a + b;
+}
+
+fn load(index: u32) -> u32 {
+	if (index >= params.count) {
+		return identity();
+	}
+	if (params.from_scratch != 0u) {
+		return scratch[params.in_offset + index];
+	}
+	return src[index];
+}
+
+@compute @workgroup_size(256, 1, 1)
+fn reduce(@builtin(local_invocation_id) local_id: vec3<u32>, @builtin(workgroup_id) workgroup_id: vec3<u32>) {
+	let first = workgroup_id.x * 512u + local_id.x;
+	partial[local_id.x] = combine(load(first), load(first + 256u));
+	workgroupBarrier();
+	for (var stride = 128u; stride > 0u; stride >>= 1u) {
+		if (local_id.x < stride) {
+			partial[local_id.x] = combine(partial[local_id.x], partial[local_id.x + stride]);
+		}
+		workgroupBarrier();
+	}
+	if (local_id.x == 0u) {
+		if (params.to_dst != 0u) {
+			dst[0] = partial[0];
+		} else {
+			scratch[params.out_offset + workgroup_id.x] = partial[0];
+		}
+	}
+}
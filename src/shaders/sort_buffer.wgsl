@@ -0,0 +1,62 @@
+// One step of a bitonic sort, over `count` elements of `stride` words each, ordered by the key
+// `key_offset` words into each element. Each invocation compares one pair of elements, and swaps
+// them if they're out of order. The sort is made of a sequence of these steps, each with a
+// different `span`.
+//
+// This uses the variant of the bitonic network where every comparison sorts in ascending order,
+// which starts each block with a "flip" step comparing mirrored pairs. Elements past `count` are
+// treated as if they held a key larger than any other, so any pair that reaches past `count` is
+// already in order, which is how counts that aren't a power of two are handled without padding the
+// buffer.
+
+struct SortParams {
+	span: u32,
+	flip: u32,
+	count: u32,
+	stride: u32,
+	key_offset: u32,
+	key_type: u32,
+}
+
+const KEY_U32: u32 = 0u;
+const KEY_F32: u32 = 1u;
+
+@group(0) @binding(0) var<uniform> params: SortParams;
+
+@group(0) @binding(1) var<storage, read_write> data: array<u32>;
+
+fn sort_key(index: u32) -> u32 {
+	let bits = data[index * params.stride + params.key_offset];
+	if (params.key_type == KEY_F32) {
+		// Flipping the sign bit of positive floats, and every bit of negative floats, makes their
+		// bit patterns sort in the same order as the floats themselves.
+		let mask = select(0x80000000u, 0xffffffffu, (bits >> 31u) == 1u);
+		return bits ^ mask;
+	}
+	return bits;
+}
+
+@compute @workgroup_size(256, 1, 1)
+fn bitonic_step(@builtin(global_invocation_id) invocation_id: vec3<u32>) {
+	let span = params.span;
+	let block = invocation_id.x / span;
+	let offset = invocation_id.x % span;
+	let first = block * 2u * span + offset;
+	var second = first + span;
+	if (params.flip != 0u) {
+		second = block * 2u * span + 2u * span - 1u - offset;
+	}
+	if (second >= params.count) {
+		return;
+	}
+
+	if (sort_key(first) > sort_key(second)) {
+		for (var i = 0u; i < params.stride; i++) {
+			let a = first * params.stride + i;
+			let b = second * params.stride + i;
+			let temp = data[a];
+			data[a] = data[b];
+			data[b] = temp;
+		}
+	}
+}
@@ -0,0 +1,133 @@
+use bevy::{
+	prelude::*,
+	render::{render_resource::PipelineCache, renderer::RenderDevice},
+};
+use bytemuck::{Pod, Zeroable};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use super::builtin_pass::{BuiltinBinding, BuiltinDispatch, BuiltinPass};
+use crate::shader_buffer_set::{ShaderBufferHandle, ShaderBufferSet};
+
+pub(crate) const SORT_BUFFER_SHADER_HANDLE: Handle<Shader> =
+	Handle::weak_from_u128(0x5c1e_7a0b_3d84_4f62_9e0c_b1a6_2f47_d813);
+
+const WORKGROUP_SIZE: u32 = 256;
+
+/// The type of the key a [SortBuffer](crate::ComputeAction::SortBuffer) action sorts by.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum SortKeyType {
+	/// The key is a `u32`.
+	U32,
+
+	/// The key is an `f32`. Negative numbers sort before positive ones, and NaNs sort to one end or the other depending on their sign bit.
+	F32,
+}
+
+/// Describes the layout of the elements sorted by a [SortBuffer](crate::ComputeAction::SortBuffer) action. The buffer is treated as a tightly packed array of elements, each `stride` bytes long, each with a 4-byte key `offset` bytes into it. Both must be multiples of 4. When the buffer holds an array of structs, the stride is the size of the struct as laid out by WGSL, including any padding.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct SortKey {
+	/// The type of the key.
+	pub key_type: SortKeyType,
+
+	/// The offset of the key within each element, in bytes.
+	pub offset: u32,
+
+	/// The size of each element, in bytes.
+	pub stride: u32,
+}
+
+impl SortKey {
+	/// Sort a plain array of `u32`s.
+	pub fn u32() -> Self { Self { key_type: SortKeyType::U32, offset: 0, stride: 4 } }
+
+	/// Sort a plain array of `f32`s.
+	pub fn f32() -> Self { Self { key_type: SortKeyType::F32, offset: 0, stride: 4 } }
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Pod, Zeroable)]
+struct SortParams {
+	span: u32,
+	flip: u32,
+	count: u32,
+	stride: u32,
+	key_offset: u32,
+	key_type: u32,
+}
+
+/// Check that a buffer can be sorted by a key, returning what's wrong if it can't, which [sort_buffer_pass] panics with.
+pub(crate) fn check_sort_buffer(
+	buffers: &ShaderBufferSet, buffer: ShaderBufferHandle, element_count: u32, key: SortKey,
+) -> Result<(), String> {
+	if key.offset % 4 != 0 || key.stride % 4 != 0 || key.stride == 0 || key.offset >= key.stride {
+		return Err(format!(
+			"Tried to sort {} with a key at offset {} and a stride of {}, but both must be multiples of 4, and the key must be inside the element",
+			buffer.describe(buffers),
+			key.offset,
+			key.stride
+		));
+	}
+	let Some(gpu_buffer) = buffers.gpu_buffer(buffer) else {
+		return Err(format!("Tried to sort {}, which isn't a storage buffer", buffer.describe(buffers)));
+	};
+	if element_count as u64 * key.stride as u64 > gpu_buffer.size() {
+		return Err(format!(
+			"Tried to sort {} elements of {} bytes in {}, which is too small to hold them",
+			element_count,
+			key.stride,
+			buffer.describe(buffers)
+		));
+	}
+	Ok(())
+}
+
+/// Build the passes for a bitonic sort. The element count is rounded up to a power of two, and the sort is made of log2(n) stages, each made of a flip step followed by a descending series of steps, for log2(n) * (log2(n) + 1) / 2 dispatches in total.
+pub(crate) fn sort_buffer_pass(
+	device: &RenderDevice, pipeline_cache: &PipelineCache, buffers: &ShaderBufferSet, buffer: ShaderBufferHandle,
+	element_count: u32, key: SortKey,
+) -> BuiltinPass {
+	if let Err(error) = check_sort_buffer(buffers, buffer, element_count, key) {
+		panic!("{}", error);
+	}
+
+	let padded_count = element_count.max(1).next_power_of_two();
+	let workgroups = UVec3::new((padded_count / 2).div_ceil(WORKGROUP_SIZE).max(1), 1, 1);
+	let mut dispatches = Vec::new();
+	let mut block = 2;
+	while block <= padded_count {
+		let mut span = block / 2;
+		let mut flip = 1;
+		while span >= 1 {
+			dispatches.push(BuiltinDispatch {
+				entry_point: 0,
+				params: SortParams {
+					span,
+					flip,
+					count: element_count,
+					stride: key.stride / 4,
+					key_offset: key.offset / 4,
+					key_type: match key.key_type {
+						SortKeyType::U32 => 0,
+						SortKeyType::F32 => 1,
+					},
+				},
+				workgroups,
+			});
+			span /= 2;
+			flip = 0;
+		}
+		block *= 2;
+	}
+
+	BuiltinPass::new(
+		device,
+		pipeline_cache,
+		SORT_BUFFER_SHADER_HANDLE,
+		&["bitonic_step"],
+		vec![BuiltinBinding::Buffer(buffer)],
+		dispatches,
+	)
+}
@@ -0,0 +1,95 @@
+use std::sync::{Arc, Mutex};
+
+use bevy::{
+	prelude::*,
+	render::{
+		render_resource::{BufferInitDescriptor, BufferUsages, CommandEncoder},
+		renderer::RenderDevice,
+	},
+};
+
+use crate::shader_buffer_set::{ShaderBufferHandle, ShaderBufferSet};
+
+/// Identifies a write queued with [queue_write_at](ShaderBufferSet::queue_write_at), so it can be matched up with the [StepWriteAppliedEvent] sent once it's been made.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct StepWriteId(u64);
+
+/// This event is sent once a write queued with [queue_write_at](ShaderBufferSet::queue_write_at) has been made, so the main world can let go of whatever edit it was holding on to. Each queued write is made, and this event sent for it, exactly once. It arrives a frame or two after the write was made, like the [ComputeProgress](crate::ComputeProgress).
+#[derive(Event, Clone, Debug, PartialEq, Eq)]
+pub struct StepWriteAppliedEvent {
+	/// The id [queue_write_at](ShaderBufferSet::queue_write_at) returned for the write.
+	pub id: StepWriteId,
+
+	/// The buffer that was written.
+	pub buffer: ShaderBufferHandle,
+
+	/// The index of the task the write was made in, as in, the index into the `Vec<ComputeTask>` that was provided in the [StartComputeEvent](crate::StartComputeEvent).
+	pub task: usize,
+
+	/// The index of the step the write was made after, in the task's steps.
+	pub step: usize,
+
+	/// The number of iterations of the task that had run before the one the write was made in.
+	pub iteration: u32,
+}
+
+/// A write queued with [queue_write_at](ShaderBufferSet::queue_write_at), waiting for its task to run.
+struct StepWrite {
+	id: StepWriteId,
+	buffer: ShaderBufferHandle,
+	task: usize,
+	step: usize,
+	data: Vec<u8>,
+}
+
+#[derive(Default)]
+struct StepWritesState {
+	next_id: u64,
+	writes: Vec<StepWrite>,
+}
+
+/// The writes queued with [queue_write_at](ShaderBufferSet::queue_write_at), shared by every copy of the [ShaderBufferSet], so the main world can queue them, and the render world can take them out as it records them.
+#[derive(Clone, Default)]
+pub(crate) struct StepWrites(Arc<Mutex<StepWritesState>>);
+
+impl StepWrites {
+	pub fn queue(&self, buffer: ShaderBufferHandle, task: usize, step: usize, data: Vec<u8>) -> StepWriteId {
+		let mut state = self.0.lock().unwrap();
+		let id = StepWriteId(state.next_id);
+		state.next_id += 1;
+		state.writes.push(StepWrite { id, buffer, task, step, data });
+		id
+	}
+
+	/// Whether there are writes to a task due before one of its steps, which are the ones queued after any earlier step, or due at the end of an iteration if there's no step, which is all of them.
+	pub fn due(&self, task: usize, before_step: Option<usize>) -> bool {
+		let state = self.0.lock().unwrap();
+		state.writes.iter().any(|write| write.task == task && before_step.is_none_or(|step| write.step < step))
+	}
+
+	/// Copy the writes [due](Self::due) at this point of an iteration into every half of their buffers, in the order they were queued, and take them out of the queue, returning the events to send for them.
+	pub fn apply(
+		&self, task: usize, before_step: Option<usize>, iteration: u32, buffers: &ShaderBufferSet, device: &RenderDevice,
+		encoder: &mut CommandEncoder,
+	) -> Vec<StepWriteAppliedEvent> {
+		let mut state = self.0.lock().unwrap();
+		let (due, waiting) = std::mem::take(&mut state.writes)
+			.into_iter()
+			.partition::<Vec<_>, _>(|write| write.task == task && before_step.is_none_or(|step| write.step < step));
+		state.writes = waiting;
+		due
+			.into_iter()
+			.map(|write| {
+				let staging = device.create_buffer_with_data(&BufferInitDescriptor {
+					label: None,
+					contents: &write.data,
+					usage: BufferUsages::COPY_SRC,
+				});
+				for buffer in buffers.all_gpu_buffers(write.buffer) {
+					encoder.copy_buffer_to_buffer(&staging, 0, &buffer, 0, write.data.len() as u64);
+				}
+				StepWriteAppliedEvent { id: write.id, buffer: write.buffer, task, step: write.step, iteration }
+			})
+			.collect()
+	}
+}
@@ -0,0 +1,305 @@
+//! Utilities for writing tests of compute shaders, which run a headless Bevy app with the render backend, without a window. Only available with the `test-utils` feature.
+//!
+//! ```Rust
+//! #[test]
+//! fn doubles_every_element() {
+//!     let Some(mut app) = ComputeTestApp::new() else {
+//!         return;
+//!     };
+//!     let buffer = app.add_storage_init(vec![1.0f32, 2.0, 3.0], Binding::SingleBound(0, 0));
+//!     app.run_until_complete(vec![ComputeTask {
+//!         label: None,
+//!         iterations: NonZeroU32::new(1),
+//!         run_mode: TaskRunMode::Continuous,
+//!         budget: None,
+//!         submission: TaskSubmission::WithFrame,
+//!         start_after: None,
+//!         iteration_buffer: None,
+//!         steps: vec![ComputeStep { schedule: StepSchedule::EveryIteration, run_if: None, action: run_double_shader() }],
+//!     }]);
+//!     assert_buffer_approx_eq(&app.read_buffer::<f32>(buffer), &[2.0, 4.0, 6.0], 1e-6);
+//!     app.assert_buffer_all(buffer, |value: f32| value.is_finite());
+//! }
+//! ```
+
+use std::{fmt::Debug, num::NonZeroU32};
+
+use bevy::{
+	app::{Plugins, PluginsState},
+	prelude::*,
+	render::{
+		pipelined_rendering::PipelinedRenderingPlugin,
+		render_resource::{encase::internal::WriteInto, BufferUsages, ShaderType},
+		renderer::{RenderDevice, RenderQueue},
+		RenderPlugin,
+	},
+	tasks::block_on,
+	window::ExitCondition,
+	winit::WinitPlugin,
+};
+use bytemuck::Pod;
+
+use crate::{
+	f16_to_f32, BevyComputePlugin, Binding, BufferReadbackEvent, ComputeAction, ComputePluginSettings, ComputeSequenceId,
+	ComputeState, ComputeStep, ComputeTask, PollingMode, ShaderBufferHandle, ShaderBufferSet, StartComputeEvent,
+	StepSchedule, TaskRunMode, TaskSubmission,
+};
+
+/// The number of frames [run_until_complete](ComputeTestApp::run_until_complete) will wait for a compute sequence to finish before giving up. This includes the frames spent waiting for shaders to compile.
+pub const MAX_FRAMES: u32 = 10_000;
+
+/// The number of mismatching elements the assertions here list in their panic messages. Any more are only counted, so a buffer that's wrong everywhere doesn't bury the first few differences.
+pub const MAX_REPORTED_MISMATCHES: usize = 16;
+
+#[derive(Resource, Default)]
+struct TestReadbacks(Vec<BufferReadbackEvent>);
+
+fn collect_readbacks(mut readbacks: ResMut<TestReadbacks>, mut events: EventReader<BufferReadbackEvent>) {
+	readbacks.0.extend(events.read().cloned());
+}
+
+/// A headless Bevy app with the render backend and the [BevyComputePlugin], for running compute sequences to completion and reading back the results in tests.
+pub struct ComputeTestApp {
+	app: App,
+}
+
+impl ComputeTestApp {
+	/// Create the app. If there's no GPU adapter available, as on many CI machines, or the adapter can't run compute shaders, this logs a warning and returns `None`, so tests can skip themselves gracefully instead of failing.
+	pub fn new() -> Option<Self> { Self::with_polling(PollingMode::default()) }
+
+	/// Create the app, as with [new](ComputeTestApp::new), with the plugin polling the GPU the given way. With [Manual](PollingMode::Manual) polling, the test has to poll the GPU itself, or nothing that waits on it will ever finish.
+	pub fn with_polling(polling: PollingMode) -> Option<Self> { Self::build(polling, ()) }
+
+	/// Create the app, as with [new](ComputeTestApp::new), with more plugins added alongside the [BevyComputePlugin], such as the [ComputeProfilingPlugin](crate::ComputeProfilingPlugin), which can't be added once the app has been made.
+	pub fn with_plugins<M>(plugins: impl Plugins<M>) -> Option<Self> { Self::build(PollingMode::default(), plugins) }
+
+	fn build<M>(polling: PollingMode, plugins: impl Plugins<M>) -> Option<Self> {
+		let instance = wgpu::Instance::new(wgpu::InstanceDescriptor::default());
+		if block_on(instance.request_adapter(&wgpu::RequestAdapterOptions::default())).is_none() {
+			warn!("No GPU adapter is available, so the compute test is being skipped");
+			return None;
+		}
+
+		let mut app = App::new();
+		app
+			.insert_resource(ComputePluginSettings { polling, ..default() })
+			.add_plugins((
+				DefaultPlugins
+					.set(WindowPlugin {
+						primary_window: None,
+						exit_condition: ExitCondition::DontExit,
+						close_when_requested: false,
+					})
+					.set(RenderPlugin { synchronous_pipeline_compilation: true, ..default() })
+					.disable::<WinitPlugin>()
+					.disable::<PipelinedRenderingPlugin>()
+					.disable::<bevy::log::LogPlugin>(),
+				BevyComputePlugin,
+			))
+			.add_plugins(plugins)
+			.init_resource::<TestReadbacks>()
+			.add_systems(Update, collect_readbacks);
+		while app.plugins_state() == PluginsState::Adding {
+			bevy::tasks::tick_global_task_pools_on_main_thread();
+		}
+		app.finish();
+		app.cleanup();
+		if *app.world().resource::<ComputeState>() == ComputeState::Unsupported {
+			warn!("The GPU adapter doesn't support compute shaders, so the compute test is being skipped");
+			return None;
+		}
+		Some(Self { app })
+	}
+
+	/// The underlying Bevy app, for anything not covered by the other functions.
+	pub fn app(&mut self) -> &mut App { &mut self.app }
+
+	/// The [ShaderBufferSet], for adding buffers with functions that aren't wrapped here.
+	pub fn buffer_set(&mut self) -> Mut<'_, ShaderBufferSet> { self.app.world_mut().resource_mut::<ShaderBufferSet>() }
+
+	/// Add a storage buffer initialized with the provided data, which can be copied from and written to by shaders. See [add_storage_init](ShaderBufferSet::add_storage_init). Panics if it can't be added, such as when another buffer is already bound there.
+	pub fn add_storage_init<T: ShaderType + WriteInto + Clone + Default>(
+		&mut self, data: T, binding: Binding,
+	) -> ShaderBufferHandle {
+		let world = self.app.world_mut();
+		let render_device = world.resource::<RenderDevice>().clone();
+		let render_queue = world.resource::<RenderQueue>().clone();
+		world
+			.resource_mut::<ShaderBufferSet>()
+			.add_storage_init(
+				&render_device,
+				&render_queue,
+				data,
+				BufferUsages::STORAGE | BufferUsages::COPY_SRC | BufferUsages::COPY_DST,
+				binding,
+				false,
+			)
+			.unwrap_or_else(|error| panic!("{}", error))
+	}
+
+	/// Start a compute sequence with the given tasks, and update the app until it's finished. Every task must have a finite number of iterations. Panics if the sequence hasn't finished after [MAX_FRAMES] frames.
+	pub fn run_until_complete(&mut self, tasks: Vec<ComputeTask>) {
+		if tasks.iter().any(|task| task.iterations.is_none()) {
+			panic!("Tried to run a compute sequence to completion, but it has a task that runs forever");
+		}
+		self.app.world_mut().send_event(StartComputeEvent {
+			sequence: ComputeSequenceId::MAIN,
+			tasks,
+			iteration_buffer: None,
+			seed: 0,
+		});
+		self.app.update();
+		for _ in 0..MAX_FRAMES {
+			self.app.update();
+			if *self.app.world().resource::<ComputeState>() == ComputeState::Finished {
+				return;
+			}
+		}
+		panic!("The compute sequence didn't finish within {} frames", MAX_FRAMES);
+	}
+
+	/// Read the contents of a storage buffer or texture back to the CPU, as a [Vec] of some plain-old-data type, such as `u32`, `f32` or `[f32; 4]`. This runs a compute sequence of its own, so it can't be called while another is running. A storage buffer must have been created with [BufferUsages::COPY_SRC], and can't be a double buffer. A double buffered texture is read from its front buffer.
+	pub fn read_buffer<T: Pod>(&mut self, handle: ShaderBufferHandle) -> Vec<T> {
+		self.app.world_mut().resource_mut::<TestReadbacks>().0.clear();
+		self.run_until_complete(vec![ComputeTask {
+			label: None,
+			iterations: NonZeroU32::new(2),
+			run_mode: TaskRunMode::Continuous,
+			budget: None,
+			submission: TaskSubmission::WithFrame,
+			start_after: None,
+			iteration_buffer: None,
+			steps: vec![ComputeStep {
+				schedule: StepSchedule::EveryIteration,
+				run_if: None,
+				action: ComputeAction::CopyBuffer { src: handle, offset: 0, size: None },
+			}],
+		}]);
+		// The readback is sent before the task is marked as done, but both arrive
+		// in the same frame, so update once more to make sure it's been collected.
+		self.app.update();
+		let readbacks = &self.app.world().resource::<TestReadbacks>().0;
+		let Some(readback) = readbacks.iter().rev().find(|readback| readback.handle == handle) else {
+			panic!("Tried to read back {}, but no data came back", handle.describe(self.app.world().resource()));
+		};
+		bytemuck::pod_collect_to_vec(&readback.data)
+	}
+
+	/// Read back a storage buffer or texture with [read_buffer](ComputeTestApp::read_buffer), decoding it as 16-bit floats, such as the channels of an [Rgba16Float](bevy::render::render_resource::TextureFormat::Rgba16Float) texture, converted to `f32`s.
+	pub fn read_buffer_f16(&mut self, handle: ShaderBufferHandle) -> Vec<f32> {
+		self.read_buffer::<u16>(handle).into_iter().map(f16_to_f32).collect()
+	}
+
+	/// Read back a storage buffer or texture with [read_buffer](ComputeTestApp::read_buffer), and assert that it's exactly the expected elements. On failure, the panic message lists the first [MAX_REPORTED_MISMATCHES] elements that differ.
+	pub fn assert_buffer_eq<T: Pod + PartialEq + Debug>(&mut self, handle: ShaderBufferHandle, expected: &[T]) {
+		let actual = self.read_buffer::<T>(handle);
+		let name = handle.describe(self.app.world().resource());
+		if actual.len() != expected.len() {
+			panic!("{} has {} elements, but {} were expected", name, actual.len(), expected.len());
+		}
+		let mismatches = actual
+			.iter()
+			.zip(expected)
+			.enumerate()
+			.filter(|(_, (actual, expected))| actual != expected)
+			.map(|(i, (actual, expected))| format!("  [{}]: got {:?}, expected {:?}", i, actual, expected));
+		fail_on_mismatches(mismatches, |count| format!("{} of {} elements of {} differ", count, actual.len(), name));
+	}
+
+	/// Read back a storage buffer or texture with [read_buffer](ComputeTestApp::read_buffer), and assert that every element satisfies the predicate, such as `|value: f32| value.is_finite()`. On failure, the panic message lists the first [MAX_REPORTED_MISMATCHES] elements that don't.
+	pub fn assert_buffer_all<T: Pod + Debug>(&mut self, handle: ShaderBufferHandle, predicate: impl Fn(T) -> bool) {
+		let actual = self.read_buffer::<T>(handle);
+		let name = handle.describe(self.app.world().resource());
+		let mismatches = actual
+			.iter()
+			.enumerate()
+			.filter(|(_, value)| !predicate(**value))
+			.map(|(i, value)| format!("  [{}]: got {:?}", i, value));
+		fail_on_mismatches(mismatches, |count| {
+			format!("{} of {} elements of {} don't satisfy the predicate", count, actual.len(), name)
+		});
+	}
+
+	/// Read back a texture with [read_buffer](ComputeTestApp::read_buffer), and assert that every channel of the pixel at `x`, `y` is within `tolerance` of the expected channels, where `T` is the type of one channel, such as `f32` for [R32Float](bevy::render::render_resource::TextureFormat::R32Float) or `u8` for [Rgba8Unorm](bevy::render::render_resource::TextureFormat::Rgba8Unorm). Panics if the buffer isn't a texture, the pixel is out of bounds, or the number of expected channels doesn't match the format.
+	pub fn assert_texture_pixel<T: Pod + Debug + Into<f64>>(
+		&mut self, handle: ShaderBufferHandle, x: u32, y: u32, expected: &[T], tolerance: T,
+	) {
+		let buffers = self.app.world().resource::<ShaderBufferSet>();
+		let name = handle.describe(buffers);
+		let Some((format, size)) = buffers.texture_format_and_size(handle) else {
+			panic!("Tried to check a pixel of {}, but it isn't a texture", name);
+		};
+		if x >= size.width || y >= size.height {
+			panic!("Tried to check pixel ({}, {}) of {}, but it's only {}x{}", x, y, name, size.width, size.height);
+		}
+		let pixel_size = format.block_copy_size(None).unwrap_or(0) as usize;
+		if pixel_size != size_of_val(expected) {
+			panic!(
+				"Tried to check pixel ({}, {}) of {}, but {} channels of {} bytes don't make up a {:?} pixel",
+				x,
+				y,
+				name,
+				expected.len(),
+				size_of::<T>(),
+				format
+			);
+		}
+		let start = (y * size.width + x) as usize * expected.len();
+		let actual = self.read_buffer::<T>(handle)[start..start + expected.len()].to_vec();
+		let out_of_tolerance = actual.iter().zip(expected).any(|(actual, expected)| {
+			let difference = ((*actual).into() - (*expected).into()).abs();
+			difference.is_nan() || difference > tolerance.into()
+		});
+		if out_of_tolerance {
+			panic!(
+				"Pixel ({}, {}) of {} differs by more than {:?}: got {:?}, expected {:?}",
+				x, y, name, tolerance, actual, expected
+			);
+		}
+	}
+}
+
+/// Panic if there are any mismatches, with the summary for their count followed by the first [MAX_REPORTED_MISMATCHES] of them.
+fn fail_on_mismatches(mut mismatches: impl Iterator<Item = String>, summary: impl FnOnce(usize) -> String) {
+	let reported = mismatches.by_ref().take(MAX_REPORTED_MISMATCHES).collect::<Vec<_>>();
+	if reported.is_empty() {
+		return;
+	}
+	let unreported = mismatches.count();
+	let mut message = format!("{}:\n{}", summary(reported.len() + unreported), reported.join("\n"));
+	if unreported > 0 {
+		message += &format!("\n  ... and {} more", unreported);
+	}
+	panic!("{}", message);
+}
+
+/// The app is exited when it's dropped, so everything the plugin made on the GPU is released in order, rather than along with the device, as it is in a real app.
+impl Drop for ComputeTestApp {
+	fn drop(&mut self) {
+		// Updating again while a test is already panicking could panic a second time, and abort.
+		if !std::thread::panicking() {
+			self.app.world_mut().send_event(AppExit::Success);
+			self.app.update();
+		}
+	}
+}
+
+/// Assert that two slices are the same length, and that every element of `actual` is within `tolerance` of the corresponding element of `expected`. On failure, the panic message lists the first [MAX_REPORTED_MISMATCHES] elements that are out of tolerance.
+pub fn assert_buffer_approx_eq(actual: &[f32], expected: &[f32], tolerance: f32) {
+	if actual.len() != expected.len() {
+		panic!("Buffer has {} elements, but {} were expected", actual.len(), expected.len());
+	}
+	let mismatches = actual
+		.iter()
+		.zip(expected)
+		.enumerate()
+		// NaNs never compare as within tolerance, so they always count as mismatches.
+		.filter(|(_, (actual, expected))| {
+			let difference = (*actual - *expected).abs();
+			difference.is_nan() || difference > tolerance
+		})
+		.map(|(i, (actual, expected))| format!("  [{}]: got {}, expected {}", i, actual, expected));
+	fail_on_mismatches(mismatches, |count| {
+		format!("{} of {} elements differ by more than {}", count, actual.len(), tolerance)
+	});
+}
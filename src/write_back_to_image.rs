@@ -0,0 +1,61 @@
+use bevy::{
+	prelude::*,
+	render::{
+		render_asset::RenderAssetUsages,
+		render_resource::{Extent3d, TextureDimension, TextureFormat},
+	},
+};
+
+use super::{BufferReadbackEvent, ComputeErrorEvent, WriteBackToImageEvent};
+use crate::shader_buffer_set::ShaderBufferSet;
+
+/// The write-backs asked for with a [WriteBackToImageEvent] that are still waiting for their buffer to be read back.
+#[derive(Resource, Default)]
+pub(crate) struct ImageWriteBacks(Vec<WriteBackToImageEvent>);
+
+/// Queue up every [WriteBackToImageEvent], and write each readback of a texture buffer into the images waiting for it.
+pub(crate) fn write_back_to_images(
+	mut requests: EventReader<WriteBackToImageEvent>, mut readbacks: EventReader<BufferReadbackEvent>,
+	mut pending: ResMut<ImageWriteBacks>, buffers: Res<ShaderBufferSet>, mut images: ResMut<Assets<Image>>,
+	mut errors: EventWriter<ComputeErrorEvent>,
+) {
+	for request in requests.read() {
+		if buffers.texture_format_and_size(request.buffer).is_none() {
+			panic!("Tried to write {} back to an image, but it isn't a texture", request.buffer.describe(&buffers));
+		}
+		pending.0.push(request.clone());
+	}
+	for readback in readbacks.read() {
+		let Some((format, size)) = buffers.texture_format_and_size(readback.handle) else {
+			continue;
+		};
+		for request in pending.0.iter().filter(|request| request.buffer == readback.handle) {
+			write_back_to_image(request, format, size, &readback.data, &mut images, &mut errors);
+		}
+		pending.0.retain(|request| request.buffer != readback.handle);
+	}
+}
+
+fn write_back_to_image(
+	request: &WriteBackToImageEvent, format: TextureFormat, size: Extent3d, data: &[u8], images: &mut Assets<Image>,
+	errors: &mut EventWriter<ComputeErrorEvent>,
+) {
+	let Some(image) = images.get_mut(&request.target) else {
+		let image = Image::new(size, TextureDimension::D2, data.to_vec(), format, RenderAssetUsages::all());
+		images.insert(&request.target, image);
+		return;
+	};
+	// A texture added with an sRGB format is stored in the linear one, but holds the same bytes.
+	let image_format = image.texture_descriptor.format;
+	if image_format.remove_srgb_suffix() != format {
+		errors.send(ComputeErrorEvent::ImageFormatMismatch {
+			buffer: request.buffer,
+			target: request.target.clone(),
+			buffer_format: format,
+			image_format,
+		});
+		return;
+	}
+	image.texture_descriptor.size = size;
+	image.data = data.to_vec();
+}
@@ -0,0 +1,6650 @@
+use std::{
+	num::NonZeroU32,
+	panic::{catch_unwind, AssertUnwindSafe},
+	time::Duration,
+};
+
+use bevy::{
+	asset::{Assets, RenderAssetUsages},
+	image::Image,
+	prelude::{
+		AppExit, AssetServer, ButtonInput, Camera, Camera3d, DetectChanges, EventReader, EventWriter, IntoSystemConfigs,
+		Local, MouseButton, Mut, Plugin, PostUpdate, Rect, Res, ResMut, Resource, Shader, Transform, UVec2, UVec3, Update,
+		Vec2, Vec3, Vec4,
+	},
+	render::{
+		camera::RenderTarget,
+		graph::CameraDriverLabel,
+		render_asset::RenderAssetBytesPerFrame,
+		render_graph::RenderGraph,
+		render_resource::{
+			BindingType, BufferBindingType, BufferUsages, CommandEncoderDescriptor, ComputePassDescriptor,
+			DispatchIndirectArgs, DrawIndirectArgs, Extent3d, FilterMode, Maintain, PipelineLayoutDescriptor,
+			RawComputePipelineDescriptor, SamplerDescriptor, ShaderModuleDescriptor, ShaderSource, ShaderStages, Source,
+			StorageTextureAccess, TextureDimension, TextureSampleType, TextureUsages, WgpuFeatures,
+		},
+		renderer::{RenderAdapterInfo, RenderDevice, RenderQueue},
+		Render, RenderApp, RenderSet,
+	},
+};
+use bevy_compute::{
+	encode_time_path, f16_to_f32, f32_to_f16, iteration_seed, iterations_per_second_path,
+	prelude::*,
+	render::{ComputeBindGroups, ComputeLabel, ComputeWorld, ShaderBufferRenderSet},
+	test_utils::{assert_buffer_approx_eq, ComputeTestApp, MAX_FRAMES, MAX_REPORTED_MISMATCHES},
+	ComputeValidationReport, ShaderBufferError, TraceEvent,
+};
+#[cfg(feature = "checkpoint")]
+use bevy_compute::{
+	BuffersLoadedEvent, BuffersSavedEvent, CheckpointEntryProblem, CheckpointError, CheckpointFailedEvent,
+	CheckpointLayout, LoadBuffersEvent, SaveBuffersEvent, CHECKPOINT_VERSION,
+};
+
+const SHADER_ASSET_PATH: &str = "shaders/test_kernels.wgsl";
+
+fn run_shader(entry_point: &str) -> ComputeStep {
+	ComputeStep {
+		schedule: StepSchedule::EveryIteration,
+		run_if: None,
+		action: ComputeAction::RunShader {
+			shader: SHADER_ASSET_PATH.to_owned(),
+			entry_point: entry_point.to_owned(),
+			shader_defs: Vec::new(),
+			overrides: Vec::new(),
+			dispatch: DispatchSize::Workgroups { x: 1, y: 1, z: 1 },
+			auto_swap: Vec::new(),
+			skip_if_empty: None,
+			buffer_orientation: Vec::new(),
+			push_constants: None,
+			dynamic_offsets: Vec::new(),
+		},
+	}
+}
+
+fn run_shader_per_entity(entry_point: &str) -> ComputeStep {
+	ComputeStep {
+		schedule: StepSchedule::EveryIteration,
+		run_if: None,
+		action: ComputeAction::RunShaderPerEntity {
+			shader: SHADER_ASSET_PATH.to_owned(),
+			entry_point: entry_point.to_owned(),
+			shader_defs: Vec::new(),
+			overrides: Vec::new(),
+			dispatch: DispatchSize::Workgroups { x: 1, y: 1, z: 1 },
+		},
+	}
+}
+
+#[test]
+fn runs_a_task_for_its_iteration_count() {
+	let Some(mut app) = ComputeTestApp::new() else {
+		return;
+	};
+	let data = app.add_storage_init(vec![0.0f32; 16], Binding::SingleBound(0, 0));
+	app.run_until_complete(vec![ComputeTask {
+		label: None,
+		iterations: NonZeroU32::new(5),
+		run_mode: TaskRunMode::Continuous,
+		budget: None,
+		submission: TaskSubmission::WithFrame,
+		start_after: None,
+		iteration_buffer: None,
+		steps: vec![run_shader("increment")],
+	}]);
+	assert_buffer_approx_eq(&app.read_buffer::<f32>(data), &[5.0; 16], 0.0);
+}
+
+#[derive(Resource, Default)]
+struct DoneIterations(Vec<u32>);
+
+#[derive(Resource, Default)]
+struct DoneLabels(Vec<Option<String>>);
+
+#[test]
+fn sends_one_done_event_per_finite_task_and_none_for_endless_ones() {
+	let Some(mut app) = ComputeTestApp::new() else {
+		return;
+	};
+	app.app().init_resource::<DoneLabels>().add_systems(
+		Update,
+		|mut events: EventReader<ComputeTaskDoneEvent>, mut done: ResMut<DoneLabels>| {
+			done.0.extend(events.read().map(|event| event.group_finished_label.clone()))
+		},
+	);
+	app.add_storage_init(vec![0.0f32; 16], Binding::SingleBound(0, 0));
+	let task = |label: &str, iterations| ComputeTask {
+		label: Some(label.to_owned()),
+		iterations: NonZeroU32::new(iterations),
+		run_mode: TaskRunMode::Continuous,
+		budget: None,
+		submission: TaskSubmission::WithFrame,
+		start_after: None,
+		iteration_buffer: None,
+		steps: vec![run_shader("increment")],
+	};
+	app.app().world_mut().send_event(StartComputeEvent {
+		sequence: ComputeSequenceId::MAIN,
+		tasks: vec![task("once", 1), task("bake", 3), task("forever", 0)],
+		iteration_buffer: None,
+		seed: 0,
+	});
+	for _ in 0..100 {
+		app.app().update();
+	}
+	assert_eq!(app.app().world().resource::<DoneLabels>().0, vec![Some("once".to_owned()), Some("bake".to_owned())]);
+	assert!(matches!(app.app().world().resource::<ComputeState>(), ComputeState::Running { .. }));
+}
+
+#[test]
+fn extends_and_restarts_tasks_by_runtime_iteration_counts() {
+	let Some(mut app) = ComputeTestApp::new() else {
+		return;
+	};
+	app.app().init_resource::<DoneIterations>().add_systems(
+		Update,
+		|mut events: EventReader<ComputeTaskDoneEvent>, mut done: ResMut<DoneIterations>| {
+			done.0.extend(events.read().map(|event| event.iterations))
+		},
+	);
+	let data = app.add_storage_init(vec![0.0f32; 16], Binding::SingleBound(0, 0));
+	app.app().world_mut().resource_mut::<ComputeTaskIterations>().0.insert("count".to_owned(), 5);
+
+	// The task would run forever, but runs for the 5 iterations in the resource instead, extended by 3.
+	app.app().world_mut().send_event(StartComputeEvent {
+		sequence: ComputeSequenceId::MAIN,
+		tasks: vec![ComputeTask {
+			label: Some("count".to_owned()),
+			iterations: None,
+			run_mode: TaskRunMode::Continuous,
+			budget: None,
+			submission: TaskSubmission::WithFrame,
+			start_after: None,
+			iteration_buffer: None,
+			steps: vec![run_shader("increment")],
+		}],
+		iteration_buffer: None,
+		seed: 0,
+	});
+	app.app().update();
+	app.app().world_mut().send_event(ExtendComputeTaskEvent {
+		sequence: ComputeSequenceId::MAIN,
+		task: 0,
+		additional: 3,
+	});
+	for _ in 0..200 {
+		app.app().update();
+		if *app.app().world().resource::<ComputeState>() == ComputeState::Finished {
+			break;
+		}
+	}
+	assert_eq!(app.app().world().resource::<DoneIterations>().0, vec![8]);
+
+	// Extending the finished task runs it again, for just the additional iterations.
+	app.app().world_mut().send_event(ExtendComputeTaskEvent {
+		sequence: ComputeSequenceId::MAIN,
+		task: 0,
+		additional: 2,
+	});
+	app.app().update();
+	for _ in 0..200 {
+		app.app().update();
+		if *app.app().world().resource::<ComputeState>() == ComputeState::Finished {
+			break;
+		}
+	}
+	assert_eq!(app.app().world().resource::<DoneIterations>().0, vec![8, 2]);
+	assert_buffer_approx_eq(&app.read_buffer::<f32>(data), &[10.0; 16], 0.0);
+}
+
+#[test]
+fn runs_on_demand_tasks_for_just_the_iterations_they_are_triggered_for() {
+	let Some(mut app) = ComputeTestApp::new() else {
+		return;
+	};
+	let data = app.add_storage_init(vec![0.0f32; 16], Binding::SingleBound(0, 0));
+	// The on-demand task runs in a sequence of its own, so buffers can be read back with the main one.
+	let picking = ComputeSequenceId(1);
+	app.app().world_mut().send_event(StartComputeEvent {
+		sequence: picking,
+		tasks: vec![ComputeTask {
+			label: Some("pick".to_owned()),
+			iterations: None,
+			run_mode: TaskRunMode::OnDemand,
+			budget: None,
+			submission: TaskSubmission::WithFrame,
+			start_after: None,
+			iteration_buffer: None,
+			steps: vec![run_shader("increment")],
+		}],
+		iteration_buffer: None,
+		seed: 0,
+	});
+	let iterations = |app: &mut ComputeTestApp| {
+		app.app().world().resource::<ComputeSequenceStates>().progress(picking).map_or(0, |progress| progress.iterations)
+	};
+	let run_until = |app: &mut ComputeTestApp, target| {
+		for _ in 0..MAX_FRAMES {
+			app.app().update();
+			if iterations(app) >= target {
+				break;
+			}
+		}
+		// A few more frames, to be sure nothing more runs than was triggered.
+		for _ in 0..5 {
+			app.app().update();
+		}
+	};
+
+	// Until it's triggered, the task sits idle.
+	run_until(&mut app, 1);
+	assert_eq!(iterations(&mut app), 0);
+	assert_buffer_approx_eq(&app.read_buffer::<f32>(data), &[0.0; 16], 0.0);
+
+	// Triggers sent in the same frame add up, and one for a label with no on-demand task is only logged.
+	for (label, count) in [("pick", 2), ("pick", 1), ("missing", 5)] {
+		app.app().world_mut().send_event(TriggerComputeTaskEvent {
+			sequence: picking,
+			label: label.to_owned(),
+			iterations: count,
+		});
+	}
+	run_until(&mut app, 3);
+	assert_eq!(iterations(&mut app), 3);
+	let states = app.app().world().resource::<ComputeSequenceStates>();
+	assert!(matches!(states.state(picking), Some(ComputeState::Running { .. })));
+	assert_buffer_approx_eq(&app.read_buffer::<f32>(data), &[3.0; 16], 0.0);
+
+	// Triggered again, it carries on from where it left off.
+	app.app().world_mut().send_event(TriggerComputeTaskEvent {
+		sequence: picking,
+		label: "pick".to_owned(),
+		iterations: 1,
+	});
+	run_until(&mut app, 4);
+	assert_eq!(iterations(&mut app), 4);
+	assert_buffer_approx_eq(&app.read_buffer::<f32>(data), &[4.0; 16], 0.0);
+}
+
+#[test]
+fn piles_up_triggers_sent_while_an_on_demand_task_runs_and_then_drains_them() {
+	let Some(mut app) = ComputeTestApp::new() else {
+		return;
+	};
+	let data = app.add_storage_init(vec![0.0f32; 16], Binding::SingleBound(0, 0));
+	let picking = ComputeSequenceId(1);
+	app.app().world_mut().send_event(StartComputeEvent {
+		sequence: picking,
+		tasks: vec![ComputeTask {
+			label: Some("pick".to_owned()),
+			iterations: None,
+			run_mode: TaskRunMode::OnDemand,
+			budget: None,
+			submission: TaskSubmission::WithFrame,
+			start_after: None,
+			iteration_buffer: None,
+			steps: vec![run_shader("increment")],
+		}],
+		iteration_buffer: None,
+		seed: 0,
+	});
+	// Triggers are only taken once the sequence has started, the frame after it's sent.
+	app.app().update();
+	let trigger = |app: &mut ComputeTestApp, iterations| {
+		app.app().world_mut().send_event(TriggerComputeTaskEvent {
+			sequence: picking,
+			label: "pick".to_owned(),
+			iterations,
+		});
+	};
+	let iterations = |app: &mut ComputeTestApp| {
+		app.app().world().resource::<ComputeSequenceStates>().progress(picking).map_or(0, |progress| progress.iterations)
+	};
+
+	// More triggers arrive while the task is still running the iterations of the first, and pile up on top of them.
+	trigger(&mut app, 3);
+	for _ in 0..MAX_FRAMES {
+		app.app().update();
+		if iterations(&mut app) >= 1 {
+			break;
+		}
+	}
+	assert!(iterations(&mut app) < 3);
+	trigger(&mut app, 2);
+	trigger(&mut app, 1);
+	for _ in 0..MAX_FRAMES {
+		app.app().update();
+		if iterations(&mut app) >= 6 {
+			break;
+		}
+	}
+
+	// Once they've all run, the task drains to idle, running nothing more.
+	for _ in 0..5 {
+		app.app().update();
+	}
+	assert_eq!(iterations(&mut app), 6);
+	assert_buffer_approx_eq(&app.read_buffer::<f32>(data), &[6.0; 16], 0.0);
+
+	// Triggers that would overflow the count saturate, rather than panicking.
+	trigger(&mut app, u32::MAX);
+	trigger(&mut app, u32::MAX);
+	for _ in 0..5 {
+		app.app().update();
+	}
+	assert!(iterations(&mut app) > 6);
+	app.app().world_mut().send_event(StopComputeEvent(picking));
+	app.app().update();
+}
+
+#[derive(Resource, Default)]
+struct CompletedTasks(Vec<(u64, Option<Vec<f32>>)>);
+
+#[test]
+fn matches_one_shot_tasks_to_their_own_sequence() {
+	let Some(mut app) = ComputeTestApp::new() else {
+		return;
+	};
+	app.app().init_resource::<CompletedTasks>().add_systems(
+		Update,
+		|mut events: EventReader<ComputeTaskCompleteEvent>, mut completed: ResMut<CompletedTasks>| {
+			completed.0.extend(events.read().map(|event| {
+				let floats = |data: &Vec<u8>| -> Vec<f32> {
+					bytemuck::cast_slice::<u8, [u8; 4]>(data).iter().map(|bytes| f32::from_le_bytes(*bytes)).collect()
+				};
+				(event.task_id, event.data.as_ref().map(floats))
+			}))
+		},
+	);
+	let data = app.add_storage_init(vec![0.0f32; 16], Binding::SingleBound(0, 0));
+	let other = app.add_storage_init(vec![7.0f32; 4], Binding::SingleBound(0, 2));
+
+	// Another sequence finishes the same task indices as the one-shot task's batch, with readbacks of its own, and
+	// gets there first.
+	let read_other = |label: &str| ComputeTask {
+		label: Some(label.to_owned()),
+		iterations: NonZeroU32::new(1),
+		run_mode: TaskRunMode::Continuous,
+		budget: None,
+		submission: TaskSubmission::WithFrame,
+		start_after: None,
+		iteration_buffer: None,
+		steps: vec![ComputeStep {
+			schedule: StepSchedule::EveryIteration,
+			run_if: None,
+			action: ComputeAction::ReadBuffer { buffer: other },
+		}],
+	};
+	app.app().world_mut().send_event(StartComputeEvent {
+		sequence: ComputeSequenceId(1),
+		tasks: vec![read_other("first"), read_other("second")],
+		iteration_buffer: None,
+		seed: 0,
+	});
+	app.app().world_mut().send_event(RunComputeTaskEvent {
+		task_id: 0,
+		task: OneShotTask::new(SHADER_ASSET_PATH, "increment", UVec3::ONE).readback(data),
+	});
+	for _ in 0..MAX_FRAMES {
+		app.app().update();
+		if !app.app().world().resource::<CompletedTasks>().0.is_empty() {
+			break;
+		}
+	}
+	for _ in 0..5 {
+		app.app().update();
+	}
+
+	// The one-shot task completes once, with its own readback, and its sequence is stopped once it's done.
+	assert_eq!(app.app().world().resource::<CompletedTasks>().0, vec![(0, Some(vec![1.0; 16]))]);
+	let states = app.app().world().resource::<ComputeSequenceStates>();
+	assert_eq!(states.sequences().collect::<Vec<_>>(), vec![ComputeSequenceId(1)]);
+}
+
+#[test]
+fn completes_one_shot_tasks_sent_with_the_same_id_with_their_own_readbacks() {
+	let Some(mut app) = ComputeTestApp::new() else {
+		return;
+	};
+	app.app().init_resource::<CompletedTasks>().add_systems(
+		Update,
+		|mut events: EventReader<ComputeTaskCompleteEvent>, mut completed: ResMut<CompletedTasks>| {
+			completed.0.extend(events.read().map(|event| {
+				let floats = |data: &Vec<u8>| -> Vec<f32> {
+					bytemuck::cast_slice::<u8, [u8; 4]>(data).iter().map(|bytes| f32::from_le_bytes(*bytes)).collect()
+				};
+				(event.task_id, event.data.as_ref().map(floats))
+			}))
+		},
+	);
+	let data = app.add_storage_init(vec![0.0f32; 16], Binding::SingleBound(0, 0));
+	let other = app.add_storage_init(vec![7.0f32; 4], Binding::SingleBound(0, 2));
+	for readback in [data, other] {
+		app.app().world_mut().send_event(RunComputeTaskEvent {
+			task_id: 5,
+			task: OneShotTask::new(SHADER_ASSET_PATH, "increment", UVec3::ONE).bind(data).readback(readback),
+		});
+	}
+	for _ in 0..MAX_FRAMES {
+		app.app().update();
+		if app.app().world().resource::<CompletedTasks>().0.len() >= 2 {
+			break;
+		}
+	}
+	assert_eq!(app.app().world().resource::<CompletedTasks>().0, vec![(5, Some(vec![1.0; 16])), (5, Some(vec![7.0; 4]))]);
+}
+
+#[test]
+#[should_panic(expected = "isn't bound in any group")]
+fn panics_when_binding_an_unbound_buffer_for_a_one_shot_task() {
+	let Some(mut app) = ComputeTestApp::new() else {
+		panic!("isn't bound in any group, but there's no GPU to check it on");
+	};
+	app.add_storage_init(vec![0.0f32; 16], Binding::SingleBound(0, 0));
+	let unbound = app.add_storage_init(vec![0.0f32; 16], Binding::SingleUnbound);
+	app.app().world_mut().send_event(RunComputeTaskEvent {
+		task_id: 0,
+		task: OneShotTask::new(SHADER_ASSET_PATH, "increment", UVec3::ONE).bind(unbound),
+	});
+	app.app().update();
+}
+
+#[test]
+fn stops_tasks_when_their_stop_conditions_are_met() {
+	let Some(mut app) = ComputeTestApp::new() else {
+		return;
+	};
+	app.app().init_resource::<DoneIterations>().add_systems(
+		Update,
+		|mut events: EventReader<ComputeTaskDoneEvent>, mut done: ResMut<DoneIterations>| {
+			done.0.extend(events.read().map(|event| event.iterations))
+		},
+	);
+	let data = app.add_storage_init(vec![0.0f32; 16], Binding::SingleBound(0, 0));
+	let condition = app.app().world_mut().register_system(|progress: Res<ComputeProgress>| progress.iterations >= 3);
+	app.app().world_mut().resource_mut::<ComputeTaskStopConditions>().0.insert("stop".to_owned(), condition);
+
+	// The task would run forever, but stops once the condition sees 3 iterations, which lags behind the GPU a little.
+	app.app().world_mut().send_event(StartComputeEvent {
+		sequence: ComputeSequenceId::MAIN,
+		tasks: vec![ComputeTask {
+			label: Some("stop".to_owned()),
+			iterations: None,
+			run_mode: TaskRunMode::Continuous,
+			budget: None,
+			submission: TaskSubmission::WithFrame,
+			start_after: None,
+			iteration_buffer: None,
+			steps: vec![run_shader("increment")],
+		}],
+		iteration_buffer: None,
+		seed: 0,
+	});
+	for _ in 0..200 {
+		app.app().update();
+		if *app.app().world().resource::<ComputeState>() == ComputeState::Finished {
+			break;
+		}
+	}
+	let done = app.app().world().resource::<DoneIterations>().0.clone();
+	assert_eq!(done.len(), 1);
+	assert!(done[0] >= 3);
+	assert_buffer_approx_eq(&app.read_buffer::<f32>(data), &[done[0] as f32; 16], 0.0);
+}
+
+#[test]
+fn pauses_tasks_whose_iterations_overrun_their_watchdogs() {
+	let Some(mut app) = ComputeTestApp::new() else {
+		return;
+	};
+	app
+		.app()
+		.init_resource::<Errors>()
+		.add_systems(Update, |mut events: EventReader<ComputeErrorEvent>, mut errors: ResMut<Errors>| {
+			errors.0.extend(events.read().cloned())
+		});
+	app.add_storage_init(vec![0.0f32; 16], Binding::SingleBound(0, 0));
+	// No iteration can finish in no time at all, so the first one trips the watchdog.
+	app.app().world_mut().resource_mut::<ComputeTaskWatchdogs>().0.insert("watched".to_owned(), Duration::ZERO);
+	app.app().world_mut().send_event(StartComputeEvent {
+		sequence: ComputeSequenceId::MAIN,
+		tasks: vec![ComputeTask {
+			label: Some("watched".to_owned()),
+			iterations: None,
+			run_mode: TaskRunMode::Continuous,
+			budget: None,
+			submission: TaskSubmission::WithFrame,
+			start_after: None,
+			iteration_buffer: None,
+			steps: vec![run_shader("increment")],
+		}],
+		iteration_buffer: None,
+		seed: 0,
+	});
+	for _ in 0..200 {
+		app.app().update();
+		if !app.app().world().resource::<Errors>().0.is_empty() {
+			break;
+		}
+	}
+	let errors = &app.app().world().resource::<Errors>().0;
+	assert!(matches!(
+		errors.first(),
+		Some(ComputeErrorEvent::WatchdogTimeout { task: 0, label: Some(label), steps, .. }) if label == "watched" && steps == &[0]
+	));
+
+	// Once paused, the task stops iterating, and picks up again when it's resumed.
+	for _ in 0..5 {
+		app.app().update();
+	}
+	let paused_iterations = app.app().world().resource::<ComputeProgress>().iterations;
+	for _ in 0..5 {
+		app.app().update();
+	}
+	assert_eq!(app.app().world().resource::<ComputeProgress>().iterations, paused_iterations);
+	assert_eq!(*app.app().world().resource::<ComputeState>(), ComputeState::Paused { active_groups: vec![0] });
+	app.app().world_mut().resource_mut::<ComputeTaskWatchdogs>().0.clear();
+	app.app().world_mut().send_event(PauseComputeTaskEvent { sequence: ComputeSequenceId::MAIN, task: 0, paused: false });
+	for _ in 0..10 {
+		app.app().update();
+	}
+	assert!(app.app().world().resource::<ComputeProgress>().iterations > paused_iterations);
+	assert_eq!(*app.app().world().resource::<ComputeState>(), ComputeState::Running { active_groups: vec![0] });
+}
+
+#[test]
+fn pauses_and_resumes_the_whole_sequence_where_it_left_off() {
+	let Some(mut app) = ComputeTestApp::new() else {
+		return;
+	};
+	let data = app.add_storage_init(vec![0.0f32; 16], Binding::SingleBound(0, 0));
+	let double = app.add_storage_init(vec![0.0f32; 16], Binding::Double(0, (1, 2)));
+	app.app().world_mut().send_event(StartComputeEvent {
+		sequence: ComputeSequenceId::MAIN,
+		tasks: vec![
+			ComputeTask {
+				label: None,
+				iterations: NonZeroU32::new(20),
+				run_mode: TaskRunMode::Continuous,
+				budget: None,
+				submission: TaskSubmission::WithFrame,
+				start_after: None,
+				iteration_buffer: None,
+				steps: vec![
+					run_shader("advance"),
+					ComputeStep {
+						schedule: StepSchedule::EveryIteration,
+						run_if: None,
+						action: ComputeAction::SwapBuffers { buffer: double },
+					},
+				],
+			},
+			ComputeTask {
+				label: None,
+				iterations: NonZeroU32::new(1),
+				run_mode: TaskRunMode::Continuous,
+				budget: None,
+				submission: TaskSubmission::WithFrame,
+				start_after: None,
+				iteration_buffer: None,
+				steps: vec![run_shader("snapshot")],
+			},
+		],
+		iteration_buffer: None,
+		seed: 0,
+	});
+	for _ in 0..MAX_FRAMES {
+		app.app().update();
+		if app.app().world().resource::<ComputeProgress>().iterations >= 2 {
+			break;
+		}
+	}
+	app.app().world_mut().send_event(PauseComputeEvent(ComputeSequenceId::MAIN));
+	// The pause reaches the render world a frame or two later, after which nothing more is run.
+	for _ in 0..5 {
+		app.app().update();
+	}
+	let paused_iterations = app.app().world().resource::<ComputeProgress>().iterations;
+	for _ in 0..5 {
+		app.app().update();
+	}
+	assert_eq!(app.app().world().resource::<ComputeProgress>().iterations, paused_iterations);
+	assert!(paused_iterations < 20);
+	assert_eq!(app.app().world().resource::<ComputeSequenceInfo>().tasks[0].status, ComputeTaskStatus::Paused);
+	assert_eq!(*app.app().world().resource::<ComputeState>(), ComputeState::Paused { active_groups: vec![0] });
+	let states = app.app().world().resource::<ComputeSequenceStates>();
+	assert_eq!(states.state(ComputeSequenceId::MAIN), Some(&ComputeState::Paused { active_groups: vec![0] }));
+
+	// Resumed, the task runs only the iterations it had left, so the front buffer ends up one higher for each.
+	app.app().world_mut().send_event(ResumeComputeEvent(ComputeSequenceId::MAIN));
+	app.app().update();
+	assert_eq!(*app.app().world().resource::<ComputeState>(), ComputeState::Running { active_groups: vec![0] });
+	for _ in 0..MAX_FRAMES {
+		app.app().update();
+		if *app.app().world().resource::<ComputeState>() == ComputeState::Finished {
+			break;
+		}
+	}
+	assert_eq!(*app.app().world().resource::<ComputeState>(), ComputeState::Finished);
+	app.assert_buffer_eq(data, &[20.0f32; 16]);
+}
+
+#[test]
+fn stops_the_sequence_and_starts_another_after_it() {
+	let Some(mut app) = ComputeTestApp::new() else {
+		return;
+	};
+	let data = app.add_storage_init(vec![0.0f32; 16], Binding::SingleBound(0, 0));
+	app.app().world_mut().send_event(StartComputeEvent {
+		sequence: ComputeSequenceId::MAIN,
+		tasks: vec![ComputeTask {
+			label: None,
+			iterations: None,
+			run_mode: TaskRunMode::Continuous,
+			budget: None,
+			submission: TaskSubmission::WithFrame,
+			start_after: None,
+			iteration_buffer: None,
+			steps: vec![run_shader("increment")],
+		}],
+		iteration_buffer: None,
+		seed: 0,
+	});
+	for _ in 0..MAX_FRAMES {
+		app.app().update();
+		if app.app().world().resource::<ComputeProgress>().iterations >= 2 {
+			break;
+		}
+	}
+	app.app().world_mut().send_event(StopComputeEvent(ComputeSequenceId::MAIN));
+	app.app().update();
+	assert_eq!(*app.app().world().resource::<ComputeState>(), ComputeState::Idle);
+	assert!(app.app().world().get_resource::<ComputeSequenceInfo>().is_none());
+	for _ in 0..5 {
+		app.app().update();
+	}
+	let stopped = app.read_buffer::<f32>(data);
+	assert!(stopped[0] >= 2.0);
+	assert!(stopped.iter().all(|&value| value == stopped[0]));
+
+	// Nothing more is run once it's stopped, and a new sequence carries on from what it left in the buffers.
+	for _ in 0..5 {
+		app.app().update();
+	}
+	app.run_until_complete(vec![ComputeTask {
+		label: None,
+		iterations: NonZeroU32::new(3),
+		run_mode: TaskRunMode::Continuous,
+		budget: None,
+		submission: TaskSubmission::WithFrame,
+		start_after: None,
+		iteration_buffer: None,
+		steps: vec![run_shader("increment")],
+	}]);
+	app.assert_buffer_eq(data, &[stopped[0] + 3.0; 16]);
+}
+
+#[test]
+fn runs_independent_sequences_at_once() {
+	#[derive(Resource, Default)]
+	struct DoneSequences(Vec<(ComputeSequenceId, Option<String>)>);
+
+	let Some(mut app) = ComputeTestApp::new() else {
+		return;
+	};
+	app.app().init_resource::<DoneSequences>().add_systems(
+		Update,
+		|mut events: EventReader<ComputeTaskDoneEvent>, mut done: ResMut<DoneSequences>| {
+			done.0.extend(events.read().map(|event| (event.sequence, event.group_finished_label.clone())))
+		},
+	);
+	let data = app.add_storage_init(vec![0.0f32; 16], Binding::SingleBound(0, 0));
+	let picking = ComputeSequenceId(1);
+	let task = |label: &str, iterations| ComputeTask {
+		label: Some(label.to_owned()),
+		iterations: NonZeroU32::new(iterations),
+		run_mode: TaskRunMode::Continuous,
+		budget: None,
+		submission: TaskSubmission::WithFrame,
+		start_after: None,
+		iteration_buffer: None,
+		steps: vec![run_shader("increment")],
+	};
+	app.app().world_mut().send_event(StartComputeEvent {
+		sequence: ComputeSequenceId::MAIN,
+		tasks: vec![task("erosion", 0)],
+		iteration_buffer: None,
+		seed: 0,
+	});
+	app.app().world_mut().send_event(StartComputeEvent {
+		sequence: picking,
+		tasks: vec![task("picking", 3)],
+		iteration_buffer: None,
+		seed: 0,
+	});
+	for _ in 0..MAX_FRAMES {
+		app.app().update();
+		if app.app().world().resource::<ComputeSequenceStates>().state(picking) == Some(&ComputeState::Finished) {
+			break;
+		}
+	}
+
+	// The picking sequence finishes on its own, while the main one carries on.
+	let states = app.app().world().resource::<ComputeSequenceStates>();
+	assert_eq!(states.state(picking), Some(&ComputeState::Finished));
+	assert!(matches!(states.state(ComputeSequenceId::MAIN), Some(ComputeState::Running { .. })));
+	assert!(matches!(app.app().world().resource::<ComputeState>(), ComputeState::Running { .. }));
+	assert_eq!(app.app().world().resource::<DoneSequences>().0, vec![(picking, Some("picking".to_owned()))]);
+
+	// Stopping it removes it alone.
+	app.app().world_mut().send_event(StopComputeEvent(picking));
+	app.app().update();
+	let states = app.app().world().resource::<ComputeSequenceStates>();
+	assert_eq!(states.sequences().collect::<Vec<_>>(), vec![ComputeSequenceId::MAIN]);
+	let iterations = states.progress(ComputeSequenceId::MAIN).unwrap().iterations;
+	for _ in 0..5 {
+		app.app().update();
+	}
+	let main = app.app().world().resource::<ComputeSequenceStates>().progress(ComputeSequenceId::MAIN).unwrap().clone();
+	assert!(main.iterations > iterations);
+	assert_eq!(*app.app().world().resource::<ComputeProgress>(), main);
+
+	// Both sequences incremented the same buffer.
+	app.app().world_mut().send_event(StopComputeEvent(ComputeSequenceId::MAIN));
+	app.app().update();
+	let data = app.read_buffer::<f32>(data);
+	assert!(data[0] >= 3.0 + main.iterations as f32);
+	assert!(data.iter().all(|&value| value == data[0]));
+}
+
+#[test]
+fn gates_steps_on_flags_set_in_the_main_world() {
+	let Some(mut app) = ComputeTestApp::new() else {
+		return;
+	};
+	let data = app.add_storage_init(vec![0.0f32; 16], Binding::SingleBound(0, 0));
+	let gated = |entry_point, condition| ComputeStep { run_if: Some(condition), ..run_shader(entry_point) };
+	// Several iterations run every frame, but a one-shot step still only runs once.
+	app.app().world_mut().send_event(StartComputeEvent {
+		sequence: ComputeSequenceId::MAIN,
+		tasks: vec![ComputeTask {
+			label: None,
+			iterations: None,
+			run_mode: TaskRunMode::Continuous,
+			budget: Some(FrameBudget { time: Duration::from_secs(1), estimate: Duration::from_millis(100) }),
+			submission: TaskSubmission::WithFrame,
+			start_after: None,
+			iteration_buffer: None,
+			steps: vec![
+				gated("increment", StepCondition::OneShotFlag("bump".to_owned())),
+				gated("decrement", StepCondition::ResourceFlag("drain".to_owned())),
+			],
+		}],
+		iteration_buffer: None,
+		seed: 0,
+	});
+	for _ in 0..MAX_FRAMES {
+		app.app().update();
+		if app.app().world().resource::<ComputeProgress>().iterations >= 2 {
+			break;
+		}
+	}
+	app.app().world_mut().resource_mut::<ComputeStepFlags>().set("bump", true);
+	for _ in 0..MAX_FRAMES {
+		app.app().update();
+		if !app.app().world().resource::<ComputeStepFlags>().get("bump") {
+			break;
+		}
+	}
+	assert!(!app.app().world().resource::<ComputeStepFlags>().get("bump"));
+	for _ in 0..5 {
+		app.app().update();
+	}
+	app.app().world_mut().send_event(StopComputeEvent(ComputeSequenceId::MAIN));
+	app.app().update();
+	app.assert_buffer_eq(data, &[1.0f32; 16]);
+}
+
+#[test]
+fn swaps_double_buffers_between_iterations() {
+	let Some(mut app) = ComputeTestApp::new() else {
+		return;
+	};
+	let data = app.add_storage_init(vec![0.0f32; 16], Binding::SingleBound(0, 0));
+	let double = app.add_storage_init(vec![0.0f32; 16], Binding::Double(0, (1, 2)));
+	app.run_until_complete(vec![
+		ComputeTask {
+			label: None,
+			iterations: NonZeroU32::new(4),
+			run_mode: TaskRunMode::Continuous,
+			budget: None,
+			submission: TaskSubmission::WithFrame,
+			start_after: None,
+			iteration_buffer: None,
+			steps: vec![
+				run_shader("advance"),
+				ComputeStep {
+					schedule: StepSchedule::EveryIteration,
+					run_if: None,
+					action: ComputeAction::SwapBuffers { buffer: double },
+				},
+			],
+		},
+		ComputeTask {
+			label: None,
+			iterations: NonZeroU32::new(1),
+			run_mode: TaskRunMode::Continuous,
+			budget: None,
+			submission: TaskSubmission::WithFrame,
+			start_after: None,
+			iteration_buffer: None,
+			steps: vec![run_shader("snapshot")],
+		},
+	]);
+	app.assert_buffer_eq(data, &[4.0f32; 16]);
+}
+
+#[test]
+fn mirrors_swaps_without_changing_the_buffer_set() {
+	#[derive(Resource, Default)]
+	struct ChangedFrames(u32);
+
+	let Some(mut app) = ComputeTestApp::new() else {
+		return;
+	};
+	let data = app.add_storage_init(vec![0.0f32; 16], Binding::SingleBound(0, 0));
+	let double = app.add_storage_init(vec![0.0f32; 16], Binding::Double(0, (1, 2)));
+	app.app().update();
+	app.app().init_resource::<ChangedFrames>().add_systems(
+		PostUpdate,
+		|buffers: Res<ShaderBufferSet>, mut changed: ResMut<ChangedFrames>, mut started: Local<bool>| {
+			// A new system sees every resource as changed the first time it runs.
+			if std::mem::replace(&mut *started, true) && buffers.is_changed() {
+				changed.0 += 1;
+			}
+		},
+	);
+	app.app().update();
+	app.run_until_complete(vec![
+		ComputeTask {
+			label: None,
+			iterations: NonZeroU32::new(3),
+			run_mode: TaskRunMode::Continuous,
+			budget: None,
+			submission: TaskSubmission::WithFrame,
+			start_after: None,
+			iteration_buffer: None,
+			steps: vec![
+				run_shader("advance"),
+				ComputeStep {
+					schedule: StepSchedule::EveryIteration,
+					run_if: None,
+					action: ComputeAction::SwapBuffers { buffer: double },
+				},
+			],
+		},
+		ComputeTask {
+			label: None,
+			iterations: NonZeroU32::new(1),
+			run_mode: TaskRunMode::Continuous,
+			budget: None,
+			submission: TaskSubmission::WithFrame,
+			start_after: None,
+			iteration_buffer: None,
+			steps: vec![run_shader("snapshot")],
+		},
+	]);
+	assert_eq!(app.app().world().resource::<ChangedFrames>().0, 0);
+	// The render world's copy was swapped along with the main world's, so the snapshot still reads the front buffer.
+	app.assert_buffer_eq(data, &[3.0f32; 16]);
+}
+
+#[test]
+fn makes_queued_writes_between_steps_once() {
+	#[derive(Resource, Default)]
+	struct Applied(Vec<StepWriteAppliedEvent>);
+
+	let Some(mut app) = ComputeTestApp::new() else {
+		return;
+	};
+	app.app().init_resource::<Applied>().add_systems(
+		Update,
+		|mut events: EventReader<StepWriteAppliedEvent>, mut applied: ResMut<Applied>| {
+			applied.0.extend(events.read().cloned())
+		},
+	);
+	let data = app.add_storage_init(vec![0.0f32; 16], Binding::SingleBound(0, 0));
+	let double = app.add_storage_init(vec![0.0f32; 16], Binding::Double(0, (1, 2)));
+	// Made between the advance and the swap of the first iteration, the write replaces what that advance wrote, and
+	// the three iterations after it count up from there. Made before the advance, or more than once, it wouldn't.
+	let id = app.buffer_set().queue_write_at(double, 0, 0, vec![100.0f32; 16]).unwrap();
+	app.run_until_complete(vec![
+		ComputeTask {
+			label: None,
+			iterations: NonZeroU32::new(4),
+			run_mode: TaskRunMode::Continuous,
+			budget: None,
+			submission: TaskSubmission::WithFrame,
+			start_after: None,
+			iteration_buffer: None,
+			steps: vec![
+				run_shader("advance"),
+				ComputeStep {
+					schedule: StepSchedule::EveryIteration,
+					run_if: None,
+					action: ComputeAction::SwapBuffers { buffer: double },
+				},
+			],
+		},
+		ComputeTask {
+			label: None,
+			iterations: NonZeroU32::new(1),
+			run_mode: TaskRunMode::Continuous,
+			budget: None,
+			submission: TaskSubmission::WithFrame,
+			start_after: None,
+			iteration_buffer: None,
+			steps: vec![run_shader("snapshot")],
+		},
+	]);
+	app.assert_buffer_eq(data, &[103.0f32; 16]);
+	assert_eq!(
+		app.app().world().resource::<Applied>().0,
+		[StepWriteAppliedEvent { id, buffer: double, task: 0, step: 0, iteration: 0 }]
+	);
+
+	assert!(matches!(
+		app.buffer_set().queue_write_at(double, 0, 0, vec![0.0f32; 17]),
+		Err(ShaderBufferError::TooLarge { size: 68, capacity: 64, .. })
+	));
+}
+
+#[test]
+fn runs_plugins_in_their_own_binding_namespaces() {
+	#[derive(Resource, Default)]
+	struct PluginTasks(Vec<(ShaderBufferHandle, Vec<ComputeTask>)>);
+
+	// Each plugin numbers its groups from zero, without knowing about the other.
+	struct CountingPlugin {
+		name: &'static str,
+		step: f32,
+	}
+
+	impl Plugin for CountingPlugin {
+		fn build(&self, app: &mut bevy::app::App) {
+			let render_device = app.world().resource::<RenderDevice>().clone();
+			let render_queue = app.world().resource::<RenderQueue>().clone();
+			let mut buffers = app.world_mut().resource_mut::<ShaderBufferSet>();
+			let namespace = buffers.add_namespace(self.name, 2);
+			let usage = BufferUsages::COPY_SRC;
+			let cells = namespace.binding(Binding::Double(0, (0, 1)));
+			let cells =
+				buffers.add_storage_init(&render_device, &render_queue, vec![0.0f32; 16], usage, cells, false).unwrap();
+			let rule = namespace.binding(Binding::SingleBound(1, 0));
+			buffers.add_storage_init(&render_device, &render_queue, vec![self.step; 16], usage, rule, false).unwrap();
+			let snapshot = namespace.binding(Binding::SingleBound(1, 1));
+			let snapshot =
+				buffers.add_storage_init(&render_device, &render_queue, vec![0.0f32; 16], usage, snapshot, false).unwrap();
+			let one_workgroup = DispatchSize::Workgroups { x: 1, y: 1, z: 1 };
+			let shader = "shaders/test_namespace.wgsl";
+			let tasks = vec![
+				ComputeTask::ping_pong(self.name, shader, "update", one_workgroup, &[cells], NonZeroU32::new(3))
+					.with_namespace(&namespace),
+				ComputeTask {
+					label: None,
+					iterations: NonZeroU32::new(1),
+					run_mode: TaskRunMode::Continuous,
+					budget: None,
+					submission: TaskSubmission::WithFrame,
+					start_after: None,
+					iteration_buffer: None,
+					steps: vec![ComputeStep {
+						schedule: StepSchedule::EveryIteration,
+						run_if: None,
+						action: ComputeAction::RunShader {
+							shader: shader.to_owned(),
+							entry_point: "take_snapshot".to_owned(),
+							shader_defs: Vec::new(),
+							overrides: Vec::new(),
+							dispatch: one_workgroup,
+							auto_swap: Vec::new(),
+							skip_if_empty: None,
+							buffer_orientation: Vec::new(),
+							push_constants: None,
+							dynamic_offsets: Vec::new(),
+						},
+					}],
+				}
+				.with_namespace(&namespace),
+			];
+			app.world_mut().resource_mut::<PluginTasks>().0.push((snapshot, tasks));
+		}
+	}
+
+	let Some(mut app) = ComputeTestApp::new() else {
+		return;
+	};
+	app.app().init_resource::<PluginTasks>();
+	CountingPlugin { name: "ones", step: 1.0 }.build(app.app());
+	CountingPlugin { name: "tens", step: 10.0 }.build(app.app());
+	let ones = app.buffer_set().add_namespace("ones", 2);
+	assert_eq!((ones.group(0), ones.group(1)), (0, 1));
+	let tens = app.buffer_set().add_namespace("tens", 2);
+	assert_eq!((tens.group(0), tens.group(1)), (2, 3));
+	let result = catch_unwind(AssertUnwindSafe(|| {
+		app.buffer_set().add_namespace("ones", 1);
+	}));
+	assert!(result.is_err(), "Adding a namespace again with a different number of groups should panic");
+
+	let plugins = std::mem::take(&mut app.app().world_mut().resource_mut::<PluginTasks>().0);
+	app.run_until_complete(plugins.iter().flat_map(|(_, tasks)| tasks.clone()).collect());
+	app.assert_buffer_eq(plugins[0].0, &[3.0f32; 16]);
+	app.assert_buffer_eq(plugins[1].0, &[30.0f32; 16]);
+}
+
+#[test]
+fn reads_back_buffers_with_every_polling_mode() {
+	for polling in [PollingMode::Background { interval: Duration::from_millis(1) }, PollingMode::Manual] {
+		let Some(mut app) = ComputeTestApp::with_polling(polling) else {
+			return;
+		};
+		if polling == PollingMode::Manual {
+			app.app().sub_app_mut(RenderApp).add_systems(Render, |device: Res<RenderDevice>| {
+				device.poll(Maintain::Poll);
+			});
+		}
+		let data = app.add_storage_init(vec![0.0f32; 16], Binding::SingleBound(0, 0));
+		app.run_until_complete(vec![ComputeTask {
+			label: None,
+			iterations: NonZeroU32::new(3),
+			run_mode: TaskRunMode::Continuous,
+			budget: None,
+			submission: TaskSubmission::WithFrame,
+			start_after: None,
+			iteration_buffer: None,
+			steps: vec![run_shader("increment")],
+		}]);
+		// Readbacks don't wait for the GPU with these modes, so the task waits for them instead.
+		app.assert_buffer_eq(data, &[3.0f32; 16]);
+	}
+}
+
+#[test]
+fn runs_as_many_iterations_a_frame_as_fit_the_budget() {
+	let Some(mut app) = ComputeTestApp::new() else {
+		return;
+	};
+	app.app().init_resource::<DoneIterations>().add_systems(
+		Update,
+		|mut events: EventReader<ComputeTaskDoneEvent>, mut done: ResMut<DoneIterations>| {
+			done.0.extend(events.read().map(|event| event.iterations))
+		},
+	);
+	let data = app.add_storage_init(vec![0.0f32; 16], Binding::SingleBound(0, 0));
+	let double = app.add_storage_init(vec![0.0f32; 16], Binding::Double(0, (1, 2)));
+	// Every iteration reads what the one before it wrote, so the double buffer has to be swapped
+	// between the iterations run in the same frame for the count to come out right.
+	app.app().world_mut().send_event(StartComputeEvent {
+		sequence: ComputeSequenceId::MAIN,
+		tasks: vec![
+			ComputeTask {
+				label: None,
+				iterations: NonZeroU32::new(40),
+				run_mode: TaskRunMode::Continuous,
+				budget: Some(FrameBudget { time: Duration::from_secs(1), estimate: Duration::from_millis(1) }),
+				submission: TaskSubmission::WithFrame,
+				start_after: None,
+				iteration_buffer: None,
+				steps: vec![
+					run_shader("advance"),
+					ComputeStep {
+						schedule: StepSchedule::EveryIteration,
+						run_if: None,
+						action: ComputeAction::SwapBuffers { buffer: double },
+					},
+				],
+			},
+			ComputeTask {
+				label: None,
+				iterations: NonZeroU32::new(1),
+				run_mode: TaskRunMode::Continuous,
+				budget: None,
+				submission: TaskSubmission::WithFrame,
+				start_after: None,
+				iteration_buffer: None,
+				steps: vec![run_shader("snapshot")],
+			},
+		],
+		iteration_buffer: None,
+		seed: 0,
+	});
+	let mut frames = 0;
+	while *app.app().world().resource::<ComputeState>() != ComputeState::Finished {
+		assert!(frames < 200, "The compute sequence didn't finish within 200 frames");
+		app.app().update();
+		frames += 1;
+	}
+	assert!(frames < 20, "Running 40 iterations took {} frames", frames);
+	assert_eq!(app.app().world().resource::<DoneIterations>().0, vec![40, 1]);
+	assert_buffer_approx_eq(&app.read_buffer::<f32>(data), &[40.0; 16], 0.0);
+}
+
+#[test]
+fn reads_back_a_buffer_every_iteration_in_order() {
+	let Some(mut app) = ComputeTestApp::new() else {
+		return;
+	};
+	#[derive(Resource, Default)]
+	struct Reads(Vec<(Option<String>, u32, Vec<f32>)>);
+	app.app().init_resource::<Reads>().add_systems(
+		Update,
+		|mut events: EventReader<BufferReadbackEvent>, mut reads: ResMut<Reads>| {
+			for event in events.read() {
+				reads.0.push((event.group.clone(), event.iteration, event.as_slice_of::<f32>().unwrap().to_vec()));
+			}
+		},
+	);
+	let data = app.add_storage_init(vec![0.0f32; 16], Binding::SingleBound(0, 0));
+	app.app().world_mut().send_event(StartComputeEvent {
+		sequence: ComputeSequenceId::MAIN,
+		tasks: vec![ComputeTask {
+			label: Some("watch".to_string()),
+			iterations: NonZeroU32::new(40),
+			run_mode: TaskRunMode::Continuous,
+			budget: Some(FrameBudget { time: Duration::from_secs(1), estimate: Duration::from_millis(1) }),
+			submission: TaskSubmission::WithFrame,
+			start_after: None,
+			iteration_buffer: None,
+			steps: vec![
+				run_shader("increment"),
+				ComputeStep {
+					schedule: StepSchedule::EveryIteration,
+					run_if: None,
+					action: ComputeAction::ReadBuffer { buffer: data },
+				},
+			],
+		}],
+		iteration_buffer: None,
+		seed: 0,
+	});
+	let mut frames = 0;
+	while *app.app().world().resource::<ComputeState>() != ComputeState::Finished {
+		assert!(frames < 200, "The compute sequence didn't finish within 200 frames");
+		app.app().update();
+		frames += 1;
+	}
+	assert!(frames < 20, "Running 40 iterations took {} frames", frames);
+	// Every iteration is read back, even though several run in a frame, and they all arrive before the task is done.
+	let reads = &app.app().world().resource::<Reads>().0;
+	let expected =
+		(1..=40).map(|iteration| (Some("watch".to_string()), iteration, vec![iteration as f32; 16])).collect::<Vec<_>>();
+	assert_eq!(reads, &expected);
+}
+
+#[test]
+fn reads_back_the_same_buffer_from_two_sequences() {
+	let Some(mut app) = ComputeTestApp::new() else {
+		return;
+	};
+	#[derive(Resource, Default)]
+	struct Reads(Vec<(ComputeSequenceId, Vec<f32>)>);
+	app.app().init_resource::<Reads>().add_systems(
+		Update,
+		|mut events: EventReader<BufferReadbackEvent>, mut reads: ResMut<Reads>| {
+			for event in events.read() {
+				reads.0.push((event.sequence, event.as_slice_of::<f32>().unwrap().to_vec()));
+			}
+		},
+	);
+	let data = app.add_storage_init(vec![3.0f32; 4], Binding::SingleBound(0, 0));
+	let copy = |iterations| ComputeTask {
+		label: None,
+		iterations: NonZeroU32::new(iterations),
+		run_mode: TaskRunMode::Continuous,
+		budget: None,
+		submission: TaskSubmission::WithFrame,
+		start_after: None,
+		iteration_buffer: None,
+		steps: vec![ComputeStep {
+			schedule: StepSchedule::EveryIteration,
+			run_if: None,
+			action: ComputeAction::CopyBuffer { src: data, offset: 0, size: None },
+		}],
+	};
+	// The main sequence finishes first, and drops its copy buffer while the other is still reading through its own.
+	for (sequence, iterations) in [(ComputeSequenceId::MAIN, 4), (ComputeSequenceId(1), 12)] {
+		app.app().world_mut().send_event(StartComputeEvent {
+			sequence,
+			tasks: vec![copy(iterations)],
+			iteration_buffer: None,
+			seed: 0,
+		});
+	}
+	let finished = |app: &mut ComputeTestApp, sequence| {
+		app.app().world().resource::<ComputeSequenceStates>().state(sequence) == Some(&ComputeState::Finished)
+	};
+	for _ in 0..MAX_FRAMES {
+		app.app().update();
+		if finished(&mut app, ComputeSequenceId::MAIN) && finished(&mut app, ComputeSequenceId(1)) {
+			break;
+		}
+	}
+	assert!(finished(&mut app, ComputeSequenceId::MAIN) && finished(&mut app, ComputeSequenceId(1)));
+
+	let reads = &app.app().world().resource::<Reads>().0;
+	for sequence in [ComputeSequenceId::MAIN, ComputeSequenceId(1)] {
+		assert!(reads.iter().any(|(read, _)| *read == sequence), "Nothing was read back by sequence {}", sequence.0);
+	}
+	assert!(reads.iter().all(|(_, data)| data == &[3.0; 4]), "{:?}", reads);
+}
+
+#[test]
+fn finishes_separately_submitted_tasks_once_the_gpu_has() {
+	let Some(mut app) = ComputeTestApp::new() else {
+		return;
+	};
+	app.app().init_resource::<DoneIterations>().add_systems(
+		Update,
+		|mut events: EventReader<ComputeTaskDoneEvent>, mut done: ResMut<DoneIterations>| {
+			done.0.extend(events.read().map(|event| event.iterations))
+		},
+	);
+	let data = app.add_storage_init(vec![0.0f32; 16], Binding::SingleBound(0, 0));
+	app.run_until_complete(vec![
+		ComputeTask {
+			label: Some("Bake".to_owned()),
+			iterations: NonZeroU32::new(30),
+			run_mode: TaskRunMode::Continuous,
+			budget: Some(FrameBudget { time: Duration::from_secs(1), estimate: Duration::from_millis(1) }),
+			submission: TaskSubmission::Separate,
+			start_after: None,
+			iteration_buffer: None,
+			steps: vec![run_shader("increment"), run_shader("increment")],
+		},
+		ComputeTask {
+			label: None,
+			iterations: NonZeroU32::new(1),
+			run_mode: TaskRunMode::Continuous,
+			budget: None,
+			submission: TaskSubmission::WithFrame,
+			start_after: None,
+			iteration_buffer: None,
+			steps: vec![run_shader("increment")],
+		},
+	]);
+	assert_eq!(app.app().world().resource::<DoneIterations>().0, vec![30, 1]);
+	app.assert_buffer_eq(data, &[61.0f32; 16]);
+}
+
+#[derive(Resource, Default)]
+struct PendingFrames(u32);
+
+#[test]
+fn waits_for_the_start_delay_before_running_a_task() {
+	let Some(mut app) = ComputeTestApp::new() else {
+		return;
+	};
+	app.app().init_resource::<DoneIterations>().init_resource::<PendingFrames>().add_systems(
+		Update,
+		|mut events: EventReader<ComputeTaskDoneEvent>,
+		 mut done: ResMut<DoneIterations>,
+		 progress: Res<ComputeProgress>,
+		 mut pending: ResMut<PendingFrames>| {
+			done.0.extend(events.read().map(|event| event.iterations));
+			pending.0 += progress.pending as u32;
+		},
+	);
+	let data = app.add_storage_init(vec![0.0f32; 16], Binding::SingleBound(0, 0));
+	app.run_until_complete(vec![ComputeTask {
+		label: None,
+		iterations: NonZeroU32::new(3),
+		run_mode: TaskRunMode::Continuous,
+		budget: None,
+		submission: TaskSubmission::WithFrame,
+		start_after: Some(StartDelay::Frames(20)),
+		iteration_buffer: None,
+		steps: vec![run_shader("increment")],
+	}]);
+	// The progress lags the render world by a frame or two, so it shows the task as pending for about as long.
+	let pending = app.app().world().resource::<PendingFrames>().0;
+	assert!((15..=20).contains(&pending), "The task was shown as pending for {} frames", pending);
+	assert_eq!(app.app().world().resource::<DoneIterations>().0, vec![3]);
+	app.assert_buffer_eq(data, &[3.0f32; 16]);
+}
+
+#[derive(Resource, Default)]
+struct SeenStatuses(Vec<ComputeTaskStatus>);
+
+#[test]
+fn describes_the_running_sequence_in_the_main_world() {
+	let Some(mut app) = ComputeTestApp::new() else {
+		return;
+	};
+	// In PostUpdate, so it sees the info after it's been updated for the frame.
+	app.app().init_resource::<SeenStatuses>().add_systems(
+		PostUpdate,
+		|info: Option<Res<ComputeSequenceInfo>>, mut seen: ResMut<SeenStatuses>| {
+			if let Some(status) = info.and_then(|info| info.tasks.get(1).map(|task| task.status)) {
+				if seen.0.last() != Some(&status) {
+					seen.0.push(status);
+				}
+			}
+		},
+	);
+	let data = app.add_storage_init(vec![0.0f32; 16], Binding::SingleBound(0, 0));
+	app.run_until_complete(vec![
+		ComputeTask {
+			label: Some("first".to_string()),
+			iterations: NonZeroU32::new(5),
+			run_mode: TaskRunMode::Continuous,
+			budget: None,
+			submission: TaskSubmission::WithFrame,
+			start_after: None,
+			iteration_buffer: None,
+			steps: vec![run_shader("increment")],
+		},
+		ComputeTask {
+			label: Some("second".to_string()),
+			iterations: NonZeroU32::new(5),
+			run_mode: TaskRunMode::Continuous,
+			budget: None,
+			submission: TaskSubmission::WithFrame,
+			start_after: Some(StartDelay::Frames(30)),
+			iteration_buffer: None,
+			steps: vec![run_shader("increment")],
+		},
+	]);
+	let info = app.app().world().resource::<ComputeSequenceInfo>();
+	let labels: Vec<_> = info.tasks.iter().map(|task| task.task.label.as_deref()).collect();
+	assert_eq!(labels, vec![Some("first"), Some("second")]);
+	for task in &info.tasks {
+		assert_eq!(task.status, ComputeTaskStatus::Done);
+		assert_eq!(task.iterations, 5);
+		assert_eq!(task.total_iterations, Some(5));
+	}
+	let seen = &app.app().world().resource::<SeenStatuses>().0;
+	assert_eq!(seen.first(), Some(&ComputeTaskStatus::Queued));
+	assert!(seen.contains(&ComputeTaskStatus::Delayed), "The second task went through {:?}", seen);
+	assert_eq!(seen.last(), Some(&ComputeTaskStatus::Done));
+	app.assert_buffer_eq(data, &[10.0f32; 16]);
+}
+
+#[derive(Resource, Default)]
+struct ProgressSamples(Vec<ComputeProgress>);
+
+#[test]
+fn estimates_the_time_left_once_enough_iterations_are_timed() {
+	let Some(mut app) = ComputeTestApp::new() else {
+		return;
+	};
+	app.app().init_resource::<ProgressSamples>().add_systems(
+		PostUpdate,
+		|progress: Res<ComputeProgress>, mut samples: ResMut<ProgressSamples>| {
+			if progress.current_task == 0 && progress.iterations > 0 {
+				samples.0.push(progress.clone());
+			}
+		},
+	);
+	app.add_storage_init(vec![0.0f32; 16], Binding::SingleBound(0, 0));
+	app.run_until_complete(vec![
+		ComputeTask {
+			label: None,
+			iterations: NonZeroU32::new(30),
+			run_mode: TaskRunMode::Continuous,
+			budget: None,
+			submission: TaskSubmission::WithFrame,
+			start_after: None,
+			iteration_buffer: None,
+			steps: vec![run_shader("increment")],
+		},
+		ComputeTask {
+			label: None,
+			iterations: NonZeroU32::new(20),
+			run_mode: TaskRunMode::Continuous,
+			budget: None,
+			submission: TaskSubmission::WithFrame,
+			start_after: None,
+			iteration_buffer: None,
+			steps: vec![run_shader("increment")],
+		},
+	]);
+	let samples = &app.app().world().resource::<ProgressSamples>().0;
+	assert!(samples[0].task_eta.is_none() && samples[0].sequence_eta.is_none());
+	let estimated: Vec<_> = samples.iter().filter(|progress| progress.time_per_iteration.is_some()).collect();
+	assert!(!estimated.is_empty(), "No estimate was made in {} frames", samples.len());
+	for progress in estimated {
+		let remaining = progress.remaining_iterations().unwrap();
+		assert_eq!(remaining, 30 - progress.iterations);
+		let time = progress.time_per_iteration.unwrap();
+		assert_eq!(progress.task_eta, Some(time * remaining));
+		assert_eq!(progress.sequence_eta, Some(time * (remaining + 20)));
+	}
+}
+
+#[test]
+fn ends_the_shared_compute_pass_around_builtin_steps() {
+	let Some(mut app) = ComputeTestApp::new() else {
+		return;
+	};
+	let data = app.add_storage_init(vec![0.0f32; 16], Binding::SingleBound(0, 0));
+	// The shader steps share a compute pass across steps and iterations, which has to be ended for the finite
+	// check, as it records passes of its own, and started again after it.
+	app.run_until_complete(vec![ComputeTask {
+		label: None,
+		iterations: NonZeroU32::new(20),
+		run_mode: TaskRunMode::Continuous,
+		budget: Some(FrameBudget { time: Duration::from_secs(1), estimate: Duration::from_millis(1) }),
+		submission: TaskSubmission::WithFrame,
+		start_after: None,
+		iteration_buffer: None,
+		steps: vec![
+			run_shader("increment"),
+			run_shader("increment"),
+			ComputeStep {
+				schedule: StepSchedule::EveryIteration,
+				run_if: None,
+				action: ComputeAction::CheckFinite { buffer: data, element: FiniteCheckType::Buffer },
+			},
+			run_shader("increment"),
+		],
+	}]);
+	app.assert_buffer_eq(data, &[60.0f32; 16]);
+}
+
+#[test]
+fn runs_several_entry_points_of_one_shader_in_order() {
+	let Some(mut app) = ComputeTestApp::new() else {
+		return;
+	};
+	let data = app.add_storage_init(vec![0.0f32; 16], Binding::SingleBound(0, 0));
+	let render_device = app.app().world().resource::<RenderDevice>().clone();
+	let render_queue = app.app().world().resource::<RenderQueue>().clone();
+	app
+		.buffer_set()
+		.add_storage_init(
+			&render_device,
+			&render_queue,
+			vec![5.0f32; 16],
+			BufferUsages::empty(),
+			Binding::SingleBound(0, 1),
+			true,
+		)
+		.unwrap();
+	// If the snapshot ran last, or the increments were dropped, the data would end up as the input.
+	let passes = vec![
+		("increment".to_owned(), DispatchSize::Exact { x: 16, y: 1, z: 1 }),
+		("snapshot".to_owned(), DispatchSize::Workgroups { x: 1, y: 1, z: 1 }),
+		("increment".to_owned(), DispatchSize::Exact { x: 16, y: 1, z: 1 }),
+	];
+	app.run_until_complete(vec![ComputeTask {
+		label: None,
+		iterations: NonZeroU32::new(2),
+		run_mode: TaskRunMode::Continuous,
+		budget: None,
+		submission: TaskSubmission::WithFrame,
+		start_after: None,
+		iteration_buffer: None,
+		steps: vec![
+			ComputeStep {
+				schedule: StepSchedule::EveryIteration,
+				run_if: None,
+				action: ComputeAction::RunShaderMulti { shader: SHADER_ASSET_PATH.to_owned(), shader_defs: Vec::new(), passes },
+			},
+			run_shader("increment"),
+		],
+	}]);
+	app.assert_buffer_eq(data, &[7.0f32; 16]);
+}
+
+#[test]
+fn fills_the_halves_of_a_double_buffered_texture_differently() {
+	let Some(mut app) = ComputeTestApp::new() else {
+		return;
+	};
+	let data = app.add_storage_init(vec![0.0f32; 1], Binding::SingleBound(0, 0));
+	let world = app.app().world_mut();
+	let texture = world.resource_scope(|world, mut images: Mut<Assets<Image>>| {
+		world
+			.resource_mut::<ShaderBufferSet>()
+			.add_texture_fill_double(
+				&mut images,
+				1,
+				1,
+				TextureFormat::R32Float,
+				&1.0f32.to_ne_bytes(),
+				&2.0f32.to_ne_bytes(),
+				Binding::Double(0, (1, 2)),
+			)
+			.unwrap()
+	});
+	let first = app.buffer_set().image_at_binding(texture, 1).unwrap();
+	let second = app.buffer_set().image_at_binding(texture, 2).unwrap();
+	assert_ne!(first, second);
+	assert_eq!(app.buffer_set().image_at_binding(texture, 0), None);
+	let read_front = ComputeStep {
+		schedule: StepSchedule::EveryIteration,
+		run_if: None,
+		action: ComputeAction::RunShader {
+			shader: "shaders/test_double_texture.wgsl".to_owned(),
+			entry_point: "read_front".to_owned(),
+			shader_defs: Vec::new(),
+			overrides: Vec::new(),
+			dispatch: ONE_WORKGROUP,
+			auto_swap: Vec::new(),
+			skip_if_empty: None,
+			buffer_orientation: Vec::new(),
+			push_constants: None,
+			dynamic_offsets: Vec::new(),
+		},
+	};
+	let task = |steps| ComputeTask {
+		label: None,
+		iterations: NonZeroU32::new(1),
+		run_mode: TaskRunMode::Continuous,
+		budget: None,
+		submission: TaskSubmission::WithFrame,
+		start_after: None,
+		iteration_buffer: None,
+		steps,
+	};
+	app.run_until_complete(vec![task(vec![read_front.clone()])]);
+	app.assert_buffer_eq(data, &[1.0f32]);
+
+	// Once swapped, the second fill is the one read from the front binding.
+	app.run_until_complete(vec![
+		task(vec![ComputeStep {
+			schedule: StepSchedule::EveryIteration,
+			run_if: None,
+			action: ComputeAction::SwapBuffers { buffer: texture },
+		}]),
+		task(vec![read_front]),
+	]);
+	app.assert_buffer_eq(data, &[2.0f32]);
+	assert_eq!(app.buffer_set().image_at_binding(texture, 1), Some(second));
+	assert_eq!(app.buffer_set().image_at_binding(texture, 2), Some(first));
+}
+
+#[test]
+fn reads_back_the_front_half_of_a_double_buffered_texture() {
+	let Some(mut app) = ComputeTestApp::new() else {
+		return;
+	};
+	// Rows of 3 pixels are 12 bytes, far short of the 256 their copies are padded to.
+	let world = app.app().world_mut();
+	let texture = world.resource_scope(|world, mut images: Mut<Assets<Image>>| {
+		world
+			.resource_mut::<ShaderBufferSet>()
+			.add_texture_fill_double(
+				&mut images,
+				3,
+				2,
+				TextureFormat::R32Float,
+				&1.0f32.to_ne_bytes(),
+				&2.0f32.to_ne_bytes(),
+				Binding::Double(0, (1, 2)),
+			)
+			.unwrap()
+	});
+	app.assert_buffer_eq(texture, &[1.0f32; 6]);
+	app.run_until_complete(vec![ComputeTask {
+		label: None,
+		iterations: NonZeroU32::new(1),
+		run_mode: TaskRunMode::Continuous,
+		budget: None,
+		submission: TaskSubmission::WithFrame,
+		start_after: None,
+		iteration_buffer: None,
+		steps: vec![ComputeStep {
+			schedule: StepSchedule::EveryIteration,
+			run_if: None,
+			action: ComputeAction::SwapBuffers { buffer: texture },
+		}],
+	}]);
+	app.assert_buffer_eq(texture, &[2.0f32; 6]);
+}
+
+#[test]
+fn waits_for_every_texture_to_be_filled_before_dispatching() {
+	let Some(mut app) = ComputeTestApp::new() else {
+		return;
+	};
+	// Only one asset is uploaded per frame from here on, so the halves of the double buffer reach the GPU in
+	// different frames. Bevy's own meshes need to be uploaded first, as some of its render systems expect them.
+	app.app().update();
+	app.app().insert_resource(RenderAssetBytesPerFrame::new(1));
+	let data = app.add_storage_init(vec![0.0f32; 1], Binding::SingleBound(0, 0));
+	let world = app.app().world_mut();
+	let texture = world.resource_scope(|world, mut images: Mut<Assets<Image>>| {
+		world
+			.resource_mut::<ShaderBufferSet>()
+			.add_texture_fill_double(
+				&mut images,
+				64,
+				64,
+				TextureFormat::R32Float,
+				&1.0f32.to_ne_bytes(),
+				&2.0f32.to_ne_bytes(),
+				Binding::Double(0, (1, 2)),
+			)
+			.unwrap()
+	});
+	let read_front = ComputeStep {
+		schedule: StepSchedule::EveryIteration,
+		run_if: None,
+		action: ComputeAction::RunShader {
+			shader: "shaders/test_double_texture.wgsl".to_owned(),
+			entry_point: "read_front".to_owned(),
+			shader_defs: Vec::new(),
+			overrides: Vec::new(),
+			dispatch: ONE_WORKGROUP,
+			auto_swap: Vec::new(),
+			skip_if_empty: None,
+			buffer_orientation: Vec::new(),
+			push_constants: None,
+			dynamic_offsets: Vec::new(),
+		},
+	};
+	let task = |steps| ComputeTask {
+		label: None,
+		iterations: NonZeroU32::new(1),
+		run_mode: TaskRunMode::Continuous,
+		budget: None,
+		submission: TaskSubmission::WithFrame,
+		start_after: None,
+		iteration_buffer: None,
+		steps,
+	};
+	app.run_until_complete(vec![task(vec![read_front.clone()])]);
+	app.assert_buffer_eq(data, &[1.0f32]);
+	app.assert_buffer_all(texture, |value: f32| value == 1.0);
+
+	app.run_until_complete(vec![
+		task(vec![ComputeStep {
+			schedule: StepSchedule::EveryIteration,
+			run_if: None,
+			action: ComputeAction::SwapBuffers { buffer: texture },
+		}]),
+		task(vec![read_front]),
+	]);
+	app.assert_buffer_eq(data, &[2.0f32]);
+	app.assert_buffer_all(texture, |value: f32| value == 2.0);
+}
+
+#[test]
+fn binds_a_double_buffer_the_other_way_round_for_one_step() {
+	let Some(mut app) = ComputeTestApp::new() else {
+		return;
+	};
+	let data = app.add_storage_init(vec![0.0f32; 16], Binding::SingleBound(0, 0));
+	let double = app.add_storage_init(vec![1.0f32; 16], Binding::Double(0, (1, 2)));
+	let mut advance = run_shader("advance");
+	if let ComputeAction::RunShader { auto_swap, .. } = &mut advance.action {
+		auto_swap.push(double);
+	}
+	// Reversed, the snapshot reads what the advance just wrote to the back buffer, before it's swapped.
+	let mut snapshot = run_shader("snapshot");
+	if let ComputeAction::RunShader { buffer_orientation, .. } = &mut snapshot.action {
+		buffer_orientation.push((double, BufferOrientation::Reversed));
+	}
+	app.run_until_complete(vec![ComputeTask {
+		label: None,
+		iterations: NonZeroU32::new(2),
+		run_mode: TaskRunMode::Continuous,
+		budget: None,
+		submission: TaskSubmission::WithFrame,
+		start_after: None,
+		iteration_buffer: None,
+		steps: vec![advance, snapshot],
+	}]);
+	// The orientation only applies to the snapshot, so the second advance still reads the first one's output.
+	app.assert_buffer_eq(data, &[3.0f32; 16]);
+}
+
+#[test]
+#[should_panic(expected = "isn't a double buffer")]
+fn panics_when_a_step_reverses_a_buffer_that_isnt_double() {
+	let Some(mut app) = ComputeTestApp::new() else {
+		panic!("isn't a double buffer, but there's no GPU to check it on");
+	};
+	let data = app.add_storage_init(vec![0.0f32; 16], Binding::SingleBound(0, 0));
+	let mut increment = run_shader("increment");
+	if let ComputeAction::RunShader { buffer_orientation, .. } = &mut increment.action {
+		buffer_orientation.push((data, BufferOrientation::Reversed));
+	}
+	app.run_until_complete(vec![ComputeTask {
+		label: None,
+		iterations: NonZeroU32::new(1),
+		run_mode: TaskRunMode::Continuous,
+		budget: None,
+		submission: TaskSubmission::WithFrame,
+		start_after: None,
+		iteration_buffer: None,
+		steps: vec![increment],
+	}]);
+}
+
+#[test]
+#[should_panic(expected = "can't be chunked")]
+fn panics_when_a_pass_of_a_multi_entry_point_step_is_chunked() {
+	let Some(mut app) = ComputeTestApp::new() else {
+		panic!("can't be chunked, but there's no GPU to check it on");
+	};
+	app.add_storage_init(vec![0.0f32; 16], Binding::SingleBound(0, 0));
+	let chunked = DispatchSize::Chunked { total: (1, 1, 1), max_per_dispatch: (1, 1, 1), chunks_per_iteration: None };
+	app.run_until_complete(vec![ComputeTask {
+		label: None,
+		iterations: NonZeroU32::new(1),
+		run_mode: TaskRunMode::Continuous,
+		budget: None,
+		submission: TaskSubmission::WithFrame,
+		start_after: None,
+		iteration_buffer: None,
+		steps: vec![ComputeStep {
+			schedule: StepSchedule::EveryIteration,
+			run_if: None,
+			action: ComputeAction::RunShaderMulti {
+				shader: SHADER_ASSET_PATH.to_owned(),
+				shader_defs: Vec::new(),
+				passes: vec![("increment".to_owned(), chunked)],
+			},
+		}],
+	}]);
+}
+
+#[test]
+fn splits_chunked_dispatches_with_smaller_chunks_at_the_edges() {
+	let dispatch: DispatchSize =
+		DispatchSize::Chunked { total: (5, 3, 1), max_per_dispatch: (2, 2, 1), chunks_per_iteration: None };
+	assert_eq!(
+		dispatch.chunks(UVec3::new(2, 2, 1)),
+		vec![
+			(UVec3::new(0, 0, 0), UVec3::new(2, 2, 1)),
+			(UVec3::new(4, 0, 0), UVec3::new(2, 2, 1)),
+			(UVec3::new(8, 0, 0), UVec3::new(1, 2, 1)),
+			(UVec3::new(0, 4, 0), UVec3::new(2, 1, 1)),
+			(UVec3::new(4, 4, 0), UVec3::new(2, 1, 1)),
+			(UVec3::new(8, 4, 0), UVec3::new(1, 1, 1)),
+		]
+	);
+	assert_eq!(dispatch.workgroup_count(UVec3::new(2, 2, 1)), UVec3::new(5, 3, 1));
+}
+
+#[test]
+fn covers_the_whole_grid_once_with_chunked_dispatches() {
+	let Some(mut app) = ComputeTestApp::new() else {
+		return;
+	};
+	let data = app.add_storage_init(vec![0.0f32; 60], Binding::SingleBound(0, 0));
+	let render_device = app.app().world().resource::<RenderDevice>().clone();
+	let render_queue = app.app().world().resource::<RenderQueue>().clone();
+	app.buffer_set().add_chunk_uniform(&render_device, &render_queue, Binding::SingleBound(0, 1)).unwrap();
+	let cover = |chunks_per_iteration| ComputeStep {
+		schedule: StepSchedule::EveryIteration,
+		run_if: None,
+		action: ComputeAction::RunShader {
+			shader: "shaders/test_chunks.wgsl".to_owned(),
+			entry_point: "cover".to_owned(),
+			shader_defs: Vec::new(),
+			overrides: Vec::new(),
+			// The 10x6 grid is 5x3 workgroups of 2x2, which don't divide evenly into chunks of 2x2 workgroups.
+			dispatch: DispatchSize::Chunked { total: (5, 3, 1), max_per_dispatch: (2, 2, 1), chunks_per_iteration },
+			auto_swap: Vec::new(),
+			skip_if_empty: None,
+			buffer_orientation: Vec::new(),
+			push_constants: None,
+			dynamic_offsets: Vec::new(),
+		},
+	};
+	app.run_until_complete(vec![ComputeTask {
+		label: None,
+		iterations: NonZeroU32::new(2),
+		run_mode: TaskRunMode::Continuous,
+		budget: None,
+		submission: TaskSubmission::WithFrame,
+		start_after: None,
+		iteration_buffer: None,
+		steps: vec![cover(None)],
+	}]);
+	assert_buffer_approx_eq(&app.read_buffer::<f32>(data), &[2.0; 60], 0.0);
+
+	// Four of the six chunks an iteration covers the grid twice more in three iterations.
+	app.run_until_complete(vec![ComputeTask {
+		label: None,
+		iterations: NonZeroU32::new(3),
+		run_mode: TaskRunMode::Continuous,
+		budget: None,
+		submission: TaskSubmission::WithFrame,
+		start_after: None,
+		iteration_buffer: None,
+		steps: vec![cover(NonZeroU32::new(4))],
+	}]);
+	assert_buffer_approx_eq(&app.read_buffer::<f32>(data), &[4.0; 60], 0.0);
+}
+
+#[test]
+fn runs_ping_pong_tasks() {
+	let Some(mut app) = ComputeTestApp::new() else {
+		return;
+	};
+	let data = app.add_storage_init(vec![0.0f32; 16], Binding::SingleBound(0, 0));
+	let double = app.add_storage_init(vec![0.0f32; 16], Binding::Double(0, (1, 2)));
+	let one_workgroup = DispatchSize::Workgroups { x: 1, y: 1, z: 1 };
+	let advance =
+		ComputeTask::ping_pong("advance", SHADER_ASSET_PATH, "advance", one_workgroup, &[double], NonZeroU32::new(5))
+			.with_schedule(StepSchedule::MaxFrequencyHz(1000.0));
+	assert_eq!(advance.label.as_deref(), Some("advance"));
+	assert!(matches!(
+		advance.steps.as_slice(),
+		[ComputeStep { schedule: StepSchedule::MaxFrequencyHz(_), run_if: None, action: ComputeAction::RunShader { auto_swap, .. } }] if *auto_swap == [double]
+	));
+	app.run_until_complete(vec![
+		advance.with_schedule(StepSchedule::EveryIteration),
+		ComputeTask {
+			label: None,
+			iterations: NonZeroU32::new(1),
+			run_mode: TaskRunMode::Continuous,
+			budget: None,
+			submission: TaskSubmission::WithFrame,
+			start_after: None,
+			iteration_buffer: None,
+			steps: vec![run_shader("snapshot")],
+		},
+	]);
+	app.assert_buffer_eq(data, &[5.0f32; 16]);
+}
+
+#[test]
+fn runs_steps_on_their_schedules() {
+	assert_eq!(StepSchedule::from(NonZeroU32::new(30)), StepSchedule::MaxFrequencyHz(30.0));
+	assert_eq!(StepSchedule::from(None), StepSchedule::EveryIteration);
+	let Some(mut app) = ComputeTestApp::new() else {
+		return;
+	};
+	app.app().init_resource::<ValidationReports>().add_systems(
+		Update,
+		|mut events: EventReader<ComputeValidationReport>, mut reports: ResMut<ValidationReports>| {
+			reports.0.extend(events.read().cloned())
+		},
+	);
+	let data = app.add_storage_init(vec![0.0f32; 16], Binding::SingleBound(0, 0));
+	let task = |schedule| {
+		ComputeTask {
+			label: None,
+			iterations: NonZeroU32::new(7),
+			run_mode: TaskRunMode::Continuous,
+			budget: None,
+			submission: TaskSubmission::WithFrame,
+			start_after: None,
+			iteration_buffer: None,
+			steps: vec![run_shader("increment")],
+		}
+		.with_schedule(schedule)
+	};
+	app.run_until_complete(vec![task(StepSchedule::EveryNFrames(NonZeroU32::new(3).unwrap()))]);
+	app.assert_buffer_eq(data, &[3.0f32; 16]);
+
+	app.app().world_mut().send_event(ValidateComputeEvent {
+		tasks: vec![task(StepSchedule::MaxFrequencyHz(0.0)), task(StepSchedule::MaxFrequencyHz(f32::NAN))],
+		iteration_buffer: None,
+	});
+	for _ in 0..1000 {
+		app.app().update();
+		if !app.app().world().resource::<ValidationReports>().0.is_empty() {
+			break;
+		}
+	}
+	let findings = &app.app().world().resource::<ValidationReports>().0[0].findings;
+	let messages = findings.iter().map(|finding| (finding.task, finding.message.as_str())).collect::<Vec<_>>();
+	assert_eq!(
+		messages,
+		vec![
+			(Some(0), "Tried to run a step at most 0 times a second, which isn't a positive, finite frequency"),
+			(Some(1), "Tried to run a step at most NaN times a second, which isn't a positive, finite frequency"),
+		]
+	);
+}
+
+#[test]
+#[should_panic(expected = "isn't a double buffer")]
+fn panics_when_a_ping_pong_buffer_isnt_double_buffered() {
+	let Some(mut app) = ComputeTestApp::new() else {
+		panic!("isn't a double buffer, but there's no GPU to check it on");
+	};
+	let data = app.add_storage_init(vec![0.0f32; 16], Binding::SingleBound(0, 0));
+	let one_workgroup = DispatchSize::Workgroups { x: 1, y: 1, z: 1 };
+	app.run_until_complete(vec![ComputeTask::ping_pong(
+		"increment",
+		SHADER_ASSET_PATH,
+		"increment",
+		one_workgroup,
+		&[data],
+		NonZeroU32::new(1),
+	)]);
+}
+
+#[test]
+fn dispatches_over_the_whole_of_a_texture() {
+	let Some(mut app) = ComputeTestApp::new() else {
+		return;
+	};
+	// Neither dimension is a multiple of the 8x8 workgroup size, so the edges are only covered by rounding up.
+	let world = app.app().world_mut();
+	let texture = world.resource_scope(|world, mut images: Mut<Assets<Image>>| {
+		world
+			.resource_mut::<ShaderBufferSet>()
+			.add_texture_fill(
+				&mut images,
+				67,
+				5,
+				TextureFormat::R32Float,
+				&0.0f32.to_ne_bytes(),
+				StorageTextureAccess::WriteOnly,
+				Binding::SingleBound(0, 0),
+			)
+			.unwrap()
+	});
+	app.run_until_complete(vec![ComputeTask {
+		label: None,
+		iterations: NonZeroU32::new(1),
+		run_mode: TaskRunMode::Continuous,
+		budget: None,
+		submission: TaskSubmission::WithFrame,
+		start_after: None,
+		iteration_buffer: None,
+		steps: vec![ComputeStep {
+			schedule: StepSchedule::EveryIteration,
+			run_if: None,
+			action: ComputeAction::RunShader {
+				shader: "shaders/test_dispatch_texture.wgsl".to_owned(),
+				entry_point: "fill".to_owned(),
+				shader_defs: Vec::new(),
+				overrides: Vec::new(),
+				dispatch: DispatchSize::FromTexture { buffer: texture },
+				auto_swap: Vec::new(),
+				skip_if_empty: None,
+				buffer_orientation: Vec::new(),
+				push_constants: None,
+				dynamic_offsets: Vec::new(),
+			},
+		}],
+	}]);
+	app.assert_buffer_eq(texture, &[1.0f32; 67 * 5]);
+}
+
+#[test]
+#[should_panic(expected = "isn't a storage texture")]
+fn panics_when_dispatching_over_a_buffer_that_isnt_a_texture() {
+	let Some(mut app) = ComputeTestApp::new() else {
+		panic!("isn't a storage texture, but there's no GPU to check it on");
+	};
+	let data = app.add_storage_init(vec![0.0f32; 16], Binding::SingleBound(0, 0));
+	app.run_until_complete(vec![ComputeTask {
+		label: None,
+		iterations: NonZeroU32::new(1),
+		run_mode: TaskRunMode::Continuous,
+		budget: None,
+		submission: TaskSubmission::WithFrame,
+		start_after: None,
+		iteration_buffer: None,
+		steps: vec![ComputeStep {
+			schedule: StepSchedule::EveryIteration,
+			run_if: None,
+			action: ComputeAction::RunShader {
+				shader: SHADER_ASSET_PATH.to_owned(),
+				entry_point: "increment".to_owned(),
+				shader_defs: Vec::new(),
+				overrides: Vec::new(),
+				dispatch: DispatchSize::FromTexture { buffer: data },
+				auto_swap: Vec::new(),
+				skip_if_empty: None,
+				buffer_orientation: Vec::new(),
+				push_constants: None,
+				dynamic_offsets: Vec::new(),
+			},
+		}],
+	}]);
+}
+
+#[test]
+fn swaps_double_buffers_after_shader_steps_that_ask_for_it() {
+	let Some(mut app) = ComputeTestApp::new() else {
+		return;
+	};
+	let data = app.add_storage_init(vec![0.0f32; 16], Binding::SingleBound(0, 0));
+	let double = app.add_storage_init(vec![0.0f32; 16], Binding::Double(0, (1, 2)));
+	let mut advance = run_shader("advance");
+	if let ComputeAction::RunShader { auto_swap, .. } = &mut advance.action {
+		auto_swap.push(double);
+	}
+	app.run_until_complete(vec![
+		ComputeTask {
+			label: None,
+			iterations: NonZeroU32::new(4),
+			run_mode: TaskRunMode::Continuous,
+			budget: None,
+			submission: TaskSubmission::WithFrame,
+			start_after: None,
+			iteration_buffer: None,
+			steps: vec![advance],
+		},
+		ComputeTask {
+			label: None,
+			iterations: NonZeroU32::new(1),
+			run_mode: TaskRunMode::Continuous,
+			budget: None,
+			submission: TaskSubmission::WithFrame,
+			start_after: None,
+			iteration_buffer: None,
+			steps: vec![run_shader("snapshot")],
+		},
+	]);
+	app.assert_buffer_eq(data, &[4.0f32; 16]);
+}
+
+#[test]
+fn sets_the_back_buffer_of_double_buffered_uniforms() {
+	let Some(mut app) = ComputeTestApp::new() else {
+		return;
+	};
+	let record = app.add_storage_init(vec![0.0f32; 2], Binding::SingleBound(0, 0));
+	let render_device = app.app().world().resource::<RenderDevice>().clone();
+	let render_queue = app.app().world().resource::<RenderQueue>().clone();
+	let params = app
+		.buffer_set()
+		.add_uniform_init(&render_device, &render_queue, Vec4::ONE, BufferUsages::empty(), Binding::Double(0, (1, 2)))
+		.unwrap();
+	let record_halves = ComputeStep {
+		schedule: StepSchedule::EveryIteration,
+		run_if: None,
+		action: ComputeAction::RunShader {
+			shader: "shaders/test_uniforms.wgsl".to_owned(),
+			entry_point: "record_halves".to_owned(),
+			shader_defs: Vec::new(),
+			overrides: Vec::new(),
+			dispatch: DispatchSize::Workgroups { x: 1, y: 1, z: 1 },
+			auto_swap: Vec::new(),
+			skip_if_empty: None,
+			buffer_orientation: Vec::new(),
+			push_constants: None,
+			dynamic_offsets: Vec::new(),
+		},
+	};
+	let task = |steps| ComputeTask {
+		label: None,
+		iterations: NonZeroU32::new(1),
+		run_mode: TaskRunMode::Continuous,
+		budget: None,
+		submission: TaskSubmission::WithFrame,
+		start_after: None,
+		iteration_buffer: None,
+		steps,
+	};
+
+	app.buffer_set().write_back_buffer(params, Vec4::splat(2.0)).unwrap();
+	app.run_until_complete(vec![task(vec![record_halves.clone()])]);
+	app.assert_buffer_eq(record, &[1.0f32, 2.0]);
+
+	// After a swap, the back buffer is the one that was in front.
+	let swap = ComputeStep {
+		schedule: StepSchedule::EveryIteration,
+		run_if: None,
+		action: ComputeAction::SwapBuffers { buffer: params },
+	};
+	app.run_until_complete(vec![task(vec![swap])]);
+	app.run_until_complete(vec![task(vec![record_halves.clone()])]);
+	app.assert_buffer_eq(record, &[2.0f32, 1.0]);
+	app.buffer_set().write_back_buffer(params, Vec4::splat(3.0)).unwrap();
+	app.run_until_complete(vec![task(vec![record_halves])]);
+	app.assert_buffer_eq(record, &[2.0f32, 3.0]);
+}
+
+#[test]
+fn binds_double_storage_buffers_read_only_in_front_and_sets_either_half() {
+	let Some(mut app) = ComputeTestApp::new() else {
+		return;
+	};
+	let data = app.add_storage_init(vec![0.0f32; 16], Binding::SingleBound(0, 0));
+	let double = app.add_storage_init(vec![0.0f32; 16], Binding::Double(0, (1, 2)));
+	let readonly = |app: &mut ComputeTestApp| {
+		let entries = app.buffer_set().layout_entry_for(double).unwrap();
+		entries
+			.iter()
+			.map(|entry| match entry.ty {
+				BindingType::Buffer { ty: BufferBindingType::Storage { read_only }, .. } => read_only,
+				ty => panic!("Expected a storage buffer entry, got {:?}", ty),
+			})
+			.collect::<Vec<_>>()
+	};
+	assert_eq!(readonly(&mut app), vec![true, false]);
+	let task = |steps| ComputeTask {
+		label: None,
+		iterations: NonZeroU32::new(1),
+		run_mode: TaskRunMode::Continuous,
+		budget: None,
+		submission: TaskSubmission::WithFrame,
+		start_after: None,
+		iteration_buffer: None,
+		steps,
+	};
+	let step = |writable_input: bool| {
+		let run = |entry_point| {
+			let mut step = run_shader(entry_point);
+			if let ComputeAction::RunShader { shader_defs, .. } = &mut step.action {
+				shader_defs.extend(writable_input.then(|| "WRITABLE_INPUT".into()));
+			}
+			step
+		};
+		vec![
+			task(vec![
+				run("advance"),
+				ComputeStep {
+					schedule: StepSchedule::EveryIteration,
+					run_if: None,
+					action: ComputeAction::SwapBuffers { buffer: double },
+				},
+			]),
+			task(vec![run("snapshot")]),
+		]
+	};
+
+	// Only the front buffer is set, so the back buffer the step writes over is still zero until it does.
+	app.buffer_set().write_front_buffer(double, vec![5.0f32; 16]).unwrap();
+	app.run_until_complete(step(false));
+	app.assert_buffer_eq(data, &[6.0f32; 16]);
+	app.buffer_set().write_back_buffer(double, vec![1.0f32; 16]).unwrap();
+	app.run_until_complete(step(false));
+	app.assert_buffer_eq(data, &[7.0f32; 16]);
+
+	// The pipelines are rebuilt for the new layout, which the shader declares the front buffer to match.
+	app.buffer_set().set_double_storage_access(double, false, false);
+	assert_eq!(readonly(&mut app), vec![false, false]);
+	app.run_until_complete(step(true));
+	app.assert_buffer_eq(data, &[8.0f32; 16]);
+	assert!(matches!(
+		app.buffer_set().write_front_buffer(data, vec![0.0f32; 16]),
+		Err(ShaderBufferError::WrongBufferKind { expected: "a double buffered storage or uniform buffer", .. })
+	));
+}
+
+#[test]
+fn binds_the_halves_of_a_double_texture_with_the_access_set() {
+	let Some(mut app) = ComputeTestApp::new() else {
+		return;
+	};
+	let world = app.app().world_mut();
+	let texture = world.resource_scope(|world, mut images: Mut<Assets<Image>>| {
+		world
+			.resource_mut::<ShaderBufferSet>()
+			.add_texture_fill_double(
+				&mut images,
+				2,
+				2,
+				TextureFormat::R32Float,
+				&1.0f32.to_ne_bytes(),
+				&2.0f32.to_ne_bytes(),
+				Binding::Double(0, (1, 2)),
+			)
+			.unwrap()
+	});
+	let access = |app: &mut ComputeTestApp| {
+		let entries = app.buffer_set().layout_entry_for(texture).unwrap();
+		entries
+			.iter()
+			.map(|entry| match entry.ty {
+				BindingType::StorageTexture { access, .. } => access,
+				ty => panic!("Expected a storage texture entry, got {:?}", ty),
+			})
+			.collect::<Vec<_>>()
+	};
+	assert_eq!(access(&mut app), vec![StorageTextureAccess::ReadOnly, StorageTextureAccess::WriteOnly]);
+
+	// The step reads what's already in the back half as it writes it, and the swap still brings the sum to the front.
+	app.buffer_set().set_double_texture_access(texture, StorageTextureAccess::ReadOnly, StorageTextureAccess::ReadWrite);
+	assert_eq!(access(&mut app), vec![StorageTextureAccess::ReadOnly, StorageTextureAccess::ReadWrite]);
+	app.run_until_complete(vec![ComputeTask {
+		label: None,
+		iterations: NonZeroU32::new(1),
+		run_mode: TaskRunMode::Continuous,
+		budget: None,
+		submission: TaskSubmission::WithFrame,
+		start_after: None,
+		iteration_buffer: None,
+		steps: vec![ComputeStep {
+			schedule: StepSchedule::EveryIteration,
+			run_if: None,
+			action: ComputeAction::RunShader {
+				shader: "shaders/test_double_texture_access.wgsl".to_owned(),
+				entry_point: "accumulate".to_owned(),
+				shader_defs: Vec::new(),
+				overrides: Vec::new(),
+				dispatch: ONE_WORKGROUP,
+				auto_swap: vec![texture],
+				skip_if_empty: None,
+				buffer_orientation: Vec::new(),
+				push_constants: None,
+				dynamic_offsets: Vec::new(),
+			},
+		}],
+	}]);
+	app.assert_buffer_eq(texture, &[3.0f32; 4]);
+
+	let storage = app.add_storage_init(vec![0.0f32; 4], Binding::Double(1, (0, 1)));
+	let result = catch_unwind(AssertUnwindSafe(|| {
+		app.buffer_set().set_double_texture_access(
+			storage,
+			StorageTextureAccess::ReadWrite,
+			StorageTextureAccess::ReadWrite,
+		);
+	}));
+	let message = *result.unwrap_err().downcast::<String>().unwrap();
+	assert!(message.contains("isn't a double buffered storage texture"), "{}", message);
+}
+
+#[test]
+fn sorts_u32_and_f32_keys() {
+	let Some(mut app) = ComputeTestApp::new() else {
+		return;
+	};
+	let unsorted = [7u32, 3, 900, 0, 12, 3, 65, 1, 4000, 22, 5];
+	let u32_buffer = app.add_storage_init(unsorted.to_vec(), Binding::SingleBound(0, 0));
+	let f32_buffer = app.add_storage_init(vec![2.5f32, -1.0, 0.0, -30.0, 8.0, 1.5, -0.5], Binding::SingleBound(0, 1));
+	app.run_until_complete(vec![ComputeTask {
+		label: None,
+		iterations: NonZeroU32::new(1),
+		run_mode: TaskRunMode::Continuous,
+		budget: None,
+		submission: TaskSubmission::WithFrame,
+		start_after: None,
+		iteration_buffer: None,
+		steps: vec![
+			ComputeStep {
+				schedule: StepSchedule::EveryIteration,
+				run_if: None,
+				action: ComputeAction::SortBuffer {
+					buffer: u32_buffer,
+					element_count: unsorted.len() as u32,
+					key: SortKey::u32(),
+				},
+			},
+			ComputeStep {
+				schedule: StepSchedule::EveryIteration,
+				run_if: None,
+				action: ComputeAction::SortBuffer { buffer: f32_buffer, element_count: 7, key: SortKey::f32() },
+			},
+		],
+	}]);
+	let mut sorted = unsorted.to_vec();
+	sorted.sort();
+	assert_eq!(app.read_buffer::<u32>(u32_buffer), sorted);
+	assert_buffer_approx_eq(&app.read_buffer::<f32>(f32_buffer), &[-30.0, -1.0, -0.5, 0.0, 1.5, 2.5, 8.0], 0.0);
+}
+
+#[test]
+fn reduces_buffers_over_multiple_passes() {
+	let Some(mut app) = ComputeTestApp::new() else {
+		return;
+	};
+	// Enough elements to take three passes, and not a multiple of the workgroup size.
+	let ones = app.add_storage_init(vec![1u32; 300_001], Binding::SingleBound(0, 0));
+	let floats = app.add_storage_init(vec![2.5f32, -1.0, 0.0, -30.0, 8.0, 1.5, -0.5], Binding::SingleBound(0, 1));
+	let ints = app.add_storage_init((0..1000).map(|i| i - 600).collect::<Vec<i32>>(), Binding::SingleBound(0, 2));
+	let results = [3, 4, 5, 6].map(|binding| app.add_storage_init(vec![0u32], Binding::SingleBound(0, binding)));
+	let reduce = |src, dst, op, element| ComputeStep {
+		schedule: StepSchedule::EveryIteration,
+		run_if: None,
+		action: ComputeAction::ReduceBuffer { src, dst, op, element },
+	};
+	app.run_until_complete(vec![ComputeTask {
+		label: None,
+		iterations: NonZeroU32::new(1),
+		run_mode: TaskRunMode::Continuous,
+		budget: None,
+		submission: TaskSubmission::WithFrame,
+		start_after: None,
+		iteration_buffer: None,
+		steps: vec![
+			reduce(ones, results[0], ReduceOp::Sum, ReduceElement::U32),
+			reduce(floats, results[1], ReduceOp::Min, ReduceElement::F32),
+			reduce(ints, results[2], ReduceOp::Max, ReduceElement::I32),
+			reduce(ints, results[3], ReduceOp::Sum, ReduceElement::I32),
+		],
+	}]);
+	assert_eq!(app.read_buffer::<u32>(results[0]), [300_001]);
+	assert_eq!(app.read_buffer::<f32>(results[1]), [-30.0]);
+	assert_eq!(app.read_buffer::<i32>(results[2]), [399]);
+	assert_eq!(app.read_buffer::<i32>(results[3]), [(-600..400).sum::<i32>()]);
+}
+
+#[test]
+fn prefix_sums_match_a_cpu_scan() {
+	let Some(mut app) = ComputeTestApp::new() else {
+		return;
+	};
+	// One element, one more than a half-block, and enough to take three levels, none a multiple of the block size.
+	let inputs = [1, 257, 65_537, 300_001].map(|count| (0..count).map(|i| (i * 7 + 3) % 11).collect::<Vec<u32>>());
+	let buffers: Vec<_> = inputs
+		.iter()
+		.enumerate()
+		.map(|(i, input)| {
+			// Padding past the element count checks that nothing beyond it is touched.
+			let mut padded = input.clone();
+			padded.push(u32::MAX);
+			app.add_storage_init(padded, Binding::SingleBound(0, i as u32))
+		})
+		.collect();
+	app.run_until_complete(vec![ComputeTask {
+		label: None,
+		iterations: NonZeroU32::new(1),
+		run_mode: TaskRunMode::Continuous,
+		budget: None,
+		submission: TaskSubmission::WithFrame,
+		start_after: None,
+		iteration_buffer: None,
+		steps: buffers
+			.iter()
+			.zip(&inputs)
+			.map(|(&buffer, input)| ComputeStep {
+				schedule: StepSchedule::EveryIteration,
+				run_if: None,
+				action: ComputeAction::PrefixSum { buffer, element_count: input.len() as u32 },
+			})
+			.collect(),
+	}]);
+	for (buffer, input) in buffers.into_iter().zip(inputs) {
+		let mut expected: Vec<u32> = input
+			.iter()
+			.scan(0, |sum, &value| {
+				let before = *sum;
+				*sum += value;
+				Some(before)
+			})
+			.collect();
+		expected.push(u32::MAX);
+		assert!(app.read_buffer::<u32>(buffer) == expected, "prefix sum of {} elements is wrong", input.len());
+	}
+}
+
+#[test]
+fn runs_shaders_once_per_entity() {
+	let Some(mut app) = ComputeTestApp::new() else {
+		return;
+	};
+	app.add_storage_init(vec![2.0f32; 16], Binding::SingleBound(0, 0));
+	let agents = [0.0, 10.0, 20.0].map(|start| {
+		let buffer = app.add_storage_init(vec![start; 16], Binding::PerEntity(1, 0));
+		app.app().world_mut().spawn(ComputeBufferComponent(buffer));
+		buffer
+	});
+	app.run_until_complete(vec![ComputeTask {
+		label: None,
+		iterations: NonZeroU32::new(3),
+		run_mode: TaskRunMode::Continuous,
+		budget: None,
+		submission: TaskSubmission::WithFrame,
+		start_after: None,
+		iteration_buffer: None,
+		steps: vec![run_shader_per_entity("advance_agent")],
+	}]);
+	for (agent, start) in agents.into_iter().zip([0.0, 10.0, 20.0]) {
+		assert_buffer_approx_eq(&app.read_buffer::<f32>(agent), &[start + 6.0; 16], 0.0);
+	}
+}
+
+#[test]
+fn releases_per_entity_buffers_on_despawn() {
+	let Some(mut app) = ComputeTestApp::new() else {
+		return;
+	};
+	let buffer = app.add_storage_init(vec![0.0f32; 16], Binding::PerEntity(1, 0));
+	let entity = app.app().world_mut().spawn(ComputeBufferComponent(buffer)).id();
+	app.app().world_mut().despawn(entity);
+	assert!(app.buffer_set().gpu_buffer(buffer).is_none());
+}
+
+#[test]
+fn resolves_imports_with_shader_defs() {
+	let Some(mut app) = ComputeTestApp::new() else {
+		return;
+	};
+	let asset_server = app.app().world().resource::<AssetServer>().clone();
+	app.app().world_mut().resource_mut::<ComputeShaderLibraries>().add(&asset_server, "shaders/test_library.wgsl");
+	let data = app.add_storage_init(vec![0.0f32; 16], Binding::SingleBound(0, 0));
+	app.run_until_complete(vec![ComputeTask {
+		label: None,
+		iterations: NonZeroU32::new(3),
+		run_mode: TaskRunMode::Continuous,
+		budget: None,
+		submission: TaskSubmission::WithFrame,
+		start_after: None,
+		iteration_buffer: None,
+		steps: vec![ComputeStep {
+			schedule: StepSchedule::EveryIteration,
+			run_if: None,
+			action: ComputeAction::RunShader {
+				shader: "shaders/test_import.wgsl".to_owned(),
+				entry_point: "increment_by_step".to_owned(),
+				shader_defs: vec!["DOUBLE_STEP".into()],
+				overrides: Vec::new(),
+				dispatch: DispatchSize::Workgroups { x: 1, y: 1, z: 1 },
+				auto_swap: Vec::new(),
+				skip_if_empty: None,
+				buffer_orientation: Vec::new(),
+				push_constants: None,
+				dynamic_offsets: Vec::new(),
+			},
+		}],
+	}]);
+	assert_buffer_approx_eq(&app.read_buffer::<f32>(data), &[6.0; 16], 0.0);
+}
+
+#[test]
+fn specializes_one_shader_differently_in_each_step() {
+	let Some(mut app) = ComputeTestApp::new() else {
+		return;
+	};
+	let asset_server = app.app().world().resource::<AssetServer>().clone();
+	app.app().world_mut().resource_mut::<ComputeShaderLibraries>().add(&asset_server, "shaders/test_library.wgsl");
+	let data = app.add_storage_init(vec![0.0f32; 16], Binding::SingleBound(0, 0));
+	let step = |shader_defs| ComputeStep {
+		schedule: StepSchedule::EveryIteration,
+		run_if: None,
+		action: ComputeAction::RunShader {
+			shader: "shaders/test_import.wgsl".to_owned(),
+			entry_point: "increment_by_step".to_owned(),
+			shader_defs,
+			overrides: Vec::new(),
+			dispatch: DispatchSize::Workgroups { x: 1, y: 1, z: 1 },
+			auto_swap: Vec::new(),
+			skip_if_empty: None,
+			buffer_orientation: Vec::new(),
+			push_constants: None,
+			dynamic_offsets: Vec::new(),
+		},
+	};
+	let tasks = || {
+		vec![ComputeTask {
+			label: None,
+			iterations: NonZeroU32::new(1),
+			run_mode: TaskRunMode::Continuous,
+			budget: None,
+			submission: TaskSubmission::WithFrame,
+			start_after: None,
+			iteration_buffer: None,
+			steps: vec![step(vec!["DOUBLE_STEP".into()]), step(vec!["DOUBLE_STEP".into()]), step(Vec::new())],
+		}]
+	};
+	app.run_until_complete(tasks());
+	assert_buffer_approx_eq(&app.read_buffer::<f32>(data), &[5.0; 16], 0.0);
+
+	// Started again, the sequence runs the pipelines it made the first time.
+	app.run_until_complete(tasks());
+	assert_buffer_approx_eq(&app.read_buffer::<f32>(data), &[10.0; 16], 0.0);
+}
+
+fn add_step(overrides: &[(&str, f64)], dispatch: DispatchSize) -> ComputeStep {
+	ComputeStep {
+		schedule: StepSchedule::EveryIteration,
+		run_if: None,
+		action: ComputeAction::RunShader {
+			shader: "shaders/test_overrides.wgsl".to_owned(),
+			entry_point: "add_step".to_owned(),
+			shader_defs: Vec::new(),
+			overrides: overrides.iter().map(|(name, value)| (name.to_string(), *value)).collect(),
+			dispatch,
+			auto_swap: Vec::new(),
+			skip_if_empty: None,
+			buffer_orientation: Vec::new(),
+			push_constants: None,
+			dynamic_offsets: Vec::new(),
+		},
+	}
+}
+
+const ONE_WORKGROUP: DispatchSize = DispatchSize::Workgroups { x: 1, y: 1, z: 1 };
+
+#[test]
+fn sets_override_constants_per_step() {
+	let Some(mut app) = ComputeTestApp::new() else {
+		return;
+	};
+	let data = app.add_storage_init(vec![0.0f32; 16], Binding::SingleBound(0, 0));
+	app.run_until_complete(vec![ComputeTask {
+		label: None,
+		iterations: NonZeroU32::new(2),
+		run_mode: TaskRunMode::Continuous,
+		budget: None,
+		submission: TaskSubmission::WithFrame,
+		start_after: None,
+		iteration_buffer: None,
+		steps: vec![
+			add_step(&[("STEP", 2.0), ("WORKGROUP_SIZE", 64.0)], ONE_WORKGROUP),
+			add_step(&[("STEP", 0.5), ("WORKGROUP_SIZE", 64.0)], ONE_WORKGROUP),
+		],
+	}]);
+	assert_buffer_approx_eq(&app.read_buffer::<f32>(data), &[5.0; 16], 0.0);
+}
+
+#[derive(Resource, Default)]
+struct Errors(Vec<ComputeErrorEvent>);
+
+#[test]
+fn reports_unknown_override_constants() {
+	let Some(mut app) = ComputeTestApp::new() else {
+		return;
+	};
+	app
+		.app()
+		.init_resource::<Errors>()
+		.add_systems(Update, |mut events: EventReader<ComputeErrorEvent>, mut errors: ResMut<Errors>| {
+			errors.0.extend(events.read().cloned())
+		});
+	let data = app.add_storage_init(vec![0.0f32; 16], Binding::SingleBound(0, 0));
+	app.run_until_complete(vec![ComputeTask {
+		label: None,
+		iterations: NonZeroU32::new(1),
+		run_mode: TaskRunMode::Continuous,
+		budget: None,
+		submission: TaskSubmission::WithFrame,
+		start_after: None,
+		iteration_buffer: None,
+		steps: vec![add_step(&[("STEP", 3.0), ("WORKGROUP_SIZE", 64.0), ("STPE", 1.0)], ONE_WORKGROUP)],
+	}]);
+	assert_buffer_approx_eq(&app.read_buffer::<f32>(data), &[3.0; 16], 0.0);
+	let errors = &app.app().world().resource::<Errors>().0;
+	assert!(matches!(errors.as_slice(), [ComputeErrorEvent::UnknownOverride { name, .. }] if name == "STPE"));
+}
+
+/// Send a [SwapStepShaderEvent] for the first step of the first task once it's run some iterations.
+fn swap_after(
+	iterations: u32, shader: &'static str, entry_point: Option<&'static str>,
+) -> impl FnMut(Res<ComputeProgress>, EventWriter<SwapStepShaderEvent>, Local<bool>) {
+	move |progress, mut events, mut sent| {
+		if !*sent && progress.iterations >= iterations {
+			*sent = true;
+			events.send(SwapStepShaderEvent {
+				sequence: ComputeSequenceId::MAIN,
+				task: 0,
+				step: 0,
+				shader: shader.to_owned(),
+				entry_point: entry_point.map(str::to_owned),
+			});
+		}
+	}
+}
+
+#[test]
+fn swaps_the_shader_of_a_running_step_without_restarting_it() {
+	let Some(mut app) = ComputeTestApp::new() else {
+		return;
+	};
+	app.app().init_resource::<DoneIterations>().add_systems(
+		Update,
+		(
+			swap_after(10, SHADER_ASSET_PATH, Some("decrement")),
+			|mut events: EventReader<ComputeTaskDoneEvent>, mut done: ResMut<DoneIterations>| {
+				done.0.extend(events.read().map(|event| event.iterations))
+			},
+		),
+	);
+	let data = app.add_storage_init(vec![0.0f32; 16], Binding::SingleBound(0, 0));
+	app.run_until_complete(vec![ComputeTask {
+		label: None,
+		iterations: NonZeroU32::new(40),
+		run_mode: TaskRunMode::Continuous,
+		budget: None,
+		submission: TaskSubmission::WithFrame,
+		start_after: None,
+		iteration_buffer: None,
+		steps: vec![run_shader("increment")],
+	}]);
+	assert_eq!(app.app().world().resource::<DoneIterations>().0, vec![40]);
+	// The step incremented for at least 10 iterations, and decremented for the rest, once the new pipeline was ready.
+	let values = app.read_buffer::<f32>(data);
+	assert!(values.iter().all(|value| *value == values[0]));
+	assert!((-20.0..40.0).contains(&values[0]), "The step ended with {}", values[0]);
+}
+
+#[test]
+fn changes_the_dispatch_size_of_a_running_step() {
+	let Some(mut app) = ComputeTestApp::new() else {
+		return;
+	};
+	let data = app.add_storage_init(vec![0.0f32; 128], Binding::SingleBound(0, 0));
+	app.app().world_mut().send_event(StartComputeEvent {
+		sequence: ComputeSequenceId::MAIN,
+		tasks: vec![ComputeTask {
+			label: None,
+			iterations: None,
+			run_mode: TaskRunMode::Continuous,
+			budget: None,
+			submission: TaskSubmission::WithFrame,
+			start_after: None,
+			iteration_buffer: None,
+			steps: vec![run_shader("increment")],
+		}],
+		iteration_buffer: None,
+		seed: 0,
+	});
+	for _ in 0..MAX_FRAMES {
+		app.app().update();
+		if app.app().world().resource::<ComputeProgress>().iterations >= 2 {
+			break;
+		}
+	}
+	// Steps that don't exist, or don't have a dispatch size, are only logged.
+	app.app().world_mut().send_event(UpdateDispatchSizeEvent {
+		sequence: ComputeSequenceId::MAIN,
+		task: 0,
+		step: 1,
+		dispatch: DispatchSize::Workgroups { x: 4, y: 1, z: 1 },
+	});
+	app.app().world_mut().send_event(UpdateDispatchSizeEvent {
+		sequence: ComputeSequenceId::MAIN,
+		task: 0,
+		step: 0,
+		dispatch: DispatchSize::Exact { x: 128, y: 1, z: 1 },
+	});
+	for _ in 0..10 {
+		app.app().update();
+	}
+	app.app().world_mut().send_event(StopComputeEvent(ComputeSequenceId::MAIN));
+	app.app().update();
+
+	// The first workgroup ran every iteration, and the second only once the size was changed.
+	let values = app.read_buffer::<f32>(data);
+	assert!(values[127] >= 1.0);
+	assert!(values[0] > values[127]);
+	assert!(values[..64].iter().all(|&value| value == values[0]));
+	assert!(values[64..].iter().all(|&value| value == values[127]));
+}
+
+#[test]
+fn keeps_the_old_shader_when_a_swapped_in_one_fails() {
+	let Some(mut app) = ComputeTestApp::new() else {
+		return;
+	};
+	app.app().init_resource::<Errors>().add_systems(
+		Update,
+		(
+			swap_after(5, "shaders/test_broken.wgsl", None),
+			|mut events: EventReader<ComputeErrorEvent>, mut errors: ResMut<Errors>| errors.0.extend(events.read().cloned()),
+		),
+	);
+	let data = app.add_storage_init(vec![0.0f32; 16], Binding::SingleBound(0, 0));
+	app.run_until_complete(vec![ComputeTask {
+		label: None,
+		iterations: NonZeroU32::new(60),
+		run_mode: TaskRunMode::Continuous,
+		budget: None,
+		submission: TaskSubmission::WithFrame,
+		start_after: None,
+		iteration_buffer: None,
+		steps: vec![run_shader("increment")],
+	}]);
+	let errors = &app.app().world().resource::<Errors>().0;
+	assert!(
+		matches!(errors.as_slice(), [ComputeErrorEvent::ShaderSwapFailed { task: 0, step: 0, shader, .. }] if shader == "shaders/test_broken.wgsl"),
+		"Got {:?}",
+		errors
+	);
+	app.assert_buffer_eq(data, &[60.0f32; 16]);
+}
+
+#[test]
+fn reports_missing_entry_points_and_broken_shaders_once_and_waits() {
+	let Some(mut app) = ComputeTestApp::new() else {
+		return;
+	};
+	app
+		.app()
+		.init_resource::<Errors>()
+		.add_systems(Update, |mut events: EventReader<ComputeErrorEvent>, mut errors: ResMut<Errors>| {
+			errors.0.extend(events.read().cloned())
+		});
+	let data = app.add_storage_init(vec![0.0f32; 16], Binding::SingleBound(0, 0));
+	let mut broken = run_shader("increment");
+	if let ComputeAction::RunShader { shader, .. } = &mut broken.action {
+		*shader = "shaders/test_broken.wgsl".to_owned();
+	}
+	// Neither task ever runs, but they each only report their problem once, however long they wait.
+	for step in [run_shader("incremnet"), broken] {
+		app.app().world_mut().send_event(StartComputeEvent {
+			sequence: ComputeSequenceId::MAIN,
+			tasks: vec![ComputeTask {
+				label: None,
+				iterations: NonZeroU32::new(1),
+				run_mode: TaskRunMode::Continuous,
+				budget: None,
+				submission: TaskSubmission::WithFrame,
+				start_after: None,
+				iteration_buffer: None,
+				steps: vec![step],
+			}],
+			iteration_buffer: None,
+			seed: 0,
+		});
+		for _ in 0..100 {
+			app.app().update();
+		}
+		assert_eq!(app.app().world().resource::<ComputeProgress>().iterations, 0);
+		app.app().world_mut().send_event(StopComputeEvent(ComputeSequenceId::MAIN));
+		app.app().update();
+	}
+	let errors = &app.app().world().resource::<Errors>().0;
+	assert!(
+		matches!(
+			errors.as_slice(),
+			[
+				ComputeErrorEvent::MissingEntryPoint { shader: missing_shader, entry_point: missing },
+				ComputeErrorEvent::PipelineFailed { task: 0, step: 0, shader, entry_point, .. },
+			] if missing_shader == SHADER_ASSET_PATH && missing == "incremnet"
+				&& shader == "shaders/test_broken.wgsl" && entry_point == "increment"
+		),
+		"Got {:?}",
+		errors
+	);
+	app.assert_buffer_eq(data, &[0.0f32; 16]);
+}
+
+/// Replace part of the source of a loaded shader, as if the file had been edited and hot-reloaded.
+fn reload_shader(app: &mut ComputeTestApp, path: &str, from: &str, to: &str) {
+	let handle = app.app().world().resource::<AssetServer>().load::<Shader>(path);
+	let mut shaders = app.app().world_mut().resource_mut::<Assets<Shader>>();
+	let mut shader = shaders.get(&handle).unwrap().clone();
+	let Source::Wgsl(source) = &shader.source else {
+		panic!("{} isn't a WGSL shader", path);
+	};
+	shader.source = Source::Wgsl(source.replace(from, to).into());
+	shaders.insert(&handle, shader);
+}
+
+#[test]
+fn rebuilds_pipelines_when_their_shader_is_reloaded() {
+	let Some(mut app) = ComputeTestApp::new() else {
+		return;
+	};
+	app
+		.app()
+		.init_resource::<Errors>()
+		.add_systems(Update, |mut events: EventReader<ComputeErrorEvent>, mut errors: ResMut<Errors>| {
+			errors.0.extend(events.read().cloned())
+		});
+	let data = app.add_storage_init(vec![0.0f32; 16], Binding::SingleBound(0, 0));
+	app.app().world_mut().send_event(StartComputeEvent {
+		sequence: ComputeSequenceId::MAIN,
+		tasks: vec![ComputeTask {
+			label: None,
+			iterations: None,
+			run_mode: TaskRunMode::Continuous,
+			budget: None,
+			submission: TaskSubmission::WithFrame,
+			start_after: None,
+			iteration_buffer: None,
+			steps: vec![run_shader("increment")],
+		}],
+		iteration_buffer: None,
+		seed: 0,
+	});
+	let iterations = |app: &mut ComputeTestApp| app.app().world().resource::<ComputeProgress>().iterations;
+	for _ in 0..MAX_FRAMES {
+		app.app().update();
+		if iterations(&mut app) >= 3 {
+			break;
+		}
+	}
+
+	// A reload that doesn't compile is reported once, and the step carries on with its old pipeline.
+	reload_shader(&mut app, SHADER_ASSET_PATH, "+= 1.0;", "+= undeclared;");
+	for _ in 0..MAX_FRAMES {
+		app.app().update();
+		if !app.app().world().resource::<Errors>().0.is_empty() {
+			break;
+		}
+	}
+	let failed_at = iterations(&mut app);
+	for _ in 0..10 {
+		app.app().update();
+	}
+	assert!(iterations(&mut app) > failed_at);
+	let errors = &app.app().world().resource::<Errors>().0;
+	assert!(
+		matches!(
+			errors.as_slice(),
+			[ComputeErrorEvent::ShaderReloadFailed { task: 0, step: 0, shader, entry_point, .. }]
+				if shader == SHADER_ASSET_PATH && entry_point == "increment"
+		),
+		"Got {:?}",
+		errors
+	);
+
+	// Once it's fixed, the step switches over to the new pipeline.
+	reload_shader(&mut app, SHADER_ASSET_PATH, "+= undeclared;", "+= 1000.0;");
+	for _ in 0..60 {
+		app.app().update();
+	}
+	app.app().world_mut().send_event(StopComputeEvent(ComputeSequenceId::MAIN));
+	app.app().update();
+	let values = app.read_buffer::<f32>(data);
+	assert!(values.iter().all(|&value| value == values[0]));
+	assert!(values[0] >= 1000.0, "The step ended with {}", values[0]);
+	assert_eq!(app.app().world().resource::<Errors>().0.len(), 1);
+}
+
+fn increment_unless_empty(counter: ShaderBufferHandle) -> ComputeTask {
+	let mut increment = run_shader("increment");
+	if let ComputeAction::RunShader { skip_if_empty, .. } = &mut increment.action {
+		*skip_if_empty = Some(counter);
+	}
+	ComputeTask {
+		label: None,
+		iterations: NonZeroU32::new(30),
+		run_mode: TaskRunMode::Continuous,
+		budget: None,
+		submission: TaskSubmission::WithFrame,
+		start_after: None,
+		iteration_buffer: None,
+		steps: vec![increment],
+	}
+}
+
+#[test]
+fn skips_steps_while_their_counter_is_empty() {
+	let Some(mut app) = ComputeTestApp::new() else {
+		return;
+	};
+	let data = app.add_storage_init(vec![0.0f32; 16], Binding::SingleBound(0, 0));
+	let empty = app.add_storage_init(vec![0u32; 4], Binding::SingleUnbound);
+	app.run_until_complete(vec![increment_unless_empty(empty)]);
+	// The step runs until the count has been read back, which takes a frame or two.
+	let values = app.read_buffer::<f32>(data);
+	assert!((1.0..10.0).contains(&values[0]), "The step ran {} times", values[0]);
+	let counted = app.add_storage_init(vec![3u32; 4], Binding::SingleUnbound);
+	app.run_until_complete(vec![increment_unless_empty(counted)]);
+	app.assert_buffer_eq(data, &[values[0] + 30.0; 16]);
+}
+
+#[test]
+fn dispatches_exact_sizes_using_the_declared_workgroup_size() {
+	let Some(mut app) = ComputeTestApp::new() else {
+		return;
+	};
+	let data = app.add_storage_init(vec![0.0f32; 16], Binding::SingleBound(0, 0));
+	let exact = DispatchSize::Exact { x: 16, y: 1, z: 1 };
+	let mut increment = run_shader("increment");
+	if let ComputeAction::RunShader { dispatch, .. } = &mut increment.action {
+		*dispatch = exact;
+	}
+	app.run_until_complete(vec![ComputeTask {
+		label: None,
+		iterations: NonZeroU32::new(1),
+		run_mode: TaskRunMode::Continuous,
+		budget: None,
+		submission: TaskSubmission::WithFrame,
+		start_after: None,
+		iteration_buffer: None,
+		// The shader's default workgroup size is 64, so if the overridden size of 2 were
+		// ignored, only one workgroup would be dispatched, covering 2 elements.
+		steps: vec![increment, add_step(&[("STEP", 1.0), ("WORKGROUP_SIZE", 2.0)], exact)],
+	}]);
+	assert_buffer_approx_eq(&app.read_buffer::<f32>(data), &[2.0; 16], 0.0);
+}
+
+#[test]
+fn writes_indirect_draw_arguments() {
+	let Some(mut app) = ComputeTestApp::new() else {
+		return;
+	};
+	app.add_storage_init(vec![1.0f32, -1.0, 2.0, 0.0, 3.0, -4.0, 5.0, 0.5], Binding::SingleBound(0, 0));
+	let render_device = app.app().world().resource::<RenderDevice>().clone();
+	let args = app
+		.buffer_set()
+		.add_draw_indirect_args(
+			&render_device,
+			DrawIndirectArgs { vertex_count: 6, instance_count: 0, first_vertex: 0, first_instance: 0 },
+			Binding::SingleBound(0, 1),
+		)
+		.unwrap();
+	app.run_until_complete(vec![ComputeTask {
+		label: None,
+		iterations: NonZeroU32::new(1),
+		run_mode: TaskRunMode::Continuous,
+		budget: None,
+		submission: TaskSubmission::WithFrame,
+		start_after: None,
+		iteration_buffer: None,
+		steps: vec![ComputeStep {
+			schedule: StepSchedule::EveryIteration,
+			run_if: None,
+			action: ComputeAction::RunShader {
+				shader: "shaders/test_indirect.wgsl".to_owned(),
+				entry_point: "count_positive".to_owned(),
+				shader_defs: Vec::new(),
+				overrides: Vec::new(),
+				dispatch: DispatchSize::Workgroups { x: 1, y: 1, z: 1 },
+				auto_swap: Vec::new(),
+				skip_if_empty: None,
+				buffer_orientation: Vec::new(),
+				push_constants: None,
+				dynamic_offsets: Vec::new(),
+			},
+		}],
+	}]);
+	assert_eq!(app.read_buffer::<u32>(args), vec![6, 5, 0, 0]);
+}
+
+/// A task that counts the positive values into the workgroup counts at `args`, and then dispatches that many workgroups.
+fn indirect_dispatch_task(args: ShaderBufferHandle) -> ComputeTask {
+	ComputeTask {
+		label: None,
+		iterations: NonZeroU32::new(1),
+		run_mode: TaskRunMode::Continuous,
+		budget: None,
+		submission: TaskSubmission::WithFrame,
+		start_after: None,
+		iteration_buffer: None,
+		steps: vec![
+			ComputeStep {
+				schedule: StepSchedule::EveryIteration,
+				run_if: None,
+				action: ComputeAction::RunShader {
+					shader: "shaders/test_dispatch_indirect.wgsl".to_owned(),
+					entry_point: "count_positive".to_owned(),
+					shader_defs: Vec::new(),
+					overrides: Vec::new(),
+					dispatch: DispatchSize::Workgroups { x: 1, y: 1, z: 1 },
+					auto_swap: Vec::new(),
+					skip_if_empty: None,
+					buffer_orientation: Vec::new(),
+					push_constants: None,
+					dynamic_offsets: Vec::new(),
+				},
+			},
+			ComputeStep {
+				schedule: StepSchedule::EveryIteration,
+				run_if: None,
+				action: ComputeAction::RunShaderIndirect {
+					shader: "shaders/test_dispatch_indirect.wgsl".to_owned(),
+					entry_point: "count_workgroups".to_owned(),
+					shader_defs: Vec::new(),
+					overrides: Vec::new(),
+					indirect_buffer: args,
+					offset: 0,
+				},
+			},
+		],
+	}
+}
+
+#[test]
+fn dispatches_as_many_workgroups_as_an_earlier_step_counted() {
+	let Some(mut app) = ComputeTestApp::new() else {
+		return;
+	};
+	app.add_storage_init(vec![1.0f32, -1.0, 2.0, 0.0, 3.0, -4.0, 5.0, 0.5], Binding::SingleBound(0, 0));
+	let render_device = app.app().world().resource::<RenderDevice>().clone();
+	let args = app
+		.buffer_set()
+		.add_dispatch_indirect_args(&render_device, DispatchIndirectArgs { x: 0, y: 1, z: 1 }, Binding::SingleBound(0, 1))
+		.unwrap();
+	let workgroups = app.add_storage_init(vec![0u32], Binding::SingleBound(0, 2));
+	app.run_until_complete(vec![indirect_dispatch_task(args)]);
+	assert_eq!(app.read_buffer::<u32>(args), vec![5, 1, 1]);
+	app.assert_buffer_eq(workgroups, &[5u32]);
+}
+
+#[test]
+#[should_panic(expected = "which wasn't made with BufferUsages::INDIRECT")]
+fn panics_when_dispatching_indirectly_from_a_buffer_without_the_indirect_usage() {
+	let Some(mut app) = ComputeTestApp::new() else {
+		panic!("which wasn't made with BufferUsages::INDIRECT, but there's no GPU to check it on");
+	};
+	app.add_storage_init(vec![1.0f32; 8], Binding::SingleBound(0, 0));
+	let args = app.add_storage_init(vec![0u32, 1, 1], Binding::SingleBound(0, 1));
+	app.add_storage_init(vec![0u32], Binding::SingleBound(0, 2));
+	app.run_until_complete(vec![indirect_dispatch_task(args)]);
+}
+
+fn copy_step(
+	src: ShaderBufferHandle, dst: ShaderBufferHandle, src_offset: u64, dst_offset: u64, size: Option<u64>,
+) -> ComputeStep {
+	ComputeStep {
+		schedule: StepSchedule::EveryIteration,
+		run_if: None,
+		action: ComputeAction::CopyBufferToBuffer { src, dst, src_offset, dst_offset, size },
+	}
+}
+
+#[test]
+fn copies_between_storage_buffers_on_the_gpu() {
+	let Some(mut app) = ComputeTestApp::new() else {
+		return;
+	};
+	let data = app.add_storage_init(vec![0.0f32; 16], Binding::SingleBound(0, 0));
+	let part = app.add_storage_init(vec![0.0f32; 8], Binding::SingleUnbound);
+	let whole = app.add_storage_init(vec![0.0f32; 16], Binding::SingleUnbound);
+	app.run_until_complete(vec![
+		ComputeTask {
+			label: None,
+			iterations: NonZeroU32::new(4),
+			run_mode: TaskRunMode::Continuous,
+			budget: None,
+			submission: TaskSubmission::WithFrame,
+			start_after: None,
+			iteration_buffer: None,
+			steps: vec![run_shader("increment"), copy_step(data, part, 8, 4, Some(16))],
+		},
+		ComputeTask {
+			label: None,
+			iterations: NonZeroU32::new(1),
+			run_mode: TaskRunMode::Continuous,
+			budget: None,
+			submission: TaskSubmission::WithFrame,
+			start_after: None,
+			iteration_buffer: None,
+			steps: vec![copy_step(data, whole, 0, 0, None)],
+		},
+	]);
+	app.assert_buffer_eq(part, &[0.0f32, 4.0, 4.0, 4.0, 4.0, 0.0, 0.0, 0.0]);
+	app.assert_buffer_eq(whole, &[4.0f32; 16]);
+}
+
+#[test]
+#[should_panic(expected = "which wasn't made with BufferUsages::COPY_SRC")]
+fn panics_when_copying_out_of_a_buffer_without_the_copy_src_usage() {
+	let Some(mut app) = ComputeTestApp::new() else {
+		panic!("which wasn't made with BufferUsages::COPY_SRC, but there's no GPU to check it on");
+	};
+	let render_device = app.app().world().resource::<RenderDevice>().clone();
+	let src = app
+		.buffer_set()
+		.add_storage_zeroed(&render_device, 16, BufferUsages::empty(), Binding::SingleUnbound, false)
+		.unwrap();
+	let dst = app.add_storage_init(vec![0.0f32; 4], Binding::SingleUnbound);
+	app.run_until_complete(vec![ComputeTask {
+		label: None,
+		iterations: NonZeroU32::new(1),
+		run_mode: TaskRunMode::Continuous,
+		budget: None,
+		submission: TaskSubmission::WithFrame,
+		start_after: None,
+		iteration_buffer: None,
+		steps: vec![copy_step(src, dst, 0, 0, None)],
+	}]);
+}
+
+#[test]
+fn reads_back_just_part_of_a_storage_buffer() {
+	#[derive(Resource, Default)]
+	struct Reads(Vec<Vec<f32>>);
+
+	let Some(mut app) = ComputeTestApp::new() else {
+		return;
+	};
+	app.app().init_resource::<Reads>().add_systems(
+		Update,
+		|mut events: EventReader<BufferReadbackEvent>, mut reads: ResMut<Reads>| {
+			reads.0.extend(events.read().map(|event| event.as_slice_of::<f32>().unwrap().to_vec()))
+		},
+	);
+	let data = app.add_storage_init((0..16).map(|i| i as f32).collect::<Vec<_>>(), Binding::SingleBound(0, 0));
+	let copy = |offset, size| ComputeStep {
+		schedule: StepSchedule::EveryIteration,
+		run_if: None,
+		action: ComputeAction::CopyBuffer { src: data, offset, size },
+	};
+	app.run_until_complete(vec![
+		ComputeTask {
+			label: None,
+			iterations: NonZeroU32::new(1),
+			run_mode: TaskRunMode::Continuous,
+			budget: None,
+			submission: TaskSubmission::WithFrame,
+			start_after: None,
+			iteration_buffer: None,
+			steps: vec![copy(8, Some(16))],
+		},
+		ComputeTask {
+			label: None,
+			iterations: NonZeroU32::new(1),
+			run_mode: TaskRunMode::Continuous,
+			budget: None,
+			submission: TaskSubmission::WithFrame,
+			start_after: None,
+			iteration_buffer: None,
+			steps: vec![copy(56, None)],
+		},
+	]);
+	assert_eq!(app.app().world().resource::<Reads>().0, vec![vec![2.0, 3.0, 4.0, 5.0], vec![14.0, 15.0]]);
+}
+
+#[test]
+#[should_panic(expected = "but the offset and size must be multiples of 4")]
+fn panics_when_reading_back_a_misaligned_part_of_a_buffer() {
+	let Some(mut app) = ComputeTestApp::new() else {
+		panic!("but the offset and size must be multiples of 4, but there's no GPU to check it on");
+	};
+	let data = app.add_storage_init(vec![0.0f32; 16], Binding::SingleBound(0, 0));
+	app.run_until_complete(vec![ComputeTask {
+		label: None,
+		iterations: NonZeroU32::new(2),
+		run_mode: TaskRunMode::Continuous,
+		budget: None,
+		submission: TaskSubmission::WithFrame,
+		start_after: None,
+		iteration_buffer: None,
+		steps: vec![ComputeStep {
+			schedule: StepSchedule::EveryIteration,
+			run_if: None,
+			action: ComputeAction::CopyBuffer { src: data, offset: 2, size: Some(4) },
+		}],
+	}]);
+}
+
+fn texture_copy_step(action: ComputeAction) -> ComputeStep {
+	ComputeStep { schedule: StepSchedule::EveryIteration, run_if: None, action }
+}
+
+fn add_r32_texture(app: &mut ComputeTestApp, width: u32, height: u32, binding: Binding) -> ShaderBufferHandle {
+	app.app().world_mut().resource_scope(|world, mut images: Mut<Assets<Image>>| {
+		world
+			.resource_mut::<ShaderBufferSet>()
+			.add_texture_fill(
+				&mut images,
+				width,
+				height,
+				TextureFormat::R32Uint,
+				&0u32.to_ne_bytes(),
+				StorageTextureAccess::ReadOnly,
+				binding,
+			)
+			.unwrap()
+	})
+}
+
+#[test]
+fn copies_between_textures_and_buffers_without_padding_the_rows() {
+	let Some(mut app) = ComputeTestApp::new() else {
+		return;
+	};
+	// Rows of 3 texels are copied one at a time, and rows of 64, which are 256 bytes, a whole layer at a time.
+	let mut steps = Vec::new();
+	let mut copies = Vec::new();
+	for (width, first_binding) in [(3, 0), (64, 3)] {
+		let texels = (0..width * 2).map(|texel| texel * 7 + 1).collect::<Vec<u32>>();
+		let src = app.add_storage_init(texels.clone(), Binding::SingleBound(0, first_binding));
+		let texture = add_r32_texture(&mut app, width, 2, Binding::SingleBound(0, first_binding + 1));
+		let dst = app.add_storage_init(vec![0u32; texels.len()], Binding::SingleBound(0, first_binding + 2));
+		steps.push(texture_copy_step(ComputeAction::CopyBufferToTexture { src, dst: texture }));
+		steps.push(texture_copy_step(ComputeAction::CopyTextureToBuffer { src: texture, dst }));
+		copies.push((texture, dst, texels));
+	}
+	app.run_until_complete(vec![ComputeTask {
+		label: None,
+		iterations: NonZeroU32::new(1),
+		run_mode: TaskRunMode::Continuous,
+		budget: None,
+		submission: TaskSubmission::WithFrame,
+		start_after: None,
+		iteration_buffer: None,
+		steps,
+	}]);
+	for (texture, dst, texels) in copies {
+		app.assert_buffer_eq(texture, &texels);
+		app.assert_buffer_eq(dst, &texels);
+	}
+}
+
+#[test]
+#[should_panic(
+	expected = "which needs 24 bytes, for 3x2x1 texels of R32Uint at 4 bytes each, but the buffer only holds 16 bytes"
+)]
+fn panics_when_copying_a_texture_into_a_buffer_too_small_for_it() {
+	let Some(mut app) = ComputeTestApp::new() else {
+		panic!("which needs 24 bytes, for 3x2x1 texels of R32Uint at 4 bytes each, but the buffer only holds 16 bytes, but there's no GPU to check it on");
+	};
+	let texture = add_r32_texture(&mut app, 3, 2, Binding::SingleBound(0, 0));
+	let dst = app.add_storage_init(vec![0u32; 4], Binding::SingleBound(0, 1));
+	app.run_until_complete(vec![ComputeTask {
+		label: None,
+		iterations: NonZeroU32::new(1),
+		run_mode: TaskRunMode::Continuous,
+		budget: None,
+		submission: TaskSubmission::WithFrame,
+		start_after: None,
+		iteration_buffer: None,
+		steps: vec![texture_copy_step(ComputeAction::CopyTextureToBuffer { src: texture, dst })],
+	}]);
+}
+
+#[test]
+fn writes_the_same_globals_for_every_step_of_a_tick() {
+	let Some(mut app) = ComputeTestApp::new() else {
+		return;
+	};
+	let record = app.add_storage_init(vec![0u32; 4], Binding::SingleBound(0, 0));
+	let render_device = app.app().world().resource::<RenderDevice>().clone();
+	let render_queue = app.app().world().resource::<RenderQueue>().clone();
+	app.buffer_set().add_globals(&render_device, &render_queue, Binding::SingleBound(0, 1)).unwrap();
+	let result = catch_unwind(AssertUnwindSafe(|| {
+		app.buffer_set().add_globals(&render_device, &render_queue, Binding::SingleBound(1, 0)).unwrap();
+	}));
+	assert!(result.is_err(), "Adding a second globals buffer should panic");
+
+	let step = |entry_point: &str| ComputeStep {
+		schedule: StepSchedule::EveryIteration,
+		run_if: None,
+		action: ComputeAction::RunShader {
+			shader: "shaders/test_globals.wgsl".to_owned(),
+			entry_point: entry_point.to_owned(),
+			shader_defs: Vec::new(),
+			overrides: Vec::new(),
+			dispatch: DispatchSize::Workgroups { x: 1, y: 1, z: 1 },
+			auto_swap: Vec::new(),
+			skip_if_empty: None,
+			buffer_orientation: Vec::new(),
+			push_constants: None,
+			dynamic_offsets: Vec::new(),
+		},
+	};
+	app.run_until_complete(vec![ComputeTask {
+		label: None,
+		iterations: NonZeroU32::new(3),
+		run_mode: TaskRunMode::Continuous,
+		budget: None,
+		submission: TaskSubmission::WithFrame,
+		start_after: None,
+		iteration_buffer: None,
+		steps: vec![step("record_globals"), step("record_frame_count")],
+	}]);
+	let record = app.read_buffer::<u32>(record);
+	assert!(f32::from_bits(record[0]) > 0.0, "The time should have advanced, but was {}", f32::from_bits(record[0]));
+	assert!(f32::from_bits(record[1]) >= 0.0);
+	assert!(record[2] > 0);
+	assert_eq!(record[2], record[3]);
+}
+
+#[test]
+fn writes_a_reproducible_seed_for_every_iteration_to_the_globals() {
+	let Some(mut app) = ComputeTestApp::new() else {
+		return;
+	};
+	let record = app.add_storage_init(vec![0u32; 64], Binding::SingleBound(0, 0));
+	let render_device = app.app().world().resource::<RenderDevice>().clone();
+	let render_queue = app.app().world().resource::<RenderQueue>().clone();
+	app.buffer_set().add_globals(&render_device, &render_queue, Binding::SingleBound(0, 1)).unwrap();
+	let iteration = app
+		.buffer_set()
+		.add_uniform_init(&render_device, &render_queue, 0u32, BufferUsages::empty(), Binding::SingleBound(0, 5))
+		.unwrap();
+	// With a budget, several iterations run in a frame, and each has to get its own seed.
+	let task = |budget| ComputeTask {
+		label: None,
+		iterations: NonZeroU32::new(64),
+		run_mode: TaskRunMode::Continuous,
+		budget,
+		submission: TaskSubmission::WithFrame,
+		start_after: None,
+		iteration_buffer: None,
+		steps: vec![ComputeStep {
+			schedule: StepSchedule::EveryIteration,
+			run_if: None,
+			action: ComputeAction::RunShader {
+				shader: "shaders/test_globals.wgsl".to_owned(),
+				entry_point: "record_seed".to_owned(),
+				shader_defs: Vec::new(),
+				overrides: Vec::new(),
+				dispatch: DispatchSize::Workgroups { x: 1, y: 1, z: 1 },
+				auto_swap: Vec::new(),
+				skip_if_empty: None,
+				buffer_orientation: Vec::new(),
+				push_constants: None,
+				dynamic_offsets: Vec::new(),
+			},
+		}],
+	};
+	let mut run = |budget, seed| {
+		app.app().world_mut().send_event(StartComputeEvent {
+			sequence: ComputeSequenceId::MAIN,
+			tasks: vec![task(None), task(budget)],
+			iteration_buffer: Some(iteration),
+			seed,
+		});
+		for _ in 0..MAX_FRAMES {
+			app.app().update();
+			if *app.app().world().resource::<ComputeState>() == ComputeState::Finished {
+				break;
+			}
+		}
+		assert_eq!(app.app().world().resource::<ComputeProgress>().seed, iteration_seed(seed, 1, 64));
+		app.read_buffer::<u32>(record)
+	};
+
+	let expected = (0..64).map(|iteration| iteration_seed(42, 1, iteration)).collect::<Vec<_>>();
+	let budgeted = run(Some(FrameBudget { time: Duration::from_secs(1), estimate: Duration::from_millis(1) }), 42);
+	assert_eq!(budgeted, expected);
+	assert_eq!(run(None, 42), expected);
+	let reseeded = run(None, 43);
+	assert_ne!(reseeded, expected);
+	assert_eq!(reseeded[5], iteration_seed(43, 1, 5));
+	assert_ne!(iteration_seed(42, 0, 5), iteration_seed(42, 1, 5));
+}
+
+#[test]
+fn rotates_rings_between_the_dispatches_of_an_iteration() {
+	let Some(mut app) = ComputeTestApp::new() else {
+		return;
+	};
+	let record = app.add_storage_init(vec![0u32; 36], Binding::SingleBound(0, 0));
+	let render_device = app.app().world().resource::<RenderDevice>().clone();
+	let render_queue = app.app().world().resource::<RenderQueue>().clone();
+	app.buffer_set().add_globals(&render_device, &render_queue, Binding::SingleBound(0, 1)).unwrap();
+	let iteration = app
+		.buffer_set()
+		.add_uniform_init(&render_device, &render_queue, 0u32, BufferUsages::empty(), Binding::SingleBound(0, 2))
+		.unwrap();
+	let ring = app.add_storage_init([0u32; 4], Binding::Ring(0, 3, 3));
+	let double = app.add_storage_init([0u32; 4], Binding::Double(0, (6, 7)));
+	assert_eq!(app.buffer_set().ring_slot(ring), Some(0));
+	assert_eq!(app.buffer_set().ring_position(ring), Some(0));
+	let run_ring_shader = |entry_point: &str| ComputeStep {
+		schedule: StepSchedule::EveryIteration,
+		run_if: None,
+		action: ComputeAction::RunShader {
+			shader: "shaders/test_ring.wgsl".to_owned(),
+			entry_point: entry_point.to_owned(),
+			shader_defs: Vec::new(),
+			overrides: Vec::new(),
+			dispatch: DispatchSize::Workgroups { x: 1, y: 1, z: 1 },
+			auto_swap: Vec::new(),
+			skip_if_empty: None,
+			buffer_orientation: Vec::new(),
+			push_constants: None,
+			dynamic_offsets: Vec::new(),
+		},
+	};
+	let step = |action| ComputeStep { schedule: StepSchedule::EveryIteration, run_if: None, action };
+	// The rotation and the swap both happen in the middle of an iteration, and the steps after them have to see both.
+	let tasks = |budget| {
+		vec![
+			ComputeTask {
+				label: None,
+				iterations: NonZeroU32::new(7),
+				run_mode: TaskRunMode::Continuous,
+				budget,
+				submission: TaskSubmission::WithFrame,
+				start_after: None,
+				iteration_buffer: None,
+				steps: vec![
+					run_ring_shader("record_before"),
+					run_ring_shader("stamp"),
+					step(ComputeAction::RotateBuffers { buffer: ring }),
+					run_ring_shader("record_after"),
+					run_ring_shader("advance"),
+					step(ComputeAction::SwapBuffers { buffer: double }),
+				],
+			},
+			ComputeTask {
+				label: None,
+				iterations: NonZeroU32::new(1),
+				run_mode: TaskRunMode::Continuous,
+				budget: None,
+				submission: TaskSubmission::WithFrame,
+				start_after: None,
+				iteration_buffer: None,
+				steps: vec![run_ring_shader("snapshot")],
+			},
+		]
+	};
+	let mut run = |budget| {
+		let start = app.buffer_set().ring_position(ring).unwrap();
+		app.app().world_mut().send_event(StartComputeEvent {
+			sequence: ComputeSequenceId::MAIN,
+			tasks: tasks(budget),
+			iteration_buffer: Some(iteration),
+			seed: 0,
+		});
+		for _ in 0..MAX_FRAMES {
+			app.app().update();
+			if *app.app().world().resource::<ComputeState>() == ComputeState::Finished {
+				break;
+			}
+		}
+		assert_eq!(app.buffer_set().ring_position(ring), Some((start + 7) % 3));
+		let record = app.read_buffer::<u32>(record);
+		for iteration in 0..7 {
+			let (before, after) = (record[iteration as usize * 2], record[iteration as usize * 2 + 1]);
+			assert_eq!(before, (start + iteration) % 3, "before rotating in iteration {}", iteration);
+			assert_eq!(after, (start + iteration + 1) % 3, "after rotating in iteration {}", iteration);
+		}
+		// Each buffer of the ring holds the stamp of the last iteration it was current in.
+		let stamps = (0..3).map(|buffer| (0..7).rev().find(|iteration| (start + iteration) % 3 == buffer).unwrap() + 1);
+		assert_eq!(record[32..35], stamps.collect::<Vec<_>>());
+		record[35]
+	};
+
+	assert_eq!(run(Some(FrameBudget { time: Duration::from_secs(1), estimate: Duration::from_millis(1) })), 7);
+	assert_eq!(run(None), 14);
+	assert_eq!(app.buffer_set().ring_position(ring), Some(2));
+}
+
+#[test]
+fn gives_each_ring_the_lowest_free_slot() {
+	let Some(mut app) = ComputeTestApp::new() else {
+		return;
+	};
+	let rings = (0..4).map(|group| app.add_storage_init([0u32; 4], Binding::Ring(group, 0, 2))).collect::<Vec<_>>();
+	for (slot, &ring) in rings.iter().enumerate() {
+		assert_eq!(app.buffer_set().ring_slot(ring), Some(slot as u32));
+	}
+	let single = app.add_storage_init([0u32; 4], Binding::SingleBound(4, 0));
+	assert_eq!(app.buffer_set().ring_slot(single), None);
+	let world = app.app().world_mut();
+	world.resource_scope(|world, mut images: Mut<Assets<Image>>| {
+		world.resource_mut::<ShaderBufferSet>().delete_buffer(rings[1], &mut images).unwrap();
+	});
+	let replacement = app.add_storage_init([0u32; 4], Binding::Ring(1, 0, 3));
+	assert_eq!(app.buffer_set().ring_slot(replacement), Some(1));
+	let result = catch_unwind(AssertUnwindSafe(|| app.add_storage_init([0u32; 4], Binding::Ring(4, 1, 2))));
+	let message = result.unwrap_err().downcast::<String>().unwrap();
+	assert!(message.contains("already 4 rings"), "{}", message);
+}
+
+#[test]
+#[should_panic(expected = "isn't a ring")]
+fn panics_when_a_step_rotates_a_buffer_that_isnt_a_ring() {
+	let Some(mut app) = ComputeTestApp::new() else {
+		panic!("isn't a ring, but there's no GPU to check it on");
+	};
+	let double = app.add_storage_init([0u32; 4], Binding::Double(0, (0, 1)));
+	app.run_until_complete(vec![ComputeTask {
+		label: None,
+		iterations: NonZeroU32::new(1),
+		run_mode: TaskRunMode::Continuous,
+		budget: None,
+		submission: TaskSubmission::WithFrame,
+		start_after: None,
+		iteration_buffer: None,
+		steps: vec![ComputeStep {
+			schedule: StepSchedule::EveryIteration,
+			run_if: None,
+			action: ComputeAction::RotateBuffers { buffer: double },
+		}],
+	}]);
+}
+
+#[test]
+fn writes_each_tasks_own_iteration_counts_to_its_iteration_buffer() {
+	let Some(mut app) = ComputeTestApp::new() else {
+		return;
+	};
+	let record = app.add_storage_init(vec![0u32; 20], Binding::SingleBound(0, 0));
+	let render_device = app.app().world().resource::<RenderDevice>().clone();
+	let render_queue = app.app().world().resource::<RenderQueue>().clone();
+	let local = app
+		.buffer_set()
+		.add_uniform_init(&render_device, &render_queue, 0u32, BufferUsages::empty(), Binding::SingleBound(0, 1))
+		.unwrap();
+	let counts = app
+		.buffer_set()
+		.add_uniform_init(
+			&render_device,
+			&render_queue,
+			ComputeIterationCounts::default(),
+			BufferUsages::empty(),
+			Binding::SingleBound(0, 2),
+		)
+		.unwrap();
+	let task = |label: &str, iterations, budget, iteration_buffer, entry_point: &str| ComputeTask {
+		label: Some(label.to_owned()),
+		iterations: NonZeroU32::new(iterations),
+		run_mode: TaskRunMode::Continuous,
+		budget,
+		submission: TaskSubmission::WithFrame,
+		start_after: None,
+		iteration_buffer: Some(iteration_buffer),
+		steps: vec![ComputeStep {
+			schedule: StepSchedule::EveryIteration,
+			run_if: None,
+			action: ComputeAction::RunShader {
+				shader: "shaders/test_task_iterations.wgsl".to_owned(),
+				entry_point: entry_point.to_owned(),
+				shader_defs: Vec::new(),
+				overrides: Vec::new(),
+				dispatch: DispatchSize::Workgroups { x: 1, y: 1, z: 1 },
+				auto_swap: Vec::new(),
+				skip_if_empty: None,
+				buffer_orientation: Vec::new(),
+				push_constants: None,
+				dynamic_offsets: Vec::new(),
+			},
+		}],
+	};
+	// With a budget, the second task runs several iterations a frame, and each has to get its own counts.
+	let budget = FrameBudget { time: Duration::from_secs(1), estimate: Duration::from_millis(1) };
+	app.run_until_complete(vec![
+		task("init", 3, None, TaskIterationBuffer::Local(local), "record_local"),
+		task("update", 8, Some(budget), TaskIterationBuffer::Counts(counts), "record_counts"),
+	]);
+
+	// Checked before the record is read back, which runs a compute sequence of its own.
+	let state = app.app().world().resource::<ComputeIterationState>();
+	assert_eq!(state.global, 11);
+	assert_eq!(
+		state.task("update"),
+		Some(&TaskIterationState { label: Some("update".to_owned()), iterations: 8, total_iterations: Some(8) })
+	);
+	assert_eq!(state.tasks[0].iterations, 3);
+	let mut expected = vec![1, 2, 3, 0];
+	expected.extend((0..8).flat_map(|iteration| [iteration + 1, 3 + iteration + 1]));
+	app.assert_buffer_eq(record, &expected);
+}
+
+#[test]
+fn writes_the_size_of_the_tracked_texture_to_the_size_uniform() {
+	let Some(mut app) = ComputeTestApp::new() else {
+		return;
+	};
+	let record = app.add_storage_init(vec![u32::MAX; 4], Binding::SingleBound(0, 0));
+	let render_device = app.app().world().resource::<RenderDevice>().clone();
+	let render_queue = app.app().world().resource::<RenderQueue>().clone();
+	let texture = app.app().world_mut().resource_scope(|world, mut images: Mut<Assets<Image>>| {
+		world
+			.resource_mut::<ShaderBufferSet>()
+			.add_texture_fill(
+				&mut images,
+				8,
+				4,
+				TextureFormat::Rgba8Unorm,
+				&[0; 4],
+				StorageTextureAccess::WriteOnly,
+				Binding::SingleBound(1, 0),
+			)
+			.unwrap()
+	});
+	app.buffer_set().add_size_uniform(&render_device, &render_queue, Some(texture), Binding::SingleBound(0, 2)).unwrap();
+	let result = catch_unwind(AssertUnwindSafe(|| {
+		app.buffer_set().add_size_uniform(&render_device, &render_queue, None, Binding::SingleBound(2, 0)).unwrap();
+	}));
+	assert!(result.is_err(), "Adding a second size uniform should panic");
+
+	app.run_until_complete(vec![ComputeTask {
+		label: None,
+		iterations: NonZeroU32::new(1),
+		run_mode: TaskRunMode::Continuous,
+		budget: None,
+		submission: TaskSubmission::WithFrame,
+		start_after: None,
+		iteration_buffer: None,
+		steps: vec![ComputeStep {
+			schedule: StepSchedule::EveryIteration,
+			run_if: None,
+			action: ComputeAction::RunShader {
+				shader: "shaders/test_globals.wgsl".to_owned(),
+				entry_point: "record_size".to_owned(),
+				shader_defs: Vec::new(),
+				overrides: Vec::new(),
+				dispatch: DispatchSize::Workgroups { x: 1, y: 1, z: 1 },
+				auto_swap: Vec::new(),
+				skip_if_empty: None,
+				buffer_orientation: Vec::new(),
+				push_constants: None,
+				dynamic_offsets: Vec::new(),
+			},
+		}],
+	}]);
+	// The test app has no window, so only the texture has a size.
+	assert_eq!(app.read_buffer::<u32>(record), vec![0, 0, 8, 4]);
+}
+
+#[test]
+fn writes_the_mouse_buttons_to_the_cursor_uniform() {
+	let Some(mut app) = ComputeTestApp::new() else {
+		return;
+	};
+	let record = app.add_storage_init(vec![u32::MAX; 4], Binding::SingleBound(0, 0));
+	let render_device = app.app().world().resource::<RenderDevice>().clone();
+	let render_queue = app.app().world().resource::<RenderQueue>().clone();
+	let texture = app.app().world_mut().resource_scope(|world, mut images: Mut<Assets<Image>>| {
+		world
+			.resource_mut::<ShaderBufferSet>()
+			.add_texture_fill(
+				&mut images,
+				8,
+				4,
+				TextureFormat::Rgba8Unorm,
+				&[0; 4],
+				StorageTextureAccess::WriteOnly,
+				Binding::SingleBound(1, 0),
+			)
+			.unwrap()
+	});
+	let display = Rect::from_center_size(Vec2::ZERO, Vec2::new(32.0, 16.0));
+	app
+		.buffer_set()
+		.add_cursor_uniform(&render_device, &render_queue, texture, display, Binding::SingleBound(0, 3))
+		.unwrap();
+	app.buffer_set().set_cursor_display(display);
+	app.app().world_mut().resource_mut::<ButtonInput<MouseButton>>().press(MouseButton::Left);
+
+	app.run_until_complete(vec![ComputeTask {
+		label: None,
+		iterations: NonZeroU32::new(1),
+		run_mode: TaskRunMode::Continuous,
+		budget: None,
+		submission: TaskSubmission::WithFrame,
+		start_after: None,
+		iteration_buffer: None,
+		steps: vec![ComputeStep {
+			schedule: StepSchedule::EveryIteration,
+			run_if: None,
+			action: ComputeAction::RunShader {
+				shader: "shaders/test_globals.wgsl".to_owned(),
+				entry_point: "record_cursor".to_owned(),
+				shader_defs: Vec::new(),
+				overrides: Vec::new(),
+				dispatch: DispatchSize::Workgroups { x: 1, y: 1, z: 1 },
+				auto_swap: Vec::new(),
+				skip_if_empty: None,
+				buffer_orientation: Vec::new(),
+				push_constants: None,
+				dynamic_offsets: Vec::new(),
+			},
+		}],
+	}]);
+	// The test app has no window, so the cursor is never over the texture.
+	assert_eq!(app.read_buffer::<u32>(record), vec![0, 0, ComputeCursor::LEFT, 0]);
+}
+
+#[test]
+fn writes_the_matrices_of_the_marked_camera_to_the_camera_uniform() {
+	let Some(mut app) = ComputeTestApp::new() else {
+		return;
+	};
+	let record = app.add_storage_init(vec![0.0f32; 4], Binding::SingleBound(0, 0));
+	let render_device = app.app().world().resource::<RenderDevice>().clone();
+	let render_queue = app.app().world().resource::<RenderQueue>().clone();
+	app.buffer_set().add_camera_uniform(&render_device, &render_queue, Binding::SingleBound(0, 4)).unwrap();
+	// There's no window in the test app, so the camera renders to an image instead.
+	let mut target = Image::new_fill(
+		Extent3d { width: 4, height: 4, depth_or_array_layers: 1 },
+		TextureDimension::D2,
+		&[0; 4],
+		TextureFormat::Bgra8UnormSrgb,
+		RenderAssetUsages::default(),
+	);
+	target.texture_descriptor.usage |= TextureUsages::RENDER_ATTACHMENT;
+	let target = app.app().world_mut().resource_mut::<Assets<Image>>().add(target);
+	app.app().world_mut().spawn((
+		Camera3d::default(),
+		Camera { target: RenderTarget::Image(target), ..Default::default() },
+		Transform::from_xyz(1.0, 2.0, 3.0).looking_at(Vec3::ZERO, Vec3::Y),
+		ComputeCamera,
+	));
+
+	app.run_until_complete(vec![ComputeTask {
+		label: None,
+		iterations: NonZeroU32::new(1),
+		run_mode: TaskRunMode::Continuous,
+		budget: None,
+		submission: TaskSubmission::WithFrame,
+		start_after: None,
+		iteration_buffer: None,
+		steps: vec![ComputeStep {
+			schedule: StepSchedule::EveryIteration,
+			run_if: None,
+			action: ComputeAction::RunShader {
+				shader: "shaders/test_globals.wgsl".to_owned(),
+				entry_point: "record_camera".to_owned(),
+				shader_defs: Vec::new(),
+				overrides: Vec::new(),
+				dispatch: DispatchSize::Workgroups { x: 1, y: 1, z: 1 },
+				auto_swap: Vec::new(),
+				skip_if_empty: None,
+				buffer_orientation: Vec::new(),
+				push_constants: None,
+				dynamic_offsets: Vec::new(),
+			},
+		}],
+	}]);
+	assert_buffer_approx_eq(&app.read_buffer::<f32>(record), &[1.0, 2.0, 3.0, 0.0], 1e-5);
+}
+
+#[test]
+fn writes_texture_readbacks_back_to_images() {
+	let Some(mut app) = ComputeTestApp::new() else {
+		return;
+	};
+	app
+		.app()
+		.init_resource::<Errors>()
+		.add_systems(Update, |mut events: EventReader<ComputeErrorEvent>, mut errors: ResMut<Errors>| {
+			errors.0.extend(events.read().cloned())
+		});
+	// A row of 5 texels is 20 bytes, far short of the 256 bytes each row is padded to in the copy.
+	let world = app.app().world_mut();
+	let texture = world.resource_scope(|world, mut images: Mut<Assets<Image>>| {
+		world
+			.resource_mut::<ShaderBufferSet>()
+			.add_texture_fill(
+				&mut images,
+				5,
+				3,
+				TextureFormat::R32Float,
+				&2.5f32.to_ne_bytes(),
+				StorageTextureAccess::ReadOnly,
+				Binding::SingleBound(0, 0),
+			)
+			.unwrap()
+	});
+	let mut images = world.resource_mut::<Assets<Image>>();
+	let target = images.add(Image::new_fill(
+		Extent3d { width: 1, height: 1, depth_or_array_layers: 1 },
+		TextureDimension::D2,
+		&0.0f32.to_ne_bytes(),
+		TextureFormat::R32Float,
+		RenderAssetUsages::default(),
+	));
+	let created = images.reserve_handle();
+	let mismatched = images.add(Image::default());
+	for target in [target.clone(), created.clone(), mismatched.clone()] {
+		world.send_event(WriteBackToImageEvent { buffer: texture, target });
+	}
+
+	app.run_until_complete(vec![ComputeTask {
+		label: None,
+		iterations: NonZeroU32::new(2),
+		run_mode: TaskRunMode::Continuous,
+		budget: None,
+		submission: TaskSubmission::WithFrame,
+		start_after: None,
+		iteration_buffer: None,
+		steps: vec![ComputeStep {
+			schedule: StepSchedule::EveryIteration,
+			run_if: None,
+			action: ComputeAction::CopyBuffer { src: texture, offset: 0, size: None },
+		}],
+	}]);
+	app.app().update();
+	let world = app.app().world();
+	let images = world.resource::<Assets<Image>>();
+	for target in [&target, &created] {
+		let image = images.get(target).unwrap();
+		assert_eq!(image.texture_descriptor.size, Extent3d { width: 5, height: 3, depth_or_array_layers: 1 });
+		assert_eq!(bytemuck::cast_slice::<u8, f32>(&image.data), &[2.5; 15]);
+	}
+	assert!(matches!(
+		world.resource::<Errors>().0.as_slice(),
+		[ComputeErrorEvent::ImageFormatMismatch { buffer_format: TextureFormat::R32Float, .. }]
+	));
+}
+
+#[test]
+fn writes_images_into_part_of_a_texture() {
+	let Some(mut app) = ComputeTestApp::new() else {
+		return;
+	};
+	let world = app.app().world_mut();
+	let (single, double) = world.resource_scope(|world, mut images: Mut<Assets<Image>>| {
+		let mut buffers = world.resource_mut::<ShaderBufferSet>();
+		let mut add = |binding| {
+			buffers
+				.add_texture_fill(
+					&mut images,
+					4,
+					3,
+					TextureFormat::R32Float,
+					&1.0f32.to_ne_bytes(),
+					StorageTextureAccess::ReadOnly,
+					binding,
+				)
+				.unwrap()
+		};
+		(add(Binding::SingleBound(0, 0)), add(Binding::Double(0, (1, 2))))
+	});
+	let decal = Image::new(
+		Extent3d { width: 2, height: 1, depth_or_array_layers: 1 },
+		TextureDimension::D2,
+		bytemuck::cast_slice(&[5.0f32, 6.0]).to_vec(),
+		TextureFormat::R32Float,
+		RenderAssetUsages::default(),
+	);
+	let error =
+		app.buffer_set().write_texture_from_image(single, &decal, UVec2::new(3, 0), TextureWriteTarget::Both).unwrap_err();
+	assert!(matches!(error, ShaderBufferError::RegionOutOfBounds { origin: UVec2 { x: 3, y: 0 }, .. }), "{}", error);
+	let mut buffers = app.buffer_set();
+	buffers.write_texture_from_image(single, &decal, UVec2::new(1, 2), TextureWriteTarget::Both).unwrap();
+	buffers.write_texture_from_image(double, &decal, UVec2::ZERO, TextureWriteTarget::Back).unwrap();
+
+	app.assert_buffer_eq(single, &[1.0f32, 1.0, 1.0, 1.0, 1.0, 1.0, 1.0, 1.0, 1.0, 5.0, 6.0, 1.0]);
+	app.assert_texture_pixel(single, 2, 2, &[6.0f32], 0.0);
+	// Only the back buffer was written, and the front buffer is the one that's copied.
+	app.assert_buffer_all(double, |value: f32| value == 1.0);
+}
+
+#[test]
+fn clears_buffers_and_textures_in_steps() {
+	let Some(mut app) = ComputeTestApp::new() else {
+		return;
+	};
+	let buffer = app.add_storage_init(vec![1.0f32; 8], Binding::SingleBound(0, 0));
+	let swapped = app.add_storage_init(vec![1.0f32; 4], Binding::Double(1, (0, 1)));
+	let both = app.add_storage_init(vec![1.0f32; 4], Binding::Double(1, (2, 3)));
+	let before_swap = app.add_storage_init(vec![9.0f32; 4], Binding::SingleUnbound);
+	let after_swap = app.add_storage_init(vec![9.0f32; 4], Binding::SingleUnbound);
+	let both_front = app.add_storage_init(vec![9.0f32; 4], Binding::SingleUnbound);
+	let (counts, colors, volume) = app.app().world_mut().resource_scope(|world, mut images: Mut<Assets<Image>>| {
+		let mut buffers = world.resource_mut::<ShaderBufferSet>();
+		let access = StorageTextureAccess::ReadOnly;
+		(
+			buffers
+				.add_texture_fill(
+					&mut images,
+					3,
+					2,
+					TextureFormat::R32Uint,
+					&7u32.to_le_bytes(),
+					access,
+					Binding::SingleBound(2, 0),
+				)
+				.unwrap(),
+			buffers
+				.add_texture_fill(&mut images, 3, 2, TextureFormat::Rgba8Unorm, &[9; 4], access, Binding::Double(2, (1, 2)))
+				.unwrap(),
+			buffers
+				.add_texture_fill_3d(
+					&mut images,
+					3,
+					2,
+					2,
+					TextureFormat::R32Float,
+					&1.0f32.to_le_bytes(),
+					access,
+					Binding::SingleBound(2, 3),
+				)
+				.unwrap(),
+		)
+	});
+	let step = |action| ComputeStep { schedule: StepSchedule::EveryIteration, run_if: None, action };
+	app.run_until_complete(vec![ComputeTask {
+		label: None,
+		iterations: NonZeroU32::new(1),
+		run_mode: TaskRunMode::Continuous,
+		budget: None,
+		submission: TaskSubmission::WithFrame,
+		start_after: None,
+		iteration_buffer: None,
+		steps: vec![
+			step(ComputeAction::ClearBuffer { buffer, offset: 8, size: Some(16), halves: ClearHalves::Back }),
+			step(ComputeAction::ClearBuffer { buffer: swapped, offset: 0, size: None, halves: ClearHalves::Back }),
+			copy_step(swapped, before_swap, 0, 0, None),
+			step(ComputeAction::SwapBuffers { buffer: swapped }),
+			step(ComputeAction::ClearBuffer { buffer: both, offset: 0, size: None, halves: ClearHalves::Both }),
+			copy_step(both, both_front, 0, 0, None),
+			step(ComputeAction::ClearTexture {
+				texture: counts,
+				value: [4_000_000_000.0, 0.0, 0.0, 0.0],
+				halves: ClearHalves::Back,
+			}),
+			step(ComputeAction::ClearTexture { texture: colors, value: [1.0, 0.5, -1.0, 1.0], halves: ClearHalves::Both }),
+			step(ComputeAction::ClearTexture { texture: volume, value: [2.5, 0.0, 0.0, 0.0], halves: ClearHalves::Back }),
+		],
+	}]);
+	app.assert_buffer_eq(buffer, &[1.0f32, 1.0, 0.0, 0.0, 0.0, 0.0, 1.0, 1.0]);
+	// Only the back buffer was cleared, so the front is untouched until they're swapped.
+	app.assert_buffer_eq(before_swap, &[1.0f32; 4]);
+	app.assert_buffer_eq(both_front, &[0.0f32; 4]);
+	app.assert_buffer_eq(counts, &[4_000_000_000u32; 6]);
+	app.assert_buffer_eq(colors, &[[255u8, 128, 0, 255]; 6]);
+	app.assert_buffer_eq(volume, &[2.5f32; 12]);
+
+	// The swap brings the cleared buffer to the front, and without one, the front texture is left as it was.
+	app.run_until_complete(vec![ComputeTask {
+		label: None,
+		iterations: NonZeroU32::new(1),
+		run_mode: TaskRunMode::Continuous,
+		budget: None,
+		submission: TaskSubmission::WithFrame,
+		start_after: None,
+		iteration_buffer: None,
+		steps: vec![
+			copy_step(swapped, after_swap, 0, 0, None),
+			step(ComputeAction::ClearTexture { texture: colors, value: [0.0; 4], halves: ClearHalves::Back }),
+		],
+	}]);
+	app.assert_buffer_eq(after_swap, &[0.0f32; 4]);
+	app.assert_buffer_eq(colors, &[[255u8, 128, 0, 255]; 6]);
+}
+
+#[test]
+#[should_panic(expected = "the offset and size must be multiples of 4")]
+fn panics_when_a_cleared_range_isnt_aligned() {
+	let Some(mut app) = ComputeTestApp::new() else {
+		panic!("the offset and size must be multiples of 4, but there's no GPU to check it on");
+	};
+	let buffer = app.add_storage_init(vec![1.0f32; 8], Binding::SingleBound(0, 0));
+	app.run_until_complete(vec![ComputeTask {
+		label: None,
+		iterations: NonZeroU32::new(1),
+		run_mode: TaskRunMode::Continuous,
+		budget: None,
+		submission: TaskSubmission::WithFrame,
+		start_after: None,
+		iteration_buffer: None,
+		steps: vec![ComputeStep {
+			schedule: StepSchedule::EveryIteration,
+			run_if: None,
+			action: ComputeAction::ClearBuffer { buffer, offset: 2, size: None, halves: ClearHalves::Back },
+		}],
+	}]);
+}
+
+#[test]
+fn writes_regions_of_a_texture_from_the_cpu() {
+	let Some(mut app) = ComputeTestApp::new() else {
+		return;
+	};
+	let texture = app.app().world_mut().resource_scope(|world, mut images: Mut<Assets<Image>>| {
+		world
+			.resource_mut::<ShaderBufferSet>()
+			.add_texture_fill(
+				&mut images,
+				3,
+				2,
+				TextureFormat::Rgba8Unorm,
+				&[0; 4],
+				StorageTextureAccess::ReadOnly,
+				Binding::Double(0, (0, 1)),
+			)
+			.unwrap()
+	});
+	let tile: [[u8; 4]; 4] = [[1, 2, 3, 4], [5, 6, 7, 8], [9, 10, 11, 12], [13, 14, 15, 16]];
+	let error = app
+		.buffer_set()
+		.write_texture_region(
+			texture,
+			UVec2::new(1, 0),
+			UVec2::new(2, 2),
+			&bytemuck::cast_slice(&tile)[4..],
+			TextureWriteTarget::Back,
+		)
+		.unwrap_err();
+	assert!(matches!(error, ShaderBufferError::RegionSizeMismatch { size: 12, expected: 16, .. }), "{}", error);
+	app
+		.buffer_set()
+		.write_texture_region(
+			texture,
+			UVec2::new(1, 0),
+			UVec2::new(2, 2),
+			bytemuck::cast_slice(&tile),
+			TextureWriteTarget::Back,
+		)
+		.unwrap();
+
+	app.run_until_complete(vec![ComputeTask {
+		label: None,
+		iterations: NonZeroU32::new(1),
+		run_mode: TaskRunMode::Continuous,
+		budget: None,
+		submission: TaskSubmission::WithFrame,
+		start_after: None,
+		iteration_buffer: None,
+		steps: vec![ComputeStep {
+			schedule: StepSchedule::EveryIteration,
+			run_if: None,
+			action: ComputeAction::SwapBuffers { buffer: texture },
+		}],
+	}]);
+	app.assert_buffer_eq(texture, &[[0; 4], tile[0], tile[1], [0; 4], tile[2], tile[3]]);
+	app.assert_texture_pixel(texture, 2, 1, &tile[3], 0);
+	let result = catch_unwind(AssertUnwindSafe(|| app.assert_texture_pixel(texture, 3, 0, &[0u8; 4], 0)));
+	assert!(result.is_err(), "Checking a pixel out of bounds should panic");
+}
+
+#[test]
+fn lists_the_first_mismatches_when_buffer_assertions_fail() {
+	let Some(mut app) = ComputeTestApp::new() else {
+		return;
+	};
+	let data = app.add_storage_init((0..40).collect::<Vec<u32>>(), Binding::SingleBound(0, 0));
+	let message = |result: Result<(), Box<dyn std::any::Any + Send>>| {
+		*result.expect_err("The assertion should fail").downcast::<String>().expect("The panic message should be a string")
+	};
+
+	let failure = message(catch_unwind(AssertUnwindSafe(|| app.assert_buffer_eq(data, &[0u32; 40]))));
+	assert!(failure.starts_with("39 of 40 elements"), "{}", failure);
+	assert!(failure.contains("  [1]: got 1, expected 0"), "{}", failure);
+	assert!(failure.contains(&format!("  [{}]: got", MAX_REPORTED_MISMATCHES)), "{}", failure);
+	assert!(!failure.contains(&format!("  [{}]: got", MAX_REPORTED_MISMATCHES + 1)), "{}", failure);
+	assert!(failure.ends_with(&format!("... and {} more", 39 - MAX_REPORTED_MISMATCHES)), "{}", failure);
+
+	let failure = message(catch_unwind(AssertUnwindSafe(|| app.assert_buffer_all(data, |value: u32| value != 7))));
+	assert!(failure.starts_with("1 of 40 elements"), "{}", failure);
+	assert!(failure.ends_with("  [7]: got 7"), "{}", failure);
+	app.assert_buffer_all(data, |value: u32| value < 40);
+}
+
+#[test]
+fn reads_back_visualized_buffers_without_a_sequence() {
+	let Some(mut app) = ComputeTestApp::new() else {
+		return;
+	};
+	// The test app is already finished, so plugins can't be added the usual way.
+	ComputeDebugVisualizationPlugin { enabled: true }.build(app.app());
+	let grid = app.add_storage_init(vec![0.0f32, 1.0, 2.0, 3.0], Binding::SingleBound(0, 0));
+	let world = app.app().world_mut();
+	let image = world.resource_scope(|world, mut images: Mut<Assets<Image>>| {
+		let mut visualizations = world.resource_mut::<ComputeDebugVisualizations>();
+		visualizations.rate = f32::INFINITY;
+		visualizations.add(grid, VisualizeBuffer::Heatmap { width: 2, height: 2 }, &mut images).unwrap()
+	});
+	for _ in 0..100 {
+		app.app().update();
+		if app.app().world().resource::<ComputeDebugVisualizations>().latest(grid).is_some() {
+			break;
+		}
+	}
+	let data =
+		app.app().world().resource::<ComputeDebugVisualizations>().latest(grid).map(bytemuck::cast_slice::<u8, f32>);
+	assert_eq!(data, Some(&[0.0, 1.0, 2.0, 3.0][..]));
+	let pixels = &app.app().world().resource::<Assets<Image>>().get(&image).unwrap().data;
+	assert_eq!(pixels, &[0, 0, 0, 255, 255, 0, 0, 255, 255, 255, 0, 255, 255, 255, 255, 255]);
+}
+
+#[test]
+fn exposes_raw_buffers_and_layout_entries() {
+	let Some(mut app) = ComputeTestApp::new() else {
+		return;
+	};
+	let double = app.add_storage_init(vec![0.0f32; 16], Binding::Double(0, (1, 2)));
+	let entries = app.buffer_set().layout_entry_for(double).unwrap();
+	assert_eq!(entries.iter().map(|entry| entry.binding).collect::<Vec<_>>(), vec![1, 2]);
+
+	app.app().update();
+	let render_world = app.app().sub_app(RenderApp).world();
+	let buffers = render_world.resource::<ShaderBufferSet>();
+	let buffer = render_world.resource::<ShaderBufferRenderSet>().wgpu_buffer(double, buffers).unwrap();
+	assert_eq!(buffer.size(), 64);
+}
+
+#[test]
+fn reads_copy_buffers_back_as_shader_types() {
+	let Some(mut app) = ComputeTestApp::new() else {
+		return;
+	};
+	// A vec3 takes up 16 bytes in an array, so the second element starts after a float of padding.
+	let data = app.add_storage_init(vec![Vec3::new(1.0, 2.0, 3.0), Vec3::new(4.0, 5.0, 6.0)], Binding::SingleBound(0, 0));
+	let other = app.add_storage_init(vec![0.0f32; 4], Binding::SingleBound(0, 1));
+	app.app().world_mut().send_event(StartComputeEvent {
+		sequence: ComputeSequenceId::MAIN,
+		tasks: vec![ComputeTask {
+			label: None,
+			iterations: None,
+			run_mode: TaskRunMode::Continuous,
+			budget: None,
+			submission: TaskSubmission::WithFrame,
+			start_after: None,
+			iteration_buffer: None,
+			steps: vec![ComputeStep {
+				schedule: StepSchedule::EveryIteration,
+				run_if: None,
+				action: ComputeAction::CopyBuffer { src: data, offset: 0, size: None },
+			}],
+		}],
+		iteration_buffer: None,
+		seed: 0,
+	});
+	for _ in 0..MAX_FRAMES {
+		app.app().update();
+		if app.app().world().resource::<ComputeProgress>().iterations >= 2 {
+			break;
+		}
+	}
+
+	let render_world = app.app().sub_app(RenderApp).world();
+	let buffers = render_world.resource::<ShaderBufferSet>();
+	let device = render_world.resource::<RenderDevice>();
+	let render_buffers = render_world.resource::<ShaderBufferRenderSet>();
+	let main = ComputeSequenceId::MAIN;
+	assert_eq!(
+		render_buffers.read_copy_buffer_as_vec::<Vec3>(main, data, buffers, device),
+		Ok(Some(vec![Vec3::new(1.0, 2.0, 3.0), Vec3::new(4.0, 5.0, 6.0)]))
+	);
+	assert_eq!(
+		render_buffers.read_copy_buffer_as::<[Vec4; 2]>(main, data, buffers, device),
+		Ok(Some([Vec4::new(1.0, 2.0, 3.0, 0.0), Vec4::new(4.0, 5.0, 6.0, 0.0)]))
+	);
+
+	// Types that don't fit the bytes are reported with both sizes.
+	let error = render_buffers.read_copy_buffer_as::<Vec4>(main, data, buffers, device).unwrap_err();
+	assert!(
+		matches!(error, ShaderBufferError::ReadbackSizeMismatch { size: 32, expected: 16, array: false, .. }),
+		"{}",
+		error
+	);
+	let error = render_buffers.read_copy_buffer_as_vec::<[f32; 3]>(main, data, buffers, device).unwrap_err();
+	assert!(
+		matches!(error, ShaderBufferError::ReadbackSizeMismatch { size: 32, expected: 12, array: true, .. }),
+		"{}",
+		error
+	);
+	let error = render_buffers.read_copy_buffer_as_vec::<f32>(main, other, buffers, device).unwrap_err();
+	assert!(matches!(error, ShaderBufferError::NoCopyBuffer { .. }), "{}", error);
+	app.app().world_mut().send_event(StopComputeEvent(ComputeSequenceId::MAIN));
+	app.app().update();
+}
+
+#[test]
+fn stays_idle_until_a_buffer_is_added() {
+	let Some(mut app) = ComputeTestApp::new() else {
+		return;
+	};
+	app.app().update();
+	let extracted_at = |app: &mut ComputeTestApp| {
+		app.app().sub_app(RenderApp).world().get_resource_change_ticks::<ShaderBufferSet>().unwrap().changed
+	};
+	let first = extracted_at(&mut app);
+	for _ in 0..5 {
+		app.app().update();
+	}
+	assert_eq!(extracted_at(&mut app), first, "An empty, unchanged buffer set shouldn't be extracted again");
+	let render_world = app.app().sub_app(RenderApp).world();
+	assert!(render_world.resource::<RenderGraph>().get_node_state(ComputeLabel).is_err());
+
+	app.add_storage_init(vec![1.0f32; 4], Binding::SingleBound(0, 0));
+	app.app().update();
+	assert_ne!(extracted_at(&mut app), first);
+	assert!(!app.app().sub_app(RenderApp).world().resource::<ShaderBufferSet>().is_empty());
+}
+
+#[test]
+fn runs_without_a_camera_driver_in_the_render_graph() {
+	let Some(mut app) = ComputeTestApp::new() else {
+		return;
+	};
+	let mut render_graph = app.app().sub_app_mut(RenderApp).world_mut().resource_mut::<RenderGraph>();
+	render_graph.remove_node(CameraDriverLabel).unwrap();
+	let data = app.add_storage_init(vec![1.0f32; 16], Binding::SingleBound(0, 0));
+	app.run_until_complete(vec![ComputeTask {
+		label: None,
+		iterations: NonZeroU32::new(3),
+		run_mode: TaskRunMode::Continuous,
+		budget: None,
+		submission: TaskSubmission::WithFrame,
+		start_after: None,
+		iteration_buffer: None,
+		steps: vec![run_shader("increment")],
+	}]);
+	app.assert_buffer_eq(data, &[4.0f32; 16]);
+	let render_world = app.app().sub_app(RenderApp).world();
+	assert!(render_world.resource::<RenderGraph>().get_node_state(ComputeLabel).is_ok());
+}
+
+#[test]
+fn tags_which_world_a_buffer_set_is_in() {
+	let Some(mut app) = ComputeTestApp::new() else {
+		return;
+	};
+	let data = app.add_storage_init(vec![0.0f32; 16], Binding::SingleBound(0, 0));
+	assert_eq!(app.buffer_set().world(), ComputeWorld::Main);
+	app.app().update();
+	let render_world = app.app().sub_app_mut(RenderApp).world_mut();
+	assert_eq!(render_world.resource::<ShaderBufferSet>().world(), ComputeWorld::Render);
+	if cfg!(debug_assertions) {
+		// Changes to the render world's copy would be lost at the next extract.
+		let mut render_buffers = render_world.resource::<ShaderBufferSet>().clone();
+		let result = catch_unwind(AssertUnwindSafe(|| render_buffers.name_buffer(data, "data")));
+		assert!(result.is_err(), "Naming a buffer in the render world's copy should panic");
+		let main_buffers = app.buffer_set().clone();
+		let render_world = app.app().sub_app(RenderApp).world();
+		let result = catch_unwind(AssertUnwindSafe(|| {
+			render_world.resource::<ShaderBufferRenderSet>().wgpu_buffer(data, &main_buffers)
+		}));
+		assert!(result.is_err(), "Passing the main world's buffer set to the render set should panic");
+	}
+}
+
+#[test]
+fn ignores_start_events_when_compute_is_unsupported() {
+	let Some(mut app) = ComputeTestApp::new() else {
+		return;
+	};
+	let buffer = app.add_storage_init(vec![1.0f32, 2.0, 3.0, 4.0], Binding::SingleBound(0, 0));
+	let world = app.app().world_mut();
+	world.insert_resource(ComputeState::Unsupported);
+	world.send_event(StartComputeEvent {
+		sequence: ComputeSequenceId::MAIN,
+		tasks: vec![ComputeTask {
+			label: None,
+			iterations: NonZeroU32::new(1),
+			run_mode: TaskRunMode::Continuous,
+			budget: None,
+			submission: TaskSubmission::WithFrame,
+			start_after: None,
+			iteration_buffer: None,
+			steps: vec![run_shader("increment")],
+		}],
+		iteration_buffer: None,
+		seed: 0,
+	});
+	for _ in 0..10 {
+		app.app().update();
+	}
+	assert_eq!(*app.app().world().resource::<ComputeState>(), ComputeState::Unsupported);
+	app.app().world_mut().insert_resource(ComputeState::Idle);
+	assert_eq!(app.read_buffer::<f32>(buffer), vec![1.0, 2.0, 3.0, 4.0]);
+}
+
+#[test]
+fn reports_the_capabilities_of_the_device_in_both_worlds() {
+	let Some(mut app) = ComputeTestApp::new() else {
+		return;
+	};
+	let world = app.app().world();
+	let capabilities = world.resource::<ComputeCapabilities>().clone();
+	let device = world.resource::<RenderDevice>();
+	assert!(capabilities.compute_shaders);
+	assert_eq!(capabilities.texture_binding_arrays, device.features().contains(WgpuFeatures::TEXTURE_BINDING_ARRAY));
+	assert_eq!(capabilities.timestamps, device.features().contains(WgpuFeatures::TIMESTAMP_QUERY));
+	assert_eq!(capabilities.int64, device.features().contains(WgpuFeatures::SHADER_INT64));
+	assert_eq!(capabilities.int64_atomics, device.features().contains(WgpuFeatures::SHADER_INT64_ATOMIC_ALL_OPS));
+	assert_eq!(capabilities.shader_f16, device.features().contains(WgpuFeatures::SHADER_F16));
+	assert_eq!(capabilities.max_workgroup_size.x, device.limits().max_compute_workgroup_size_x);
+	assert_eq!(capabilities.max_storage_buffer_binding_size, device.limits().max_storage_buffer_binding_size);
+	assert_eq!(app.app().sub_app(RenderApp).world().resource::<ComputeCapabilities>(), &capabilities);
+}
+
+#[test]
+fn rejects_read_write_textures_the_gpu_cannot_bind() {
+	let Some(mut app) = ComputeTestApp::new() else {
+		return;
+	};
+	let world = app.app().world_mut();
+	let capabilities = world.resource::<ComputeCapabilities>().clone();
+	assert!(capabilities.supports_read_write_storage(TextureFormat::R32Float));
+	world.resource_scope(|world, mut images: Mut<Assets<Image>>| {
+		let mut buffer_set = world.resource_mut::<ShaderBufferSet>();
+		let mut add = |format: TextureFormat, binding| {
+			let fill = vec![0; format.block_copy_size(None).unwrap() as usize];
+			buffer_set.add_texture_fill(&mut images, 4, 4, format, &fill, StorageTextureAccess::ReadWrite, binding).unwrap()
+		};
+		add(TextureFormat::R32Float, Binding::SingleBound(0, 0));
+		// A double buffer ignores the access, so it's fine with any format.
+		add(TextureFormat::Rgba8Unorm, Binding::Double(0, (1, 2)));
+		let write_only =
+			capabilities.storage_formats.iter().find(|format| !capabilities.supports_read_write_storage(**format));
+		if let Some(format) = write_only {
+			let result = catch_unwind(AssertUnwindSafe(|| add(*format, Binding::SingleBound(0, 3))));
+			assert!(result.is_err());
+		}
+		// This format can't be a storage texture at all.
+		let result = catch_unwind(AssertUnwindSafe(|| add(TextureFormat::Rgb9e5Ufloat, Binding::SingleBound(0, 4))));
+		assert!(result.is_err());
+	});
+}
+
+#[test]
+fn converts_between_f16_and_f32() {
+	assert_eq!(f32_to_f16(1.0), 0x3c00);
+	assert_eq!(f32_to_f16(-2.0), 0xc000);
+	assert_eq!(f32_to_f16(65504.0), 0x7bff);
+	assert_eq!(f32_to_f16(65520.0), 0x7c00, "Halfway past the largest value rounds to infinity");
+	assert_eq!(f32_to_f16(1.0 + 1.0 / 2048.0), 0x3c00, "Ties round to even");
+	assert_eq!(f32_to_f16(1e-8), 0);
+	assert_eq!(f16_to_f32(0x0001), 2.0f32.powi(-24));
+	assert!(f16_to_f32(f32_to_f16(f32::NAN)).is_nan());
+	for bits in 0..=u16::MAX {
+		let value = f16_to_f32(bits);
+		if !value.is_nan() {
+			assert_eq!(f32_to_f16(value), bits, "{:#06x} didn't survive a round trip through {}", bits, value);
+		}
+	}
+}
+
+#[test]
+fn names_the_diagnostics_of_tasks_by_sequence() {
+	assert_eq!(encode_time_path(ComputeSequenceId::MAIN, 0, Some("simulate")).as_str(), "compute/encode_time/simulate");
+	assert_eq!(encode_time_path(ComputeSequenceId(2), 0, Some("a/b")).as_str(), "compute/encode_time/sequence_2/a_b");
+	assert_eq!(
+		iterations_per_second_path(ComputeSequenceId(1), 3, None).as_str(),
+		"compute/iterations_per_second/sequence_1/task_3"
+	);
+}
+
+#[test]
+fn writes_and_reads_back_half_float_textures() {
+	let Some(mut app) = ComputeTestApp::new() else {
+		return;
+	};
+	let world = app.app().world_mut();
+	if !world.resource::<ComputeCapabilities>().supports_storage(TextureFormat::Rgba16Float) {
+		return;
+	}
+	let fill = [0.25f32, 2.0, -8.0, 1.0].map(f32_to_f16);
+	let texture = world.resource_scope(|world, mut images: Mut<Assets<Image>>| {
+		world
+			.resource_mut::<ShaderBufferSet>()
+			.add_texture_fill(
+				&mut images,
+				2,
+				1,
+				TextureFormat::Rgba16Float,
+				bytemuck::cast_slice(&fill),
+				StorageTextureAccess::WriteOnly,
+				Binding::SingleBound(0, 0),
+			)
+			.unwrap()
+	});
+	app.run_until_complete(vec![ComputeTask {
+		label: None,
+		iterations: NonZeroU32::new(1),
+		run_mode: TaskRunMode::Continuous,
+		budget: None,
+		submission: TaskSubmission::WithFrame,
+		start_after: None,
+		iteration_buffer: None,
+		steps: vec![ComputeStep {
+			schedule: StepSchedule::EveryIteration,
+			run_if: None,
+			action: ComputeAction::RunShader {
+				shader: "shaders/test_half_texture.wgsl".to_owned(),
+				entry_point: "write_half".to_owned(),
+				shader_defs: Vec::new(),
+				overrides: Vec::new(),
+				dispatch: ONE_WORKGROUP,
+				auto_swap: Vec::new(),
+				skip_if_empty: None,
+				buffer_orientation: Vec::new(),
+				push_constants: None,
+				dynamic_offsets: Vec::new(),
+			},
+		}],
+	}]);
+	assert_eq!(app.read_buffer_f16(texture), vec![0.5, -1.0, 3.0, 1024.0, 0.25, 2.0, -8.0, 1.0]);
+}
+
+#[test]
+fn writes_and_reads_back_3d_textures_and_texture_arrays() {
+	let Some(mut app) = ComputeTestApp::new() else {
+		return;
+	};
+	let world = app.app().world_mut();
+	let (volume, layers) = world.resource_scope(|world, mut images: Mut<Assets<Image>>| {
+		let mut buffers = world.resource_mut::<ShaderBufferSet>();
+		let fill = (-1.0f32).to_ne_bytes();
+		let access = StorageTextureAccess::WriteOnly;
+		let format = TextureFormat::R32Float;
+		(
+			buffers.add_texture_fill_3d(&mut images, 3, 2, 2, format, &fill, access, Binding::SingleBound(0, 0)).unwrap(),
+			buffers.add_texture_fill_layers(&mut images, 3, 2, 2, format, &fill, access, Binding::SingleBound(0, 1)).unwrap(),
+		)
+	});
+	app.assert_buffer_eq(volume, &[-1.0f32; 12]);
+	app.run_until_complete(vec![ComputeTask {
+		label: None,
+		iterations: NonZeroU32::new(1),
+		run_mode: TaskRunMode::Continuous,
+		budget: None,
+		submission: TaskSubmission::WithFrame,
+		start_after: None,
+		iteration_buffer: None,
+		steps: vec![ComputeStep {
+			schedule: StepSchedule::EveryIteration,
+			run_if: None,
+			action: ComputeAction::RunShader {
+				shader: "shaders/test_texture_3d.wgsl".to_owned(),
+				entry_point: "write_coordinates".to_owned(),
+				shader_defs: Vec::new(),
+				overrides: Vec::new(),
+				dispatch: ONE_WORKGROUP,
+				auto_swap: Vec::new(),
+				skip_if_empty: None,
+				buffer_orientation: Vec::new(),
+				push_constants: None,
+				dynamic_offsets: Vec::new(),
+			},
+		}],
+	}]);
+	// Rows of 3 pixels are padded to 256 bytes in the copies, across every slice and layer.
+	let expected = [0.0f32, 1.0, 2.0, 10.0, 11.0, 12.0, 100.0, 101.0, 102.0, 110.0, 111.0, 112.0];
+	app.assert_buffer_eq(layers, &expected);
+	// wgpu's GL backend only binds the first slice of a 3D storage texture, though copies still cover them all.
+	if app.app().sub_app(RenderApp).world().resource::<RenderAdapterInfo>().backend == wgpu::Backend::Gl {
+		assert_eq!(app.read_buffer::<f32>(volume)[..6], expected[..6]);
+	} else {
+		app.assert_buffer_eq(volume, &expected);
+	}
+}
+
+#[test]
+fn binds_the_element_of_a_dynamic_uniform_each_step_picks() {
+	let Some(mut app) = ComputeTestApp::new() else {
+		return;
+	};
+	app.app().init_resource::<ValidationReports>().add_systems(
+		Update,
+		|mut events: EventReader<ComputeValidationReport>, mut reports: ResMut<ValidationReports>| {
+			reports.0.extend(events.read().cloned())
+		},
+	);
+	let values = app.add_storage_init(vec![0u32; 4], Binding::SingleBound(0, 0));
+	let render_device = app.app().world().resource::<RenderDevice>().clone();
+	let mut buffers = app.buffer_set();
+	let elements =
+		buffers.add_uniform_dynamic::<UVec2>(&render_device, 3, BufferUsages::empty(), Binding::SingleBound(0, 1)).unwrap();
+	for (index, element) in [UVec2::new(0, 5), UVec2::new(1, 7), UVec2::new(2, 11)].into_iter().enumerate() {
+		buffers.write_uniform_element(elements, index as u32, element).unwrap();
+	}
+	assert!(matches!(
+		buffers.write_uniform_element(elements, 3, UVec2::ZERO),
+		Err(ShaderBufferError::OutOfRange { start: 3, count: 1, capacity: 3, .. })
+	));
+	assert!(matches!(
+		buffers.write_uniform_element(values, 0, UVec2::ZERO),
+		Err(ShaderBufferError::WrongBufferKind { expected: "a dynamic uniform buffer", .. })
+	));
+	let step = |dynamic_offsets| ComputeStep {
+		schedule: StepSchedule::EveryIteration,
+		run_if: None,
+		action: ComputeAction::RunShader {
+			shader: "shaders/test_dynamic_uniform.wgsl".to_owned(),
+			entry_point: "write_element".to_owned(),
+			shader_defs: Vec::new(),
+			overrides: Vec::new(),
+			dispatch: ONE_WORKGROUP,
+			auto_swap: Vec::new(),
+			skip_if_empty: None,
+			buffer_orientation: Vec::new(),
+			push_constants: None,
+			dynamic_offsets,
+		},
+	};
+	let task = |steps| ComputeTask {
+		label: None,
+		iterations: NonZeroU32::new(1),
+		run_mode: TaskRunMode::Continuous,
+		budget: None,
+		submission: TaskSubmission::WithFrame,
+		start_after: None,
+		iteration_buffer: None,
+		steps,
+	};
+	// The step that doesn't pick an element binds the first.
+	app.run_until_complete(vec![task(vec![step(vec![(elements, 2)]), step(vec![(elements, 1)]), step(Vec::new())])]);
+	app.assert_buffer_eq(values, &[5u32, 7, 11, 0]);
+
+	app.app().world_mut().send_event(ValidateComputeEvent {
+		tasks: vec![task(vec![step(vec![(elements, 3)]), step(vec![(values, 0)])])],
+		iteration_buffer: None,
+	});
+	for _ in 0..1000 {
+		app.app().update();
+		if !app.app().world().resource::<ValidationReports>().0.is_empty() {
+			break;
+		}
+	}
+	let findings = &app.app().world().resource::<ValidationReports>().0[0].findings;
+	let messages = findings.iter().map(|finding| (finding.step, finding.message.clone())).collect::<Vec<_>>();
+	let buffers = app.app().world().resource::<ShaderBufferSet>();
+	assert_eq!(
+		messages,
+		vec![
+			(
+				Some(0),
+				format!("Tried to bind element 3 of {} for a step, but it only holds 3 elements", elements.describe(buffers))
+			),
+			(
+				Some(1),
+				format!(
+					"Tried to bind element 0 of {} for a step, but it isn't a dynamic uniform buffer",
+					values.describe(buffers)
+				)
+			),
+		]
+	);
+}
+
+#[test]
+fn sets_push_constants_before_each_dispatch() {
+	let Some(mut app) = ComputeTestApp::new() else {
+		return;
+	};
+	if !app.app().world().resource::<ComputeCapabilities>().push_constants {
+		return;
+	}
+	app.app().init_resource::<ValidationReports>().add_systems(
+		Update,
+		|mut events: EventReader<ComputeValidationReport>, mut reports: ResMut<ValidationReports>| {
+			reports.0.extend(events.read().cloned())
+		},
+	);
+	let values = app.add_storage_init(vec![0u32; 8], Binding::SingleBound(0, 0));
+	let step = |push_constants| ComputeStep {
+		schedule: StepSchedule::EveryIteration,
+		run_if: None,
+		action: ComputeAction::RunShader {
+			shader: "shaders/test_push_constants.wgsl".to_owned(),
+			entry_point: "write_push_constants".to_owned(),
+			shader_defs: Vec::new(),
+			overrides: Vec::new(),
+			dispatch: ONE_WORKGROUP,
+			auto_swap: Vec::new(),
+			skip_if_empty: None,
+			buffer_orientation: Vec::new(),
+			push_constants,
+			dynamic_offsets: Vec::new(),
+		},
+	};
+	let steps = vec![
+		step(Some(PushConstants::Computed {
+			size: 8,
+			data: |_, iteration| bytemuck::cast_slice(&[iteration, iteration * 10]).to_vec(),
+		})),
+		step(Some(PushConstants::Bytes(bytemuck::cast_slice(&[7u32, 99]).to_vec()))),
+	];
+	let task = |steps| ComputeTask {
+		label: None,
+		iterations: NonZeroU32::new(4),
+		run_mode: TaskRunMode::Continuous,
+		budget: None,
+		submission: TaskSubmission::WithFrame,
+		start_after: None,
+		iteration_buffer: None,
+		steps,
+	};
+	app.run_until_complete(vec![task(steps)]);
+	app.assert_buffer_eq(values, &[0u32, 10, 20, 30, 0, 0, 0, 99]);
+
+	app.app().world_mut().send_event(ValidateComputeEvent {
+		tasks: vec![task(vec![step(None), step(Some(PushConstants::Bytes(vec![0; 6])))])],
+		iteration_buffer: None,
+	});
+	for _ in 0..1000 {
+		app.app().update();
+		if !app.app().world().resource::<ValidationReports>().0.is_empty() {
+			break;
+		}
+	}
+	let findings = &app.app().world().resource::<ValidationReports>().0[0].findings;
+	let messages = findings.iter().map(|finding| (finding.step, finding.message.as_str())).collect::<Vec<_>>();
+	assert_eq!(
+		messages,
+		vec![
+			(
+				Some(0),
+				"write_push_constants in shaders/test_push_constants.wgsl uses 8 bytes of push constants in push, but the step gives it 0"
+			),
+			(
+				Some(1),
+				"write_push_constants in shaders/test_push_constants.wgsl uses 8 bytes of push constants in push, but the step gives it 6"
+			),
+			(
+				Some(1),
+				"Tried to give shaders/test_push_constants.wgsl 6 bytes of push constants, but they must come to a multiple of 4 bytes"
+			),
+		]
+	);
+}
+
+#[test]
+#[should_panic(expected = "a pixel of that format is 8 bytes")]
+fn panics_when_a_texture_fill_isnt_one_pixel() {
+	let Some(mut app) = ComputeTestApp::new() else {
+		panic!("a pixel of that format is 8 bytes, but there's no GPU to check it on");
+	};
+	app.app().world_mut().resource_scope(|world, mut images: Mut<Assets<Image>>| {
+		world
+			.resource_mut::<ShaderBufferSet>()
+			.add_texture_fill(
+				&mut images,
+				1,
+				1,
+				TextureFormat::Rgba16Float,
+				&1.0f32.to_ne_bytes(),
+				StorageTextureAccess::WriteOnly,
+				Binding::SingleBound(0, 0),
+			)
+			.unwrap()
+	});
+}
+
+#[test]
+fn counts_past_u32_with_u64_counters() {
+	let Some(mut app) = ComputeTestApp::new() else {
+		return;
+	};
+	if !app.app().world().resource::<ComputeCapabilities>().int64_atomics {
+		return;
+	}
+	let device = app.app().world().resource::<RenderDevice>().clone();
+	let counters = app.buffer_set().add_counters_u64(&device, 2, Binding::SingleBound(0, 0)).unwrap();
+	app.run_until_complete(vec![ComputeTask {
+		label: None,
+		iterations: NonZeroU32::new(1),
+		run_mode: TaskRunMode::Continuous,
+		budget: None,
+		submission: TaskSubmission::WithFrame,
+		start_after: None,
+		iteration_buffer: None,
+		steps: vec![ComputeStep {
+			schedule: StepSchedule::EveryIteration,
+			run_if: None,
+			action: ComputeAction::RunShader {
+				shader: "shaders/test_counters_u64.wgsl".to_owned(),
+				entry_point: "count_wide".to_owned(),
+				shader_defs: Vec::new(),
+				overrides: Vec::new(),
+				dispatch: ONE_WORKGROUP,
+				auto_swap: Vec::new(),
+				skip_if_empty: None,
+				buffer_orientation: Vec::new(),
+				push_constants: None,
+				dynamic_offsets: Vec::new(),
+			},
+		}],
+	}]);
+	app.assert_buffer_eq(counters, &[4u64 << 32, 4]);
+}
+
+#[test]
+fn refuses_buffers_over_the_memory_budget_and_frees_deleted_ones() {
+	let Some(mut app) = ComputeTestApp::new() else {
+		return;
+	};
+	app.buffer_set().set_memory_budget(Some(1024));
+	let data = app.add_storage_init(vec![0.0f32; 64], Binding::Double(0, (0, 1)));
+	assert_eq!(app.buffer_set().allocated_bytes(), 512);
+	assert_eq!(
+		app.buffer_set().fits_budget(768),
+		Err(ShaderBufferError::BudgetExceeded { requested: 768, used: 512, budget: 1024 })
+	);
+	let world = app.app().world_mut();
+	world.resource_scope(|world, mut images: Mut<Assets<Image>>| {
+		let mut buffer_set = world.resource_mut::<ShaderBufferSet>();
+		// An 8x16 texture of 4 byte texels is 512 bytes, which fits exactly, but two of them would go over.
+		let mut add = |binding| {
+			buffer_set.add_texture_fill(
+				&mut images,
+				8,
+				16,
+				TextureFormat::R32Float,
+				&[0; 4],
+				StorageTextureAccess::ReadWrite,
+				binding,
+			)
+		};
+		assert_eq!(
+			add(Binding::Double(1, (0, 1))),
+			Err(ShaderBufferError::BudgetExceeded { requested: 1024, used: 512, budget: 1024 })
+		);
+		let texture = add(Binding::SingleBound(1, 0)).unwrap();
+		assert_eq!(buffer_set.allocated_bytes(), 1024);
+		buffer_set.delete_buffer(data, &mut images).unwrap();
+		buffer_set.delete_buffer(texture, &mut images).unwrap();
+		assert_eq!(buffer_set.allocated_bytes(), 0);
+	});
+}
+
+#[test]
+fn shares_layouts_with_other_shader_stages() {
+	let Some(mut app) = ComputeTestApp::new() else {
+		return;
+	};
+	let buffer = app.add_storage_init(vec![1.0f32, 2.0, 3.0, 4.0], Binding::SingleBound(0, 0));
+	app.buffer_set().set_visibility(buffer, ShaderStages::FRAGMENT);
+	let entries = app.buffer_set().layout_entry_for(buffer).unwrap();
+	assert_eq!(entries[0].visibility, ShaderStages::COMPUTE | ShaderStages::FRAGMENT);
+	assert_eq!(app.buffer_set().layout_entries_for_group(0), Some(entries));
+	assert_eq!(app.buffer_set().layout_entries_for_group(1), None);
+	let render_device = app.app().world().resource::<RenderDevice>().clone();
+	let main_layout = app.buffer_set().bind_group_layout_for_group(0, &render_device).unwrap();
+	assert!(app.buffer_set().bind_group_layout_for_group(1, &render_device).is_none());
+
+	app.run_until_complete(vec![ComputeTask {
+		label: None,
+		iterations: NonZeroU32::new(1),
+		run_mode: TaskRunMode::Continuous,
+		budget: None,
+		submission: TaskSubmission::WithFrame,
+		start_after: None,
+		iteration_buffer: None,
+		steps: vec![run_shader("increment")],
+	}]);
+	let render_world = app.app().sub_app_mut(RenderApp).world_mut();
+	assert!(render_world.resource::<ComputeBindGroups>().get(0).is_some());
+	render_world.resource_scope(|world, mut render_buffers: Mut<ShaderBufferRenderSet>| {
+		let buffers = world.resource::<ShaderBufferSet>();
+		let device = world.resource::<RenderDevice>();
+		let layout = render_buffers.bind_group_layout(0, buffers, device).unwrap();
+		assert_eq!(render_buffers.bind_group_layout(0, buffers, device).unwrap().id(), layout.id());
+		assert!(render_buffers.bind_group_layout(1, buffers, device).is_none());
+	});
+
+	// A layout made from the main world binds the bind group bevy_compute made against its own layout.
+	let render_world = app.app().sub_app(RenderApp).world();
+	let bind_group = render_world.resource::<ComputeBindGroups>().get(0).unwrap().clone();
+	let device = render_world.resource::<RenderDevice>();
+	let module = device.create_shader_module(ShaderModuleDescriptor {
+		label: None,
+		source: ShaderSource::Wgsl(
+			"@group(0) @binding(0) var<storage, read_write> data: array<f32>;\n@compute @workgroup_size(1) fn main() { data[0] += 10.0; }".into(),
+		),
+	});
+	let pipeline_layout = device.create_pipeline_layout(&PipelineLayoutDescriptor {
+		label: None,
+		bind_group_layouts: &[&main_layout],
+		push_constant_ranges: &[],
+	});
+	let pipeline = device.create_compute_pipeline(&RawComputePipelineDescriptor {
+		label: None,
+		layout: Some(&pipeline_layout),
+		module: &module,
+		entry_point: Some("main"),
+		compilation_options: Default::default(),
+		cache: None,
+	});
+	let mut encoder = device.create_command_encoder(&CommandEncoderDescriptor::default());
+	{
+		let mut pass = encoder.begin_compute_pass(&ComputePassDescriptor::default());
+		pass.set_pipeline(&pipeline);
+		pass.set_bind_group(0, &bind_group, &[]);
+		pass.dispatch_workgroups(1, 1, 1);
+	}
+	render_world.resource::<RenderQueue>().submit([encoder.finish()]);
+	assert_buffer_approx_eq(&app.read_buffer::<f32>(buffer), &[12.0, 3.0, 4.0, 5.0], 1e-6);
+}
+
+#[test]
+fn counts_generations_of_queued_bind_groups() {
+	let Some(mut app) = ComputeTestApp::new() else {
+		return;
+	};
+	let buffer = app.add_storage_init(vec![1.0f32, 2.0, 3.0, 4.0], Binding::SingleBound(0, 0));
+	let task = || ComputeTask {
+		label: None,
+		iterations: NonZeroU32::new(1),
+		run_mode: TaskRunMode::Continuous,
+		budget: None,
+		submission: TaskSubmission::WithFrame,
+		start_after: None,
+		iteration_buffer: None,
+		steps: vec![run_shader("increment")],
+	};
+	let generations = |app: &mut ComputeTestApp| {
+		let render_buffers = app.app().sub_app(RenderApp).world().resource::<ShaderBufferRenderSet>();
+		(render_buffers.bind_group_generation(0).unwrap(), render_buffers.layout_generation(0).unwrap())
+	};
+
+	app.run_until_complete(vec![task()]);
+	let render_world = app.app().sub_app_mut(RenderApp).world_mut();
+	render_world.resource_scope(|world, mut render_buffers: Mut<ShaderBufferRenderSet>| {
+		let buffers = world.resource::<ShaderBufferSet>();
+		let device = world.resource::<RenderDevice>();
+		let layout = render_buffers.bind_group_layout(0, buffers, device).unwrap();
+		assert!(render_buffers.bind_group(0).is_some());
+		assert_eq!(render_buffers.current_layout(0).unwrap().id(), layout.id());
+		assert!(render_buffers.bind_group(1).is_none());
+		assert!(render_buffers.bind_group_generation(1).is_none());
+	});
+	let first = generations(&mut app);
+
+	app.run_until_complete(vec![task()]);
+	assert_eq!(generations(&mut app), first, "Nothing changed, so neither generation should have");
+
+	app.add_storage_init(vec![0.0f32; 4], Binding::SingleBound(0, 1));
+	app.run_until_complete(vec![task()]);
+	assert_eq!(generations(&mut app), (first.0 + 1, first.1 + 1));
+	assert_buffer_approx_eq(&app.read_buffer::<f32>(buffer), &[4.0, 5.0, 6.0, 7.0], 1e-6);
+}
+
+#[test]
+fn reuses_the_bind_groups_of_groups_that_didnt_change() {
+	let Some(mut app) = ComputeTestApp::new() else {
+		return;
+	};
+	let buffer = app.add_storage_init(vec![1.0f32, 2.0, 3.0, 4.0], Binding::SingleBound(0, 0));
+	let double = app.add_storage_init(vec![0.0f32; 4], Binding::Double(1, (0, 1)));
+	let task = |steps| ComputeTask {
+		label: None,
+		iterations: NonZeroU32::new(1),
+		run_mode: TaskRunMode::Continuous,
+		budget: None,
+		submission: TaskSubmission::WithFrame,
+		start_after: None,
+		iteration_buffer: None,
+		steps,
+	};
+	let bind_group_ids = |app: &mut ComputeTestApp| {
+		let render_buffers = app.app().sub_app(RenderApp).world().resource::<ShaderBufferRenderSet>();
+		(render_buffers.bind_group(0).unwrap().id(), render_buffers.bind_group(1).unwrap().id())
+	};
+
+	app.run_until_complete(vec![task(vec![run_shader("increment")])]);
+	let first = bind_group_ids(&mut app);
+	app.run_until_complete(vec![task(vec![run_shader("increment")])]);
+	assert_eq!(bind_group_ids(&mut app), first, "Nothing changed, so neither bind group should have been remade");
+
+	app.run_until_complete(vec![task(vec![ComputeStep {
+		schedule: StepSchedule::EveryIteration,
+		run_if: None,
+		action: ComputeAction::SwapBuffers { buffer: double },
+	}])]);
+	app.run_until_complete(vec![task(vec![run_shader("increment")])]);
+	let (group0, group1) = bind_group_ids(&mut app);
+	assert_eq!(group0, first.0, "Only the group with the swapped buffer should have a new bind group");
+	assert_ne!(group1, first.1);
+	assert_buffer_approx_eq(&app.read_buffer::<f32>(buffer), &[4.0, 5.0, 6.0, 7.0], 1e-6);
+}
+
+#[test]
+fn leaves_out_the_groups_below_the_ones_a_sequence_uses() {
+	let Some(mut app) = ComputeTestApp::new() else {
+		return;
+	};
+	// The shader only references group 1, so group 0 gets an empty layout in its pipeline, and an empty bind group.
+	let data = app.add_storage_init(vec![5.0f32; 4], Binding::SingleBound(0, 0));
+	let agent = app.add_storage_init(vec![0.0f32; 4], Binding::SingleBound(1, 0));
+	let mut step = run_shader("increment_agent");
+	if let ComputeAction::RunShader { shader, .. } = &mut step.action {
+		*shader = "shaders/test_upper_group.wgsl".to_owned();
+	}
+	app.run_until_complete(vec![ComputeTask {
+		label: None,
+		iterations: NonZeroU32::new(3),
+		run_mode: TaskRunMode::Continuous,
+		budget: None,
+		submission: TaskSubmission::WithFrame,
+		start_after: None,
+		iteration_buffer: None,
+		steps: vec![step],
+	}]);
+	assert_buffer_approx_eq(&app.read_buffer::<f32>(agent), &[3.0; 4], 0.0);
+	assert_buffer_approx_eq(&app.read_buffer::<f32>(data), &[5.0; 4], 0.0);
+}
+
+#[test]
+fn adds_and_deletes_buffers_while_a_sequence_is_running() {
+	let Some(mut app) = ComputeTestApp::new() else {
+		return;
+	};
+	let data = app.add_storage_init(vec![0.0f32; 16], Binding::SingleBound(0, 0));
+	let spare = app.add_storage_init(vec![0.0f32; 4], Binding::SingleBound(0, 1));
+	app.app().world_mut().send_event(StartComputeEvent {
+		sequence: ComputeSequenceId::MAIN,
+		tasks: vec![ComputeTask {
+			label: None,
+			iterations: None,
+			run_mode: TaskRunMode::Continuous,
+			budget: None,
+			submission: TaskSubmission::WithFrame,
+			start_after: None,
+			iteration_buffer: None,
+			steps: vec![run_shader("increment")],
+		}],
+		iteration_buffer: None,
+		seed: 0,
+	});
+	let iterations = |app: &mut ComputeTestApp| app.app().world().resource::<ComputeProgress>().iterations;
+	for _ in 0..MAX_FRAMES {
+		app.app().update();
+		if iterations(&mut app) >= 2 {
+			break;
+		}
+	}
+
+	// The shader doesn't use either, so the task carries on against the new layouts.
+	let added = app.add_storage_init(vec![0.0f32; 4], Binding::SingleBound(1, 0));
+	let world = app.app().world_mut();
+	world.resource_scope(|world, mut images: Mut<Assets<Image>>| {
+		world.resource_mut::<ShaderBufferSet>().delete_buffer(spare, &mut images).unwrap();
+	});
+	let before = iterations(&mut app);
+	for _ in 0..MAX_FRAMES {
+		app.app().update();
+		if iterations(&mut app) >= before + 3 {
+			break;
+		}
+	}
+	assert!(iterations(&mut app) >= before + 3, "The task should have kept running");
+	let render_buffers = app.app().sub_app(RenderApp).world().resource::<ShaderBufferRenderSet>();
+	assert!(render_buffers.current_layout(1).is_some(), "The added buffer's group should have a layout");
+	app.app().world_mut().send_event(StopComputeEvent(ComputeSequenceId::MAIN));
+	for _ in 0..5 {
+		app.app().update();
+	}
+	let stopped = app.read_buffer::<f32>(data);
+	assert!(stopped[0] >= (before + 3) as f32);
+	assert!(stopped.iter().all(|&value| value == stopped[0]));
+	app.assert_buffer_eq(added, &[0.0f32; 4]);
+}
+
+#[test]
+#[should_panic(expected = "group 0 binding 0 was deleted while the compute sequence was running")]
+fn panics_when_a_buffer_the_running_task_uses_is_deleted() {
+	let Some(mut app) = ComputeTestApp::new() else {
+		panic!("group 0 binding 0 was deleted while the compute sequence was running, but there's no GPU to check it on");
+	};
+	let data = app.add_storage_init(vec![0.0f32; 16], Binding::SingleBound(0, 0));
+	app.app().world_mut().send_event(StartComputeEvent {
+		sequence: ComputeSequenceId::MAIN,
+		tasks: vec![ComputeTask {
+			label: None,
+			iterations: None,
+			run_mode: TaskRunMode::Continuous,
+			budget: None,
+			submission: TaskSubmission::WithFrame,
+			start_after: None,
+			iteration_buffer: None,
+			steps: vec![run_shader("increment")],
+		}],
+		iteration_buffer: None,
+		seed: 0,
+	});
+	for _ in 0..MAX_FRAMES {
+		app.app().update();
+		if app.app().world().resource::<ComputeProgress>().iterations >= 2 {
+			break;
+		}
+	}
+	let world = app.app().world_mut();
+	world.resource_scope(|world, mut images: Mut<Assets<Image>>| {
+		world.resource_mut::<ShaderBufferSet>().delete_buffer(data, &mut images).unwrap();
+	});
+	app.app().update();
+}
+
+#[test]
+fn binds_images_from_handles_once_they_have_loaded() {
+	let Some(mut app) = ComputeTestApp::new() else {
+		return;
+	};
+	let data = app.add_storage_init(vec![0.0f32; 1], Binding::SingleBound(0, 0));
+	let world = app.app().world_mut();
+	// Like a PNG loaded with the asset server, this is sRGB where the GPU can make sRGB views of a linear texture, and
+	// either way, can't be a storage texture until its usages are patched.
+	let format = match world.resource::<ComputeCapabilities>().srgb_views {
+		true => TextureFormat::Rgba8UnormSrgb,
+		false => TextureFormat::Rgba8Unorm,
+	};
+	let (heights, eroded_image) = world.resource_scope(|_, mut images: Mut<Assets<Image>>| {
+		let eroded = Image::new_fill(
+			Extent3d { width: 2, height: 1, depth_or_array_layers: 1 },
+			TextureDimension::D2,
+			&[0, 0, 255, 255],
+			format,
+			RenderAssetUsages::default(),
+		);
+		(images.reserve_handle(), images.add(eroded))
+	});
+	let mut buffer_set = app.buffer_set();
+	buffer_set.add_texture_from_handle(heights.clone(), Binding::SingleBound(0, 1), None).unwrap();
+	let eroded = buffer_set
+		.add_texture_from_handle(eroded_image.clone(), Binding::SingleBound(0, 2), Some(StorageTextureAccess::WriteOnly))
+		.unwrap();
+	app.app().world_mut().send_event(StartComputeEvent {
+		sequence: ComputeSequenceId::MAIN,
+		tasks: vec![ComputeTask {
+			label: None,
+			iterations: NonZeroU32::new(1),
+			run_mode: TaskRunMode::Continuous,
+			budget: None,
+			submission: TaskSubmission::WithFrame,
+			start_after: None,
+			iteration_buffer: None,
+			steps: vec![ComputeStep {
+				schedule: StepSchedule::EveryIteration,
+				run_if: None,
+				action: ComputeAction::RunShader {
+					shader: "shaders/test_texture_handles.wgsl".to_owned(),
+					entry_point: "erode".to_owned(),
+					shader_defs: Vec::new(),
+					overrides: Vec::new(),
+					dispatch: ONE_WORKGROUP,
+					auto_swap: Vec::new(),
+					skip_if_empty: None,
+					buffer_orientation: Vec::new(),
+					push_constants: None,
+					dynamic_offsets: Vec::new(),
+				},
+			}],
+		}],
+		iteration_buffer: None,
+		seed: 0,
+	});
+	for _ in 0..20 {
+		app.app().update();
+	}
+	assert_ne!(*app.app().world().resource::<ComputeState>(), ComputeState::Finished, "The heights haven't loaded yet");
+	let images = app.app().world().resource::<Assets<Image>>();
+	let descriptor = &images.get(&eroded_image).unwrap().texture_descriptor;
+	assert!(descriptor.usage.contains(TextureUsages::STORAGE_BINDING));
+	assert_eq!(descriptor.format, TextureFormat::Rgba8Unorm);
+
+	let loaded = Image::new_fill(
+		Extent3d { width: 1, height: 1, depth_or_array_layers: 1 },
+		TextureDimension::D2,
+		&0.75f32.to_le_bytes(),
+		TextureFormat::R32Float,
+		RenderAssetUsages::default(),
+	);
+	app.app().world_mut().resource_mut::<Assets<Image>>().insert(&heights, loaded);
+	for _ in 0..MAX_FRAMES {
+		app.app().update();
+		if *app.app().world().resource::<ComputeState>() == ComputeState::Finished {
+			break;
+		}
+	}
+	assert_buffer_approx_eq(&app.read_buffer::<f32>(data), &[0.75], 1e-6);
+	app.assert_texture_pixel::<u8>(eroded, 0, 0, &[0, 0, 255, 255], 0);
+	app.assert_texture_pixel::<u8>(eroded, 1, 0, &[96, 0, 0, 255], 1);
+
+	// The images belong to the test, so deleting the buffers leaves them alone.
+	let world = app.app().world_mut();
+	world.resource_scope(|world, mut images: Mut<Assets<Image>>| {
+		world.resource_mut::<ShaderBufferSet>().delete_buffer(eroded, &mut images).unwrap();
+	});
+	assert!(app.app().world().resource::<Assets<Image>>().contains(&eroded_image));
+}
+
+#[test]
+fn times_shader_steps_with_the_profiling_plugin() {
+	let Some(mut app) = ComputeTestApp::with_plugins(ComputeProfilingPlugin) else {
+		return;
+	};
+	let buffer = app.add_storage_init(vec![1.0f32, 2.0, 3.0, 4.0], Binding::SingleBound(0, 0));
+	app.run_until_complete(vec![ComputeTask {
+		label: Some("profiled".to_owned()),
+		iterations: NonZeroU32::new(4),
+		run_mode: TaskRunMode::Continuous,
+		budget: None,
+		submission: TaskSubmission::WithFrame,
+		start_after: None,
+		iteration_buffer: None,
+		steps: vec![run_shader("increment"), run_shader("decrement"), run_shader("increment")],
+	}]);
+	// Each dispatch is in a pass of its own, which mustn't change what the steps do.
+	assert_buffer_approx_eq(&app.read_buffer::<f32>(buffer), &[5.0, 6.0, 7.0, 8.0], 1e-6);
+
+	// The timestamps are read back a frame or two after they're written.
+	for _ in 0..MAX_FRAMES {
+		if !app.app().world().resource::<ComputeTimings>().steps.is_empty() {
+			break;
+		}
+		app.app().update();
+	}
+	let world = app.app().world();
+	let timings = world.resource::<ComputeTimings>();
+	if !world.resource::<ComputeCapabilities>().timestamps {
+		assert!(timings.steps.is_empty(), "Nothing can be timed without timestamp queries");
+		return;
+	}
+	let mut steps = timings.steps.keys().cloned().collect::<Vec<_>>();
+	steps.sort();
+	let step = |task: &str, entry_point: &str| (task.to_owned(), entry_point.to_owned());
+	assert_eq!(steps, vec![step("profiled", "decrement"), step("profiled", "increment")]);
+	for time in timings.steps.values() {
+		assert!(time.is_finite() && *time >= 0.0);
+	}
+	assert_eq!(timings.get("profiled", "increment"), timings.steps.get(&step("profiled", "increment")).copied());
+	assert!(timings.get("task_0", "increment").is_none());
+}
+
+#[test]
+fn counts_buffers_and_textures_into_histograms() {
+	let Some(mut app) = ComputeTestApp::new() else {
+		return;
+	};
+	let values =
+		app.add_storage_init(vec![-1.0f32, 0.0, 0.1, 0.3, 0.55, 0.99, 1.0, 2.0, f32::NAN], Binding::SingleBound(0, 0));
+	// These start out non-zero, to check that they're cleared first.
+	let discarded = app.add_storage_init(vec![7u32; 4], Binding::SingleBound(0, 1));
+	let clamped = app.add_storage_init(vec![7u32; 4], Binding::SingleBound(0, 2));
+	let render_device = app.app().world().resource::<RenderDevice>().clone();
+	let texture_bins = app.buffer_set().add_counters(&render_device, 4, Binding::SingleBound(0, 3)).unwrap();
+	let world = app.app().world_mut();
+	let texture = world.resource_scope(|world, mut images: Mut<Assets<Image>>| {
+		world
+			.resource_mut::<ShaderBufferSet>()
+			.add_texture_fill(
+				&mut images,
+				4,
+				2,
+				TextureFormat::R32Float,
+				&0.3f32.to_le_bytes(),
+				StorageTextureAccess::WriteOnly,
+				Binding::SingleBound(1, 0),
+			)
+			.unwrap()
+	});
+	let histogram = |src, bins, source, out_of_range| ComputeStep {
+		schedule: StepSchedule::EveryIteration,
+		run_if: None,
+		action: ComputeAction::Histogram { src, bins, bin_count: 4, range: (0.0, 1.0), source, out_of_range },
+	};
+	app.run_until_complete(vec![ComputeTask {
+		label: None,
+		iterations: NonZeroU32::new(1),
+		run_mode: TaskRunMode::Continuous,
+		budget: None,
+		submission: TaskSubmission::WithFrame,
+		start_after: None,
+		iteration_buffer: None,
+		steps: vec![
+			histogram(values, discarded, HistogramSource::Buffer, HistogramOutOfRange::Discard),
+			histogram(values, clamped, HistogramSource::Buffer, HistogramOutOfRange::Clamp),
+			histogram(texture, texture_bins, HistogramSource::Texture, HistogramOutOfRange::Discard),
+		],
+	}]);
+	assert_eq!(app.read_buffer::<u32>(discarded), [2, 1, 1, 2]);
+	assert_eq!(app.read_buffer::<u32>(clamped), [3, 1, 1, 3]);
+	assert_eq!(app.read_buffer::<u32>(texture_bins), [0, 8, 0, 0]);
+}
+
+#[test]
+fn convolves_textures_in_place_and_into_others() {
+	let Some(mut app) = ComputeTestApp::new() else {
+		return;
+	};
+	let (field, blurred) = app.app().world_mut().resource_scope(|world, mut images: Mut<Assets<Image>>| {
+		let mut buffers = world.resource_mut::<ShaderBufferSet>();
+		let format = TextureFormat::R32Float;
+		let access = StorageTextureAccess::ReadWrite;
+		let field =
+			buffers.add_texture_fill(&mut images, 5, 3, format, &[0; 4], access, Binding::SingleBound(0, 0)).unwrap();
+		let format = TextureFormat::Rgba32Float;
+		let blurred =
+			buffers.add_texture_fill(&mut images, 5, 3, format, &[0; 16], access, Binding::SingleBound(0, 1)).unwrap();
+		(field, blurred)
+	});
+	let impulse = 1.0f32.to_le_bytes();
+	app
+		.buffer_set()
+		.write_texture_region(field, UVec2::new(0, 1), UVec2::ONE, &impulse, TextureWriteTarget::Both)
+		.unwrap();
+	let convolve = |src, dst, kernel, edges| ComputeStep {
+		schedule: StepSchedule::EveryIteration,
+		run_if: None,
+		action: ComputeAction::Convolve { src, dst, kernel, edges },
+	};
+	app.run_until_complete(vec![ComputeTask {
+		label: None,
+		iterations: NonZeroU32::new(1),
+		run_mode: TaskRunMode::Continuous,
+		budget: None,
+		submission: TaskSubmission::WithFrame,
+		start_after: None,
+		iteration_buffer: None,
+		steps: vec![
+			convolve(field, field, ConvolutionKernel::Box { radius: 1 }, ConvolutionEdges::Wrap),
+			// This shifts everything right and down a texel, clamping at the left and top.
+			convolve(field, blurred, ConvolutionKernel::Custom(vec![1.0, 0.0, 0.0]), ConvolutionEdges::Clamp),
+		],
+	}]);
+	// The impulse is spread over the 3x3 texels around it, wrapping around to the right edge.
+	let ninth = 1.0 / 9.0;
+	let row = [ninth, ninth, 0.0, 0.0, ninth];
+	assert_buffer_approx_eq(&app.read_buffer::<f32>(field), &row.repeat(3), 1e-6);
+	let shifted = [ninth, ninth, ninth, 0.0, 0.0].map(|value| [value, 0.0, 0.0, 1.0]).concat().repeat(3);
+	assert_buffer_approx_eq(&app.read_buffer::<f32>(blurred), &shifted, 1e-6);
+}
+
+#[test]
+fn reports_the_first_value_that_isnt_finite_once_per_check() {
+	let Some(mut app) = ComputeTestApp::new() else {
+		return;
+	};
+	app
+		.app()
+		.init_resource::<Errors>()
+		.add_systems(Update, |mut events: EventReader<ComputeErrorEvent>, mut errors: ResMut<Errors>| {
+			errors.0.extend(events.read().cloned())
+		});
+	let mut values = vec![1.0f32; 20];
+	values[9] = f32::INFINITY;
+	values[5] = f32::NAN;
+	let corrupt = app.add_storage_init(values, Binding::SingleBound(0, 0));
+	let clean = app.add_storage_init(vec![1.0f32; 20], Binding::SingleBound(0, 1));
+	let world = app.app().world_mut();
+	let texture = world.resource_scope(|world, mut images: Mut<Assets<Image>>| {
+		world
+			.resource_mut::<ShaderBufferSet>()
+			.add_texture_fill(
+				&mut images,
+				4,
+				2,
+				TextureFormat::R32Float,
+				&0.5f32.to_ne_bytes(),
+				StorageTextureAccess::ReadOnly,
+				Binding::SingleBound(1, 0),
+			)
+			.unwrap()
+	});
+	let texel = f32::NEG_INFINITY.to_ne_bytes();
+	app
+		.buffer_set()
+		.write_texture_region(texture, UVec2::new(2, 1), UVec2::ONE, &texel, TextureWriteTarget::Both)
+		.unwrap();
+	let check = |buffer, element| ComputeStep {
+		schedule: StepSchedule::EveryIteration,
+		run_if: None,
+		action: ComputeAction::CheckFinite { buffer, element },
+	};
+	let tasks = vec![ComputeTask {
+		label: Some("Simulate".to_owned()),
+		iterations: NonZeroU32::new(3),
+		run_mode: TaskRunMode::Continuous,
+		budget: None,
+		submission: TaskSubmission::WithFrame,
+		start_after: None,
+		iteration_buffer: None,
+		steps: vec![
+			check(clean, FiniteCheckType::Buffer),
+			check(corrupt, FiniteCheckType::Buffer),
+			check(texture, FiniteCheckType::Texture),
+		],
+	}];
+	app.run_until_complete(tasks.clone());
+	// The results are read back over the following frames.
+	for _ in 0..100 {
+		app.app().update();
+	}
+	let errors = &app.app().world().resource::<Errors>().0;
+	assert_eq!(errors.len(), 2, "{:?}", errors);
+	for error in errors {
+		let ComputeErrorEvent::NonFiniteValue { sequence, task, label, step, iteration, buffer, index, value } = error
+		else {
+			panic!("Expected a NonFiniteValue error, but got {:?}", error);
+		};
+		assert_eq!((*sequence, *task, label.as_deref(), *iteration), (ComputeSequenceId::MAIN, 0, Some("Simulate"), 1));
+		match step {
+			1 => assert!(*buffer == corrupt && *index == 5 && value.is_nan(), "{:?}", error),
+			2 => assert!(*buffer == texture && *index == 6 && *value == f32::NEG_INFINITY, "{:?}", error),
+			_ => panic!("Step {} shouldn't have found anything", step),
+		}
+	}
+
+	app.app().world_mut().resource_mut::<Errors>().0.clear();
+	app.app().insert_resource(ComputeFiniteChecks(false));
+	app.run_until_complete(tasks);
+	for _ in 0..100 {
+		app.app().update();
+	}
+	assert!(app.app().world().resource::<Errors>().0.is_empty(), "Disabled checks shouldn't report anything");
+}
+
+#[test]
+fn stores_srgb_textures_in_the_linear_format_with_an_srgb_view() {
+	let Some(mut app) = ComputeTestApp::new() else {
+		return;
+	};
+	let data = app.add_storage_init(vec![1.0f32, 2.0], Binding::SingleBound(0, 0));
+	let world = app.app().world_mut();
+	let srgb_views = world.resource::<ComputeCapabilities>().srgb_views;
+	let texture = world.resource_scope(|world, mut images: Mut<Assets<Image>>| {
+		let mut add = || {
+			world
+				.resource_mut::<ShaderBufferSet>()
+				.add_texture_fill(
+					&mut images,
+					4,
+					4,
+					TextureFormat::Rgba8UnormSrgb,
+					&[255, 128, 0, 255],
+					StorageTextureAccess::WriteOnly,
+					Binding::SingleBound(2, 0),
+				)
+				.unwrap()
+		};
+		if srgb_views {
+			Some(add())
+		} else {
+			assert!(catch_unwind(AssertUnwindSafe(add)).is_err());
+			None
+		}
+	});
+	let Some(texture) = texture else {
+		return;
+	};
+	let image_handle = app.buffer_set().image_handle(texture).unwrap();
+	let images = app.app().world().resource::<Assets<Image>>();
+	let image = images.get(&image_handle).unwrap();
+	assert_eq!(image.texture_descriptor.format, TextureFormat::Rgba8Unorm);
+	assert_eq!(image.texture_view_descriptor.as_ref().unwrap().format, Some(TextureFormat::Rgba8UnormSrgb));
+	assert!(app.buffer_set().debug_dump().contains("sRGB view"));
+	// The bind group has to bind a linear view of the texture for this to run at all.
+	app.run_until_complete(vec![ComputeTask {
+		label: None,
+		iterations: NonZeroU32::new(1),
+		run_mode: TaskRunMode::Continuous,
+		budget: None,
+		submission: TaskSubmission::WithFrame,
+		start_after: None,
+		iteration_buffer: None,
+		steps: vec![run_shader("increment")],
+	}]);
+	assert_buffer_approx_eq(&app.read_buffer::<f32>(data), &[2.0, 3.0], 1e-6);
+}
+
+#[test]
+fn binds_custom_texture_views() {
+	let Some(mut app) = ComputeTestApp::new() else {
+		return;
+	};
+	let data = app.add_storage_init(vec![1.0f32, 2.0], Binding::SingleBound(0, 0));
+	let world = app.app().world_mut();
+	world.resource_scope(|world, mut images: Mut<Assets<Image>>| {
+		let mut buffer_set = world.resource_mut::<ShaderBufferSet>();
+		let texture = buffer_set
+			.add_texture_fill(
+				&mut images,
+				4,
+				4,
+				TextureFormat::Rgba8Unorm,
+				&[0, 0, 0, 255],
+				StorageTextureAccess::WriteOnly,
+				Binding::SingleBound(2, 0),
+			)
+			.unwrap();
+		let view = TextureViewSpec { mip_level_count: Some(1), array_layer_count: Some(1), ..Default::default() };
+		buffer_set.set_texture_view(texture, Some(view));
+	});
+	app.run_until_complete(vec![ComputeTask {
+		label: None,
+		iterations: NonZeroU32::new(1),
+		run_mode: TaskRunMode::Continuous,
+		budget: None,
+		submission: TaskSubmission::WithFrame,
+		start_after: None,
+		iteration_buffer: None,
+		steps: vec![run_shader("increment")],
+	}]);
+	assert_buffer_approx_eq(&app.read_buffer::<f32>(data), &[2.0, 3.0], 1e-6);
+}
+
+#[test]
+fn binds_texture_arrays_and_rebuilds_pipelines_when_resized() {
+	let Some(mut app) = ComputeTestApp::new() else {
+		return;
+	};
+	let data = app.add_storage_init(vec![0.0f32; 2], Binding::SingleBound(0, 0));
+	let world = app.app().world_mut();
+	if !world.resource::<ComputeCapabilities>().texture_binding_arrays {
+		return;
+	}
+	let render_device = world.resource::<RenderDevice>().clone();
+	let (array, images) = world.resource_scope(|world, mut images: Mut<Assets<Image>>| {
+		let images = [51, 255, 102]
+			.map(|red| {
+				images.add(Image::new_fill(
+					Extent3d { width: 1, height: 1, depth_or_array_layers: 1 },
+					TextureDimension::D2,
+					&[red, 0, 0, 255],
+					TextureFormat::Rgba8Unorm,
+					RenderAssetUsages::RENDER_WORLD,
+				))
+			})
+			.to_vec();
+		let array = world
+			.resource_mut::<ShaderBufferSet>()
+			.add_texture_array_binding(
+				&render_device,
+				images[..2].to_vec(),
+				TextureSampleType::Float { filterable: true },
+				Binding::SingleBound(0, 1),
+			)
+			.unwrap();
+		(array, images)
+	});
+	world.send_event(StartComputeEvent {
+		sequence: ComputeSequenceId::MAIN,
+		tasks: vec![ComputeTask {
+			label: None,
+			iterations: NonZeroU32::new(20),
+			run_mode: TaskRunMode::Continuous,
+			budget: None,
+			submission: TaskSubmission::WithFrame,
+			start_after: None,
+			iteration_buffer: None,
+			steps: vec![ComputeStep {
+				schedule: StepSchedule::EveryIteration,
+				run_if: None,
+				action: ComputeAction::RunShader {
+					shader: "shaders/test_texture_array.wgsl".to_owned(),
+					entry_point: "load_red".to_owned(),
+					shader_defs: Vec::new(),
+					overrides: Vec::new(),
+					dispatch: DispatchSize::Workgroups { x: 1, y: 1, z: 1 },
+					auto_swap: Vec::new(),
+					skip_if_empty: None,
+					buffer_orientation: Vec::new(),
+					push_constants: None,
+					dynamic_offsets: Vec::new(),
+				},
+			}],
+		}],
+		iteration_buffer: None,
+		seed: 0,
+	});
+	// Resizing the array partway through the task changes the layout, which would
+	// fail validation if the pipelines weren't rebuilt.
+	let mut resized = false;
+	for _ in 0..1000 {
+		app.app().update();
+		let state = app.app().world().resource::<ComputeState>().clone();
+		if !resized && matches!(state, ComputeState::Running { .. }) {
+			app.buffer_set().set_texture_array(array, images.clone());
+			resized = true;
+		}
+		if state == ComputeState::Finished {
+			break;
+		}
+	}
+	assert!(resized);
+	assert_eq!(app.buffer_set().layout_entry_for(array).unwrap()[0].count, NonZeroU32::new(3));
+	assert_buffer_approx_eq(&app.read_buffer::<f32>(data), &[0.2, 1.0], 1e-6);
+}
+
+#[test]
+fn samples_textures_and_checks_their_sample_types() {
+	let Some(mut app) = ComputeTestApp::new() else {
+		return;
+	};
+	app.app().init_resource::<ValidationReports>().add_systems(
+		Update,
+		|mut events: EventReader<ComputeValidationReport>, mut reports: ResMut<ValidationReports>| {
+			reports.0.extend(events.read().cloned())
+		},
+	);
+	let data = app.add_storage_init(vec![0.0f32; 3], Binding::SingleBound(0, 0));
+	let world = app.app().world_mut();
+	let render_device = world.resource::<RenderDevice>().clone();
+	world.resource_scope(|world, mut images: Mut<Assets<Image>>| {
+		let mut image = |width, pixel: &[u8], format| {
+			images.add(Image::new_fill(
+				Extent3d { width, height: 1, depth_or_array_layers: 1 },
+				TextureDimension::D2,
+				pixel,
+				format,
+				RenderAssetUsages::RENDER_WORLD,
+			))
+		};
+		let mut color = image(2, &[0, 0, 0, 255], TextureFormat::Rgba8Unorm);
+		let heights = image(1, &2.5f32.to_le_bytes(), TextureFormat::R32Float);
+		let counts = image(1, &7u32.to_le_bytes(), TextureFormat::R32Uint);
+		let mut buffer_set = world.resource_mut::<ShaderBufferSet>();
+		// Make the second texel white, so sampling halfway between the two gives grey.
+		images.get_mut(&mut color).unwrap().data[4] = 255;
+		buffer_set
+			.add_sampled_texture(color, TextureSampleType::Float { filterable: true }, Binding::SingleBound(0, 1))
+			.unwrap();
+		let descriptor =
+			SamplerDescriptor { mag_filter: FilterMode::Linear, min_filter: FilterMode::Linear, ..Default::default() };
+		buffer_set.add_sampler(&render_device, &descriptor, Binding::SingleBound(0, 2)).unwrap();
+		buffer_set
+			.add_sampled_texture(heights, TextureSampleType::Float { filterable: false }, Binding::SingleBound(0, 3))
+			.unwrap();
+		buffer_set.add_sampled_texture(counts.clone(), TextureSampleType::Uint, Binding::SingleBound(0, 4)).unwrap();
+		buffer_set.add_sampled_texture(counts, TextureSampleType::Uint, Binding::SingleBound(1, 0)).unwrap();
+	});
+	let step = |entry_point: &str| ComputeStep {
+		schedule: StepSchedule::EveryIteration,
+		run_if: None,
+		action: ComputeAction::RunShader {
+			shader: "shaders/test_sampled_textures.wgsl".to_owned(),
+			entry_point: entry_point.to_owned(),
+			shader_defs: Vec::new(),
+			overrides: Vec::new(),
+			dispatch: ONE_WORKGROUP,
+			auto_swap: Vec::new(),
+			skip_if_empty: None,
+			buffer_orientation: Vec::new(),
+			push_constants: None,
+			dynamic_offsets: Vec::new(),
+		},
+	};
+	let task = |steps| ComputeTask {
+		label: None,
+		iterations: NonZeroU32::new(1),
+		run_mode: TaskRunMode::Continuous,
+		budget: None,
+		submission: TaskSubmission::WithFrame,
+		start_after: None,
+		iteration_buffer: None,
+		steps,
+	};
+	app.app().world_mut().send_event(ValidateComputeEvent {
+		tasks: vec![task(vec![step("read_textures"), step("read_signed")])],
+		iteration_buffer: None,
+	});
+	for _ in 0..1000 {
+		app.app().update();
+		if !app.app().world().resource::<ValidationReports>().0.is_empty() {
+			break;
+		}
+	}
+	let findings = &app.app().world().resource::<ValidationReports>().0[0].findings;
+	assert_eq!(findings.iter().map(|finding| finding.step).collect::<Vec<_>>(), vec![Some(1)]);
+	assert!(findings[0].message.contains("sample type Uint"));
+	app.run_until_complete(vec![task(vec![step("read_textures")])]);
+	assert_buffer_approx_eq(&app.read_buffer::<f32>(data), &[0.5, 2.5, 7.0], 0.01);
+}
+
+#[test]
+fn rebuilds_pipelines_when_a_layout_changes_mid_task() {
+	let Some(mut app) = ComputeTestApp::new() else {
+		return;
+	};
+	let data = app.add_storage_init(vec![0.0f32; 2], Binding::SingleBound(0, 0));
+	app.app().world_mut().send_event(StartComputeEvent {
+		sequence: ComputeSequenceId::MAIN,
+		tasks: vec![ComputeTask {
+			label: None,
+			iterations: NonZeroU32::new(20),
+			run_mode: TaskRunMode::Continuous,
+			budget: None,
+			submission: TaskSubmission::WithFrame,
+			start_after: None,
+			iteration_buffer: None,
+			steps: vec![run_shader("increment")],
+		}],
+		iteration_buffer: None,
+		seed: 0,
+	});
+	let mut changed = false;
+	for _ in 0..1000 {
+		app.app().update();
+		let state = app.app().world().resource::<ComputeState>().clone();
+		if !changed && matches!(state, ComputeState::Running { .. }) {
+			app.buffer_set().set_visibility(data, ShaderStages::FRAGMENT);
+			changed = true;
+		}
+		if state == ComputeState::Finished {
+			break;
+		}
+	}
+	assert!(changed);
+	assert_buffer_approx_eq(&app.read_buffer::<f32>(data), &[20.0, 20.0], 1e-6);
+}
+
+#[test]
+fn sets_single_elements_of_padded_arrays() {
+	let Some(mut app) = ComputeTestApp::new() else {
+		return;
+	};
+	// A vec3 takes up 16 bytes in an array, so each element is followed by a float of padding.
+	let data = app.add_storage_init(vec![Vec3::ZERO; 4], Binding::SingleBound(0, 0));
+	app.buffer_set().write_buffer_element(data, 1, Vec3::new(1.0, 2.0, 3.0)).unwrap();
+	app.buffer_set().write_buffer_elements(data, 2, &[Vec3::splat(4.0), Vec3::splat(5.0)]).unwrap();
+	app.assert_buffer_eq(data, &[0.0f32, 0.0, 0.0, 0.0, 1.0, 2.0, 3.0, 0.0, 4.0, 4.0, 4.0, 0.0, 5.0, 5.0, 5.0, 0.0]);
+
+	let error = app.buffer_set().write_buffer_elements(data, 3, &[Vec3::ONE, Vec3::ONE]).unwrap_err();
+	assert!(matches!(error, ShaderBufferError::OutOfRange { start: 3, count: 2, capacity: 4, .. }), "{}", error);
+}
+
+#[test]
+fn writes_at_byte_offsets_into_both_halves() {
+	let Some(mut app) = ComputeTestApp::new() else {
+		return;
+	};
+	let data = app.add_storage_init(vec![0.0f32; 4], Binding::SingleBound(0, 0));
+	let double = app.add_storage_init(vec![0.0f32; 4], Binding::Double(0, (1, 2)));
+	app.buffer_set().write_buffer_at(data, 4, Vec2::new(1.0, 2.0)).unwrap();
+	app.buffer_set().write_buffer_bytes(data, 12, bytemuck::bytes_of(&3.0f32)).unwrap();
+	app.assert_buffer_eq(data, &[0.0f32, 1.0, 2.0, 3.0]);
+
+	// The back half of the double buffer is written too, as the snapshot after the swap reads it.
+	app.buffer_set().write_buffer_bytes(double, 4, bytemuck::cast_slice(&[5.0f32, 6.0])).unwrap();
+	let task = |steps| ComputeTask {
+		label: None,
+		iterations: NonZeroU32::new(1),
+		run_mode: TaskRunMode::Continuous,
+		budget: None,
+		submission: TaskSubmission::WithFrame,
+		start_after: None,
+		iteration_buffer: None,
+		steps,
+	};
+	let swap = ComputeStep {
+		schedule: StepSchedule::EveryIteration,
+		run_if: None,
+		action: ComputeAction::SwapBuffers { buffer: double },
+	};
+	app.run_until_complete(vec![task(vec![swap]), task(vec![run_shader("snapshot")])]);
+	app.assert_buffer_eq(data, &[0.0f32, 5.0, 6.0, 0.0]);
+
+	// Writes past the end, or that aren't aligned, fail without writing anything.
+	let bad = [(12, 8), (2, 4), (4, 2), (u64::MAX - 3, 4)];
+	for (offset, size) in bad {
+		let error = app.buffer_set().write_buffer_bytes(data, offset, &vec![0xff; size]).unwrap_err();
+		assert!(
+			matches!(error, ShaderBufferError::BadWriteRange { offset: o, size: s, capacity: 16, .. } if o == offset && s == size as u64),
+			"{}",
+			error
+		);
+	}
+	assert!(matches!(
+		app.buffer_set().write_buffer_at(data, 16, 1.0f32),
+		Err(ShaderBufferError::BadWriteRange { offset: 16, size: 4, .. })
+	));
+	app.assert_buffer_eq(data, &[0.0f32, 5.0, 6.0, 0.0]);
+}
+
+#[test]
+fn queues_writes_in_order_without_changing_the_buffer_set() {
+	let Some(mut app) = ComputeTestApp::new() else {
+		return;
+	};
+	let data = app.add_storage_init(vec![0u32; 4], Binding::SingleBound(0, 0));
+	app.app().update();
+
+	app.buffer_set().write_buffer(data, vec![1u32, 2, 3, 4]).unwrap();
+	let queue = app.app().world().resource::<BufferWriteQueue>().clone();
+	queue.queue_write(data, bytemuck::cast_slice(&[5u32, 6]));
+	// Writes that don't fit, or aren't aligned, are dropped once they're made.
+	queue.queue_write(data, &[0xff; 3]);
+	queue.queue_write(data, &[0xff; 20]);
+	assert!(!app.app().world().resource_ref::<ShaderBufferSet>().is_changed());
+	app.assert_buffer_eq(data, &[5u32, 6, 3, 4]);
+
+	// A write to a buffer deleted after it was queued is dropped too.
+	queue.queue_write(data, bytemuck::cast_slice(&[7u32]));
+	app.app().world_mut().resource_scope(|world, mut images: Mut<Assets<Image>>| {
+		world.resource_mut::<ShaderBufferSet>().delete_buffer(data, &mut images).unwrap();
+	});
+	app.app().update();
+}
+
+#[test]
+fn returns_errors_for_missing_buffers_and_the_wrong_kind() {
+	let Some(mut app) = ComputeTestApp::new() else {
+		return;
+	};
+	let data = app.add_storage_init(vec![0.0f32; 4], Binding::SingleBound(0, 0));
+	let texture = app.app().world_mut().resource_scope(|world, mut images: Mut<Assets<Image>>| {
+		world
+			.resource_mut::<ShaderBufferSet>()
+			.add_texture_fill(
+				&mut images,
+				4,
+				4,
+				TextureFormat::R32Float,
+				&0.0f32.to_ne_bytes(),
+				StorageTextureAccess::ReadOnly,
+				Binding::SingleBound(0, 1),
+			)
+			.unwrap()
+	});
+	assert!(matches!(
+		app.buffer_set().write_buffer(texture, vec![1.0f32; 16]),
+		Err(ShaderBufferError::WrongBufferKind { expected: "a storage or uniform buffer", .. })
+	));
+	assert!(matches!(
+		app.buffer_set().write_back_buffer(data, vec![1.0f32; 4]),
+		Err(ShaderBufferError::WrongBufferKind { expected: "a double buffered storage or uniform buffer", .. })
+	));
+	assert!(matches!(
+		app.buffer_set().write_buffer(data, vec![1.0f32; 5]),
+		Err(ShaderBufferError::TooLarge { size: 20, capacity: 16, .. })
+	));
+
+	// Nothing that failed was written.
+	app.assert_buffer_eq(data, &[0.0f32; 4]);
+
+	app.app().world_mut().resource_scope(|world, mut images: Mut<Assets<Image>>| {
+		let mut buffer_set = world.resource_mut::<ShaderBufferSet>();
+		buffer_set.delete_buffer(data, &mut images).unwrap();
+		assert_eq!(buffer_set.delete_buffer(data, &mut images), Err(ShaderBufferError::MissingBuffer { buffer: data }));
+	});
+	assert_eq!(
+		app.buffer_set().write_buffer(data, vec![1.0f32; 4]),
+		Err(ShaderBufferError::MissingBuffer { buffer: data })
+	);
+}
+
+#[derive(Resource, Default)]
+struct ReadbackSizes(Vec<usize>);
+
+#[test]
+fn resizes_storage_buffers_keeping_their_handles() {
+	let Some(mut app) = ComputeTestApp::new() else {
+		return;
+	};
+	let data = app.add_storage_init(vec![1.0f32; 16], Binding::SingleBound(0, 0));
+	let double = app.add_storage_init(vec![0.0f32; 16], Binding::Double(0, (1, 2)));
+	let render_device = app.app().world().resource::<RenderDevice>().clone();
+	let render_queue = app.app().world().resource::<RenderQueue>().clone();
+	app.app().init_resource::<ReadbackSizes>().add_systems(
+		Update,
+		move |mut events: EventReader<CopyBufferEvent>, mut sizes: ResMut<ReadbackSizes>| {
+			sizes.0.extend(events.read().filter(|event| event.buffer == data).map(|event| event.data.len()))
+		},
+	);
+	app.app().world_mut().send_event(StartComputeEvent {
+		sequence: ComputeSequenceId::MAIN,
+		tasks: vec![ComputeTask {
+			label: None,
+			iterations: None,
+			run_mode: TaskRunMode::Continuous,
+			budget: None,
+			submission: TaskSubmission::WithFrame,
+			start_after: None,
+			iteration_buffer: None,
+			steps: vec![
+				run_shader("increment"),
+				ComputeStep {
+					schedule: StepSchedule::EveryIteration,
+					run_if: None,
+					action: ComputeAction::CopyBuffer { src: data, offset: 0, size: None },
+				},
+			],
+		}],
+		iteration_buffer: None,
+		seed: 0,
+	});
+	for _ in 0..MAX_FRAMES {
+		app.app().update();
+		if app.app().world().resource::<ComputeProgress>().iterations >= 2 {
+			break;
+		}
+	}
+
+	// The running task carries on with the same handle, reading back the new size.
+	app.buffer_set().resize_storage(data, 128, &render_device, &render_queue, true).unwrap();
+	for _ in 0..10 {
+		app.app().update();
+	}
+	app.app().world_mut().send_event(StopComputeEvent(ComputeSequenceId::MAIN));
+	app.app().update();
+	let sizes = &app.app().world().resource::<ReadbackSizes>().0;
+	assert_eq!(sizes.first(), Some(&64));
+	assert_eq!(sizes.last(), Some(&128));
+
+	// The old contents were kept, and the rest started out zeroed.
+	let values = app.read_buffer::<f32>(data);
+	assert_eq!(values.len(), 32);
+	assert!(values[16] >= 1.0);
+	assert!(values[0] > values[16] + 1.0);
+	assert!(values[..16].iter().all(|&value| value == values[0]));
+	assert!(values[16..].iter().all(|&value| value == values[16]));
+
+	// Both halves of a double buffer are resized.
+	app.buffer_set().resize_storage(double, 128, &render_device, &render_queue, false).unwrap();
+	app.run_until_complete(vec![
+		ComputeTask {
+			label: None,
+			iterations: NonZeroU32::new(3),
+			run_mode: TaskRunMode::Continuous,
+			budget: None,
+			submission: TaskSubmission::WithFrame,
+			start_after: None,
+			iteration_buffer: None,
+			steps: vec![
+				run_shader("advance"),
+				ComputeStep {
+					schedule: StepSchedule::EveryIteration,
+					run_if: None,
+					action: ComputeAction::SwapBuffers { buffer: double },
+				},
+			],
+		},
+		ComputeTask {
+			label: None,
+			iterations: NonZeroU32::new(1),
+			run_mode: TaskRunMode::Continuous,
+			budget: None,
+			submission: TaskSubmission::WithFrame,
+			start_after: None,
+			iteration_buffer: None,
+			steps: vec![run_shader("snapshot")],
+		},
+	]);
+	app.assert_buffer_eq(data, &[3.0f32; 32]);
+
+	// Keeping the contents needs them to be copied out of the old buffer.
+	let uncopyable = app
+		.buffer_set()
+		.add_storage_zeroed(&render_device, 16, BufferUsages::STORAGE, Binding::SingleBound(1, 0), false)
+		.unwrap();
+	assert!(matches!(
+		app.buffer_set().resize_storage(uncopyable, 32, &render_device, &render_queue, true),
+		Err(ShaderBufferError::WrongBufferKind { expected: "a storage buffer made with BufferUsages::COPY_SRC", .. })
+	));
+	app.buffer_set().resize_storage(uncopyable, 32, &render_device, &render_queue, false).unwrap();
+}
+
+#[test]
+fn reads_back_parts_of_resized_storage_buffers() {
+	#[derive(Resource, Default)]
+	struct Sizes(Vec<(ShaderBufferHandle, usize)>);
+
+	let Some(mut app) = ComputeTestApp::new() else {
+		return;
+	};
+	let middle = app.add_storage_init(vec![1.0f32; 16], Binding::SingleBound(0, 0));
+	let tail = app.add_storage_init(vec![1.0f32; 16], Binding::SingleBound(0, 1));
+	let render_device = app.app().world().resource::<RenderDevice>().clone();
+	let render_queue = app.app().world().resource::<RenderQueue>().clone();
+	app.app().init_resource::<Sizes>().add_systems(
+		Update,
+		|mut events: EventReader<CopyBufferEvent>, mut sizes: ResMut<Sizes>| {
+			sizes.0.extend(events.read().map(|event| (event.buffer, event.data.len())))
+		},
+	);
+	let copy = |src, offset, size| ComputeStep {
+		schedule: StepSchedule::EveryIteration,
+		run_if: None,
+		action: ComputeAction::CopyBuffer { src, offset, size },
+	};
+	app.app().world_mut().send_event(StartComputeEvent {
+		sequence: ComputeSequenceId::MAIN,
+		tasks: vec![ComputeTask {
+			label: None,
+			iterations: None,
+			run_mode: TaskRunMode::Continuous,
+			budget: None,
+			submission: TaskSubmission::WithFrame,
+			start_after: None,
+			iteration_buffer: None,
+			steps: vec![copy(middle, 8, Some(16)), copy(tail, 48, None)],
+		}],
+		iteration_buffer: None,
+		seed: 0,
+	});
+	for _ in 0..10 {
+		app.app().update();
+	}
+
+	// A part with a size stays that size, and a part that runs to the end grows with the buffer.
+	app.buffer_set().resize_storage(middle, 128, &render_device, &render_queue, true).unwrap();
+	app.buffer_set().resize_storage(tail, 128, &render_device, &render_queue, true).unwrap();
+	for _ in 0..10 {
+		app.app().update();
+	}
+	let last_size = |app: &mut ComputeTestApp, buffer| {
+		app.app().world().resource::<Sizes>().0.iter().rev().find(|(handle, _)| *handle == buffer).map(|&(_, size)| size)
+	};
+	assert_eq!(last_size(&mut app, middle), Some(16));
+	assert_eq!(last_size(&mut app, tail), Some(80));
+	assert!(app.app().world().resource::<Sizes>().0.contains(&(tail, 16)));
+
+	// A part that no longer fits isn't read back, but doesn't stop the task.
+	app.buffer_set().resize_storage(middle, 16, &render_device, &render_queue, true).unwrap();
+	for _ in 0..5 {
+		app.app().update();
+	}
+	app.app().world_mut().resource_mut::<Sizes>().0.clear();
+	for _ in 0..5 {
+		app.app().update();
+	}
+	let sizes = &app.app().world().resource::<Sizes>().0;
+	assert!(sizes.iter().all(|(handle, _)| *handle == tail));
+	assert!(!sizes.is_empty());
+	app.app().world_mut().send_event(StopComputeEvent(ComputeSequenceId::MAIN));
+	app.app().update();
+}
+
+#[test]
+fn replays_recorded_writes_at_the_same_iterations() {
+	let Some(mut app) = ComputeTestApp::new() else {
+		return;
+	};
+	let data = app.add_storage_init(vec![0.0f32; 4], Binding::SingleBound(0, 0));
+	let agent = app.add_storage_init(vec![0.0f32; 4], Binding::SingleBound(1, 0));
+	let task = || ComputeTask {
+		label: None,
+		iterations: NonZeroU32::new(30),
+		run_mode: TaskRunMode::Continuous,
+		budget: None,
+		submission: TaskSubmission::WithFrame,
+		start_after: None,
+		iteration_buffer: None,
+		steps: vec![run_shader("advance_agent")],
+	};
+
+	// Write to the buffer every few frames, at whichever iteration that happens to land.
+	app.buffer_set().start_recording();
+	app.app().world_mut().send_event(StartComputeEvent {
+		sequence: ComputeSequenceId::MAIN,
+		tasks: vec![task()],
+		iteration_buffer: None,
+		seed: 0,
+	});
+	for frame in 1..1000 {
+		if frame % 7 == 0 {
+			app.buffer_set().write_buffer(data, vec![frame as f32; 4]).unwrap();
+		}
+		app.app().update();
+		if *app.app().world().resource::<ComputeState>() == ComputeState::Finished {
+			break;
+		}
+	}
+	let trace = app.buffer_set().stop_recording().unwrap();
+	assert!(trace.entries.iter().any(|entry| matches!(entry.event, TraceEvent::Write { .. })));
+	let live = app.read_buffer::<f32>(agent);
+	assert!(live[0] > 0.0);
+
+	// Wait a few frames before the replay, so it can't line up with the original by chance.
+	app.buffer_set().write_buffer(data, vec![0.0f32; 4]).unwrap();
+	app.buffer_set().write_buffer(agent, vec![0.0f32; 4]).unwrap();
+	app.buffer_set().replay(trace.clone());
+	app.buffer_set().start_recording();
+	for _ in 0..5 {
+		app.app().update();
+	}
+	app.run_until_complete(vec![task()]);
+	let replayed = app.buffer_set().stop_recording().unwrap();
+	assert!(!app.buffer_set().replaying());
+	assert_eq!(replayed, trace);
+	assert_eq!(app.read_buffer::<f32>(agent), live);
+}
+
+#[cfg(feature = "checkpoint")]
+#[derive(Resource, Default)]
+struct CheckpointEvents {
+	saved: Vec<BuffersSavedEvent>,
+	loaded: Vec<BuffersLoadedEvent>,
+	failed: Vec<CheckpointFailedEvent>,
+}
+
+#[cfg(feature = "checkpoint")]
+#[test]
+fn saves_buffers_to_checkpoints_and_loads_them_back() {
+	let Some(mut app) = ComputeTestApp::new() else {
+		return;
+	};
+	app.app().init_resource::<CheckpointEvents>().add_systems(
+		Update,
+		|mut saved: EventReader<BuffersSavedEvent>,
+		 mut loaded: EventReader<BuffersLoadedEvent>,
+		 mut failed: EventReader<CheckpointFailedEvent>,
+		 mut events: ResMut<CheckpointEvents>| {
+			events.saved.extend(saved.read().cloned());
+			events.loaded.extend(loaded.read().cloned());
+			events.failed.extend(failed.read().cloned());
+		},
+	);
+	let values = app.add_storage_init(vec![1.0f32, 2.0, 3.0, 4.0], Binding::SingleBound(0, 0));
+	let texture = app.app().world_mut().resource_scope(|world, mut images: Mut<Assets<Image>>| {
+		world
+			.resource_mut::<ShaderBufferSet>()
+			.add_texture_fill(
+				&mut images,
+				2,
+				1,
+				TextureFormat::Rgba8Unorm,
+				&[0; 4],
+				StorageTextureAccess::ReadOnly,
+				Binding::Double(0, (1, 2)),
+			)
+			.unwrap()
+	});
+	app.buffer_set().name_buffer(values, "values");
+	app.buffer_set().name_buffer(texture, "texture");
+	let swap = |app: &mut ComputeTestApp| {
+		app.run_until_complete(vec![ComputeTask {
+			label: None,
+			iterations: NonZeroU32::new(1),
+			run_mode: TaskRunMode::Continuous,
+			budget: None,
+			submission: TaskSubmission::WithFrame,
+			start_after: None,
+			iteration_buffer: None,
+			steps: vec![ComputeStep {
+				schedule: StepSchedule::EveryIteration,
+				run_if: None,
+				action: ComputeAction::SwapBuffers { buffer: texture },
+			}],
+		}]);
+	};
+	let tile = [[1u8, 2, 3, 4], [5, 6, 7, 8]];
+	app
+		.buffer_set()
+		.write_texture_region(texture, UVec2::ZERO, UVec2::new(2, 1), bytemuck::cast_slice(&tile), TextureWriteTarget::Back)
+		.unwrap();
+	// Loads happen in the same schedule as the events are collected, so they may take two frames to show up.
+	let load = |app: &mut ComputeTestApp| {
+		app.app().update();
+		app.app().update();
+	};
+	swap(&mut app);
+
+	let path = std::env::temp_dir().join(format!("bevy_compute_checkpoint_{}.bin", std::process::id()));
+	app.app().world_mut().send_event(SaveBuffersEvent { handles: vec![values, texture], path: path.clone() });
+	for _ in 0..100 {
+		app.app().update();
+		if !app.app().world().resource::<CheckpointEvents>().saved.is_empty() {
+			break;
+		}
+	}
+	let saved = &app.app().world().resource::<CheckpointEvents>().saved;
+	assert_eq!(saved.len(), 1);
+	assert_eq!(
+		saved[0]
+			.entries
+			.iter()
+			.map(|entry| (entry.name.as_str(), entry.double_buffered, entry.front_is_second, entry.bytes))
+			.collect::<Vec<_>>(),
+		vec![("values", false, false, 16), ("texture", true, true, 16)]
+	);
+
+	// Overwrite everything, and put the other half of the texture in front, so the load has to undo it all.
+	app.buffer_set().write_buffer(values, vec![0.0f32; 4]).unwrap();
+	app
+		.buffer_set()
+		.write_texture_region(texture, UVec2::ZERO, UVec2::new(2, 1), &[9; 8], TextureWriteTarget::Both)
+		.unwrap();
+	swap(&mut app);
+	app.app().world_mut().send_event(LoadBuffersEvent { path: path.clone() });
+	load(&mut app);
+	assert_eq!(app.app().world().resource::<CheckpointEvents>().loaded.len(), 1);
+	assert_buffer_approx_eq(&app.read_buffer::<f32>(values), &[1.0, 2.0, 3.0, 4.0], 0.0);
+	assert_eq!(app.read_buffer::<[u8; 4]>(texture), tile.to_vec());
+
+	// A checkpoint only loads into buffers that match it, and nothing is loaded if any don't.
+	app.buffer_set().name_buffer(values, "renamed");
+	app.app().world_mut().send_event(LoadBuffersEvent { path: path.clone() });
+	load(&mut app);
+	let failed = &app.app().world().resource::<CheckpointEvents>().failed;
+	assert!(matches!(
+		&failed[..],
+		[CheckpointFailedEvent { error: CheckpointError::Entries(errors), .. }]
+			if errors.len() == 1 && errors[0].name == "values" && errors[0].problem == CheckpointEntryProblem::NoSuchBuffer
+	));
+
+	// Files from later versions of the format are rejected.
+	let mut bytes = std::fs::read(&path).unwrap();
+	bytes[8..12].copy_from_slice(&(CHECKPOINT_VERSION + 1).to_le_bytes());
+	std::fs::write(&path, bytes).unwrap();
+	app.app().world_mut().send_event(LoadBuffersEvent { path: path.clone() });
+	load(&mut app);
+	let failed = &app.app().world().resource::<CheckpointEvents>().failed;
+	assert_eq!(failed.last().unwrap().error, CheckpointError::UnsupportedVersion(CHECKPOINT_VERSION + 1));
+	std::fs::remove_file(path).unwrap();
+}
+
+#[cfg(feature = "checkpoint")]
+#[test]
+fn reports_every_buffer_a_checkpoint_cant_save() {
+	let Some(mut app) = ComputeTestApp::new() else {
+		return;
+	};
+	app.app().init_resource::<CheckpointEvents>().add_systems(
+		Update,
+		|mut saved: EventReader<BuffersSavedEvent>,
+		 mut loaded: EventReader<BuffersLoadedEvent>,
+		 mut failed: EventReader<CheckpointFailedEvent>,
+		 mut events: ResMut<CheckpointEvents>| {
+			events.saved.extend(saved.read().cloned());
+			events.loaded.extend(loaded.read().cloned());
+			events.failed.extend(failed.read().cloned());
+		},
+	);
+	let render_device = app.app().world().resource::<RenderDevice>().clone();
+	let named = app.add_storage_init(vec![1.0f32; 4], Binding::SingleBound(0, 0));
+	let unnamed = app.add_storage_init(vec![2.0f32; 4], Binding::SingleBound(0, 1));
+	let uncopyable = app
+		.buffer_set()
+		.add_storage_zeroed(&render_device, 16, BufferUsages::STORAGE, Binding::SingleBound(0, 2), false)
+		.unwrap();
+	let deleted = app.add_storage_init(vec![3.0f32; 4], Binding::SingleUnbound);
+	app.buffer_set().name_buffer(named, "named");
+	app.buffer_set().name_buffer(uncopyable, "uncopyable");
+	app.app().world_mut().resource_scope(|world, mut images: Mut<Assets<Image>>| {
+		world.resource_mut::<ShaderBufferSet>().delete_buffer(deleted, &mut images).unwrap();
+	});
+
+	// Nothing is saved, and every buffer that couldn't be is listed in the one event.
+	let path = std::env::temp_dir().join(format!("bevy_compute_checkpoint_failed_{}.bin", std::process::id()));
+	let handles = vec![named, unnamed, uncopyable, deleted];
+	app.app().world_mut().send_event(SaveBuffersEvent { handles, path: path.clone() });
+	for _ in 0..5 {
+		app.app().update();
+	}
+	let events = app.app().world().resource::<CheckpointEvents>();
+	assert!(events.saved.is_empty());
+	assert_eq!(events.failed.len(), 1);
+	let CheckpointError::Entries(errors) = &events.failed[0].error else {
+		panic!("Expected the failed buffers to be listed, but got {:?}", events.failed[0].error);
+	};
+	assert_eq!(
+		errors.iter().map(|error| (error.name.clone(), error.problem.clone())).collect::<Vec<_>>(),
+		vec![
+			(unnamed.to_string(), CheckpointEntryProblem::Unnamed),
+			("uncopyable".to_owned(), CheckpointEntryProblem::NotCopySrc),
+			(deleted.to_string(), CheckpointEntryProblem::Deleted),
+		]
+	);
+	assert!(!path.exists());
+}
+
+#[cfg(feature = "checkpoint")]
+#[test]
+fn saves_3d_textures_and_texture_arrays_to_checkpoints() {
+	let Some(mut app) = ComputeTestApp::new() else {
+		return;
+	};
+	app.app().init_resource::<CheckpointEvents>().add_systems(
+		Update,
+		|mut saved: EventReader<BuffersSavedEvent>,
+		 mut loaded: EventReader<BuffersLoadedEvent>,
+		 mut failed: EventReader<CheckpointFailedEvent>,
+		 mut events: ResMut<CheckpointEvents>| {
+			events.saved.extend(saved.read().cloned());
+			events.loaded.extend(loaded.read().cloned());
+			events.failed.extend(failed.read().cloned());
+		},
+	);
+	let world = app.app().world_mut();
+	let (volume, layers, spare_volume, spare_layers) = world.resource_scope(|world, mut images: Mut<Assets<Image>>| {
+		let mut buffers = world.resource_mut::<ShaderBufferSet>();
+		let fill = (-1.0f32).to_ne_bytes();
+		let access = StorageTextureAccess::WriteOnly;
+		let format = TextureFormat::R32Float;
+		(
+			buffers.add_texture_fill_3d(&mut images, 3, 2, 2, format, &fill, access, Binding::SingleBound(0, 0)).unwrap(),
+			buffers.add_texture_fill_layers(&mut images, 3, 2, 2, format, &fill, access, Binding::SingleBound(0, 1)).unwrap(),
+			buffers.add_texture_fill_3d(&mut images, 3, 2, 2, format, &fill, access, Binding::SingleUnbound).unwrap(),
+			buffers.add_texture_fill_layers(&mut images, 3, 2, 2, format, &fill, access, Binding::SingleUnbound).unwrap(),
+		)
+	});
+	app.run_until_complete(vec![ComputeTask {
+		label: None,
+		iterations: NonZeroU32::new(1),
+		run_mode: TaskRunMode::Continuous,
+		budget: None,
+		submission: TaskSubmission::WithFrame,
+		start_after: None,
+		iteration_buffer: None,
+		steps: vec![ComputeStep {
+			schedule: StepSchedule::EveryIteration,
+			run_if: None,
+			action: ComputeAction::RunShader {
+				shader: "shaders/test_texture_3d.wgsl".to_owned(),
+				entry_point: "write_coordinates".to_owned(),
+				shader_defs: Vec::new(),
+				overrides: Vec::new(),
+				dispatch: ONE_WORKGROUP,
+				auto_swap: Vec::new(),
+				skip_if_empty: None,
+				buffer_orientation: Vec::new(),
+				push_constants: None,
+				dynamic_offsets: Vec::new(),
+			},
+		}],
+	}]);
+	app.buffer_set().name_buffer(volume, "volume");
+	app.buffer_set().name_buffer(layers, "layers");
+
+	let path = std::env::temp_dir().join(format!("bevy_compute_checkpoint_3d_{}.bin", std::process::id()));
+	app.app().world_mut().send_event(SaveBuffersEvent { handles: vec![volume, layers], path: path.clone() });
+	for _ in 0..100 {
+		app.app().update();
+		if !app.app().world().resource::<CheckpointEvents>().saved.is_empty() {
+			break;
+		}
+	}
+	let saved = &app.app().world().resource::<CheckpointEvents>().saved;
+	assert_eq!(saved.len(), 1);
+	let layout =
+		CheckpointLayout::Texture { format: "R32Float".to_owned(), width: 3, height: 2, depth_or_array_layers: 2 };
+	for entry in &saved[0].entries {
+		assert_eq!((&entry.layout, entry.bytes), (&layout, 48), "{} should be saved with every slice", entry.name);
+	}
+
+	// Loaded into the spare textures, every slice and layer comes back, as it was in the textures that were saved.
+	app.buffer_set().name_buffer(volume, "saved volume");
+	app.buffer_set().name_buffer(layers, "saved layers");
+	app.buffer_set().name_buffer(spare_volume, "volume");
+	app.buffer_set().name_buffer(spare_layers, "layers");
+	app.app().world_mut().send_event(LoadBuffersEvent { path: path.clone() });
+	app.app().update();
+	app.app().update();
+	assert_eq!(app.app().world().resource::<CheckpointEvents>().loaded.len(), 1);
+	let expected = app.read_buffer::<f32>(volume);
+	app.assert_buffer_eq(spare_volume, &expected);
+	app.assert_buffer_eq(spare_layers, &[0.0f32, 1.0, 2.0, 10.0, 11.0, 12.0, 100.0, 101.0, 102.0, 110.0, 111.0, 112.0]);
+	std::fs::remove_file(path).unwrap();
+}
+
+#[derive(Resource, Default)]
+struct ValidationReports(Vec<ComputeValidationReport>);
+
+#[test]
+fn validates_sequences_without_running_them() {
+	let Some(mut app) = ComputeTestApp::new() else {
+		return;
+	};
+	app.app().init_resource::<ValidationReports>().add_systems(
+		Update,
+		|mut events: EventReader<ComputeValidationReport>, mut reports: ResMut<ValidationReports>| {
+			reports.0.extend(events.read().cloned())
+		},
+	);
+	let asset_server = app.app().world().resource::<AssetServer>().clone();
+	app.app().world_mut().resource_mut::<ComputeShaderLibraries>().add(&asset_server, "shaders/test_library.wgsl");
+	let data = app.add_storage_init(vec![0.0f32; 16], Binding::SingleBound(0, 0));
+	let render_device = app.app().world().resource::<RenderDevice>().clone();
+	let render_queue = app.app().world().resource::<RenderQueue>().clone();
+	let readonly = app
+		.buffer_set()
+		.add_storage_init(
+			&render_device,
+			&render_queue,
+			vec![0.0f32; 16],
+			BufferUsages::empty(),
+			Binding::SingleBound(0, 1),
+			true,
+		)
+		.unwrap();
+	// The usages it needs to be bound and set are added, even though none were asked for.
+	let usage = app.buffer_set().gpu_buffer(readonly).unwrap().usage();
+	assert!(usage.contains(BufferUsages::STORAGE | BufferUsages::COPY_DST));
+	let with_action = |step: ComputeStep, f: fn(&mut ComputeAction)| {
+		let mut step = step;
+		f(&mut step.action);
+		step
+	};
+	let imported = ComputeStep {
+		schedule: StepSchedule::EveryIteration,
+		run_if: None,
+		action: ComputeAction::RunShader {
+			shader: "shaders/test_import.wgsl".to_owned(),
+			entry_point: "increment_by_step".to_owned(),
+			shader_defs: vec!["DOUBLE_STEP".into()],
+			overrides: Vec::new(),
+			dispatch: ONE_WORKGROUP,
+			auto_swap: Vec::new(),
+			skip_if_empty: None,
+			buffer_orientation: Vec::new(),
+			push_constants: None,
+			dynamic_offsets: Vec::new(),
+		},
+	};
+	let task = |steps| ComputeTask {
+		label: None,
+		iterations: NonZeroU32::new(1),
+		run_mode: TaskRunMode::Continuous,
+		budget: None,
+		submission: TaskSubmission::WithFrame,
+		start_after: None,
+		iteration_buffer: None,
+		steps,
+	};
+	app.app().world_mut().send_event(ValidateComputeEvent {
+		tasks: vec![task(vec![
+			run_shader("increment"),
+			imported,
+			add_step(&[("STEP", 2.0), ("WORKGROUP_SIZE", 32.0)], DispatchSize::Exact { x: 16, y: 1, z: 1 }),
+		])],
+		iteration_buffer: None,
+	});
+	app.app().world_mut().send_event(ValidateComputeEvent {
+		tasks: vec![task(vec![
+			with_action(run_shader("increment"), |action| {
+				if let ComputeAction::RunShader { shader, .. } = action {
+					*shader = "shaders/missing.wgsl".to_owned();
+				}
+			}),
+			run_shader("nonexistent"),
+			ComputeStep {
+				schedule: StepSchedule::EveryIteration,
+				run_if: None,
+				action: ComputeAction::SwapBuffers { buffer: data },
+			},
+			add_step(&[("WORKGROUP_SIZE", 64.0), ("STPE", 1.0)], ONE_WORKGROUP),
+			with_action(run_shader("advance"), |action| {
+				if let ComputeAction::RunShader { shader_defs, .. } = action {
+					shader_defs.push("WRITABLE_INPUT".into());
+				}
+			}),
+			with_action(run_shader("increment"), |action| {
+				if let ComputeAction::RunShader { dispatch, .. } = action {
+					*dispatch = DispatchSize::Workgroups { x: 100_000_000, y: 1, z: 1 };
+				}
+			}),
+			ComputeStep {
+				schedule: StepSchedule::EveryIteration,
+				run_if: None,
+				action: ComputeAction::PrefixSum { buffer: readonly, element_count: 16 },
+			},
+		])],
+		iteration_buffer: Some(data),
+	});
+	for _ in 0..1000 {
+		app.app().update();
+		if app.app().world().resource::<ValidationReports>().0.len() == 2 {
+			break;
+		}
+	}
+	let reports = &app.app().world().resource::<ValidationReports>().0;
+	assert_eq!(reports.len(), 2);
+	assert_eq!(reports[0].findings, Vec::new());
+	assert!(reports[1].has_errors());
+	assert_eq!(
+		reports[1].findings.iter().map(|finding| (finding.severity, finding.task, finding.step)).collect::<Vec<_>>(),
+		vec![
+			(ValidationSeverity::Error, Some(0), Some(0)),
+			(ValidationSeverity::Error, Some(0), Some(1)),
+			(ValidationSeverity::Error, Some(0), Some(2)),
+			(ValidationSeverity::Warning, Some(0), Some(3)),
+			(ValidationSeverity::Error, Some(0), Some(4)),
+			(ValidationSeverity::Error, Some(0), Some(4)),
+			(ValidationSeverity::Error, Some(0), Some(5)),
+			(ValidationSeverity::Warning, Some(0), Some(6)),
+		]
+	);
+	assert!(reports[1].findings[4].message.contains("read-only"));
+	assert!(reports[1].findings[5].message.contains("no buffer is bound there"));
+	assert!(reports[1].findings[7].message.contains("was added as read-only, but this step writes to it"));
+	assert_eq!(*app.app().world().resource::<ComputeState>(), ComputeState::Idle);
+}
+
+#[test]
+fn labels_the_gpu_resources_of_labeled_buffers() {
+	let Some(mut app) = ComputeTestApp::new() else {
+		return;
+	};
+	let render_device = app.app().world().resource::<RenderDevice>().clone();
+	let render_queue = app.app().world().resource::<RenderQueue>().clone();
+	let values = app
+		.buffer_set()
+		.labeled("values")
+		.add_storage_init(
+			&render_device,
+			&render_queue,
+			vec![1u32, 2, 3, 4],
+			BufferUsages::COPY_SRC,
+			Binding::SingleBound(0, 0),
+			false,
+		)
+		.unwrap();
+	let unlabeled = app.add_storage_init(vec![0u32; 4], Binding::SingleBound(0, 1));
+	let field = app.app().world_mut().resource_scope(|world, mut images: Mut<Assets<Image>>| {
+		world
+			.resource_mut::<ShaderBufferSet>()
+			.labeled("field")
+			.add_texture_fill(
+				&mut images,
+				2,
+				2,
+				TextureFormat::R32Float,
+				&[0; 4],
+				StorageTextureAccess::ReadOnly,
+				Binding::Double(1, (0, 1)),
+			)
+			.unwrap()
+	});
+	let buffers = app.buffer_set();
+	assert_eq!(buffers.buffer_label(values), Some("values"));
+	assert_eq!(buffers.buffer_label(unlabeled), None);
+	assert!(values.describe(&buffers).contains("labeled \"values\""));
+	assert!(buffers.debug_dump().contains("labeled \"field\""));
+	let images = buffers.image_handles(field).unwrap();
+	let assets = app.app().world().resource::<Assets<Image>>();
+	assert_eq!(assets.get(&images.front).unwrap().texture_descriptor.label, Some("field (front)"));
+	assert_eq!(assets.get(&images.back).unwrap().texture_descriptor.label, Some("field (back)"));
+
+	// The labeled buffer and its copy buffer work as any other.
+	assert_eq!(app.read_buffer::<u32>(values), vec![1, 2, 3, 4]);
+}
+
+#[test]
+fn warns_when_a_buffer_consumed_by_rendering_is_double_buffered() {
+	let Some(mut app) = ComputeTestApp::new() else {
+		return;
+	};
+	app.app().init_resource::<ValidationReports>().add_systems(
+		Update,
+		|mut events: EventReader<ComputeValidationReport>, mut reports: ResMut<ValidationReports>| {
+			reports.0.extend(events.read().cloned())
+		},
+	);
+	let single = app.add_storage_init(vec![0.0f32; 16], Binding::SingleBound(0, 0));
+	let double = app.add_storage_init(vec![0.0f32; 16], Binding::Double(0, (1, 2)));
+	app.buffer_set().consume_in_render(single);
+	app.buffer_set().consume_in_render(double);
+	app.buffer_set().consume_in_render(double);
+	assert!(app.buffer_set().consumed_in_render(single));
+	assert!(app.buffer_set().debug_dump().contains("consumed by rendering"));
+	app.app().world_mut().send_event(ValidateComputeEvent { tasks: Vec::new(), iteration_buffer: None });
+	for _ in 0..1000 {
+		app.app().update();
+		if !app.app().world().resource::<ValidationReports>().0.is_empty() {
+			break;
+		}
+	}
+	let reports = &app.app().world().resource::<ValidationReports>().0;
+	assert_eq!(reports.len(), 1);
+	assert_eq!(
+		reports[0].findings.iter().map(|finding| (finding.severity, finding.task, finding.step)).collect::<Vec<_>>(),
+		vec![(ValidationSeverity::Warning, None, None)]
+	);
+	assert!(reports[0].findings[0].message.contains("double buffered"));
+	// Deleting a buffer forgets that it was consumed, as its handle may be reused.
+	let world = app.app().world_mut();
+	world.resource_scope(|world, mut images: Mut<Assets<Image>>| {
+		let mut buffer_set = world.resource_mut::<ShaderBufferSet>();
+		buffer_set.delete_buffer(single, &mut images).unwrap();
+		assert!(!buffer_set.consumed_in_render(single));
+		assert!(buffer_set.consumed_in_render(double));
+	});
+}
+
+#[test]
+fn rejects_buffers_at_bindings_already_taken() {
+	let Some(mut app) = ComputeTestApp::new() else {
+		return;
+	};
+	app.app().init_resource::<ValidationReports>().add_systems(
+		Update,
+		|mut events: EventReader<ComputeValidationReport>, mut reports: ResMut<ValidationReports>| {
+			reports.0.extend(events.read().cloned())
+		},
+	);
+	let single = app.add_storage_init(vec![0.0f32; 16], Binding::SingleBound(0, 2));
+	app.buffer_set().name_buffer(single, "heights");
+	app.add_storage_init(vec![0.0f32; 16], Binding::Double(0, (0, 1)));
+	let bindings =
+		[Binding::SingleBound(0, 2), Binding::SingleBound(0, 1), Binding::Double(0, (3, 2)), Binding::Double(1, (4, 4))];
+	let render_device = app.app().world().resource::<RenderDevice>().clone();
+	let render_queue = app.app().world().resource::<RenderQueue>().clone();
+	let allocated = app.buffer_set().allocated_bytes();
+	let errors = bindings.map(|binding| {
+		app
+			.buffer_set()
+			.add_storage_init(&render_device, &render_queue, vec![0.0f32; 16], BufferUsages::STORAGE, binding, false)
+			.expect_err("Adding a buffer at a binding that's already taken should fail")
+	});
+	let ShaderBufferError::DuplicateBinding { group: 0, binding: 2, existing } = &errors[0] else {
+		panic!("Expected a DuplicateBinding error, got {:?}", errors[0]);
+	};
+	assert!(existing.contains("\"heights\""), "{}", existing);
+	assert!(matches!(errors[1], ShaderBufferError::DuplicateBinding { group: 0, binding: 1, .. }));
+	assert!(matches!(errors[2], ShaderBufferError::DuplicateBinding { group: 0, binding: 2, .. }));
+	assert!(errors[3].to_string().contains("the front buffer of the same double buffer"), "{}", errors[3]);
+	// Nothing was allocated for the buffers that were refused.
+	assert_eq!(app.buffer_set().allocated_bytes(), allocated);
+	// The same binding in another group is free.
+	app.add_storage_init(vec![0.0f32; 16], Binding::SingleBound(2, 2));
+
+	// Group 1 has been skipped, which the validation warns about.
+	app.app().world_mut().send_event(ValidateComputeEvent { tasks: Vec::new(), iteration_buffer: None });
+	for _ in 0..1000 {
+		app.app().update();
+		if !app.app().world().resource::<ValidationReports>().0.is_empty() {
+			break;
+		}
+	}
+	let reports = &app.app().world().resource::<ValidationReports>().0;
+	assert_eq!(reports.len(), 1);
+	assert_eq!(
+		reports[0].findings.iter().map(|finding| (finding.severity, finding.task, finding.step)).collect::<Vec<_>>(),
+		vec![(ValidationSeverity::Warning, None, None)]
+	);
+	assert!(reports[0].findings[0].message.contains("Group 1 has no buffers"));
+}
+
+#[test]
+#[should_panic(expected = "already used by \"heights\" (storage, 64 B, group 0 binding 0)")]
+fn names_the_buffer_already_using_a_name() {
+	let Some(mut app) = ComputeTestApp::new() else {
+		panic!("already used by \"heights\" (storage, 64 B, group 0 binding 0), but there's no GPU to check it on");
+	};
+	let heights = app.add_storage_init(vec![0.0f32; 16], Binding::SingleBound(0, 0));
+	let other = app.add_storage_init(vec![0.0f32; 16], Binding::SingleBound(0, 1));
+	app.buffer_set().name_buffer(heights, "heights");
+	app.buffer_set().name_buffer(other, "heights");
+}
+
+#[test]
+fn attributes_wgpu_errors_to_the_task_being_submitted() {
+	let Some(mut app) = ComputeTestApp::new() else {
+		return;
+	};
+	let data = app.add_storage_init(vec![0.0f32; 16], Binding::SingleBound(0, 0));
+	// Destroying the buffer once its bind group has been made for the frame is only caught by wgpu when the frame is
+	// submitted. It's destroyed a few frames in, once the task is sure to be running.
+	app.app().sub_app_mut(RenderApp).add_systems(
+		Render,
+		(move |buffers: Res<ShaderBufferSet>, bind_groups: Option<Res<ComputeBindGroups>>, mut frames: Local<u32>| {
+			if bind_groups.is_some() {
+				*frames += 1;
+			}
+			if let (Some(buffer), 5) = (buffers.gpu_buffer(data), *frames) {
+				buffer.destroy();
+			}
+		})
+		.in_set(RenderSet::PrepareBindGroups),
+	);
+	let result = catch_unwind(AssertUnwindSafe(|| {
+		app.run_until_complete(vec![ComputeTask {
+			label: Some("broken".to_owned()),
+			iterations: NonZeroU32::new(100),
+			run_mode: TaskRunMode::Continuous,
+			budget: None,
+			submission: TaskSubmission::WithFrame,
+			start_after: None,
+			iteration_buffer: None,
+			steps: vec![run_shader("increment")],
+		}]);
+	}));
+	let error = result.expect_err("Submitting a destroyed buffer should panic");
+	let message = error.downcast_ref::<String>().expect("The panic should have a formatted message");
+	assert!(
+		message.contains("in the submission of steps [0] of compute task 0 (\"broken\")"),
+		"The error should name the task, but was: {}",
+		message
+	);
+	// The render world panicked partway through a frame, so the app can't be updated again to exit cleanly.
+	std::mem::forget(app);
+}
+
+#[test]
+fn releases_gpu_resources_when_the_app_exits() {
+	for _ in 0..3 {
+		let Some(mut app) = ComputeTestApp::new() else {
+			return;
+		};
+		let data = app.add_storage_init(vec![1.0f32; 16], Binding::SingleBound(0, 0));
+		let double = app.add_storage_init(vec![0.0f32; 16], Binding::Double(0, (1, 2)));
+		app.run_until_complete(vec![ComputeTask {
+			label: None,
+			iterations: NonZeroU32::new(2),
+			run_mode: TaskRunMode::Continuous,
+			budget: None,
+			submission: TaskSubmission::WithFrame,
+			start_after: None,
+			iteration_buffer: None,
+			steps: vec![ComputeStep {
+				schedule: StepSchedule::EveryIteration,
+				run_if: None,
+				action: ComputeAction::SwapBuffers { buffer: double },
+			}],
+		}]);
+		app.assert_buffer_eq(data, &[1.0f32; 16]);
+
+		app.app().world_mut().send_event(AppExit::Success);
+		app.app().update();
+		assert!(app.buffer_set().gpu_buffer(data).is_none());
+		assert!(app.buffer_set().gpu_buffer(double).is_none());
+		assert!(app.app().sub_app(RenderApp).world().get_resource::<ComputeBindGroups>().is_none());
+		// Another frame after exiting mustn't touch anything that's been released.
+		app.app().update();
+	}
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn round_trips_compute_tasks_through_serde() {
+	let tasks = vec![
+		ComputeTask {
+			label: Some("simulate".to_owned()),
+			iterations: NonZeroU32::new(10),
+			run_mode: TaskRunMode::Continuous,
+			budget: None,
+			submission: TaskSubmission::Separate,
+			start_after: Some(StartDelay::Frames(3)),
+			iteration_buffer: Some(TaskIterationBuffer::Counts("iteration".to_owned())),
+			steps: vec![
+				ComputeStep {
+					schedule: StepSchedule::MaxFrequencyHz(30.0),
+					run_if: Some(StepCondition::ResourceFlag("running".to_owned())),
+					action: ComputeAction::RunShader {
+						shader: SHADER_ASSET_PATH.to_owned(),
+						entry_point: "increment".to_owned(),
+						shader_defs: vec![
+							ComputeShaderDef::Bool("FAST".to_owned(), true),
+							ComputeShaderDef::UInt("COUNT".to_owned(), 16),
+						],
+						overrides: vec![("scale".to_owned(), 0.5)],
+						dispatch: DispatchSize::Exact { x: 64, y: 1, z: 1 },
+						auto_swap: vec!["state".to_owned()],
+						skip_if_empty: None,
+						buffer_orientation: vec![("state".to_owned(), BufferOrientation::Reversed)],
+						push_constants: Some(PushConstants::Bytes(vec![1, 0, 0, 0])),
+						dynamic_offsets: Vec::new(),
+					},
+				},
+				ComputeStep {
+					schedule: StepSchedule::EveryNFrames(NonZeroU32::new(4).unwrap()),
+					run_if: Some(StepCondition::OneShotFlag("snapshot".to_owned())),
+					action: ComputeAction::CopyBuffer { src: "state".to_owned(), offset: 16, size: Some(32) },
+				},
+			],
+		},
+		ComputeTask {
+			label: None,
+			iterations: None,
+			run_mode: TaskRunMode::OnDemand,
+			budget: None,
+			submission: TaskSubmission::WithFrame,
+			start_after: None,
+			iteration_buffer: None,
+			steps: vec![ComputeStep {
+				schedule: StepSchedule::EveryIteration,
+				run_if: None,
+				action: ComputeAction::ReadBuffer { buffer: "counter".to_owned() },
+			}],
+		},
+	];
+
+	let serialized = ron::to_string(&tasks).unwrap();
+	let deserialized: Vec<ComputeTask<String>> = ron::from_str(&serialized).unwrap();
+	assert_eq!(deserialized, tasks);
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn deserializes_the_old_max_frequency_of_steps_as_a_schedule() {
+	let steps: Vec<ComputeStep<String>> = ron::from_str(
+		r#"[
+			(max_frequency: Some(30), action: SwapBuffers(buffer: "state")),
+			(max_frequency: None, action: SwapBuffers(buffer: "state")),
+			(action: SwapBuffers(buffer: "state")),
+		]"#,
+	)
+	.unwrap();
+	let schedules: Vec<_> = steps.iter().map(|step| step.schedule).collect();
+	assert_eq!(
+		schedules,
+		vec![StepSchedule::MaxFrequencyHz(30.0), StepSchedule::EveryIteration, StepSchedule::EveryIteration]
+	);
+}